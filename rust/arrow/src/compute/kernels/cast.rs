@@ -70,6 +70,8 @@ pub fn can_cast_types(from_type: &DataType, to_type: &DataType) -> bool {
 
         (_, Boolean) => DataType::is_numeric(from_type),
         (Boolean, _) => DataType::is_numeric(to_type) || to_type == &Utf8,
+        (Utf8, LargeUtf8) => true,
+        (LargeUtf8, Utf8) => true,
         (Utf8, _) => DataType::is_numeric(to_type),
         (_, Utf8) => DataType::is_numeric(from_type) || from_type == &Binary,
 
@@ -366,6 +368,8 @@ pub fn cast(array: &ArrayRef, to_type: &DataType) -> Result<ArrayRef> {
                 from_type, to_type,
             ))),
         },
+        (Utf8, LargeUtf8) => cast_string_offsets::<i32, i64>(array),
+        (LargeUtf8, Utf8) => cast_string_offsets::<i64, i32>(array),
         (Utf8, _) => match to_type {
             UInt8 => cast_string_to_numeric::<UInt8Type>(array),
             UInt16 => cast_string_to_numeric::<UInt16Type>(array),
@@ -896,6 +900,30 @@ where
         .collect()
 }
 
+/// Cast between string arrays of different offset sizes, i.e. `Utf8` and `LargeUtf8`
+fn cast_string_offsets<FROM, TO>(array: &ArrayRef) -> Result<ArrayRef>
+where
+    FROM: StringOffsetSizeTrait,
+    TO: StringOffsetSizeTrait,
+{
+    let from = array
+        .as_any()
+        .downcast_ref::<GenericStringArray<FROM>>()
+        .unwrap();
+
+    // the values must be addressable by the offsets of the target type
+    let start = from.value_offset(0).to_usize().unwrap();
+    let end = from.value_offset(from.len()).to_usize().unwrap();
+    if TO::from_usize(end - start).is_none() {
+        return Err(ArrowError::ComputeError(format!(
+            "Cannot cast to {:?}: the string values are too large",
+            TO::DATA_TYPE
+        )));
+    }
+
+    Ok(Arc::new(from.iter().collect::<GenericStringArray<TO>>()) as ArrayRef)
+}
+
 /// Cast numeric types to Boolean
 ///
 /// Any zero value returns `false` while non-zero returns `true`
@@ -1322,6 +1350,24 @@ mod tests {
         assert_eq!(false, c.is_valid(2));
     }
 
+    #[test]
+    fn test_cast_utf8_to_large_utf8() {
+        let a = StringArray::from(vec![Some("hello"), None, Some("arrow")]);
+        let array = Arc::new(a) as ArrayRef;
+        let b = cast(&array, &DataType::LargeUtf8).unwrap();
+        let c = b.as_any().downcast_ref::<LargeStringArray>().unwrap();
+        assert_eq!("hello", c.value(0));
+        assert_eq!(false, c.is_valid(1));
+        assert_eq!("arrow", c.value(2));
+
+        // and back
+        let d = cast(&b, &DataType::Utf8).unwrap();
+        let e = d.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!("hello", e.value(0));
+        assert_eq!(false, e.is_valid(1));
+        assert_eq!("arrow", e.value(2));
+    }
+
     #[test]
     fn test_cast_bool_to_i32() {
         let a = BooleanArray::from(vec![Some(true), Some(false), None]);
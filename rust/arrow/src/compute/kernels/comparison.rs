@@ -29,7 +29,9 @@ use std::sync::Arc;
 use crate::array::*;
 use crate::buffer::{Buffer, MutableBuffer};
 use crate::compute::util::combine_option_bitmap;
-use crate::datatypes::{ArrowNumericType, BooleanType, DataType};
+use crate::datatypes::{
+    ArrowNativeType, ArrowNumericType, ArrowPrimitiveType, BooleanType, DataType,
+};
 use crate::error::{ArrowError, Result};
 use crate::util::bit_util;
 
@@ -248,6 +250,103 @@ pub fn gt_eq_utf8_scalar(left: &StringArray, right: &str) -> Result<BooleanArray
     compare_op_scalar!(left, right, |a, b| a >= b)
 }
 
+/// Helper function to compare a dictionary array of strings with a scalar. `op` is
+/// evaluated once per value of the dictionary, and its results are then looked up by
+/// the keys of the array, so that the array is not decoded.
+fn compare_dict_op_scalar<K, F>(
+    left: &DictionaryArray<K>,
+    right: &str,
+    op: F,
+) -> Result<BooleanArray>
+where
+    K: ArrowPrimitiveType,
+    F: Fn(&str, &str) -> bool,
+{
+    let values = left.values();
+    let values = values
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .ok_or_else(|| {
+            ArrowError::ComputeError(format!(
+                "Cannot compare a dictionary of {:?} values with a string",
+                left.value_type()
+            ))
+        })?;
+    let results = (0..values.len())
+        .map(|i| {
+            if values.is_null(i) {
+                None
+            } else {
+                Some(op(values.value(i), right))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let keys = left.keys();
+    let mut result = BooleanBuilder::new(keys.len());
+    for i in 0..keys.len() {
+        if keys.is_null(i) {
+            result.append_null()?;
+            continue;
+        }
+        let key = keys.value(i);
+        let value = key
+            .to_usize()
+            .and_then(|key| results.get(key))
+            .ok_or_else(|| {
+                ArrowError::ComputeError(format!(
+                    "Dictionary key {:?} is out of the {} values of the dictionary",
+                    key,
+                    values.len()
+                ))
+            })?;
+        result.append_option(*value)?;
+    }
+    Ok(result.finish())
+}
+
+pub fn eq_dict_utf8_scalar<K: ArrowPrimitiveType>(
+    left: &DictionaryArray<K>,
+    right: &str,
+) -> Result<BooleanArray> {
+    compare_dict_op_scalar(left, right, |a, b| a == b)
+}
+
+pub fn neq_dict_utf8_scalar<K: ArrowPrimitiveType>(
+    left: &DictionaryArray<K>,
+    right: &str,
+) -> Result<BooleanArray> {
+    compare_dict_op_scalar(left, right, |a, b| a != b)
+}
+
+pub fn lt_dict_utf8_scalar<K: ArrowPrimitiveType>(
+    left: &DictionaryArray<K>,
+    right: &str,
+) -> Result<BooleanArray> {
+    compare_dict_op_scalar(left, right, |a, b| a < b)
+}
+
+pub fn lt_eq_dict_utf8_scalar<K: ArrowPrimitiveType>(
+    left: &DictionaryArray<K>,
+    right: &str,
+) -> Result<BooleanArray> {
+    compare_dict_op_scalar(left, right, |a, b| a <= b)
+}
+
+pub fn gt_dict_utf8_scalar<K: ArrowPrimitiveType>(
+    left: &DictionaryArray<K>,
+    right: &str,
+) -> Result<BooleanArray> {
+    compare_dict_op_scalar(left, right, |a, b| a > b)
+}
+
+pub fn gt_eq_dict_utf8_scalar<K: ArrowPrimitiveType>(
+    left: &DictionaryArray<K>,
+    right: &str,
+) -> Result<BooleanArray> {
+    compare_dict_op_scalar(left, right, |a, b| a >= b)
+}
+
 /// Helper function to perform boolean lambda function on values from two arrays using
 /// SIMD.
 #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd"))]
@@ -1076,6 +1175,59 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_dict_utf8_scalar() -> Result<()> {
+        let dict: DictionaryArray<Int8Type> = vec![Some("b"), None, Some("a"), Some("c")]
+            .into_iter()
+            .collect();
+        let expected = |res: BooleanArray| {
+            (0..res.len())
+                .map(|i| {
+                    if res.is_null(i) {
+                        None
+                    } else {
+                        Some(res.value(i))
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            expected(eq_dict_utf8_scalar(&dict, "b")?),
+            vec![Some(true), None, Some(false), Some(false)]
+        );
+        assert_eq!(
+            expected(neq_dict_utf8_scalar(&dict, "b")?),
+            vec![Some(false), None, Some(true), Some(true)]
+        );
+        assert_eq!(
+            expected(lt_dict_utf8_scalar(&dict, "b")?),
+            vec![Some(false), None, Some(true), Some(false)]
+        );
+        assert_eq!(
+            expected(lt_eq_dict_utf8_scalar(&dict, "b")?),
+            vec![Some(true), None, Some(true), Some(false)]
+        );
+        assert_eq!(
+            expected(gt_dict_utf8_scalar(&dict, "b")?),
+            vec![Some(false), None, Some(false), Some(true)]
+        );
+        assert_eq!(
+            expected(gt_eq_dict_utf8_scalar(&dict, "b")?),
+            vec![Some(true), None, Some(false), Some(true)]
+        );
+
+        let sliced = dict.slice(2, 2);
+        let sliced = sliced
+            .as_any()
+            .downcast_ref::<DictionaryArray<Int8Type>>()
+            .unwrap();
+        assert_eq!(
+            expected(eq_dict_utf8_scalar(sliced, "c")?),
+            vec![Some(false), Some(true)]
+        );
+        Ok(())
+    }
+
     test_utf8!(
         test_utf8_array_like,
         vec!["arrow", "arrow", "arrow", "arrow"],
@@ -31,6 +31,8 @@
 //! assert_eq!(arr.len(), 3);
 //! ```
 
+use std::sync::Arc;
+
 use crate::array::*;
 use crate::datatypes::*;
 use crate::error::{ArrowError, Result};
@@ -117,6 +119,20 @@ pub fn concat(array_list: &[ArrayRef]) -> Result<ArrayRef> {
         DataType::List(nested_field) => {
             concat_list(array_data_list, nested_field.data_type())
         }
+        DataType::Dictionary(key_type, _) => match key_type.as_ref() {
+            DataType::Int8 => concat_dictionary::<Int8Type>(array_list),
+            DataType::Int16 => concat_dictionary::<Int16Type>(array_list),
+            DataType::Int32 => concat_dictionary::<Int32Type>(array_list),
+            DataType::Int64 => concat_dictionary::<Int64Type>(array_list),
+            DataType::UInt8 => concat_dictionary::<UInt8Type>(array_list),
+            DataType::UInt16 => concat_dictionary::<UInt16Type>(array_list),
+            DataType::UInt32 => concat_dictionary::<UInt32Type>(array_list),
+            DataType::UInt64 => concat_dictionary::<UInt64Type>(array_list),
+            t => Err(ArrowError::ComputeError(format!(
+                "Concat not supported for dictionary key type {:?}",
+                t
+            ))),
+        },
         t => Err(ArrowError::ComputeError(format!(
             "Concat not supported for data type {:?}",
             t
@@ -124,6 +140,80 @@ pub fn concat(array_list: &[ArrayRef]) -> Result<ArrayRef> {
     }
 }
 
+/// Concatenate dictionary arrays, keeping them encoded. Arrays that share their
+/// values keep them, otherwise the values are concatenated and the keys of each array
+/// offset by the number of values before its own.
+fn concat_dictionary<K: ArrowPrimitiveType>(array_list: &[ArrayRef]) -> Result<ArrayRef> {
+    let data_type = array_list[0].data_type();
+    let dictionaries = array_list
+        .iter()
+        .map(|array| {
+            if array.data_type() != data_type {
+                return Err(ArrowError::InvalidArgumentError(
+                    "Cannot concatenate arrays if data types are different".to_string(),
+                ));
+            }
+            Ok(array.as_any().downcast_ref::<DictionaryArray<K>>().unwrap())
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let first_values = &dictionaries[0].data_ref().child_data()[0];
+    let shared_values = dictionaries
+        .iter()
+        .all(|dict| Arc::ptr_eq(&dict.data_ref().child_data()[0], first_values));
+    let values = if shared_values {
+        first_values.clone()
+    } else {
+        concat(
+            &dictionaries
+                .iter()
+                .map(|dict| dict.values())
+                .collect::<Vec<_>>(),
+        )?
+        .data()
+    };
+
+    let len = dictionaries.iter().map(|dict| dict.len()).sum();
+    let mut keys = PrimitiveBuilder::<K>::new(len);
+    let mut offset = 0;
+    for dict in &dictionaries {
+        let dict_keys = dict.keys();
+        for i in 0..dict_keys.len() {
+            if dict_keys.is_null(i) {
+                keys.append_null()?;
+                continue;
+            }
+            let key = dict_keys
+                .value(i)
+                .to_usize()
+                .and_then(|key| K::Native::from_usize(key + offset))
+                .ok_or_else(|| {
+                    ArrowError::ComputeError(format!(
+                        "The concatenated dictionary has too many values for keys of {:?}",
+                        K::DATA_TYPE
+                    ))
+                })?;
+            keys.append_value(key)?;
+        }
+        if !shared_values {
+            offset += dict.values().len();
+        }
+    }
+    let keys = keys.finish();
+    let keys_data = keys.data_ref();
+
+    let data = Arc::new(ArrayData::new(
+        data_type.clone(),
+        len,
+        Some(keys_data.null_count()),
+        keys_data.null_buffer().cloned(),
+        0,
+        keys_data.buffers().to_vec(),
+        vec![values],
+    ));
+    Ok(Arc::new(DictionaryArray::<K>::from(data)))
+}
+
 #[inline]
 fn concat_primitive<T>(array_data_list: &[ArrayDataRef]) -> Result<ArrayRef>
 where
@@ -370,4 +460,65 @@ mod tests {
 
         Ok(())
     }
+
+    /// The values of a dictionary array of strings
+    fn dictionary_values(array: &ArrayRef) -> Vec<Option<String>> {
+        let dict = array
+            .as_any()
+            .downcast_ref::<DictionaryArray<Int32Type>>()
+            .unwrap();
+        let values = dict.values();
+        let values = values.as_any().downcast_ref::<StringArray>().unwrap();
+        (0..dict.len())
+            .map(|i| {
+                if dict.keys().is_null(i) {
+                    None
+                } else {
+                    Some(values.value(dict.keys().value(i) as usize).to_string())
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_concat_dictionary_arrays() -> Result<()> {
+        let a: DictionaryArray<Int32Type> = vec![Some("a"), None, Some("b"), Some("a")]
+            .into_iter()
+            .collect();
+        let b: DictionaryArray<Int32Type> = vec!["c", "a"].into_iter().collect();
+        let a = Arc::new(a) as ArrayRef;
+        let b = Arc::new(b) as ArrayRef;
+
+        let array = concat(&[a.clone(), b])?;
+        assert_eq!(
+            array.data_type(),
+            &DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+        );
+        let expected = vec![Some("a"), None, Some("b"), Some("a"), Some("c"), Some("a")];
+        let expected = expected
+            .into_iter()
+            .map(|v| v.map(|v| v.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(dictionary_values(&array), expected);
+
+        // slices of the same array share their values
+        let array = concat(&[a.slice(2, 2), a.slice(0, 2)])?;
+        let dict = array
+            .as_any()
+            .downcast_ref::<DictionaryArray<Int32Type>>()
+            .unwrap();
+        assert_eq!(dict.values().len(), 2);
+        assert_eq!(
+            dictionary_values(&array),
+            expected[2..4]
+                .iter()
+                .chain(&expected[0..2])
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+
+        let re = concat(&[a, Arc::new(StringArray::from(vec!["a"])) as ArrayRef]);
+        assert!(re.is_err());
+        Ok(())
+    }
 }
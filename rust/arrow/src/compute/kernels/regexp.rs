@@ -0,0 +1,273 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines kernels to evaluate regular expressions on \[Large\]StringArray,
+//! such as `regexp_is_match_utf8` (the SQL `~` operator) and `regexp_match`.
+//!
+//! Patterns given as a scalar are compiled once per call; patterns given as an
+//! array are compiled once per distinct (pattern, flags) pair.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use regex::Regex;
+
+use crate::array::*;
+use crate::compute::util::combine_option_bitmap;
+use crate::datatypes::{BooleanType, DataType};
+use crate::error::{ArrowError, Result};
+
+/// Compiles `pattern`, applying `flags` (e.g. `i` for case-insensitive matching)
+/// through the inline flag syntax of the `regex` crate, e.g. `(?i)pattern`.
+fn compile_regex(pattern: &str, flags: Option<&str>) -> Result<Regex> {
+    let pattern = match flags {
+        None | Some("") => pattern.to_string(),
+        Some(flags) => {
+            if flags.contains('g') {
+                return Err(ArrowError::ComputeError(
+                    "Matching does not support the \"global\" flag".to_string(),
+                ));
+            }
+            format!("(?{}){}", flags, pattern)
+        }
+    };
+    Regex::new(&pattern).map_err(|e| {
+        ArrowError::ComputeError(format!("Regular expression did not compile: {}", e))
+    })
+}
+
+/// Returns the value of `flags_array` at `i`, if any.
+fn flag_at<OffsetSize: StringOffsetSizeTrait>(
+    flags_array: Option<&GenericStringArray<OffsetSize>>,
+    i: usize,
+) -> Option<&str> {
+    flags_array.and_then(|flags| {
+        if flags.is_null(i) {
+            None
+        } else {
+            Some(flags.value(i))
+        }
+    })
+}
+
+/// Perform SQL `array ~ regex_array` operation on \[Large\]StringArray, row by row.
+///
+/// `flags_array` optionally sets flags for each row, such as `i` for case-insensitive
+/// matching. Each distinct pattern is compiled once.
+pub fn regexp_is_match_utf8<OffsetSize: StringOffsetSizeTrait>(
+    array: &GenericStringArray<OffsetSize>,
+    regex_array: &GenericStringArray<OffsetSize>,
+    flags_array: Option<&GenericStringArray<OffsetSize>>,
+) -> Result<BooleanArray> {
+    if array.len() != regex_array.len() {
+        return Err(ArrowError::ComputeError(
+            "Cannot perform comparison operation on arrays of different length"
+                .to_string(),
+        ));
+    }
+
+    let null_bit_buffer =
+        combine_option_bitmap(array.data_ref(), regex_array.data_ref(), array.len())?;
+
+    let mut patterns: HashMap<(&str, Option<&str>), Regex> = HashMap::new();
+    let mut result = BooleanBufferBuilder::new(array.len());
+    for i in 0..array.len() {
+        if array.is_null(i) || regex_array.is_null(i) {
+            result.append(false)?;
+            continue;
+        }
+
+        let key = (regex_array.value(i), flag_at(flags_array, i));
+        let re = match patterns.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(compile_regex(key.0, key.1)?),
+        };
+        result.append(re.is_match(array.value(i)))?;
+    }
+
+    let data = ArrayData::new(
+        DataType::Boolean,
+        array.len(),
+        None,
+        null_bit_buffer,
+        0,
+        vec![result.finish()],
+        vec![],
+    );
+    Ok(PrimitiveArray::<BooleanType>::from(Arc::new(data)))
+}
+
+/// Perform SQL `array ~ regex` operation on \[Large\]StringArray, where `regex`
+/// is a scalar pattern compiled once, with optional `flag`s.
+pub fn regexp_is_match_utf8_scalar<OffsetSize: StringOffsetSizeTrait>(
+    array: &GenericStringArray<OffsetSize>,
+    regex: &str,
+    flag: Option<&str>,
+) -> Result<BooleanArray> {
+    let null_bit_buffer = array.data().null_buffer().cloned();
+    let re = compile_regex(regex, flag)?;
+
+    let mut result = BooleanBufferBuilder::new(array.len());
+    for i in 0..array.len() {
+        result.append(!array.is_null(i) && re.is_match(array.value(i)))?;
+    }
+
+    let data = ArrayData::new(
+        DataType::Boolean,
+        array.len(),
+        None,
+        null_bit_buffer,
+        0,
+        vec![result.finish()],
+        vec![],
+    );
+    Ok(PrimitiveArray::<BooleanType>::from(Arc::new(data)))
+}
+
+/// Extracts the groups captured by the first match of a regular expression in each
+/// row of a \[Large\]StringArray, as a `List(Utf8)` array.
+///
+/// When the pattern has no capture groups, the list contains the whole match.
+/// Rows that do not match, or whose value or pattern is null, are null.
+/// `flags_array` optionally sets flags for each row, such as `i` for case-insensitive
+/// matching. Each distinct pattern is compiled once.
+pub fn regexp_match<OffsetSize: StringOffsetSizeTrait>(
+    array: &GenericStringArray<OffsetSize>,
+    regex_array: &GenericStringArray<OffsetSize>,
+    flags_array: Option<&GenericStringArray<OffsetSize>>,
+) -> Result<ArrayRef> {
+    if array.len() != regex_array.len() {
+        return Err(ArrowError::ComputeError(
+            "Cannot perform regexp_match on arrays of different length".to_string(),
+        ));
+    }
+
+    let mut patterns: HashMap<(&str, Option<&str>), Regex> = HashMap::new();
+    let mut builder = ListBuilder::new(StringBuilder::new(array.len()));
+    for i in 0..array.len() {
+        if array.is_null(i) || regex_array.is_null(i) {
+            builder.append(false)?;
+            continue;
+        }
+
+        let key = (regex_array.value(i), flag_at(flags_array, i));
+        let re = match patterns.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(compile_regex(key.0, key.1)?),
+        };
+
+        match re.captures(array.value(i)) {
+            Some(captures) => {
+                if captures.len() == 1 {
+                    // no capture groups: return the whole match
+                    builder.values().append_value(&captures[0])?;
+                } else {
+                    for group in captures.iter().skip(1) {
+                        match group {
+                            Some(group) => {
+                                builder.values().append_value(group.as_str())?
+                            }
+                            None => builder.values().append_null()?,
+                        }
+                    }
+                }
+                builder.append(true)?;
+            }
+            None => builder.append(false)?,
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regexp_is_match_utf8() -> Result<()> {
+        let array =
+            StringArray::from(vec![Some("arrow"), Some("ARROW"), None, Some("parquet")]);
+        let patterns =
+            StringArray::from(vec![Some("^ar"), Some("^ar"), Some("^ar"), None]);
+
+        let result = regexp_is_match_utf8(&array, &patterns, None)?;
+        assert_eq!(true, result.value(0));
+        assert_eq!(false, result.value(1));
+        assert_eq!(false, result.is_valid(2));
+        assert_eq!(false, result.is_valid(3));
+
+        let flags = StringArray::from(vec!["i", "i", "i", "i"]);
+        let result = regexp_is_match_utf8(&array, &patterns, Some(&flags))?;
+        assert_eq!(true, result.value(0));
+        assert_eq!(true, result.value(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_regexp_is_match_utf8_scalar() -> Result<()> {
+        let array = LargeStringArray::from(vec![Some("arrow"), Some("ARROW"), None]);
+
+        let result = regexp_is_match_utf8_scalar(&array, "row$", None)?;
+        assert_eq!(true, result.value(0));
+        assert_eq!(false, result.value(1));
+        assert_eq!(false, result.is_valid(2));
+
+        let result = regexp_is_match_utf8_scalar(&array, "row$", Some("i"))?;
+        assert_eq!(true, result.value(1));
+
+        assert!(regexp_is_match_utf8_scalar(&array, "(", None).is_err());
+        assert!(regexp_is_match_utf8_scalar(&array, "a", Some("g")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_regexp_match() -> Result<()> {
+        let array = StringArray::from(vec![
+            Some("Mozilla/5.0 Firefox/81.0"),
+            Some("Mozilla/5.0 Chrome/86.0"),
+            Some("curl/7.68.0"),
+            None,
+        ]);
+        let patterns = StringArray::from(vec![
+            "(Firefox|Chrome)/(\\d+)",
+            "(Firefox|Chrome)/(\\d+)",
+            "(Firefox|Chrome)/(\\d+)",
+            "(Firefox|Chrome)/(\\d+)",
+        ]);
+
+        let result = regexp_match(&array, &patterns, None)?;
+        let result = result.as_any().downcast_ref::<ListArray>().unwrap();
+        assert_eq!(4, result.len());
+
+        let first = result.value(0);
+        let first = first.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(2, first.len());
+        assert_eq!("Firefox", first.value(0));
+        assert_eq!("81", first.value(1));
+
+        let second = result.value(1);
+        let second = second.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!("Chrome", second.value(0));
+        assert_eq!("86", second.value(1));
+
+        assert_eq!(false, result.is_valid(2));
+        assert_eq!(false, result.is_valid(3));
+        Ok(())
+    }
+}
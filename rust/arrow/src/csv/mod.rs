@@ -20,7 +20,9 @@
 pub mod reader;
 pub mod writer;
 
+pub use self::reader::infer_reader_schema;
 pub use self::reader::infer_schema_from_files;
+pub use self::reader::ByteRangeReader;
 pub use self::reader::Reader;
 pub use self::reader::ReaderBuilder;
 pub use self::writer::Writer;
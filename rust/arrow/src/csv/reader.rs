@@ -43,7 +43,7 @@
 use lazy_static::lazy_static;
 use regex::{Regex, RegexBuilder};
 use std::fs::File;
-use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
 use std::sync::Arc;
 use std::{collections::HashSet, iter::Skip};
 use std::{fmt, iter::Take};
@@ -184,6 +184,25 @@ fn infer_file_schema<R: Read + Seek>(
     Ok((Schema::new(fields), records_count))
 }
 
+/// Infer the schema of CSV data from `reader`, by reading through its first n records,
+/// with `max_read_records` controlling the maximum number of records to read.
+///
+/// Return infered schema and number of records used for inference. The reader is
+/// seeked back to the start once the schema is inferred.
+pub fn infer_reader_schema<R: Read + Seek>(
+    reader: &mut R,
+    delimiter: u8,
+    max_read_records: Option<usize>,
+    has_header: bool,
+) -> Result<(Schema, usize)> {
+    infer_file_schema(
+        &mut BufReader::new(reader),
+        delimiter,
+        max_read_records,
+        has_header,
+    )
+}
+
 /// Infer schema from a list of CSV files by reading through first n records
 /// with `max_read_records` controlling the maximum number of records to read.
 ///
@@ -369,6 +388,85 @@ impl<R: Read> Iterator for Reader<R> {
     }
 }
 
+/// Reads the lines of a reader that start within a byte range of it, so that a file
+/// can be read in parallel by the readers of consecutive ranges.
+///
+/// A line belongs to the range that contains its first byte: the reader skips the end
+/// of the line that the range starts within, and reads past the end of the range to
+/// finish its last line. The ranges must therefore split the file at line breaks that
+/// end records, i.e. the values of the file must not contain line breaks.
+pub struct ByteRangeReader<R: Read> {
+    reader: BufReader<R>,
+    /// The offset of the next byte to read
+    position: u64,
+    /// The end of the range, exclusive
+    end: u64,
+    /// Whether the last line of the range has been read
+    done: bool,
+}
+
+impl<R: Read + Seek> ByteRangeReader<R> {
+    /// Create a reader of the lines of `reader` that start within `start..end`
+    pub fn try_new(reader: R, start: u64, end: u64) -> Result<Self> {
+        let mut reader = BufReader::new(reader);
+        let mut position = start;
+        if start > 0 {
+            // the first line of the range follows the first line break from the byte
+            // before the range, which ends the line that the range starts within
+            position = start - 1;
+            reader.seek(SeekFrom::Start(position))?;
+            loop {
+                let available = reader.fill_buf()?;
+                if available.is_empty() {
+                    break;
+                }
+                match available.iter().position(|b| *b == b'\n') {
+                    Some(i) => {
+                        reader.consume(i + 1);
+                        position += i as u64 + 1;
+                        break;
+                    }
+                    None => {
+                        let len = available.len();
+                        reader.consume(len);
+                        position += len as u64;
+                    }
+                }
+            }
+        } else {
+            reader.seek(SeekFrom::Start(0))?;
+        }
+        Ok(Self {
+            reader,
+            position,
+            end,
+            done: position >= end,
+        })
+    }
+}
+
+impl<R: Read> Read for ByteRangeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.done || buf.is_empty() {
+            return Ok(0);
+        }
+        let available = self.reader.fill_buf()?;
+        let mut len = available.len().min(buf.len());
+        if self.position + len as u64 >= self.end {
+            // the range ends with the line that contains its last byte
+            let from = (self.end - 1).saturating_sub(self.position) as usize;
+            if let Some(i) = available[from..len].iter().position(|b| *b == b'\n') {
+                len = from + i + 1;
+                self.done = true;
+            }
+        }
+        buf[..len].copy_from_slice(&available[..len]);
+        self.reader.consume(len);
+        self.position += len as u64;
+        Ok(len)
+    }
+}
+
 /// parses a slice of [csv_crate::StringRecord] into a [array::record_batch::RecordBatch].
 fn parse(
     rows: &[StringRecord],
@@ -620,6 +718,42 @@ impl ReaderBuilder {
             self.projection.clone(),
         ))
     }
+
+    /// Create a new `Reader` of the records of `reader` that start within the byte
+    /// range `start..end`, see [ByteRangeReader]. The header, if any, is only read by
+    /// the range that starts at 0, and the schema is inferred from the start of the
+    /// file. The line numbers of parsing errors are relative to the range.
+    pub fn build_byte_range<R: Read + Seek>(
+        self,
+        reader: R,
+        start: u64,
+        end: u64,
+    ) -> Result<Reader<ByteRangeReader<R>>> {
+        let mut buf_reader = BufReader::new(reader);
+        let schema = match self.schema {
+            Some(schema) => schema,
+            None => {
+                let (inferred_schema, _) = infer_file_schema(
+                    &mut buf_reader,
+                    self.delimiter.unwrap_or(b','),
+                    self.max_records,
+                    self.has_header,
+                )?;
+
+                Arc::new(inferred_schema)
+            }
+        };
+        let reader = ByteRangeReader::try_new(buf_reader.into_inner(), start, end)?;
+        Ok(Reader::new(
+            reader,
+            schema,
+            self.has_header && start == 0,
+            self.delimiter,
+            self.batch_size,
+            None,
+            self.projection,
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -991,4 +1125,47 @@ mod tests {
         assert!(csv.next().is_none());
         Ok(())
     }
+
+    #[test]
+    fn test_byte_ranges() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, false),
+        ]));
+        let data = "a,b\n1,\"x, y\"\n22,z\r\n333,\"\"\n4444,w";
+        let read = |start: u64, end: u64| -> Result<Vec<i32>> {
+            let reader = ReaderBuilder::new()
+                .with_schema(schema.clone())
+                .has_header(true)
+                .with_batch_size(2)
+                .build_byte_range(Cursor::new(data), start, end)?;
+            let mut values = vec![];
+            for batch in reader {
+                let batch = batch?;
+                let a = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap();
+                values.extend((0..a.len()).map(|i| a.value(i)));
+            }
+            Ok(values)
+        };
+
+        // every row is read once, whatever the size of the ranges
+        let len = data.len() as u64;
+        for size in 1..=len {
+            let mut values = vec![];
+            let mut start = 0;
+            while start < len {
+                values.extend(read(start, (start + size).min(len))?);
+                start += size;
+            }
+            assert_eq!(values, vec![1, 22, 333, 4444], "range size {}", size);
+        }
+
+        // a range within a line reads nothing
+        assert!(read(5, 7)?.is_empty());
+        Ok(())
+    }
 }
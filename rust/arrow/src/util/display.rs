@@ -23,7 +23,7 @@ use crate::array;
 use crate::array::Array;
 use crate::datatypes::{
     ArrowNativeType, ArrowPrimitiveType, DataType, Int16Type, Int32Type, Int64Type,
-    Int8Type, TimeUnit, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+    Int8Type, IntervalUnit, TimeUnit, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
 
 use array::DictionaryArray;
@@ -105,6 +105,24 @@ pub fn array_value_to_string(column: &array::ArrayRef, row: usize) -> Result<Str
         DataType::Time64(unit) if *unit == TimeUnit::Nanosecond => {
             make_string!(array::Time64NanosecondArray, column, row)
         }
+        DataType::Duration(unit) if *unit == TimeUnit::Second => {
+            make_string!(array::DurationSecondArray, column, row)
+        }
+        DataType::Duration(unit) if *unit == TimeUnit::Millisecond => {
+            make_string!(array::DurationMillisecondArray, column, row)
+        }
+        DataType::Duration(unit) if *unit == TimeUnit::Microsecond => {
+            make_string!(array::DurationMicrosecondArray, column, row)
+        }
+        DataType::Duration(unit) if *unit == TimeUnit::Nanosecond => {
+            make_string!(array::DurationNanosecondArray, column, row)
+        }
+        DataType::Interval(IntervalUnit::YearMonth) => {
+            make_string!(array::IntervalYearMonthArray, column, row)
+        }
+        DataType::Interval(IntervalUnit::DayTime) => {
+            make_string!(array::IntervalDayTimeArray, column, row)
+        }
         DataType::List(_) => make_string_from_list!(column, row),
         DataType::Dictionary(index_type, _value_type) => match **index_type {
             DataType::Int8 => dict_array_value_to_string::<Int8Type>(column, row),
@@ -23,7 +23,7 @@ use arrow::{
 };
 
 use datafusion::error::Result;
-use datafusion::{physical_plan::functions::ScalarFunctionImplementation, prelude::*};
+use datafusion::prelude::*;
 use std::sync::Arc;
 
 // create local execution context with an in-memory table
@@ -60,7 +60,9 @@ async fn main() -> Result<()> {
     let mut ctx = create_context()?;
 
     // First, declare the actual implementation of the calculation
-    let pow: ScalarFunctionImplementation = Arc::new(|args: &[ArrayRef]| {
+    // `make_scalar_function` adapts it to the arguments of UDFs, which may also be
+    // scalars, e.g. when a literal is passed, by expanding them into arrays
+    let pow = make_scalar_function(|args: &[ArrayRef]| {
         // in DataFusion, all `args` and output are dynamically-typed arrays, which means that we need to:
         // 1. cast the values to the type we want
         // 2. perform the computation for every element in the array (using a loop or SIMD)
@@ -103,12 +105,15 @@ async fn main() -> Result<()> {
     // * give it a name so that it shows nicely when the plan is printed
     // * declare what input it expects
     // * declare its return type
+    // * declare whether it returns the same value for the same arguments
     let pow = create_udf(
         "pow",
         // expects two f64
         vec![DataType::Float64, DataType::Float64],
         // returns f64
         Arc::new(DataType::Float64),
+        // returns the same value for the same arguments
+        Volatility::Immutable,
         pow,
     );
 
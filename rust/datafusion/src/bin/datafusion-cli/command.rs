@@ -0,0 +1,256 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The commands of the CLI: SQL statements, `SET key = value` and meta-commands that
+//! start with a backslash
+
+use datafusion::error::{DataFusionError, Result};
+
+/// A command read from the terminal, a script or the command line
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// A SQL statement, without its terminating semicolon
+    Query(String),
+    /// `SET key = value`, which changes the configuration of the context
+    Set(String, String),
+    /// `\d`, `\dt`: lists the registered tables
+    ListTables,
+    /// `\d name`: describes the columns of a table
+    DescribeTable(String),
+    /// `\timing`: toggles printing how long queries take
+    ToggleTiming,
+    /// `\quiet`: toggles printing the number of rows of results
+    ToggleQuiet,
+    /// `\pset format name`: changes the output format
+    SetFormat(String),
+    /// `\?`: prints the meta-commands
+    Help,
+    /// `\q`: exits the CLI
+    Quit,
+}
+
+/// The help printed by `\?`
+pub const HELP: &str = "\
+\\d            list tables
+\\d NAME       describe the columns of table NAME
+\\dt           list tables
+\\timing       toggle printing how long queries take
+\\quiet        toggle printing the number of rows of results
+\\pset format  set the output format: table, csv, tsv, json or ndjson
+\\?            print this help
+\\q            quit
+SET batch_size = N, SET concurrency = N change the execution configuration";
+
+impl Command {
+    /// Parses a meta-command, a line that starts with a backslash
+    pub fn parse_meta(line: &str) -> Result<Command> {
+        let mut words = line.trim().trim_end_matches(';').split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let args = words.collect::<Vec<_>>();
+        match (command, args.as_slice()) {
+            ("\\d", []) | ("\\dt", []) => Ok(Command::ListTables),
+            ("\\d", [name]) => Ok(Command::DescribeTable(name.to_string())),
+            ("\\timing", []) => Ok(Command::ToggleTiming),
+            ("\\quiet", []) => Ok(Command::ToggleQuiet),
+            ("\\pset", ["format", name]) => Ok(Command::SetFormat(name.to_string())),
+            ("\\?", []) => Ok(Command::Help),
+            ("\\q", []) => Ok(Command::Quit),
+            _ => Err(DataFusionError::Plan(format!(
+                "Unknown meta-command '{}', \\? lists the meta-commands",
+                line.trim()
+            ))),
+        }
+    }
+
+    /// Parses a statement, which is either `SET key = value` or a query
+    pub fn parse_statement(statement: &str) -> Command {
+        let trimmed = statement.trim();
+        let is_set = trimmed
+            .get(..4)
+            .map(|prefix| prefix.eq_ignore_ascii_case("set "))
+            .unwrap_or(false);
+        if is_set {
+            let assignment = &trimmed[4..];
+            if let Some(i) = assignment.find('=') {
+                let key = assignment[..i].trim().to_lowercase();
+                let value = assignment[i + 1..].trim().trim_matches('\'');
+                return Command::Set(key, value.to_string());
+            }
+        }
+        Command::Query(trimmed.to_string())
+    }
+}
+
+/// Collects lines into commands: meta-commands take a line of their own, while SQL
+/// statements may span several lines and end with a semicolon
+#[derive(Debug, Default)]
+pub struct CommandBuffer {
+    /// The text of the statement that is not terminated yet
+    statement: String,
+}
+
+impl CommandBuffer {
+    /// Create an empty buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether there is no unterminated statement in the buffer
+    pub fn is_empty(&self) -> bool {
+        self.statement.trim().is_empty()
+    }
+
+    /// Adds `line` to the buffer and returns the commands it completes
+    pub fn push_line(&mut self, line: &str) -> Result<Vec<Command>> {
+        if self.is_empty() && line.trim_start().starts_with('\\') {
+            self.statement.clear();
+            return Ok(vec![Command::parse_meta(line)?]);
+        }
+        if !self.statement.is_empty() {
+            self.statement.push('\n');
+        }
+        self.statement.push_str(line);
+
+        let mut commands = vec![];
+        let mut start = 0;
+        for end in statement_ends(&self.statement) {
+            let statement = &self.statement[start..end];
+            if !statement.trim().is_empty() {
+                commands.push(Command::parse_statement(statement));
+            }
+            start = end + 1;
+        }
+        self.statement = self.statement[start..].to_string();
+        Ok(commands)
+    }
+
+    /// Returns the unterminated statement left in the buffer, if any, at the end of
+    /// a script
+    pub fn finish(&mut self) -> Option<Command> {
+        if self.is_empty() {
+            None
+        } else {
+            let statement = std::mem::replace(&mut self.statement, String::new());
+            Some(Command::parse_statement(&statement))
+        }
+    }
+}
+
+/// Parses all the commands of a script or of the `-c` argument
+pub fn parse_commands(text: &str) -> Result<Vec<Command>> {
+    let mut buffer = CommandBuffer::new();
+    let mut commands = vec![];
+    for line in text.lines() {
+        commands.extend(buffer.push_line(line)?);
+    }
+    commands.extend(buffer.finish());
+    Ok(commands)
+}
+
+/// The offsets of the semicolons of `text` that end statements, skipping those
+/// within quotes and comments
+fn statement_ends(text: &str) -> Vec<usize> {
+    let mut ends = vec![];
+    let mut quote = None;
+    let mut comment = false;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            _ if comment => comment = c != '\n',
+            (None, '\'') | (None, '"') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '-') if chars.peek().map(|(_, c)| *c) == Some('-') => comment = true,
+            (None, ';') => ends.push(i),
+            _ => {}
+        }
+    }
+    ends
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_meta_commands() -> Result<()> {
+        assert_eq!(Command::parse_meta("\\d")?, Command::ListTables);
+        assert_eq!(Command::parse_meta(" \\dt ")?, Command::ListTables);
+        assert_eq!(
+            Command::parse_meta("\\d aggregate_test_100")?,
+            Command::DescribeTable("aggregate_test_100".to_string())
+        );
+        assert_eq!(Command::parse_meta("\\timing")?, Command::ToggleTiming);
+        assert_eq!(
+            Command::parse_meta("\\pset format csv")?,
+            Command::SetFormat("csv".to_string())
+        );
+        let err = Command::parse_meta("\\x").expect_err("expected an error");
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Unknown meta-command '\\x', \\? lists the meta-commands"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_set() {
+        assert_eq!(
+            Command::parse_statement("SET batch_size = 1024"),
+            Command::Set("batch_size".to_string(), "1024".to_string())
+        );
+        assert_eq!(
+            Command::parse_statement("set Concurrency='2'"),
+            Command::Set("concurrency".to_string(), "2".to_string())
+        );
+        assert_eq!(
+            Command::parse_statement(" SELECT 1 "),
+            Command::Query("SELECT 1".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_script() -> Result<()> {
+        let script = "\\timing\nSELECT 'a;b'; SELECT 2\n-- a comment; with a semicolon\n, 3;\nSELECT 4";
+        assert_eq!(
+            parse_commands(script)?,
+            vec![
+                Command::ToggleTiming,
+                Command::Query("SELECT 'a;b'".to_string()),
+                Command::Query(
+                    "SELECT 2\n-- a comment; with a semicolon\n, 3".to_string()
+                ),
+                Command::Query("SELECT 4".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn buffer_lines() -> Result<()> {
+        let mut buffer = CommandBuffer::new();
+        assert_eq!(buffer.push_line("SELECT")?, vec![]);
+        assert!(!buffer.is_empty());
+        // a backslash within a statement is not a meta-command
+        assert_eq!(
+            buffer.push_line("'\\d';")?,
+            vec![Command::Query("SELECT\n'\\d'".to_string())]
+        );
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.finish(), None);
+        Ok(())
+    }
+}
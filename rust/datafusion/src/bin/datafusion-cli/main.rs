@@ -17,6 +17,10 @@
 
 // Only bring in dependencies for the repl when the cli feature is enabled.
 #[cfg(feature = "cli")]
+mod command;
+#[cfg(feature = "cli")]
+mod print_format;
+#[cfg(feature = "cli")]
 mod repl;
 
 pub fn main() {
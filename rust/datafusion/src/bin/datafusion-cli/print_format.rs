@@ -0,0 +1,246 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The formats that the CLI prints the results of queries in
+
+use arrow::array::{Array, ArrayRef};
+use arrow::csv::WriterBuilder;
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::array_value_to_string;
+use arrow::util::pretty;
+use datafusion::error::{DataFusionError, Result};
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// The format of the results of queries
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrintFormat {
+    /// A table with borders, for reading in a terminal
+    Table,
+    /// Comma separated values with a header row
+    Csv,
+    /// Tab separated values with a header row
+    Tsv,
+    /// A JSON array with an object per row
+    Json,
+    /// A JSON object per line, one for each row
+    NdJson,
+}
+
+/// The names of the formats, as accepted by `--format`
+pub const PRINT_FORMATS: &[&str] = &["table", "csv", "tsv", "json", "ndjson"];
+
+impl FromStr for PrintFormat {
+    type Err = DataFusionError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(PrintFormat::Table),
+            "csv" => Ok(PrintFormat::Csv),
+            "tsv" => Ok(PrintFormat::Tsv),
+            "json" => Ok(PrintFormat::Json),
+            "ndjson" => Ok(PrintFormat::NdJson),
+            other => Err(DataFusionError::Plan(format!(
+                "Unknown output format '{}', expected one of {}",
+                other,
+                PRINT_FORMATS.join(", ")
+            ))),
+        }
+    }
+}
+
+impl PrintFormat {
+    /// Formats `batches` in this format
+    pub fn format_batches(&self, batches: &[RecordBatch]) -> Result<String> {
+        match self {
+            PrintFormat::Table => Ok(pretty::pretty_format_batches(batches)?),
+            PrintFormat::Csv => format_delimited(batches, b','),
+            PrintFormat::Tsv => format_delimited(batches, b'\t'),
+            PrintFormat::Json => {
+                let rows = json_rows(batches)?;
+                if rows.is_empty() {
+                    Ok("[]".to_string())
+                } else {
+                    Ok(format!("[\n  {}\n]", rows.join(",\n  ")))
+                }
+            }
+            PrintFormat::NdJson => Ok(json_rows(batches)?.join("\n")),
+        }
+    }
+
+    /// Prints `batches` to the standard output in this format
+    pub fn print_batches(&self, batches: &[RecordBatch]) -> Result<()> {
+        let formatted = self.format_batches(batches)?;
+        if formatted.ends_with('\n') || formatted.is_empty() {
+            print!("{}", formatted);
+        } else {
+            println!("{}", formatted);
+        }
+        Ok(())
+    }
+}
+
+/// Formats `batches` as delimited values with a header row
+fn format_delimited(batches: &[RecordBatch], delimiter: u8) -> Result<String> {
+    let mut bytes = vec![];
+    {
+        let mut writer = WriterBuilder::new()
+            .with_delimiter(delimiter)
+            .build(&mut bytes);
+        for batch in batches {
+            writer.write(batch)?;
+        }
+    }
+    String::from_utf8(bytes).map_err(|e| DataFusionError::Execution(e.to_string()))
+}
+
+/// Formats each row of `batches` as a JSON object
+fn json_rows(batches: &[RecordBatch]) -> Result<Vec<String>> {
+    let mut rows = vec![];
+    for batch in batches {
+        let schema = batch.schema();
+        for row in 0..batch.num_rows() {
+            let mut object = String::from("{");
+            for (i, column) in batch.columns().iter().enumerate() {
+                if i > 0 {
+                    object.push(',');
+                }
+                write_json_string(&mut object, schema.field(i).name());
+                object.push(':');
+                write_json_value(&mut object, column, row)?;
+            }
+            object.push('}');
+            rows.push(object);
+        }
+    }
+    Ok(rows)
+}
+
+/// Writes the value at `row` of `column`: numbers and booleans as they are, other
+/// values as strings
+fn write_json_value(out: &mut String, column: &ArrayRef, row: usize) -> Result<()> {
+    if column.is_null(row) {
+        out.push_str("null");
+        return Ok(());
+    }
+    let value = array_value_to_string(column, row)?;
+    match column.data_type() {
+        DataType::Boolean
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => out.push_str(&value),
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => {
+            // JSON has no representation of NaN and infinities
+            match value.parse::<f64>() {
+                Ok(v) if v.is_finite() => out.push_str(&value),
+                _ => out.push_str("null"),
+            }
+        }
+        _ => write_json_string(out, &value),
+    }
+    Ok(())
+}
+
+/// Writes `value` as a quoted JSON string
+fn write_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                write!(out, "\\u{:04x}", c as u32).unwrap();
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Float64Array, Int32Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+    use std::sync::Arc;
+
+    fn batch() -> Result<RecordBatch> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+            Field::new("c", DataType::Float64, true),
+        ]));
+        Ok(RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), None])),
+                Arc::new(StringArray::from(vec![Some("x\"y"), Some("z")])),
+                Arc::new(Float64Array::from(vec![Some(0.5), Some(f64::NAN)])),
+            ],
+        )?)
+    }
+
+    #[test]
+    fn parse_formats() -> Result<()> {
+        assert_eq!("CSV".parse::<PrintFormat>()?, PrintFormat::Csv);
+        assert_eq!("ndjson".parse::<PrintFormat>()?, PrintFormat::NdJson);
+        let err = "xml".parse::<PrintFormat>().expect_err("expected an error");
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Unknown output format 'xml', expected one of table, csv, tsv, json, ndjson"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn format_delimited_values() -> Result<()> {
+        let batches = vec![batch()?];
+        assert_eq!(
+            PrintFormat::Csv.format_batches(&batches)?,
+            "a,b,c\n1,\"x\"\"y\",0.5\n,z,NaN\n"
+        );
+        assert_eq!(
+            PrintFormat::Tsv.format_batches(&batches)?,
+            "a\tb\tc\n1\t\"x\"\"y\"\t0.5\n\tz\tNaN\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn format_json() -> Result<()> {
+        let batches = vec![batch()?];
+        assert_eq!(
+            PrintFormat::NdJson.format_batches(&batches)?,
+            "{\"a\":1,\"b\":\"x\\\"y\",\"c\":0.5}\n{\"a\":null,\"b\":\"z\",\"c\":null}"
+        );
+        assert_eq!(
+            PrintFormat::Json.format_batches(&batches)?,
+            "[\n  {\"a\":1,\"b\":\"x\\\"y\",\"c\":0.5},\n  {\"a\":null,\"b\":\"z\",\"c\":null}\n]"
+        );
+        assert_eq!(PrintFormat::Json.format_batches(&[])?, "[]");
+        Ok(())
+    }
+}
@@ -0,0 +1,356 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![allow(bare_trait_objects)]
+
+use crate::command::{parse_commands, Command, CommandBuffer, HELP};
+use crate::print_format::{PrintFormat, PRINT_FORMATS};
+use arrow::array::StringArray;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use clap::{crate_version, App, Arg};
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::{ExecutionConfig, ExecutionContext};
+use rustyline::Editor;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// The name of the script that is executed when the CLI starts, in the home directory
+const RC_FILE: &str = ".datafusionrc";
+
+#[tokio::main]
+pub async fn main() {
+    let matches = App::new("DataFusion")
+        .version(crate_version!())
+        .about(
+            "DataFusion is an in-memory query engine that uses Apache Arrow \
+             as the memory model. It supports executing SQL queries against CSV and \
+             Parquet files as well as querying directly against in-memory data.",
+        )
+        .arg(
+            Arg::with_name("data-path")
+                .help("Path to your data, default to current directory")
+                .short("p")
+                .long("data-path")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("batch-size")
+                .help("The batch size of each query, default value is 1048576")
+                .short("b")
+                .long("batch-size")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("command")
+                .help("Execute the given commands and exit")
+                .short("c")
+                .long("command")
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("file")
+                .help("Execute the commands of the given files and exit")
+                .short("f")
+                .long("file")
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("format")
+                .help("The output format of the results")
+                .long("format")
+                .possible_values(PRINT_FORMATS)
+                .default_value("table"),
+        )
+        .arg(
+            Arg::with_name("quiet")
+                .help("Do not print the number of rows and timing of the results")
+                .short("q")
+                .long("quiet"),
+        )
+        .arg(
+            Arg::with_name("rc")
+                .help("The script executed on startup, default to ~/.datafusionrc")
+                .long("rc")
+                .takes_value(true),
+        )
+        .get_matches();
+
+    if let Some(path) = matches.value_of("data-path") {
+        let p = Path::new(path);
+        env::set_current_dir(&p).unwrap();
+    };
+
+    let batch_size = matches
+        .value_of("batch-size")
+        .map(|size| size.parse::<usize>().unwrap())
+        .unwrap_or(1_048_576);
+
+    let ctx =
+        ExecutionContext::with_config(ExecutionConfig::new().with_batch_size(batch_size));
+
+    let files = matches
+        .values_of("file")
+        .map(|files| files.collect::<Vec<_>>())
+        .unwrap_or_default();
+    let commands = matches
+        .values_of("command")
+        .map(|commands| commands.collect::<Vec<_>>())
+        .unwrap_or_default();
+    let interactive = files.is_empty() && commands.is_empty();
+
+    let mut session = Session {
+        ctx,
+        format: matches
+            .value_of("format")
+            .unwrap_or("table")
+            .parse()
+            .unwrap(),
+        // scripts only print their results, unless they ask for more
+        timing: interactive && !matches.is_present("quiet"),
+        quiet: !interactive || matches.is_present("quiet"),
+    };
+
+    let rc_file = matches
+        .value_of("rc")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(RC_FILE)));
+    if let Some(rc_file) = rc_file.filter(|path| path.is_file()) {
+        if let Err(err) = session.exec_file(&rc_file).await {
+            eprintln!("{}", err);
+        }
+    }
+
+    if interactive {
+        session.exec_interactive().await;
+        return;
+    }
+
+    for file in files {
+        if let Err(err) = session.exec_file(Path::new(file)).await {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+    for command in commands {
+        if let Err(err) = session.exec_script(command).await {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+}
+
+/// The context that the commands are executed in, and how their results are printed
+struct Session {
+    ctx: ExecutionContext,
+    format: PrintFormat,
+    /// Whether to print how long queries take
+    timing: bool,
+    /// Whether to omit the number of rows of results
+    quiet: bool,
+}
+
+impl Session {
+    /// Reads commands from the terminal until the user exits
+    async fn exec_interactive(&mut self) {
+        let mut rl = Editor::<()>::new();
+        rl.load_history(".history").ok();
+
+        let mut buffer = CommandBuffer::new();
+        loop {
+            let line = match rl.readline("> ") {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+            if buffer.is_empty() && is_exit_command(&line) {
+                break;
+            }
+            let commands = match buffer.push_line(&line) {
+                Ok(commands) => commands,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    continue;
+                }
+            };
+            if !commands.is_empty() {
+                rl.add_history_entry(line.trim_end());
+            }
+            for command in commands {
+                match self.exec_command(command).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        rl.save_history(".history").ok();
+                        return;
+                    }
+                    Err(err) => eprintln!("{}", err),
+                }
+            }
+        }
+
+        rl.save_history(".history").ok();
+    }
+
+    /// Executes the commands of the script at `path`
+    async fn exec_file(&mut self, path: &Path) -> Result<()> {
+        let script = fs::read_to_string(path).map_err(|e| {
+            DataFusionError::Execution(format!("Cannot read {}: {}", path.display(), e))
+        })?;
+        self.exec_script(&script).await
+    }
+
+    /// Executes the commands of `script`, stopping at the first error or `\q`
+    async fn exec_script(&mut self, script: &str) -> Result<()> {
+        for command in parse_commands(script)? {
+            if !self.exec_command(command).await? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes `command`, and returns whether to go on with the next commands
+    async fn exec_command(&mut self, command: Command) -> Result<bool> {
+        match command {
+            Command::Query(sql) => self.exec_and_print(&sql).await?,
+            Command::Set(key, value) => self.set(&key, &value)?,
+            Command::ListTables => {
+                let mut tables = self.ctx.tables().into_iter().collect::<Vec<_>>();
+                tables.sort();
+                let schema =
+                    Schema::new(vec![Field::new("table", DataType::Utf8, false)]);
+                let batch = RecordBatch::try_new(
+                    Arc::new(schema),
+                    vec![Arc::new(StringArray::from(
+                        tables.iter().map(|t| t.as_str()).collect::<Vec<_>>(),
+                    ))],
+                )?;
+                self.format.print_batches(&[batch])?;
+            }
+            Command::DescribeTable(name) => {
+                let schema = self.ctx.table(&name)?.schema().clone();
+                let fields = schema.fields();
+                let names = fields.iter().map(|f| f.name().as_str()).collect::<Vec<_>>();
+                let types = fields
+                    .iter()
+                    .map(|f| format!("{:?}", f.data_type()))
+                    .collect::<Vec<_>>();
+                let nullable = fields
+                    .iter()
+                    .map(|f| if f.is_nullable() { "YES" } else { "NO" })
+                    .collect::<Vec<_>>();
+                let batch = RecordBatch::try_new(
+                    Arc::new(Schema::new(vec![
+                        Field::new("column", DataType::Utf8, false),
+                        Field::new("type", DataType::Utf8, false),
+                        Field::new("nullable", DataType::Utf8, false),
+                    ])),
+                    vec![
+                        Arc::new(StringArray::from(names)),
+                        Arc::new(StringArray::from(
+                            types.iter().map(|t| t.as_str()).collect::<Vec<_>>(),
+                        )),
+                        Arc::new(StringArray::from(nullable)),
+                    ],
+                )?;
+                self.format.print_batches(&[batch])?;
+            }
+            Command::ToggleTiming => {
+                self.timing = !self.timing;
+                eprintln!("Timing is {}.", if self.timing { "on" } else { "off" });
+            }
+            Command::ToggleQuiet => {
+                self.quiet = !self.quiet;
+                eprintln!("Quiet is {}.", if self.quiet { "on" } else { "off" });
+            }
+            Command::SetFormat(name) => self.format = name.parse()?,
+            Command::Help => println!("{}", HELP),
+            Command::Quit => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Changes the configuration of the context with `SET key = value`
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let n = value
+            .parse::<usize>()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| {
+                DataFusionError::Plan(format!(
+                    "The value of {} must be a positive integer, but got '{}'",
+                    key, value
+                ))
+            })?;
+        let config = &mut self.ctx.state.config;
+        match key {
+            "batch_size" => config.batch_size = n,
+            "concurrency" => config.concurrency = n,
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "Unknown configuration '{}', expected batch_size or concurrency",
+                    key
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    async fn exec_and_print(&mut self, sql: &str) -> Result<()> {
+        let now = Instant::now();
+
+        let df = self.ctx.sql(sql)?;
+        let results = df.collect().await?;
+
+        let row_count: usize = results.iter().map(|b| b.num_rows()).sum();
+        if row_count > 0 {
+            self.format.print_batches(&results)?;
+        }
+
+        let mut status = vec![];
+        if !self.quiet {
+            status.push(format!(
+                "{} {} in set.",
+                row_count,
+                if row_count == 1 { "row" } else { "rows" }
+            ));
+        }
+        if self.timing {
+            status.push(format!(
+                "Query took {:.3} seconds.",
+                now.elapsed().as_secs_f64()
+            ));
+        }
+        if !status.is_empty() {
+            eprintln!("{}", status.join(" "));
+        }
+
+        Ok(())
+    }
+}
+
+fn is_exit_command(line: &str) -> bool {
+    let line = line.trim_end().to_lowercase();
+    line == "quit" || line == "exit"
+}
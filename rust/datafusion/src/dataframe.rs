@@ -19,8 +19,9 @@
 
 use crate::arrow::record_batch::RecordBatch;
 use crate::error::Result;
-use crate::logical_plan::{Expr, FunctionRegistry, LogicalPlan};
-use arrow::datatypes::Schema;
+use crate::execution::cancellation::{until_cancelled, CancellationToken};
+use crate::logical_plan::{DFSchema, Expr, FunctionRegistry, JoinType, LogicalPlan};
+use crate::physical_plan::SendableRecordBatchStream;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -131,6 +132,22 @@ pub trait DataFrame {
     /// ```
     fn limit(&self, n: usize) -> Result<Arc<dyn DataFrame>>;
 
+    /// Keep a random sample of the rows of this DataFrame, each of which is kept with the
+    /// probability `fraction`, between 0 and 1. The same `seed` keeps the same rows of
+    /// the same data.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// let df = df.sample(0.1, 42)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn sample(&self, fraction: f64, seed: u64) -> Result<Arc<dyn DataFrame>>;
+
     /// Sort the DataFrame by the specified sorting expressions. Any expression can be turned into
     /// a sort expression by calling its [sort](../logical_plan/enum.Expr.html#method.sort) method.
     ///
@@ -146,6 +163,194 @@ pub trait DataFrame {
     /// ```
     fn sort(&self, expr: Vec<Expr>) -> Result<Arc<dyn DataFrame>>;
 
+    /// Join this DataFrame with another DataFrame, on the equality of each of the
+    /// columns `left_cols` of this DataFrame with the column of `right_cols` at the
+    /// same position. When `left_cols` and `right_cols` are the same names, the right
+    /// columns are merged into the left ones, as with `JOIN ... USING`.
+    ///
+    /// The inputs are sorted on the join columns, unless they already are, and merged.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut ctx = ExecutionContext::new();
+    /// let left = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// let right = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?
+    ///     .select(vec![col("a"), col("b").alias("b2")])?;
+    /// let df = left.join(right, JoinType::Inner, &["a"], &["a"])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn join(
+        &self,
+        right: Arc<dyn DataFrame>,
+        join_type: JoinType,
+        left_cols: &[&str],
+        right_cols: &[&str],
+    ) -> Result<Arc<dyn DataFrame>>;
+
+    /// Produce a row for each element of the lists of the list column `column`,
+    /// repeating the other columns. Rows whose list is empty or null are dropped.
+    ///
+    /// ```no_run
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_parquet("orders.parquet")?;
+    /// let df = df.unnest("items")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn unnest(&self, column: &str) -> Result<Arc<dyn DataFrame>>;
+
+    /// Return a new DataFrame without duplicate rows.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// let df = df.select_columns(vec!["a"])?.distinct()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn distinct(&self) -> Result<Arc<dyn DataFrame>>;
+
+    /// Return a new DataFrame with the column `name` computed by `expr`, which replaces
+    /// the column of the same name if there is one and is appended otherwise.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// let df = df.with_column("ab", col("a") + col("b"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn with_column(&self, name: &str, expr: Expr) -> Result<Arc<dyn DataFrame>>;
+
+    /// Return a new DataFrame without the given columns.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// let df = df.drop_columns(&["b", "c"])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn drop_columns(&self, columns: &[&str]) -> Result<Arc<dyn DataFrame>>;
+
+    /// Return a new DataFrame with the column `old_name` renamed to `new_name`.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// let df = df.with_column_renamed("a", "id")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn with_column_renamed(
+        &self,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<Arc<dyn DataFrame>>;
+
+    /// Executes this DataFrame to compute summary statistics of its columns, and
+    /// returns them as a DataFrame with a `summary` column naming the statistic (count,
+    /// mean, std, min and max) and a string column for each column of this DataFrame.
+    /// The statistics that do not apply to the type of a column are null.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// df.describe().await?.show().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn describe(&self) -> Result<Arc<dyn DataFrame>>;
+
+    /// Executes this DataFrame and prints its results to the standard output.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// df.show().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn show(&self) -> Result<()>;
+
+    /// Executes this DataFrame and prints its first `n` rows to the standard output.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// df.show_limit(10).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn show_limit(&self, n: usize) -> Result<()>;
+
+    /// Executes this DataFrame and returns a new DataFrame over its results, which are
+    /// held in memory, so that they are not computed again by the queries of the new
+    /// DataFrame.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// let df = df.filter(col("a").lt_eq(col("b")))?.cache().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn cache(&self) -> Result<Arc<dyn DataFrame>>;
+
+    /// Executes this DataFrame and returns a stream of its results, merged into a
+    /// single partition, instead of collecting them in memory.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use futures::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// let mut stream = df.execute_stream().await?;
+    /// while let Some(batch) = stream.next().await {
+    ///     let batch = batch?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn execute_stream(&self) -> Result<SendableRecordBatchStream>;
+
     /// Executes this DataFrame and collects all results into a vector of RecordBatch.
     ///
     /// ```
@@ -161,6 +366,34 @@ pub trait DataFrame {
     /// ```
     async fn collect(&self) -> Result<Vec<RecordBatch>>;
 
+    /// Executes this DataFrame and collects all results into a vector of RecordBatch,
+    /// unless `token` is cancelled first, in which case the execution is stopped and
+    /// `DataFusionError::Cancelled` is returned.
+    ///
+    /// The default implementation drops the future of [DataFrame::collect] when `token`
+    /// is cancelled.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion::execution::cancellation::CancellationToken;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let mut ctx = ExecutionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new())?;
+    /// let token = CancellationToken::new();
+    /// // `token` can be cancelled from another task, e.g. when the client disconnects
+    /// let batches = df.collect_with_cancellation(&token).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn collect_with_cancellation(
+        &self,
+        token: &CancellationToken,
+    ) -> Result<Vec<RecordBatch>> {
+        until_cancelled(self.collect(), token).await
+    }
+
     /// Returns the schema describing the output of this DataFrame in terms of columns returned,
     /// where each column has a name, data type, and nullability attribute.
 
@@ -174,7 +407,7 @@ pub trait DataFrame {
     /// # Ok(())
     /// # }
     /// ```
-    fn schema(&self) -> &Schema;
+    fn schema(&self) -> &DFSchema;
 
     /// Return the logical plan represented by this DataFrame.
     fn to_logical_plan(&self) -> LogicalPlan;
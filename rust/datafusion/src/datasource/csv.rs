@@ -37,11 +37,12 @@ use arrow::datatypes::SchemaRef;
 use std::string::String;
 use std::sync::Arc;
 
+use crate::datasource::object_store::{LocalFileSystem, ObjectStore};
 use crate::datasource::TableProvider;
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::csv::CsvExec;
 pub use crate::physical_plan::csv::CsvReadOptions;
-use crate::physical_plan::{common, ExecutionPlan};
+use crate::physical_plan::ExecutionPlan;
 
 /// Represents a CSV file with a provided schema
 pub struct CsvFile {
@@ -51,20 +52,33 @@ pub struct CsvFile {
     has_header: bool,
     delimiter: u8,
     file_extension: String,
+    target_partition_size: u64,
+    newlines_in_values: bool,
+    /// The store that the files are read from
+    object_store: Arc<dyn ObjectStore>,
 }
 
 impl CsvFile {
     /// Attempt to initialize a new `CsvFile` from a file path
     pub fn try_new(path: &str, options: CsvReadOptions) -> Result<Self> {
+        Self::try_new_with_object_store(path, options, Arc::new(LocalFileSystem))
+    }
+
+    /// Attempt to initialize a new `CsvFile` from a path of `object_store`
+    pub fn try_new_with_object_store(
+        path: &str,
+        options: CsvReadOptions,
+        object_store: Arc<dyn ObjectStore>,
+    ) -> Result<Self> {
         let schema = Arc::new(match options.schema {
             Some(s) => s.clone(),
             None => {
-                let mut filenames: Vec<String> = vec![];
-                common::build_file_list(path, &mut filenames, options.file_extension)?;
-                if filenames.is_empty() {
-                    return Err(DataFusionError::Plan("No files found".to_string()));
+                match CsvExec::list_files_and_schema(&object_store, path, &options)? {
+                    (_, Some(schema)) => schema,
+                    (_, None) => {
+                        return Err(DataFusionError::Plan("No files found".to_string()))
+                    }
                 }
-                CsvExec::try_infer_schema(&filenames, &options)?
             }
         });
 
@@ -74,6 +88,9 @@ impl CsvFile {
             has_header: options.has_header,
             delimiter: options.delimiter,
             file_extension: String::from(options.file_extension),
+            target_partition_size: options.target_partition_size,
+            newlines_in_values: options.newlines_in_values,
+            object_store,
         })
     }
 }
@@ -88,15 +105,18 @@ impl TableProvider for CsvFile {
         projection: &Option<Vec<usize>>,
         batch_size: usize,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(CsvExec::try_new(
+        Ok(Arc::new(CsvExec::try_new_with_object_store(
             &self.path,
             CsvReadOptions::new()
                 .schema(&self.schema)
                 .has_header(self.has_header)
                 .delimiter(self.delimiter)
-                .file_extension(self.file_extension.as_str()),
+                .file_extension(self.file_extension.as_str())
+                .target_partition_size(self.target_partition_size)
+                .newlines_in_values(self.newlines_in_values),
             projection.clone(),
             batch_size,
+            self.object_store.clone(),
         )?))
     }
 }
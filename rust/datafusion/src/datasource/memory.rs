@@ -27,6 +27,7 @@ use arrow::record_batch::RecordBatch;
 use crate::datasource::TableProvider;
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::common;
+use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::memory::MemoryExec;
 use crate::physical_plan::ExecutionPlan;
 
@@ -34,6 +35,7 @@ use crate::physical_plan::ExecutionPlan;
 pub struct MemTable {
     schema: SchemaRef,
     batches: Vec<Vec<RecordBatch>>,
+    sort_order: Vec<PhysicalSortExpr>,
 }
 
 impl MemTable {
@@ -47,6 +49,7 @@ impl MemTable {
             Ok(Self {
                 schema,
                 batches: partitions,
+                sort_order: vec![],
             })
         } else {
             Err(DataFusionError::Plan(
@@ -55,6 +58,14 @@ impl MemTable {
         }
     }
 
+    /// Declare that the rows of each partition are sorted by `sort_order`, which sorts on
+    /// columns of the table, so that queries do not sort them again. The rows are not
+    /// checked to be sorted.
+    pub fn with_sort_order(mut self, sort_order: Vec<PhysicalSortExpr>) -> Self {
+        self.sort_order = sort_order;
+        self
+    }
+
     /// Create a mem table by reading from another data source
     pub async fn load(t: &dyn TableProvider, batch_size: usize) -> Result<Self> {
         let schema = t.schema();
@@ -64,7 +75,7 @@ impl MemTable {
         let tasks = (0..partition_count)
             .map(|part_i| {
                 let exec = exec.clone();
-                tokio::spawn(async move {
+                common::spawn_abortable(async move {
                     let stream = exec.execute(part_i).await?;
                     common::collect(stream).await
                 })
@@ -76,7 +87,7 @@ impl MemTable {
         let mut data: Vec<Vec<RecordBatch>> =
             Vec::with_capacity(exec.output_partitioning().partition_count());
         for task in tasks {
-            let result = task.await.expect("MemTable::load could not join task")?;
+            let result = task.await??;
             data.push(result);
         }
 
@@ -120,12 +131,16 @@ impl TableProvider for MemTable {
             .collect();
 
         let projected_schema = Arc::new(Schema::new(projected_columns?));
-
-        Ok(Arc::new(MemoryExec::try_new(
-            &self.batches.clone(),
-            projected_schema,
-            projection.clone(),
-        )?))
+        let ordering = common::project_ordering(&self.sort_order, &projected_schema);
+
+        Ok(Arc::new(
+            MemoryExec::try_new(
+                &self.batches.clone(),
+                projected_schema,
+                projection.clone(),
+            )?
+            .with_output_ordering(ordering),
+        ))
     }
 }
 
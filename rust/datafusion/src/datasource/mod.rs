@@ -20,8 +20,13 @@
 pub mod csv;
 pub mod datasource;
 pub mod memory;
+pub mod object_store;
 pub mod parquet;
+pub mod series;
+pub mod stream;
 
 pub use self::csv::{CsvFile, CsvReadOptions};
 pub use self::datasource::TableProvider;
 pub use self::memory::MemTable;
+pub use self::object_store::{LocalFileSystem, ObjectStore};
+pub use self::stream::StreamTable;
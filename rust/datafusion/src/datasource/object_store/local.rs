@@ -0,0 +1,74 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Object store of the local filesystem
+
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+
+use async_trait::async_trait;
+
+use super::{ObjectMeta, ObjectStore};
+use crate::error::{DataFusionError, Result};
+
+/// An [ObjectStore] whose objects are the files of the local filesystem, and whose
+/// paths are the paths of the files. The prefix of a listing is either the path of a
+/// file, or the path of a directory whose files are listed recursively.
+#[derive(Debug, Default)]
+pub struct LocalFileSystem;
+
+#[async_trait]
+impl ObjectStore for LocalFileSystem {
+    async fn list(&self, prefix: &str) -> Result<Vec<ObjectMeta>> {
+        let mut objects = vec![];
+        list_files(prefix, &mut objects)?;
+        objects.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(objects)
+    }
+
+    async fn size(&self, path: &str) -> Result<u64> {
+        Ok(fs::metadata(path)?.len())
+    }
+
+    async fn read_range(&self, path: &str, start: u64, length: usize) -> Result<Vec<u8>> {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(start))?;
+        let mut buffer = Vec::with_capacity(length);
+        file.take(length as u64).read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+/// Recursively add the files at `path` to `objects`
+fn list_files(path: &str, objects: &mut Vec<ObjectMeta>) -> Result<()> {
+    let metadata = fs::metadata(path)?;
+    if metadata.is_file() {
+        objects.push(ObjectMeta {
+            path: path.to_string(),
+            size: metadata.len(),
+        });
+    } else {
+        for entry in fs::read_dir(path)? {
+            let entry_path = entry?.path();
+            match entry_path.to_str() {
+                Some(path_name) => list_files(path_name, objects)?,
+                None => return Err(DataFusionError::Plan("Invalid path".to_string())),
+            }
+        }
+    }
+    Ok(())
+}
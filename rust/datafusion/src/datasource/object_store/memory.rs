@@ -0,0 +1,83 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Object store that holds its objects in memory
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+
+use super::{ObjectMeta, ObjectStore};
+use crate::error::{DataFusionError, Result};
+
+/// An [ObjectStore] that holds its objects in memory, mostly useful in tests
+#[derive(Debug, Default)]
+pub struct InMemoryObjectStore {
+    objects: RwLock<BTreeMap<String, Vec<u8>>>,
+}
+
+impl InMemoryObjectStore {
+    /// Create a new, empty, store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store `data` at `path`, replacing the object that was there, if any
+    pub fn put(&self, path: &str, data: Vec<u8>) {
+        self.objects.write().unwrap().insert(path.to_string(), data);
+    }
+
+    fn not_found(path: &str) -> DataFusionError {
+        DataFusionError::Execution(format!("Object not found: {}", path))
+    }
+}
+
+#[async_trait]
+impl ObjectStore for InMemoryObjectStore {
+    async fn list(&self, prefix: &str) -> Result<Vec<ObjectMeta>> {
+        Ok(self
+            .objects
+            .read()
+            .unwrap()
+            .range(prefix.to_string()..)
+            .take_while(|(path, _)| path.starts_with(prefix))
+            .map(|(path, data)| ObjectMeta {
+                path: path.clone(),
+                size: data.len() as u64,
+            })
+            .collect())
+    }
+
+    async fn size(&self, path: &str) -> Result<u64> {
+        match self.objects.read().unwrap().get(path) {
+            Some(data) => Ok(data.len() as u64),
+            None => Err(Self::not_found(path)),
+        }
+    }
+
+    async fn read_range(&self, path: &str, start: u64, length: usize) -> Result<Vec<u8>> {
+        match self.objects.read().unwrap().get(path) {
+            Some(data) => {
+                let start = (start as usize).min(data.len());
+                let end = start.saturating_add(length).min(data.len());
+                Ok(data[start..end].to_vec())
+            }
+            None => Err(Self::not_found(path)),
+        }
+    }
+}
@@ -0,0 +1,260 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Access to the files of file-based data sources, such as CSV and Parquet files,
+//! wherever they are stored.
+//!
+//! An [ObjectStore] lists the objects under a prefix and reads byte ranges of them,
+//! so that a scan only fetches the parts of a file that it needs. [LocalFileSystem]
+//! reads files from the local filesystem, and [InMemoryObjectStore] serves objects held
+//! in memory, which is mostly useful in tests.
+
+pub mod local;
+pub mod memory;
+
+use std::fmt::Debug;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use async_trait::async_trait;
+use futures::executor::block_on;
+use tokio::runtime::Handle;
+
+use crate::error::{DataFusionError, Result};
+
+pub use self::local::LocalFileSystem;
+pub use self::memory::InMemoryObjectStore;
+
+/// The size of the ranges that an [ObjectReader] fetches at once
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// The description of an object of an [ObjectStore]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectMeta {
+    /// The path of the object in its store
+    pub path: String,
+    /// The size of the object, in bytes
+    pub size: u64,
+}
+
+/// A store of objects, such as files, that can be listed and read by byte ranges.
+///
+/// The scans call the store from threads of their own, see [spawn_store_thread], which
+/// enter the tokio runtime of the query, so that implementations may rely on it.
+#[async_trait]
+pub trait ObjectStore: Debug + Send + Sync {
+    /// Returns the objects whose path starts with `prefix`, ordered by path
+    async fn list(&self, prefix: &str) -> Result<Vec<ObjectMeta>>;
+
+    /// Returns the size of the object at `path`, in bytes
+    async fn size(&self, path: &str) -> Result<u64>;
+
+    /// Returns the `length` bytes of the object at `path` that start at offset `start`.
+    /// Fewer bytes are returned if the object ends before the range does.
+    async fn read_range(&self, path: &str, start: u64, length: usize) -> Result<Vec<u8>>;
+}
+
+/// Returns the objects of `store` under `prefix` whose path ends with `extension`
+pub async fn list_files(
+    store: &dyn ObjectStore,
+    prefix: &str,
+    extension: &str,
+) -> Result<Vec<ObjectMeta>> {
+    Ok(store
+        .list(prefix)
+        .await?
+        .into_iter()
+        .filter(|object| object.path.ends_with(extension))
+        .collect())
+}
+
+/// Spawns `f`, which blocks on calls to object stores, on a thread of its own, so that
+/// the workers of the async runtime are not blocked. The thread enters the tokio
+/// runtime of the caller, if there is one, for the stores that rely on it.
+pub(crate) fn spawn_store_thread<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let runtime = Handle::try_current().ok();
+    thread::spawn(move || match runtime {
+        Some(runtime) => runtime.enter(f),
+        None => f(),
+    })
+}
+
+/// Runs `f`, which blocks on calls to object stores, on a thread spawned by
+/// [spawn_store_thread], and waits for its result. This is for the synchronous
+/// constructors of the scans, which list the files and read their schemas.
+pub(crate) fn run_on_store_thread<F, T>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    spawn_store_thread(f).join().unwrap_or_else(|_| {
+        Err(DataFusionError::Internal(
+            "The thread calling the object store panicked".to_string(),
+        ))
+    })
+}
+
+/// A synchronous reader of an object, for readers such as the CSV reader that consume
+/// `Read` implementations. It fetches the object by ranges of a fixed size as it is
+/// read, blocking on the store, so it is meant to be used from a thread spawned by
+/// [spawn_store_thread].
+#[derive(Debug)]
+pub struct ObjectReader {
+    store: Arc<dyn ObjectStore>,
+    object: ObjectMeta,
+    /// The offset of the next byte to read
+    position: u64,
+    /// The bytes of the last range fetched from the store
+    buffer: Vec<u8>,
+    /// The offset of the first byte of `buffer`
+    buffer_start: u64,
+}
+
+impl ObjectReader {
+    /// Create a reader of `object`, positioned at its start
+    pub fn new(store: Arc<dyn ObjectStore>, object: ObjectMeta) -> Self {
+        Self {
+            store,
+            object,
+            position: 0,
+            buffer: vec![],
+            buffer_start: 0,
+        }
+    }
+
+    /// Create a reader of the object at `path`, fetching its size from `store`
+    pub fn try_new(store: Arc<dyn ObjectStore>, path: &str) -> Result<Self> {
+        let size = block_on(store.size(path))?;
+        let object = ObjectMeta {
+            path: path.to_string(),
+            size,
+        };
+        Ok(Self::new(store, object))
+    }
+}
+
+impl Read for ObjectReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.object.size || buf.is_empty() {
+            return Ok(0);
+        }
+        let buffer_end = self.buffer_start + self.buffer.len() as u64;
+        if self.position < self.buffer_start || self.position >= buffer_end {
+            self.buffer = block_on(self.store.read_range(
+                &self.object.path,
+                self.position,
+                READ_BUFFER_SIZE,
+            ))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            self.buffer_start = self.position;
+            if self.buffer.is_empty() {
+                return Ok(0);
+            }
+        }
+        let offset = (self.position - self.buffer_start) as usize;
+        let len = buf.len().min(self.buffer.len() - offset);
+        buf[..len].copy_from_slice(&self.buffer[offset..offset + len]);
+        self.position += len as u64;
+        Ok(len)
+    }
+}
+
+impl Seek for ObjectReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => offset_position(self.object.size, offset),
+            SeekFrom::Current(offset) => offset_position(self.position, offset),
+        };
+        match position {
+            Some(position) => {
+                self.position = position;
+                Ok(position)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                DataFusionError::Execution(format!(
+                    "Invalid seek to a negative position in {}",
+                    self.object.path
+                )),
+            )),
+        }
+    }
+}
+
+fn offset_position(base: u64, offset: i64) -> Option<u64> {
+    if offset >= 0 {
+        base.checked_add(offset as u64)
+    } else {
+        base.checked_sub(offset.wrapping_neg() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_and_seek() -> Result<()> {
+        let store = Arc::new(InMemoryObjectStore::new());
+        let data: Vec<u8> = (0..=255).cycle().take(READ_BUFFER_SIZE + 100).collect();
+        store.put("a/b.bin", data.clone());
+        store.put("a/c.txt", vec![]);
+
+        let objects = block_on(list_files(store.as_ref(), "a/", ".bin"))?;
+        assert_eq!(
+            objects,
+            vec![ObjectMeta {
+                path: "a/b.bin".to_string(),
+                size: data.len() as u64,
+            }]
+        );
+
+        let mut reader = ObjectReader::try_new(store, "a/b.bin")?;
+        let mut read = vec![];
+        reader.read_to_end(&mut read)?;
+        assert_eq!(data, read);
+
+        reader.seek(SeekFrom::End(-2))?;
+        let mut buf = [0; 4];
+        assert_eq!(2, reader.read(&mut buf)?);
+        assert_eq!(&data[data.len() - 2..], &buf[..2]);
+
+        reader.seek(SeekFrom::Start(10))?;
+        reader.seek(SeekFrom::Current(-5))?;
+        reader.read_exact(&mut buf)?;
+        assert_eq!(&data[5..9], &buf[..]);
+        assert!(reader.seek(SeekFrom::Current(-10)).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn store_thread_enters_runtime() -> Result<()> {
+        let current = thread::current().id();
+        let (id, in_runtime) = run_on_store_thread(|| {
+            Ok((thread::current().id(), Handle::try_current().is_ok()))
+        })?;
+        assert_ne!(current, id);
+        assert!(in_runtime);
+        Ok(())
+    }
+}
@@ -22,8 +22,11 @@ use std::sync::Arc;
 
 use arrow::datatypes::*;
 
+use crate::datasource::object_store::{LocalFileSystem, ObjectStore};
 use crate::datasource::TableProvider;
 use crate::error::Result;
+use crate::physical_plan::common;
+use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::parquet::ParquetExec;
 use crate::physical_plan::ExecutionPlan;
 
@@ -31,18 +34,39 @@ use crate::physical_plan::ExecutionPlan;
 pub struct ParquetTable {
     path: String,
     schema: SchemaRef,
+    object_store: Arc<dyn ObjectStore>,
+    sort_order: Vec<PhysicalSortExpr>,
 }
 
 impl ParquetTable {
     /// Attempt to initialize a new `ParquetTable` from a file path.
     pub fn try_new(path: &str) -> Result<Self> {
-        let parquet_exec = ParquetExec::try_new(path, None, 0)?;
+        Self::try_new_with_object_store(path, Arc::new(LocalFileSystem))
+    }
+
+    /// Attempt to initialize a new `ParquetTable` from a path of `object_store`.
+    pub fn try_new_with_object_store(
+        path: &str,
+        object_store: Arc<dyn ObjectStore>,
+    ) -> Result<Self> {
+        let parquet_exec =
+            ParquetExec::try_new_with_object_store(path, None, 0, object_store.clone())?;
         let schema = parquet_exec.schema();
         Ok(Self {
             path: path.to_string(),
             schema,
+            object_store,
+            sort_order: vec![],
         })
     }
+
+    /// Declare that the rows of each file are sorted by `sort_order`, which sorts on
+    /// columns of the table, so that queries do not sort them again. The rows are not
+    /// checked to be sorted.
+    pub fn with_sort_order(mut self, sort_order: Vec<PhysicalSortExpr>) -> Self {
+        self.sort_order = sort_order;
+        self
+    }
 }
 
 impl TableProvider for ParquetTable {
@@ -58,11 +82,14 @@ impl TableProvider for ParquetTable {
         projection: &Option<Vec<usize>>,
         batch_size: usize,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(ParquetExec::try_new(
+        let exec = ParquetExec::try_new_with_object_store(
             &self.path,
             projection.clone(),
             batch_size,
-        )?))
+            self.object_store.clone(),
+        )?;
+        let ordering = common::project_ordering(&self.sort_order, &exec.schema());
+        Ok(Arc::new(exec.with_output_ordering(ordering)))
     }
 }
 
@@ -0,0 +1,88 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Table of a series of numbers or timestamps, which the `generate_series` and
+//! `range` table functions produce
+
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+
+use crate::datasource::TableProvider;
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::series::{SeriesExec, SeriesStep};
+use crate::physical_plan::ExecutionPlan;
+
+/// Table of the values from `start` to `stop`, separated by `step`, in a single
+/// column named `value`
+pub struct SeriesTable {
+    schema: SchemaRef,
+    start: i64,
+    stop: i64,
+    step: SeriesStep,
+    include_stop: bool,
+}
+
+impl SeriesTable {
+    /// Create a new table of the values of type `data_type`, which is `Int64` or
+    /// `Timestamp(Nanosecond, None)`, from `start` to `stop`, separated by `step`,
+    /// including `stop` if `include_stop` is true
+    pub fn new(
+        data_type: DataType,
+        start: i64,
+        stop: i64,
+        step: SeriesStep,
+        include_stop: bool,
+    ) -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![Field::new("value", data_type, false)])),
+            start,
+            stop,
+            step,
+            include_stop,
+        }
+    }
+}
+
+impl TableProvider for SeriesTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if let Some(projection) = projection {
+            if projection.iter().any(|i| *i != 0) {
+                return Err(DataFusionError::Internal(format!(
+                    "Projection {:?} is out of the columns of a series",
+                    projection
+                )));
+            }
+        }
+        Ok(Arc::new(SeriesExec::try_new(
+            self.start,
+            self.stop,
+            self.step,
+            self.include_stop,
+            self.schema.clone(),
+            batch_size,
+        )?))
+    }
+}
@@ -0,0 +1,242 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Table of the batches of a stream, e.g. of a live source, which queries read as
+//! they arrive.
+//!
+//! Queries of an unbounded stream run as long as the stream does, and their results
+//! are read with `DataFrame::execute_stream` rather than collected. Aggregations
+//! of an unbounded stream must be grouped by a `tumble` window of its event time:
+//!
+//! ```sql
+//! SELECT tumble(ts, INTERVAL '1 minute'), COUNT(*)
+//! FROM events
+//! GROUP BY tumble(ts, INTERVAL '1 minute')
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use arrow::datatypes::SchemaRef;
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+use futures::Stream;
+
+use crate::datasource::TableProvider;
+use crate::error::Result;
+use crate::physical_plan::stream::{
+    RecordBatchStreamAdapter, SharedRecordBatchStream, StreamExec,
+};
+use crate::physical_plan::{ExecutionPlan, SendableRecordBatchStream};
+
+/// Table of the batches of a stream. The stream is read by the first query of
+/// the table, as its batches are not kept.
+pub struct StreamTable {
+    schema: SchemaRef,
+    stream: SharedRecordBatchStream,
+    unbounded: bool,
+}
+
+impl StreamTable {
+    /// Create a new table of the batches of `stream`, which is unbounded
+    pub fn new(stream: SendableRecordBatchStream) -> Self {
+        Self {
+            schema: stream.schema(),
+            stream: Arc::new(Mutex::new(Some(stream))),
+            unbounded: true,
+        }
+    }
+
+    /// Create a new table of the batches of `stream`, which are of schema `schema`
+    pub fn from_stream<S>(schema: SchemaRef, stream: S) -> Self
+    where
+        S: Stream<Item = ArrowResult<RecordBatch>> + Send + 'static,
+    {
+        Self::new(Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            Box::pin(stream),
+        )))
+    }
+
+    /// Declare whether the stream may not end. Aggregations of bounded streams are
+    /// planned as those of any other table.
+    pub fn with_unbounded(mut self, unbounded: bool) -> Self {
+        self.unbounded = unbounded;
+        self
+    }
+}
+
+impl TableProvider for StreamTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _batch_size: usize,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(StreamExec::try_new(
+            self.stream.clone(),
+            self.schema.clone(),
+            projection.clone(),
+            self.unbounded,
+        )?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::context::ExecutionContext;
+    use arrow::array::{Array, Int32Array, Int64Array, TimestampNanosecondArray};
+    use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use futures::channel::mpsc;
+    use futures::StreamExt;
+
+    /// Minute `m` of 2021-01-01, in nanoseconds
+    fn minute(m: i64) -> i64 {
+        1_609_459_200_000_000_000 + m * 60_000_000_000
+    }
+
+    fn events(schema: &SchemaRef, ts: Vec<i64>, v: Vec<i32>) -> RecordBatch {
+        RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(TimestampNanosecondArray::from_vec(ts, None)),
+                Arc::new(Int32Array::from(v)),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn sum(batch: &RecordBatch) -> i64 {
+        let sum = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        sum.value(0)
+    }
+
+    #[tokio::test]
+    async fn filter_and_project_as_batches_arrive() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("ts", DataType::Timestamp(TimeUnit::Nanosecond, None), false),
+            Field::new("v", DataType::Int32, false),
+        ]));
+        let (sender, receiver) = mpsc::unbounded();
+        let mut ctx = ExecutionContext::new();
+        ctx.register_table(
+            "events",
+            Box::new(StreamTable::from_stream(schema.clone(), receiver)),
+        );
+
+        let df = ctx.sql("SELECT v FROM events WHERE v > 1")?;
+        let mut results = df.execute_stream().await?;
+
+        sender
+            .unbounded_send(Ok(events(&schema, vec![minute(0), minute(0)], vec![1, 2])))
+            .unwrap();
+        let batch = results.next().await.unwrap()?;
+        assert_eq!(batch.num_columns(), 1);
+        let v = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(v.len(), 1);
+        assert_eq!(v.value(0), 2);
+
+        drop(sender);
+        assert!(results.next().await.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tumbling_windows_are_emitted_once_closed() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("ts", DataType::Timestamp(TimeUnit::Nanosecond, None), false),
+            Field::new("v", DataType::Int32, false),
+        ]));
+        let (sender, receiver) = mpsc::unbounded();
+        let mut ctx = ExecutionContext::new();
+        ctx.register_table(
+            "events",
+            Box::new(StreamTable::from_stream(schema.clone(), receiver)),
+        );
+
+        let df = ctx.sql(
+            "SELECT tumble(ts, INTERVAL '1 minute'), SUM(v) \
+             FROM events GROUP BY tumble(ts, INTERVAL '1 minute')",
+        )?;
+        let mut results = df.execute_stream().await?;
+
+        // the first window is emitted when a row of a later window arrives
+        sender
+            .unbounded_send(Ok(events(&schema, vec![minute(0), minute(0)], vec![1, 2])))
+            .unwrap();
+        sender
+            .unbounded_send(Ok(events(&schema, vec![minute(1), minute(0)], vec![3, 4])))
+            .unwrap();
+        let batch = results.next().await.unwrap()?;
+        assert_eq!(batch.num_rows(), 1);
+        let window = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .unwrap();
+        assert_eq!(window.value(0), minute(0));
+        assert_eq!(sum(&batch), 7);
+
+        // the last window is emitted at the end of the stream, the late row of the
+        // first window is dropped
+        sender
+            .unbounded_send(Ok(events(&schema, vec![minute(0), minute(1)], vec![5, 6])))
+            .unwrap();
+        drop(sender);
+        let batch = results.next().await.unwrap()?;
+        let window = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .unwrap();
+        assert_eq!(window.value(0), minute(1));
+        assert_eq!(sum(&batch), 9);
+        assert!(results.next().await.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn aggregation_without_window() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int32, false)]));
+        let mut ctx = ExecutionContext::new();
+        ctx.register_table(
+            "events",
+            Box::new(StreamTable::from_stream(schema, futures::stream::empty())),
+        );
+
+        let plan = ctx.create_logical_plan("SELECT SUM(v) FROM events")?;
+        let plan = ctx.optimize(&plan)?;
+        let err = ctx.create_physical_plan(&plan).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Aggregations of unbounded inputs must be grouped \
+             by a tumble window"
+        );
+        Ok(())
+    }
+}
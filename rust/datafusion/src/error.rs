@@ -56,6 +56,11 @@ pub enum DataFusionError {
     /// Error returned during execution of the query.
     /// Examples include files not found, errors in parsing certain types.
     Execution(String),
+    /// Error returned when a query needs more resources, such as memory, than it is
+    /// allowed to use.
+    ResourcesExhausted(String),
+    /// Error returned when a query is cancelled, or times out, before it completes.
+    Cancelled(String),
 }
 
 impl DataFusionError {
@@ -73,7 +78,14 @@ impl From<io::Error> for DataFusionError {
 
 impl From<ArrowError> for DataFusionError {
     fn from(e: ArrowError) -> Self {
-        DataFusionError::ArrowError(e)
+        match e {
+            // unwrap the errors that operators pass through arrow streams
+            ArrowError::ExternalError(e) => match e.downcast::<DataFusionError>() {
+                Ok(e) => *e,
+                Err(e) => DataFusionError::ArrowError(ArrowError::ExternalError(e)),
+            },
+            e => DataFusionError::ArrowError(e),
+        }
     }
 }
 
@@ -113,6 +125,12 @@ impl Display for DataFusionError {
             DataFusionError::Execution(ref desc) => {
                 write!(f, "Execution error: {}", desc)
             }
+            DataFusionError::ResourcesExhausted(ref desc) => {
+                write!(f, "Resources exhausted: {}", desc)
+            }
+            DataFusionError::Cancelled(ref desc) => {
+                write!(f, "Query cancelled: {}", desc)
+            }
         }
     }
 }
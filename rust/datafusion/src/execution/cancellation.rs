@@ -0,0 +1,293 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Cancellation of running queries.
+//!
+//! Dropping the stream of an execution plan stops all the work that produces it,
+//! including the threads and tasks that its operators started. A query collected with
+//! [ExecutionContext::collect_with_cancellation] is dropped, and therefore stopped, when
+//! its [CancellationToken] is cancelled or its timeout elapses.
+//!
+//! [ExecutionContext::collect_with_cancellation]: super::context::ExecutionContext::collect_with_cancellation
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use arrow::record_batch::RecordBatch;
+use futures::future::{self, Either};
+use futures::{pin_mut, StreamExt};
+
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::SendableRecordBatchStream;
+
+/// A token to cancel the queries it is given to, from another task or thread. Clones
+/// of a token share its state, so that cancelling any of them cancels all the queries.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<TokenState>,
+}
+
+#[derive(Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    /// The tasks waiting for the token to be cancelled, by the id of their [Cancelled]
+    wakers: Mutex<HashMap<usize, Waker>>,
+    /// The id of the next [Cancelled] future of the token
+    next_id: AtomicUsize,
+}
+
+impl CancellationToken {
+    /// Create a new token, that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the queries of this token. Queries that start afterwards are cancelled
+    /// right away.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        let wakers = std::mem::take(&mut *self.inner.wakers.lock().unwrap());
+        wakers.into_iter().for_each(|(_, waker)| waker.wake());
+    }
+
+    /// Whether this token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// A future that completes once this token is cancelled
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled {
+            token: self.clone(),
+            id: self.inner.next_id.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
+
+impl std::fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// Future returned by [CancellationToken::cancelled]. The waker of its task is
+/// unregistered from the token when it is dropped.
+pub struct Cancelled {
+    token: CancellationToken,
+    id: usize,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        let mut wakers = self.token.inner.wakers.lock().unwrap();
+        // the token may have been cancelled before the lock was taken
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        match wakers.get_mut(&self.id) {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            Some(waker) => *waker = cx.waker().clone(),
+            None => {
+                wakers.insert(self.id, cx.waker().clone());
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Cancelled {
+    fn drop(&mut self) {
+        if let Ok(mut wakers) = self.token.inner.wakers.lock() {
+            wakers.remove(&self.id);
+        }
+    }
+}
+
+/// Awaits `future`, unless `token` is cancelled first, in which case `future` is
+/// dropped and `DataFusionError::Cancelled` is returned.
+pub(crate) async fn until_cancelled<T>(
+    future: impl Future<Output = Result<T>>,
+    token: &CancellationToken,
+) -> Result<T> {
+    if token.is_cancelled() {
+        return Err(cancelled_error());
+    }
+    let cancelled = token.cancelled();
+    pin_mut!(future);
+    match future::select(future, cancelled).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err(cancelled_error()),
+    }
+}
+
+/// Collects the batches of the stream returned by `execute`, unless `token` is
+/// cancelled or `timeout` elapses first. In that case the stream is dropped, which
+/// stops the execution of the plan, and `DataFusionError::Cancelled` is returned.
+pub(crate) async fn collect_until_cancelled(
+    execute: impl Future<Output = Result<SendableRecordBatchStream>>,
+    token: &CancellationToken,
+    timeout: Option<Duration>,
+) -> Result<Vec<RecordBatch>> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let check = || -> Result<()> {
+        if token.is_cancelled() {
+            Err(cancelled_error())
+        } else {
+            match (deadline, timeout) {
+                (Some(deadline), Some(timeout)) if Instant::now() >= deadline => {
+                    Err(timeout_error(timeout))
+                }
+                _ => Ok(()),
+            }
+        }
+    };
+
+    let collect = async {
+        check()?;
+        let mut stream = execute.await?;
+        let mut batches = vec![];
+        while let Some(batch) = stream.next().await {
+            batches.push(batch?);
+            // streams that read synchronously never yield to `interrupted` below, so
+            // they are also interrupted between batches
+            check()?;
+        }
+        Ok(batches)
+    };
+
+    let interrupted = async {
+        match timeout {
+            Some(timeout) => {
+                let cancelled = token.cancelled();
+                let elapsed = tokio::time::delay_for(timeout);
+                pin_mut!(elapsed);
+                match future::select(cancelled, elapsed).await {
+                    Either::Left(_) => cancelled_error(),
+                    Either::Right(_) => timeout_error(timeout),
+                }
+            }
+            None => {
+                token.cancelled().await;
+                cancelled_error()
+            }
+        }
+    };
+
+    pin_mut!(collect);
+    pin_mut!(interrupted);
+    match future::select(collect, interrupted).await {
+        Either::Left((result, _)) => result,
+        Either::Right((e, _)) => Err(e),
+    }
+}
+
+fn cancelled_error() -> DataFusionError {
+    DataFusionError::Cancelled("the query was cancelled".to_string())
+}
+
+fn timeout_error(timeout: Duration) -> DataFusionError {
+    DataFusionError::Cancelled(format!("the query timed out after {:?}", timeout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::common::SizedRecordBatchStream;
+    use arrow::datatypes::Schema;
+
+    #[tokio::test]
+    async fn cancel_waiting_query() -> Result<()> {
+        let token = CancellationToken::new();
+        let cancel = {
+            let token = token.clone();
+            async move {
+                tokio::task::yield_now().await;
+                token.cancel();
+            }
+        };
+        // a query whose plan never produces a stream
+        let query = collect_until_cancelled(future::pending(), &token, None);
+
+        let (result, _) = future::join(query, cancel).await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Query cancelled: the query was cancelled"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn timeout() -> Result<()> {
+        let token = CancellationToken::new();
+        let query = collect_until_cancelled(
+            future::pending(),
+            &token,
+            Some(Duration::from_millis(10)),
+        );
+        assert_eq!(
+            query.await.unwrap_err().to_string(),
+            "Query cancelled: the query timed out after 10ms"
+        );
+        assert!(!token.is_cancelled());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn not_cancelled() -> Result<()> {
+        let token = CancellationToken::new();
+        let stream: SendableRecordBatchStream = Box::pin(SizedRecordBatchStream::new(
+            Arc::new(Schema::empty()),
+            vec![],
+        ));
+        let batches =
+            collect_until_cancelled(future::ready(Ok(stream)), &token, None).await?;
+        assert!(batches.is_empty());
+
+        // queries of a cancelled token do not start
+        token.cancel();
+        let result = collect_until_cancelled(future::pending(), &token, None).await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unregister_wakers() -> Result<()> {
+        let token = CancellationToken::new();
+        for _ in 0..10 {
+            let query = until_cancelled(future::pending::<Result<()>>(), &token);
+            pin_mut!(query);
+            let _ = futures::poll!(query.as_mut());
+            let _ = futures::poll!(query.as_mut());
+            assert_eq!(token.inner.wakers.lock().unwrap().len(), 1);
+        }
+        // the wakers of the dropped queries are not kept by the token
+        assert!(token.inner.wakers.lock().unwrap().is_empty());
+        Ok(())
+    }
+}
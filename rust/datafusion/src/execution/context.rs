@@ -19,9 +19,10 @@
 
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::string::String;
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::{StreamExt, TryStreamExt};
 
@@ -30,23 +31,30 @@ use arrow::datatypes::*;
 use arrow::record_batch::RecordBatch;
 
 use crate::datasource::csv::CsvFile;
+use crate::datasource::object_store::{LocalFileSystem, ObjectStore};
 use crate::datasource::parquet::ParquetTable;
 use crate::datasource::TableProvider;
 use crate::error::{DataFusionError, Result};
+use crate::execution::cancellation::{collect_until_cancelled, CancellationToken};
 use crate::execution::dataframe_impl::DataFrameImpl;
+use crate::execution::memory_manager::MemoryPool;
+use crate::execution::prepared_statement::PreparedStatement;
 use crate::logical_plan::{
-    FunctionRegistry, LogicalPlan, LogicalPlanBuilder, TableSource,
+    FunctionRegistry, LogicalPlan, LogicalPlanBuilder, TableSource, ToDFSchema,
 };
+use crate::optimizer::common_subexpr_eliminate::CommonSubexprEliminate;
+use crate::optimizer::constant_folding::ConstantFolding;
 use crate::optimizer::filter_push_down::FilterPushDown;
 use crate::optimizer::optimizer::OptimizerRule;
 use crate::optimizer::projection_push_down::ProjectionPushDown;
-use crate::physical_plan::common;
 use crate::physical_plan::csv::CsvReadOptions;
+use crate::physical_plan::memory::MemoryStream;
 use crate::physical_plan::merge::MergeExec;
 use crate::physical_plan::planner::DefaultPhysicalPlanner;
 use crate::physical_plan::udf::ScalarUDF;
-use crate::physical_plan::ExecutionPlan;
+use crate::physical_plan::udtf::TableUDF;
 use crate::physical_plan::PhysicalPlanner;
+use crate::physical_plan::{ExecutionPlan, SendableRecordBatchStream};
 use crate::sql::{
     parser::{DFParser, FileType},
     planner::{SchemaProvider, SqlToRel},
@@ -111,7 +119,9 @@ impl ExecutionContext {
                 scalar_functions: HashMap::new(),
                 var_provider: HashMap::new(),
                 aggregate_functions: HashMap::new(),
+                table_functions: HashMap::new(),
                 config,
+                memory_pool: Arc::new(MemoryPool::default()),
             },
         };
         ctx
@@ -122,6 +132,12 @@ impl ExecutionContext {
         &self.state.config
     }
 
+    /// The memory pool that the queries of this context reserve memory from, whose
+    /// usage is the memory held by all the queries that are executing
+    pub fn memory_pool(&self) -> &Arc<MemoryPool> {
+        &self.state.memory_pool
+    }
+
     /// Execute a SQL query and produce a Relation (a schema-aware iterator over a series
     /// of RecordBatch instances)
     pub fn sql(&mut self, sql: &str) -> Result<Arc<dyn DataFrame>> {
@@ -139,7 +155,7 @@ impl ExecutionContext {
                         name,
                         location,
                         CsvReadOptions::new()
-                            .schema(&schema)
+                            .schema(&Schema::from(schema.as_ref()))
                             .has_header(*has_header),
                     )?;
                     let plan = LogicalPlanBuilder::empty().build()?;
@@ -160,6 +176,20 @@ impl ExecutionContext {
         }
     }
 
+    /// Prepares a SQL query whose values are given by placeholders, `$1`, `$2`, ... or `?`,
+    /// so that it can be executed many times with different parameters without being
+    /// planned again, and without interpolating the values into the SQL text.
+    pub fn prepare(&self, sql: &str) -> Result<PreparedStatement> {
+        let plan = self.create_logical_plan(sql)?;
+        if let LogicalPlan::CreateExternalTable { .. } = plan {
+            return Err(DataFusionError::NotImplemented(
+                "Only queries can be prepared".to_string(),
+            ));
+        }
+        let plan = self.optimize(&plan)?;
+        PreparedStatement::try_new(self.state.clone(), plan)
+    }
+
     /// Creates a logical plan. This function is intended for internal use and should not be
     /// called directly.
     pub fn create_logical_plan(&self, sql: &str) -> Result<LogicalPlan> {
@@ -199,13 +229,25 @@ impl ExecutionContext {
             .insert(f.name.clone(), Arc::new(f));
     }
 
+    /// Register a table UDF, which can then be called in the FROM clause of SQL
+    /// statements executed against this context
+    pub fn register_udtf(&mut self, f: TableUDF) {
+        self.state
+            .table_functions
+            .insert(f.name.clone(), Arc::new(f));
+    }
+
     /// Creates a DataFrame for reading a CSV data source.
     pub fn read_csv(
         &mut self,
         filename: &str,
         options: CsvReadOptions,
     ) -> Result<Arc<dyn DataFrame>> {
-        let csv = CsvFile::try_new(filename, options)?;
+        let csv = CsvFile::try_new_with_object_store(
+            filename,
+            options,
+            self.state.config.object_store.clone(),
+        )?;
 
         let table_scan = LogicalPlan::CsvScan {
             path: filename.to_string(),
@@ -213,7 +255,7 @@ impl ExecutionContext {
             has_header: options.has_header,
             delimiter: Some(options.delimiter),
             projection: None,
-            projected_schema: csv.schema().clone(),
+            projected_schema: csv.schema().clone().to_dfschema_ref()?,
         };
 
         Ok(Arc::new(DataFrameImpl::new(
@@ -224,13 +266,16 @@ impl ExecutionContext {
 
     /// Creates a DataFrame for reading a Parquet data source.
     pub fn read_parquet(&mut self, filename: &str) -> Result<Arc<dyn DataFrame>> {
-        let parquet = ParquetTable::try_new(filename)?;
+        let parquet = ParquetTable::try_new_with_object_store(
+            filename,
+            self.state.config.object_store.clone(),
+        )?;
 
         let table_scan = LogicalPlan::ParquetScan {
             path: filename.to_string(),
             schema: parquet.schema().clone(),
             projection: None,
-            projected_schema: parquet.schema().clone(),
+            projected_schema: parquet.schema().clone().to_dfschema_ref()?,
         };
 
         Ok(Arc::new(DataFrameImpl::new(
@@ -249,8 +294,9 @@ impl ExecutionContext {
             schema_name: "".to_string(),
             source: TableSource::FromProvider(provider),
             table_schema: schema.clone(),
-            projected_schema: schema,
+            projected_schema: schema.to_dfschema_ref()?,
             projection: None,
+            alias: None,
         };
         Ok(Arc::new(DataFrameImpl::new(
             self.state.clone(),
@@ -266,14 +312,22 @@ impl ExecutionContext {
         filename: &str,
         options: CsvReadOptions,
     ) -> Result<()> {
-        self.register_table(name, Box::new(CsvFile::try_new(filename, options)?));
+        let table = CsvFile::try_new_with_object_store(
+            filename,
+            options,
+            self.state.config.object_store.clone(),
+        )?;
+        self.register_table(name, Box::new(table));
         Ok(())
     }
 
     /// Register a Parquet data source so that it can be referenced from SQL statements
     /// executed against this context.
     pub fn register_parquet(&mut self, name: &str, filename: &str) -> Result<()> {
-        let table = ParquetTable::try_new(&filename)?;
+        let table = ParquetTable::try_new_with_object_store(
+            &filename,
+            self.state.config.object_store.clone(),
+        )?;
         self.register_table(name, Box::new(table));
         Ok(())
     }
@@ -296,17 +350,13 @@ impl ExecutionContext {
     pub fn table(&mut self, table_name: &str) -> Result<Arc<dyn DataFrame>> {
         match self.state.datasources.get(table_name) {
             Some(provider) => {
-                let schema = provider.schema().clone();
-                let table_scan = LogicalPlan::TableScan {
-                    schema_name: "".to_string(),
-                    source: TableSource::FromContext(table_name.to_string()),
-                    table_schema: schema.clone(),
-                    projected_schema: schema,
-                    projection: None,
-                };
+                let schema = provider.schema();
+                let table_scan =
+                    LogicalPlanBuilder::scan("", table_name, schema.as_ref(), None)?
+                        .build()?;
                 Ok(Arc::new(DataFrameImpl::new(
                     self.state.clone(),
-                    &LogicalPlanBuilder::from(&table_scan).build()?,
+                    &table_scan,
                 )))
             }
             _ => Err(DataFusionError::Plan(format!(
@@ -324,41 +374,80 @@ impl ExecutionContext {
     /// Optimize the logical plan by applying optimizer rules
     pub fn optimize(&self, plan: &LogicalPlan) -> Result<LogicalPlan> {
         // Apply standard rewrites and optimizations
-        let mut plan = ProjectionPushDown::new().optimize(&plan)?;
+        let mut plan = ConstantFolding::new().optimize(&plan)?;
+        plan = ProjectionPushDown::new().optimize(&plan)?;
         plan = FilterPushDown::new().optimize(&plan)?;
+        plan = CommonSubexprEliminate::new().optimize(&plan)?;
 
         self.state.config.query_planner.rewrite_logical_plan(plan)
     }
 
-    /// Create a physical plan from a logical plan
+    /// Create a physical plan from a logical plan. The plan reserves memory from a pool
+    /// of its own, limited to `ExecutionConfig::max_memory_per_query`.
     pub fn create_physical_plan(
         &self,
         logical_plan: &LogicalPlan,
     ) -> Result<Arc<dyn ExecutionPlan>> {
+        let mut state = self.state.clone();
+        state.memory_pool = self
+            .state
+            .memory_pool
+            .new_child(self.state.config.max_memory_per_query);
         self.state
             .config
             .query_planner
-            .create_physical_plan(logical_plan, &self.state)
+            .create_physical_plan(logical_plan, &state)
     }
 
-    /// Execute a physical plan and collect the results in memory
+    /// Execute a physical plan and collect the results in memory. The query fails with
+    /// `DataFusionError::Cancelled` if it runs for longer than
+    /// `ExecutionConfig::query_timeout`.
     pub async fn collect(
         &self,
         plan: Arc<dyn ExecutionPlan>,
     ) -> Result<Vec<RecordBatch>> {
-        match plan.output_partitioning().partition_count() {
-            0 => Ok(vec![]),
-            1 => {
-                let it = plan.execute(0).await?;
-                common::collect(it).await
-            }
+        self.collect_with_cancellation(plan, &CancellationToken::new())
+            .await
+    }
+
+    /// Execute a physical plan and collect the results in memory, unless `token` is
+    /// cancelled or the query runs for longer than `ExecutionConfig::query_timeout`
+    /// first, in which case the execution of the plan is stopped and
+    /// `DataFusionError::Cancelled` is returned.
+    pub async fn collect_with_cancellation(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        token: &CancellationToken,
+    ) -> Result<Vec<RecordBatch>> {
+        let plan = match plan.output_partitioning().partition_count() {
+            0 => return Ok(vec![]),
+            1 => plan,
             _ => {
                 // merge into a single partition
-                let plan = MergeExec::new(plan.clone());
+                let plan = Arc::new(MergeExec::new(plan.clone()));
                 // MergeExec must produce a single partition
                 assert_eq!(1, plan.output_partitioning().partition_count());
-                common::collect(plan.execute(0).await?).await
+                plan
             }
+        };
+        collect_until_cancelled(plan.execute(0), token, self.state.config.query_timeout)
+            .await
+    }
+
+    /// Execute a physical plan and return a stream of its results, merged into a single
+    /// partition, without collecting them in memory
+    pub async fn execute_stream(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+    ) -> Result<SendableRecordBatchStream> {
+        match plan.output_partitioning().partition_count() {
+            0 => Ok(Box::pin(MemoryStream::try_new(
+                vec![],
+                plan.schema(),
+                None,
+            )?)),
+            1 => plan.execute(0).await,
+            _ => MergeExec::new(plan.clone()).execute(0).await,
         }
     }
 
@@ -441,6 +530,15 @@ pub struct ExecutionConfig {
     pub concurrency: usize,
     /// Default batch size when reading data sources
     pub batch_size: usize,
+    /// Maximum number of bytes that the operators of a query may hold at once, if any
+    pub max_memory_per_query: Option<usize>,
+    /// Directory that aggregations spill the state of their groups to when the memory
+    /// of a query is exhausted, if any. Without one, such queries fail.
+    pub spill_dir: Option<PathBuf>,
+    /// Maximum duration of the queries collected by the context, if any
+    pub query_timeout: Option<Duration>,
+    /// The store that the files of CSV and Parquet data sources are read from
+    pub object_store: Arc<dyn ObjectStore>,
     /// Responsible for planning `LogicalPlan`s, and `ExecutionPlan`
     query_planner: Arc<dyn QueryPlanner + Send + Sync>,
 }
@@ -451,6 +549,10 @@ impl ExecutionConfig {
         Self {
             concurrency: num_cpus::get(),
             batch_size: 4096,
+            max_memory_per_query: None,
+            spill_dir: None,
+            query_timeout: None,
+            object_store: Arc::new(LocalFileSystem),
             query_planner: Arc::new(DefaultQueryPlanner {}),
         }
     }
@@ -471,6 +573,34 @@ impl ExecutionConfig {
         self
     }
 
+    /// Customize the maximum number of bytes that the operators of a query may hold at
+    /// once. Queries that need more fail with `DataFusionError::ResourcesExhausted`.
+    pub fn with_max_memory_per_query(mut self, n: usize) -> Self {
+        self.max_memory_per_query = Some(n);
+        self
+    }
+
+    /// Spill the state of the groups of aggregations to temporary files in `dir` when
+    /// they exceed the memory of a query, rather than failing
+    pub fn with_spill_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.spill_dir = Some(dir.into());
+        self
+    }
+
+    /// Customize the maximum duration of the queries collected by the context, after
+    /// which they are stopped and fail with `DataFusionError::Cancelled`
+    pub fn with_query_timeout(mut self, timeout: Duration) -> Self {
+        self.query_timeout = Some(timeout);
+        self
+    }
+
+    /// Read the files of CSV and Parquet data sources from `object_store` rather than
+    /// from the local filesystem
+    pub fn with_object_store(mut self, object_store: Arc<dyn ObjectStore>) -> Self {
+        self.object_store = object_store;
+        self
+    }
+
     /// Replace the default query planner
     pub fn with_query_planner(
         mut self,
@@ -492,8 +622,13 @@ pub struct ExecutionContextState {
     pub var_provider: HashMap<VarType, Arc<dyn VarProvider + Send + Sync>>,
     /// Aggregate functions registered in the context
     pub aggregate_functions: HashMap<String, Arc<AggregateUDF>>,
+    /// Table functions registered in the context
+    pub table_functions: HashMap<String, Arc<TableUDF>>,
     /// Context configuration
     pub config: ExecutionConfig,
+    /// The pool that operators reserve memory from. Each query is planned with a
+    /// child of the pool of the context, so that its limit applies to the query.
+    pub memory_pool: Arc<MemoryPool>,
 }
 
 impl SchemaProvider for ExecutionContextState {
@@ -512,6 +647,10 @@ impl SchemaProvider for ExecutionContextState {
             .get(name)
             .and_then(|func| Some(func.clone()))
     }
+
+    fn get_table_function_meta(&self, name: &str) -> Option<Arc<TableUDF>> {
+        self.table_functions.get(name).cloned()
+    }
 }
 
 impl FunctionRegistry for ExecutionContextState {
@@ -541,21 +680,41 @@ impl FunctionRegistry for ExecutionContextState {
             Ok(result.unwrap())
         }
     }
+
+    fn udtf(&self, name: &str) -> Result<&TableUDF> {
+        self.table_functions
+            .get(name)
+            .map(|func| func.as_ref())
+            .ok_or_else(|| {
+                DataFusionError::Plan(format!(
+                    "There is no UDTF named \"{}\" in the registry",
+                    name
+                ))
+            })
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use crate::logical_plan::{col, create_udf, sum};
-    use crate::physical_plan::functions::ScalarFunctionImplementation;
+    use crate::datasource::object_store::InMemoryObjectStore;
+    use crate::logical_plan::{col, create_udf, create_udtf, sum};
+    use crate::physical_plan::expressions::{col as physical_col, PhysicalSortExpr};
+    use crate::physical_plan::functions::{make_scalar_function, Volatility};
+    use crate::physical_plan::sort::SortOptions;
+    use crate::physical_plan::sort_preserving_merge::SortPreservingMergeExec;
+    use crate::physical_plan::udtf::TableFunctionImplementation;
+    use crate::scalar::ScalarValue;
     use crate::test;
     use crate::variable::VarType;
     use crate::{
         datasource::MemTable, logical_plan::create_udaf,
         physical_plan::expressions::AvgAccumulator,
     };
-    use arrow::array::{ArrayRef, Float64Array, Int32Array, StringArray};
+    use arrow::array::{
+        Array, ArrayRef, Float64Array, Int32Array, StringArray, TimestampNanosecondArray,
+    };
     use arrow::compute::add;
     use std::fs::File;
     use std::thread::{self, JoinHandle};
@@ -727,7 +886,7 @@ mod tests {
             )?]],
             schema: schema.clone(),
             projection: None,
-            projected_schema: schema.clone(),
+            projected_schema: schema.clone().to_dfschema_ref()?,
         })
         .project(vec![col("b")])?
         .build()?;
@@ -785,6 +944,182 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn cancel_query() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+        let df = ctx.sql("SELECT c1, c2 FROM test ORDER BY c1")?;
+
+        let token = CancellationToken::new();
+        assert_eq!(
+            40,
+            df.collect_with_cancellation(&token).await?[0].num_rows()
+        );
+
+        token.cancel();
+        match df.collect_with_cancellation(&token).await {
+            Err(DataFusionError::Cancelled(_)) => Ok(()),
+            Err(e) => panic!("Unexpected error: {}", e),
+            Ok(_) => panic!("Expected the query to be cancelled"),
+        }
+    }
+
+    #[tokio::test]
+    async fn query_timeout() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let schema = populate_csv_partitions(&tmp_dir, 4, ".csv")?;
+        let mut ctx = ExecutionContext::with_config(
+            ExecutionConfig::new().with_query_timeout(Duration::from_nanos(1)),
+        );
+        ctx.register_csv(
+            "test",
+            tmp_dir.path().to_str().unwrap(),
+            CsvReadOptions::new().schema(&schema),
+        )?;
+
+        match collect(&mut ctx, "SELECT c1, c2 FROM test").await {
+            Err(e @ DataFusionError::Cancelled(_)) => assert_eq!(
+                e.to_string(),
+                "Query cancelled: the query timed out after 1ns"
+            ),
+            Err(e) => panic!("Unexpected error: {}", e),
+            Ok(_) => panic!("Expected the query to time out"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn memory_pool_usage() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+        collect(&mut ctx, "SELECT c1, c2 FROM test ORDER BY c1").await?;
+
+        // the memory is released once the query completes
+        assert_eq!(0, ctx.memory_pool().reserved());
+        assert!(ctx.memory_pool().peak_reserved() > 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn max_memory_per_query() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let schema = populate_csv_partitions(&tmp_dir, 4, ".csv")?;
+        let mut ctx = ExecutionContext::with_config(
+            ExecutionConfig::new().with_max_memory_per_query(64),
+        );
+        ctx.register_csv(
+            "test",
+            tmp_dir.path().to_str().unwrap(),
+            CsvReadOptions::new().schema(&schema),
+        )?;
+
+        for sql in &[
+            "SELECT c1, c2 FROM test ORDER BY c1",
+            "SELECT c1, COUNT(DISTINCT c2) FROM test GROUP BY c1",
+            "SELECT COUNT(DISTINCT c2) FROM test",
+        ] {
+            match collect(&mut ctx, sql).await {
+                Err(DataFusionError::ResourcesExhausted(_)) => {}
+                Err(e) => panic!("Unexpected error for {}: {}", sql, e),
+                Ok(_) => panic!("Expected {} to exceed the memory limit", sql),
+            }
+        }
+        assert_eq!(0, ctx.memory_pool().reserved());
+
+        // queries that do not buffer their input are not limited
+        collect(&mut ctx, "SELECT c1, c2 FROM test WHERE c2 > 3").await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_from_object_store() -> Result<()> {
+        let store = Arc::new(InMemoryObjectStore::new());
+        store.put("data/part-0.csv", b"c1,c2\n1,10\n2,20\n".to_vec());
+        store.put("data/part-1.csv", b"c1,c2\n1,30\n".to_vec());
+        store.put("data/_SUCCESS", vec![]);
+        let mut ctx = ExecutionContext::with_config(
+            ExecutionConfig::new().with_object_store(store),
+        );
+        ctx.register_csv("test", "data/", CsvReadOptions::new())?;
+
+        let results =
+            collect(&mut ctx, "SELECT c1, SUM(c2) FROM test GROUP BY c1").await?;
+        assert_eq!(results.len(), 1);
+
+        let expected: Vec<&str> = vec!["1,40", "2,20"];
+        let mut rows = test::format_batch(&results[0]);
+        rows.sort();
+        assert_eq!(rows, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join() -> Result<()> {
+        let store = Arc::new(InMemoryObjectStore::new());
+        store.put("customers.csv", b"id,name\n2,bob\n1,alice\n".to_vec());
+        store.put(
+            "orders.csv",
+            b"customer_id,amount\n3,30\n1,10\n1,20\n".to_vec(),
+        );
+        store.put("refunds.csv", b"id,reason\n1.0,damaged\n".to_vec());
+        let mut ctx = ExecutionContext::with_config(
+            ExecutionConfig::new().with_object_store(store),
+        );
+        ctx.register_csv("customers", "customers.csv", CsvReadOptions::new())?;
+        ctx.register_csv("orders", "orders.csv", CsvReadOptions::new())?;
+
+        let results = collect(
+            &mut ctx,
+            "SELECT name, amount FROM customers JOIN orders ON id = customer_id",
+        )
+        .await?;
+        let mut rows = results
+            .iter()
+            .flat_map(test::format_batch)
+            .collect::<Vec<_>>();
+        rows.sort();
+        assert_eq!(rows, vec!["alice,10", "alice,20"]);
+
+        // the customers without orders have a null amount
+        let results = collect(
+            &mut ctx,
+            "SELECT name, COUNT(amount) FROM customers \
+             LEFT JOIN orders ON customers.id = orders.customer_id GROUP BY name",
+        )
+        .await?;
+        let mut rows = results
+            .iter()
+            .flat_map(test::format_batch)
+            .collect::<Vec<_>>();
+        rows.sort();
+        assert_eq!(rows, vec!["alice,2", "bob,0"]);
+
+        // keys with the same name are only merged by USING, and keys of different
+        // types are coerced to a common type
+        ctx.register_csv("refunds", "refunds.csv", CsvReadOptions::new())?;
+        for (sql, expected) in &[
+            (
+                "SELECT name, COUNT(refunds.id) FROM customers \
+                 LEFT JOIN refunds ON customers.id = refunds.id GROUP BY name",
+                vec!["alice,1", "bob,0"],
+            ),
+            (
+                "SELECT name, COUNT(id) FROM customers \
+                 LEFT JOIN refunds USING (id) GROUP BY name",
+                vec!["alice,1", "bob,1"],
+            ),
+        ] {
+            let results = collect(&mut ctx, sql).await?;
+            let mut rows = results
+                .iter()
+                .flat_map(test::format_batch)
+                .collect::<Vec<_>>();
+            rows.sort();
+            assert_eq!(&rows, expected, "{}", sql);
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn aggregate() -> Result<()> {
         let results = execute("SELECT SUM(c1), SUM(c2) FROM test", 4).await?;
@@ -870,6 +1205,133 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn aggregate_common_subexpressions() -> Result<()> {
+        let results = execute(
+            "SELECT c1, SUM(c2 + c1), MAX(c2 + c1) FROM test WHERE c2 + c1 > 3 GROUP BY c1",
+            4,
+        )
+        .await?;
+        assert_eq!(results.len(), 1);
+
+        let batch = &results[0];
+
+        assert_eq!(
+            field_names(batch),
+            vec!["c1", "SUM(c2 Plus c1)", "MAX(c2 Plus c1)"]
+        );
+
+        let expected: Vec<&str> = vec!["0,49,10", "1,60,11", "2,72,12", "3,85,13"];
+        let mut rows = test::format_batch(&batch);
+        rows.sort();
+        assert_eq!(rows, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn prepared_statement() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let ctx = create_ctx(&tmp_dir, 4)?;
+
+        let statement =
+            ctx.prepare("SELECT c1, SUM(c2 * $2) FROM test WHERE c2 > $1 GROUP BY c1")?;
+        assert_eq!(
+            statement.parameter_types(),
+            &[Some(DataType::UInt64), Some(DataType::UInt64)]
+        );
+
+        let cases = vec![
+            (5, 2, vec!["0,80", "1,80", "2,80", "3,80"]),
+            (8, 1, vec!["0,19", "1,19", "2,19", "3,19"]),
+        ];
+        for (min, factor, expected) in cases {
+            let params = vec![
+                ScalarValue::Int64(Some(min)),
+                ScalarValue::Int64(Some(factor)),
+            ];
+            let results = statement.execute(params).await?;
+            assert_eq!(results.len(), 1);
+
+            let batch = &results[0];
+            assert_eq!(field_names(batch), vec!["c1", "SUM(c2 Multiply $2)"]);
+
+            let mut rows = test::format_batch(&batch);
+            rows.sort();
+            assert_eq!(rows, expected);
+        }
+
+        let err = statement
+            .bind(vec![ScalarValue::Int64(Some(5))])
+            .expect_err("binding should have failed");
+        assert_eq!(
+            "Plan(\"Expected 2 parameters, but 1 were given\")",
+            format!("{:?}", err)
+        );
+
+        let params = vec![
+            ScalarValue::Utf8(Some("five".to_string())),
+            ScalarValue::Int64(Some(1)),
+        ];
+        let err = statement
+            .bind(params)
+            .expect_err("binding should have failed");
+        assert_eq!(
+            "Plan(\"Parameter $1 expects a value of type UInt64, but Utf8(\\\"five\\\") was given\")",
+            format!("{:?}", err)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn prepared_statement_with_now() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let ctx = create_ctx(&tmp_dir, 1)?;
+
+        // the current time is taken when the statement is executed, not prepared
+        let statement = ctx.prepare("SELECT now() FROM test LIMIT 1")?;
+        let mut times = vec![];
+        for _ in 0..2 {
+            thread::sleep(std::time::Duration::from_millis(2));
+            let results = statement.execute(vec![]).await?;
+            let now = results[0]
+                .column(0)
+                .as_any()
+                .downcast_ref::<TimestampNanosecondArray>()
+                .expect("expected a timestamp array")
+                .value(0);
+            times.push(now);
+        }
+        assert!(times[0] < times[1]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unbound_placeholder() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+
+        let err = ctx
+            .sql("SELECT c1 FROM test WHERE c2 > ?")?
+            .collect()
+            .await
+            .expect_err("query should have failed");
+        assert!(format!("{:?}", err).contains(
+            "Placeholder $1 must be bound to a value before the query is executed"
+        ));
+
+        let err = ctx
+            .prepare("CREATE EXTERNAL TABLE t STORED AS PARQUET LOCATION 'foo.parquet'")
+            .err()
+            .expect("only queries can be prepared");
+        assert_eq!(
+            "NotImplemented(\"Only queries can be prepared\")",
+            format!("{:?}", err)
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn aggregate_grouped_avg() -> Result<()> {
         let results = execute("SELECT c1, AVG(c2) FROM test GROUP BY c1", 4).await?;
@@ -1240,6 +1702,98 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn table_functions() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let cases = vec![
+            (
+                "SELECT * FROM generate_series(1, 10, 3)",
+                vec!["1", "4", "7", "10"],
+            ),
+            ("SELECT value FROM range(3)", vec!["0", "1", "2"]),
+            (
+                "SELECT r.value * 2 FROM range(5, -5, -4) AS r WHERE r.value <> 1",
+                vec!["10", "-6"],
+            ),
+        ];
+        for (sql, expected) in cases {
+            let results = collect(&mut ctx, sql).await?;
+            let rows = results
+                .iter()
+                .flat_map(test::format_batch)
+                .collect::<Vec<_>>();
+            assert_eq!(rows, expected, "{}", sql);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn table_function_of_timestamps() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let sql = "SELECT * FROM generate_series(\
+                   CAST('2021-01-01T00:00:00Z' AS TIMESTAMP), \
+                   CAST('2021-01-01T02:30:00Z' AS TIMESTAMP), \
+                   INTERVAL '1 hour')";
+        let results = collect(&mut ctx, sql).await?;
+        let mut values = vec![];
+        for batch in &results {
+            let array = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<TimestampNanosecondArray>()
+                .expect("expected a timestamp array");
+            values.extend((0..array.len()).map(|i| array.value(i)));
+        }
+        let hour = 3_600_000_000_000;
+        let start = 1_609_459_200_000_000_000;
+        assert_eq!(values, vec![start, start + hour, start + 2 * hour]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn table_udf() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+
+        // repeats a string a number of times
+        let repeat: TableFunctionImplementation = Arc::new(|args: &[ScalarValue]| {
+            let (value, count) = match args {
+                [ScalarValue::Utf8(Some(value)), ScalarValue::Int64(Some(count))] => {
+                    (value, *count as usize)
+                }
+                _ => {
+                    return Err(DataFusionError::Plan(
+                        "repeat expects a string and a count".to_string(),
+                    ))
+                }
+            };
+            let schema = Arc::new(Schema::new(vec![Field::new(
+                "value",
+                DataType::Utf8,
+                false,
+            )]));
+            let array = StringArray::from(vec![value.as_str(); count]);
+            let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)])?;
+            Ok(Arc::new(MemTable::new(schema, vec![vec![batch]])?))
+        });
+        ctx.register_udtf(create_udtf("repeat", repeat));
+
+        let results = collect(&mut ctx, "SELECT value FROM repeat('a', 3)").await?;
+        let rows = results
+            .iter()
+            .flat_map(test::format_batch)
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec!["a", "a", "a"]);
+
+        let err = collect(&mut ctx, "SELECT value FROM repeat(3)")
+            .await
+            .expect_err("query should have failed");
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: repeat expects a string and a count"
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn scalar_udf() -> Result<()> {
         let schema = Schema::new(vec![
@@ -1260,7 +1814,7 @@ mod tests {
         let provider = MemTable::new(Arc::new(schema), vec![vec![batch]])?;
         ctx.register_table("t", Box::new(provider));
 
-        let myfunc: ScalarFunctionImplementation = Arc::new(|args: &[ArrayRef]| {
+        let myfunc = make_scalar_function(|args: &[ArrayRef]| {
             let l = &args[0]
                 .as_any()
                 .downcast_ref::<Int32Array>()
@@ -1276,6 +1830,7 @@ mod tests {
             "my_add",
             vec![DataType::Int32, DataType::Int32],
             Arc::new(DataType::Int32),
+            Volatility::Immutable,
             myfunc,
         ));
 
@@ -1367,6 +1922,47 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn sorted_table() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let batch = |a: Vec<i32>, b: Vec<i32>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int32Array::from(a)), Arc::new(Int32Array::from(b))],
+            )
+        };
+        let partitions = vec![
+            vec![batch(vec![1, 3], vec![1, 1])?, batch(vec![5], vec![0])?],
+            vec![batch(vec![2, 4], vec![1, 1])?],
+        ];
+
+        let mut ctx = ExecutionContext::new();
+        let provider = MemTable::new(schema.clone(), partitions)?.with_sort_order(vec![
+            PhysicalSortExpr {
+                expr: physical_col("a"),
+                options: SortOptions::default(),
+            },
+        ]);
+        ctx.register_table("t", Box::new(provider));
+
+        // the partitions are merged rather than sorted again
+        let plan =
+            ctx.create_logical_plan("SELECT a, b FROM t WHERE b > 0 ORDER BY a")?;
+        let plan = ctx.create_physical_plan(&ctx.optimize(&plan)?)?;
+        assert!(plan.as_any().is::<SortPreservingMergeExec>());
+
+        let results = ctx.collect(plan).await?;
+        let rows = results
+            .iter()
+            .flat_map(test::format_batch)
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec!["1,1", "2,1", "3,1", "4,1"]);
+        Ok(())
+    }
+
     /// tests the creation, registration and usage of a UDAF
     #[tokio::test]
     async fn simple_udaf() -> Result<()> {
@@ -19,12 +19,22 @@
 
 use std::sync::Arc;
 
+use crate::arrow::array::{ArrayRef, StringArray};
+use crate::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use crate::arrow::record_batch::RecordBatch;
+use crate::arrow::util::pretty;
 use crate::dataframe::*;
-use crate::error::Result;
+use crate::datasource::MemTable;
+use crate::error::{DataFusionError, Result};
+use crate::execution::cancellation::CancellationToken;
 use crate::execution::context::{ExecutionContext, ExecutionContextState};
-use crate::logical_plan::{col, Expr, FunctionRegistry, LogicalPlan, LogicalPlanBuilder};
-use arrow::datatypes::Schema;
+use crate::logical_plan::{
+    avg, col, count, lit, max, min, sum, Column, DFSchema, Expr, FunctionRegistry,
+    JoinConstraint, JoinType, LogicalPlan, LogicalPlanBuilder, SampleMethod,
+};
+use crate::physical_plan::aggregates::{self, AggregateFunction};
+use crate::physical_plan::SendableRecordBatchStream;
+use crate::scalar::ScalarValue;
 
 use async_trait::async_trait;
 
@@ -42,8 +52,72 @@ impl DataFrameImpl {
             plan: plan.clone(),
         }
     }
+
+    /// Create a DataFrame over `batches`, which are held in memory
+    fn from_batches(
+        &self,
+        schema: SchemaRef,
+        batches: Vec<RecordBatch>,
+    ) -> Result<Arc<dyn DataFrame>> {
+        let table = MemTable::new(schema, vec![batches])?;
+        let mut ctx = ExecutionContext::from(self.ctx_state.clone());
+        ctx.read_table(Arc::new(table))
+    }
+
+    /// The columns of this DataFrame, as expressions
+    fn columns(&self) -> Vec<(&str, Expr)> {
+        self.plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| (f.name().as_str(), Expr::Column(f.qualified_column())))
+            .collect()
+    }
+
+    /// Compute the aggregates `aggr_expr` over all the rows, in a single row
+    async fn aggregate_row(&self, aggr_expr: Vec<Expr>) -> Result<RecordBatch> {
+        let plan = LogicalPlanBuilder::from(&self.plan)
+            .aggregate(vec![], aggr_expr)?
+            .build()?;
+        let batches = DataFrameImpl::new(self.ctx_state.clone(), &plan)
+            .collect()
+            .await?;
+        batches
+            .into_iter()
+            .find(|batch| batch.num_rows() == 1)
+            .ok_or_else(|| {
+                DataFusionError::Internal(
+                    "The aggregate of describe did not produce a row".to_string(),
+                )
+            })
+    }
+}
+
+/// The value of the aggregate at index `i` of the single row `batch`, or None if there
+/// is no such aggregate or its value is null
+fn aggregate_value(batch: &RecordBatch, i: Option<usize>) -> Result<Option<ScalarValue>> {
+    match i {
+        Some(i) => {
+            let value = ScalarValue::try_from_array(batch.column(i), 0)?;
+            Ok(if value.is_null() { None } else { Some(value) })
+        }
+        None => Ok(None),
+    }
 }
 
+/// The aggregates that `DataFrame::describe` computes for a column, as indices of the
+/// aggregate expressions. The statistics that do not apply to the type of the column
+/// have no aggregate.
+struct ColumnStatistics {
+    count: usize,
+    mean: Option<usize>,
+    min: Option<usize>,
+    max: Option<usize>,
+}
+
+/// The names of the statistics of `DataFrame::describe`, in the order of its rows
+const STATISTICS: &[&str] = &["count", "mean", "std", "min", "max"];
+
 #[async_trait]
 impl DataFrame for DataFrameImpl {
     /// Apply a projection based on a list of column names
@@ -54,9 +128,8 @@ impl DataFrame for DataFrameImpl {
                 self.plan
                     .schema()
                     // take the index to ensure that the column exists in the schema
-                    .index_of(name.to_owned())
-                    .and_then(|_| Ok(col(name)))
-                    .map_err(|e| e.into())
+                    .index_of(name)
+                    .map(|_| col(name))
             })
             .collect::<Result<Vec<_>>>()?;
         self.select(exprs)
@@ -96,12 +169,271 @@ impl DataFrame for DataFrameImpl {
         Ok(Arc::new(DataFrameImpl::new(self.ctx_state.clone(), &plan)))
     }
 
+    /// Keep a random sample of the rows
+    fn sample(&self, fraction: f64, seed: u64) -> Result<Arc<dyn DataFrame>> {
+        let plan = LogicalPlanBuilder::from(&self.plan)
+            .sample(SampleMethod::Bernoulli, fraction, seed)?
+            .build()?;
+        Ok(Arc::new(DataFrameImpl::new(self.ctx_state.clone(), &plan)))
+    }
+
     /// Sort by specified sorting expressions
     fn sort(&self, expr: Vec<Expr>) -> Result<Arc<dyn DataFrame>> {
         let plan = LogicalPlanBuilder::from(&self.plan).sort(expr)?.build()?;
         Ok(Arc::new(DataFrameImpl::new(self.ctx_state.clone(), &plan)))
     }
 
+    /// Join with another DataFrame on pairs of columns
+    fn join(
+        &self,
+        right: Arc<dyn DataFrame>,
+        join_type: JoinType,
+        left_cols: &[&str],
+        right_cols: &[&str],
+    ) -> Result<Arc<dyn DataFrame>> {
+        let columns = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| Column::from_name(name))
+                .collect::<Vec<_>>()
+        };
+        // the keys are merged, as with `JOIN ... USING`, when they have the same names
+        let join_constraint = if left_cols == right_cols {
+            JoinConstraint::Using
+        } else {
+            JoinConstraint::On
+        };
+        let plan = LogicalPlanBuilder::from(&self.plan)
+            .join_detailed(
+                &right.to_logical_plan(),
+                join_type,
+                columns(left_cols),
+                columns(right_cols),
+                join_constraint,
+            )?
+            .build()?;
+        Ok(Arc::new(DataFrameImpl::new(self.ctx_state.clone(), &plan)))
+    }
+
+    /// Produce a row for each element of the lists of a column
+    fn unnest(&self, column: &str) -> Result<Arc<dyn DataFrame>> {
+        let plan = LogicalPlanBuilder::from(&self.plan)
+            .unnest(Column::from_name(column))?
+            .build()?;
+        Ok(Arc::new(DataFrameImpl::new(self.ctx_state.clone(), &plan)))
+    }
+
+    /// Remove duplicate rows by grouping by all the columns
+    fn distinct(&self) -> Result<Arc<dyn DataFrame>> {
+        let group_expr = self.columns().into_iter().map(|(_, c)| c).collect();
+        self.aggregate(group_expr, vec![])
+    }
+
+    /// Replace or append the column `name`
+    fn with_column(&self, name: &str, expr: Expr) -> Result<Arc<dyn DataFrame>> {
+        let mut replaced = false;
+        let mut exprs = self
+            .columns()
+            .into_iter()
+            .map(|(column_name, column)| {
+                if column_name == name {
+                    replaced = true;
+                    expr.alias(name)
+                } else {
+                    column
+                }
+            })
+            .collect::<Vec<_>>();
+        if !replaced {
+            exprs.push(expr.alias(name));
+        }
+        self.select(exprs)
+    }
+
+    /// Project all the columns but the given ones
+    fn drop_columns(&self, columns: &[&str]) -> Result<Arc<dyn DataFrame>> {
+        for name in columns {
+            // take the index to ensure that the column exists in the schema
+            self.plan.schema().index_of(name)?;
+        }
+        let exprs = self
+            .columns()
+            .into_iter()
+            .filter(|(name, _)| !columns.contains(name))
+            .map(|(_, column)| column)
+            .collect();
+        self.select(exprs)
+    }
+
+    /// Project all the columns, aliasing `old_name` to `new_name`
+    fn with_column_renamed(
+        &self,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<Arc<dyn DataFrame>> {
+        self.plan.schema().index_of(old_name)?;
+        let exprs = self
+            .columns()
+            .into_iter()
+            .map(|(name, column)| {
+                if name == old_name {
+                    column.alias(new_name)
+                } else {
+                    column
+                }
+            })
+            .collect();
+        self.select(exprs)
+    }
+
+    /// Compute the count, mean, min and max of all the columns with a single aggregate,
+    /// then their standard deviations with a second one over the deviations from the means
+    async fn describe(&self) -> Result<Arc<dyn DataFrame>> {
+        let supports = |fun: &AggregateFunction, data_type: &DataType| {
+            aggregates::return_type(fun, &vec![data_type.clone()]).is_ok()
+        };
+        let fields = self.plan.schema().fields();
+        let mut aggr_expr = vec![];
+        let mut statistics = vec![];
+        for (field, (_, column)) in fields.iter().zip(self.columns()) {
+            let mut push = |expr: Expr| {
+                let name = format!("describe_{}", aggr_expr.len());
+                aggr_expr.push(expr.alias(&name));
+                aggr_expr.len() - 1
+            };
+            let data_type = field.data_type();
+            let numeric = supports(&AggregateFunction::Avg, data_type);
+            let ordered = supports(&AggregateFunction::Min, data_type);
+            statistics.push(ColumnStatistics {
+                count: push(count(column.clone())),
+                mean: if numeric {
+                    Some(push(avg(column.clone())))
+                } else {
+                    None
+                },
+                min: if ordered {
+                    Some(push(min(column.clone())))
+                } else {
+                    None
+                },
+                max: if ordered {
+                    Some(push(max(column)))
+                } else {
+                    None
+                },
+            });
+        }
+
+        let batch = self.aggregate_row(aggr_expr).await?;
+        let mut counts = vec![];
+        let mut means = vec![];
+        for statistics in &statistics {
+            counts.push(match aggregate_value(&batch, Some(statistics.count))? {
+                Some(ScalarValue::UInt64(Some(count))) => count,
+                other => {
+                    return Err(DataFusionError::Internal(format!(
+                        "Unexpected count of describe {:?}",
+                        other
+                    )))
+                }
+            });
+            means.push(match aggregate_value(&batch, statistics.mean)? {
+                Some(ScalarValue::Float64(Some(mean))) => Some(mean),
+                _ => None,
+            });
+        }
+
+        // The sums of the squared deviations from the means, as subtracting the squared
+        // mean from the mean of the squares loses the precision of values that are
+        // large compared to their spread
+        let mut deviation_expr = vec![];
+        let mut deviations = vec![];
+        for ((_, column), (count, mean)) in
+            self.columns().into_iter().zip(counts.iter().zip(&means))
+        {
+            deviations.push(match mean {
+                Some(mean) if *count > 1 => {
+                    let deviation = Expr::Cast {
+                        expr: Box::new(column),
+                        data_type: DataType::Float64,
+                    } - lit(*mean);
+                    let name = format!("describe_{}", deviation_expr.len());
+                    deviation_expr.push(sum(deviation.clone() * deviation).alias(&name));
+                    Some(deviation_expr.len() - 1)
+                }
+                _ => None,
+            });
+        }
+        let deviation_batch = if deviation_expr.is_empty() {
+            None
+        } else {
+            Some(self.aggregate_row(deviation_expr).await?)
+        };
+
+        let mut schema_fields = vec![Field::new("summary", DataType::Utf8, false)];
+        let mut columns: Vec<ArrayRef> =
+            vec![Arc::new(StringArray::from(STATISTICS.to_vec()))];
+        for (i, (field, statistics)) in fields.iter().zip(statistics).enumerate() {
+            let (count, mean) = (counts[i], means[i]);
+            // the sample standard deviation
+            let std = match &deviation_batch {
+                Some(deviation_batch) => {
+                    match aggregate_value(deviation_batch, deviations[i])? {
+                        Some(ScalarValue::Float64(Some(squared_deviations))) => {
+                            Some((squared_deviations / (count as f64 - 1.0)).sqrt())
+                        }
+                        _ => None,
+                    }
+                }
+                None => None,
+            };
+            let values = vec![
+                Some(count.to_string()),
+                mean.map(|mean| mean.to_string()),
+                std.map(|std| std.to_string()),
+                aggregate_value(&batch, statistics.min)?.map(|min| min.to_string()),
+                aggregate_value(&batch, statistics.max)?.map(|max| max.to_string()),
+            ];
+            schema_fields.push(Field::new(field.name(), DataType::Utf8, true));
+            columns.push(Arc::new(StringArray::from(
+                values.iter().map(|v| v.as_deref()).collect::<Vec<_>>(),
+            )));
+        }
+
+        let schema = Arc::new(Schema::new(schema_fields));
+        let batch = RecordBatch::try_new(schema.clone(), columns)?;
+        self.from_batches(schema, vec![batch])
+    }
+
+    async fn show(&self) -> Result<()> {
+        let results = self.collect().await?;
+        Ok(pretty::print_batches(&results)?)
+    }
+
+    async fn show_limit(&self, n: usize) -> Result<()> {
+        let plan = LogicalPlanBuilder::from(&self.plan).limit(n)?.build()?;
+        DataFrameImpl::new(self.ctx_state.clone(), &plan)
+            .show()
+            .await
+    }
+
+    /// Collect the results into a `MemTable`
+    async fn cache(&self) -> Result<Arc<dyn DataFrame>> {
+        let ctx = ExecutionContext::from(self.ctx_state.clone());
+        let plan = ctx.optimize(&self.plan)?;
+        let plan = ctx.create_physical_plan(&plan)?;
+        let schema = plan.schema();
+        let batches = ctx.collect(plan).await?;
+        self.from_batches(schema, batches)
+    }
+
+    async fn execute_stream(&self) -> Result<SendableRecordBatchStream> {
+        let ctx = ExecutionContext::from(self.ctx_state.clone());
+        let plan = ctx.optimize(&self.plan)?;
+        let plan = ctx.create_physical_plan(&plan)?;
+        ctx.execute_stream(plan).await
+    }
+
     /// Convert to logical plan
     fn to_logical_plan(&self) -> LogicalPlan {
         self.plan.clone()
@@ -110,14 +442,22 @@ impl DataFrame for DataFrameImpl {
     // Convert the logical plan represented by this DataFrame into a physical plan and
     // execute it
     async fn collect(&self) -> Result<Vec<RecordBatch>> {
+        self.collect_with_cancellation(&CancellationToken::new())
+            .await
+    }
+
+    async fn collect_with_cancellation(
+        &self,
+        token: &CancellationToken,
+    ) -> Result<Vec<RecordBatch>> {
         let ctx = ExecutionContext::from(self.ctx_state.clone());
         let plan = ctx.optimize(&self.plan)?;
         let plan = ctx.create_physical_plan(&plan)?;
-        Ok(ctx.collect(plan).await?)
+        Ok(ctx.collect_with_cancellation(plan, token).await?)
     }
 
     /// Returns the schema from the logical plan
-    fn schema(&self) -> &Schema {
+    fn schema(&self) -> &DFSchema {
         self.plan.schema()
     }
 
@@ -139,8 +479,10 @@ mod tests {
     use crate::datasource::csv::CsvReadOptions;
     use crate::execution::context::ExecutionContext;
     use crate::logical_plan::*;
-    use crate::{physical_plan::functions::ScalarFunctionImplementation, test};
-    use arrow::{array::ArrayRef, datatypes::DataType};
+    use crate::physical_plan::functions::{make_scalar_function, Volatility};
+    use crate::test;
+    use arrow::array::{Array, Int32Array, Int64Array};
+    use futures::TryStreamExt;
 
     #[test]
     fn select_columns() -> Result<()> {
@@ -220,6 +562,60 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn sample() -> Result<()> {
+        // build query using Table API
+        let t = test_table()?;
+        let plan = t
+            .sample(0.25, 7)?
+            .select_columns(vec!["c1"])?
+            .to_logical_plan();
+
+        // build query using SQL
+        let sql_plan = create_plan(
+            "SELECT c1 FROM aggregate_test_100 TABLESAMPLE BERNOULLI (25) REPEATABLE (7)",
+        )?;
+
+        // the two plans should be identical
+        assert_same_plan(&plan, &sql_plan);
+
+        let err = match t.sample(2.0, 7) {
+            Ok(_) => panic!("expected an error"),
+            Err(e) => e,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: The fraction of a sample must be between 0 and 1, but is 2"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn join() -> Result<()> {
+        let left = test_table()?.select_columns(vec!["c1", "c2"])?;
+        let right = test_table()?.select_columns(vec!["c1", "c3"])?;
+        let df = left.join(right, JoinType::Left, &["c1"], &["c1"])?;
+
+        // the right join column is merged into the left one
+        let fields = df
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| (f.name().as_str(), f.is_nullable()))
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec![("c1", false), ("c2", false), ("c3", true)]);
+
+        let plan = format!("{:?}", df.to_logical_plan());
+        assert!(
+            plan.starts_with(
+                "Join: type=Left, using=[aggregate_test_100.c1 = aggregate_test_100.c1]"
+            ),
+            "{}",
+            plan
+        );
+        Ok(())
+    }
+
     #[test]
     fn explain() -> Result<()> {
         // build query using Table API
@@ -246,14 +642,16 @@ mod tests {
         register_aggregate_csv(&mut ctx)?;
 
         // declare the udf
-        let my_fn: ScalarFunctionImplementation =
-            Arc::new(|_: &[ArrayRef]| unimplemented!("my_fn is not implemented"));
+        let my_fn = make_scalar_function(|_: &[ArrayRef]| {
+            unimplemented!("my_fn is not implemented")
+        });
 
         // create and register the udf
         ctx.register_udf(create_udf(
             "my_fn",
             vec![DataType::Float64],
             Arc::new(DataType::Float64),
+            Volatility::Immutable,
             my_fn,
         ));
 
@@ -275,6 +673,163 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn with_column_and_drop_columns() -> Result<()> {
+        let df = test_table()?.select_columns(vec!["c1", "c2", "c3"])?;
+        let df = df
+            .with_column("c2", col("c2") + lit(1))?
+            .with_column("c4", col("c3"))?
+            .drop_columns(&["c1"])?
+            .with_column_renamed("c3", "c5")?;
+        let names = df
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["c2", "c5", "c4"]);
+
+        let err = df.drop_columns(&["c1"]).expect_err("expected an error");
+        assert!(err.to_string().contains("c1"), "{}", err);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn distinct() -> Result<()> {
+        let df = test_table()?.select_columns(vec!["c1"])?.distinct()?;
+        let plan = format!("{:?}", df.to_logical_plan());
+        assert!(
+            plan.starts_with("Aggregate: groupBy=[[#aggregate_test_100.c1]], aggr=[[]]"),
+            "{}",
+            plan
+        );
+
+        let rows: usize = df.collect().await?.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, 5);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn describe() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+                Arc::new(StringArray::from(vec![
+                    Some("x"),
+                    Some("y"),
+                    None,
+                    Some("w"),
+                ])),
+            ],
+        )?;
+        let mut ctx = ExecutionContext::new();
+        let df = ctx.read_table(Arc::new(MemTable::new(schema, vec![vec![batch]])?))?;
+
+        let results = df.describe().await?.collect().await?;
+        let column = |i: usize| {
+            let array = results[0]
+                .column(i)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .expect("expected a StringArray");
+            (0..array.len())
+                .map(|row| {
+                    if array.is_null(row) {
+                        None
+                    } else {
+                        Some(array.value(row).to_string())
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            column(0),
+            vec![
+                some("count"),
+                some("mean"),
+                some("std"),
+                some("min"),
+                some("max")
+            ]
+        );
+        assert_eq!(
+            column(1),
+            vec![
+                some("4"),
+                some("2.5"),
+                Some(f64::sqrt(5.0 / 3.0).to_string()),
+                some("1"),
+                some("4")
+            ]
+        );
+        assert_eq!(column(2), vec![some("3"), None, None, some("w"), some("y")]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn describe_large_values() -> Result<()> {
+        // the squares of the values are beyond the precision of a f64, but not their
+        // deviations from the mean
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let values = (1..=4).map(|i| 1_000_000_000 + i).collect::<Vec<i64>>();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from(values))],
+        )?;
+        let mut ctx = ExecutionContext::new();
+        let df = ctx.read_table(Arc::new(MemTable::new(schema, vec![vec![batch]])?))?;
+
+        let results = df.describe().await?.collect().await?;
+        let array = results[0]
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .expect("expected a StringArray");
+        let values = (0..array.len())
+            .map(|row| array.value(row))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                "4",
+                "1000000002.5",
+                f64::sqrt(5.0 / 3.0).to_string().as_str(),
+                "1000000001",
+                "1000000004"
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cache_and_execute_stream() -> Result<()> {
+        let df = test_table()?
+            .select_columns(vec!["c1", "c2"])?
+            .filter(col("c2").gt(lit(3)))?;
+        let cached = df.cache().await?;
+        assert!(
+            format!("{:?}", cached.to_logical_plan()).starts_with("TableScan"),
+            "{:?}",
+            cached.to_logical_plan()
+        );
+
+        let count = |batches: &[RecordBatch]| -> usize {
+            batches.iter().map(|b| b.num_rows()).sum()
+        };
+        let expected = count(&df.collect().await?);
+        assert_eq!(count(&cached.collect().await?), expected);
+
+        let batches = df.execute_stream().await?.try_collect::<Vec<_>>().await?;
+        assert_eq!(count(&batches), expected);
+        Ok(())
+    }
+
     /// Compare the formatted string representation of two plans for equality
     fn assert_same_plan(plan1: &LogicalPlan, plan2: &LogicalPlan) {
         assert_eq!(format!("{:?}", plan1), format!("{:?}", plan2));
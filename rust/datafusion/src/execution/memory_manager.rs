@@ -0,0 +1,275 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Accounting of the memory that operators hold while a query executes, so that a query
+//! that needs more memory than it is allowed fails instead of exhausting the memory of
+//! the whole process.
+//!
+//! An [ExecutionContext](super::context::ExecutionContext) owns a [MemoryPool] that
+//! reports the memory used by all of its queries. Each query is planned against a child
+//! of that pool, whose limit is [ExecutionConfig::max_memory_per_query], and its
+//! operators take a [MemoryReservation] from it before they buffer data.
+//!
+//! [ExecutionConfig::max_memory_per_query]: super::context::ExecutionConfig::max_memory_per_query
+
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::error::{DataFusionError, Result};
+
+/// A pool of memory that operators reserve from before they allocate. A pool may have
+/// a limit, and may be the child of another pool, in which case every reservation
+/// from the child is also a reservation from its parent.
+pub struct MemoryPool {
+    /// The maximum number of bytes that can be reserved from this pool, if any
+    limit: Option<usize>,
+    /// The number of bytes currently reserved from this pool
+    reserved: AtomicUsize,
+    /// The largest number of bytes that has been reserved from this pool at once
+    peak: AtomicUsize,
+    /// The pool that all the reservations from this pool are also made from
+    parent: Option<Arc<MemoryPool>>,
+}
+
+impl MemoryPool {
+    /// Create a new pool, from which at most `limit` bytes can be reserved at once
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit,
+            reserved: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            parent: None,
+        }
+    }
+
+    /// Create a new pool whose reservations are also made from this pool, and from
+    /// which at most `limit` bytes can be reserved at once
+    pub fn new_child(self: &Arc<Self>, limit: Option<usize>) -> Arc<Self> {
+        Arc::new(Self {
+            parent: Some(self.clone()),
+            ..Self::new(limit)
+        })
+    }
+
+    /// The maximum number of bytes that can be reserved from this pool, if any
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// The number of bytes currently reserved from this pool
+    pub fn reserved(&self) -> usize {
+        self.reserved.load(Ordering::SeqCst)
+    }
+
+    /// The largest number of bytes that has been reserved from this pool at once
+    pub fn peak_reserved(&self) -> usize {
+        self.peak.load(Ordering::SeqCst)
+    }
+
+    /// Reserve `bytes` more bytes from this pool and its ancestors on behalf of
+    /// `consumer`, or return `DataFusionError::ResourcesExhausted` if that would exceed
+    /// the limit of any of them
+    fn try_grow(&self, bytes: usize, consumer: &str) -> Result<()> {
+        let mut reserved = self.reserved();
+        loop {
+            let new_reserved = reserved.saturating_add(bytes);
+            if let Some(limit) = self.limit {
+                if new_reserved > limit {
+                    return Err(DataFusionError::ResourcesExhausted(format!(
+                        "Cannot reserve {} bytes of memory for {}: {} of the {} bytes \
+                         of its memory pool are already reserved",
+                        bytes, consumer, reserved, limit
+                    )));
+                }
+            }
+            match self.reserved.compare_exchange(
+                reserved,
+                new_reserved,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => break,
+                Err(current) => reserved = current,
+            }
+        }
+
+        if let Some(parent) = &self.parent {
+            if let Err(e) = parent.try_grow(bytes, consumer) {
+                self.reserved.fetch_sub(bytes, Ordering::SeqCst);
+                return Err(e);
+            }
+        }
+        self.update_peak();
+        Ok(())
+    }
+
+    /// Release `bytes` bytes previously reserved from this pool and its ancestors
+    fn shrink(&self, bytes: usize) {
+        self.reserved.fetch_sub(bytes, Ordering::SeqCst);
+        if let Some(parent) = &self.parent {
+            parent.shrink(bytes);
+        }
+    }
+
+    fn update_peak(&self) {
+        let reserved = self.reserved();
+        let mut peak = self.peak_reserved();
+        while reserved > peak {
+            match self.peak.compare_exchange(
+                peak,
+                reserved,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => break,
+                Err(current) => peak = current,
+            }
+        }
+    }
+}
+
+impl fmt::Debug for MemoryPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the ancestors are left out, as they are shared by many pools
+        f.debug_struct("MemoryPool")
+            .field("limit", &self.limit)
+            .field("reserved", &self.reserved())
+            .finish()
+    }
+}
+
+impl Default for MemoryPool {
+    /// A pool without a limit
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+/// Memory reserved from a [MemoryPool] by a single consumer, such as the stream of an
+/// operator. The memory is returned to the pool when the reservation is dropped.
+#[derive(Debug)]
+pub struct MemoryReservation {
+    /// The name of the consumer, used in errors
+    consumer: String,
+    pool: Arc<MemoryPool>,
+    /// The number of bytes currently reserved
+    size: usize,
+}
+
+impl MemoryReservation {
+    /// Create a new, empty, reservation from `pool`, on behalf of `consumer`
+    pub fn new(consumer: impl Into<String>, pool: Arc<MemoryPool>) -> Self {
+        Self {
+            consumer: consumer.into(),
+            pool,
+            size: 0,
+        }
+    }
+
+    /// The number of bytes currently reserved
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Reserve `bytes` more bytes, or return `DataFusionError::ResourcesExhausted` if
+    /// the pool cannot provide them
+    pub fn try_grow(&mut self, bytes: usize) -> Result<()> {
+        self.pool.try_grow(bytes, &self.consumer)?;
+        self.size += bytes;
+        Ok(())
+    }
+
+    /// Release `bytes` bytes of this reservation
+    pub fn shrink(&mut self, bytes: usize) {
+        let bytes = bytes.min(self.size);
+        self.pool.shrink(bytes);
+        self.size -= bytes;
+    }
+
+    /// Grow or shrink this reservation to `size` bytes
+    pub fn try_resize(&mut self, size: usize) -> Result<()> {
+        if size > self.size {
+            self.try_grow(size - self.size)
+        } else {
+            self.shrink(self.size - size);
+            Ok(())
+        }
+    }
+
+    /// Release all of this reservation
+    pub fn free(&mut self) {
+        self.shrink(self.size)
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.free()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reservations() -> Result<()> {
+        let pool = Arc::new(MemoryPool::default());
+        let query = pool.new_child(Some(100));
+
+        let mut r1 = MemoryReservation::new("r1", query.clone());
+        let mut r2 = MemoryReservation::new("r2", query.clone());
+        r1.try_grow(60)?;
+        r2.try_grow(30)?;
+        assert_eq!(90, query.reserved());
+        assert_eq!(90, pool.reserved());
+
+        let err = r2.try_grow(20).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Resources exhausted: Cannot reserve 20 bytes of memory for r2: 90 of the \
+             100 bytes of its memory pool are already reserved"
+        );
+        assert_eq!(30, r2.size());
+        assert_eq!(90, pool.reserved());
+
+        r1.try_resize(10)?;
+        r2.try_grow(20)?;
+        assert_eq!(60, query.reserved());
+        drop(r1);
+        drop(r2);
+        assert_eq!(0, query.reserved());
+        assert_eq!(0, pool.reserved());
+        assert_eq!(90, query.peak_reserved());
+        Ok(())
+    }
+
+    #[test]
+    fn parent_limit() -> Result<()> {
+        let pool = Arc::new(MemoryPool::new(Some(50)));
+        let query = pool.new_child(None);
+
+        let mut r = MemoryReservation::new("r", query.clone());
+        assert!(r.try_grow(60).is_err());
+        // the failed reservation is not left behind in the child
+        assert_eq!(0, query.reserved());
+        r.try_grow(50)?;
+        assert_eq!(50, pool.reserved());
+        Ok(())
+    }
+}
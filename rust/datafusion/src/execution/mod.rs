@@ -17,5 +17,8 @@
 
 //! DataFusion query execution
 
+pub mod cancellation;
 pub mod context;
 pub mod dataframe_impl;
+pub mod memory_manager;
+pub mod prepared_statement;
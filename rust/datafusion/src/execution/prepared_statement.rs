@@ -0,0 +1,197 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Prepared statements: queries with placeholders that are planned once and executed
+//! many times with different parameters.
+
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::{ExecutionContext, ExecutionContextState};
+use crate::logical_plan::{Expr, LogicalPlan};
+use crate::optimizer::utils;
+use crate::physical_plan::expressions::cast_scalar;
+use crate::scalar::ScalarValue;
+
+/// A query whose placeholders (`$1`, `$2`, ... or `?`) are bound to values when it is
+/// executed. The query is parsed, planned and optimized only once, when it is prepared
+/// with [ExecutionContext::prepare].
+pub struct PreparedStatement {
+    ctx_state: ExecutionContextState,
+    plan: LogicalPlan,
+    parameter_types: Vec<Option<DataType>>,
+}
+
+impl PreparedStatement {
+    /// Create a new prepared statement from an optimized logical plan
+    pub fn try_new(ctx_state: ExecutionContextState, plan: LogicalPlan) -> Result<Self> {
+        let mut parameter_types = vec![];
+        collect_plan_parameters(&plan, &mut parameter_types)?;
+        Ok(Self {
+            ctx_state,
+            plan,
+            parameter_types,
+        })
+    }
+
+    /// The optimized logical plan of this statement, with its placeholders unbound
+    pub fn logical_plan(&self) -> &LogicalPlan {
+        &self.plan
+    }
+
+    /// The types of the parameters of this statement, where the type of `$1` comes first.
+    /// A type is `None` when it could not be inferred from the query, in which case
+    /// the parameter takes the type of the value it is bound to.
+    pub fn parameter_types(&self) -> &[Option<DataType>] {
+        &self.parameter_types
+    }
+
+    /// Returns the logical plan of this statement with its placeholders replaced by
+    /// `params`, where `params[0]` is bound to `$1`.
+    ///
+    /// # Errors
+    ///
+    /// This function errors when the number of parameters does not match the number of
+    /// placeholders, or when a parameter cannot be cast to the type of its placeholder.
+    pub fn bind(&self, params: Vec<ScalarValue>) -> Result<LogicalPlan> {
+        if params.len() != self.parameter_types.len() {
+            return Err(DataFusionError::Plan(format!(
+                "Expected {} parameters, but {} were given",
+                self.parameter_types.len(),
+                params.len()
+            )));
+        }
+        let params = params
+            .iter()
+            .zip(self.parameter_types.iter())
+            .enumerate()
+            .map(|(i, (value, data_type))| match data_type {
+                Some(data_type) => bind_value(i + 1, value, data_type),
+                None => Ok(value.clone()),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        bind_plan(&self.plan, &params)
+    }
+
+    /// Binds `params` to the placeholders of this statement and executes it, collecting
+    /// the results in memory.
+    pub async fn execute(&self, params: Vec<ScalarValue>) -> Result<Vec<RecordBatch>> {
+        let plan = self.bind(params)?;
+        let ctx = ExecutionContext::from(self.ctx_state.clone());
+        let plan = ctx.create_physical_plan(&plan)?;
+        ctx.collect(plan).await
+    }
+}
+
+/// Returns the position of a placeholder, e.g. 1 for `$1`
+fn placeholder_index(id: &str) -> Result<usize> {
+    match id.get(1..).map(|index| index.parse::<usize>()) {
+        Some(Ok(index)) if index > 0 => Ok(index),
+        _ => Err(DataFusionError::Plan(format!(
+            "Invalid placeholder '{}'. Placeholders are numbered from $1",
+            id
+        ))),
+    }
+}
+
+fn collect_plan_parameters(
+    plan: &LogicalPlan,
+    accum: &mut Vec<Option<DataType>>,
+) -> Result<()> {
+    for expr in utils::expressions(plan) {
+        collect_parameters(&expr, accum)?;
+    }
+    for input in utils::inputs(plan) {
+        collect_plan_parameters(input, accum)?;
+    }
+    Ok(())
+}
+
+/// Recursively walk an expression tree, recording the type of each placeholder at
+/// its position in `accum`
+fn collect_parameters(expr: &Expr, accum: &mut Vec<Option<DataType>>) -> Result<()> {
+    if let Expr::Placeholder { id, data_type } = expr {
+        let index = placeholder_index(id)?;
+        if accum.len() < index {
+            accum.resize(index, None);
+        }
+        return match (&accum[index - 1], data_type) {
+            (Some(existing), Some(data_type)) if existing != data_type => {
+                Err(DataFusionError::Plan(format!(
+                    "Placeholder {} is used both as {:?} and as {:?}",
+                    id, existing, data_type
+                )))
+            }
+            (None, data_type) => {
+                accum[index - 1] = data_type.clone();
+                Ok(())
+            }
+            _ => Ok(()),
+        };
+    }
+    for e in utils::expr_sub_expressions(expr)? {
+        collect_parameters(e, accum)?;
+    }
+    Ok(())
+}
+
+/// Casts the value of parameter `index` to the type of its placeholder
+fn bind_value(
+    index: usize,
+    value: &ScalarValue,
+    data_type: &DataType,
+) -> Result<ScalarValue> {
+    let invalid = || {
+        DataFusionError::Plan(format!(
+            "Parameter ${} expects a value of type {:?}, but {:?} was given",
+            index, data_type, value
+        ))
+    };
+    let bound = cast_scalar(value, data_type).map_err(|_| invalid())?;
+    // casts of values that cannot be represented in the target type return nulls
+    if bound.is_null() && !value.is_null() {
+        return Err(invalid());
+    }
+    Ok(bound)
+}
+
+fn bind_plan(plan: &LogicalPlan, params: &[ScalarValue]) -> Result<LogicalPlan> {
+    let expr = utils::expressions(plan)
+        .iter()
+        .map(|e| bind_expr(e, params))
+        .collect::<Result<Vec<_>>>()?;
+    let inputs = utils::inputs(plan)
+        .into_iter()
+        .map(|input| bind_plan(input, params))
+        .collect::<Result<Vec<_>>>()?;
+    utils::from_plan(plan, &expr, &inputs)
+}
+
+/// Replaces the placeholders of `expr` by their values. The values keep the name of
+/// their placeholder, so that the columns of the plan are named as when it was prepared.
+fn bind_expr(expr: &Expr, params: &[ScalarValue]) -> Result<Expr> {
+    if let Expr::Placeholder { id, .. } = expr {
+        let value = params[placeholder_index(id)? - 1].clone();
+        return Ok(Expr::Alias(Box::new(Expr::Literal(value)), id.clone()));
+    }
+    let expressions = utils::expr_sub_expressions(expr)?
+        .into_iter()
+        .map(|e| bind_expr(e, params))
+        .collect::<Result<Vec<_>>>()?;
+    utils::rewrite_expression(expr, &expressions)
+}
@@ -159,6 +159,7 @@ pub mod optimizer;
 pub mod physical_plan;
 pub mod prelude;
 pub mod scalar;
+pub mod serde;
 pub mod sql;
 pub mod variable;
 
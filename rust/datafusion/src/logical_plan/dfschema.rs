@@ -0,0 +1,401 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! DFSchema is an extended schema struct that DataFusion uses to provide support for
+//! fields with optional relation names.
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use super::Column;
+use crate::error::{DataFusionError, Result};
+
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+
+/// A reference-counted reference to a `DFSchema`.
+pub type DFSchemaRef = Arc<DFSchema>;
+
+/// DFSchema wraps an Arrow schema and adds relation names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DFSchema {
+    /// Fields
+    fields: Vec<DFField>,
+}
+
+impl DFSchema {
+    /// Creates an empty `DFSchema`
+    pub fn empty() -> Self {
+        Self { fields: vec![] }
+    }
+
+    /// Create a new `DFSchema`
+    ///
+    /// # Errors
+    /// This function errors when two fields have the same name and the same
+    /// (or no) qualifier.
+    pub fn new(fields: Vec<DFField>) -> Result<Self> {
+        let mut names = HashSet::new();
+        for field in &fields {
+            if !names.insert((field.qualifier(), field.name())) {
+                return Err(DataFusionError::Plan(format!(
+                    "Schema contains duplicate field name '{}'",
+                    field.qualified_name()
+                )));
+            }
+        }
+        Ok(Self { fields })
+    }
+
+    /// Create a `DFSchema` from an Arrow schema, qualifying all of its fields
+    /// with the relation name `qualifier`
+    pub fn try_from_qualified_schema(qualifier: &str, schema: &Schema) -> Result<Self> {
+        Self::new(
+            schema
+                .fields()
+                .iter()
+                .map(|f| DFField::from_qualified(qualifier, f.clone()))
+                .collect(),
+        )
+    }
+
+    /// Get a list of fields
+    pub fn fields(&self) -> &Vec<DFField> {
+        &self.fields
+    }
+
+    /// Returns an immutable reference of a specific `Field` instance selected using an
+    /// offset within the internal `fields` vector
+    pub fn field(&self, i: usize) -> &DFField {
+        &self.fields[i]
+    }
+
+    /// Find the index of the column with the given unqualified name
+    pub fn index_of(&self, name: &str) -> Result<usize> {
+        self.index_of_column(&Column::from_name(name))
+    }
+
+    /// Find the index of the field referenced by `column`
+    pub fn index_of_column(&self, column: &Column) -> Result<usize> {
+        let matches = self
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| {
+                field.name() == &column.name
+                    && match &column.relation {
+                        Some(relation) => field.qualifier() == Some(relation),
+                        None => true,
+                    }
+            })
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        match matches.as_slice() {
+            [i] => Ok(*i),
+            [] => Err(DataFusionError::Plan(format!(
+                "No field named '{}'. Valid fields are {}.",
+                column.flat_name(),
+                self.fields
+                    .iter()
+                    .map(|f| format!("'{}'", f.qualified_name()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+            _ => Err(DataFusionError::Plan(format!(
+                "Ambiguous reference to field named '{}'. Consider qualifying it \
+                 with one of the relations {}.",
+                column.flat_name(),
+                matches
+                    .iter()
+                    .filter_map(|i| self.fields[*i].qualifier())
+                    .map(|q| format!("'{}'", q))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
+    }
+
+    /// Find the field referenced by `column`. An unqualified column matches a field
+    /// of any relation, and errors when several fields match it.
+    pub fn field_from_column(&self, column: &Column) -> Result<&DFField> {
+        Ok(self.field(self.index_of_column(column)?))
+    }
+
+    /// Find the field with the given name, optionally qualified by a relation
+    pub fn field_with_name(
+        &self,
+        qualifier: Option<&str>,
+        name: &str,
+    ) -> Result<&DFField> {
+        self.field_from_column(&Column::new(qualifier, name))
+    }
+
+    /// Find the field with the given unqualified name
+    pub fn field_with_unqualified_name(&self, name: &str) -> Result<&DFField> {
+        self.field_with_name(None, name)
+    }
+
+    /// Find the field with the given qualified name
+    pub fn field_with_qualified_name(
+        &self,
+        qualifier: &str,
+        name: &str,
+    ) -> Result<&DFField> {
+        self.field_with_name(Some(qualifier), name)
+    }
+}
+
+impl From<&DFSchema> for Schema {
+    /// Convert a `DFSchema` to an Arrow schema, dropping the qualifiers
+    fn from(df_schema: &DFSchema) -> Self {
+        Schema::new(df_schema.fields.iter().map(|f| f.field().clone()).collect())
+    }
+}
+
+impl From<DFSchema> for Schema {
+    fn from(df_schema: DFSchema) -> Self {
+        (&df_schema).into()
+    }
+}
+
+impl TryFrom<Schema> for DFSchema {
+    type Error = DataFusionError;
+
+    /// Create an unqualified `DFSchema` from an Arrow schema
+    fn try_from(schema: Schema) -> std::result::Result<Self, Self::Error> {
+        Self::new(
+            schema
+                .fields()
+                .iter()
+                .map(|f| DFField::from(f.clone()))
+                .collect(),
+        )
+    }
+}
+
+impl Display for DFSchema {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.fields
+                .iter()
+                .map(|field| format!(
+                    "{}: {:?}",
+                    field.qualified_name(),
+                    field.data_type()
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+/// Convenience conversions into a `DFSchema`
+pub trait ToDFSchema
+where
+    Self: Sized,
+{
+    /// Attempt to create a `DFSchema`
+    fn to_dfschema(self) -> Result<DFSchema>;
+
+    /// Attempt to create a `DFSchemaRef`
+    fn to_dfschema_ref(self) -> Result<DFSchemaRef> {
+        Ok(Arc::new(self.to_dfschema()?))
+    }
+}
+
+impl ToDFSchema for Schema {
+    fn to_dfschema(self) -> Result<DFSchema> {
+        DFSchema::try_from(self)
+    }
+}
+
+impl ToDFSchema for SchemaRef {
+    fn to_dfschema(self) -> Result<DFSchema> {
+        self.as_ref().clone().to_dfschema()
+    }
+}
+
+impl ToDFSchema for Vec<DFField> {
+    fn to_dfschema(self) -> Result<DFSchema> {
+        DFSchema::new(self)
+    }
+}
+
+/// DFField wraps an Arrow field and adds an optional qualifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DFField {
+    /// Optional qualifier (usually a table or relation name)
+    qualifier: Option<String>,
+    /// Arrow field definition
+    field: Field,
+}
+
+impl DFField {
+    /// Creates a new `DFField`
+    pub fn new(
+        qualifier: Option<&str>,
+        name: &str,
+        data_type: DataType,
+        nullable: bool,
+    ) -> Self {
+        DFField {
+            qualifier: qualifier.map(|s| s.to_owned()),
+            field: Field::new(name, data_type, nullable),
+        }
+    }
+
+    /// Create a qualified field from an existing Arrow field
+    pub fn from_qualified(qualifier: &str, field: Field) -> Self {
+        Self {
+            qualifier: Some(qualifier.to_owned()),
+            field,
+        }
+    }
+
+    /// Returns an immutable reference to the `DFField`'s unqualified name
+    pub fn name(&self) -> &String {
+        self.field.name()
+    }
+
+    /// Returns an immutable reference to the `DFField`'s data-type
+    pub fn data_type(&self) -> &DataType {
+        self.field.data_type()
+    }
+
+    /// Indicates whether this `DFField` supports null values
+    pub fn is_nullable(&self) -> bool {
+        self.field.is_nullable()
+    }
+
+    /// Returns the qualified name, e.g. `t.a`, or the name if there is no qualifier
+    pub fn qualified_name(&self) -> String {
+        self.qualified_column().flat_name()
+    }
+
+    /// Returns a column that references this field, qualified by its relation
+    pub fn qualified_column(&self) -> Column {
+        Column {
+            relation: self.qualifier.clone(),
+            name: self.field.name().clone(),
+        }
+    }
+
+    /// Get the optional qualifier
+    pub fn qualifier(&self) -> Option<&String> {
+        self.qualifier.as_ref()
+    }
+
+    /// Get the arrow field
+    pub fn field(&self) -> &Field {
+        &self.field
+    }
+}
+
+impl From<Field> for DFField {
+    /// Create an unqualified field from an existing Arrow field
+    fn from(field: Field) -> Self {
+        Self {
+            qualifier: None,
+            field,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_schema(qualifier: &str) -> Result<DFSchema> {
+        DFSchema::try_from_qualified_schema(
+            qualifier,
+            &Schema::new(vec![
+                Field::new("c0", DataType::Boolean, true),
+                Field::new("c1", DataType::Int32, false),
+            ]),
+        )
+    }
+
+    #[test]
+    fn qualified_lookup() -> Result<()> {
+        let schema = test_schema("t1")?;
+        assert_eq!(1, schema.index_of_column(&Column::new(Some("t1"), "c1"))?);
+        assert_eq!(1, schema.index_of("c1")?);
+        assert_eq!(
+            &DataType::Boolean,
+            schema.field_with_qualified_name("t1", "c0")?.data_type()
+        );
+        assert_eq!("t1.c0", schema.field(0).qualified_name());
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_field() -> Result<()> {
+        let schema = test_schema("t1")?;
+        let err = schema
+            .field_with_qualified_name("t2", "c0")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Error during planning: No field named 't2.c0'. Valid fields are 't1.c0', 't1.c1'."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ambiguous_field() -> Result<()> {
+        let mut fields = test_schema("t1")?.fields().clone();
+        fields.extend(test_schema("t2")?.fields().clone());
+        let schema = DFSchema::new(fields)?;
+
+        assert_eq!(3, schema.index_of_column(&Column::new(Some("t2"), "c1"))?);
+        let err = schema.index_of("c1").unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Error during planning: Ambiguous reference to field named 'c1'. \
+             Consider qualifying it with one of the relations 't1', 't2'."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_field() -> Result<()> {
+        let mut fields = test_schema("t1")?.fields().clone();
+        fields.push(DFField::new(Some("t1"), "c0", DataType::Utf8, true));
+        let err = DFSchema::new(fields).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Error during planning: Schema contains duplicate field name 't1.c0'"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn to_arrow_schema() -> Result<()> {
+        let schema = test_schema("t1")?;
+        let arrow_schema: Schema = (&schema).into();
+        assert_eq!(arrow_schema.field(0).name(), "c0");
+        assert_eq!(
+            schema,
+            DFSchema::try_from_qualified_schema("t1", &arrow_schema)?
+        );
+        Ok(())
+    }
+}
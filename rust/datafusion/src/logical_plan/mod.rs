@@ -40,21 +40,29 @@ use crate::{
 };
 use crate::{
     physical_plan::{
-        aggregates, expressions::binary_operator_data_type, functions, udf::ScalarUDF,
+        aggregates,
+        expressions::{binary_operator_data_type, get_indexed_field, order_coercion},
+        functions,
+        udf::ScalarUDF,
+        udtf::{TableFunctionImplementation, TableUDF},
     },
     sql::parser::FileType,
 };
 use arrow::record_batch::RecordBatch;
-use functions::{ReturnTypeFunction, ScalarFunctionImplementation, Signature};
+use functions::{
+    ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility,
+};
 
+mod dfschema;
 mod operators;
+pub use dfschema::{DFField, DFSchema, DFSchemaRef, ToDFSchema};
 pub use operators::Operator;
 
 fn create_function_name(
     fun: &String,
     distinct: bool,
     args: &[Expr],
-    input_schema: &Schema,
+    input_schema: &DFSchema,
 ) -> Result<String> {
     let names: Vec<String> = args
         .iter()
@@ -69,10 +77,10 @@ fn create_function_name(
 
 /// Returns a readable name of an expression based on the input schema.
 /// This function recursively transverses the expression for names such as "CAST(a > 2)".
-fn create_name(e: &Expr, input_schema: &Schema) -> Result<String> {
+fn create_name(e: &Expr, input_schema: &DFSchema) -> Result<String> {
     match e {
         Expr::Alias(_, name) => Ok(name.clone()),
-        Expr::Column(name) => Ok(name.clone()),
+        Expr::Column(c) => Ok(c.name.clone()),
         Expr::ScalarVariable(variable_names) => Ok(variable_names.join(".")),
         Expr::Literal(value) => Ok(format!("{:?}", value)),
         Expr::BinaryExpr { left, op, right } => {
@@ -84,6 +92,10 @@ fn create_name(e: &Expr, input_schema: &Schema) -> Result<String> {
             let expr = create_name(expr, input_schema)?;
             Ok(format!("CAST({} AS {:?})", expr, data_type))
         }
+        Expr::GetIndexedField { expr, key } => {
+            let expr = create_name(expr, input_schema)?;
+            Ok(format!("{}[{}]", expr, key))
+        }
         Expr::Not(expr) => {
             let expr = create_name(expr, input_schema)?;
             Ok(format!("NOT {}", expr))
@@ -96,6 +108,31 @@ fn create_name(e: &Expr, input_schema: &Schema) -> Result<String> {
             let expr = create_name(expr, input_schema)?;
             Ok(format!("{} IS NOT NULL", expr))
         }
+        Expr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => {
+            let expr = create_name(expr, input_schema)?;
+            let low = create_name(low, input_schema)?;
+            let high = create_name(high, input_schema)?;
+            let not = if *negated { "NOT " } else { "" };
+            Ok(format!("{} {}BETWEEN {} AND {}", expr, not, low, high))
+        }
+        Expr::InList {
+            expr,
+            list,
+            negated,
+        } => {
+            let expr = create_name(expr, input_schema)?;
+            let list = list
+                .iter()
+                .map(|e| create_name(e, input_schema))
+                .collect::<Result<Vec<_>>>()?;
+            let not = if *negated { "NOT " } else { "" };
+            Ok(format!("{} {}IN ({})", expr, not, list.join(", ")))
+        }
         Expr::ScalarFunction { fun, args, .. } => {
             create_function_name(&fun.to_string(), false, args, input_schema)
         }
@@ -115,6 +152,7 @@ fn create_name(e: &Expr, input_schema: &Schema) -> Result<String> {
             }
             Ok(format!("{}({})", fun.name, names.join(",")))
         }
+        Expr::Placeholder { id, .. } => Ok(id.clone()),
         other => Err(DataFusionError::NotImplemented(format!(
             "Physical plan does not support logical expression {:?}",
             other
@@ -123,10 +161,52 @@ fn create_name(e: &Expr, input_schema: &Schema) -> Result<String> {
 }
 
 /// Create field meta-data from an expression, for use in a result set schema
-pub fn exprlist_to_fields(expr: &[Expr], input_schema: &Schema) -> Result<Vec<Field>> {
+pub fn exprlist_to_fields(
+    expr: &[Expr],
+    input_schema: &DFSchema,
+) -> Result<Vec<DFField>> {
     expr.iter().map(|e| e.to_field(input_schema)).collect()
 }
 
+/// A reference to a column, optionally qualified by the name of its relation,
+/// e.g. `a` or `t.a`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Column {
+    /// The relation (table or alias) the column belongs to, if qualified
+    pub relation: Option<String>,
+    /// The name of the column
+    pub name: String,
+}
+
+impl Column {
+    /// Create a column reference, optionally qualified by `relation`
+    pub fn new(relation: Option<&str>, name: &str) -> Self {
+        Self {
+            relation: relation.map(|r| r.to_owned()),
+            name: name.to_owned(),
+        }
+    }
+
+    /// Create a column reference that is not qualified by a relation
+    pub fn from_name(name: &str) -> Self {
+        Self::new(None, name)
+    }
+
+    /// Returns the column reference as a string, e.g. `t.a`, or `a` when unqualified
+    pub fn flat_name(&self) -> String {
+        match &self.relation {
+            Some(relation) => format!("{}.{}", relation, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.flat_name())
+    }
+}
+
 /// `Expr` is a logical expression. A logical expression is something like `1 + 1`, or `CAST(c1 AS int)`.
 /// Logical expressions know how to compute its [arrow::datatypes::DataType] and nullability.
 /// `Expr` is a central struct of DataFusion's query API.
@@ -134,10 +214,10 @@ pub fn exprlist_to_fields(expr: &[Expr], input_schema: &Schema) -> Result<Vec<Fi
 /// # Examples
 ///
 /// ```
-/// # use datafusion::logical_plan::Expr;
+/// # use datafusion::logical_plan::{Column, Expr};
 /// # use datafusion::error::Result;
 /// # fn main() -> Result<()> {
-/// let expr = Expr::Column("c1".to_string()) + Expr::Column("c2".to_string());
+/// let expr = Expr::Column(Column::from_name("c1")) + Expr::Column(Column::from_name("c2"));
 /// println!("{:?}", expr);
 /// # Ok(())
 /// # }
@@ -146,8 +226,8 @@ pub fn exprlist_to_fields(expr: &[Expr], input_schema: &Schema) -> Result<Vec<Fi
 pub enum Expr {
     /// An expression with a specific name.
     Alias(Box<Expr>, String),
-    /// A named reference to a field in a schema.
-    Column(String),
+    /// A named reference to a field in a schema, optionally qualified by a relation.
+    Column(Column),
     /// A named reference to a variable in a registry.
     ScalarVariable(Vec<String>),
     /// A constant value.
@@ -169,6 +249,27 @@ pub enum Expr {
     IsNotNull(Box<Expr>),
     /// Whether an expression is Null. This expression is never null.
     IsNull(Box<Expr>),
+    /// Whether an expression is between a given range, e.g. `age BETWEEN 18 AND 65`.
+    Between {
+        /// The value to compare
+        expr: Box<Expr>,
+        /// Whether the expression is negated (`NOT BETWEEN`)
+        negated: bool,
+        /// The low end of the range, inclusive
+        low: Box<Expr>,
+        /// The high end of the range, inclusive
+        high: Box<Expr>,
+    },
+    /// Whether an expression is equal to one of a list of expressions,
+    /// e.g. `state IN ('CA', 'NY')`.
+    InList {
+        /// The value to look for
+        expr: Box<Expr>,
+        /// The list of values to compare against
+        list: Vec<Expr>,
+        /// Whether the expression is negated (`NOT IN`)
+        negated: bool,
+    },
     /// Casts the expression to a given type. This expression is guaranteed to have a fixed type.
     Cast {
         /// The expression being cast
@@ -176,6 +277,14 @@ pub enum Expr {
         /// The `DataType` the expression will yield
         data_type: DataType,
     },
+    /// The field of a struct, e.g. `a.b` or `a["b"]`, or the element of a list at a
+    /// 1-based index, e.g. `a[1]`, which is null when the list has no such element.
+    GetIndexedField {
+        /// The struct or list
+        expr: Box<Expr>,
+        /// The name of the field (`Utf8`) or the index of the element (`Int64`)
+        key: ScalarValue,
+    },
     /// A sort expression, that can be used to sort values.
     Sort {
         /// The expression to sort on
@@ -217,23 +326,35 @@ pub enum Expr {
     },
     /// Represents a reference to all fields in a schema.
     Wildcard,
+    /// A parameter of a prepared statement, e.g. `$1`, whose value is bound when the
+    /// statement is executed.
+    Placeholder {
+        /// The name of the placeholder, e.g. `$1`
+        id: String,
+        /// The type of the placeholder, when it could be inferred from its usage
+        data_type: Option<DataType>,
+    },
 }
 
 impl Expr {
-    /// Returns the [arrow::datatypes::DataType] of the expression based on [arrow::datatypes::Schema].
+    /// Returns the [arrow::datatypes::DataType] of the expression based on [DFSchema].
     ///
     /// # Errors
     ///
     /// This function errors when it is not possible to compute its [arrow::datatypes::DataType].
     /// This happens when e.g. the expression refers to a column that does not exist in the schema, or when
     /// the expression is incorrectly typed (e.g. `[utf8] + [bool]`).
-    pub fn get_type(&self, schema: &Schema) -> Result<DataType> {
+    pub fn get_type(&self, schema: &DFSchema) -> Result<DataType> {
         match self {
             Expr::Alias(expr, _) => expr.get_type(schema),
-            Expr::Column(name) => Ok(schema.field_with_name(name)?.data_type().clone()),
+            Expr::Column(c) => Ok(schema.field_from_column(c)?.data_type().clone()),
             Expr::ScalarVariable(_) => Ok(DataType::Utf8),
             Expr::Literal(l) => Ok(l.get_datatype()),
             Expr::Cast { data_type, .. } => Ok(data_type.clone()),
+            Expr::GetIndexedField { expr, key } => {
+                let field = get_indexed_field(&expr.get_type(schema)?, key)?;
+                Ok(field.data_type().clone())
+            }
             Expr::ScalarUDF { fun, args } => {
                 let data_types = args
                     .iter()
@@ -265,6 +386,8 @@ impl Expr {
             Expr::Not(_) => Ok(DataType::Boolean),
             Expr::IsNull(_) => Ok(DataType::Boolean),
             Expr::IsNotNull(_) => Ok(DataType::Boolean),
+            Expr::Between { .. } => Ok(DataType::Boolean),
+            Expr::InList { .. } => Ok(DataType::Boolean),
             Expr::BinaryExpr {
                 ref left,
                 ref right,
@@ -279,22 +402,30 @@ impl Expr {
                 "Wildcard expressions are not valid in a logical query plan".to_owned(),
             )),
             Expr::Nested(e) => e.get_type(schema),
+            Expr::Placeholder { id, data_type } => data_type.clone().ok_or_else(|| {
+                DataFusionError::Plan(format!(
+                    "The type of placeholder {} could not be inferred. Use CAST({} AS <type>) to declare it.",
+                    id, id
+                ))
+            }),
         }
     }
 
-    /// Returns the nullability of the expression based on [arrow::datatypes::Schema].
+    /// Returns the nullability of the expression based on [DFSchema].
     ///
     /// # Errors
     ///
     /// This function errors when it is not possible to compute its nullability.
     /// This happens when the expression refers to a column that does not exist in the schema.
-    pub fn nullable(&self, input_schema: &Schema) -> Result<bool> {
+    pub fn nullable(&self, input_schema: &DFSchema) -> Result<bool> {
         match self {
             Expr::Alias(expr, _) => expr.nullable(input_schema),
-            Expr::Column(name) => Ok(input_schema.field_with_name(name)?.is_nullable()),
+            Expr::Column(c) => Ok(input_schema.field_from_column(c)?.is_nullable()),
             Expr::Literal(value) => Ok(value.is_null()),
             Expr::ScalarVariable(_) => Ok(true),
             Expr::Cast { expr, .. } => expr.nullable(input_schema),
+            // lists may not have an element at the index
+            Expr::GetIndexedField { .. } => Ok(true),
             Expr::ScalarFunction { .. } => Ok(true),
             Expr::ScalarUDF { .. } => Ok(true),
             Expr::AggregateFunction { .. } => Ok(true),
@@ -302,29 +433,53 @@ impl Expr {
             Expr::Not(expr) => expr.nullable(input_schema),
             Expr::IsNull(_) => Ok(false),
             Expr::IsNotNull(_) => Ok(false),
+            Expr::Between {
+                expr, low, high, ..
+            } => Ok(expr.nullable(input_schema)?
+                || low.nullable(input_schema)?
+                || high.nullable(input_schema)?),
+            Expr::InList { expr, list, .. } => {
+                let mut nullable = expr.nullable(input_schema)?;
+                for e in list {
+                    nullable = nullable || e.nullable(input_schema)?;
+                }
+                Ok(nullable)
+            }
             Expr::BinaryExpr {
                 ref left,
+                ref op,
                 ref right,
-                ..
-            } => Ok(left.nullable(input_schema)? || right.nullable(input_schema)?),
+            } => match op {
+                // nulls are compared as values, so the result is never null
+                Operator::IsDistinctFrom | Operator::IsNotDistinctFrom => Ok(false),
+                _ => Ok(left.nullable(input_schema)? || right.nullable(input_schema)?),
+            },
             Expr::Sort { ref expr, .. } => expr.nullable(input_schema),
             Expr::Nested(e) => e.nullable(input_schema),
+            Expr::Placeholder { .. } => Ok(true),
             Expr::Wildcard => Err(DataFusionError::Internal(
                 "Wildcard expressions are not valid in a logical query plan".to_owned(),
             )),
         }
     }
 
-    /// Returns the name of this expression based on [arrow::datatypes::Schema].
+    /// Returns the name of this expression based on [DFSchema].
     ///
-    /// This represents how a column with this expression is named when no alias is chosen
-    pub fn name(&self, input_schema: &Schema) -> Result<String> {
+    /// This represents how a column with this expression is named when no alias is chosen.
+    /// Column references are named after the column, without their relation.
+    pub fn name(&self, input_schema: &DFSchema) -> Result<String> {
         create_name(self, input_schema)
     }
 
-    /// Returns a [arrow::datatypes::Field] compatible with this expression.
-    pub fn to_field(&self, input_schema: &Schema) -> Result<Field> {
-        Ok(Field::new(
+    /// Returns a [DFField] compatible with this expression. Column references keep the
+    /// qualifier of the field they resolve to, all other expressions are unqualified.
+    pub fn to_field(&self, input_schema: &DFSchema) -> Result<DFField> {
+        let qualifier = match self {
+            Expr::Column(c) => input_schema.field_from_column(c)?.qualifier().cloned(),
+            _ => None,
+        };
+        Ok(DFField::new(
+            qualifier.as_deref(),
             &self.name(input_schema)?,
             self.get_type(input_schema)?,
             self.nullable(input_schema)?,
@@ -337,7 +492,7 @@ impl Expr {
     ///
     /// This function errors when it is impossible to cast the
     /// expression to the target [arrow::datatypes::DataType].
-    pub fn cast_to(&self, cast_to_type: &DataType, schema: &Schema) -> Result<Expr> {
+    pub fn cast_to(&self, cast_to_type: &DataType, schema: &DFSchema) -> Result<Expr> {
         let this_type = self.get_type(schema)?;
         if this_type == *cast_to_type {
             Ok(self.clone())
@@ -414,6 +569,81 @@ impl Expr {
         binary_expr(self.clone(), Operator::NotLike, other.clone())
     }
 
+    /// matches (string) a regular expression, case sensitively (`~`)
+    pub fn regex_match(&self, pattern: Expr) -> Expr {
+        binary_expr(self.clone(), Operator::RegexMatch, pattern)
+    }
+
+    /// matches (string) a regular expression, case insensitively (`~*`)
+    pub fn regex_imatch(&self, pattern: Expr) -> Expr {
+        binary_expr(self.clone(), Operator::RegexIMatch, pattern)
+    }
+
+    /// does not match a regular expression, case sensitively (`!~`)
+    pub fn regex_not_match(&self, pattern: Expr) -> Expr {
+        binary_expr(self.clone(), Operator::RegexNotMatch, pattern)
+    }
+
+    /// does not match a regular expression, case insensitively (`!~*`)
+    pub fn regex_not_imatch(&self, pattern: Expr) -> Expr {
+        binary_expr(self.clone(), Operator::RegexNotIMatch, pattern)
+    }
+
+    /// is distinct from another expression, treating nulls as comparable values
+    pub fn is_distinct_from(&self, other: Expr) -> Expr {
+        binary_expr(self.clone(), Operator::IsDistinctFrom, other)
+    }
+
+    /// is not distinct from another expression, treating nulls as comparable values
+    pub fn is_not_distinct_from(&self, other: Expr) -> Expr {
+        binary_expr(self.clone(), Operator::IsNotDistinctFrom, other)
+    }
+
+    /// is between `low` and `high`, inclusive
+    pub fn between(&self, low: Expr, high: Expr) -> Expr {
+        Expr::Between {
+            expr: Box::new(self.clone()),
+            negated: false,
+            low: Box::new(low),
+            high: Box::new(high),
+        }
+    }
+
+    /// is not between `low` and `high`
+    pub fn not_between(&self, low: Expr, high: Expr) -> Expr {
+        Expr::Between {
+            expr: Box::new(self.clone()),
+            negated: true,
+            low: Box::new(low),
+            high: Box::new(high),
+        }
+    }
+
+    /// is equal to one of the expressions of `list`, or to none of them when `negated`
+    pub fn in_list(&self, list: Vec<Expr>, negated: bool) -> Expr {
+        Expr::InList {
+            expr: Box::new(self.clone()),
+            list,
+            negated,
+        }
+    }
+
+    /// The field named `name` of a struct, e.g. `col("a").field("b")` for `a.b`
+    pub fn field(&self, name: &str) -> Expr {
+        Expr::GetIndexedField {
+            expr: Box::new(self.clone()),
+            key: ScalarValue::Utf8(Some(name.to_owned())),
+        }
+    }
+
+    /// The element of a list at the 1-based `index`, e.g. `col("a").index(1)` for `a[1]`
+    pub fn index(&self, index: i64) -> Expr {
+        Expr::GetIndexedField {
+            expr: Box::new(self.clone()),
+            key: ScalarValue::Int64(Some(index)),
+        }
+    }
+
     /// Alias
     pub fn alias(&self, name: &str) -> Expr {
         Expr::Alias(Box::new(self.clone()), name.to_owned())
@@ -451,9 +681,10 @@ pub fn and(left: &Expr, right: &Expr) -> Expr {
     }
 }
 
-/// Create a column expression based on a column name
+/// Create a column expression based on a column name. The name is not split on dots,
+/// use [Column::new] to reference a column qualified by a relation.
 pub fn col(name: &str) -> Expr {
-    Expr::Column(name.to_owned())
+    Expr::Column(Column::from_name(name))
 }
 
 /// Create an expression to represent the min() aggregate function
@@ -579,6 +810,78 @@ unary_math_expr!(Log, ln);
 unary_math_expr!(Log2, log2);
 unary_math_expr!(Log10, log10);
 
+/// Create a convenience function representing a scalar function with a fixed number of arguments
+macro_rules! scalar_expr {
+    ($ENUM:ident, $FUNC:ident, $($ARG:ident),*) => {
+        #[allow(missing_docs)]
+        pub fn $FUNC($($ARG: Expr),*) -> Expr {
+            Expr::ScalarFunction {
+                fun: functions::BuiltinScalarFunction::$ENUM,
+                args: vec![$($ARG),*],
+            }
+        }
+    };
+}
+
+/// Create a convenience function representing a scalar function with optional arguments
+macro_rules! nary_scalar_expr {
+    ($ENUM:ident, $FUNC:ident) => {
+        #[allow(missing_docs)]
+        pub fn $FUNC(args: Vec<Expr>) -> Expr {
+            Expr::ScalarFunction {
+                fun: functions::BuiltinScalarFunction::$ENUM,
+                args,
+            }
+        }
+    };
+}
+
+// generate methods for creating the supported string expressions
+scalar_expr!(Lower, lower, string);
+scalar_expr!(Upper, upper, string);
+scalar_expr!(Trim, trim, string);
+scalar_expr!(Reverse, reverse, string);
+scalar_expr!(Initcap, initcap, string);
+scalar_expr!(Ascii, ascii, string);
+scalar_expr!(Chr, chr, code);
+scalar_expr!(MD5, md5, string);
+scalar_expr!(SHA256, sha256, string);
+scalar_expr!(OctetLength, octet_length, string);
+scalar_expr!(BitLength, bit_length, string);
+scalar_expr!(Left, left, string, n);
+scalar_expr!(Right, right, string, n);
+scalar_expr!(Repeat, repeat, string, n);
+scalar_expr!(Strpos, strpos, string, substring);
+scalar_expr!(StartsWith, starts_with, string, prefix);
+scalar_expr!(Replace, replace, string, from, to);
+scalar_expr!(SplitPart, split_part, string, delimiter, n);
+nary_scalar_expr!(Btrim, btrim);
+nary_scalar_expr!(Ltrim, ltrim);
+nary_scalar_expr!(Rtrim, rtrim);
+nary_scalar_expr!(Substr, substr);
+nary_scalar_expr!(Lpad, lpad);
+nary_scalar_expr!(Rpad, rpad);
+nary_scalar_expr!(ConcatWithSeparator, concat_ws);
+nary_scalar_expr!(RegexpMatch, regexp_match);
+nary_scalar_expr!(RegexpReplace, regexp_replace);
+
+// generate methods for creating the supported date/time expressions
+scalar_expr!(DateTrunc, date_trunc, granularity, timestamp);
+scalar_expr!(DatePart, date_part, field, timestamp);
+nary_scalar_expr!(ToChar, to_char);
+
+// generate methods for creating the supported conditional expressions
+nary_scalar_expr!(Coalesce, coalesce);
+scalar_expr!(NullIf, nullif, value, other);
+
+/// returns the current timestamp, which is the same for all the rows of a query
+pub fn now() -> Expr {
+    Expr::ScalarFunction {
+        fun: functions::BuiltinScalarFunction::Now,
+        args: vec![],
+    }
+}
+
 /// returns the length of a string in bytes
 pub fn length(e: Expr) -> Expr {
     Expr::ScalarFunction {
@@ -595,6 +898,15 @@ pub fn concat(args: Vec<Expr>) -> Expr {
     }
 }
 
+/// returns the start of the tumbling window of length `interval` that contains the
+/// timestamp `expr`, which groups the rows of streaming aggregations by window
+pub fn tumble(expr: Expr, interval: Expr) -> Expr {
+    Expr::ScalarFunction {
+        fun: functions::BuiltinScalarFunction::Tumble,
+        args: vec![expr, interval],
+    }
+}
+
 /// returns an array of fixed size with each argument on it.
 pub fn array(args: Vec<Expr>) -> Expr {
     Expr::ScalarFunction {
@@ -603,19 +915,52 @@ pub fn array(args: Vec<Expr>) -> Expr {
     }
 }
 
+/// returns the number of elements of a list
+pub fn array_length(array: Expr) -> Expr {
+    Expr::ScalarFunction {
+        fun: functions::BuiltinScalarFunction::ArrayLength,
+        args: vec![array],
+    }
+}
+
+/// returns whether a list contains a value
+pub fn array_contains(array: Expr, value: Expr) -> Expr {
+    Expr::ScalarFunction {
+        fun: functions::BuiltinScalarFunction::ArrayContains,
+        args: vec![array, value],
+    }
+}
+
 /// Creates a new UDF with a specific signature and specific return type.
 /// This is a helper function to create a new UDF.
 /// The function `create_udf` returns a subset of all possible `ScalarFunction`:
 /// * the UDF has a fixed return type
 /// * the UDF has a fixed signature (e.g. [f64, f64])
+///
+/// The `volatility` of the UDF tells the optimizer whether its calls may be
+/// evaluated when the plan is optimized or shared between expressions.
 pub fn create_udf(
     name: &str,
     input_types: Vec<DataType>,
     return_type: Arc<DataType>,
+    volatility: Volatility,
     fun: ScalarFunctionImplementation,
 ) -> ScalarUDF {
     let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(return_type.clone()));
-    ScalarUDF::new(name, &Signature::Exact(input_types), &return_type, &fun)
+    ScalarUDF::new(
+        name,
+        &Signature::Exact(input_types),
+        volatility,
+        &return_type,
+        &fun,
+    )
+}
+
+/// Creates a new UDTF, which creates the table of each of its calls from their
+/// literal arguments.
+/// This is a helper function to create a new UDTF.
+pub fn create_udtf(name: &str, fun: TableFunctionImplementation) -> TableUDF {
+    TableUDF::new(name, &fun)
 }
 
 /// Creates a new UDAF with a specific signature, state type and return type.
@@ -656,15 +1001,40 @@ impl fmt::Debug for Expr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Expr::Alias(expr, alias) => write!(f, "{:?} AS {}", expr, alias),
-            Expr::Column(name) => write!(f, "#{}", name),
+            Expr::Column(c) => write!(f, "#{}", c),
             Expr::ScalarVariable(var_names) => write!(f, "{}", var_names.join(".")),
             Expr::Literal(v) => write!(f, "{:?}", v),
             Expr::Cast { expr, data_type } => {
                 write!(f, "CAST({:?} AS {:?})", expr, data_type)
             }
+            Expr::GetIndexedField { expr, key } => write!(f, "{:?}[{}]", expr, key),
             Expr::Not(expr) => write!(f, "NOT {:?}", expr),
             Expr::IsNull(expr) => write!(f, "{:?} IS NULL", expr),
             Expr::IsNotNull(expr) => write!(f, "{:?} IS NOT NULL", expr),
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                if *negated {
+                    write!(f, "{:?} NOT BETWEEN {:?} AND {:?}", expr, low, high)
+                } else {
+                    write!(f, "{:?} BETWEEN {:?} AND {:?}", expr, low, high)
+                }
+            }
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let list = list.iter().map(|e| format!("{:?}", e)).collect::<Vec<_>>();
+                if *negated {
+                    write!(f, "{:?} NOT IN ({})", expr, list.join(", "))
+                } else {
+                    write!(f, "{:?} IN ({})", expr, list.join(", "))
+                }
+            }
             Expr::BinaryExpr { left, op, right } => {
                 write!(f, "{:?} {:?} {:?}", left, op, right)
             }
@@ -701,6 +1071,7 @@ impl fmt::Debug for Expr {
             }
             Expr::Wildcard => write!(f, "*"),
             Expr::Nested(expr) => write!(f, "({:?})", expr),
+            Expr::Placeholder { id, .. } => write!(f, "{}", id),
         }
     }
 }
@@ -719,7 +1090,7 @@ pub trait UserDefinedLogicalNode: Debug {
     fn inputs(&self) -> Vec<&LogicalPlan>;
 
     /// Return the output schema of this logical plan node
-    fn schema(&self) -> &SchemaRef;
+    fn schema(&self) -> &DFSchemaRef;
 
     /// returns all expressions in the current logical plan node. This
     /// should not include expressions of any inputs (aka
@@ -772,6 +1143,47 @@ pub enum TableSource {
     FromProvider(Arc<dyn TableProvider + Send + Sync>),
 }
 
+/// The type of a join, which determines the rows that it produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinType {
+    /// The pairs of a left row and a right row that match
+    Inner,
+    /// The pairs of matching rows, and the left rows without a match, with nulls for
+    /// the columns of the right input
+    Left,
+    /// The pairs of matching rows, and the right rows without a match, with nulls for
+    /// the columns of the left input
+    Right,
+    /// The pairs of matching rows, and the left and right rows without a match
+    Full,
+    /// The left rows that match at least one right row, once each, with the columns
+    /// of the left input only
+    Semi,
+    /// The left rows that match no right row, with the columns of the left input only
+    Anti,
+}
+
+/// How the keys of a join are given, which determines whether the right keys are
+/// merged into the left keys in its output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinConstraint {
+    /// `JOIN ... ON l.a = r.b`: the output has the columns of both inputs
+    On,
+    /// `JOIN ... USING (a)`: each right key is merged into the left key of the same
+    /// name, so that the output has a single column for both
+    Using,
+}
+
+/// How a [LogicalPlan::Sample] chooses the rows that it keeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleMethod {
+    /// Each row is kept independently of the others
+    Bernoulli,
+    /// Whole blocks of rows, such as the row groups of a Parquet file or the batches
+    /// of other inputs, are kept or skipped, which is cheaper but less uniform
+    System,
+}
+
 /// A LogicalPlan represents the different types of relational
 /// operators (such as Projection, Filter, etc) and can be created by
 /// the SQL query planner and the DataFrame API.
@@ -790,7 +1202,7 @@ pub enum LogicalPlan {
         /// The incoming logical plan
         input: Arc<LogicalPlan>,
         /// The schema description of the output
-        schema: SchemaRef,
+        schema: DFSchemaRef,
     },
     /// Filters rows from its input that do not match an
     /// expression (essentially a WHERE clause with a predicate
@@ -816,7 +1228,7 @@ pub enum LogicalPlan {
         /// Aggregate expressions
         aggr_expr: Vec<Expr>,
         /// The schema description of the aggregate output
-        schema: SchemaRef,
+        schema: DFSchemaRef,
     },
     /// Sorts its input according to a list of sort expressions.
     Sort {
@@ -825,6 +1237,27 @@ pub enum LogicalPlan {
         /// The incoming logical plan
         input: Arc<LogicalPlan>,
     },
+    /// Joins two inputs on the equality of pairs of their columns
+    /// (essentially a `JOIN ... ON l.a = r.b`).
+    ///
+    /// The output contains the columns of the left input followed by those of the
+    /// right input, except for the right keys of a `USING` join, which are merged into
+    /// the left keys, see [LogicalPlanBuilder::join_using].
+    Join {
+        /// The left input
+        left: Arc<LogicalPlan>,
+        /// The right input
+        right: Arc<LogicalPlan>,
+        /// The pairs of a column of the left input and a column of the right input
+        /// that are equal in the rows that the join matches
+        on: Vec<(Column, Column)>,
+        /// The type of the join
+        join_type: JoinType,
+        /// How the keys of the join are given
+        join_constraint: JoinConstraint,
+        /// The schema description of the output
+        schema: DFSchemaRef,
+    },
     /// Produces rows from a table provider by reference or from the context
     TableScan {
         /// The name of the schema
@@ -835,8 +1268,11 @@ pub enum LogicalPlan {
         table_schema: SchemaRef,
         /// Optional column indices to use as a projection
         projection: Option<Vec<usize>>,
-        /// The schema description of the output
-        projected_schema: SchemaRef,
+        /// The schema description of the output, qualified by the alias of the
+        /// table, or by its name when it has no alias
+        projected_schema: DFSchemaRef,
+        /// An optional alias for the table, e.g. `o` in `FROM orders AS o`
+        alias: Option<String>,
     },
     /// Produces rows that come from a `Vec` of in memory `RecordBatch`es
     InMemoryScan {
@@ -847,7 +1283,7 @@ pub enum LogicalPlan {
         /// Optional column indices to use as a projection
         projection: Option<Vec<usize>>,
         /// The schema description of the output
-        projected_schema: SchemaRef,
+        projected_schema: DFSchemaRef,
     },
     /// Produces rows by scanning Parquet file(s)
     ParquetScan {
@@ -858,7 +1294,7 @@ pub enum LogicalPlan {
         /// Optional column indices to use as a projection
         projection: Option<Vec<usize>>,
         /// The schema description of the output
-        projected_schema: SchemaRef,
+        projected_schema: DFSchemaRef,
     },
     /// Produces rows by scanning a CSV file(s)
     CsvScan {
@@ -873,12 +1309,12 @@ pub enum LogicalPlan {
         /// Optional column indices to use as a projection
         projection: Option<Vec<usize>>,
         /// The schema description of the output
-        projected_schema: SchemaRef,
+        projected_schema: DFSchemaRef,
     },
     /// Produces no rows: An empty relation with an empty schema
     EmptyRelation {
         /// The schema description of the output
-        schema: SchemaRef,
+        schema: DFSchemaRef,
     },
     /// Produces the first `n` tuples from its input and discards the rest.
     Limit {
@@ -887,10 +1323,33 @@ pub enum LogicalPlan {
         /// The logical plan
         input: Arc<LogicalPlan>,
     },
+    /// Produces a row for each element of the lists of a column of its input, which
+    /// repeats the other columns of the row of the list. Rows whose list is empty or
+    /// null produce no rows.
+    Unnest {
+        /// The input plan
+        input: Arc<LogicalPlan>,
+        /// The list column, whose values are replaced by the elements of the lists
+        column: Column,
+        /// The schema description of the output
+        schema: DFSchemaRef,
+    },
+    /// Produces a random sample of the rows of its input. The same seed produces the
+    /// same sample of the same input.
+    Sample {
+        /// How the rows are chosen
+        method: SampleMethod,
+        /// The expected fraction of the rows that are kept, between 0 and 1
+        fraction: f64,
+        /// The seed of the random choices
+        seed: u64,
+        /// The input plan
+        input: Arc<LogicalPlan>,
+    },
     /// Creates an external table.
     CreateExternalTable {
         /// The table schema
-        schema: SchemaRef,
+        schema: DFSchemaRef,
         /// The table name
         name: String,
         /// The physical location
@@ -910,7 +1369,17 @@ pub enum LogicalPlan {
         /// Represent the various stages plans have gone through
         stringified_plans: Vec<StringifiedPlan>,
         /// The output schema of the explain (2 columns of text)
-        schema: SchemaRef,
+        schema: DFSchemaRef,
+    },
+    /// Runs the input plan and produces a relation with its physical
+    /// plan, annotated with the metrics recorded while it ran
+    Analyze {
+        /// Should extra (detailed) metrics be included?
+        verbose: bool,
+        /// The logical plan that is being EXPLAIN ANALYZE'd
+        input: Arc<LogicalPlan>,
+        /// The output schema of the explain (2 columns of text)
+        schema: DFSchemaRef,
     },
     /// Extension operator defined outside of DataFusion
     Extension {
@@ -921,7 +1390,7 @@ pub enum LogicalPlan {
 
 impl LogicalPlan {
     /// Get a reference to the logical plan's schema
-    pub fn schema(&self) -> &SchemaRef {
+    pub fn schema(&self) -> &DFSchemaRef {
         match self {
             LogicalPlan::EmptyRelation { schema } => &schema,
             LogicalPlan::InMemoryScan {
@@ -940,9 +1409,13 @@ impl LogicalPlan {
             LogicalPlan::Filter { input, .. } => input.schema(),
             LogicalPlan::Aggregate { schema, .. } => &schema,
             LogicalPlan::Sort { input, .. } => input.schema(),
+            LogicalPlan::Join { schema, .. } => &schema,
             LogicalPlan::Limit { input, .. } => input.schema(),
+            LogicalPlan::Unnest { schema, .. } => &schema,
+            LogicalPlan::Sample { input, .. } => input.schema(),
             LogicalPlan::CreateExternalTable { schema, .. } => &schema,
             LogicalPlan::Explain { schema, .. } => &schema,
+            LogicalPlan::Analyze { schema, .. } => &schema,
             LogicalPlan::Extension { node } => &node.schema(),
         }
     }
@@ -969,15 +1442,18 @@ impl LogicalPlan {
             LogicalPlan::TableScan {
                 ref source,
                 ref projection,
+                ref alias,
                 ..
-            } => match source {
-                TableSource::FromContext(table_name) => {
-                    write!(f, "TableScan: {} projection={:?}", table_name, projection)
+            } => {
+                write!(f, "TableScan: ")?;
+                if let TableSource::FromContext(table_name) = source {
+                    write!(f, "{} ", table_name)?;
                 }
-                TableSource::FromProvider(_) => {
-                    write!(f, "TableScan: projection={:?}", projection)
+                if let Some(alias) = alias {
+                    write!(f, "AS {} ", alias)?;
                 }
-            },
+                write!(f, "projection={:?}", projection)
+            }
             LogicalPlan::InMemoryScan { ref projection, .. } => {
                 write!(f, "InMemoryScan: projection={:?}", projection)
             }
@@ -1040,12 +1516,59 @@ impl LogicalPlan {
                 }
                 input.fmt_with_indent(f, indent + 1)
             }
+            LogicalPlan::Join {
+                ref left,
+                ref right,
+                ref on,
+                ref join_type,
+                ref join_constraint,
+                ..
+            } => {
+                let on = on
+                    .iter()
+                    .map(|(l, r)| format!("{} = {}", l, r))
+                    .collect::<Vec<_>>();
+                let constraint = match join_constraint {
+                    JoinConstraint::On => "on",
+                    JoinConstraint::Using => "using",
+                };
+                write!(
+                    f,
+                    "Join: type={:?}, {}=[{}]",
+                    join_type,
+                    constraint,
+                    on.join(", ")
+                )?;
+                left.fmt_with_indent(f, indent + 1)?;
+                right.fmt_with_indent(f, indent + 1)
+            }
             LogicalPlan::Limit {
                 ref input, ref n, ..
             } => {
                 write!(f, "Limit: {}", n)?;
                 input.fmt_with_indent(f, indent + 1)
             }
+            LogicalPlan::Unnest {
+                ref input,
+                ref column,
+                ..
+            } => {
+                write!(f, "Unnest: #{}", column)?;
+                input.fmt_with_indent(f, indent + 1)
+            }
+            LogicalPlan::Sample {
+                ref input,
+                method,
+                fraction,
+                seed,
+            } => {
+                write!(
+                    f,
+                    "Sample: method={:?}, fraction={}, seed={}",
+                    method, fraction, seed
+                )?;
+                input.fmt_with_indent(f, indent + 1)
+            }
             LogicalPlan::CreateExternalTable { ref name, .. } => {
                 write!(f, "CreateExternalTable: {:?}", name)
             }
@@ -1053,6 +1576,12 @@ impl LogicalPlan {
                 write!(f, "Explain")?;
                 plan.fmt_with_indent(f, indent + 1)
             }
+            LogicalPlan::Analyze {
+                ref input, verbose, ..
+            } => {
+                write!(f, "Analyze: verbose={}", verbose)?;
+                input.fmt_with_indent(f, indent + 1)
+            }
             LogicalPlan::Extension { ref node } => {
                 node.fmt_for_explain(f)?;
                 node.inputs()
@@ -1080,6 +1609,9 @@ pub trait FunctionRegistry {
 
     /// Returns a reference to the udaf named `name`.
     fn udaf(&self, name: &str) -> Result<&AggregateUDF>;
+
+    /// Returns a reference to the udtf named `name`.
+    fn udtf(&self, name: &str) -> Result<&TableUDF>;
 }
 
 /// Builder for logical plans
@@ -1096,7 +1628,7 @@ impl LogicalPlanBuilder {
     /// Create an empty relation
     pub fn empty() -> Self {
         Self::from(&LogicalPlan::EmptyRelation {
-            schema: SchemaRef::new(Schema::empty()),
+            schema: DFSchemaRef::new(DFSchema::empty()),
         })
     }
 
@@ -1116,15 +1648,12 @@ impl LogicalPlanBuilder {
                 .to_owned(),
         };
 
-        let projected_schema = SchemaRef::new(
-            projection
-                .clone()
-                .map(|p| {
-                    Schema::new(p.iter().map(|i| schema.field(*i).clone()).collect())
-                })
-                .or(Some(schema.clone()))
-                .unwrap(),
-        );
+        let projected_schema = projection
+            .clone()
+            .map(|p| Schema::new(p.iter().map(|i| schema.field(*i).clone()).collect()))
+            .or(Some(schema.clone()))
+            .unwrap()
+            .to_dfschema_ref()?;
 
         Ok(Self::from(&LogicalPlan::CsvScan {
             path: path.to_owned(),
@@ -1144,8 +1673,9 @@ impl LogicalPlanBuilder {
         let projected_schema = projection
             .clone()
             .map(|p| Schema::new(p.iter().map(|i| schema.field(*i).clone()).collect()));
-        let projected_schema =
-            projected_schema.map_or(schema.clone(), |s| SchemaRef::new(s));
+        let projected_schema = projected_schema
+            .map_or(schema.clone(), |s| SchemaRef::new(s))
+            .to_dfschema_ref()?;
 
         Ok(Self::from(&LogicalPlan::ParquetScan {
             path: path.to_owned(),
@@ -1161,6 +1691,47 @@ impl LogicalPlanBuilder {
         table_name: &str,
         table_schema: &Schema,
         projection: Option<Vec<usize>>,
+    ) -> Result<Self> {
+        Self::scan_with_alias(schema_name, table_name, None, table_schema, projection)
+    }
+
+    /// Scan a table provider that is not registered in a context, e.g. the table of a
+    /// table function call, whose columns are qualified by `alias`, if any
+    pub fn scan_provider(
+        provider: Arc<dyn TableProvider + Send + Sync>,
+        alias: Option<&str>,
+        projection: Option<Vec<usize>>,
+    ) -> Result<Self> {
+        let table_schema = provider.schema();
+        let projected_schema = match &projection {
+            Some(p) => {
+                Schema::new(p.iter().map(|i| table_schema.field(*i).clone()).collect())
+            }
+            None => table_schema.as_ref().clone(),
+        };
+        let projected_schema = match alias {
+            Some(alias) => DFSchema::try_from_qualified_schema(alias, &projected_schema)?,
+            None => projected_schema.to_dfschema()?,
+        };
+
+        Ok(Self::from(&LogicalPlan::TableScan {
+            schema_name: "".to_owned(),
+            source: TableSource::FromProvider(provider),
+            table_schema,
+            projected_schema: DFSchemaRef::new(projected_schema),
+            projection,
+            alias: alias.map(|a| a.to_owned()),
+        }))
+    }
+
+    /// Scan a data source whose columns are qualified by `alias` instead of the
+    /// table name, e.g. for `FROM orders AS o`
+    pub fn scan_with_alias(
+        schema_name: &str,
+        table_name: &str,
+        alias: Option<&str>,
+        table_schema: &Schema,
+        projection: Option<Vec<usize>>,
     ) -> Result<Self> {
         let table_schema = SchemaRef::new(table_schema.clone());
         let projected_schema = projection.clone().map(|p| {
@@ -1168,13 +1739,18 @@ impl LogicalPlanBuilder {
         });
         let projected_schema =
             projected_schema.map_or(table_schema.clone(), |s| SchemaRef::new(s));
+        let projected_schema = DFSchema::try_from_qualified_schema(
+            alias.unwrap_or(table_name),
+            &projected_schema,
+        )?;
 
         Ok(Self::from(&LogicalPlan::TableScan {
             schema_name: schema_name.to_owned(),
             source: TableSource::FromContext(table_name.to_owned()),
             table_schema,
-            projected_schema,
+            projected_schema: DFSchemaRef::new(projected_schema),
             projection,
+            alias: alias.map(|a| a.to_owned()),
         }))
     }
 
@@ -1197,12 +1773,12 @@ impl LogicalPlanBuilder {
 
         validate_unique_names("Projections", &projected_expr, input_schema)?;
 
-        let schema = Schema::new(exprlist_to_fields(&projected_expr, input_schema)?);
+        let schema = DFSchema::new(exprlist_to_fields(&projected_expr, input_schema)?)?;
 
         Ok(Self::from(&LogicalPlan::Projection {
             expr: projected_expr,
             input: Arc::new(self.plan.clone()),
-            schema: SchemaRef::new(schema),
+            schema: DFSchemaRef::new(schema),
         }))
     }
 
@@ -1222,6 +1798,23 @@ impl LogicalPlanBuilder {
         }))
     }
 
+    /// Apply a random sample that keeps the expected `fraction` of the rows, which
+    /// must be between 0 and 1, chosen by `method` from the random choices of `seed`
+    pub fn sample(&self, method: SampleMethod, fraction: f64, seed: u64) -> Result<Self> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(DataFusionError::Plan(format!(
+                "The fraction of a sample must be between 0 and 1, but is {}",
+                fraction
+            )));
+        }
+        Ok(Self::from(&LogicalPlan::Sample {
+            method,
+            fraction,
+            seed,
+            input: Arc::new(self.plan.clone()),
+        }))
+    }
+
     /// Apply a sort
     pub fn sort(&self, expr: Vec<Expr>) -> Result<Self> {
         Ok(Self::from(&LogicalPlan::Sort {
@@ -1237,13 +1830,163 @@ impl LogicalPlanBuilder {
 
         validate_unique_names("Aggregations", &all_expr, self.plan.schema())?;
 
-        let aggr_schema = Schema::new(exprlist_to_fields(&all_expr, self.plan.schema())?);
+        let aggr_schema =
+            DFSchema::new(exprlist_to_fields(&all_expr, self.plan.schema())?)?;
 
         Ok(Self::from(&LogicalPlan::Aggregate {
             input: Arc::new(self.plan.clone()),
             group_expr,
             aggr_expr,
-            schema: SchemaRef::new(aggr_schema),
+            schema: DFSchemaRef::new(aggr_schema),
+        }))
+    }
+
+    /// Apply a join of the plan with `right`, matching the rows where each of
+    /// `left_keys`, columns of the plan, equals the column of `right_keys` at the same
+    /// position, a column of `right`, as with `JOIN ... ON`.
+    ///
+    /// The output has the columns of the plan followed by those of `right`. Semi and
+    /// anti joins only produce the columns of the plan. The keys of a pair with
+    /// different types are coerced to a common type, which is the type of their
+    /// columns in the output.
+    ///
+    /// # Errors
+    /// This function errors under any of the following conditions:
+    /// * A key is not a column of its input, or the keys are empty
+    /// * The keys of a pair have types that can't be coerced to a common type
+    /// * Both inputs have a column with the same qualified name, which is not
+    ///   supported yet
+    pub fn join(
+        &self,
+        right: &LogicalPlan,
+        join_type: JoinType,
+        left_keys: Vec<Column>,
+        right_keys: Vec<Column>,
+    ) -> Result<Self> {
+        self.join_detailed(right, join_type, left_keys, right_keys, JoinConstraint::On)
+    }
+
+    /// Apply a join of the plan with `right` on the equality of their columns
+    /// `using_keys`, as with `JOIN ... USING`: each right key is merged into the left
+    /// key of the same name, so that the output has a single column for both, which
+    /// holds the key of the left row, or of the right row when there is no left row.
+    ///
+    /// # Errors
+    /// See [LogicalPlanBuilder::join], except that the merged keys may have the same
+    /// qualified name.
+    pub fn join_using(
+        &self,
+        right: &LogicalPlan,
+        join_type: JoinType,
+        using_keys: Vec<Column>,
+    ) -> Result<Self> {
+        self.join_detailed(
+            right,
+            join_type,
+            using_keys.clone(),
+            using_keys,
+            JoinConstraint::Using,
+        )
+    }
+
+    /// Apply a join of the plan with `right`, whose keys are merged as given by
+    /// `join_constraint`, see [LogicalPlanBuilder::join] and
+    /// [LogicalPlanBuilder::join_using]. With [JoinConstraint::Using], only the pairs
+    /// of keys with the same name are merged.
+    pub fn join_detailed(
+        &self,
+        right: &LogicalPlan,
+        join_type: JoinType,
+        left_keys: Vec<Column>,
+        right_keys: Vec<Column>,
+        join_constraint: JoinConstraint,
+    ) -> Result<Self> {
+        if left_keys.is_empty() || left_keys.len() != right_keys.len() {
+            return Err(DataFusionError::Plan(format!(
+                "Joins require as many left keys as right keys, and at least one, \
+                 but got {} and {}",
+                left_keys.len(),
+                right_keys.len()
+            )));
+        }
+        let left_schema = self.plan.schema();
+        let right_schema = right.schema();
+        let on = left_keys
+            .iter()
+            .zip(&right_keys)
+            .map(|(l, r)| {
+                let l = left_schema.field_from_column(l)?;
+                let r = right_schema.field_from_column(r)?;
+                join_key_type(l.data_type(), r.data_type()).ok_or_else(|| {
+                    DataFusionError::Plan(format!(
+                        "Join columns {} and {} have types {:?} and {:?}, which \
+                         can't be coerced to a common type",
+                        l.qualified_name(),
+                        r.qualified_name(),
+                        l.data_type(),
+                        r.data_type()
+                    ))
+                })?;
+                Ok((l.qualified_column(), r.qualified_column()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let schema = build_join_schema(
+            left_schema,
+            right_schema,
+            &on,
+            &join_type,
+            &join_constraint,
+        )?;
+
+        Ok(Self::from(&LogicalPlan::Join {
+            left: Arc::new(self.plan.clone()),
+            right: Arc::new(right.clone()),
+            on,
+            join_type,
+            join_constraint,
+            schema: DFSchemaRef::new(schema),
+        }))
+    }
+
+    /// Unnest the list column `column`: each element of its lists becomes a row, which
+    /// repeats the values of the other columns of the row of the list.
+    ///
+    /// # Errors
+    /// This function errors when `column` is not a `List`, `LargeList` or
+    /// `FixedSizeList` column of the plan.
+    pub fn unnest(&self, column: Column) -> Result<Self> {
+        let input_schema = self.plan.schema();
+        let index = input_schema.index_of_column(&column)?;
+        let fields = input_schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                if i != index {
+                    return Ok(field.clone());
+                }
+                match field.data_type() {
+                    DataType::List(item)
+                    | DataType::LargeList(item)
+                    | DataType::FixedSizeList(item, _) => Ok(DFField::new(
+                        field.qualifier().map(|q| q.as_str()),
+                        field.name(),
+                        item.data_type().clone(),
+                        item.is_nullable(),
+                    )),
+                    other => Err(DataFusionError::Plan(format!(
+                        "Only list columns can be unnested, but {} is of type {:?}",
+                        field.qualified_name(),
+                        other
+                    ))),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self::from(&LogicalPlan::Unnest {
+            input: Arc::new(self.plan.clone()),
+            column: input_schema.field(index).qualified_column(),
+            schema: DFSchemaRef::new(DFSchema::new(fields)?),
         }))
     }
 
@@ -1254,7 +1997,7 @@ impl LogicalPlanBuilder {
             format!("{:#?}", self.plan.clone()),
         )];
 
-        let schema = LogicalPlan::explain_schema();
+        let schema = LogicalPlan::explain_schema().to_dfschema_ref()?;
 
         Ok(Self::from(&LogicalPlan::Explain {
             verbose,
@@ -1264,17 +2007,132 @@ impl LogicalPlanBuilder {
         }))
     }
 
+    /// Create a plan that runs the plan and reports the metrics recorded by each
+    /// of the operators of its physical plan
+    pub fn explain_analyze(&self, verbose: bool) -> Result<Self> {
+        Ok(Self::from(&LogicalPlan::Analyze {
+            verbose,
+            input: Arc::new(self.plan.clone()),
+            schema: LogicalPlan::explain_schema().to_dfschema_ref()?,
+        }))
+    }
+
     /// Build the plan
     pub fn build(&self) -> Result<LogicalPlan> {
         Ok(self.plan.clone())
     }
 }
 
+/// The type that a pair of join keys of types `left` and `right` are coerced to,
+/// if any
+pub(crate) fn join_key_type(left: &DataType, right: &DataType) -> Option<DataType> {
+    // the keys are compared in order by the sort-merge join
+    order_coercion(left, right)
+}
+
+/// The pairs of keys of a join that are merged into a single column of its output,
+/// see [LogicalPlanBuilder::join_detailed]
+pub(crate) fn merged_join_keys<'a>(
+    on: &'a [(Column, Column)],
+    join_type: &JoinType,
+    join_constraint: &JoinConstraint,
+) -> Vec<&'a (Column, Column)> {
+    let with_right = !matches!(join_type, JoinType::Semi | JoinType::Anti);
+    on.iter()
+        .filter(|(l, r)| {
+            with_right && *join_constraint == JoinConstraint::Using && l.name == r.name
+        })
+        .collect()
+}
+
+/// The schema of the output of a join, see [LogicalPlanBuilder::join_detailed]. The
+/// columns of a side of the join that may have no matching row become nullable, and
+/// the keys have the type that they are coerced to.
+fn build_join_schema(
+    left: &DFSchema,
+    right: &DFSchema,
+    on: &[(Column, Column)],
+    join_type: &JoinType,
+    join_constraint: &JoinConstraint,
+) -> Result<DFSchema> {
+    let left_nullable = matches!(join_type, JoinType::Right | JoinType::Full);
+    let right_nullable = matches!(join_type, JoinType::Left | JoinType::Full);
+    let with_right = !matches!(join_type, JoinType::Semi | JoinType::Anti);
+    let merged = merged_join_keys(on, join_type, join_constraint);
+    // the coerced type of each key
+    let mut key_types = HashMap::new();
+    for (l, r) in on {
+        let l_type = left.field_from_column(l)?.data_type();
+        let r_type = right.field_from_column(r)?.data_type();
+        if let Some(key_type) = join_key_type(l_type, r_type) {
+            key_types.insert(l, key_type.clone());
+            key_types.insert(r, key_type);
+        }
+    }
+    let data_type = |field: &DFField| {
+        key_types
+            .get(&field.qualified_column())
+            .unwrap_or_else(|| field.data_type())
+            .clone()
+    };
+
+    let mut fields = vec![];
+    for field in left.fields() {
+        let column = field.qualified_column();
+        let nullable = match merged.iter().find(|(l, _)| *l == column) {
+            Some((_, r)) => {
+                let right_nullable = right.field_from_column(r)?.is_nullable();
+                match join_type {
+                    JoinType::Right => right_nullable,
+                    JoinType::Full => field.is_nullable() || right_nullable,
+                    _ => field.is_nullable(),
+                }
+            }
+            None => field.is_nullable() || left_nullable,
+        };
+        fields.push(DFField::new(
+            field.qualifier().map(|q| q.as_str()),
+            field.name(),
+            data_type(field),
+            nullable,
+        ));
+    }
+    if with_right {
+        for field in right.fields() {
+            let column = field.qualified_column();
+            if merged.iter().any(|(_, r)| *r == column) {
+                continue;
+            }
+            fields.push(DFField::new(
+                field.qualifier().map(|q| q.as_str()),
+                field.name(),
+                data_type(field),
+                field.is_nullable() || right_nullable,
+            ));
+        }
+    }
+
+    // the columns with the same name are told apart by their qualifiers
+    for (i, field) in fields.iter().enumerate() {
+        if fields[..i]
+            .iter()
+            .any(|f| f.qualified_name() == field.qualified_name())
+        {
+            return Err(DataFusionError::Plan(format!(
+                "Joins whose inputs both have a column named '{}' are not \
+                 supported, unless they are merged keys of a USING join",
+                field.qualified_name()
+            )));
+        }
+    }
+    DFSchema::new(fields)
+}
+
 /// Errors if one or more expressions have equal names.
 fn validate_unique_names(
     node_name: &str,
     expressions: &[Expr],
-    input_schema: &Schema,
+    input_schema: &DFSchema,
 ) -> Result<()> {
     let mut unique_names = HashMap::new();
     expressions.iter().enumerate().map(|(position, expr)| {
@@ -1448,6 +2306,143 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn plan_builder_join() -> Result<()> {
+        let departments = LogicalPlanBuilder::scan(
+            "default",
+            "departments",
+            &Schema::new(vec![
+                Field::new("state", DataType::Utf8, false),
+                Field::new("manager", DataType::Utf8, false),
+            ]),
+            None,
+        )?
+        .build()?;
+        let plan = LogicalPlanBuilder::scan(
+            "default",
+            "employee.csv",
+            &employee_schema(),
+            Some(vec![0, 3]),
+        )?
+        .join(
+            &departments,
+            JoinType::Left,
+            vec![Column::from_name("state")],
+            vec![Column::new(Some("departments"), "state")],
+        )?
+        .build()?;
+
+        let expected = "Join: type=Left, on=[employee.csv.state = departments.state]\
+        \n  TableScan: employee.csv projection=Some([0, 3])\
+        \n  TableScan: departments projection=None";
+        assert_eq!(expected, format!("{:?}", plan));
+
+        // the output has the keys of both inputs, and the right columns become
+        // nullable
+        let fields = |plan: &LogicalPlan| {
+            plan.schema()
+                .fields()
+                .iter()
+                .map(|f| (f.qualified_name(), f.is_nullable()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            fields(&plan),
+            vec![
+                ("employee.csv.id".to_string(), false),
+                ("employee.csv.state".to_string(), false),
+                ("departments.state".to_string(), true),
+                ("departments.manager".to_string(), true),
+            ]
+        );
+
+        // the right key of a USING join is merged into the left key
+        let plan = LogicalPlanBuilder::scan(
+            "default",
+            "employee.csv",
+            &employee_schema(),
+            Some(vec![0, 3]),
+        )?
+        .join_using(
+            &departments,
+            JoinType::Left,
+            vec![Column::from_name("state")],
+        )?
+        .build()?;
+        let expected = "Join: type=Left, using=[employee.csv.state = departments.state]\
+        \n  TableScan: employee.csv projection=Some([0, 3])\
+        \n  TableScan: departments projection=None";
+        assert_eq!(expected, format!("{:?}", plan));
+        assert_eq!(
+            fields(&plan),
+            vec![
+                ("employee.csv.id".to_string(), false),
+                ("employee.csv.state".to_string(), false),
+                ("departments.manager".to_string(), true),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_keys_of_different_types() -> Result<()> {
+        let plan = LogicalPlanBuilder::scan(
+            "default",
+            "employee.csv",
+            &employee_schema(),
+            Some(vec![0, 3]),
+        )?
+        .build()?;
+        let err = LogicalPlanBuilder::scan(
+            "default",
+            "manager.csv",
+            &employee_schema(),
+            Some(vec![0, 3]),
+        )?
+        .join(
+            &plan,
+            JoinType::Inner,
+            vec![Column::from_name("id")],
+            vec![Column::from_name("state")],
+        )
+        .unwrap_err()
+        .to_string();
+        assert_eq!(
+            err,
+            "Error during planning: Join columns manager.csv.id and employee.csv.state \
+             have types Int32 and Utf8, which can't be coerced to a common type"
+        );
+
+        // keys of numeric types are coerced to a common type
+        let managers = LogicalPlanBuilder::scan(
+            "default",
+            "managers",
+            &Schema::new(vec![Field::new("manager_id", DataType::Int64, false)]),
+            None,
+        )?
+        .build()?;
+        let plan = LogicalPlanBuilder::from(&plan)
+            .join(
+                &managers,
+                JoinType::Inner,
+                vec![Column::from_name("id")],
+                vec![Column::from_name("manager_id")],
+            )?
+            .build()?;
+        let types = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![DataType::Int64, DataType::Utf8, DataType::Int64]
+        );
+        Ok(())
+    }
+
     #[test]
     fn projection_non_unique_names() -> Result<()> {
         let plan = LogicalPlanBuilder::scan(
@@ -52,6 +52,18 @@ pub enum Operator {
     Like,
     /// Does not match a wildcard pattern
     NotLike,
+    /// Case sensitive regular expression match, like `~`
+    RegexMatch,
+    /// Case insensitive regular expression match, like `~*`
+    RegexIMatch,
+    /// Case sensitive regular expression not match, like `!~`
+    RegexNotMatch,
+    /// Case insensitive regular expression not match, like `!~*`
+    RegexNotIMatch,
+    /// Expressions are not equal, where null is comparable to other values
+    IsDistinctFrom,
+    /// Expressions are equal, where null is comparable to other values
+    IsNotDistinctFrom,
 }
 
 impl fmt::Display for Operator {
@@ -72,6 +84,12 @@ impl fmt::Display for Operator {
             Operator::Or => "OR",
             Operator::Like => "LIKE",
             Operator::NotLike => "NOT LIKE",
+            Operator::RegexMatch => "~",
+            Operator::RegexIMatch => "~*",
+            Operator::RegexNotMatch => "!~",
+            Operator::RegexNotIMatch => "!~*",
+            Operator::IsDistinctFrom => "IS DISTINCT FROM",
+            Operator::IsNotDistinctFrom => "IS NOT DISTINCT FROM",
         };
         write!(f, "{}", display)
     }
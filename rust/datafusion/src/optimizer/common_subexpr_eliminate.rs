@@ -0,0 +1,592 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Common subexpression elimination, an optimizer rule that computes the
+//! subexpressions repeated in the expressions of a plan node only once
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::logical_plan::{Column, DFSchema, DFSchemaRef, Expr, LogicalPlan};
+use crate::optimizer::optimizer::OptimizerRule;
+use crate::optimizer::utils;
+use crate::physical_plan::functions::Volatility;
+
+/// Prefix of the names of the columns that common subexpressions are computed into
+const COMMON_EXPR_PREFIX: &str = "__common_expr_";
+
+/// Common subexpression elimination optimizer rule.
+///
+/// A subexpression that the expressions of a `Projection`, an `Aggregate` or a
+/// `Filter` compute more than once is computed once, into a column of a projection
+/// that is added below the node, and the expressions of the node are rewritten to
+/// reference that column. E.g.
+///
+/// ```text
+/// Projection: #a Multiply #b Plus Int32(1), SQRT(#a Multiply #b)
+///   TableScan: test projection=None
+/// ```
+///
+/// is optimized to
+///
+/// ```text
+/// Projection: #__common_expr_1 Plus Int32(1) AS a Multiply b Plus Int32(1), SQRT(#__common_expr_1) AS SQRT(a Multiply b)
+///   Projection: #test.a, #test.b, #a Multiply #b AS __common_expr_1
+///     TableScan: test projection=None
+/// ```
+///
+/// The rewritten node produces the same columns as the original one. Only the
+/// subexpressions that reference a column are eliminated, and never aggregates or
+/// volatile functions, which may not return the same value each time they are
+/// called.
+pub struct CommonSubexprEliminate {
+    /// The number of the last column that a common subexpression was computed into
+    next_id: usize,
+}
+
+impl OptimizerRule for CommonSubexprEliminate {
+    fn name(&self) -> &str {
+        "common_subexpr_eliminate"
+    }
+
+    fn optimize(&mut self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        match plan {
+            LogicalPlan::Projection {
+                expr,
+                input,
+                schema,
+            } => {
+                let input = self.optimize(input)?;
+                let (expr, input) = match self.eliminate(expr, &input)? {
+                    Some((new_expr, new_input)) => {
+                        (keep_names(expr, new_expr, input.schema())?, new_input)
+                    }
+                    None => (expr.clone(), input),
+                };
+                Ok(LogicalPlan::Projection {
+                    expr,
+                    input: Arc::new(input),
+                    schema: schema.clone(),
+                })
+            }
+            LogicalPlan::Aggregate {
+                group_expr,
+                aggr_expr,
+                input,
+                schema,
+            } => {
+                let input = self.optimize(input)?;
+                let expr = group_expr
+                    .iter()
+                    .chain(aggr_expr.iter())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let (mut expr, input) = match self.eliminate(&expr, &input)? {
+                    Some((new_expr, new_input)) => {
+                        (keep_names(&expr, new_expr, input.schema())?, new_input)
+                    }
+                    None => (expr, input),
+                };
+                let aggr_expr = expr.split_off(group_expr.len());
+                Ok(LogicalPlan::Aggregate {
+                    group_expr: expr,
+                    aggr_expr,
+                    input: Arc::new(input),
+                    schema: schema.clone(),
+                })
+            }
+            LogicalPlan::Filter { predicate, input } => {
+                let input = self.optimize(input)?;
+                match self.eliminate(&[predicate.clone()], &input)? {
+                    Some((mut new_expr, new_input)) => {
+                        // the columns of the common subexpressions are not part of the
+                        // output of the filter
+                        let filter = LogicalPlan::Filter {
+                            predicate: new_expr.remove(0),
+                            input: Arc::new(new_input),
+                        };
+                        Ok(LogicalPlan::Projection {
+                            expr: columns(input.schema()),
+                            input: Arc::new(filter),
+                            schema: input.schema().clone(),
+                        })
+                    }
+                    None => Ok(LogicalPlan::Filter {
+                        predicate: predicate.clone(),
+                        input: Arc::new(input),
+                    }),
+                }
+            }
+            LogicalPlan::Explain {
+                verbose,
+                plan,
+                stringified_plans,
+                schema,
+            } => utils::optimize_explain(
+                self,
+                *verbose,
+                &*plan,
+                stringified_plans,
+                &*schema,
+            ),
+            _ => self.optimize_children(plan),
+        }
+    }
+}
+
+impl CommonSubexprEliminate {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self { next_id: 0 }
+    }
+
+    /// Rewrites `expr`, which is evaluated against `input`, so that its common
+    /// subexpressions are computed once by a projection on top of `input`. Returns
+    /// the rewritten expressions and that projection, or `None` if `expr` has no
+    /// common subexpressions.
+    fn eliminate(
+        &mut self,
+        expr: &[Expr],
+        input: &LogicalPlan,
+    ) -> Result<Option<(Vec<Expr>, LogicalPlan)>> {
+        let mut counts = HashMap::new();
+        for e in expr {
+            count_sub_expressions(e, &mut counts)?;
+        }
+        let common_ids = counts
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(id, _)| id)
+            .collect::<HashSet<_>>();
+        if common_ids.is_empty() {
+            return Ok(None);
+        }
+
+        let input_schema = input.schema();
+        let mut common_exprs = CommonExprs::default();
+        let expr = expr
+            .iter()
+            .map(|e| {
+                self.replace_common_exprs(e, &common_ids, &mut common_exprs, input_schema)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut projection_expr = columns(input_schema);
+        let mut fields = input_schema.fields().clone();
+        for common_expr in common_exprs.exprs {
+            fields.push(common_expr.to_field(input_schema)?);
+            projection_expr.push(common_expr);
+        }
+        // the common subexpressions may themselves share subexpressions, which are
+        // computed by a projection below this one
+        let (projection_expr, projection_input) =
+            match self.eliminate(&projection_expr, input)? {
+                Some(eliminated) => eliminated,
+                None => (projection_expr, input.clone()),
+            };
+        let projection = LogicalPlan::Projection {
+            expr: projection_expr,
+            input: Arc::new(projection_input),
+            schema: DFSchemaRef::new(DFSchema::new(fields)?),
+        };
+        Ok(Some((expr, projection)))
+    }
+
+    /// Replaces the outermost subexpressions of `expr` whose identifiers are in
+    /// `common_ids` by references to the columns they are computed into, which are
+    /// added to `common_exprs`
+    fn replace_common_exprs(
+        &mut self,
+        expr: &Expr,
+        common_ids: &HashSet<String>,
+        common_exprs: &mut CommonExprs,
+        input_schema: &DFSchema,
+    ) -> Result<Expr> {
+        let id = expr_identifier(expr)?;
+        if is_computed(expr) && common_ids.contains(&id) {
+            let name = match common_exprs.names.get(&id) {
+                Some(name) => name.clone(),
+                None => {
+                    let name = self.next_name(input_schema);
+                    common_exprs
+                        .exprs
+                        .push(Expr::Alias(Box::new(expr.clone()), name.clone()));
+                    common_exprs.names.insert(id, name.clone());
+                    name
+                }
+            };
+            return Ok(Expr::Column(Column::from_name(&name)));
+        }
+
+        let children = utils::expr_sub_expressions(expr)?
+            .into_iter()
+            .map(|e| self.replace_common_exprs(e, common_ids, common_exprs, input_schema))
+            .collect::<Result<Vec<_>>>()?;
+        utils::rewrite_expression(expr, &children)
+    }
+
+    /// A name for the column of a common subexpression that is not the name of a
+    /// field of `schema`
+    fn next_name(&mut self, schema: &DFSchema) -> String {
+        loop {
+            self.next_id += 1;
+            let name = format!("{}{}", COMMON_EXPR_PREFIX, self.next_id);
+            if !schema.fields().iter().any(|field| field.name() == &name) {
+                return name;
+            }
+        }
+    }
+}
+
+/// The common subexpressions that were replaced by references to columns, aliased
+/// to the names of their columns, in the order they were found
+#[derive(Default)]
+struct CommonExprs {
+    exprs: Vec<Expr>,
+    /// The names of the columns by the [identifier] of their expression
+    names: HashMap<String, String>,
+}
+
+/// What [count_sub_expressions] found out about an expression
+struct ExprInfo {
+    /// The [identifier] of the expression
+    id: String,
+    /// Whether the expression references a column
+    has_column: bool,
+    /// Whether the expression contains an aggregate or a volatile function
+    excluded: bool,
+}
+
+/// Whether `expr` calls a volatile function, built-in or user defined, whose calls
+/// with the same arguments may return different values, so that they can't be shared
+fn is_volatile(expr: &Expr) -> bool {
+    match expr {
+        Expr::ScalarFunction { fun, .. } => fun.volatility() == Volatility::Volatile,
+        Expr::ScalarUDF { fun, .. } => fun.volatility == Volatility::Volatile,
+        _ => false,
+    }
+}
+
+/// Counts the subexpressions of `expr` that may be eliminated, including `expr`
+/// itself, into `counts`, by their [identifier]
+fn count_sub_expressions(
+    expr: &Expr,
+    counts: &mut HashMap<String, usize>,
+) -> Result<ExprInfo> {
+    let children = utils::expr_sub_expressions(expr)?
+        .into_iter()
+        .map(|e| count_sub_expressions(e, counts))
+        .collect::<Result<Vec<_>>>()?;
+    let ids = children.iter().map(|c| c.id.clone()).collect::<Vec<_>>();
+    let info = ExprInfo {
+        id: identifier(expr, &ids),
+        has_column: matches!(expr, Expr::Column(_))
+            || children.iter().any(|c| c.has_column),
+        excluded: matches!(
+            expr,
+            Expr::AggregateFunction { .. } | Expr::AggregateUDF { .. }
+        ) || is_volatile(expr)
+            || children.iter().any(|c| c.excluded),
+    };
+
+    if info.has_column && !info.excluded && is_computed(expr) {
+        *counts.entry(info.id.clone()).or_insert(0) += 1;
+    }
+    Ok(info)
+}
+
+/// The [identifier] of `expr`
+fn expr_identifier(expr: &Expr) -> Result<String> {
+    let children = utils::expr_sub_expressions(expr)?
+        .into_iter()
+        .map(expr_identifier)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(identifier(expr, &children))
+}
+
+/// Whether `expr` computes a value, rather than naming one or wrapping another
+/// expression
+fn is_computed(expr: &Expr) -> bool {
+    !matches!(
+        expr,
+        Expr::Column(_)
+            | Expr::ScalarVariable(_)
+            | Expr::Literal(_)
+            | Expr::Placeholder { .. }
+            | Expr::Alias(..)
+            | Expr::Nested(_)
+            | Expr::Sort { .. }
+    )
+}
+
+/// Identifies `expr`, whose direct children have the identifiers `children`, by
+/// its structure: two expressions with the same identifier compute the same value.
+/// Unlike the names of expressions, identifiers are not ambiguous about the order
+/// in which operators are applied.
+fn identifier(expr: &Expr, children: &[String]) -> String {
+    let label = match expr {
+        // parentheses do not change the value of an expression
+        Expr::Nested(_) => return children[0].clone(),
+        Expr::Column(c) => return format!("#{}", c.flat_name()),
+        Expr::ScalarVariable(var_names) => return var_names.join("."),
+        Expr::Literal(value) => return format!("{:?}", value),
+        Expr::Placeholder { id, .. } => return id.clone(),
+        Expr::Wildcard => return "*".to_owned(),
+        Expr::Alias(_, alias) => format!("AS {}", alias),
+        Expr::BinaryExpr { op, .. } => format!("{:?}", op),
+        Expr::Not(_) => "NOT".to_owned(),
+        Expr::IsNull(_) => "IS NULL".to_owned(),
+        Expr::IsNotNull(_) => "IS NOT NULL".to_owned(),
+        Expr::Between { negated, .. } => {
+            (if *negated { "NOT BETWEEN" } else { "BETWEEN" }).to_owned()
+        }
+        Expr::InList { negated, .. } => {
+            (if *negated { "NOT IN" } else { "IN" }).to_owned()
+        }
+        Expr::Cast { data_type, .. } => format!("CAST AS {:?}", data_type),
+        Expr::GetIndexedField { key, .. } => format!("[{:?}]", key),
+        Expr::Sort {
+            asc, nulls_first, ..
+        } => format!("SORT {} {}", asc, nulls_first),
+        Expr::ScalarFunction { fun, .. } => fun.to_string(),
+        Expr::ScalarUDF { fun, .. } => fun.name.clone(),
+        Expr::AggregateFunction { fun, distinct, .. } => {
+            if *distinct {
+                format!("{} DISTINCT", fun)
+            } else {
+                fun.to_string()
+            }
+        }
+        Expr::AggregateUDF { fun, .. } => fun.name.clone(),
+    };
+    format!("{}({})", label, children.join(", "))
+}
+
+/// References to all the fields of `schema`
+fn columns(schema: &DFSchema) -> Vec<Expr> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| Expr::Column(field.qualified_column()))
+        .collect()
+}
+
+/// Aliases the expressions of `new_expr` that were rewritten from the expressions
+/// of `expr` to the names of those, which are evaluated against `input_schema`, so
+/// that the rewritten node produces the same columns
+fn keep_names(
+    expr: &[Expr],
+    new_expr: Vec<Expr>,
+    input_schema: &DFSchema,
+) -> Result<Vec<Expr>> {
+    expr.iter()
+        .zip(new_expr)
+        .map(|(e, new_e)| match e {
+            // the names of aliases and columns are not changed by the rewrite
+            Expr::Alias(..) | Expr::Column(_) => Ok(new_e),
+            _ => Ok(Expr::Alias(Box::new(new_e), e.name(input_schema)?)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::{
+        abs, avg, col, create_udf, lit, sum, LogicalPlanBuilder, Operator,
+    };
+    use crate::physical_plan::functions::make_scalar_function;
+    use crate::physical_plan::udf::ScalarUDF;
+    use crate::test::*;
+    use arrow::array::ArrayRef;
+    use arrow::datatypes::DataType;
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let mut rule = CommonSubexprEliminate::new();
+        let optimized_plan = rule.optimize(plan).expect("failed to optimize plan");
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+        assert_eq!(optimized_plan.schema(), plan.schema());
+    }
+
+    #[test]
+    fn eliminate_in_projection() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let expr = col("a") * col("b") + lit(1);
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![expr.clone(), expr / lit(2), col("c")])?
+            .build()?;
+
+        let expected = "\
+        Projection: #__common_expr_1 AS a Multiply b Plus Int32(1), #__common_expr_1 Divide Int32(2) AS a Multiply b Plus Int32(1) Divide Int32(2), #c\
+        \n  Projection: #test.a, #test.b, #test.c, #a Multiply #b Plus Int32(1) AS __common_expr_1\
+        \n    TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn eliminate_nested_common_exprs() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let product = col("a") * col("b");
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![
+                product.clone(),
+                (product.clone() + lit(1)).alias("x"),
+                (product + lit(1)).alias("y"),
+            ])?
+            .build()?;
+
+        let expected = "\
+        Projection: #__common_expr_1 AS a Multiply b, #__common_expr_2 AS x, #__common_expr_2 AS y\
+        \n  Projection: #test.a, #test.b, #test.c, #__common_expr_3 AS __common_expr_1, #__common_expr_3 Plus Int32(1) AS __common_expr_2\
+        \n    Projection: #test.a, #test.b, #test.c, #a Multiply #b AS __common_expr_3\
+        \n      TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn eliminate_in_aggregate() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let product = col("a") * col("b");
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .aggregate(vec![col("c")], vec![sum(product.clone()), avg(product)])?
+            .build()?;
+
+        let expected = "\
+        Aggregate: groupBy=[[#c]], aggr=[[SUM(#__common_expr_1) AS SUM(a Multiply b), AVG(#__common_expr_1) AS AVG(a Multiply b)]]\
+        \n  Projection: #test.a, #test.b, #test.c, #a Multiply #b AS __common_expr_1\
+        \n    TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn eliminate_in_filter() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let expr = col("a") + col("b");
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .filter(expr.clone().gt(lit(1u32)).and(expr.lt(lit(10u32))))?
+            .build()?;
+
+        let expected = "\
+        Projection: #test.a, #test.b, #test.c\
+        \n  Filter: #__common_expr_1 Gt UInt32(1) And #__common_expr_1 Lt UInt32(10)\
+        \n    Projection: #test.a, #test.b, #test.c, #a Plus #b AS __common_expr_1\
+        \n      TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_distinct_exprs() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        // (a + b) * c and a + (b * c) are printed the same, but are not equal
+        let left = Expr::BinaryExpr {
+            left: Box::new(col("a") + col("b")),
+            op: Operator::Multiply,
+            right: Box::new(col("c")),
+        };
+        let right = Expr::BinaryExpr {
+            left: Box::new(col("a")),
+            op: Operator::Plus,
+            right: Box::new(col("b") * col("c")),
+        };
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![left.alias("x"), right.alias("y")])?
+            .build()?;
+
+        assert_optimized_plan_eq(&plan, &format!("{:?}", plan));
+        Ok(())
+    }
+
+    #[test]
+    fn eliminate_immutable_user_defined_functions() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let udf = identity_udf(Volatility::Immutable);
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![
+                (udf.call(vec![col("a")]) + lit(1u32)).alias("x"),
+                (udf.call(vec![col("a")]) + lit(2u32)).alias("y"),
+            ])?
+            .build()?;
+
+        let expected = "\
+        Projection: #__common_expr_1 Plus UInt32(1) AS x, #__common_expr_1 Plus UInt32(2) AS y\
+        \n  Projection: #test.a, #test.b, #test.c, my_udf(#a) AS __common_expr_1\
+        \n    TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_volatile_functions() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let udf = identity_udf(Volatility::Volatile);
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![
+                (udf.call(vec![col("a")]) + lit(1u32)).alias("x"),
+                (udf.call(vec![col("a")]) + lit(2u32)).alias("y"),
+            ])?
+            .build()?;
+
+        assert_optimized_plan_eq(&plan, &format!("{:?}", plan));
+
+        // nor the built-in functions of their results
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![
+                (abs(udf.call(vec![col("a")])) + lit(1f64)).alias("x"),
+                (abs(udf.call(vec![col("a")])) + lit(2f64)).alias("y"),
+            ])?
+            .build()?;
+        assert_optimized_plan_eq(&plan, &format!("{:?}", plan));
+        Ok(())
+    }
+
+    fn identity_udf(volatility: Volatility) -> ScalarUDF {
+        create_udf(
+            "my_udf",
+            vec![DataType::UInt32],
+            Arc::new(DataType::UInt32),
+            volatility,
+            make_scalar_function(|args: &[ArrayRef]| Ok(args[0].clone())),
+        )
+    }
+
+    #[test]
+    fn avoids_names_of_input_columns() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let expr = col("a") + col("__common_expr_1");
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![col("a"), col("b").alias("__common_expr_1")])?
+            .project(vec![
+                (expr.clone() * lit(2u32)).alias("x"),
+                (expr * lit(3u32)).alias("y"),
+            ])?
+            .build()?;
+
+        let expected = "\
+        Projection: #__common_expr_2 Multiply UInt32(2) AS x, #__common_expr_2 Multiply UInt32(3) AS y\
+        \n  Projection: #test.a, #__common_expr_1, #a Plus #__common_expr_1 AS __common_expr_2\
+        \n    Projection: #a, #b AS __common_expr_1\
+        \n      TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+}
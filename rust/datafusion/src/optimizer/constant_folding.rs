@@ -0,0 +1,281 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Constant folding, an optimizer rule that evaluates the calls of functions whose
+//! arguments are all literals when the plan is optimized
+
+use std::sync::Arc;
+
+use arrow::array::NullArray;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::error::Result;
+use crate::logical_plan::{Expr, LogicalPlan};
+use crate::optimizer::optimizer::OptimizerRule;
+use crate::optimizer::utils;
+use crate::physical_plan::expressions::lit;
+use crate::physical_plan::functions::{self, Volatility};
+use crate::physical_plan::udf;
+use crate::scalar::ScalarValue;
+
+/// Constant folding optimizer rule.
+///
+/// A call of a built-in or user defined scalar function whose arguments are all
+/// literals is replaced by the literal of its value, unless the function is
+/// volatile, i.e. may return a different value each time it is called. Calls
+/// whose arguments become literals are folded too. E.g.
+///
+/// ```text
+/// Projection: #a, sqrt(sqrt(Int64(16)))
+///   TableScan: test projection=None
+/// ```
+///
+/// is optimized to
+///
+/// ```text
+/// Projection: #a, Float64(2) AS sqrt(sqrt(Int64(16)))
+///   TableScan: test projection=None
+/// ```
+///
+/// The rewritten node produces the same columns as the original one. A call that
+/// fails is kept, so that its error is returned when the plan is executed.
+pub struct ConstantFolding {}
+
+impl OptimizerRule for ConstantFolding {
+    fn name(&self) -> &str {
+        "constant_folding"
+    }
+
+    fn optimize(&mut self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        match plan {
+            LogicalPlan::Explain {
+                verbose,
+                plan,
+                stringified_plans,
+                schema,
+            } => utils::optimize_explain(
+                self,
+                *verbose,
+                &*plan,
+                stringified_plans,
+                &*schema,
+            ),
+            _ => {
+                let inputs = utils::inputs(plan)
+                    .into_iter()
+                    .map(|input| self.optimize(input))
+                    .collect::<Result<Vec<_>>>()?;
+                let expr = utils::expressions(plan);
+                let folded = expr.iter().map(fold).collect::<Result<Vec<_>>>()?;
+                let expr = match plan {
+                    // the names of the columns of projections and aggregates are
+                    // those of their expressions
+                    LogicalPlan::Projection { input, .. }
+                    | LogicalPlan::Aggregate { input, .. } => expr
+                        .iter()
+                        .zip(folded)
+                        .map(|(e, folded)| match (e, folded) {
+                            (_, None) => Ok(e.clone()),
+                            (Expr::Alias(..), Some(new_e)) => Ok(new_e),
+                            (_, Some(new_e)) => {
+                                Ok(Expr::Alias(Box::new(new_e), e.name(input.schema())?))
+                            }
+                        })
+                        .collect::<Result<Vec<_>>>()?,
+                    _ => expr
+                        .iter()
+                        .zip(folded)
+                        .map(|(e, folded)| folded.unwrap_or_else(|| e.clone()))
+                        .collect(),
+                };
+                utils::from_plan(plan, &expr, &inputs)
+            }
+        }
+    }
+}
+
+impl ConstantFolding {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Rewrites `expr` with its calls whose arguments are all literals replaced by
+/// their values, or returns `None` if there are no such calls
+fn fold(expr: &Expr) -> Result<Option<Expr>> {
+    let children = utils::expr_sub_expressions(expr)?;
+    let folded_children = children
+        .iter()
+        .map(|child| fold(child))
+        .collect::<Result<Vec<_>>>()?;
+
+    let rewritten = if folded_children.iter().any(Option::is_some) {
+        let children = children
+            .into_iter()
+            .zip(folded_children)
+            .map(|(child, folded)| folded.unwrap_or_else(|| child.clone()))
+            .collect::<Vec<_>>();
+        Some(utils::rewrite_expression(expr, &children)?)
+    } else {
+        None
+    };
+
+    let value = evaluate(rewritten.as_ref().unwrap_or(expr));
+    Ok(value.map(Expr::Literal).or(rewritten))
+}
+
+/// The value of `expr` if it is a call of an immutable function whose arguments are all
+/// literals, and that succeeds. Stable functions, such as `now()`, are left to be
+/// evaluated when the plan is executed, as an optimized plan may be executed more than
+/// once, e.g. by a prepared statement.
+fn evaluate(expr: &Expr) -> Option<ScalarValue> {
+    let literals = |args: &[Expr]| {
+        args.iter()
+            .map(|arg| match arg {
+                Expr::Literal(value) => Some(lit(value.clone())),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+    };
+
+    // the arguments do not reference the columns of the batch the call is
+    // evaluated against, which only sets the number of rows
+    let schema = Schema::new(vec![Field::new("_", DataType::Null, true)]);
+    let physical_expr = match expr {
+        Expr::ScalarFunction { fun, args }
+            if fun.volatility() == Volatility::Immutable =>
+        {
+            functions::create_physical_expr(fun, &literals(args)?, &schema).ok()?
+        }
+        Expr::ScalarUDF { fun, args } if fun.volatility == Volatility::Immutable => {
+            udf::create_physical_expr(fun, &literals(args)?, &schema).ok()?
+        }
+        _ => return None,
+    };
+    let batch =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(NullArray::new(1))]).ok()?;
+    let array = physical_expr.evaluate(&batch).ok()?;
+    ScalarValue::try_from_array(&array, 0).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::{col, concat, create_udf, lit, sum, LogicalPlanBuilder};
+    use crate::physical_plan::functions::make_scalar_function;
+    use crate::test::*;
+    use arrow::array::ArrayRef;
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let mut rule = ConstantFolding::new();
+        let optimized_plan = rule.optimize(plan).expect("failed to optimize plan");
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+        assert_eq!(plan.schema(), optimized_plan.schema());
+    }
+
+    fn sqrt(arg: Expr) -> Expr {
+        Expr::ScalarFunction {
+            fun: functions::BuiltinScalarFunction::Sqrt,
+            args: vec![arg],
+        }
+    }
+
+    #[test]
+    fn fold_nested_calls() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![
+                col("a"),
+                sqrt(sqrt(lit(16i64))),
+                concat(vec![lit("a"), lit("b")]).alias("x"),
+            ])?
+            .build()?;
+
+        let expected = "\
+        Projection: #a, Float64(2) AS sqrt(sqrt(Int64(16))), Utf8(\"ab\") AS x\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn fold_arguments_of_calls_of_columns() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .filter(col("a").eq(sqrt(lit(4i64))))?
+            .aggregate(vec![col("b")], vec![sum(sqrt(lit(9i64)))])?
+            .build()?;
+
+        let expected = "\
+        Aggregate: groupBy=[[#b]], aggr=[[SUM(Float64(3)) AS SUM(sqrt(Int64(9)))]]\
+        \n  Filter: #a Eq Float64(2)\
+        \n    TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_volatile_and_failing_calls() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let volatile = create_udf(
+            "my_random",
+            vec![DataType::Float64],
+            Arc::new(DataType::Float64),
+            Volatility::Volatile,
+            make_scalar_function(|args: &[ArrayRef]| Ok(args[0].clone())),
+        );
+        let to_timestamp = Expr::ScalarFunction {
+            fun: functions::BuiltinScalarFunction::ToTimestamp,
+            args: vec![lit("not a timestamp")],
+        };
+        // stable functions are evaluated when the plan is executed
+        let now = Expr::ScalarFunction {
+            fun: functions::BuiltinScalarFunction::Now,
+            args: vec![],
+        };
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![volatile.call(vec![lit(1.0)]), to_timestamp, now])?
+            .build()?;
+
+        assert_optimized_plan_eq(&plan, &format!("{:?}", plan));
+        Ok(())
+    }
+
+    #[test]
+    fn fold_immutable_user_defined_functions() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let udf = create_udf(
+            "my_identity",
+            vec![DataType::Float64],
+            Arc::new(DataType::Float64),
+            Volatility::Immutable,
+            make_scalar_function(|args: &[ArrayRef]| Ok(args[0].clone())),
+        );
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![udf.call(vec![lit(1.5)])])?
+            .build()?;
+
+        let expected = "\
+        Projection: Float64(1.5) AS my_identity(Float64(1.5))\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+}
@@ -75,6 +75,12 @@ impl OptimizerRule for FilterPushDown {
     }
 
     fn optimize(&mut self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        if let LogicalPlan::Analyze { .. } = plan {
+            // the analyzed plan is optimized on its own, as the filters in it
+            // cannot cross the Analyze node
+            return self.optimize_children(plan);
+        }
+
         let result = analyze_plan(plan, 0)?;
         let break_points = result.break_points.clone();
 
@@ -201,7 +207,9 @@ fn analyze_plan(plan: &LogicalPlan, depth: usize) -> Result<AnalysisResult> {
             Ok(result)
         }
         LogicalPlan::Sort { input, .. } => analyze_plan(&input, depth + 1),
-        LogicalPlan::Limit { input, .. } => {
+        // a sample chooses its rows by their position, so filtering first would change
+        // which rows are chosen
+        LogicalPlan::Limit { input, .. } | LogicalPlan::Sample { input, .. } => {
             let mut result = analyze_plan(&input, depth + 1)?;
 
             // collect all columns that break at this depth
@@ -214,6 +222,16 @@ fn analyze_plan(plan: &LogicalPlan, depth: usize) -> Result<AnalysisResult> {
             result.break_points.insert(depth, columns);
             Ok(result)
         }
+        LogicalPlan::Unnest { input, column, .. } => {
+            let mut result = analyze_plan(&input, depth + 1)?;
+
+            // filters on the other columns can be applied before their rows are
+            // repeated, but the unnested column has different values below
+            let mut columns = HashSet::new();
+            columns.insert(column.name.clone());
+            result.break_points.insert(depth, columns);
+            Ok(result)
+        }
         // all other plans add breaks to all their columns to indicate that filters can't proceed further.
         _ => {
             let columns = plan
@@ -253,6 +271,17 @@ fn optimize_plan(
             // ignore old filters
             Ok(optimize_plan(&input, new_filters, depth + 1)?)
         }
+        LogicalPlan::Join { .. } => {
+            // the filters above a join are not pushed into its inputs, which are
+            // optimized on their own
+            let expr = utils::expressions(plan);
+            let new_inputs = utils::inputs(plan)
+                .into_iter()
+                .map(|input| FilterPushDown::new().optimize(input))
+                .collect::<Result<Vec<_>>>()?;
+
+            utils::from_plan(plan, &expr, &new_inputs)
+        }
         _ => {
             // all other nodes are copied, optimizing recursively.
             let expr = utils::expressions(plan);
@@ -288,8 +317,8 @@ fn rewrite(expr: &Expr, projection: &HashMap<String, Expr>) -> Result<Expr> {
         .collect::<Result<Vec<_>>>()?;
 
     match expr {
-        Expr::Column(name) => {
-            if let Some(expr) = projection.get(name) {
+        Expr::Column(c) => {
+            if let Some(expr) = projection.get(&c.name) {
                 return Ok(expr.clone());
             }
         }
@@ -303,8 +332,11 @@ fn rewrite(expr: &Expr, projection: &HashMap<String, Expr>) -> Result<Expr> {
 mod tests {
     use super::*;
     use crate::logical_plan::col;
-    use crate::logical_plan::{lit, sum, Expr, LogicalPlanBuilder, Operator};
+    use crate::logical_plan::{
+        lit, sum, Column, Expr, JoinType, LogicalPlanBuilder, Operator, SampleMethod,
+    };
     use crate::test::*;
+    use arrow::datatypes::{DataType, Field, Schema};
 
     fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
         let mut rule = FilterPushDown::new();
@@ -347,6 +379,72 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn filter_after_sample() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .sample(SampleMethod::Bernoulli, 0.5, 7)?
+            .filter(col("a").eq(lit(1i64)))?
+            .build()?;
+        // the filter would change which rows are sampled
+        let expected = "\
+            Filter: #a Eq Int64(1)\
+            \n  Sample: method=Bernoulli, fraction=0.5, seed=7\
+            \n    TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn filter_around_join() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let right = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![col("a")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .filter(col("a").eq(lit(1i64)))?
+            .project(vec![col("a"), col("b")])?
+            .join_using(&right, JoinType::Inner, vec![Column::from_name("a")])?
+            .filter(col("b").eq(lit(2i64)))?
+            .build()?;
+        // the filter above the join stays above it, and the filter in its input is kept
+        let expected = "\
+            Filter: #b Eq Int64(2)\
+            \n  Join: type=Inner, using=[test.a = test.a]\
+            \n    Projection: #a, #b\
+            \n      Filter: #a Eq Int64(1)\
+            \n        TableScan: test projection=None\
+            \n    Projection: #a\
+            \n      TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn filter_around_unnest() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new(
+                "l",
+                DataType::List(Box::new(Field::new("item", DataType::Int64, true))),
+                true,
+            ),
+        ]);
+        let plan = LogicalPlanBuilder::scan("default", "test", &schema, None)?
+            .unnest(Column::from_name("l"))?
+            .filter(col("l").gt(lit(1i64)))?
+            .filter(col("a").eq(lit(1i64)))?
+            .build()?;
+        // the filter of the unnested column stays above the unnest
+        let expected = "\
+            Filter: #l Gt Int64(1)\
+            \n  Unnest: #l\
+            \n    Filter: #a Eq Int64(1)\
+            \n      TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
     #[test]
     fn filter_jump_2_plans() -> Result<()> {
         let table_scan = test_table_scan()?;
@@ -414,6 +512,27 @@ mod tests {
         Ok(())
     }
 
+    /// verifies that IN lists and BETWEEN are pushed down and re-written like other filters
+    #[test]
+    fn in_list_and_between() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![col("a").alias("b"), col("c")])?
+            .filter(
+                col("b")
+                    .in_list(vec![lit(1i64), lit(2i64)], true)
+                    .and(col("c").between(lit(1i64), lit(10i64))),
+            )?
+            .build()?;
+        // filter is before projection
+        let expected = "\
+            Projection: #a AS b, #c\
+            \n  Filter: #a NOT IN (Int64(1), Int64(2)) And #c BETWEEN Int64(1) AND Int64(10)\
+            \n    TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
     fn add(left: Expr, right: Expr) -> Expr {
         Expr::BinaryExpr {
             left: Box::new(left),
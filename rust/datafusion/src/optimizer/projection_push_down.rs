@@ -19,10 +19,10 @@
 //! loaded into memory
 
 use crate::error::{DataFusionError, Result};
-use crate::logical_plan::LogicalPlan;
+use crate::logical_plan::{DFField, DFSchema, DFSchemaRef, LogicalPlan, TableSource};
 use crate::optimizer::optimizer::OptimizerRule;
 use crate::optimizer::utils;
-use arrow::datatypes::{Field, Schema, SchemaRef};
+use arrow::datatypes::Schema;
 use arrow::error::Result as ArrowResult;
 use std::{collections::HashSet, sync::Arc};
 use utils::optimize_explain;
@@ -57,10 +57,11 @@ impl ProjectionPushDown {
 
 fn get_projected_schema(
     schema: &Schema,
+    qualifier: Option<&str>,
     projection: &Option<Vec<usize>>,
     required_columns: &HashSet<String>,
     has_projection: bool,
-) -> Result<(Vec<usize>, SchemaRef)> {
+) -> Result<(Vec<usize>, DFSchemaRef)> {
     if projection.is_some() {
         return Err(DataFusionError::Internal(
             "Cannot run projection push-down rule more than once".to_string(),
@@ -98,12 +99,19 @@ fn get_projected_schema(
     projection.sort();
 
     // create the projected schema
-    let mut projected_fields: Vec<Field> = Vec::with_capacity(projection.len());
+    let mut projected_fields: Vec<DFField> = Vec::with_capacity(projection.len());
     for i in &projection {
-        projected_fields.push(schema.fields()[*i].clone());
+        let field = schema.fields()[*i].clone();
+        projected_fields.push(match qualifier {
+            Some(qualifier) => DFField::from_qualified(qualifier, field),
+            None => DFField::from(field),
+        });
     }
 
-    Ok((projection, SchemaRef::new(Schema::new(projected_fields))))
+    Ok((
+        projection,
+        DFSchemaRef::new(DFSchema::new(projected_fields)?),
+    ))
 }
 
 /// Recursively transverses the logical plan removing expressions and that are not needed.
@@ -154,7 +162,7 @@ fn optimize_plan(
                 Ok(LogicalPlan::Projection {
                     expr: new_expr,
                     input: Arc::new(new_input),
-                    schema: SchemaRef::new(Schema::new(new_fields)),
+                    schema: DFSchemaRef::new(DFSchema::new(new_fields)?),
                 })
             }
         }
@@ -191,14 +199,14 @@ fn optimize_plan(
                 })
                 .collect::<Result<()>>()?;
 
-            let new_schema = Schema::new(
+            let new_schema = DFSchema::new(
                 schema
                     .fields()
                     .iter()
                     .filter(|x| new_required_columns.contains(x.name()))
                     .cloned()
                     .collect(),
-            );
+            )?;
 
             Ok(LogicalPlan::Aggregate {
                 group_expr: group_expr.clone(),
@@ -209,7 +217,7 @@ fn optimize_plan(
                     &new_required_columns,
                     true,
                 )?),
-                schema: SchemaRef::new(new_schema),
+                schema: DFSchemaRef::new(new_schema),
             })
         }
         // scans:
@@ -219,10 +227,17 @@ fn optimize_plan(
             source,
             table_schema,
             projection,
+            alias,
             ..
         } => {
+            // the columns of the scan are qualified by its alias or table name
+            let qualifier = alias.as_deref().or(match source {
+                TableSource::FromContext(table_name) => Some(table_name.as_str()),
+                TableSource::FromProvider(_) => None,
+            });
             let (projection, projected_schema) = get_projected_schema(
                 &table_schema,
+                qualifier,
                 projection,
                 required_columns,
                 has_projection,
@@ -235,6 +250,7 @@ fn optimize_plan(
                 table_schema: table_schema.clone(),
                 projection: Some(projection),
                 projected_schema: projected_schema,
+                alias: alias.clone(),
             })
         }
         LogicalPlan::InMemoryScan {
@@ -245,6 +261,7 @@ fn optimize_plan(
         } => {
             let (projection, projected_schema) = get_projected_schema(
                 &schema,
+                None,
                 projection,
                 required_columns,
                 has_projection,
@@ -266,6 +283,7 @@ fn optimize_plan(
         } => {
             let (projection, projected_schema) = get_projected_schema(
                 &schema,
+                None,
                 projection,
                 required_columns,
                 has_projection,
@@ -288,6 +306,7 @@ fn optimize_plan(
         } => {
             let (projection, projected_schema) = get_projected_schema(
                 &schema,
+                None,
                 projection,
                 required_columns,
                 has_projection,
@@ -306,10 +325,15 @@ fn optimize_plan(
             stringified_plans,
             schema,
         } => optimize_explain(optimizer, *verbose, &*plan, stringified_plans, &*schema),
+        // the analyzed plan produces all its columns, as if it was the root
+        LogicalPlan::Analyze { .. } => optimizer.optimize_children(plan),
         // all other nodes: Add any additional columns used by
         // expressions in this node to the list of required columns
         LogicalPlan::Limit { .. }
+        | LogicalPlan::Unnest { .. }
+        | LogicalPlan::Sample { .. }
         | LogicalPlan::Filter { .. }
+        | LogicalPlan::Join { .. }
         | LogicalPlan::EmptyRelation { .. }
         | LogicalPlan::Sort { .. }
         | LogicalPlan::CreateExternalTable { .. }
@@ -338,7 +362,7 @@ mod tests {
 
     use super::*;
     use crate::logical_plan::{col, lit};
-    use crate::logical_plan::{max, min, Expr, LogicalPlanBuilder};
+    use crate::logical_plan::{max, min, Column, Expr, LogicalPlanBuilder};
     use crate::test::*;
     use arrow::datatypes::DataType;
 
@@ -553,6 +577,39 @@ mod tests {
         Ok(())
     }
 
+    /// tests that the pruned scan keeps qualifying its columns by the table alias
+    #[test]
+    fn table_scan_with_alias() -> Result<()> {
+        let schema: Schema = test_table_scan()?.schema().as_ref().into();
+        let table_scan = LogicalPlanBuilder::scan_with_alias(
+            "default",
+            "test",
+            Some("t"),
+            &schema,
+            None,
+        )?
+        .build()?;
+
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![Expr::Column(Column::new(Some("t"), "b"))])?
+            .build()?;
+
+        let expected = "Projection: #t.b\
+        \n  TableScan: test AS t projection=Some([1])";
+
+        let optimized_plan = optimize(&plan)?;
+        assert_eq!(format!("{:?}", optimized_plan), expected);
+        match &optimized_plan {
+            LogicalPlan::Projection { input, .. } => {
+                let field = input.schema().field(0);
+                assert_eq!("t.b", field.qualified_name());
+            }
+            _ => panic!("expected a projection, got {:?}", optimized_plan),
+        }
+
+        Ok(())
+    }
+
     fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
         let optimized_plan = optimize(plan).expect("failed to optimize plan");
         let formatted_plan = format!("{:?}", optimized_plan);
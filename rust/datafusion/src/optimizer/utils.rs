@@ -19,11 +19,12 @@
 
 use std::{collections::HashSet, sync::Arc};
 
-use arrow::datatypes::{Schema, SchemaRef};
-
 use super::optimizer::OptimizerRule;
 use crate::error::{DataFusionError, Result};
-use crate::logical_plan::{Expr, LogicalPlan, PlanType, StringifiedPlan};
+use crate::logical_plan::{
+    DFSchema, DFSchemaRef, Expr, LogicalPlan, LogicalPlanBuilder, PlanType,
+    StringifiedPlan,
+};
 
 /// Recursively walk a list of expression trees, collecting the unique set of column
 /// names referenced in the expression
@@ -38,12 +39,12 @@ pub fn exprlist_to_column_names(
 }
 
 /// Recursively walk an expression tree, collecting the unique set of column names
-/// referenced in the expression
+/// referenced in the expression. Names are collected without their relation.
 pub fn expr_to_column_names(expr: &Expr, accum: &mut HashSet<String>) -> Result<()> {
     match expr {
         Expr::Alias(expr, _) => expr_to_column_names(expr, accum),
-        Expr::Column(name) => {
-            accum.insert(name.clone());
+        Expr::Column(c) => {
+            accum.insert(c.name.clone());
             Ok(())
         }
         Expr::ScalarVariable(var_names) => {
@@ -54,15 +55,29 @@ pub fn expr_to_column_names(expr: &Expr, accum: &mut HashSet<String>) -> Result<
             // not needed
             Ok(())
         }
+        Expr::Placeholder { .. } => Ok(()),
         Expr::Not(e) => expr_to_column_names(e, accum),
         Expr::IsNull(e) => expr_to_column_names(e, accum),
         Expr::IsNotNull(e) => expr_to_column_names(e, accum),
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            expr_to_column_names(expr, accum)?;
+            expr_to_column_names(low, accum)?;
+            expr_to_column_names(high, accum)?;
+            Ok(())
+        }
+        Expr::InList { expr, list, .. } => {
+            expr_to_column_names(expr, accum)?;
+            exprlist_to_column_names(list, accum)
+        }
         Expr::BinaryExpr { left, right, .. } => {
             expr_to_column_names(left, accum)?;
             expr_to_column_names(right, accum)?;
             Ok(())
         }
         Expr::Cast { expr, .. } => expr_to_column_names(expr, accum),
+        Expr::GetIndexedField { expr, .. } => expr_to_column_names(expr, accum),
         Expr::Sort { expr, .. } => expr_to_column_names(expr, accum),
         Expr::AggregateFunction { args, .. } => exprlist_to_column_names(args, accum),
         Expr::AggregateUDF { args, .. } => exprlist_to_column_names(args, accum),
@@ -82,7 +97,7 @@ pub fn optimize_explain(
     verbose: bool,
     plan: &LogicalPlan,
     stringified_plans: &Vec<StringifiedPlan>,
-    schema: &Schema,
+    schema: &DFSchema,
 ) -> Result<LogicalPlan> {
     // These are the fields of LogicalPlan::Explain It might be nice
     // to transform that enum Variant into its own struct and avoid
@@ -94,7 +109,7 @@ pub fn optimize_explain(
         PlanType::OptimizedLogicalPlan { optimizer_name },
         format!("{:#?}", plan),
     ));
-    let schema = SchemaRef::new(schema.clone());
+    let schema = DFSchemaRef::new(schema.clone());
 
     Ok(LogicalPlan::Explain {
         verbose,
@@ -119,6 +134,15 @@ pub fn expressions(plan: &LogicalPlan) -> Vec<Expr> {
             result
         }
         LogicalPlan::Sort { expr, .. } => expr.clone(),
+        LogicalPlan::Join { on, .. } => {
+            let (left_keys, right_keys): (Vec<_>, Vec<_>) = on.iter().cloned().unzip();
+            left_keys
+                .into_iter()
+                .chain(right_keys)
+                .map(Expr::Column)
+                .collect()
+        }
+        LogicalPlan::Unnest { column, .. } => vec![Expr::Column(column.clone())],
         LogicalPlan::Extension { node } => node.expressions(),
         // plans without expressions
         LogicalPlan::TableScan { .. }
@@ -127,8 +151,10 @@ pub fn expressions(plan: &LogicalPlan) -> Vec<Expr> {
         | LogicalPlan::CsvScan { .. }
         | LogicalPlan::EmptyRelation { .. }
         | LogicalPlan::Limit { .. }
+        | LogicalPlan::Sample { .. }
         | LogicalPlan::CreateExternalTable { .. }
-        | LogicalPlan::Explain { .. } => vec![],
+        | LogicalPlan::Explain { .. }
+        | LogicalPlan::Analyze { .. } => vec![],
     }
 }
 
@@ -140,6 +166,10 @@ pub fn inputs(plan: &LogicalPlan) -> Vec<&LogicalPlan> {
         LogicalPlan::Aggregate { input, .. } => vec![input],
         LogicalPlan::Sort { input, .. } => vec![input],
         LogicalPlan::Limit { input, .. } => vec![input],
+        LogicalPlan::Unnest { input, .. } => vec![input],
+        LogicalPlan::Sample { input, .. } => vec![input],
+        LogicalPlan::Join { left, right, .. } => vec![left, right],
+        LogicalPlan::Analyze { input, .. } => vec![input],
         LogicalPlan::Extension { node } => node.inputs(),
         // plans without inputs
         LogicalPlan::TableScan { .. }
@@ -184,6 +214,46 @@ pub fn from_plan(
             n: *n,
             input: Arc::new(inputs[0].clone()),
         }),
+        LogicalPlan::Sample {
+            method,
+            fraction,
+            seed,
+            ..
+        } => Ok(LogicalPlan::Sample {
+            method: *method,
+            fraction: *fraction,
+            seed: *seed,
+            input: Arc::new(inputs[0].clone()),
+        }),
+        // the schema of a join depends on the schemas of its inputs
+        LogicalPlan::Join {
+            on,
+            join_type,
+            join_constraint,
+            ..
+        } => {
+            let (left_keys, right_keys) = on.iter().cloned().unzip();
+            LogicalPlanBuilder::from(&inputs[0])
+                .join_detailed(
+                    &inputs[1],
+                    *join_type,
+                    left_keys,
+                    right_keys,
+                    *join_constraint,
+                )?
+                .build()
+        }
+        // the schema of an unnest depends on the schema of its input
+        LogicalPlan::Unnest { column, .. } => LogicalPlanBuilder::from(&inputs[0])
+            .unnest(column.clone())?
+            .build(),
+        LogicalPlan::Analyze {
+            verbose, schema, ..
+        } => Ok(LogicalPlan::Analyze {
+            verbose: *verbose,
+            input: Arc::new(inputs[0].clone()),
+            schema: schema.clone(),
+        }),
         LogicalPlan::Extension { node } => Ok(LogicalPlan::Extension {
             node: node.from_template(expr, inputs),
         }),
@@ -204,15 +274,25 @@ pub fn expr_sub_expressions(expr: &Expr) -> Result<Vec<&Expr>> {
         Expr::BinaryExpr { left, right, .. } => Ok(vec![left, right]),
         Expr::IsNull(e) => Ok(vec![e]),
         Expr::IsNotNull(e) => Ok(vec![e]),
+        Expr::Between {
+            expr, low, high, ..
+        } => Ok(vec![expr, low, high]),
+        Expr::InList { expr, list, .. } => {
+            let mut expressions = vec![expr.as_ref()];
+            expressions.extend(list.iter());
+            Ok(expressions)
+        }
         Expr::ScalarFunction { args, .. } => Ok(args.iter().collect()),
         Expr::ScalarUDF { args, .. } => Ok(args.iter().collect()),
         Expr::AggregateFunction { args, .. } => Ok(args.iter().collect()),
         Expr::AggregateUDF { args, .. } => Ok(args.iter().collect()),
         Expr::Cast { expr, .. } => Ok(vec![expr]),
+        Expr::GetIndexedField { expr, .. } => Ok(vec![expr]),
         Expr::Column(_) => Ok(vec![]),
         Expr::Alias(expr, ..) => Ok(vec![expr]),
         Expr::Literal(_) => Ok(vec![]),
         Expr::ScalarVariable(_) => Ok(vec![]),
+        Expr::Placeholder { .. } => Ok(vec![]),
         Expr::Not(expr) => Ok(vec![expr]),
         Expr::Sort { expr, .. } => Ok(vec![expr]),
         Expr::Wildcard { .. } => Err(DataFusionError::Internal(
@@ -233,6 +313,17 @@ pub fn rewrite_expression(expr: &Expr, expressions: &Vec<Expr>) -> Result<Expr>
         }),
         Expr::IsNull(_) => Ok(Expr::IsNull(Box::new(expressions[0].clone()))),
         Expr::IsNotNull(_) => Ok(Expr::IsNotNull(Box::new(expressions[0].clone()))),
+        Expr::Between { negated, .. } => Ok(Expr::Between {
+            expr: Box::new(expressions[0].clone()),
+            negated: *negated,
+            low: Box::new(expressions[1].clone()),
+            high: Box::new(expressions[2].clone()),
+        }),
+        Expr::InList { negated, .. } => Ok(Expr::InList {
+            expr: Box::new(expressions[0].clone()),
+            list: expressions[1..].to_vec(),
+            negated: *negated,
+        }),
         Expr::ScalarFunction { fun, .. } => Ok(Expr::ScalarFunction {
             fun: fun.clone(),
             args: expressions.clone(),
@@ -254,6 +345,10 @@ pub fn rewrite_expression(expr: &Expr, expressions: &Vec<Expr>) -> Result<Expr>
             expr: Box::new(expressions[0].clone()),
             data_type: data_type.clone(),
         }),
+        Expr::GetIndexedField { key, .. } => Ok(Expr::GetIndexedField {
+            expr: Box::new(expressions[0].clone()),
+            key: key.clone(),
+        }),
         Expr::Alias(_, alias) => {
             Ok(Expr::Alias(Box::new(expressions[0].clone()), alias.clone()))
         }
@@ -261,6 +356,7 @@ pub fn rewrite_expression(expr: &Expr, expressions: &Vec<Expr>) -> Result<Expr>
         Expr::Column(_) => Ok(expr.clone()),
         Expr::Literal(_) => Ok(expr.clone()),
         Expr::ScalarVariable(_) => Ok(expr.clone()),
+        Expr::Placeholder { .. } => Ok(expr.clone()),
         Expr::Sort {
             asc, nulls_first, ..
         } => Ok(Expr::Sort {
@@ -278,7 +374,7 @@ pub fn rewrite_expression(expr: &Expr, expressions: &Vec<Expr>) -> Result<Expr>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::logical_plan::{col, LogicalPlanBuilder};
+    use crate::logical_plan::{col, LogicalPlanBuilder, ToDFSchema};
     use arrow::datatypes::DataType;
     use std::collections::HashSet;
 
@@ -321,7 +417,7 @@ mod tests {
         let mut optimizer = TestOptimizer {};
 
         let empty_plan = LogicalPlanBuilder::empty().build()?;
-        let schema = LogicalPlan::explain_schema();
+        let schema = LogicalPlan::explain_schema().to_dfschema_ref()?;
 
         let optimized_explain = optimize_explain(
             &mut optimizer,
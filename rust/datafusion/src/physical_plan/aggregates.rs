@@ -34,7 +34,7 @@ use super::{
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::distinct_expressions;
 use crate::physical_plan::expressions;
-use arrow::datatypes::{DataType, Schema};
+use arrow::datatypes::{DataType, DateUnit, Schema, TimeUnit};
 use expressions::{avg_return_type, sum_return_type};
 use std::{fmt, str::FromStr, sync::Arc};
 
@@ -176,14 +176,37 @@ static NUMERICS: &'static [DataType] = &[
     DataType::Float64,
 ];
 
+static TEMPORALS: &'static [DataType] = &[
+    DataType::Date32(DateUnit::Day),
+    DataType::Date64(DateUnit::Millisecond),
+    DataType::Time32(TimeUnit::Second),
+    DataType::Time32(TimeUnit::Millisecond),
+    DataType::Time64(TimeUnit::Microsecond),
+    DataType::Time64(TimeUnit::Nanosecond),
+    DataType::Timestamp(TimeUnit::Second, None),
+    DataType::Timestamp(TimeUnit::Millisecond, None),
+    DataType::Timestamp(TimeUnit::Microsecond, None),
+    DataType::Timestamp(TimeUnit::Nanosecond, None),
+    DataType::Duration(TimeUnit::Second),
+    DataType::Duration(TimeUnit::Millisecond),
+    DataType::Duration(TimeUnit::Microsecond),
+    DataType::Duration(TimeUnit::Nanosecond),
+];
+
 /// the signatures supported by the function `fun`.
 fn signature(fun: &AggregateFunction) -> Signature {
     // note: the physical expression must accept the type returned by this function or the execution panics.
     match fun {
         AggregateFunction::Count => Signature::Any(1),
         AggregateFunction::Min | AggregateFunction::Max => {
-            let mut valid = vec![DataType::Utf8, DataType::LargeUtf8];
+            let mut valid = vec![
+                DataType::Utf8,
+                DataType::LargeUtf8,
+                DataType::Binary,
+                DataType::LargeBinary,
+            ];
             valid.extend_from_slice(NUMERICS);
+            valid.extend_from_slice(TEMPORALS);
             Signature::Uniform(1, valid)
         }
         AggregateFunction::Avg | AggregateFunction::Sum => {
@@ -204,6 +227,13 @@ mod tests {
 
         let observed = return_type(&AggregateFunction::Max, &vec![DataType::Int32])?;
         assert_eq!(DataType::Int32, observed);
+
+        let timestamp = DataType::Timestamp(TimeUnit::Nanosecond, None);
+        let observed = return_type(&AggregateFunction::Max, &vec![timestamp.clone()])?;
+        assert_eq!(timestamp, observed);
+
+        let observed = return_type(&AggregateFunction::Min, &vec![DataType::Binary])?;
+        assert_eq!(DataType::Binary, observed);
         Ok(())
     }
 
@@ -0,0 +1,157 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the EXPLAIN ANALYZE operator
+
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::{common, common::SizedRecordBatchStream, ExecutionPlan};
+use arrow::{array::StringBuilder, datatypes::SchemaRef, record_batch::RecordBatch};
+use futures::{future, StreamExt};
+
+use super::metrics::format_plan_with_metrics;
+use super::{Partitioning, SendableRecordBatchStream};
+use async_trait::async_trait;
+
+/// EXPLAIN ANALYZE execution plan operator. This operator runs its input to
+/// completion, discarding its output, and then produces the input plan annotated
+/// with the metrics that each of its operators recorded.
+#[derive(Debug)]
+pub struct AnalyzeExec {
+    /// Whether to report the total number of output rows and the duration as well
+    verbose: bool,
+    /// The plan to run and analyze
+    input: Arc<dyn ExecutionPlan>,
+    /// The schema that this exec plan node outputs
+    schema: SchemaRef,
+}
+
+impl AnalyzeExec {
+    /// Create a new AnalyzeExec
+    pub fn new(verbose: bool, input: Arc<dyn ExecutionPlan>, schema: SchemaRef) -> Self {
+        AnalyzeExec {
+            verbose,
+            input,
+            schema,
+        }
+    }
+
+    /// Whether to report the total number of output rows and the duration as well
+    pub fn verbose(&self) -> bool {
+        self.verbose
+    }
+
+    /// The plan to run and analyze
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for AnalyzeExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(AnalyzeExec::new(
+                self.verbose,
+                children[0].clone(),
+                self.schema.clone(),
+            ))),
+            _ => Err(DataFusionError::Internal(
+                "AnalyzeExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        if 0 != partition {
+            return Err(DataFusionError::Internal(format!(
+                "AnalyzeExec invalid partition {}",
+                partition
+            )));
+        }
+
+        // run all the partitions of the input in parallel, only counting their rows
+        let start = Instant::now();
+        let tasks =
+            (0..self.input.output_partitioning().partition_count()).map(|part_i| {
+                let input = self.input.clone();
+                common::spawn_abortable(async move {
+                    let mut stream = input.execute(part_i).await?;
+                    let mut rows = 0;
+                    while let Some(batch) = stream.next().await {
+                        rows += batch?.num_rows();
+                    }
+                    Result::Ok(rows)
+                })
+            });
+        let results = future::try_join_all(tasks).await?;
+        let output_rows = results.into_iter().collect::<Result<Vec<_>>>()?;
+        let output_rows: usize = output_rows.iter().sum();
+        let duration = start.elapsed();
+
+        let mut type_builder = StringBuilder::new(3);
+        let mut plan_builder = StringBuilder::new(3);
+
+        type_builder.append_value("Plan with Metrics")?;
+        plan_builder.append_value(&format_plan_with_metrics(self.input.as_ref()))?;
+
+        if self.verbose {
+            type_builder.append_value("Output Rows")?;
+            plan_builder.append_value(&output_rows.to_string())?;
+
+            type_builder.append_value("Duration")?;
+            plan_builder.append_value(&format!("{:?}", duration))?;
+        }
+
+        let record_batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(type_builder.finish()),
+                Arc::new(plan_builder.finish()),
+            ],
+        )?;
+
+        Ok(Box::pin(SizedRecordBatchStream::new(
+            self.schema.clone(),
+            vec![Arc::new(record_batch)],
+        )))
+    }
+}
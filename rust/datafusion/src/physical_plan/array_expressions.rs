@@ -18,8 +18,12 @@
 //! Array expressions
 
 use crate::error::{DataFusionError, Result};
+use crate::scalar::ScalarValue;
 use arrow::array::*;
+use arrow::compute::take;
 use arrow::datatypes::DataType;
+use std::convert::TryFrom;
+use std::ops::Range;
 use std::sync::Arc;
 
 macro_rules! downcast_vec {
@@ -88,6 +92,122 @@ pub fn array(args: &[ArrayRef]) -> Result<ArrayRef> {
     }
 }
 
+/// Returns the values of `array`, a `List`, `LargeList` or `FixedSizeList` array,
+/// and the range of each of its lists in these values, `None` for null lists.
+pub(crate) fn list_ranges(
+    array: &ArrayRef,
+) -> Result<(ArrayRef, Vec<Option<Range<usize>>>)> {
+    let range = |is_null: bool, offset: usize, length: usize| {
+        if is_null {
+            None
+        } else {
+            Some(offset..offset + length)
+        }
+    };
+    match array.data_type() {
+        DataType::List(_) => {
+            let list = array.as_any().downcast_ref::<ListArray>().unwrap();
+            let ranges = (0..list.len())
+                .map(|i| {
+                    range(
+                        list.is_null(i),
+                        list.value_offset(i) as usize,
+                        list.value_length(i) as usize,
+                    )
+                })
+                .collect();
+            Ok((list.values(), ranges))
+        }
+        DataType::LargeList(_) => {
+            let list = array.as_any().downcast_ref::<LargeListArray>().unwrap();
+            let ranges = (0..list.len())
+                .map(|i| {
+                    range(
+                        list.is_null(i),
+                        list.value_offset(i) as usize,
+                        list.value_length(i) as usize,
+                    )
+                })
+                .collect();
+            Ok((list.values(), ranges))
+        }
+        DataType::FixedSizeList(_, _) => {
+            let list = array.as_any().downcast_ref::<FixedSizeListArray>().unwrap();
+            let ranges = (0..list.len())
+                .map(|i| {
+                    range(
+                        list.is_null(i),
+                        list.value_offset(i) as usize,
+                        list.value_length() as usize,
+                    )
+                })
+                .collect();
+            Ok((list.values(), ranges))
+        }
+        data_type => Err(DataFusionError::Internal(format!(
+            "Expected a list, but got an array of type '{:?}'.",
+            data_type
+        ))),
+    }
+}
+
+/// Returns the element of each list of `array` at the 1-based `index`, or null when
+/// the list is null or has no such element.
+pub fn list_element(array: &ArrayRef, index: i64) -> Result<ArrayRef> {
+    let (values, ranges) = list_ranges(array)?;
+    let indices = ranges
+        .iter()
+        .map(|range| match range {
+            Some(range) if index >= 1 && index as usize <= range.len() => {
+                Some((range.start + index as usize - 1) as u32)
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    // `take` reads the first value for null indices, which an empty array lacks
+    let values = if values.is_empty() {
+        ScalarValue::try_from(values.data_type())?.to_array()
+    } else {
+        values
+    };
+    Ok(take(&values, &UInt32Array::from(indices), None)?)
+}
+
+/// Returns the number of elements of each list of the first argument.
+pub fn array_length(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let (_, ranges) = list_ranges(&args[0])?;
+    let lengths = ranges
+        .iter()
+        .map(|range| range.as_ref().map(|range| range.len() as i64))
+        .collect::<Vec<_>>();
+    Ok(Arc::new(Int64Array::from(lengths)))
+}
+
+/// Returns whether each list of the first argument contains the value of the second
+/// argument, of the type of the elements of the lists. The result is null for null
+/// lists and values.
+pub fn array_contains(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let (values, ranges) = list_ranges(&args[0])?;
+    let mut result = Vec::with_capacity(ranges.len());
+    for (i, range) in ranges.into_iter().enumerate() {
+        let value = ScalarValue::try_from_array(&args[1], i)?;
+        result.push(match range {
+            Some(range) if !value.is_null() => {
+                let mut contains = false;
+                for j in range {
+                    if ScalarValue::try_from_array(&values, j)? == value {
+                        contains = true;
+                        break;
+                    }
+                }
+                Some(contains)
+            }
+            _ => None,
+        });
+    }
+    Ok(Arc::new(BooleanArray::from(result)))
+}
+
 /// Currently supported types by the array function.
 /// The order of these types correspond to the order on which coercion applies
 /// This should thus be from least informative to most informative
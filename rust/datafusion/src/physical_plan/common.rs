@@ -17,27 +17,34 @@
 
 //! Defines common code used in execution plans
 
-use std::fs;
-use std::fs::metadata;
+use std::cmp::Ordering;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use super::expressions::{Column, PhysicalSortExpr};
 use super::{RecordBatchStream, SendableRecordBatchStream};
+use crate::datasource::object_store::{list_files, LocalFileSystem};
 use crate::error::{DataFusionError, Result};
 
 use array::{
-    BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
-    Int8Array, LargeStringArray, StringArray, UInt16Array, UInt32Array, UInt64Array,
-    UInt8Array,
+    BooleanArray, Date32Array, Date64Array, Float32Array, Float64Array, Int16Array,
+    Int32Array, Int64Array, Int8Array, LargeStringArray, StringArray,
+    TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
+    TimestampSecondArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
 };
-use arrow::datatypes::{DataType, SchemaRef};
+use arrow::datatypes::{DataType, SchemaRef, TimeUnit};
 use arrow::error::Result as ArrowResult;
 use arrow::record_batch::RecordBatch;
 use arrow::{
     array::{self, ArrayRef},
     datatypes::Schema,
 };
-use futures::{Stream, TryStreamExt};
+use futures::executor::block_on;
+use futures::future::{self, AbortHandle, Aborted};
+use futures::{FutureExt, Stream, TryStreamExt};
+use tokio::task::JoinHandle;
 
 /// Stream of record batches
 pub struct SizedRecordBatchStream {
@@ -87,30 +94,171 @@ pub async fn collect(stream: SendableRecordBatchStream) -> Result<Vec<RecordBatc
         .map_err(|e| DataFusionError::from(e))
 }
 
-/// Recursively build a list of files in a directory with a given extension
-pub fn build_file_list(dir: &str, filenames: &mut Vec<String>, ext: &str) -> Result<()> {
-    let metadata = metadata(dir)?;
-    if metadata.is_file() {
-        if dir.ends_with(ext) {
-            filenames.push(dir.to_string());
+/// The memory held by the arrays of a record batch, in bytes
+pub fn batch_memory_size(batch: &RecordBatch) -> usize {
+    batch
+        .columns()
+        .iter()
+        .map(|array| array.get_array_memory_size())
+        .sum()
+}
+
+macro_rules! compare_typed {
+    ($LEFT:expr, $L:expr, $RIGHT:expr, $R:expr, $ARRAYTYPE:ident) => {{
+        let left = $LEFT.as_any().downcast_ref::<$ARRAYTYPE>().unwrap();
+        let right = $RIGHT.as_any().downcast_ref::<$ARRAYTYPE>().unwrap();
+        left.value($L).cmp(&right.value($R))
+    }};
+}
+
+macro_rules! compare_float {
+    ($LEFT:expr, $L:expr, $RIGHT:expr, $R:expr, $ARRAYTYPE:ident) => {{
+        let left = $LEFT.as_any().downcast_ref::<$ARRAYTYPE>().unwrap();
+        let right = $RIGHT.as_any().downcast_ref::<$ARRAYTYPE>().unwrap();
+        let (left, right) = (left.value($L), right.value($R));
+        // NaNs are last, as in the order of the sort kernels
+        match (left.is_nan(), right.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => left.partial_cmp(&right).unwrap(),
         }
-    } else {
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if let Some(path_name) = path.to_str() {
-                if path.is_dir() {
-                    build_file_list(path_name, filenames, ext)?;
-                } else {
-                    if path_name.ends_with(ext) {
-                        filenames.push(path_name.to_string());
-                    }
-                }
-            } else {
-                return Err(DataFusionError::Plan("Invalid path".to_string()));
-            }
+    }};
+}
+
+/// Whether [compare_values] supports the values of `data_type`
+pub(crate) fn is_comparable_type(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Boolean
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Utf8
+            | DataType::LargeUtf8
+            | DataType::Date32(_)
+            | DataType::Date64(_)
+            | DataType::Timestamp(_, _)
+    )
+}
+
+/// Compares two values that are not null, of two arrays of the same type, in
+/// ascending order
+pub(crate) fn compare_values(
+    left: &ArrayRef,
+    l: usize,
+    right: &ArrayRef,
+    r: usize,
+) -> Result<Ordering> {
+    Ok(match left.data_type() {
+        DataType::Boolean => compare_typed!(left, l, right, r, BooleanArray),
+        DataType::Int8 => compare_typed!(left, l, right, r, Int8Array),
+        DataType::Int16 => compare_typed!(left, l, right, r, Int16Array),
+        DataType::Int32 => compare_typed!(left, l, right, r, Int32Array),
+        DataType::Int64 => compare_typed!(left, l, right, r, Int64Array),
+        DataType::UInt8 => compare_typed!(left, l, right, r, UInt8Array),
+        DataType::UInt16 => compare_typed!(left, l, right, r, UInt16Array),
+        DataType::UInt32 => compare_typed!(left, l, right, r, UInt32Array),
+        DataType::UInt64 => compare_typed!(left, l, right, r, UInt64Array),
+        DataType::Float32 => compare_float!(left, l, right, r, Float32Array),
+        DataType::Float64 => compare_float!(left, l, right, r, Float64Array),
+        DataType::Utf8 => compare_typed!(left, l, right, r, StringArray),
+        DataType::LargeUtf8 => compare_typed!(left, l, right, r, LargeStringArray),
+        DataType::Date32(_) => compare_typed!(left, l, right, r, Date32Array),
+        DataType::Date64(_) => compare_typed!(left, l, right, r, Date64Array),
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            compare_typed!(left, l, right, r, TimestampSecondArray)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            compare_typed!(left, l, right, r, TimestampMillisecondArray)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            compare_typed!(left, l, right, r, TimestampMicrosecondArray)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            compare_typed!(left, l, right, r, TimestampNanosecondArray)
         }
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "Cannot compare values of type {:?}",
+                other
+            )))
+        }
+    })
+}
+
+/// The leading sort expressions of `ordering` that sort on columns of `schema`, by
+/// which the rows of data sorted by `ordering` remain sorted once they are projected
+/// to `schema`
+pub fn project_ordering(
+    ordering: &[PhysicalSortExpr],
+    schema: &Schema,
+) -> Vec<PhysicalSortExpr> {
+    ordering
+        .iter()
+        .take_while(|sort_expr| {
+            sort_expr
+                .expr
+                .as_any()
+                .downcast_ref::<Column>()
+                .map_or(false, |column| schema.index_of(column.name()).is_ok())
+        })
+        .cloned()
+        .collect()
+}
+
+/// A task spawned by [spawn_abortable], that is aborted when its handle is dropped, so
+/// that the task does not outlive the stream or the future that awaits it
+pub(crate) struct AbortOnDropHandle<T> {
+    join_handle: JoinHandle<std::result::Result<T, Aborted>>,
+    abort_handle: AbortHandle,
+}
+
+impl<T> Drop for AbortOnDropHandle<T> {
+    fn drop(&mut self) {
+        self.abort_handle.abort()
+    }
+}
+
+impl<T> Future for AbortOnDropHandle<T> {
+    type Output = Result<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.join_handle.poll_unpin(cx).map(|result| match result {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(Aborted)) => Err(DataFusionError::Internal(
+                "Task was aborted while it was awaited".to_string(),
+            )),
+            Err(e) => Err(DataFusionError::Execution(format!("Task failed: {}", e))),
+        })
     }
+}
+
+/// Spawns `future` on the tokio runtime, like `tokio::spawn`, but aborts it when the
+/// returned handle is dropped
+pub(crate) fn spawn_abortable<F>(future: F) -> AbortOnDropHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let (future, abort_handle) = future::abortable(future);
+    AbortOnDropHandle {
+        join_handle: tokio::spawn(future),
+        abort_handle,
+    }
+}
+
+/// Recursively build a list of files in a directory with a given extension
+pub fn build_file_list(dir: &str, filenames: &mut Vec<String>, ext: &str) -> Result<()> {
+    let files = block_on(list_files(&LocalFileSystem, dir, ext))?;
+    filenames.extend(files.into_iter().map(|file| file.path));
     Ok(())
 }
 
@@ -156,6 +304,10 @@ pub fn create_batch_empty(schema: &Schema) -> ArrowResult<RecordBatch> {
             DataType::LargeUtf8 => {
                 Ok(Arc::new(LargeStringArray::from(vec![] as Vec<&str>)) as ArrayRef)
             }
+            DataType::Dictionary(_, value_type) if **value_type == DataType::Utf8 => {
+                let values = Arc::new(StringArray::from(vec![] as Vec<&str>)) as ArrayRef;
+                Ok(arrow::compute::cast(&values, f.data_type())?)
+            }
             DataType::Boolean => {
                 Ok(Arc::new(BooleanArray::from(vec![] as Vec<bool>)) as ArrayRef)
             }
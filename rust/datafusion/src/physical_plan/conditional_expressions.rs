@@ -0,0 +1,190 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Conditional expressions, such as `coalesce` and `nullif`
+
+use crate::error::{DataFusionError, Result};
+use arrow::array::*;
+use arrow::datatypes::{DataType, TimeUnit};
+use std::sync::Arc;
+
+macro_rules! downcast_vec {
+    ($ARGS:expr, $ARRAY_TYPE:ident) => {{
+        $ARGS
+            .iter()
+            .map(|e| match e.as_any().downcast_ref::<$ARRAY_TYPE>() {
+                Some(array) => Ok(array),
+                _ => Err(DataFusionError::Internal("failed to downcast".to_string())),
+            })
+    }};
+}
+
+macro_rules! coalesce {
+    ($ARGS:expr, $ARRAY_TYPE:ident, $BUILDER_TYPE:ident) => {{
+        // downcast all arguments to their common format
+        let args =
+            downcast_vec!($ARGS, $ARRAY_TYPE).collect::<Result<Vec<&$ARRAY_TYPE>>>()?;
+
+        let mut builder = <$BUILDER_TYPE>::new(args[0].len());
+        for index in 0..args[0].len() {
+            match args.iter().find(|arg| arg.is_valid(index)) {
+                Some(arg) => builder.append_value(arg.value(index))?,
+                None => builder.append_null()?,
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }};
+}
+
+/// returns the first of its arguments that is not null, or null when all of them are.
+/// coalesce(NULL, 2, 3) = 2
+pub fn coalesce(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.is_empty() {
+        return Err(DataFusionError::Internal(
+            "coalesce requires at least one argument".to_string(),
+        ));
+    }
+
+    match args[0].data_type() {
+        DataType::Boolean => coalesce!(args, BooleanArray, BooleanBuilder),
+        DataType::Int8 => coalesce!(args, Int8Array, Int8Builder),
+        DataType::Int16 => coalesce!(args, Int16Array, Int16Builder),
+        DataType::Int32 => coalesce!(args, Int32Array, Int32Builder),
+        DataType::Int64 => coalesce!(args, Int64Array, Int64Builder),
+        DataType::UInt8 => coalesce!(args, UInt8Array, UInt8Builder),
+        DataType::UInt16 => coalesce!(args, UInt16Array, UInt16Builder),
+        DataType::UInt32 => coalesce!(args, UInt32Array, UInt32Builder),
+        DataType::UInt64 => coalesce!(args, UInt64Array, UInt64Builder),
+        DataType::Float32 => coalesce!(args, Float32Array, Float32Builder),
+        DataType::Float64 => coalesce!(args, Float64Array, Float64Builder),
+        DataType::Utf8 => coalesce!(args, StringArray, StringBuilder),
+        DataType::LargeUtf8 => coalesce!(args, LargeStringArray, LargeStringBuilder),
+        DataType::Timestamp(TimeUnit::Nanosecond, None) => {
+            coalesce!(args, TimestampNanosecondArray, TimestampNanosecondBuilder)
+        }
+        data_type => Err(DataFusionError::NotImplemented(format!(
+            "Coalesce is not implemented for type '{:?}'.",
+            data_type
+        ))),
+    }
+}
+
+macro_rules! nullif {
+    ($ARGS:expr, $ARRAY_TYPE:ident, $BUILDER_TYPE:ident) => {{
+        let args =
+            downcast_vec!($ARGS, $ARRAY_TYPE).collect::<Result<Vec<&$ARRAY_TYPE>>>()?;
+        let (values, others) = (args[0], args[1]);
+
+        let mut builder = <$BUILDER_TYPE>::new(values.len());
+        for index in 0..values.len() {
+            let equal = values.is_valid(index)
+                && others.is_valid(index)
+                && values.value(index) == others.value(index);
+            if values.is_null(index) || equal {
+                builder.append_null()?;
+            } else {
+                builder.append_value(values.value(index))?;
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }};
+}
+
+/// returns null when both of its arguments are equal, and its first argument otherwise.
+/// nullif(1, 1) = NULL, nullif(1, 2) = 1
+pub fn nullif(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.len() != 2 {
+        return Err(DataFusionError::Internal(format!(
+            "nullif was called with {} arguments. It requires 2.",
+            args.len()
+        )));
+    }
+
+    match args[0].data_type() {
+        DataType::Boolean => nullif!(args, BooleanArray, BooleanBuilder),
+        DataType::Int8 => nullif!(args, Int8Array, Int8Builder),
+        DataType::Int16 => nullif!(args, Int16Array, Int16Builder),
+        DataType::Int32 => nullif!(args, Int32Array, Int32Builder),
+        DataType::Int64 => nullif!(args, Int64Array, Int64Builder),
+        DataType::UInt8 => nullif!(args, UInt8Array, UInt8Builder),
+        DataType::UInt16 => nullif!(args, UInt16Array, UInt16Builder),
+        DataType::UInt32 => nullif!(args, UInt32Array, UInt32Builder),
+        DataType::UInt64 => nullif!(args, UInt64Array, UInt64Builder),
+        DataType::Float32 => nullif!(args, Float32Array, Float32Builder),
+        DataType::Float64 => nullif!(args, Float64Array, Float64Builder),
+        DataType::Utf8 => nullif!(args, StringArray, StringBuilder),
+        DataType::LargeUtf8 => nullif!(args, LargeStringArray, LargeStringBuilder),
+        DataType::Timestamp(TimeUnit::Nanosecond, None) => {
+            nullif!(args, TimestampNanosecondArray, TimestampNanosecondBuilder)
+        }
+        data_type => Err(DataFusionError::NotImplemented(format!(
+            "Nullif is not implemented for type '{:?}'.",
+            data_type
+        ))),
+    }
+}
+
+/// Currently supported types by the conditional functions.
+/// The order of these types correspond to the order on which coercion applies
+/// This should thus be from least informative to most informative
+pub static SUPPORTED_CONDITIONAL_TYPES: &'static [DataType] = &[
+    DataType::Boolean,
+    DataType::UInt8,
+    DataType::UInt16,
+    DataType::UInt32,
+    DataType::UInt64,
+    DataType::Int8,
+    DataType::Int16,
+    DataType::Int32,
+    DataType::Int64,
+    DataType::Float32,
+    DataType::Float64,
+    DataType::Utf8,
+    DataType::LargeUtf8,
+    DataType::Timestamp(TimeUnit::Nanosecond, None),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coalesce_first_non_null() -> Result<()> {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None, None]));
+        let b: ArrayRef = Arc::new(Int32Array::from(vec![Some(10), Some(20), None]));
+        let result = coalesce(&[a, b])?;
+        let result = result.as_any().downcast_ref::<Int32Array>().unwrap();
+
+        assert_eq!(result.value(0), 1);
+        assert_eq!(result.value(1), 20);
+        assert!(result.is_null(2));
+        Ok(())
+    }
+
+    #[test]
+    fn nullif_equal_values() -> Result<()> {
+        let a: ArrayRef = Arc::new(StringArray::from(vec![Some("a"), Some("b"), None]));
+        let b: ArrayRef = Arc::new(StringArray::from(vec![Some("a"), None, Some("c")]));
+        let result = nullif(&[a, b])?;
+        let result = result.as_any().downcast_ref::<StringArray>().unwrap();
+
+        assert!(result.is_null(0));
+        assert_eq!(result.value(1), "b");
+        assert!(result.is_null(2));
+        Ok(())
+    }
+}
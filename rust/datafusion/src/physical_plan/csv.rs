@@ -18,23 +18,32 @@
 //! Execution plan for reading CSV files
 
 use std::any::Any;
-use std::fs::File;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use crate::datasource::object_store::{
+    list_files, run_on_store_thread, spawn_store_thread, LocalFileSystem, ObjectMeta,
+    ObjectReader, ObjectStore,
+};
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::ExecutionPlan;
-use crate::physical_plan::{common, Partitioning};
+use crate::physical_plan::Partitioning;
 use arrow::csv;
 use arrow::datatypes::{Schema, SchemaRef};
 use arrow::error::Result as ArrowResult;
 use arrow::record_batch::RecordBatch;
-use futures::Stream;
+use futures::channel::mpsc::{channel, Receiver, Sender};
+use futures::executor::block_on;
+use futures::{SinkExt, Stream, StreamExt};
 
+use super::metrics::{BaselineMetrics, MetricsSet, ObservedStream};
 use super::{RecordBatchStream, SendableRecordBatchStream};
 use async_trait::async_trait;
 
+/// The default target size of the byte ranges that CSV files are split into
+pub const DEFAULT_TARGET_PARTITION_SIZE: u64 = 128 * 1024 * 1024;
+
 /// CSV file read option
 #[derive(Copy, Clone)]
 pub struct CsvReadOptions<'a> {
@@ -53,6 +62,14 @@ pub struct CsvReadOptions<'a> {
     /// File extension; only files with this extension are selected for data input.
     /// Defaults to ".csv".
     pub file_extension: &'a str,
+    /// The target size, in bytes, of the partitions that a scan splits large files
+    /// into, when `newlines_in_values` is false. Defaults to 128 MiB.
+    pub target_partition_size: u64,
+    /// Whether quoted values may contain line breaks. Files are then not split, as a
+    /// partition cannot tell whether a line break within its range ends a record.
+    /// Defaults to true, so files are only split when the caller states that their
+    /// values have no line breaks.
+    pub newlines_in_values: bool,
 }
 
 impl<'a> CsvReadOptions<'a> {
@@ -64,6 +81,8 @@ impl<'a> CsvReadOptions<'a> {
             schema_infer_max_records: 1000,
             delimiter: b',',
             file_extension: ".csv",
+            target_partition_size: DEFAULT_TARGET_PARTITION_SIZE,
+            newlines_in_values: true,
         }
     }
 
@@ -107,6 +126,19 @@ impl<'a> CsvReadOptions<'a> {
         self.schema_infer_max_records = max_records;
         self
     }
+
+    /// Specify the target size, in bytes, of the partitions that files are split into
+    pub fn target_partition_size(mut self, target_partition_size: u64) -> Self {
+        self.target_partition_size = target_partition_size;
+        self
+    }
+
+    /// Configure whether quoted values may contain line breaks; large files are only
+    /// split into several partitions when they may not
+    pub fn newlines_in_values(mut self, newlines_in_values: bool) -> Self {
+        self.newlines_in_values = newlines_in_values;
+        self
+    }
 }
 
 /// Execution plan for scanning a CSV file
@@ -116,6 +148,12 @@ pub struct CsvExec {
     path: String,
     /// The individual files under path
     filenames: Vec<String>,
+    /// The byte ranges of the files that are read by each partition
+    partitions: Vec<FileRange>,
+    /// The target size, in bytes, of the partitions
+    target_partition_size: u64,
+    /// Whether quoted values may contain line breaks
+    newlines_in_values: bool,
     /// Schema representing the CSV file
     schema: SchemaRef,
     /// Does the CSV file have a header?
@@ -130,6 +168,10 @@ pub struct CsvExec {
     projected_schema: SchemaRef,
     /// Batch size
     batch_size: usize,
+    /// The store that the files are read from
+    object_store: Arc<dyn ObjectStore>,
+    /// Execution metrics
+    metrics: BaselineMetrics,
 }
 
 impl CsvExec {
@@ -140,17 +182,29 @@ impl CsvExec {
         projection: Option<Vec<usize>>,
         batch_size: usize,
     ) -> Result<Self> {
-        let file_extension = String::from(options.file_extension);
+        Self::try_new_with_object_store(
+            path,
+            options,
+            projection,
+            batch_size,
+            Arc::new(LocalFileSystem),
+        )
+    }
 
-        let mut filenames: Vec<String> = vec![];
-        common::build_file_list(path, &mut filenames, file_extension.as_str())?;
-        if filenames.is_empty() {
-            return Err(DataFusionError::Execution("No files found".to_string()));
-        }
+    /// Create a new execution plan for reading a set of CSV files from `object_store`
+    pub fn try_new_with_object_store(
+        path: &str,
+        options: CsvReadOptions,
+        projection: Option<Vec<usize>>,
+        batch_size: usize,
+        object_store: Arc<dyn ObjectStore>,
+    ) -> Result<Self> {
+        let file_extension = String::from(options.file_extension);
 
-        let schema = match options.schema {
-            Some(s) => s.clone(),
-            None => CsvExec::try_infer_schema(&filenames, &options)?,
+        let (files, schema) = Self::list_files_and_schema(&object_store, path, &options)?;
+        let schema = match schema {
+            Some(schema) if !files.is_empty() => schema,
+            _ => return Err(DataFusionError::Execution("No files found".to_string())),
         };
 
         let projected_schema = match &projection {
@@ -158,9 +212,23 @@ impl CsvExec {
             Some(p) => Schema::new(p.iter().map(|i| schema.field(*i).clone()).collect()),
         };
 
+        let partitions = files
+            .iter()
+            .flat_map(|file| {
+                FileRange::split(
+                    file,
+                    options.target_partition_size,
+                    options.newlines_in_values,
+                )
+            })
+            .collect();
+
         Ok(Self {
             path: path.to_string(),
-            filenames,
+            filenames: files.into_iter().map(|file| file.path).collect(),
+            partitions,
+            target_partition_size: options.target_partition_size,
+            newlines_in_values: options.newlines_in_values,
             schema: Arc::new(schema),
             has_header: options.has_header,
             delimiter: Some(options.delimiter),
@@ -168,6 +236,8 @@ impl CsvExec {
             projection,
             projected_schema: Arc::new(projected_schema),
             batch_size,
+            object_store,
+            metrics: BaselineMetrics::new(),
         })
     }
 
@@ -183,6 +253,124 @@ impl CsvExec {
             options.has_header,
         )?)
     }
+
+    /// Lists the CSV files of `object_store` under `path`, and returns them with the
+    /// schema of `options`, or else with the schema inferred from them if there are
+    /// any. The store is called from a thread of its own, see [run_on_store_thread].
+    pub(crate) fn list_files_and_schema(
+        object_store: &Arc<dyn ObjectStore>,
+        path: &str,
+        options: &CsvReadOptions,
+    ) -> Result<(Vec<ObjectMeta>, Option<Schema>)> {
+        let object_store = object_store.clone();
+        let path = path.to_string();
+        let file_extension = options.file_extension.to_string();
+        let schema = options.schema.cloned();
+        let has_header = options.has_header;
+        let delimiter = options.delimiter;
+        let schema_infer_max_records = options.schema_infer_max_records;
+        run_on_store_thread(move || {
+            let files =
+                block_on(list_files(object_store.as_ref(), &path, &file_extension))?;
+            let schema = match schema {
+                Some(schema) => Some(schema),
+                None if files.is_empty() => None,
+                None => {
+                    let options = CsvReadOptions::new()
+                        .has_header(has_header)
+                        .delimiter(delimiter)
+                        .schema_infer_max_records(schema_infer_max_records);
+                    Some(Self::infer_schema(&object_store, &files, &options)?)
+                }
+            };
+            Ok((files, schema))
+        })
+    }
+
+    /// Infer schema for the given CSV files of `object_store`, reading them in order
+    /// until `options.schema_infer_max_records` records have been read. This blocks on
+    /// the store, see [ObjectReader].
+    pub fn infer_schema(
+        object_store: &Arc<dyn ObjectStore>,
+        files: &[ObjectMeta],
+        options: &CsvReadOptions,
+    ) -> Result<Schema> {
+        let mut schemas = vec![];
+        let mut records_to_read = options.schema_infer_max_records;
+        for file in files {
+            let mut reader = ObjectReader::new(object_store.clone(), file.clone());
+            let (schema, records_read) = csv::infer_reader_schema(
+                &mut reader,
+                options.delimiter,
+                Some(records_to_read),
+                options.has_header,
+            )?;
+            if records_read == 0 {
+                continue;
+            }
+            schemas.push(schema);
+            records_to_read -= records_read;
+            if records_to_read == 0 {
+                break;
+            }
+        }
+        Ok(Schema::try_merge(&schemas)?)
+    }
+
+    /// Path to directory containing partitioned CSV files with the same schema
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The individual files under path
+    pub fn filenames(&self) -> &[String] {
+        &self.filenames
+    }
+
+    /// The target size, in bytes, of the partitions that files are split into
+    pub fn target_partition_size(&self) -> u64 {
+        self.target_partition_size
+    }
+
+    /// Whether quoted values may contain line breaks
+    pub fn newlines_in_values(&self) -> bool {
+        self.newlines_in_values
+    }
+
+    /// Schema representing the CSV file, before the projection is applied
+    pub fn file_schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Does the CSV file have a header?
+    pub fn has_header(&self) -> bool {
+        self.has_header
+    }
+
+    /// An optional column delimiter. Defaults to `b','`
+    pub fn delimiter(&self) -> Option<u8> {
+        self.delimiter
+    }
+
+    /// File extension
+    pub fn file_extension(&self) -> &str {
+        &self.file_extension
+    }
+
+    /// Optional projection for which columns to load
+    pub fn projection(&self) -> Option<&Vec<usize>> {
+        self.projection.as_ref()
+    }
+
+    /// Batch size
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// The store that the files are read from
+    pub fn object_store(&self) -> &Arc<dyn ObjectStore> {
+        &self.object_store
+    }
 }
 
 #[async_trait]
@@ -199,7 +387,7 @@ impl ExecutionPlan for CsvExec {
 
     /// Get the output partitioning of this plan
     fn output_partitioning(&self) -> Partitioning {
-        Partitioning::UnknownPartitioning(self.filenames.len())
+        Partitioning::UnknownPartitioning(self.partitions.len())
     }
 
     fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
@@ -222,71 +410,144 @@ impl ExecutionPlan for CsvExec {
     }
 
     async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
-        Ok(Box::pin(CsvStream::try_new(
-            &self.filenames[partition],
-            self.schema.clone(),
-            self.has_header,
-            self.delimiter,
-            &self.projection,
-            self.batch_size,
-        )?))
+        let range = self.partitions.get(partition).ok_or_else(|| {
+            DataFusionError::Internal(format!("CsvExec invalid partition {}", partition))
+        })?;
+        let mut builder = csv::ReaderBuilder::new()
+            .with_schema(self.schema.clone())
+            .has_header(self.has_header)
+            .with_batch_size(self.batch_size);
+        if let Some(delimiter) = self.delimiter {
+            builder = builder.with_delimiter(delimiter);
+        }
+        if let Some(projection) = &self.projection {
+            builder = builder.with_projection(projection.clone());
+        }
+        // the CSV reader blocks on the store, so it runs on a thread of its own and
+        // sends the batches through a channel. The thread stops once the stream, and so
+        // the receiver, is dropped.
+        let (mut response_tx, response_rx) = channel(2);
+        let reader = ObjectReader::new(self.object_store.clone(), range.file.clone());
+        let (start, end) = (range.start, range.end);
+        spawn_store_thread(move || {
+            match builder.build_byte_range(reader, start, end) {
+                Ok(reader) => {
+                    for batch in reader {
+                        if !send_result(&mut response_tx, batch) {
+                            // the stream was dropped
+                            break;
+                        }
+                    }
+                }
+                Err(e) => {
+                    send_result(&mut response_tx, Err(e));
+                }
+            }
+        });
+        let stream = CsvStream {
+            schema: self.projected_schema.clone(),
+            response_rx,
+        };
+        Ok(Box::pin(ObservedStream::new(
+            Box::pin(stream),
+            self.metrics.clone(),
+        )))
     }
-}
 
-/// Iterator over batches
-struct CsvStream {
-    /// Arrow CSV reader
-    reader: csv::Reader<File>,
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
 }
 
-impl CsvStream {
-    /// Create an iterator for a CSV file
-    pub fn try_new(
-        filename: &str,
-        schema: SchemaRef,
-        has_header: bool,
-        delimiter: Option<u8>,
-        projection: &Option<Vec<usize>>,
-        batch_size: usize,
-    ) -> Result<Self> {
-        let file = File::open(filename)?;
-        let reader = csv::Reader::new(
-            file,
-            schema.clone(),
-            has_header,
-            delimiter,
-            batch_size,
-            None,
-            projection.clone(),
-        );
+/// A byte range of a CSV file, which is read by a partition of a [CsvExec]. The
+/// partition reads the records that start within the range.
+#[derive(Debug, Clone)]
+struct FileRange {
+    file: ObjectMeta,
+    start: u64,
+    end: u64,
+}
 
-        Ok(Self { reader })
+impl FileRange {
+    /// Split `file` into ranges of about `target_size` bytes, or into a single range
+    /// if its values may contain line breaks
+    fn split(file: &ObjectMeta, target_size: u64, newlines_in_values: bool) -> Vec<Self> {
+        let count = if newlines_in_values {
+            1
+        } else {
+            // ceiling division, without overflowing
+            let target_size = target_size.max(1);
+            (file.size / target_size + (file.size % target_size != 0) as u64).max(1)
+        };
+        // ranges of equal sizes, rather than a smaller last one
+        (0..count)
+            .map(|i| Self {
+                file: file.clone(),
+                start: range_bound(file.size, i, count),
+                end: range_bound(file.size, i + 1, count),
+            })
+            .collect()
     }
 }
 
+/// The offset at which the range `i` of the `count` ranges of a file of `size` bytes
+/// starts
+fn range_bound(size: u64, i: u64, count: u64) -> u64 {
+    (size as u128 * i as u128 / count as u128) as u64
+}
+
+/// Sends `result` to the stream, waiting while its buffer is full. Returns false if the
+/// stream was dropped, in which case nothing more needs to be read.
+fn send_result(
+    response_tx: &mut Sender<ArrowResult<RecordBatch>>,
+    result: ArrowResult<RecordBatch>,
+) -> bool {
+    block_on(response_tx.send(result)).is_ok()
+}
+
+/// Stream of the batches read by the reader thread of a partition
+struct CsvStream {
+    schema: SchemaRef,
+    response_rx: Receiver<ArrowResult<RecordBatch>>,
+}
+
 impl Stream for CsvStream {
     type Item = ArrowResult<RecordBatch>;
 
     fn poll_next(
         mut self: Pin<&mut Self>,
-        _: &mut Context<'_>,
+        cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        Poll::Ready(self.reader.next())
+        self.response_rx.poll_next_unpin(cx)
     }
 }
 
 impl RecordBatchStream for CsvStream {
     /// Get the schema
     fn schema(&self) -> SchemaRef {
-        self.reader.schema()
+        self.schema.clone()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test::{aggr_test_schema, arrow_testdata_path};
+    use crate::physical_plan::common;
+    use crate::test::{aggr_test_schema, arrow_testdata_path, format_batch};
     use futures::StreamExt;
+    use tempfile::TempDir;
+
+    /// The rows of all the partitions of `csv`, formatted and sorted
+    async fn read_partitions(csv: &CsvExec) -> Result<Vec<String>> {
+        let mut rows = vec![];
+        for partition in 0..csv.output_partitioning().partition_count() {
+            for batch in common::collect(csv.execute(partition).await?).await? {
+                rows.extend(format_batch(&batch));
+            }
+        }
+        rows.sort();
+        Ok(rows)
+    }
 
     #[tokio::test]
     async fn csv_exec_with_projection() -> Result<()> {
@@ -335,4 +596,42 @@ mod tests {
         assert_eq!("c3", batch_schema.field(2).name());
         Ok(())
     }
+
+    #[tokio::test]
+    async fn csv_exec_with_byte_ranges() -> Result<()> {
+        let schema = aggr_test_schema();
+        let path = format!("{}/csv/aggregate_test_100.csv", arrow_testdata_path());
+        let options = CsvReadOptions::new()
+            .schema(&schema)
+            .newlines_in_values(false);
+        let csv = CsvExec::try_new(&path, options, Some(vec![0, 2]), 1024)?;
+        assert_eq!(csv.output_partitioning().partition_count(), 1);
+        let expected = read_partitions(&csv).await?;
+        assert_eq!(expected.len(), 100);
+
+        // the header is only read by the first range, and every row once
+        let options = options.target_partition_size(1000);
+        let csv = CsvExec::try_new(&path, options, Some(vec![0, 2]), 1024)?;
+        assert!(csv.output_partitioning().partition_count() > 1);
+        assert_eq!(read_partitions(&csv).await?, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn csv_exec_with_newlines_in_values() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.path().join("data.csv");
+        std::fs::write(&path, "a,b\n1,\"x\ny\"\n2,z\n3,\"u\n4,v\n\"\n")?;
+        let path = path.to_str().unwrap();
+
+        // files are not split by default, however small the partitions
+        let options = CsvReadOptions::new().target_partition_size(4);
+        let csv = CsvExec::try_new(path, options, None, 1024)?;
+        assert_eq!(csv.output_partitioning().partition_count(), 1);
+        assert_eq!(
+            read_partitions(&csv).await?,
+            vec!["1,x\ny", "2,z", "3,u\n4,v\n"]
+        );
+        Ok(())
+    }
 }
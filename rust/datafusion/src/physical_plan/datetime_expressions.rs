@@ -17,15 +17,34 @@
 
 //! DateTime expressions
 
+use std::any::type_name;
+use std::convert::TryFrom;
+use std::fmt::{Display, Write};
 use std::sync::Arc;
 
 use crate::error::{DataFusionError, Result};
+use crate::physical_plan::functions::ColumnarValue;
+use crate::scalar::ScalarValue;
 use arrow::{
-    array::{Array, ArrayData, ArrayRef, StringArray, TimestampNanosecondArray},
+    array::{
+        Array, ArrayData, ArrayRef, Date32Array, DurationNanosecondArray, Int32Array,
+        IntervalDayTimeArray, IntervalYearMonthArray, StringArray,
+        TimestampNanosecondArray,
+    },
     buffer::Buffer,
-    datatypes::{DataType, TimeUnit, ToByteSlice},
+    datatypes::{DataType, IntervalUnit, TimeUnit, ToByteSlice},
 };
-use chrono::{prelude::*, LocalResult};
+use chrono::{prelude::*, Duration, LocalResult};
+use chrono_tz::Tz;
+
+/// Number of nanoseconds in a millisecond
+const NANOS_PER_MILLI: i64 = 1_000_000;
+/// Number of nanoseconds in a second
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+/// Number of milliseconds in a day
+const MILLIS_PER_DAY: i64 = 86_400_000;
+/// Number of nanoseconds in a day
+const NANOS_PER_DAY: i64 = MILLIS_PER_DAY * NANOS_PER_MILLI;
 
 #[inline]
 /// Accepts a string in RFC3339 / ISO8601 standard format and some
@@ -78,7 +97,7 @@ use chrono::{prelude::*, LocalResult};
 /// the system timezone is set to Americas/New_York (UTC-5) the
 /// timestamp will be interpreted as though it were
 /// `1997-01-31T09:26:56.123-05:00`
-fn string_to_timestamp_nanos(s: &str) -> Result<i64> {
+pub(crate) fn string_to_timestamp_nanos(s: &str) -> Result<i64> {
     // Fast path:  RFC3339 timestamp (with a T)
     // Example: 2020-09-08T13:42:29.190855Z
     if let Ok(ts) = DateTime::parse_from_rfc3339(s) {
@@ -205,6 +224,437 @@ pub fn to_timestamp(args: &[ArrayRef]) -> Result<TimestampNanosecondArray> {
     Ok(TimestampNanosecondArray::from(Arc::new(data)))
 }
 
+/// downcasts an argument to `T`, returning an internal error on failure.
+fn downcast_arg<'a, T: Array + 'static>(arg: &'a ArrayRef, name: &str) -> Result<&'a T> {
+    arg.as_any().downcast_ref::<T>().ok_or_else(|| {
+        DataFusionError::Internal(format!(
+            "could not cast {} to {}",
+            name,
+            type_name::<T>()
+        ))
+    })
+}
+
+/// The timestamps of the argument `arg`, a `Timestamp(Nanosecond, None)` or a `Date32`
+/// array. Dates are converted to the timestamps of their midnight in UTC.
+fn timestamp_arg(arg: &ArrayRef) -> Result<TimestampNanosecondArray> {
+    match arg.data_type() {
+        DataType::Date32(_) => downcast_arg::<Date32Array>(arg, "date")?
+            .iter()
+            .map(|days| match days {
+                Some(days) => (days as i64)
+                    .checked_mul(NANOS_PER_DAY)
+                    .map(Some)
+                    .ok_or_else(timestamp_overflow),
+                None => Ok(None),
+            })
+            .collect(),
+        _ => {
+            // the data is shared once its type is checked
+            downcast_arg::<TimestampNanosecondArray>(arg, "timestamp")?;
+            Ok(TimestampNanosecondArray::from(arg.data()))
+        }
+    }
+}
+
+/// Converts a nanosecond timestamp to a datetime in UTC.
+fn naive_from_nanos(value: i64) -> NaiveDateTime {
+    NaiveDateTime::from_timestamp(
+        value.div_euclid(NANOS_PER_SECOND),
+        value.rem_euclid(NANOS_PER_SECOND) as u32,
+    )
+}
+
+/// The nanosecond timestamp of `datetime`, or `None` if it is out of the range of
+/// timestamps
+fn naive_to_nanos(datetime: NaiveDateTime) -> Option<i64> {
+    datetime
+        .timestamp()
+        .checked_mul(NANOS_PER_SECOND)?
+        .checked_add(datetime.timestamp_subsec_nanos() as i64)
+}
+
+/// Truncates the timestamp `value` to the start of its `granularity`
+fn date_trunc_single(granularity: &str, value: i64) -> Result<i64> {
+    let value = naive_from_nanos(value);
+    let date = value.date();
+    let first_of_month = |year, month| NaiveDate::from_ymd_opt(year, month, 1);
+    let truncated = match granularity.to_lowercase().as_str() {
+        "second" => date.and_hms_opt(value.hour(), value.minute(), value.second()),
+        "minute" => date.and_hms_opt(value.hour(), value.minute(), 0),
+        "hour" => date.and_hms_opt(value.hour(), 0, 0),
+        "day" => date.and_hms_opt(0, 0, 0),
+        // weeks start on Monday, as in ISO 8601
+        "week" => {
+            let days_since_monday = date.weekday().num_days_from_monday() as i64;
+            date.checked_sub_signed(Duration::days(days_since_monday))
+                .and_then(|monday| monday.and_hms_opt(0, 0, 0))
+        }
+        "month" => first_of_month(date.year(), date.month())
+            .and_then(|first| first.and_hms_opt(0, 0, 0)),
+        "quarter" => {
+            let month = (date.month0() / 3) * 3 + 1;
+            first_of_month(date.year(), month)
+                .and_then(|first| first.and_hms_opt(0, 0, 0))
+        }
+        "year" => {
+            first_of_month(date.year(), 1).and_then(|first| first.and_hms_opt(0, 0, 0))
+        }
+        unsupported => {
+            return Err(DataFusionError::Execution(format!(
+                "Unsupported date_trunc granularity: {}",
+                unsupported
+            )))
+        }
+    };
+    truncated
+        .and_then(naive_to_nanos)
+        .ok_or_else(timestamp_overflow)
+}
+
+/// date_trunc(granularity, timestamp): truncates each timestamp to the start of the
+/// `second`, `minute`, `hour`, `day`, `week`, `month`, `quarter` or `year` it belongs to.
+/// Timestamps are truncated in UTC, and dates are truncated as their midnight.
+pub fn date_trunc(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let granularities = downcast_arg::<StringArray>(&args[0], "granularity")?;
+    let timestamps = timestamp_arg(&args[1])?;
+
+    let result = granularities
+        .iter()
+        .zip(timestamps.iter())
+        .map(|(granularity, value)| match (granularity, value) {
+            (Some(granularity), Some(value)) => {
+                date_trunc_single(granularity, value).map(Some)
+            }
+            _ => Ok(None),
+        })
+        .collect::<Result<TimestampNanosecondArray>>()?;
+
+    Ok(Arc::new(result))
+}
+
+/// Extracts `field` from the timestamp `value`
+fn date_part_single(field: &str, value: i64) -> Result<i32> {
+    let value = naive_from_nanos(value);
+    Ok(match field.to_lowercase().as_str() {
+        "year" => value.year(),
+        "quarter" => (value.month0() / 3 + 1) as i32,
+        "month" => value.month() as i32,
+        "week" => value.iso_week().week() as i32,
+        "day" => value.day() as i32,
+        // day of the week, from Sunday (0) to Saturday (6)
+        "dow" => value.weekday().num_days_from_sunday() as i32,
+        // day of the year, starting from 1
+        "doy" => value.ordinal() as i32,
+        "hour" => value.hour() as i32,
+        "minute" => value.minute() as i32,
+        "second" => value.second() as i32,
+        unsupported => {
+            return Err(DataFusionError::Execution(format!(
+                "Unsupported date_part field: {}",
+                unsupported
+            )))
+        }
+    })
+}
+
+/// date_part(field, timestamp): extracts a field from each timestamp (in UTC) or date.
+/// Supported fields are `year`, `quarter`, `month`, `week`, `day`, `dow`,
+/// `doy`, `hour`, `minute` and `second`. Also used by `EXTRACT(field FROM timestamp)`.
+pub fn date_part(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let fields = downcast_arg::<StringArray>(&args[0], "field")?;
+    let timestamps = timestamp_arg(&args[1])?;
+
+    let result = fields
+        .iter()
+        .zip(timestamps.iter())
+        .map(|(field, value)| match (field, value) {
+            (Some(field), Some(value)) => date_part_single(field, value).map(Some),
+            _ => Ok(None),
+        })
+        .collect::<Result<Int32Array>>()?;
+
+    Ok(Arc::new(result))
+}
+
+/// Parses a fixed UTC offset such as `+05:30`, `-08` or `+0100`
+fn parse_fixed_offset(timezone: &str) -> Option<FixedOffset> {
+    let sign = match timezone.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits = timezone[1..].replace(":", "");
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (
+            digits[..2].parse::<i32>().ok()?,
+            digits[2..].parse::<i32>().ok()?,
+        ),
+        _ => return None,
+    };
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Writes `datetime` in `timezone` using the strftime-like `format`
+fn write_in_timezone<T: TimeZone>(
+    result: &mut String,
+    datetime: &DateTime<Utc>,
+    timezone: &T,
+    format: &str,
+) -> std::fmt::Result
+where
+    T::Offset: Display,
+{
+    write!(
+        result,
+        "{}",
+        datetime.with_timezone(timezone).format(format)
+    )
+}
+
+/// Formats the timestamp `value` according to `format`, in `timezone` (or UTC)
+fn to_char_single(value: i64, format: &str, timezone: Option<&str>) -> Result<String> {
+    let datetime = DateTime::<Utc>::from_utc(naive_from_nanos(value), Utc);
+    let mut result = String::new();
+    let written = match timezone {
+        None => write_in_timezone(&mut result, &datetime, &Utc, format),
+        Some(timezone) => {
+            if let Ok(tz) = timezone.parse::<Tz>() {
+                write_in_timezone(&mut result, &datetime, &tz, format)
+            } else if let Some(offset) = parse_fixed_offset(timezone) {
+                write_in_timezone(&mut result, &datetime, &offset, format)
+            } else {
+                return Err(DataFusionError::Execution(format!(
+                    "Unknown time zone '{}'",
+                    timezone
+                )));
+            }
+        }
+    };
+    // chrono reports unknown format specifiers as a formatting error
+    written.map_err(|_| {
+        DataFusionError::Execution(format!("Invalid format string '{}'", format))
+    })?;
+    Ok(result)
+}
+
+/// to_char(timestamp, format [, timezone]): formats each timestamp as a string.
+///
+/// `format` uses `strftime`-style specifiers, such as `%Y-%m-%d %H:%M:%S`, and the
+/// optional `timezone` is either a name from the IANA database (e.g. `Europe/Paris`)
+/// or a fixed offset (e.g. `+05:30`). Timestamps are formatted in UTC by default, and
+/// dates as their midnight.
+pub fn to_char(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let timestamps = timestamp_arg(&args[0])?;
+    let formats = downcast_arg::<StringArray>(&args[1], "format")?;
+    let timezones = match args.len() {
+        2 => None,
+        3 => Some(downcast_arg::<StringArray>(&args[2], "timezone")?),
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "to_char was called with {} arguments. It requires 2 or 3.",
+                other
+            )))
+        }
+    };
+
+    let result = (0..timestamps.len())
+        .map(|i| {
+            let timezone = match timezones {
+                Some(timezones) if timezones.is_null(i) => return Ok(None),
+                Some(timezones) => Some(timezones.value(i)),
+                None => None,
+            };
+            if timestamps.is_null(i) || formats.is_null(i) {
+                return Ok(None);
+            }
+            to_char_single(timestamps.value(i), formats.value(i), timezone).map(Some)
+        })
+        .collect::<Result<StringArray>>()?;
+
+    Ok(Arc::new(result))
+}
+
+/// Returns the number of days in `month` of `year`, or `None` if the month is out of
+/// the range of dates
+fn days_in_month(year: i32, month: u32) -> Option<u32> {
+    let (next_year, next_month) = if month == 12 {
+        (year.checked_add(1)?, 1)
+    } else {
+        (year, month + 1)
+    };
+    Some(
+        NaiveDate::from_ymd_opt(next_year, next_month, 1)?
+            .pred_opt()?
+            .day(),
+    )
+}
+
+/// Shifts `datetime` by a number of months, clamping the day to the end of the
+/// resulting month (e.g. January 31st plus one month is February 28th or 29th).
+/// Returns `None` if the result is out of the range of dates.
+fn shift_months(datetime: NaiveDateTime, months: i32) -> Option<NaiveDateTime> {
+    let total = (datetime.year() as i64 * 12 + datetime.month0() as i64)
+        .checked_add(months as i64)?;
+    let year = i32::try_from(total.div_euclid(12)).ok()?;
+    let month = total.rem_euclid(12) as u32 + 1;
+    let day = datetime.day().min(days_in_month(year, month)?);
+    Some(NaiveDate::from_ymd_opt(year, month, day)?.and_time(datetime.time()))
+}
+
+/// Shifts the nanosecond timestamp `value` by a number of months, like
+/// [shift_months]. Returns `None` if the result is out of the range of timestamps.
+pub(crate) fn shift_months_nanos(value: i64, months: i32) -> Option<i64> {
+    shift_months(naive_from_nanos(value), months).and_then(naive_to_nanos)
+}
+
+fn timestamp_overflow() -> DataFusionError {
+    DataFusionError::Execution("Timestamp out of range".to_string())
+}
+
+/// Adds `intervals` to `timestamps`, or subtracts them when `negate` is true.
+///
+/// Day-time intervals are added as an exact number of milliseconds, while year-month
+/// intervals move timestamps by calendar months, in UTC. The result keeps the time zone
+/// of `timestamps`.
+pub fn timestamp_add_interval(
+    timestamps: &ArrayRef,
+    intervals: &ArrayRef,
+    negate: bool,
+) -> Result<ArrayRef> {
+    let timezone = match timestamps.data_type() {
+        DataType::Timestamp(_, timezone) => timezone.clone(),
+        _ => None,
+    };
+    let timestamps = downcast_arg::<TimestampNanosecondArray>(timestamps, "timestamp")?;
+    let sign = if negate { -1 } else { 1 };
+
+    let result = match intervals.data_type() {
+        DataType::Interval(IntervalUnit::DayTime) => {
+            let intervals = downcast_arg::<IntervalDayTimeArray>(intervals, "interval")?;
+            timestamps
+                .iter()
+                .zip(intervals.iter())
+                .map(|(value, interval)| match (value, interval) {
+                    (Some(value), Some(interval)) => {
+                        // the days are in the upper 32 bits, the milliseconds in the lower
+                        let days = (interval >> 32) as i32 as i64;
+                        let millis = interval as i32 as i64;
+                        (days * MILLIS_PER_DAY + millis)
+                            .checked_mul(NANOS_PER_MILLI * sign)
+                            .and_then(|delta| value.checked_add(delta))
+                            .map(Some)
+                            .ok_or_else(timestamp_overflow)
+                    }
+                    _ => Ok(None),
+                })
+                .collect::<Result<Vec<_>>>()?
+        }
+        DataType::Interval(IntervalUnit::YearMonth) => {
+            let intervals =
+                downcast_arg::<IntervalYearMonthArray>(intervals, "interval")?;
+            timestamps
+                .iter()
+                .zip(intervals.iter())
+                .map(|(value, months)| match (value, months) {
+                    (Some(value), Some(months)) => months
+                        .checked_mul(sign as i32)
+                        .and_then(|months| shift_months_nanos(value, months))
+                        .map(Some)
+                        .ok_or_else(timestamp_overflow),
+                    _ => Ok(None),
+                })
+                .collect::<Result<Vec<_>>>()?
+        }
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "Cannot add {:?} to a timestamp",
+                other
+            )))
+        }
+    };
+
+    Ok(Arc::new(TimestampNanosecondArray::from_opt_vec(
+        result, timezone,
+    )))
+}
+
+/// Subtracts `rhs` from `lhs`, returning the elapsed time as a nanosecond `Duration`.
+pub fn timestamp_difference(lhs: &ArrayRef, rhs: &ArrayRef) -> Result<ArrayRef> {
+    let lhs = downcast_arg::<TimestampNanosecondArray>(lhs, "timestamp")?;
+    let rhs = downcast_arg::<TimestampNanosecondArray>(rhs, "timestamp")?;
+
+    let result = lhs
+        .iter()
+        .zip(rhs.iter())
+        .map(|(lhs, rhs)| match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => lhs
+                .checked_sub(rhs)
+                .map(Some)
+                .ok_or_else(timestamp_overflow),
+            _ => Ok(None),
+        })
+        .collect::<Result<DurationNanosecondArray>>()?;
+
+    Ok(Arc::new(result))
+}
+
+/// The start of the tumbling window of a timestamp. The windows have the length of
+/// the interval and the first one starts at the Unix epoch, e.g.
+///
+/// `tumble(TIMESTAMP '2021-01-01 10:07:00', INTERVAL '5 minutes')` is
+/// `2021-01-01 10:05:00`
+pub fn tumble(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    let length = match &args[1] {
+        ColumnarValue::Scalar(ScalarValue::IntervalDayTime(Some(interval))) => {
+            // the days are in the upper 32 bits, the milliseconds in the lower
+            let days = (*interval >> 32) as i32 as i64;
+            let millis = *interval as i32 as i64;
+            (days * MILLIS_PER_DAY + millis).checked_mul(NANOS_PER_MILLI)
+        }
+        _ => {
+            return Err(DataFusionError::Execution(
+                "The window length of tumble must be an interval literal".to_string(),
+            ))
+        }
+    };
+    let length = match length {
+        Some(length) if length > 0 => length,
+        _ => {
+            return Err(DataFusionError::Execution(
+                "The window length of tumble must be positive".to_string(),
+            ))
+        }
+    };
+    let window_start = |value: i64| value.checked_sub(value.rem_euclid(length));
+
+    match &args[0] {
+        ColumnarValue::Scalar(ScalarValue::TimestampNanosecond(value, tz)) => {
+            Ok(ColumnarValue::Scalar(ScalarValue::TimestampNanosecond(
+                value.and_then(window_start),
+                tz.clone(),
+            )))
+        }
+        ColumnarValue::Array(array) => {
+            let timestamps =
+                downcast_arg::<TimestampNanosecondArray>(array, "timestamp")?;
+            let result = timestamps
+                .iter()
+                .map(|value| value.and_then(window_start))
+                .collect::<TimestampNanosecondArray>();
+            Ok(ColumnarValue::Array(Arc::new(result)))
+        }
+        other => Err(DataFusionError::Internal(format!(
+            "Unsupported data type {:?} for function tumble",
+            other.data_type()
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -403,4 +853,217 @@ mod tests {
         }
         Ok(())
     }
+
+    /// Returns the nanosecond timestamp of a UTC date and time
+    fn utc_nanos(s: &str) -> i64 {
+        DateTime::parse_from_rfc3339(s).unwrap().timestamp_nanos()
+    }
+
+    #[test]
+    fn date_trunc_granularities() -> Result<()> {
+        let value = utc_nanos("2020-09-08T13:42:29.190855Z");
+        let cases = vec![
+            ("second", "2020-09-08T13:42:29Z"),
+            ("minute", "2020-09-08T13:42:00Z"),
+            ("hour", "2020-09-08T13:00:00Z"),
+            ("day", "2020-09-08T00:00:00Z"),
+            // 2020-09-08 is a Tuesday
+            ("week", "2020-09-07T00:00:00Z"),
+            ("month", "2020-09-01T00:00:00Z"),
+            ("quarter", "2020-07-01T00:00:00Z"),
+            ("YEAR", "2020-01-01T00:00:00Z"),
+        ];
+        for (granularity, expected) in cases {
+            assert_eq!(
+                date_trunc_single(granularity, value)?,
+                utc_nanos(expected),
+                "{}",
+                granularity
+            );
+        }
+        assert!(date_trunc_single("century", value).is_err());
+
+        // the start of the week of the earliest timestamp is out of range
+        let err = date_trunc_single("week", i64::MIN).unwrap_err();
+        assert_eq!(err.to_string(), "Execution error: Timestamp out of range");
+        Ok(())
+    }
+
+    #[test]
+    fn date_part_fields() -> Result<()> {
+        let value = utc_nanos("2020-09-08T13:42:29.190855Z");
+        let cases = vec![
+            ("year", 2020),
+            ("quarter", 3),
+            ("month", 9),
+            ("week", 37),
+            ("day", 8),
+            ("dow", 2),
+            ("doy", 252),
+            ("hour", 13),
+            ("minute", 42),
+            ("second", 29),
+        ];
+        for (field, expected) in cases {
+            assert_eq!(date_part_single(field, value)?, expected, "{}", field);
+        }
+        assert!(date_part_single("epoch", value).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn to_char_time_zones() -> Result<()> {
+        let value = utc_nanos("2020-09-08T13:42:29Z");
+        let format = "%Y-%m-%d %H:%M %z";
+        assert_eq!(
+            to_char_single(value, format, None)?,
+            "2020-09-08 13:42 +0000"
+        );
+        assert_eq!(
+            to_char_single(value, format, Some("America/New_York"))?,
+            "2020-09-08 09:42 -0400"
+        );
+        assert_eq!(
+            to_char_single(value, format, Some("+05:30"))?,
+            "2020-09-08 19:12 +0530"
+        );
+        assert!(to_char_single(value, format, Some("Mars/Olympus_Mons")).is_err());
+        assert!(to_char_single(value, "%Q", None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn date_functions_of_dates() -> Result<()> {
+        // 2020-09-08, a Tuesday
+        let dates: ArrayRef = Arc::new(Date32Array::from(vec![Some(18513), None]));
+        let string =
+            |value: &str| -> ArrayRef { Arc::new(StringArray::from(vec![value, value])) };
+
+        let result = date_trunc(&[string("week"), dates.clone()])?;
+        let result = downcast_arg::<TimestampNanosecondArray>(&result, "result")?;
+        assert_eq!(result.value(0), utc_nanos("2020-09-07T00:00:00Z"));
+        assert!(result.is_null(1));
+
+        let result = date_part(&[string("dow"), dates.clone()])?;
+        let result = downcast_arg::<Int32Array>(&result, "result")?;
+        assert_eq!(result.value(0), 2);
+        assert!(result.is_null(1));
+
+        let result = to_char(&[dates, string("%Y-%m-%d %H:%M")])?;
+        let result = downcast_arg::<StringArray>(&result, "result")?;
+        assert_eq!(result.value(0), "2020-09-08 00:00");
+        assert!(result.is_null(1));
+
+        // dates beyond the range of timestamps are errors
+        let dates: ArrayRef = Arc::new(Date32Array::from(vec![Some(i32::MAX)]));
+        let err = date_part(&[string("year"), dates]).unwrap_err();
+        assert_eq!(err.to_string(), "Execution error: Timestamp out of range");
+        Ok(())
+    }
+
+    #[test]
+    fn timestamp_interval_arithmetic() -> Result<()> {
+        let timestamps: ArrayRef = Arc::new(TimestampNanosecondArray::from_opt_vec(
+            vec![Some(utc_nanos("2020-01-31T10:00:00Z")), None],
+            None,
+        ));
+
+        // 1 day and 1 hour
+        let day_time: ArrayRef = Arc::new(IntervalDayTimeArray::from(vec![
+            Some((1 << 32) | 3_600_000),
+            Some(0),
+        ]));
+        let result = timestamp_add_interval(&timestamps, &day_time, false)?;
+        let result = downcast_arg::<TimestampNanosecondArray>(&result, "result")?;
+        assert_eq!(result.value(0), utc_nanos("2020-02-01T11:00:00Z"));
+        assert!(result.is_null(1));
+
+        // the day of the month is clamped to the end of February
+        let months: ArrayRef =
+            Arc::new(IntervalYearMonthArray::from(vec![Some(1), Some(1)]));
+        let result = timestamp_add_interval(&timestamps, &months, false)?;
+        let result = downcast_arg::<TimestampNanosecondArray>(&result, "result")?;
+        assert_eq!(result.value(0), utc_nanos("2020-02-29T10:00:00Z"));
+
+        let result = timestamp_add_interval(&timestamps, &months, true)?;
+        let result = downcast_arg::<TimestampNanosecondArray>(&result, "result")?;
+        assert_eq!(result.value(0), utc_nanos("2019-12-31T10:00:00Z"));
+
+        // the time zone of the timestamps is kept
+        let timezone = Some(Arc::new("Europe/Paris".to_string()));
+        let zoned: ArrayRef = Arc::new(TimestampNanosecondArray::from_opt_vec(
+            vec![Some(utc_nanos("2020-01-31T10:00:00Z"))],
+            timezone.clone(),
+        ));
+        let result = timestamp_add_interval(&zoned, &day_time, false)?;
+        assert_eq!(
+            result.data_type(),
+            &DataType::Timestamp(TimeUnit::Nanosecond, timezone)
+        );
+
+        // shifts beyond the range of timestamps, or of dates, are errors
+        for months in &[1_000 * 12, i32::MAX, i32::MIN] {
+            let months: ArrayRef =
+                Arc::new(IntervalYearMonthArray::from(vec![Some(*months), None]));
+            let err = timestamp_add_interval(&timestamps, &months, false).unwrap_err();
+            assert_eq!(err.to_string(), "Execution error: Timestamp out of range");
+        }
+
+        // the difference of two timestamps is a duration
+        let later: ArrayRef = Arc::new(TimestampNanosecondArray::from_opt_vec(
+            vec![Some(utc_nanos("2020-01-31T10:00:01Z")), Some(0)],
+            None,
+        ));
+        let result = timestamp_difference(&later, &timestamps)?;
+        assert_eq!(
+            result.data_type(),
+            &DataType::Duration(TimeUnit::Nanosecond)
+        );
+        let result = downcast_arg::<DurationNanosecondArray>(&result, "result")?;
+        assert_eq!(result.value(0), NANOS_PER_SECOND);
+        assert!(result.is_null(1));
+        Ok(())
+    }
+
+    #[test]
+    fn tumble_windows() -> Result<()> {
+        let five_minutes =
+            ColumnarValue::Scalar(ScalarValue::IntervalDayTime(Some(300_000)));
+        let timestamps: ArrayRef = Arc::new(TimestampNanosecondArray::from_opt_vec(
+            vec![
+                Some(utc_nanos("2021-01-01T10:07:00Z")),
+                Some(utc_nanos("2021-01-01T10:05:00Z")),
+                Some(utc_nanos("1969-12-31T23:59:59Z")),
+                None,
+            ],
+            None,
+        ));
+        let result = tumble(&[ColumnarValue::Array(timestamps), five_minutes.clone()])?
+            .into_array(4);
+        let result = downcast_arg::<TimestampNanosecondArray>(&result, "result")?;
+        assert_eq!(result.value(0), utc_nanos("2021-01-01T10:05:00Z"));
+        assert_eq!(result.value(1), utc_nanos("2021-01-01T10:05:00Z"));
+        // the windows before the epoch start at multiples of the length too
+        assert_eq!(result.value(2), utc_nanos("1969-12-31T23:55:00Z"));
+        assert!(result.is_null(3));
+
+        let scalar = ColumnarValue::Scalar(ScalarValue::TimestampNanosecond(
+            Some(utc_nanos("2021-01-01T10:07:00Z")),
+            None,
+        ));
+        match tumble(&[scalar.clone(), five_minutes])? {
+            ColumnarValue::Scalar(value) => assert_eq!(
+                value,
+                ScalarValue::TimestampNanosecond(
+                    Some(utc_nanos("2021-01-01T10:05:00Z")),
+                    None
+                )
+            ),
+            other => panic!("Expected a scalar, got {:?}", other.data_type()),
+        }
+
+        let zero = ColumnarValue::Scalar(ScalarValue::IntervalDayTime(Some(0)));
+        assert!(tumble(&[scalar, zero]).is_err());
+        Ok(())
+    }
 }
@@ -17,6 +17,7 @@
 
 //! Implementations for DISTINCT expressions, e.g. `COUNT(DISTINCT c)`
 
+use std::any::Any;
 use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::hash::Hash;
@@ -34,6 +35,13 @@ use crate::scalar::ScalarValue;
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 struct DistinctScalarValues(Vec<GroupByScalar>);
 
+impl DistinctScalarValues {
+    /// An estimate of the memory held by the values, in bytes, excluding their own size
+    fn heap_size(&self) -> usize {
+        self.0.iter().map(|v| v.size()).sum()
+    }
+}
+
 fn format_state_name(name: &str, state_name: &str) -> String {
     format!("{}[{}]", name, state_name)
 }
@@ -69,6 +77,11 @@ impl DistinctCount {
 }
 
 impl AggregateExpr for DistinctCount {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn field(&self) -> Result<Field> {
         Ok(Field::new(&self.name, self.data_type.clone(), true))
     }
@@ -94,6 +107,7 @@ impl AggregateExpr for DistinctCount {
     fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
         Ok(Box::new(DistinctCountAccumulator {
             values: FnvHashSet::default(),
+            values_heap_size: 0,
             data_types: self.input_data_types.clone(),
             count_data_type: self.data_type.clone(),
         }))
@@ -103,6 +117,8 @@ impl AggregateExpr for DistinctCount {
 #[derive(Debug)]
 struct DistinctCountAccumulator {
     values: FnvHashSet<DistinctScalarValues>,
+    /// The memory held by the elements of `values`, outside of the set itself
+    values_heap_size: usize,
     data_types: Vec<DataType>,
     count_data_type: DataType,
 }
@@ -111,12 +127,16 @@ impl Accumulator for DistinctCountAccumulator {
     fn update(&mut self, values: &Vec<ScalarValue>) -> Result<()> {
         // If a row has a NULL, it is not included in the final count.
         if !values.iter().any(|v| v.is_null()) {
-            self.values.insert(DistinctScalarValues(
+            let values = DistinctScalarValues(
                 values
                     .iter()
                     .map(GroupByScalar::try_from)
                     .collect::<Result<Vec<_>>>()?,
-            ));
+            );
+            let heap_size = values.heap_size();
+            if self.values.insert(values) {
+                self.values_heap_size += heap_size;
+            }
         }
 
         Ok(())
@@ -185,6 +205,12 @@ impl Accumulator for DistinctCountAccumulator {
             }
         }
     }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.values.capacity() * std::mem::size_of::<DistinctScalarValues>()
+            + self.values_heap_size
+    }
 }
 
 #[cfg(test)]
@@ -448,6 +474,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn count_distinct_size() -> Result<()> {
+        let agg = DistinctCount::new(
+            vec![DataType::Utf8],
+            vec![],
+            String::from("__col_name__"),
+            DataType::UInt64,
+        );
+        let mut accum = agg.create_accumulator()?;
+        let empty_size = accum.size();
+
+        accum.update(&vec![ScalarValue::Utf8(Some("a".repeat(100)))])?;
+        let size = accum.size();
+        assert!(size >= empty_size + 100);
+
+        // a value that was already seen does not take more memory
+        accum.update(&vec![ScalarValue::Utf8(Some("a".repeat(100)))])?;
+        assert_eq!(size, accum.size());
+
+        Ok(())
+    }
+
     #[test]
     fn count_distinct_update() -> Result<()> {
         let (states, result) = run_update(
@@ -52,6 +52,11 @@ impl ExplainExec {
             stringified_plans,
         }
     }
+
+    /// The strings to be printed
+    pub fn stringified_plans(&self) -> &[StringifiedPlan] {
+        &self.stringified_plans
+    }
 }
 
 #[async_trait]
@@ -17,35 +17,50 @@
 
 //! Defines physical expressions that can evaluated at runtime during query execution
 
+use std::any::Any;
+use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::fmt;
 use std::sync::Arc;
 
 use crate::error::{DataFusionError, Result};
 use crate::logical_plan::Operator;
+use crate::physical_plan::array_expressions::list_element;
+use crate::physical_plan::datetime_expressions;
 use crate::physical_plan::{Accumulator, AggregateExpr, PhysicalExpr};
 use crate::scalar::ScalarValue;
-use arrow::array::{
-    Float32Builder, Float64Builder, Int16Builder, Int32Builder, Int64Builder,
-    Int8Builder, LargeStringArray, StringBuilder, UInt16Builder, UInt32Builder,
-    UInt64Builder, UInt8Builder,
-};
+use arrow::array::{LargeStringArray, StructArray};
 use arrow::compute;
 use arrow::compute::kernels;
 use arrow::compute::kernels::arithmetic::{add, divide, multiply, subtract};
-use arrow::compute::kernels::boolean::{and, or};
+use arrow::compute::kernels::boolean::{and, not, or};
 use arrow::compute::kernels::comparison::{eq, gt, gt_eq, lt, lt_eq, neq};
+use arrow::compute::kernels::comparison::{
+    eq_dict_utf8_scalar, gt_dict_utf8_scalar, gt_eq_dict_utf8_scalar,
+    lt_dict_utf8_scalar, lt_eq_dict_utf8_scalar, neq_dict_utf8_scalar,
+};
 use arrow::compute::kernels::comparison::{
     eq_utf8, gt_eq_utf8, gt_utf8, like_utf8, lt_eq_utf8, lt_utf8, neq_utf8, nlike_utf8,
 };
+use arrow::compute::kernels::regexp::{
+    regexp_is_match_utf8, regexp_is_match_utf8_scalar,
+};
 use arrow::compute::kernels::sort::{SortColumn, SortOptions};
-use arrow::datatypes::{DataType, Schema, TimeUnit};
+use arrow::datatypes::{
+    ArrowPrimitiveType, DataType, DateUnit, Int16Type, Int32Type, Int64Type, Int8Type,
+    Schema, TimeUnit, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
 use arrow::record_batch::RecordBatch;
 use arrow::{
     array::{
-        ArrayRef, BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array,
-        Int64Array, Int8Array, StringArray, TimestampNanosecondArray, UInt16Array,
-        UInt32Array, UInt64Array, UInt8Array,
+        Array, ArrayRef, BinaryArray, BooleanArray, Date32Array, Date64Array,
+        DictionaryArray, DurationMicrosecondArray, DurationMillisecondArray,
+        DurationNanosecondArray, DurationSecondArray, FixedSizeBinaryArray, Float32Array,
+        Float64Array, GenericStringArray, Int16Array, Int32Array, Int64Array, Int8Array,
+        LargeBinaryArray, StringArray, StringOffsetSizeTrait, Time32MillisecondArray,
+        Time32SecondArray, Time64MicrosecondArray, Time64NanosecondArray,
+        TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
+        TimestampSecondArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
     },
     datatypes::Field,
 };
@@ -69,6 +84,11 @@ impl Column {
             name: name.to_owned(),
         }
     }
+
+    /// Get the column name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 }
 
 impl fmt::Display for Column {
@@ -78,6 +98,10 @@ impl fmt::Display for Column {
 }
 
 impl PhysicalExpr for Column {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     /// Get the data type of this expression, given the schema of the input
     fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
         Ok(input_schema
@@ -142,6 +166,11 @@ impl Sum {
 }
 
 impl AggregateExpr for Sum {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn field(&self) -> Result<Field> {
         Ok(Field::new(
             &self.name,
@@ -370,6 +399,11 @@ impl Avg {
 }
 
 impl AggregateExpr for Avg {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn field(&self) -> Result<Field> {
         Ok(Field::new(&self.name, DataType::Float64, true))
     }
@@ -499,6 +533,11 @@ impl Max {
 }
 
 impl AggregateExpr for Max {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn field(&self) -> Result<Field> {
         Ok(Field::new(
             &self.name,
@@ -534,12 +573,25 @@ macro_rules! typed_min_max_batch_string {
     }};
 }
 
+// Statically-typed version of min/max(array) -> ScalarValue for binary types.
+macro_rules! typed_min_max_batch_binary {
+    ($VALUES:expr, $ARRAYTYPE:ident, $SCALAR:ident, $OP:ident $(, $EXTRA_ARGS:expr)*) => {{
+        let array = $VALUES.as_any().downcast_ref::<$ARRAYTYPE>().unwrap();
+        let value = (0..array.len())
+            .filter(|i| !array.is_null(*i))
+            .map(|i| array.value(i))
+            .$OP()
+            .map(|e| e.to_vec());
+        ScalarValue::$SCALAR(value $(, $EXTRA_ARGS)*)
+    }};
+}
+
 // Statically-typed version of min/max(array) -> ScalarValue for non-string types.
 macro_rules! typed_min_max_batch {
-    ($VALUES:expr, $ARRAYTYPE:ident, $SCALAR:ident, $OP:ident) => {{
+    ($VALUES:expr, $ARRAYTYPE:ident, $SCALAR:ident, $OP:ident $(, $EXTRA_ARGS:expr)*) => {{
         let array = $VALUES.as_any().downcast_ref::<$ARRAYTYPE>().unwrap();
         let value = compute::$OP(array);
-        ScalarValue::$SCALAR(value)
+        ScalarValue::$SCALAR(value $(, $EXTRA_ARGS.clone())*)
     }};
 }
 
@@ -563,6 +615,82 @@ macro_rules! min_max_batch {
             DataType::UInt32 => typed_min_max_batch!($VALUES, UInt32Array, UInt32, $OP),
             DataType::UInt16 => typed_min_max_batch!($VALUES, UInt16Array, UInt16, $OP),
             DataType::UInt8 => typed_min_max_batch!($VALUES, UInt8Array, UInt8, $OP),
+            DataType::Date32(DateUnit::Day) => {
+                typed_min_max_batch!($VALUES, Date32Array, Date32, $OP)
+            }
+            DataType::Date64(DateUnit::Millisecond) => {
+                typed_min_max_batch!($VALUES, Date64Array, Date64, $OP)
+            }
+            DataType::Time32(TimeUnit::Second) => {
+                typed_min_max_batch!($VALUES, Time32SecondArray, Time32Second, $OP)
+            }
+            DataType::Time32(TimeUnit::Millisecond) => typed_min_max_batch!(
+                $VALUES,
+                Time32MillisecondArray,
+                Time32Millisecond,
+                $OP
+            ),
+            DataType::Time64(TimeUnit::Microsecond) => typed_min_max_batch!(
+                $VALUES,
+                Time64MicrosecondArray,
+                Time64Microsecond,
+                $OP
+            ),
+            DataType::Time64(TimeUnit::Nanosecond) => typed_min_max_batch!(
+                $VALUES,
+                Time64NanosecondArray,
+                Time64Nanosecond,
+                $OP
+            ),
+            DataType::Timestamp(TimeUnit::Second, tz) => typed_min_max_batch!(
+                $VALUES,
+                TimestampSecondArray,
+                TimestampSecond,
+                $OP,
+                tz
+            ),
+            DataType::Timestamp(TimeUnit::Millisecond, tz) => typed_min_max_batch!(
+                $VALUES,
+                TimestampMillisecondArray,
+                TimestampMillisecond,
+                $OP,
+                tz
+            ),
+            DataType::Timestamp(TimeUnit::Microsecond, tz) => typed_min_max_batch!(
+                $VALUES,
+                TimestampMicrosecondArray,
+                TimestampMicrosecond,
+                $OP,
+                tz
+            ),
+            DataType::Timestamp(TimeUnit::Nanosecond, tz) => typed_min_max_batch!(
+                $VALUES,
+                TimestampNanosecondArray,
+                TimestampNanosecond,
+                $OP,
+                tz
+            ),
+            DataType::Duration(TimeUnit::Second) => {
+                typed_min_max_batch!($VALUES, DurationSecondArray, DurationSecond, $OP)
+            }
+            DataType::Duration(TimeUnit::Millisecond) => typed_min_max_batch!(
+                $VALUES,
+                DurationMillisecondArray,
+                DurationMillisecond,
+                $OP
+            ),
+            DataType::Duration(TimeUnit::Microsecond) => typed_min_max_batch!(
+                $VALUES,
+                DurationMicrosecondArray,
+                DurationMicrosecond,
+                $OP
+            ),
+            DataType::Duration(TimeUnit::Nanosecond) => typed_min_max_batch!(
+                $VALUES,
+                DurationNanosecondArray,
+                DurationNanosecond,
+                $OP
+            ),
             other => {
                 // This should have been handled before
                 return Err(DataFusionError::Internal(format!(
@@ -583,6 +711,17 @@ fn min_batch(values: &ArrayRef) -> Result<ScalarValue> {
         DataType::LargeUtf8 => {
             typed_min_max_batch_string!(values, LargeStringArray, LargeUtf8, min_string)
         }
+        DataType::Binary => typed_min_max_batch_binary!(values, BinaryArray, Binary, min),
+        DataType::LargeBinary => {
+            typed_min_max_batch_binary!(values, LargeBinaryArray, LargeBinary, min)
+        }
+        DataType::FixedSizeBinary(size) => typed_min_max_batch_binary!(
+            values,
+            FixedSizeBinaryArray,
+            FixedSizeBinary,
+            min,
+            *size
+        ),
         _ => min_max_batch!(values, min),
     })
 }
@@ -596,31 +735,48 @@ fn max_batch(values: &ArrayRef) -> Result<ScalarValue> {
         DataType::LargeUtf8 => {
             typed_min_max_batch_string!(values, LargeStringArray, LargeUtf8, max_string)
         }
+        DataType::Binary => typed_min_max_batch_binary!(values, BinaryArray, Binary, max),
+        DataType::LargeBinary => {
+            typed_min_max_batch_binary!(values, LargeBinaryArray, LargeBinary, max)
+        }
+        DataType::FixedSizeBinary(size) => typed_min_max_batch_binary!(
+            values,
+            FixedSizeBinaryArray,
+            FixedSizeBinary,
+            max,
+            *size
+        ),
         _ => min_max_batch!(values, max),
     })
 }
 
 // min/max of two non-string scalar values.
 macro_rules! typed_min_max {
-    ($VALUE:expr, $DELTA:expr, $SCALAR:ident, $OP:ident) => {{
-        ScalarValue::$SCALAR(match ($VALUE, $DELTA) {
-            (None, None) => None,
-            (Some(a), None) => Some(a.clone()),
-            (None, Some(b)) => Some(b.clone()),
-            (Some(a), Some(b)) => Some((*a).$OP(*b)),
-        })
+    ($VALUE:expr, $DELTA:expr, $SCALAR:ident, $OP:ident $(, $EXTRA_ARGS:expr)*) => {{
+        ScalarValue::$SCALAR(
+            match ($VALUE, $DELTA) {
+                (None, None) => None,
+                (Some(a), None) => Some(a.clone()),
+                (None, Some(b)) => Some(b.clone()),
+                (Some(a), Some(b)) => Some((*a).$OP(*b)),
+            }
+            $(, $EXTRA_ARGS.clone())*
+        )
     }};
 }
 
-// min/max of two scalar string values.
+// min/max of two scalar string or binary values.
 macro_rules! typed_min_max_string {
-    ($VALUE:expr, $DELTA:expr, $SCALAR:ident, $OP:ident) => {{
-        ScalarValue::$SCALAR(match ($VALUE, $DELTA) {
-            (None, None) => None,
-            (Some(a), None) => Some(a.clone()),
-            (None, Some(b)) => Some(b.clone()),
-            (Some(a), Some(b)) => Some((a).$OP(b).clone()),
-        })
+    ($VALUE:expr, $DELTA:expr, $SCALAR:ident, $OP:ident $(, $EXTRA_ARGS:expr)*) => {{
+        ScalarValue::$SCALAR(
+            match ($VALUE, $DELTA) {
+                (None, None) => None,
+                (Some(a), None) => Some(a.clone()),
+                (None, Some(b)) => Some(b.clone()),
+                (Some(a), Some(b)) => Some((a).$OP(b).clone()),
+            }
+            $(, $EXTRA_ARGS.clone())*
+        )
     }};
 }
 
@@ -664,6 +820,67 @@ macro_rules! min_max {
             (ScalarValue::LargeUtf8(lhs), ScalarValue::LargeUtf8(rhs)) => {
                 typed_min_max_string!(lhs, rhs, LargeUtf8, $OP)
             }
+            (ScalarValue::Binary(lhs), ScalarValue::Binary(rhs)) => {
+                typed_min_max_string!(lhs, rhs, Binary, $OP)
+            }
+            (ScalarValue::LargeBinary(lhs), ScalarValue::LargeBinary(rhs)) => {
+                typed_min_max_string!(lhs, rhs, LargeBinary, $OP)
+            }
+            (
+                ScalarValue::FixedSizeBinary(lhs, size),
+                ScalarValue::FixedSizeBinary(rhs, _),
+            ) => typed_min_max_string!(lhs, rhs, FixedSizeBinary, $OP, size),
+            (ScalarValue::Date32(lhs), ScalarValue::Date32(rhs)) => {
+                typed_min_max!(lhs, rhs, Date32, $OP)
+            }
+            (ScalarValue::Date64(lhs), ScalarValue::Date64(rhs)) => {
+                typed_min_max!(lhs, rhs, Date64, $OP)
+            }
+            (ScalarValue::Time32Second(lhs), ScalarValue::Time32Second(rhs)) => {
+                typed_min_max!(lhs, rhs, Time32Second, $OP)
+            }
+            (
+                ScalarValue::Time32Millisecond(lhs),
+                ScalarValue::Time32Millisecond(rhs),
+            ) => typed_min_max!(lhs, rhs, Time32Millisecond, $OP),
+            (
+                ScalarValue::Time64Microsecond(lhs),
+                ScalarValue::Time64Microsecond(rhs),
+            ) => typed_min_max!(lhs, rhs, Time64Microsecond, $OP),
+            (ScalarValue::Time64Nanosecond(lhs), ScalarValue::Time64Nanosecond(rhs)) => {
+                typed_min_max!(lhs, rhs, Time64Nanosecond, $OP)
+            }
+            (
+                ScalarValue::TimestampSecond(lhs, tz),
+                ScalarValue::TimestampSecond(rhs, _),
+            ) => typed_min_max!(lhs, rhs, TimestampSecond, $OP, tz),
+            (
+                ScalarValue::TimestampMillisecond(lhs, tz),
+                ScalarValue::TimestampMillisecond(rhs, _),
+            ) => typed_min_max!(lhs, rhs, TimestampMillisecond, $OP, tz),
+            (
+                ScalarValue::TimestampMicrosecond(lhs, tz),
+                ScalarValue::TimestampMicrosecond(rhs, _),
+            ) => typed_min_max!(lhs, rhs, TimestampMicrosecond, $OP, tz),
+            (
+                ScalarValue::TimestampNanosecond(lhs, tz),
+                ScalarValue::TimestampNanosecond(rhs, _),
+            ) => typed_min_max!(lhs, rhs, TimestampNanosecond, $OP, tz),
+            (ScalarValue::DurationSecond(lhs), ScalarValue::DurationSecond(rhs)) => {
+                typed_min_max!(lhs, rhs, DurationSecond, $OP)
+            }
+            (
+                ScalarValue::DurationMillisecond(lhs),
+                ScalarValue::DurationMillisecond(rhs),
+            ) => typed_min_max!(lhs, rhs, DurationMillisecond, $OP),
+            (
+                ScalarValue::DurationMicrosecond(lhs),
+                ScalarValue::DurationMicrosecond(rhs),
+            ) => typed_min_max!(lhs, rhs, DurationMicrosecond, $OP),
+            (
+                ScalarValue::DurationNanosecond(lhs),
+                ScalarValue::DurationNanosecond(rhs),
+            ) => typed_min_max!(lhs, rhs, DurationNanosecond, $OP),
             e => {
                 return Err(DataFusionError::Internal(format!(
                     "MIN/MAX is not expected to receive a scalar {:?}",
@@ -751,6 +968,11 @@ impl Min {
 }
 
 impl AggregateExpr for Min {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn field(&self) -> Result<Field> {
         Ok(Field::new(
             &self.name,
@@ -844,6 +1066,11 @@ impl Count {
 }
 
 impl AggregateExpr for Count {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn field(&self) -> Result<Field> {
         Ok(Field::new(
             &self.name,
@@ -1073,6 +1300,21 @@ impl BinaryExpr {
     ) -> Self {
         Self { left, op, right }
     }
+
+    /// Get the left side of the binary expression
+    pub fn left(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.left
+    }
+
+    /// Get the operator for this binary expression
+    pub fn op(&self) -> &Operator {
+        &self.op
+    }
+
+    /// Get the right side of the binary expression
+    pub fn right(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.right
+    }
 }
 
 impl fmt::Display for BinaryExpr {
@@ -1092,11 +1334,10 @@ fn dictionary_value_coercion(
 /// Coercion rules for Dictionaries: the type that both lhs and rhs
 /// can be casted to for the purpose of a computation.
 ///
-/// It would likely be preferable to cast primitive values to
-/// dictionaries, and thus avoid unpacking dictionary as well as doing
-/// faster comparisons. However, the arrow compute kernels (e.g. eq)
-/// don't have DictionaryArray support yet, so fall back to unpacking
-/// the dictionaries
+/// Comparisons of dictionaries of strings with string literals are evaluated on the
+/// values of the dictionaries instead, see `dictionary_literal_comparison`. The
+/// arrow compute kernels (e.g. eq) don't support other operations on dictionaries
+/// yet, so fall back to unpacking the dictionaries
 fn dictionary_coercion(lhs_type: &DataType, rhs_type: &DataType) -> Option<DataType> {
     match (lhs_type, rhs_type) {
         (
@@ -1191,7 +1432,10 @@ fn eq_coercion(lhs_type: &DataType, rhs_type: &DataType) -> Option<DataType> {
 
 // coercion rules that assume an ordered set, such as "less than".
 // These are the union of all numerical coercion rules and all string coercion rules
-fn order_coercion(lhs_type: &DataType, rhs_type: &DataType) -> Option<DataType> {
+pub(crate) fn order_coercion(
+    lhs_type: &DataType,
+    rhs_type: &DataType,
+) -> Option<DataType> {
     if lhs_type == rhs_type {
         // same type => all good
         return Some(lhs_type.clone());
@@ -1217,9 +1461,17 @@ fn common_binary_type(
             _ => None,
         },
         // logical equality operators have their own rules, and always return a boolean
-        Operator::Eq | Operator::NotEq => eq_coercion(lhs_type, rhs_type),
+        Operator::Eq
+        | Operator::NotEq
+        | Operator::IsDistinctFrom
+        | Operator::IsNotDistinctFrom => eq_coercion(lhs_type, rhs_type),
         // "like" operators operate on strings and always return a boolean
         Operator::Like | Operator::NotLike => string_coercion(lhs_type, rhs_type),
+        // regular expression matches operate on strings and always return a boolean
+        Operator::RegexMatch
+        | Operator::RegexIMatch
+        | Operator::RegexNotMatch
+        | Operator::RegexNotIMatch => string_coercion(lhs_type, rhs_type),
         // order-comparison operators have their own rules
         Operator::Lt | Operator::Gt | Operator::GtEq | Operator::LtEq => {
             order_coercion(lhs_type, rhs_type)
@@ -1249,6 +1501,27 @@ fn common_binary_type(
     }
 }
 
+/// Returns the type of `lhs_type op rhs_type` when it is an arithmetic operation on
+/// timestamps and intervals, or `None` otherwise. Timestamps are computed in nanoseconds
+/// and keep their time zone.
+fn temporal_arithmetic_type(
+    lhs_type: &DataType,
+    op: &Operator,
+    rhs_type: &DataType,
+) -> Option<DataType> {
+    match (lhs_type, op, rhs_type) {
+        (DataType::Timestamp(_, tz), Operator::Plus, DataType::Interval(_))
+        | (DataType::Timestamp(_, tz), Operator::Minus, DataType::Interval(_))
+        | (DataType::Interval(_), Operator::Plus, DataType::Timestamp(_, tz)) => {
+            Some(DataType::Timestamp(TimeUnit::Nanosecond, tz.clone()))
+        }
+        (DataType::Timestamp(_, _), Operator::Minus, DataType::Timestamp(_, _)) => {
+            Some(DataType::Duration(TimeUnit::Nanosecond))
+        }
+        _ => None,
+    }
+}
+
 /// Returns the return type of a binary operator or an error when the binary operator cannot
 /// perform the computation between the argument's types, even after type coercion.
 ///
@@ -1258,6 +1531,11 @@ pub fn binary_operator_data_type(
     op: &Operator,
     rhs_type: &DataType,
 ) -> Result<DataType> {
+    // arithmetic on timestamps does not operate on a common type
+    if let Some(data_type) = temporal_arithmetic_type(lhs_type, op, rhs_type) {
+        return Ok(data_type);
+    }
+
     // validate that it is possible to perform the operation on incoming types.
     // (or the return datatype cannot be infered)
     let common_type = common_binary_type(lhs_type, op, rhs_type)?;
@@ -1266,10 +1544,16 @@ pub fn binary_operator_data_type(
         // operators that return a boolean
         Operator::Eq
         | Operator::NotEq
+        | Operator::IsDistinctFrom
+        | Operator::IsNotDistinctFrom
         | Operator::And
         | Operator::Or
         | Operator::Like
         | Operator::NotLike
+        | Operator::RegexMatch
+        | Operator::RegexIMatch
+        | Operator::RegexNotMatch
+        | Operator::RegexNotIMatch
         | Operator::Lt
         | Operator::Gt
         | Operator::GtEq
@@ -1295,6 +1579,28 @@ fn binary_cast(
     let lhs_type = &lhs.data_type(input_schema)?;
     let rhs_type = &rhs.data_type(input_schema)?;
 
+    // timestamps are cast to nanoseconds in their time zone, intervals are used as they are
+    if temporal_arithmetic_type(lhs_type, op, rhs_type).is_some() {
+        let cast_timestamp =
+            |expr: Arc<dyn PhysicalExpr>, data_type: &DataType| match data_type {
+                DataType::Timestamp(_, tz) => cast(
+                    expr,
+                    input_schema,
+                    DataType::Timestamp(TimeUnit::Nanosecond, tz.clone()),
+                ),
+                _ => Ok(expr),
+            };
+        return Ok((
+            cast_timestamp(lhs, lhs_type)?,
+            cast_timestamp(rhs, rhs_type)?,
+        ));
+    }
+
+    // the dictionary is compared with the literal without being unpacked
+    if dictionary_literal_comparison(&lhs, lhs_type, op, &rhs, rhs_type).is_some() {
+        return Ok((lhs, rhs));
+    }
+
     let cast_type = common_binary_type(lhs_type, op, rhs_type)?;
 
     Ok((
@@ -1304,6 +1610,10 @@ fn binary_cast(
 }
 
 impl PhysicalExpr for BinaryExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
         binary_operator_data_type(
             &self.left.data_type(input_schema)?,
@@ -1313,12 +1623,64 @@ impl PhysicalExpr for BinaryExpr {
     }
 
     fn nullable(&self, input_schema: &Schema) -> Result<bool> {
-        Ok(self.left.nullable(input_schema)? || self.right.nullable(input_schema)?)
+        match self.op {
+            // nulls are compared as values, so the result is never null
+            Operator::IsDistinctFrom | Operator::IsNotDistinctFrom => Ok(false),
+            _ => {
+                Ok(self.left.nullable(input_schema)?
+                    || self.right.nullable(input_schema)?)
+            }
+        }
     }
 
     fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        if string_literal(&self.left).is_some() || string_literal(&self.right).is_some() {
+            let schema = batch.schema();
+            let left_type = self.left.data_type(&schema)?;
+            let right_type = self.right.data_type(&schema)?;
+            if let Some((dictionary, op, value)) = dictionary_literal_comparison(
+                &self.left,
+                &left_type,
+                &self.op,
+                &self.right,
+                &right_type,
+            ) {
+                let dictionary = dictionary.evaluate(batch)?;
+                return compare_dictionary_literal(&dictionary, &op, value);
+            }
+        }
+
         let left = self.left.evaluate(batch)?;
+        if let Some((negated, case_insensitive)) = regex_match_modifiers(&self.op) {
+            // the pattern is evaluated by `regex_match`, as literal patterns
+            // are compiled once rather than once per row
+            return match left.data_type() {
+                DataType::Utf8 => regex_match::<i32>(
+                    &left,
+                    &self.right,
+                    batch,
+                    negated,
+                    case_insensitive,
+                ),
+                DataType::LargeUtf8 => regex_match::<i64>(
+                    &left,
+                    &self.right,
+                    batch,
+                    negated,
+                    case_insensitive,
+                ),
+                other => Err(DataFusionError::Internal(format!(
+                    "Cannot evaluate binary expression {:?} with type {:?}",
+                    self.op, other
+                ))),
+            };
+        }
         let right = self.right.evaluate(batch)?;
+        if temporal_arithmetic_type(left.data_type(), &self.op, right.data_type())
+            .is_some()
+        {
+            return temporal_arithmetic(&left, &self.op, &right);
+        }
         if left.data_type() != right.data_type() {
             // this should have been captured during planning
             return Err(DataFusionError::Internal(format!(
@@ -1337,6 +1699,8 @@ impl PhysicalExpr for BinaryExpr {
             Operator::GtEq => binary_array_op!(left, right, gt_eq),
             Operator::Eq => binary_array_op!(left, right, eq),
             Operator::NotEq => binary_array_op!(left, right, neq),
+            Operator::IsDistinctFrom => is_distinct_from(&left, &right, true),
+            Operator::IsNotDistinctFrom => is_distinct_from(&left, &right, false),
             Operator::Plus => binary_primitive_array_op!(left, right, add),
             Operator::Minus => binary_primitive_array_op!(left, right, subtract),
             Operator::Multiply => binary_primitive_array_op!(left, right, multiply),
@@ -1368,7 +1732,224 @@ impl PhysicalExpr for BinaryExpr {
             Operator::Modulus => Err(DataFusionError::NotImplemented(
                 "Modulus operator is still not supported".to_string(),
             )),
+            Operator::RegexMatch
+            | Operator::RegexIMatch
+            | Operator::RegexNotMatch
+            | Operator::RegexNotIMatch => Err(DataFusionError::Internal(format!(
+                "Regular expression operator {:?} must be evaluated by regex_match",
+                self.op
+            ))),
+        }
+    }
+}
+
+/// Evaluates an arithmetic operation on timestamps and intervals, as typed by
+/// `temporal_arithmetic_type`.
+fn temporal_arithmetic(
+    left: &ArrayRef,
+    op: &Operator,
+    right: &ArrayRef,
+) -> Result<ArrayRef> {
+    match (left.data_type(), right.data_type()) {
+        (DataType::Timestamp(_, _), DataType::Timestamp(_, _)) => {
+            datetime_expressions::timestamp_difference(left, right)
         }
+        (DataType::Timestamp(_, _), _) => datetime_expressions::timestamp_add_interval(
+            left,
+            right,
+            op == &Operator::Minus,
+        ),
+        _ => datetime_expressions::timestamp_add_interval(right, left, false),
+    }
+}
+
+/// Returns the string literal of `expr`, if it is one
+fn string_literal(expr: &Arc<dyn PhysicalExpr>) -> Option<&str> {
+    match expr.as_any().downcast_ref::<Literal>() {
+        Some(Literal {
+            value: ScalarValue::Utf8(Some(value)),
+        }) => Some(value.as_str()),
+        _ => None,
+    }
+}
+
+/// Returns the dictionary, operator and literal of a comparison between a
+/// dictionary of strings and a string literal, with the dictionary on the left of
+/// the operator, or `None` if `lhs op rhs` is not one
+fn dictionary_literal_comparison<'a>(
+    lhs: &'a Arc<dyn PhysicalExpr>,
+    lhs_type: &DataType,
+    op: &Operator,
+    rhs: &'a Arc<dyn PhysicalExpr>,
+    rhs_type: &DataType,
+) -> Option<(&'a Arc<dyn PhysicalExpr>, Operator, &'a str)> {
+    let is_string_dictionary = |data_type: &DataType| match data_type {
+        DataType::Dictionary(_, value_type) => value_type.as_ref() == &DataType::Utf8,
+        _ => false,
+    };
+    let swapped_op = match op {
+        Operator::Eq | Operator::NotEq => op.clone(),
+        Operator::Lt => Operator::Gt,
+        Operator::LtEq => Operator::GtEq,
+        Operator::Gt => Operator::Lt,
+        Operator::GtEq => Operator::LtEq,
+        _ => return None,
+    };
+    if is_string_dictionary(lhs_type) {
+        string_literal(rhs).map(|value| (lhs, op.clone(), value))
+    } else if is_string_dictionary(rhs_type) {
+        string_literal(lhs).map(|value| (rhs, swapped_op, value))
+    } else {
+        None
+    }
+}
+
+/// Compares the dictionary of strings `array` with `value`. The comparison is
+/// evaluated once per value of the dictionary rather than once per row.
+fn compare_dictionary_literal(
+    array: &ArrayRef,
+    op: &Operator,
+    value: &str,
+) -> Result<ArrayRef> {
+    match array.data_type() {
+        DataType::Dictionary(key_type, _) => match key_type.as_ref() {
+            DataType::Int8 => compare_dictionary::<Int8Type>(array, op, value),
+            DataType::Int16 => compare_dictionary::<Int16Type>(array, op, value),
+            DataType::Int32 => compare_dictionary::<Int32Type>(array, op, value),
+            DataType::Int64 => compare_dictionary::<Int64Type>(array, op, value),
+            DataType::UInt8 => compare_dictionary::<UInt8Type>(array, op, value),
+            DataType::UInt16 => compare_dictionary::<UInt16Type>(array, op, value),
+            DataType::UInt32 => compare_dictionary::<UInt32Type>(array, op, value),
+            DataType::UInt64 => compare_dictionary::<UInt64Type>(array, op, value),
+            other => Err(DataFusionError::Internal(format!(
+                "Unsupported dictionary key type {:?}",
+                other
+            ))),
+        },
+        other => Err(DataFusionError::Internal(format!(
+            "Cannot compare an array of type {:?} as a dictionary",
+            other
+        ))),
+    }
+}
+
+fn compare_dictionary<K: ArrowPrimitiveType>(
+    array: &ArrayRef,
+    op: &Operator,
+    value: &str,
+) -> Result<ArrayRef> {
+    let array = array
+        .as_any()
+        .downcast_ref::<DictionaryArray<K>>()
+        .expect("compare_dictionary failed to downcast array");
+    let result = match op {
+        Operator::Eq => eq_dict_utf8_scalar(array, value)?,
+        Operator::NotEq => neq_dict_utf8_scalar(array, value)?,
+        Operator::Lt => lt_dict_utf8_scalar(array, value)?,
+        Operator::LtEq => lt_eq_dict_utf8_scalar(array, value)?,
+        Operator::Gt => gt_dict_utf8_scalar(array, value)?,
+        Operator::GtEq => gt_eq_dict_utf8_scalar(array, value)?,
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "Cannot compare a dictionary with operator {:?}",
+                other
+            )))
+        }
+    };
+    Ok(Arc::new(result))
+}
+
+/// Evaluates `IS [NOT] DISTINCT FROM`, which compares like `=` and `!=` except that
+/// two nulls are equal and a null differs from any other value, so that the result is
+/// never null.
+fn is_distinct_from(
+    left: &ArrayRef,
+    right: &ArrayRef,
+    distinct: bool,
+) -> Result<ArrayRef> {
+    let equal: Result<ArrayRef> = binary_array_op!(left, right, eq);
+    let equal = equal?;
+    let equal = equal
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .ok_or_else(|| {
+            DataFusionError::Internal(
+                "is_distinct_from failed to downcast array".to_string(),
+            )
+        })?;
+
+    let result = (0..left.len())
+        .map(|i| {
+            let not_distinct = match (left.is_null(i), right.is_null(i)) {
+                (true, true) => true,
+                (false, false) => equal.value(i),
+                _ => false,
+            };
+            Some(not_distinct != distinct)
+        })
+        .collect::<Vec<_>>();
+    Ok(Arc::new(BooleanArray::from(result)))
+}
+
+/// Returns whether a regular expression match operator is negated and whether it
+/// is case-insensitive, or `None` for other operators.
+fn regex_match_modifiers(op: &Operator) -> Option<(bool, bool)> {
+    match op {
+        Operator::RegexMatch => Some((false, false)),
+        Operator::RegexIMatch => Some((false, true)),
+        Operator::RegexNotMatch => Some((true, false)),
+        Operator::RegexNotIMatch => Some((true, true)),
+        _ => None,
+    }
+}
+
+/// Matches the strings of `left` against the pattern `right`. A literal pattern is
+/// compiled once; other patterns are compiled once per distinct value.
+fn regex_match<OffsetSize: StringOffsetSizeTrait>(
+    left: &ArrayRef,
+    right: &Arc<dyn PhysicalExpr>,
+    batch: &RecordBatch,
+    negated: bool,
+    case_insensitive: bool,
+) -> Result<ArrayRef> {
+    let array = left
+        .as_any()
+        .downcast_ref::<GenericStringArray<OffsetSize>>()
+        .ok_or_else(|| {
+            DataFusionError::Internal("regex_match failed to downcast array".to_string())
+        })?;
+    let flag = if case_insensitive { Some("i") } else { None };
+
+    let result = match right.as_any().downcast_ref::<Literal>() {
+        Some(Literal {
+            value: ScalarValue::Utf8(Some(pattern)),
+        })
+        | Some(Literal {
+            value: ScalarValue::LargeUtf8(Some(pattern)),
+        }) => regexp_is_match_utf8_scalar(array, pattern, flag)?,
+        _ => {
+            let right = right.evaluate(batch)?;
+            let regex_array = right
+                .as_any()
+                .downcast_ref::<GenericStringArray<OffsetSize>>()
+                .ok_or_else(|| {
+                    DataFusionError::Internal(
+                        "regex_match failed to downcast array".to_string(),
+                    )
+                })?;
+            let flags_array = flag.map(|flag| {
+                std::iter::repeat(Some(flag))
+                    .take(left.len())
+                    .collect::<GenericStringArray<OffsetSize>>()
+            });
+            regexp_is_match_utf8(array, regex_array, flags_array.as_ref())?
+        }
+    };
+
+    if negated {
+        Ok(Arc::new(not(&result)?))
+    } else {
+        Ok(Arc::new(result))
     }
 }
 
@@ -1396,6 +1977,11 @@ impl NotExpr {
     pub fn new(arg: Arc<dyn PhysicalExpr>) -> Self {
         Self { arg }
     }
+
+    /// Get the input expression
+    pub fn arg(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.arg
+    }
 }
 
 impl fmt::Display for NotExpr {
@@ -1404,6 +1990,10 @@ impl fmt::Display for NotExpr {
     }
 }
 impl PhysicalExpr for NotExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
         return Ok(DataType::Boolean);
     }
@@ -1456,6 +2046,11 @@ impl IsNullExpr {
     pub fn new(arg: Arc<dyn PhysicalExpr>) -> Self {
         Self { arg }
     }
+
+    /// Get the input expression
+    pub fn arg(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.arg
+    }
 }
 
 impl fmt::Display for IsNullExpr {
@@ -1464,6 +2059,10 @@ impl fmt::Display for IsNullExpr {
     }
 }
 impl PhysicalExpr for IsNullExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
         return Ok(DataType::Boolean);
     }
@@ -1494,6 +2093,11 @@ impl IsNotNullExpr {
     pub fn new(arg: Arc<dyn PhysicalExpr>) -> Self {
         Self { arg }
     }
+
+    /// Get the input expression
+    pub fn arg(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.arg
+    }
 }
 
 impl fmt::Display for IsNotNullExpr {
@@ -1502,6 +2106,10 @@ impl fmt::Display for IsNotNullExpr {
     }
 }
 impl PhysicalExpr for IsNotNullExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
         return Ok(DataType::Boolean);
     }
@@ -1521,6 +2129,338 @@ pub fn is_not_null(arg: Arc<dyn PhysicalExpr>) -> Result<Arc<dyn PhysicalExpr>>
     Ok(Arc::new(IsNotNullExpr::new(arg)))
 }
 
+/// Builds the `InListSet::$SCALAR` of the non-null `$VALUES`, which are hashed by
+/// `$KEY`, or returns `None` from the enclosing function if a value has another type.
+macro_rules! in_list_set {
+    ($VALUES:expr, $SCALAR:ident $(, $EXTRA:tt)*, |$V:ident| $KEY:expr) => {{
+        let mut set = HashSet::new();
+        let mut contains_null = false;
+        for value in $VALUES {
+            match value {
+                ScalarValue::$SCALAR(Some($V) $(, $EXTRA)*) => {
+                    set.insert($KEY);
+                }
+                ScalarValue::$SCALAR(None $(, $EXTRA)*) => contains_null = true,
+                _ => return None,
+            }
+        }
+        Some(InListValues {
+            set: InListSet::$SCALAR(set),
+            contains_null,
+        })
+    }};
+}
+
+/// Looks up the values of `$ARRAY`, hashed by `$KEY`, in `$SET`. As in SQL, a value
+/// that is not found in a list containing a null is unknown (null) rather than false.
+macro_rules! lookup_in_set {
+    ($ARRAY:expr, $ARRAY_TYPE:ident, $SET:expr, $CONTAINS_NULL:expr, |$V:ident| $KEY:expr) => {{
+        let array = $ARRAY
+            .as_any()
+            .downcast_ref::<$ARRAY_TYPE>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "Cannot look up a value of type {:?} in a list of {}",
+                    $ARRAY.data_type(),
+                    stringify!($ARRAY_TYPE)
+                ))
+            })?;
+        let result = (0..array.len())
+            .map(|i| {
+                if array.is_null(i) {
+                    return None;
+                }
+                let $V = array.value(i);
+                if $SET.contains($KEY) {
+                    Some(true)
+                } else if $CONTAINS_NULL {
+                    None
+                } else {
+                    Some(false)
+                }
+            })
+            .collect::<Vec<_>>();
+        BooleanArray::from(result)
+    }};
+}
+
+/// The non-null values of an IN list of literals, in a set of the keys of their type
+#[derive(Debug)]
+enum InListSet {
+    Boolean(HashSet<bool>),
+    Int8(HashSet<i8>),
+    Int16(HashSet<i16>),
+    Int32(HashSet<i32>),
+    Int64(HashSet<i64>),
+    UInt8(HashSet<u8>),
+    UInt16(HashSet<u16>),
+    UInt32(HashSet<u32>),
+    UInt64(HashSet<u64>),
+    /// floats are hashed by their bits, adding zero turns -0.0 into 0.0
+    Float32(HashSet<u32>),
+    Float64(HashSet<u64>),
+    Utf8(HashSet<String>),
+    LargeUtf8(HashSet<String>),
+    TimestampNanosecond(HashSet<i64>),
+}
+
+/// The values of an IN list of literals, which are hashed once when the expression is
+/// created rather than for every batch
+#[derive(Debug)]
+struct InListValues {
+    set: InListSet,
+    contains_null: bool,
+}
+
+impl InListValues {
+    /// Hashes `values`, or returns `None` when values of their type cannot be hashed
+    fn try_new(values: &[&ScalarValue]) -> Option<Self> {
+        match values.first()?.get_datatype() {
+            DataType::Boolean => in_list_set!(values, Boolean, |v| *v),
+            DataType::Int8 => in_list_set!(values, Int8, |v| *v),
+            DataType::Int16 => in_list_set!(values, Int16, |v| *v),
+            DataType::Int32 => in_list_set!(values, Int32, |v| *v),
+            DataType::Int64 => in_list_set!(values, Int64, |v| *v),
+            DataType::UInt8 => in_list_set!(values, UInt8, |v| *v),
+            DataType::UInt16 => in_list_set!(values, UInt16, |v| *v),
+            DataType::UInt32 => in_list_set!(values, UInt32, |v| *v),
+            DataType::UInt64 => in_list_set!(values, UInt64, |v| *v),
+            DataType::Float32 => in_list_set!(values, Float32, |v| (v + 0.0).to_bits()),
+            DataType::Float64 => in_list_set!(values, Float64, |v| (v + 0.0).to_bits()),
+            DataType::Utf8 => in_list_set!(values, Utf8, |v| v.clone()),
+            DataType::LargeUtf8 => in_list_set!(values, LargeUtf8, |v| v.clone()),
+            DataType::Timestamp(TimeUnit::Nanosecond, None) => {
+                in_list_set!(values, TimestampNanosecond, _, |v| *v)
+            }
+            _ => None,
+        }
+    }
+
+    /// Looks up the values of `array`, which must have the type of the list
+    fn lookup(&self, array: &ArrayRef) -> Result<BooleanArray> {
+        let contains_null = self.contains_null;
+        Ok(match &self.set {
+            InListSet::Boolean(set) => {
+                lookup_in_set!(array, BooleanArray, set, contains_null, |v| &v)
+            }
+            InListSet::Int8(set) => {
+                lookup_in_set!(array, Int8Array, set, contains_null, |v| &v)
+            }
+            InListSet::Int16(set) => {
+                lookup_in_set!(array, Int16Array, set, contains_null, |v| &v)
+            }
+            InListSet::Int32(set) => {
+                lookup_in_set!(array, Int32Array, set, contains_null, |v| &v)
+            }
+            InListSet::Int64(set) => {
+                lookup_in_set!(array, Int64Array, set, contains_null, |v| &v)
+            }
+            InListSet::UInt8(set) => {
+                lookup_in_set!(array, UInt8Array, set, contains_null, |v| &v)
+            }
+            InListSet::UInt16(set) => {
+                lookup_in_set!(array, UInt16Array, set, contains_null, |v| &v)
+            }
+            InListSet::UInt32(set) => {
+                lookup_in_set!(array, UInt32Array, set, contains_null, |v| &v)
+            }
+            InListSet::UInt64(set) => {
+                lookup_in_set!(array, UInt64Array, set, contains_null, |v| &v)
+            }
+            InListSet::Float32(set) => {
+                lookup_in_set!(array, Float32Array, set, contains_null, |v| &(v + 0.0)
+                    .to_bits())
+            }
+            InListSet::Float64(set) => {
+                lookup_in_set!(array, Float64Array, set, contains_null, |v| &(v + 0.0)
+                    .to_bits())
+            }
+            InListSet::Utf8(set) => {
+                lookup_in_set!(array, StringArray, set, contains_null, |v| v)
+            }
+            InListSet::LargeUtf8(set) => {
+                lookup_in_set!(array, LargeStringArray, set, contains_null, |v| v)
+            }
+            InListSet::TimestampNanosecond(set) => {
+                lookup_in_set!(array, TimestampNanosecondArray, set, contains_null, |v| {
+                    &v
+                })
+            }
+        })
+    }
+}
+
+/// IN list expression, such as `c1 IN (1, 2, 3)`
+#[derive(Debug)]
+pub struct InListExpr {
+    expr: Arc<dyn PhysicalExpr>,
+    list: Vec<Arc<dyn PhysicalExpr>>,
+    negated: bool,
+    /// The hashed values of the list, when it only contains literals
+    values: Option<InListValues>,
+}
+
+impl InListExpr {
+    /// Create a new IN list expression
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        list: Vec<Arc<dyn PhysicalExpr>>,
+        negated: bool,
+    ) -> Self {
+        // a list of literals is looked up in a hash set rather than compared
+        // with one value at a time
+        let values = list
+            .iter()
+            .map(|e| e.as_any().downcast_ref::<Literal>().map(|l| &l.value))
+            .collect::<Option<Vec<_>>>()
+            .and_then(|values| InListValues::try_new(&values));
+        Self {
+            expr,
+            list,
+            negated,
+            values,
+        }
+    }
+
+    /// Input expression
+    pub fn expr(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.expr
+    }
+
+    /// List to search in
+    pub fn list(&self) -> &[Arc<dyn PhysicalExpr>] {
+        &self.list
+    }
+
+    /// Is this negated e.g. NOT IN LIST
+    pub fn negated(&self) -> bool {
+        self.negated
+    }
+
+    /// Compares `array` with each expression of the list in turn.
+    fn compared_in_list(
+        &self,
+        array: &ArrayRef,
+        batch: &RecordBatch,
+    ) -> Result<BooleanArray> {
+        let mut result = vec![Some(false); array.len()];
+        for expr in &self.list {
+            let values = expr.evaluate(batch)?;
+            let equal: Result<ArrayRef> = binary_array_op!(array, values, eq);
+            let equal = equal?;
+            let equal =
+                equal
+                    .as_any()
+                    .downcast_ref::<BooleanArray>()
+                    .ok_or_else(|| {
+                        DataFusionError::Internal(
+                            "in_list failed to downcast array".to_string(),
+                        )
+                    })?;
+            for (i, found) in result.iter_mut().enumerate() {
+                // `found OR equal`, where `NULL OR true` is true
+                *found = match (*found, equal.is_null(i)) {
+                    (Some(true), _) => Some(true),
+                    (_, true) => None,
+                    (found, false) if !equal.value(i) => found,
+                    _ => Some(true),
+                };
+            }
+        }
+        Ok(BooleanArray::from(result))
+    }
+}
+
+impl fmt::Display for InListExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let list = self.list.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        if self.negated {
+            write!(f, "{} NOT IN ({})", self.expr, list.join(", "))
+        } else {
+            write!(f, "{} IN ({})", self.expr, list.join(", "))
+        }
+    }
+}
+
+impl PhysicalExpr for InListExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn nullable(&self, input_schema: &Schema) -> Result<bool> {
+        let mut nullable = self.expr.nullable(input_schema)?;
+        for expr in &self.list {
+            nullable = nullable || expr.nullable(input_schema)?;
+        }
+        Ok(nullable)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let array = self.expr.evaluate(batch)?;
+        let found = match &self.values {
+            Some(values) => values.lookup(&array)?,
+            None => self.compared_in_list(&array, batch)?,
+        };
+
+        if self.negated {
+            Ok(Arc::new(not(&found)?))
+        } else {
+            Ok(Arc::new(found))
+        }
+    }
+}
+
+/// Casts a scalar value to `cast_type`, by casting it as a single row array.
+pub(crate) fn cast_scalar(
+    value: &ScalarValue,
+    cast_type: &DataType,
+) -> Result<ScalarValue> {
+    if &value.get_datatype() == cast_type {
+        return Ok(value.clone());
+    }
+    let array = kernels::cast::cast(&value.to_array(), cast_type)?;
+    ScalarValue::try_from_array(&array, 0)
+}
+
+/// Create an IN list expression whose value and list are coerced to a common type.
+/// This function errors if there is no such type.
+pub fn in_list(
+    expr: Arc<dyn PhysicalExpr>,
+    list: Vec<Arc<dyn PhysicalExpr>>,
+    negated: bool,
+    input_schema: &Schema,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let mut common_type = expr.data_type(input_schema)?;
+    for e in &list {
+        let list_type = e.data_type(input_schema)?;
+        common_type = eq_coercion(&common_type, &list_type).ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "'{:?} IN ({:?})' can't be evaluated because there isn't a common type to coerce the types to",
+                common_type, list_type
+            ))
+        })?;
+    }
+
+    let list = list
+        .iter()
+        .map(|e| match e.as_any().downcast_ref::<Literal>() {
+            // literals are cast while planning so that they can be hashed
+            Some(literal) => Ok(lit(cast_scalar(&literal.value, &common_type)?)),
+            None => cast(e.clone(), input_schema, common_type.clone()),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Arc::new(InListExpr::new(
+        cast(expr, input_schema, common_type)?,
+        list,
+        negated,
+    )))
+}
+
 /// CAST expression casts an expression to a specific data type
 #[derive(Debug)]
 pub struct CastExpr {
@@ -1530,6 +2470,23 @@ pub struct CastExpr {
     cast_type: DataType,
 }
 
+impl CastExpr {
+    /// Create a new CastExpr
+    pub fn new(expr: Arc<dyn PhysicalExpr>, cast_type: DataType) -> Self {
+        Self { expr, cast_type }
+    }
+
+    /// The expression to cast
+    pub fn expr(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.expr
+    }
+
+    /// The data type to cast to
+    pub fn cast_type(&self) -> &DataType {
+        &self.cast_type
+    }
+}
+
 /// Determine if a DataType is numeric or not
 pub fn is_numeric(dt: &DataType) -> bool {
     match dt {
@@ -1547,6 +2504,10 @@ impl fmt::Display for CastExpr {
 }
 
 impl PhysicalExpr for CastExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
         Ok(self.cast_type.clone())
     }
@@ -1583,6 +2544,125 @@ pub fn cast(
     }
 }
 
+/// Returns the field of the values of `expr[key]` for an `expr` of type
+/// `data_type`: the field named `key` of a struct, or the item of a list indexed by
+/// an integer `key`.
+pub fn get_indexed_field(data_type: &DataType, key: &ScalarValue) -> Result<Field> {
+    match (data_type, key) {
+        (DataType::Struct(fields), ScalarValue::Utf8(Some(name))) => fields
+            .iter()
+            .find(|field| field.name() == name)
+            .cloned()
+            .ok_or_else(|| {
+                DataFusionError::Plan(format!("Struct has no field named {}", name))
+            }),
+        (DataType::Struct(_), _) => Err(DataFusionError::Plan(format!(
+            "The fields of structs are accessed by name, but got {:?}",
+            key
+        ))),
+        (DataType::List(item), ScalarValue::Int64(Some(_)))
+        | (DataType::LargeList(item), ScalarValue::Int64(Some(_)))
+        | (DataType::FixedSizeList(item, _), ScalarValue::Int64(Some(_))) => {
+            Ok(item.as_ref().clone())
+        }
+        (DataType::List(_), _)
+        | (DataType::LargeList(_), _)
+        | (DataType::FixedSizeList(_, _), _) => Err(DataFusionError::Plan(format!(
+            "The elements of lists are accessed by an integer index, but got {:?}",
+            key
+        ))),
+        _ => Err(DataFusionError::Plan(format!(
+            "Only the fields of structs and the elements of lists can be accessed, \
+             but got an expression of type {:?}",
+            data_type
+        ))),
+    }
+}
+
+/// Expression that accesses a field of a struct or an element of a list, see
+/// [crate::logical_plan::Expr::GetIndexedField]
+#[derive(Debug)]
+pub struct GetIndexedFieldExpr {
+    /// The struct or list
+    arg: Arc<dyn PhysicalExpr>,
+    /// The name of the field or the 1-based index of the element
+    key: ScalarValue,
+}
+
+impl GetIndexedFieldExpr {
+    /// Create a new GetIndexedFieldExpr
+    pub fn new(arg: Arc<dyn PhysicalExpr>, key: ScalarValue) -> Self {
+        Self { arg, key }
+    }
+
+    /// The struct or list
+    pub fn arg(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.arg
+    }
+
+    /// The name of the field or the 1-based index of the element
+    pub fn key(&self) -> &ScalarValue {
+        &self.key
+    }
+}
+
+impl fmt::Display for GetIndexedFieldExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}[{}]", self.arg, self.key)
+    }
+}
+
+impl PhysicalExpr for GetIndexedFieldExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        let data_type = self.arg.data_type(input_schema)?;
+        Ok(get_indexed_field(&data_type, &self.key)?
+            .data_type()
+            .clone())
+    }
+
+    fn nullable(&self, _input_schema: &Schema) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let array = self.arg.evaluate(batch)?;
+        match (array.data_type(), &self.key) {
+            (DataType::Struct(_), ScalarValue::Utf8(Some(name))) => array
+                .as_any()
+                .downcast_ref::<StructArray>()
+                .and_then(|array| array.column_by_name(name))
+                .cloned()
+                .ok_or_else(|| {
+                    DataFusionError::Execution(format!(
+                        "Struct has no field named {}",
+                        name
+                    ))
+                }),
+            (_, ScalarValue::Int64(Some(index))) => list_element(&array, *index),
+            (data_type, key) => Err(DataFusionError::Execution(format!(
+                "Cannot access {:?} of an array of type {:?}",
+                key, data_type
+            ))),
+        }
+    }
+}
+
+/// Return a PhysicalExpression that accesses the field `key` of the struct or the
+/// element `key` of the list `arg`
+pub fn get_indexed_field_expr(
+    arg: Arc<dyn PhysicalExpr>,
+    key: ScalarValue,
+    input_schema: &Schema,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    // errors on invalid keys when the expression is planned
+    get_indexed_field(&arg.data_type(input_schema)?, &key)?;
+    Ok(Arc::new(GetIndexedFieldExpr::new(arg, key)))
+}
+
 /// Represents a non-null literal value
 #[derive(Debug)]
 pub struct Literal {
@@ -1594,26 +2674,15 @@ impl Literal {
     pub fn new(value: ScalarValue) -> Self {
         Self { value }
     }
+
+    /// Get the scalar value
+    pub fn value(&self) -> &ScalarValue {
+        &self.value
+    }
 }
 
 /// Build array containing the same literal value repeated. This is necessary because the Arrow
 /// memory model does not have the concept of a scalar value currently.
-macro_rules! build_literal_array {
-    ($BATCH:ident, $BUILDER:ident, $VALUE:expr) => {{
-        let mut builder = $BUILDER::new($BATCH.num_rows());
-        if $VALUE.is_none() {
-            for _ in 0..$BATCH.num_rows() {
-                builder.append_null()?;
-            }
-        } else {
-            for _ in 0..$BATCH.num_rows() {
-                builder.append_value($VALUE.unwrap())?;
-            }
-        }
-        Ok(Arc::new(builder.finish()))
-    }};
-}
-
 impl fmt::Display for Literal {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.value)
@@ -1621,6 +2690,10 @@ impl fmt::Display for Literal {
 }
 
 impl PhysicalExpr for Literal {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
         Ok(self.value.get_datatype())
     }
@@ -1630,45 +2703,7 @@ impl PhysicalExpr for Literal {
     }
 
     fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
-        match &self.value {
-            ScalarValue::Int8(value) => build_literal_array!(batch, Int8Builder, *value),
-            ScalarValue::Int16(value) => {
-                build_literal_array!(batch, Int16Builder, *value)
-            }
-            ScalarValue::Int32(value) => {
-                build_literal_array!(batch, Int32Builder, *value)
-            }
-            ScalarValue::Int64(value) => {
-                build_literal_array!(batch, Int64Builder, *value)
-            }
-            ScalarValue::UInt8(value) => {
-                build_literal_array!(batch, UInt8Builder, *value)
-            }
-            ScalarValue::UInt16(value) => {
-                build_literal_array!(batch, UInt16Builder, *value)
-            }
-            ScalarValue::UInt32(value) => {
-                build_literal_array!(batch, UInt32Builder, *value)
-            }
-            ScalarValue::UInt64(value) => {
-                build_literal_array!(batch, UInt64Builder, *value)
-            }
-            ScalarValue::Float32(value) => {
-                build_literal_array!(batch, Float32Builder, *value)
-            }
-            ScalarValue::Float64(value) => {
-                build_literal_array!(batch, Float64Builder, *value)
-            }
-            ScalarValue::Utf8(value) => build_literal_array!(
-                batch,
-                StringBuilder,
-                value.as_ref().and_then(|e| Some(&*e))
-            ),
-            other => Err(DataFusionError::Internal(format!(
-                "Unsupported literal type {:?}",
-                other
-            ))),
-        }
+        Ok(self.value.to_array_of_size(batch.num_rows()))
     }
 }
 
@@ -1686,6 +2721,16 @@ pub struct PhysicalSortExpr {
     pub options: SortOptions,
 }
 
+/// Two sort expressions are equal when they sort rows in the same order, that is when
+/// their expressions are displayed the same and their options are equal
+impl PartialEq for PhysicalSortExpr {
+    fn eq(&self, other: &PhysicalSortExpr) -> bool {
+        self.options.descending == other.options.descending
+            && self.options.nulls_first == other.options.nulls_first
+            && self.expr.to_string() == other.expr.to_string()
+    }
+}
+
 impl PhysicalSortExpr {
     /// evaluate the sort expression into SortColumn that can be passed into arrow sort kernel
     pub fn evaluate_to_sort_column(&self, batch: &RecordBatch) -> Result<SortColumn> {
@@ -1704,7 +2749,7 @@ mod tests {
     use arrow::{
         array::{
             LargeStringArray, PrimitiveArray, PrimitiveBuilder, StringArray,
-            StringDictionaryBuilder, Time64NanosecondArray,
+            StringBuilder, StringDictionaryBuilder, Time64NanosecondArray,
         },
         util::display::array_value_to_string,
     };
@@ -1917,6 +2962,77 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn binary_regex_match() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new("b", DataType::Utf8, true),
+        ]);
+        let a =
+            StringArray::from(vec![Some("Arrow"), Some("arrow"), Some("parquet"), None]);
+        let b = StringArray::from(vec![Some("^a"), Some("^A"), Some("^a"), Some("^a")]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(a), Arc::new(b)],
+        )?;
+
+        let pattern = |p: &str| lit(ScalarValue::Utf8(Some(p.to_string())));
+
+        // (operator, pattern, expected result)
+        let cases = vec![
+            (
+                Operator::RegexMatch,
+                pattern("^a"),
+                "[false, true, false, NULL]",
+            ),
+            (
+                Operator::RegexIMatch,
+                pattern("^a"),
+                "[true, true, false, NULL]",
+            ),
+            (
+                Operator::RegexNotMatch,
+                pattern("^a"),
+                "[true, false, true, NULL]",
+            ),
+            (
+                Operator::RegexNotIMatch,
+                pattern("^a"),
+                "[false, false, true, NULL]",
+            ),
+            // patterns from a column
+            (
+                Operator::RegexMatch,
+                col("b"),
+                "[false, false, false, NULL]",
+            ),
+            (Operator::RegexIMatch, col("b"), "[true, true, false, NULL]"),
+        ];
+
+        for (op, pattern, expected) in cases {
+            let expr = binary(col("a"), op, pattern, &schema)?;
+            assert_eq!(expr.data_type(&schema)?, DataType::Boolean);
+
+            let result = expr.evaluate(&batch)?;
+            let result = (0..result.len())
+                .map(|i| {
+                    if result.is_null(i) {
+                        "NULL".to_string()
+                    } else {
+                        array_value_to_string(&result, i).unwrap()
+                    }
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(format!("[{}]", result.join(", ")), expected);
+        }
+
+        // an invalid pattern is an execution error
+        let expr = binary(col("a"), Operator::RegexMatch, pattern("("), &schema)?;
+        assert!(expr.evaluate(&batch).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_dictionary_type_coersion() -> Result<()> {
         use DataType::*;
@@ -2009,6 +3125,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn dictionary_literal_comparisons() -> Result<()> {
+        let dict_type =
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        let schema = Arc::new(Schema::new(vec![Field::new("dict", dict_type, true)]));
+        let dict_array: DictionaryArray<Int32Type> =
+            vec![Some("b"), None, Some("a"), Some("c")]
+                .into_iter()
+                .collect();
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(dict_array)])?;
+        let literal = || lit(ScalarValue::Utf8(Some("b".to_string())));
+
+        // the dictionary is not cast to strings
+        let expression = binary(col("dict"), Operator::Eq, literal(), &schema)?;
+        let expression = expression.as_any().downcast_ref::<BinaryExpr>().unwrap();
+        assert!(expression.left().as_any().is::<Column>());
+        let result = expression.evaluate(&batch)?;
+        assert_eq!("true\n\nfalse\nfalse", array_to_string(&result)?);
+
+        let expression = binary(col("dict"), Operator::GtEq, literal(), &schema)?;
+        let result = expression.evaluate(&batch)?;
+        assert_eq!("true\n\nfalse\ntrue", array_to_string(&result)?);
+
+        // the operator is swapped when the literal is on the left
+        let expression = binary(literal(), Operator::Lt, col("dict"), &schema)?;
+        let result = expression.evaluate(&batch)?;
+        assert_eq!("false\n\nfalse\ntrue", array_to_string(&result)?);
+        Ok(())
+    }
+
     // Convert the array to a newline delimited string of pretty printed values
     fn array_to_string(array: &ArrayRef) -> Result<String> {
         let s = (0..array.len())
@@ -2244,6 +3390,59 @@ mod tests {
         )
     }
 
+    #[test]
+    fn min_date32() -> Result<()> {
+        let a: ArrayRef = Arc::new(Date32Array::from(vec![Some(3), None, Some(1)]));
+        generic_test_op!(
+            a,
+            DataType::Date32(DateUnit::Day),
+            Min,
+            ScalarValue::Date32(Some(1)),
+            DataType::Date32(DateUnit::Day)
+        )
+    }
+
+    #[test]
+    fn max_timestamp_with_timezone() -> Result<()> {
+        let tz = Some(Arc::new("UTC".to_string()));
+        let a: ArrayRef = Arc::new(TimestampSecondArray::from_opt_vec(
+            vec![Some(5), Some(9), None],
+            tz.clone(),
+        ));
+        generic_test_op!(
+            a,
+            DataType::Timestamp(TimeUnit::Second, tz.clone()),
+            Max,
+            ScalarValue::TimestampSecond(Some(9), tz.clone()),
+            DataType::Timestamp(TimeUnit::Second, tz.clone())
+        )
+    }
+
+    #[test]
+    fn max_binary() -> Result<()> {
+        let values: Vec<Option<&[u8]>> =
+            vec![Some(&[1, 2][..]), None, Some(&[3][..]), Some(&[][..])];
+        let a: ArrayRef = Arc::new(BinaryArray::from(values));
+        generic_test_op!(
+            a,
+            DataType::Binary,
+            Max,
+            ScalarValue::Binary(Some(vec![3])),
+            DataType::Binary
+        )
+    }
+
+    #[test]
+    fn min_binary_scalars() -> Result<()> {
+        let lhs = ScalarValue::Binary(Some(vec![1, 2]));
+        assert_eq!(min(&lhs, &ScalarValue::Binary(None))?, lhs);
+        assert_eq!(
+            min(&lhs, &ScalarValue::Binary(Some(vec![1])))?,
+            ScalarValue::Binary(Some(vec![1]))
+        );
+        Ok(())
+    }
+
     #[test]
     fn sum_i32_with_nulls() -> Result<()> {
         let a: ArrayRef = Arc::new(Int32Array::from(vec![
@@ -2807,4 +4006,103 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn is_distinct_from_op() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let a = Int32Array::from(vec![Some(1), None, None, Some(4)]);
+        let b = Int32Array::from(vec![Some(1), None, Some(3), Some(5)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(a), Arc::new(b)],
+        )?;
+
+        // (operator, expected result)
+        let cases = vec![
+            (Operator::IsDistinctFrom, vec![false, false, true, true]),
+            (Operator::IsNotDistinctFrom, vec![true, true, false, false]),
+        ];
+        for (op, expected) in cases {
+            let expr = binary(col("a"), op, col("b"), &schema)?;
+            assert_eq!(expr.nullable(&schema)?, false);
+
+            let result = expr.evaluate(&batch)?;
+            let result = result
+                .as_any()
+                .downcast_ref::<BooleanArray>()
+                .expect("failed to downcast to BooleanArray");
+            assert_eq!(&BooleanArray::from(expected), result);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn in_list_op() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let a = Int32Array::from(vec![Some(1), Some(2), None, Some(4)]);
+        let b = Int32Array::from(vec![Some(1), Some(3), Some(3), None]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(a), Arc::new(b)],
+        )?;
+
+        let int64 = |v: Option<i64>| lit(ScalarValue::Int64(v));
+
+        // (list, negated, expected result)
+        let cases = vec![
+            // literals, coerced to a common type and hashed
+            (
+                vec![int64(Some(1)), int64(Some(4))],
+                false,
+                vec![Some(true), Some(false), None, Some(true)],
+            ),
+            (
+                vec![int64(Some(1)), int64(Some(4))],
+                true,
+                vec![Some(false), Some(true), None, Some(false)],
+            ),
+            // a value that is not found in a list with a null is unknown
+            (
+                vec![int64(Some(1)), int64(None)],
+                false,
+                vec![Some(true), None, None, None],
+            ),
+            // expressions, compared one at a time
+            (
+                vec![col("b"), lit(ScalarValue::Int32(Some(4)))],
+                false,
+                vec![Some(true), Some(false), None, Some(true)],
+            ),
+        ];
+        for (list, negated, expected) in cases {
+            let expr = in_list(col("a"), list, negated, &schema)?;
+            assert_eq!(expr.data_type(&schema)?, DataType::Boolean);
+
+            let result = expr.evaluate(&batch)?;
+            let result = result
+                .as_any()
+                .downcast_ref::<BooleanArray>()
+                .expect("failed to downcast to BooleanArray");
+            assert_eq!(&BooleanArray::from(expected), result);
+        }
+
+        // there is no common type for an integer and a string
+        let list = vec![lit(ScalarValue::Utf8(Some("a".to_string())))];
+        assert!(in_list(col("a"), list, false, &schema).is_err());
+
+        // only lists of literals are hashed, once
+        let list = vec![lit(ScalarValue::Int32(Some(1)))];
+        assert!(InListExpr::new(col("a"), list, false).values.is_some());
+        let list = vec![lit(ScalarValue::Int32(Some(1))), col("b")];
+        assert!(InListExpr::new(col("a"), list, false).values.is_none());
+
+        Ok(())
+    }
 }
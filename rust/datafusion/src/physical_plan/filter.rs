@@ -23,8 +23,10 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use super::metrics::{BaselineMetrics, MetricsSet};
 use super::{RecordBatchStream, SendableRecordBatchStream};
 use crate::error::{DataFusionError, Result};
+use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::{ExecutionPlan, Partitioning, PhysicalExpr};
 use arrow::array::BooleanArray;
 use arrow::compute::filter;
@@ -44,6 +46,8 @@ pub struct FilterExec {
     predicate: Arc<dyn PhysicalExpr>,
     /// The input plan
     input: Arc<dyn ExecutionPlan>,
+    /// Execution metrics
+    metrics: BaselineMetrics,
 }
 
 impl FilterExec {
@@ -56,6 +60,7 @@ impl FilterExec {
             DataType::Boolean => Ok(Self {
                 predicate: predicate.clone(),
                 input: input.clone(),
+                metrics: BaselineMetrics::new(),
             }),
             other => Err(DataFusionError::Plan(format!(
                 "Filter predicate must return boolean values, not {:?}",
@@ -63,6 +68,16 @@ impl FilterExec {
             ))),
         }
     }
+
+    /// The expression to filter on
+    pub fn predicate(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.predicate
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
 }
 
 #[async_trait]
@@ -87,6 +102,10 @@ impl ExecutionPlan for FilterExec {
         self.input.output_partitioning()
     }
 
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        self.input.output_ordering()
+    }
+
     fn with_new_children(
         &self,
         children: Vec<Arc<dyn ExecutionPlan>>,
@@ -107,8 +126,13 @@ impl ExecutionPlan for FilterExec {
             schema: self.input.schema().clone(),
             predicate: self.predicate.clone(),
             input: self.input.execute(partition).await?,
+            metrics: self.metrics.clone(),
         }))
     }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
 }
 
 /// The FilterExec streams wraps the input iterator and applies the predicate expression to
@@ -120,6 +144,8 @@ struct FilterExecStream {
     predicate: Arc<dyn PhysicalExpr>,
     /// The input partition to filter.
     input: SendableRecordBatchStream,
+    /// Execution metrics
+    metrics: BaselineMetrics,
 }
 
 fn batch_filter(
@@ -159,10 +185,14 @@ impl Stream for FilterExecStream {
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        self.input.poll_next_unpin(cx).map(|x| match x {
-            Some(Ok(batch)) => Some(batch_filter(&batch, &self.predicate)),
+        let poll = self.input.poll_next_unpin(cx).map(|x| match x {
+            Some(Ok(batch)) => {
+                let _timer = self.metrics.elapsed_compute().timer();
+                Some(batch_filter(&batch, &self.predicate))
+            }
             other => other,
-        })
+        });
+        self.metrics.record_poll(poll)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
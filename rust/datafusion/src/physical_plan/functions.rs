@@ -30,23 +30,29 @@
 //! to a function that supports f64, it is coerced to f64.
 
 use super::{
+    expressions::{cast, lit, Literal},
     type_coercion::{coerce, data_types},
     PhysicalExpr,
 };
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::array_expressions;
+use crate::physical_plan::conditional_expressions;
 use crate::physical_plan::datetime_expressions;
 use crate::physical_plan::math_expressions;
+use crate::physical_plan::regex_expressions;
 use crate::physical_plan::string_expressions;
+use crate::scalar::ScalarValue;
 use arrow::{
-    array::ArrayRef,
+    array::{ArrayRef, UInt32Array},
     compute::kernels::length::length,
-    datatypes::TimeUnit,
+    compute::kernels::take::take,
     datatypes::{DataType, Field, Schema},
+    datatypes::{DateUnit, IntervalUnit, TimeUnit},
     record_batch::RecordBatch,
 };
+use chrono::Utc;
 use fmt::{Debug, Formatter};
-use std::{fmt, str::FromStr, sync::Arc};
+use std::{any::Any, fmt, str::FromStr, sync::Arc};
 
 /// A function's signature, which defines the function's supported argument types.
 #[derive(Debug, Clone)]
@@ -66,11 +72,85 @@ pub enum Signature {
     Exact(Vec<DataType>),
     /// fixed number of arguments of arbitrary types
     Any(usize),
+    /// one of a list of signatures
+    // A function such as `substr` is `OneOf(vec![Exact(vec![Utf8, Int64]), Exact(vec![Utf8, Int64, Int64])])`
+    // Alternatives are tried in order, preferring one that matches the arguments exactly
+    OneOf(Vec<Signature>),
+}
+
+/// Whether a function returns the same value when it is called with the same
+/// arguments, which decides whether its calls may be evaluated when the plan is
+/// optimized or computed only once per row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Volatility {
+    /// always returns the same value for the same arguments, e.g. `sqrt`
+    Immutable,
+    /// returns the same value for the same arguments within a query, e.g. `now`
+    Stable,
+    /// may return a different value on each call, even within a row, e.g. `random`
+    Volatile,
+}
+
+/// The value of an argument or of the result of a scalar function: either an array
+/// with a value per row, or a single value for all the rows, which is not expanded
+/// into an array
+#[derive(Debug, Clone)]
+pub enum ColumnarValue {
+    /// a value per row
+    Array(ArrayRef),
+    /// the same value for all the rows
+    Scalar(ScalarValue),
+}
+
+impl ColumnarValue {
+    /// The type of the values
+    pub fn data_type(&self) -> DataType {
+        match self {
+            ColumnarValue::Array(array) => array.data_type().clone(),
+            ColumnarValue::Scalar(value) => value.get_datatype(),
+        }
+    }
+
+    /// The values as an array, where a scalar is repeated `num_rows` times
+    pub fn into_array(self, num_rows: usize) -> ArrayRef {
+        match self {
+            ColumnarValue::Array(array) => array,
+            ColumnarValue::Scalar(value) => value.to_array_of_size(num_rows),
+        }
+    }
 }
 
 /// Scalar function
 pub type ScalarFunctionImplementation =
-    Arc<dyn Fn(&[ArrayRef]) -> Result<ArrayRef> + Send + Sync>;
+    Arc<dyn Fn(&[ColumnarValue]) -> Result<ColumnarValue> + Send + Sync>;
+
+/// Adapts a function of arrays into a [ScalarFunctionImplementation], for functions
+/// that do not handle scalar arguments themselves: the scalar arguments are expanded
+/// into arrays as long as the other arguments. When all the arguments are scalars,
+/// the function is called with arrays of one row and its result is a scalar.
+pub fn make_scalar_function<F>(inner: F) -> ScalarFunctionImplementation
+where
+    F: Fn(&[ArrayRef]) -> Result<ArrayRef> + Send + Sync + 'static,
+{
+    Arc::new(move |args: &[ColumnarValue]| {
+        let len = args.iter().find_map(|arg| match arg {
+            ColumnarValue::Array(array) => Some(array.len()),
+            ColumnarValue::Scalar(_) => None,
+        });
+        let arrays = args
+            .iter()
+            .map(|arg| arg.clone().into_array(len.unwrap_or(1)))
+            .collect::<Vec<_>>();
+        let result = inner(&arrays)?;
+        match len {
+            Some(_) => Ok(ColumnarValue::Array(result)),
+            // types that have no scalar value stay an array of one row
+            None => Ok(ScalarValue::try_from_array(&result, 0)
+                .map(ColumnarValue::Scalar)
+                .unwrap_or(ColumnarValue::Array(result))),
+        }
+    })
+}
 
 /// A function's return type
 pub type ReturnTypeFunction =
@@ -119,14 +199,101 @@ pub enum BuiltinScalarFunction {
     Concat,
     /// to_timestamp
     ToTimestamp,
+    /// date_trunc
+    DateTrunc,
+    /// date_part, also used by `EXTRACT`
+    DatePart,
+    /// now
+    Now,
+    /// to_char
+    ToChar,
+    /// tumble, the start of the tumbling window of a timestamp
+    Tumble,
     /// construct an array from columns
     Array,
+    /// array_length
+    ArrayLength,
+    /// array_contains
+    ArrayContains,
+    /// lower
+    Lower,
+    /// upper
+    Upper,
+    /// trim
+    Trim,
+    /// btrim
+    Btrim,
+    /// ltrim
+    Ltrim,
+    /// rtrim
+    Rtrim,
+    /// substr
+    Substr,
+    /// left
+    Left,
+    /// right
+    Right,
+    /// lpad
+    Lpad,
+    /// rpad
+    Rpad,
+    /// replace
+    Replace,
+    /// split_part
+    SplitPart,
+    /// strpos
+    Strpos,
+    /// reverse
+    Reverse,
+    /// initcap
+    Initcap,
+    /// repeat
+    Repeat,
+    /// ascii
+    Ascii,
+    /// chr
+    Chr,
+    /// starts_with
+    StartsWith,
+    /// concat_ws
+    ConcatWithSeparator,
+    /// md5
+    MD5,
+    /// sha256
+    SHA256,
+    /// octet_length
+    OctetLength,
+    /// bit_length
+    BitLength,
+    /// regexp_match
+    RegexpMatch,
+    /// regexp_replace
+    RegexpReplace,
+    /// coalesce
+    Coalesce,
+    /// nullif
+    NullIf,
 }
 
 impl fmt::Display for BuiltinScalarFunction {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // lowercase of the debug.
-        write!(f, "{}", format!("{:?}", self).to_lowercase())
+        match self {
+            // names whose words are separated by an underscore
+            BuiltinScalarFunction::SplitPart => write!(f, "split_part"),
+            BuiltinScalarFunction::StartsWith => write!(f, "starts_with"),
+            BuiltinScalarFunction::ConcatWithSeparator => write!(f, "concat_ws"),
+            BuiltinScalarFunction::OctetLength => write!(f, "octet_length"),
+            BuiltinScalarFunction::BitLength => write!(f, "bit_length"),
+            BuiltinScalarFunction::RegexpMatch => write!(f, "regexp_match"),
+            BuiltinScalarFunction::RegexpReplace => write!(f, "regexp_replace"),
+            BuiltinScalarFunction::DateTrunc => write!(f, "date_trunc"),
+            BuiltinScalarFunction::DatePart => write!(f, "date_part"),
+            BuiltinScalarFunction::ToChar => write!(f, "to_char"),
+            BuiltinScalarFunction::ArrayLength => write!(f, "array_length"),
+            BuiltinScalarFunction::ArrayContains => write!(f, "array_contains"),
+            // lowercase of the debug.
+            _ => write!(f, "{}", format!("{:?}", self).to_lowercase()),
+        }
     }
 }
 
@@ -154,7 +321,43 @@ impl FromStr for BuiltinScalarFunction {
             "length" => BuiltinScalarFunction::Length,
             "concat" => BuiltinScalarFunction::Concat,
             "to_timestamp" => BuiltinScalarFunction::ToTimestamp,
+            "date_trunc" => BuiltinScalarFunction::DateTrunc,
+            "date_part" => BuiltinScalarFunction::DatePart,
+            "now" => BuiltinScalarFunction::Now,
+            "tumble" => BuiltinScalarFunction::Tumble,
+            "to_char" => BuiltinScalarFunction::ToChar,
             "array" => BuiltinScalarFunction::Array,
+            "array_length" => BuiltinScalarFunction::ArrayLength,
+            "array_contains" => BuiltinScalarFunction::ArrayContains,
+            "lower" => BuiltinScalarFunction::Lower,
+            "upper" => BuiltinScalarFunction::Upper,
+            "trim" => BuiltinScalarFunction::Trim,
+            "btrim" => BuiltinScalarFunction::Btrim,
+            "ltrim" => BuiltinScalarFunction::Ltrim,
+            "rtrim" => BuiltinScalarFunction::Rtrim,
+            "substr" => BuiltinScalarFunction::Substr,
+            "left" => BuiltinScalarFunction::Left,
+            "right" => BuiltinScalarFunction::Right,
+            "lpad" => BuiltinScalarFunction::Lpad,
+            "rpad" => BuiltinScalarFunction::Rpad,
+            "replace" => BuiltinScalarFunction::Replace,
+            "split_part" => BuiltinScalarFunction::SplitPart,
+            "strpos" => BuiltinScalarFunction::Strpos,
+            "reverse" => BuiltinScalarFunction::Reverse,
+            "initcap" => BuiltinScalarFunction::Initcap,
+            "repeat" => BuiltinScalarFunction::Repeat,
+            "ascii" => BuiltinScalarFunction::Ascii,
+            "chr" => BuiltinScalarFunction::Chr,
+            "starts_with" => BuiltinScalarFunction::StartsWith,
+            "concat_ws" => BuiltinScalarFunction::ConcatWithSeparator,
+            "md5" => BuiltinScalarFunction::MD5,
+            "sha256" => BuiltinScalarFunction::SHA256,
+            "regexp_match" => BuiltinScalarFunction::RegexpMatch,
+            "regexp_replace" => BuiltinScalarFunction::RegexpReplace,
+            "octet_length" => BuiltinScalarFunction::OctetLength,
+            "bit_length" => BuiltinScalarFunction::BitLength,
+            "coalesce" => BuiltinScalarFunction::Coalesce,
+            "nullif" => BuiltinScalarFunction::NullIf,
             _ => {
                 return Err(DataFusionError::Plan(format!(
                     "There is no built-in function named {}",
@@ -165,6 +368,17 @@ impl FromStr for BuiltinScalarFunction {
     }
 }
 
+impl BuiltinScalarFunction {
+    /// The [Volatility] of the function
+    pub fn volatility(&self) -> Volatility {
+        match self {
+            // the current time changes between queries, but not within one
+            BuiltinScalarFunction::Now => Volatility::Stable,
+            _ => Volatility::Immutable,
+        }
+    }
+}
+
 /// Returns the datatype of the scalar function
 pub fn return_type(
     fun: &BuiltinScalarFunction,
@@ -176,9 +390,9 @@ pub fn return_type(
     // verify that this is a valid set of data types for this function
     data_types(&arg_types, &signature(fun))?;
 
-    if arg_types.len() == 0 {
+    if arg_types.len() == 0 && fun != &BuiltinScalarFunction::Now {
         // functions currently cannot be evaluated without arguments, as they can't
-        // know the number of rows to return.
+        // know the number of rows to return. `now` is planned with a literal argument.
         return Err(DataFusionError::Plan(
             format!("Function '{}' requires at least one argument", fun).to_string(),
         ));
@@ -188,28 +402,127 @@ pub fn return_type(
     // will be built-in functions whose return type depends on the
     // incoming type.
     match fun {
-        BuiltinScalarFunction::Length => Ok(match arg_types[0] {
-            DataType::LargeUtf8 => DataType::Int64,
-            DataType::Utf8 => DataType::Int32,
-            _ => {
-                // this error is internal as `data_types` should have captured this.
-                return Err(DataFusionError::Internal(
-                    "The length function can only accept strings.".to_string(),
+        BuiltinScalarFunction::Length
+        | BuiltinScalarFunction::OctetLength
+        | BuiltinScalarFunction::BitLength
+        | BuiltinScalarFunction::Strpos => utf8_to_int_type(&arg_types[0], fun),
+        BuiltinScalarFunction::Lower
+        | BuiltinScalarFunction::Upper
+        | BuiltinScalarFunction::Trim
+        | BuiltinScalarFunction::Btrim
+        | BuiltinScalarFunction::Ltrim
+        | BuiltinScalarFunction::Rtrim
+        | BuiltinScalarFunction::Substr
+        | BuiltinScalarFunction::Left
+        | BuiltinScalarFunction::Right
+        | BuiltinScalarFunction::Lpad
+        | BuiltinScalarFunction::Rpad
+        | BuiltinScalarFunction::Replace
+        | BuiltinScalarFunction::SplitPart
+        | BuiltinScalarFunction::Reverse
+        | BuiltinScalarFunction::Initcap
+        | BuiltinScalarFunction::Repeat
+        | BuiltinScalarFunction::MD5
+        | BuiltinScalarFunction::SHA256
+        | BuiltinScalarFunction::RegexpReplace => utf8_to_str_type(&arg_types[0], fun),
+        BuiltinScalarFunction::RegexpMatch => Ok(DataType::List(Box::new(Field::new(
+            "item",
+            DataType::Utf8,
+            true,
+        )))),
+        BuiltinScalarFunction::Ascii => Ok(DataType::Int32),
+        BuiltinScalarFunction::Chr => Ok(DataType::Utf8),
+        BuiltinScalarFunction::StartsWith => Ok(DataType::Boolean),
+        BuiltinScalarFunction::ConcatWithSeparator => {
+            if arg_types.len() < 2 {
+                return Err(DataFusionError::Plan(
+                    "Function 'concat_ws' requires a separator and at least one string"
+                        .to_string(),
                 ));
             }
-        }),
+            utf8_to_str_type(&arg_types[0], fun)
+        }
         BuiltinScalarFunction::Concat => Ok(DataType::Utf8),
-        BuiltinScalarFunction::ToTimestamp => {
+        BuiltinScalarFunction::ToTimestamp
+        | BuiltinScalarFunction::DateTrunc
+        | BuiltinScalarFunction::Now
+        | BuiltinScalarFunction::Tumble => {
             Ok(DataType::Timestamp(TimeUnit::Nanosecond, None))
         }
+        BuiltinScalarFunction::DatePart => Ok(DataType::Int32),
+        BuiltinScalarFunction::ToChar => Ok(DataType::Utf8),
+        // the arguments are coerced to a common type, which is also the return type
+        BuiltinScalarFunction::Coalesce | BuiltinScalarFunction::NullIf => {
+            Ok(data_types(&arg_types, &signature(fun))?[0].clone())
+        }
         BuiltinScalarFunction::Array => Ok(DataType::FixedSizeList(
             Box::new(Field::new("item", arg_types[0].clone(), true)),
             arg_types.len() as i32,
         )),
+        BuiltinScalarFunction::ArrayLength => {
+            list_item_type(&arg_types[0], fun)?;
+            Ok(DataType::Int64)
+        }
+        BuiltinScalarFunction::ArrayContains => {
+            list_item_type(&arg_types[0], fun)?;
+            Ok(DataType::Boolean)
+        }
         _ => Ok(DataType::Float64),
     }
 }
 
+/// Returns the type of string functions that return strings of the same
+/// type as their argument: `Utf8` for `Utf8` and `LargeUtf8` for `LargeUtf8`.
+fn utf8_to_str_type(
+    arg_type: &DataType,
+    fun: &BuiltinScalarFunction,
+) -> Result<DataType> {
+    match arg_type {
+        DataType::Utf8 => Ok(DataType::Utf8),
+        DataType::LargeUtf8 => Ok(DataType::LargeUtf8),
+        _ => {
+            // this error is internal as `data_types` should have captured this.
+            Err(DataFusionError::Internal(format!(
+                "The {} function can only accept strings.",
+                fun
+            )))
+        }
+    }
+}
+
+/// Returns the type of string functions that return integers, following the
+/// offset size of their argument: `Int32` for `Utf8` and `Int64` for `LargeUtf8`.
+fn utf8_to_int_type(
+    arg_type: &DataType,
+    fun: &BuiltinScalarFunction,
+) -> Result<DataType> {
+    match arg_type {
+        DataType::Utf8 => Ok(DataType::Int32),
+        DataType::LargeUtf8 => Ok(DataType::Int64),
+        _ => {
+            // this error is internal as `data_types` should have captured this.
+            Err(DataFusionError::Internal(format!(
+                "The {} function can only accept strings.",
+                fun
+            )))
+        }
+    }
+}
+
+/// Returns the type of the elements of `arg_type`, the type of the list argument
+/// of the array function `fun`.
+fn list_item_type(arg_type: &DataType, fun: &BuiltinScalarFunction) -> Result<DataType> {
+    match arg_type {
+        DataType::List(field)
+        | DataType::LargeList(field)
+        | DataType::FixedSizeList(field, _) => Ok(field.data_type().clone()),
+        _ => Err(DataFusionError::Plan(format!(
+            "The {} function can only accept lists, but got {:?}.",
+            fun, arg_type
+        ))),
+    }
+}
+
 /// Create a physical (function) expression.
 /// This function errors when `args`' can't be coerced to a valid argument type of the function.
 pub fn create_physical_expr(
@@ -217,36 +530,130 @@ pub fn create_physical_expr(
     args: &Vec<Arc<dyn PhysicalExpr>>,
     input_schema: &Schema,
 ) -> Result<Arc<dyn PhysicalExpr>> {
-    let fun_expr: ScalarFunctionImplementation = Arc::new(match fun {
-        BuiltinScalarFunction::Sqrt => math_expressions::sqrt,
-        BuiltinScalarFunction::Sin => math_expressions::sin,
-        BuiltinScalarFunction::Cos => math_expressions::cos,
-        BuiltinScalarFunction::Tan => math_expressions::tan,
-        BuiltinScalarFunction::Asin => math_expressions::asin,
-        BuiltinScalarFunction::Acos => math_expressions::acos,
-        BuiltinScalarFunction::Atan => math_expressions::atan,
-        BuiltinScalarFunction::Exp => math_expressions::exp,
-        BuiltinScalarFunction::Log => math_expressions::ln,
-        BuiltinScalarFunction::Log2 => math_expressions::log2,
-        BuiltinScalarFunction::Log10 => math_expressions::log10,
-        BuiltinScalarFunction::Floor => math_expressions::floor,
-        BuiltinScalarFunction::Ceil => math_expressions::ceil,
-        BuiltinScalarFunction::Round => math_expressions::round,
-        BuiltinScalarFunction::Trunc => math_expressions::trunc,
-        BuiltinScalarFunction::Abs => math_expressions::abs,
-        BuiltinScalarFunction::Signum => math_expressions::signum,
-        BuiltinScalarFunction::Length => |args| Ok(length(args[0].as_ref())?),
-        BuiltinScalarFunction::Concat => {
-            |args| Ok(Arc::new(string_expressions::concatenate(args)?))
+    let fun_expr: ScalarFunctionImplementation = match fun {
+        BuiltinScalarFunction::Sqrt => make_scalar_function(math_expressions::sqrt),
+        BuiltinScalarFunction::Sin => make_scalar_function(math_expressions::sin),
+        BuiltinScalarFunction::Cos => make_scalar_function(math_expressions::cos),
+        BuiltinScalarFunction::Tan => make_scalar_function(math_expressions::tan),
+        BuiltinScalarFunction::Asin => make_scalar_function(math_expressions::asin),
+        BuiltinScalarFunction::Acos => make_scalar_function(math_expressions::acos),
+        BuiltinScalarFunction::Atan => make_scalar_function(math_expressions::atan),
+        BuiltinScalarFunction::Exp => make_scalar_function(math_expressions::exp),
+        BuiltinScalarFunction::Log => make_scalar_function(math_expressions::ln),
+        BuiltinScalarFunction::Log2 => make_scalar_function(math_expressions::log2),
+        BuiltinScalarFunction::Log10 => make_scalar_function(math_expressions::log10),
+        BuiltinScalarFunction::Floor => make_scalar_function(math_expressions::floor),
+        BuiltinScalarFunction::Ceil => make_scalar_function(math_expressions::ceil),
+        BuiltinScalarFunction::Round => make_scalar_function(math_expressions::round),
+        BuiltinScalarFunction::Trunc => make_scalar_function(math_expressions::trunc),
+        BuiltinScalarFunction::Abs => make_scalar_function(math_expressions::abs),
+        BuiltinScalarFunction::Signum => make_scalar_function(math_expressions::signum),
+        BuiltinScalarFunction::Length => {
+            make_scalar_function(|args: &[ArrayRef]| Ok(length(args[0].as_ref())?))
         }
+        BuiltinScalarFunction::Concat => Arc::new(string_expressions::concat),
         BuiltinScalarFunction::ToTimestamp => {
-            |args| Ok(Arc::new(datetime_expressions::to_timestamp(args)?))
+            make_scalar_function(|args: &[ArrayRef]| {
+                Ok(Arc::new(datetime_expressions::to_timestamp(args)?))
+            })
+        }
+        BuiltinScalarFunction::DateTrunc => {
+            make_scalar_function(datetime_expressions::date_trunc)
+        }
+        BuiltinScalarFunction::DatePart => {
+            make_scalar_function(datetime_expressions::date_part)
+        }
+        BuiltinScalarFunction::ToChar => {
+            make_scalar_function(datetime_expressions::to_char)
+        }
+        BuiltinScalarFunction::Tumble => Arc::new(datetime_expressions::tumble),
+        // the timestamp is computed when the expression is planned, see below
+        BuiltinScalarFunction::Now => {
+            Arc::new(|args: &[ColumnarValue]| Ok(args[0].clone()))
+        }
+        BuiltinScalarFunction::Array => {
+            make_scalar_function(|args: &[ArrayRef]| Ok(array_expressions::array(args)?))
+        }
+        BuiltinScalarFunction::ArrayLength => {
+            make_scalar_function(array_expressions::array_length)
+        }
+        BuiltinScalarFunction::ArrayContains => {
+            make_scalar_function(array_expressions::array_contains)
+        }
+        BuiltinScalarFunction::Lower => make_scalar_function(string_expressions::lower),
+        BuiltinScalarFunction::Upper => make_scalar_function(string_expressions::upper),
+        BuiltinScalarFunction::Trim => make_scalar_function(string_expressions::btrim),
+        BuiltinScalarFunction::Btrim => make_scalar_function(string_expressions::btrim),
+        BuiltinScalarFunction::Ltrim => make_scalar_function(string_expressions::ltrim),
+        BuiltinScalarFunction::Rtrim => make_scalar_function(string_expressions::rtrim),
+        BuiltinScalarFunction::Substr => make_scalar_function(string_expressions::substr),
+        BuiltinScalarFunction::Left => make_scalar_function(string_expressions::left),
+        BuiltinScalarFunction::Right => make_scalar_function(string_expressions::right),
+        BuiltinScalarFunction::Lpad => make_scalar_function(string_expressions::lpad),
+        BuiltinScalarFunction::Rpad => make_scalar_function(string_expressions::rpad),
+        BuiltinScalarFunction::Replace => {
+            make_scalar_function(string_expressions::replace)
+        }
+        BuiltinScalarFunction::SplitPart => {
+            make_scalar_function(string_expressions::split_part)
+        }
+        BuiltinScalarFunction::Strpos => make_scalar_function(string_expressions::strpos),
+        BuiltinScalarFunction::Reverse => {
+            make_scalar_function(string_expressions::reverse)
+        }
+        BuiltinScalarFunction::Initcap => {
+            make_scalar_function(string_expressions::initcap)
+        }
+        BuiltinScalarFunction::Repeat => make_scalar_function(string_expressions::repeat),
+        BuiltinScalarFunction::Ascii => make_scalar_function(string_expressions::ascii),
+        BuiltinScalarFunction::Chr => make_scalar_function(string_expressions::chr),
+        BuiltinScalarFunction::StartsWith => {
+            make_scalar_function(string_expressions::starts_with)
+        }
+        BuiltinScalarFunction::ConcatWithSeparator => {
+            Arc::new(string_expressions::concat_ws)
+        }
+        BuiltinScalarFunction::MD5 => make_scalar_function(string_expressions::md5),
+        BuiltinScalarFunction::SHA256 => make_scalar_function(string_expressions::sha256),
+        BuiltinScalarFunction::OctetLength => {
+            make_scalar_function(|args: &[ArrayRef]| Ok(length(args[0].as_ref())?))
+        }
+        BuiltinScalarFunction::BitLength => {
+            make_scalar_function(string_expressions::bit_length)
+        }
+        BuiltinScalarFunction::RegexpMatch => {
+            make_scalar_function(regex_expressions::regexp_match)
+        }
+        BuiltinScalarFunction::RegexpReplace => {
+            make_scalar_function(regex_expressions::regexp_replace)
         }
-        BuiltinScalarFunction::Array => |args| Ok(array_expressions::array(args)?),
-    });
+        BuiltinScalarFunction::Coalesce => {
+            make_scalar_function(conditional_expressions::coalesce)
+        }
+        BuiltinScalarFunction::NullIf => {
+            make_scalar_function(conditional_expressions::nullif)
+        }
+    };
     // coerce
     let args = coerce(args, input_schema, &signature(fun))?;
 
+    // the current time is computed once, so that every row sees the same value
+    let args = if fun == &BuiltinScalarFunction::Now {
+        vec![lit(ScalarValue::TimestampNanosecond(
+            Some(Utc::now().timestamp_nanos()),
+            None,
+        ))]
+    } else if fun == &BuiltinScalarFunction::ArrayContains {
+        // the value is compared to the elements of the list, of the same type
+        let item_type = list_item_type(&args[0].data_type(input_schema)?, fun)?;
+        vec![
+            args[0].clone(),
+            cast(args[1].clone(), input_schema, item_type)?,
+        ]
+    } else {
+        args
+    };
+
     let arg_types = args
         .iter()
         .map(|e| e.data_type(input_schema))
@@ -271,9 +678,123 @@ fn signature(fun: &BuiltinScalarFunction) -> Signature {
         }
         BuiltinScalarFunction::Concat => Signature::Variadic(vec![DataType::Utf8]),
         BuiltinScalarFunction::ToTimestamp => Signature::Uniform(1, vec![DataType::Utf8]),
+        BuiltinScalarFunction::DateTrunc | BuiltinScalarFunction::DatePart => {
+            Signature::OneOf(vec![
+                Signature::Exact(vec![
+                    DataType::Utf8,
+                    DataType::Timestamp(TimeUnit::Nanosecond, None),
+                ]),
+                Signature::Exact(vec![DataType::Utf8, DataType::Date32(DateUnit::Day)]),
+            ])
+        }
+        BuiltinScalarFunction::Now => Signature::Exact(vec![]),
+        BuiltinScalarFunction::Tumble => Signature::Exact(vec![
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            DataType::Interval(IntervalUnit::DayTime),
+        ]),
+        // timestamps or dates, and the time zone is optional
+        BuiltinScalarFunction::ToChar => Signature::OneOf(vec![
+            Signature::Exact(vec![
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                DataType::Utf8,
+            ]),
+            Signature::Exact(vec![
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                DataType::Utf8,
+                DataType::Utf8,
+            ]),
+            Signature::Exact(vec![DataType::Date32(DateUnit::Day), DataType::Utf8]),
+            Signature::Exact(vec![
+                DataType::Date32(DateUnit::Day),
+                DataType::Utf8,
+                DataType::Utf8,
+            ]),
+        ]),
         BuiltinScalarFunction::Array => {
             Signature::Variadic(array_expressions::SUPPORTED_ARRAY_TYPES.to_vec())
         }
+        // the types of lists are checked by `return_type`
+        BuiltinScalarFunction::ArrayLength => Signature::Any(1),
+        BuiltinScalarFunction::ArrayContains => Signature::Any(2),
+        BuiltinScalarFunction::Lower
+        | BuiltinScalarFunction::Upper
+        | BuiltinScalarFunction::Reverse
+        | BuiltinScalarFunction::Initcap
+        | BuiltinScalarFunction::Ascii
+        | BuiltinScalarFunction::MD5
+        | BuiltinScalarFunction::SHA256
+        | BuiltinScalarFunction::OctetLength
+        | BuiltinScalarFunction::BitLength => {
+            Signature::Uniform(1, vec![DataType::Utf8, DataType::LargeUtf8])
+        }
+        // the characters to trim are optional
+        BuiltinScalarFunction::Trim
+        | BuiltinScalarFunction::Btrim
+        | BuiltinScalarFunction::Ltrim
+        | BuiltinScalarFunction::Rtrim => Signature::OneOf(vec![
+            Signature::Uniform(1, vec![DataType::Utf8, DataType::LargeUtf8]),
+            Signature::Uniform(2, vec![DataType::Utf8, DataType::LargeUtf8]),
+        ]),
+        BuiltinScalarFunction::Replace => {
+            Signature::Uniform(3, vec![DataType::Utf8, DataType::LargeUtf8])
+        }
+        BuiltinScalarFunction::Strpos | BuiltinScalarFunction::StartsWith => {
+            Signature::Uniform(2, vec![DataType::Utf8, DataType::LargeUtf8])
+        }
+        BuiltinScalarFunction::Left
+        | BuiltinScalarFunction::Right
+        | BuiltinScalarFunction::Repeat => Signature::OneOf(vec![
+            Signature::Exact(vec![DataType::Utf8, DataType::Int64]),
+            Signature::Exact(vec![DataType::LargeUtf8, DataType::Int64]),
+        ]),
+        // the number of characters to extract is optional
+        BuiltinScalarFunction::Substr => Signature::OneOf(vec![
+            Signature::Exact(vec![DataType::Utf8, DataType::Int64]),
+            Signature::Exact(vec![DataType::LargeUtf8, DataType::Int64]),
+            Signature::Exact(vec![DataType::Utf8, DataType::Int64, DataType::Int64]),
+            Signature::Exact(vec![DataType::LargeUtf8, DataType::Int64, DataType::Int64]),
+        ]),
+        // the fill string is optional
+        BuiltinScalarFunction::Lpad | BuiltinScalarFunction::Rpad => {
+            Signature::OneOf(vec![
+                Signature::Exact(vec![DataType::Utf8, DataType::Int64]),
+                Signature::Exact(vec![DataType::LargeUtf8, DataType::Int64]),
+                Signature::Exact(vec![DataType::Utf8, DataType::Int64, DataType::Utf8]),
+                Signature::Exact(vec![
+                    DataType::LargeUtf8,
+                    DataType::Int64,
+                    DataType::LargeUtf8,
+                ]),
+            ])
+        }
+        BuiltinScalarFunction::SplitPart => Signature::OneOf(vec![
+            Signature::Exact(vec![DataType::Utf8, DataType::Utf8, DataType::Int64]),
+            Signature::Exact(vec![
+                DataType::LargeUtf8,
+                DataType::LargeUtf8,
+                DataType::Int64,
+            ]),
+        ]),
+        BuiltinScalarFunction::Chr => Signature::Uniform(1, vec![DataType::Int64]),
+        BuiltinScalarFunction::ConcatWithSeparator => {
+            Signature::Variadic(vec![DataType::Utf8, DataType::LargeUtf8])
+        }
+        // the flags are optional
+        BuiltinScalarFunction::RegexpMatch => Signature::OneOf(vec![
+            Signature::Uniform(2, vec![DataType::Utf8, DataType::LargeUtf8]),
+            Signature::Uniform(3, vec![DataType::Utf8, DataType::LargeUtf8]),
+        ]),
+        BuiltinScalarFunction::RegexpReplace => Signature::OneOf(vec![
+            Signature::Uniform(3, vec![DataType::Utf8, DataType::LargeUtf8]),
+            Signature::Uniform(4, vec![DataType::Utf8, DataType::LargeUtf8]),
+        ]),
+        BuiltinScalarFunction::Coalesce => Signature::Variadic(
+            conditional_expressions::SUPPORTED_CONDITIONAL_TYPES.to_vec(),
+        ),
+        BuiltinScalarFunction::NullIf => Signature::Uniform(
+            2,
+            conditional_expressions::SUPPORTED_CONDITIONAL_TYPES.to_vec(),
+        ),
         // math expressions expect 1 argument of type f64 or f32
         // priority is given to f64 because e.g. `sqrt(1i32)` is in IR (real numbers) and thus we
         // return the best approximation for it (in f64).
@@ -317,6 +838,21 @@ impl ScalarFunctionExpr {
             return_type: return_type.clone(),
         }
     }
+
+    /// Get the name of the scalar function
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the input expressions of the function
+    pub fn args(&self) -> &[Arc<dyn PhysicalExpr>] {
+        &self.args
+    }
+
+    /// Data type produced by this expression
+    pub fn return_type(&self) -> &DataType {
+        &self.return_type
+    }
 }
 
 impl fmt::Display for ScalarFunctionExpr {
@@ -335,6 +871,10 @@ impl fmt::Display for ScalarFunctionExpr {
 }
 
 impl PhysicalExpr for ScalarFunctionExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
         Ok(self.return_type.clone())
     }
@@ -344,25 +884,44 @@ impl PhysicalExpr for ScalarFunctionExpr {
     }
 
     fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
-        // evaluate the arguments
+        // evaluate the arguments, passing literals as scalars rather than as arrays
+        // of a value per row
         let inputs = self
             .args
             .iter()
-            .map(|e| e.evaluate(batch))
+            .map(|e| match e.as_any().downcast_ref::<Literal>() {
+                Some(literal) => Ok(ColumnarValue::Scalar(literal.value().clone())),
+                None => Ok(ColumnarValue::Array(e.evaluate(batch)?)),
+            })
             .collect::<Result<Vec<_>>>()?;
 
         // evaluate the function
         let fun = self.fun.as_ref();
-        (fun)(&inputs)
+        match (fun)(&inputs)? {
+            // a function of scalars that has no scalar result returns a single row,
+            // which is the value of every row
+            ColumnarValue::Array(array) if array.len() == 1 && batch.num_rows() != 1 => {
+                let indices = UInt32Array::from(vec![0; batch.num_rows()]);
+                Ok(take(&array, &indices, None)?)
+            }
+            result => Ok(result.into_array(batch.num_rows())),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{error::Result, physical_plan::expressions::lit, scalar::ScalarValue};
+    use crate::{
+        error::Result,
+        physical_plan::expressions::{col, lit},
+        scalar::ScalarValue,
+    };
     use arrow::{
-        array::{ArrayRef, FixedSizeListArray, Float64Array, Int32Array, StringArray},
+        array::{
+            ArrayRef, FixedSizeListArray, Float64Array, Int32Array, LargeStringArray,
+            StringArray,
+        },
         datatypes::Field,
         record_batch::RecordBatch,
     };
@@ -451,6 +1010,318 @@ mod tests {
         }
     }
 
+    #[test]
+    fn concat_scalars_and_columns() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+        let columns: Vec<ArrayRef> = vec![Arc::new(StringArray::from(vec![
+            Some("x"),
+            None,
+            Some("z"),
+        ]))];
+        let expr = create_physical_expr(
+            &BuiltinScalarFunction::Concat,
+            &vec![
+                col("a"),
+                lit(ScalarValue::Utf8(Some("-".to_string()))),
+                col("a"),
+            ],
+            &schema,
+        )?;
+        let result =
+            expr.evaluate(&RecordBatch::try_new(Arc::new(schema.clone()), columns)?)?;
+        let result = result.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            vec![Some("x-x"), None, Some("z-z")]
+        );
+
+        // the scalars are not expanded into arrays
+        let result = string_expressions::concat(&[
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some("a".to_string()))),
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some("b".to_string()))),
+        ])?;
+        match result {
+            ColumnarValue::Scalar(value) => {
+                assert_eq!(value, ScalarValue::Utf8(Some("ab".to_string())))
+            }
+            ColumnarValue::Array(_) => panic!("expected a scalar"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn scalar_function_of_scalars() -> Result<()> {
+        let fun = make_scalar_function(|args: &[ArrayRef]| Ok(args[0].clone()));
+
+        let result = fun(&[ColumnarValue::Scalar(ScalarValue::Int32(Some(1)))])?;
+        match result {
+            ColumnarValue::Scalar(value) => {
+                assert_eq!(value, ScalarValue::Int32(Some(1)))
+            }
+            ColumnarValue::Array(_) => panic!("expected a scalar"),
+        }
+
+        let array: ArrayRef = Arc::new(Int32Array::from(vec![1, 2]));
+        let result = fun(&[ColumnarValue::Array(array)])?;
+        assert_eq!(result.into_array(2).len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn date_functions_of_dates_and_time_zones() -> Result<()> {
+        let timezone = Some(Arc::new("UTC".to_string()));
+        let schema = Schema::new(vec![
+            Field::new("d", DataType::Date32(DateUnit::Day), false),
+            Field::new("ts", DataType::Timestamp(TimeUnit::Second, None), false),
+            Field::new("tz", DataType::Timestamp(TimeUnit::Second, timezone), false),
+        ]);
+        let field = || lit(ScalarValue::Utf8(Some("year".to_string())));
+
+        for column in &["d", "ts"] {
+            let expr = create_physical_expr(
+                &BuiltinScalarFunction::DatePart,
+                &vec![field(), col(column)],
+                &schema,
+            )?;
+            assert_eq!(expr.data_type(&schema)?, DataType::Int32);
+        }
+
+        // the time zone of a timestamp is not dropped by a coercion
+        let result = create_physical_expr(
+            &BuiltinScalarFunction::DatePart,
+            &vec![field(), col("tz")],
+            &schema,
+        );
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn volatility() {
+        assert_eq!(
+            BuiltinScalarFunction::Sqrt.volatility(),
+            Volatility::Immutable
+        );
+        assert_eq!(BuiltinScalarFunction::Now.volatility(), Volatility::Stable);
+    }
+
+    fn test_string_function(
+        fun: BuiltinScalarFunction,
+        column: ArrayRef,
+        other_args: Vec<ScalarValue>,
+        expected_type: DataType,
+        expected: &str,
+    ) -> Result<()> {
+        // the first argument is the column `a`, the others are literals
+        let schema = Schema::new(vec![Field::new("a", column.data_type().clone(), true)]);
+        let mut args = vec![col("a")];
+        args.extend(other_args.into_iter().map(lit));
+
+        let expr = create_physical_expr(&fun, &args, &schema)?;
+
+        // type is correct
+        assert_eq!(expr.data_type(&schema)?, expected_type);
+
+        // evaluate works
+        let result = expr.evaluate(&RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![column],
+        )?)?;
+
+        // value is correct
+        assert_eq!(format!("{:?}", result), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_functions() -> Result<()> {
+        let utf8: ArrayRef = Arc::new(StringArray::from(vec![Some("hello"), None]));
+        let large_utf8: ArrayRef =
+            Arc::new(LargeStringArray::from(vec![Some("hello"), None]));
+
+        test_string_function(
+            BuiltinScalarFunction::Upper,
+            utf8.clone(),
+            vec![],
+            DataType::Utf8,
+            "StringArray\n[\n  \"HELLO\",\n  null,\n]",
+        )?;
+        test_string_function(
+            BuiltinScalarFunction::Upper,
+            large_utf8.clone(),
+            vec![],
+            DataType::LargeUtf8,
+            "LargeStringArray\n[\n  \"HELLO\",\n  null,\n]",
+        )?;
+        // the start and count are coerced to i64
+        test_string_function(
+            BuiltinScalarFunction::Substr,
+            large_utf8.clone(),
+            vec![ScalarValue::Int32(Some(2)), ScalarValue::Int32(Some(3))],
+            DataType::LargeUtf8,
+            "LargeStringArray\n[\n  \"ell\",\n  null,\n]",
+        )?;
+        // the end of the substring does not overflow
+        test_string_function(
+            BuiltinScalarFunction::Substr,
+            utf8.clone(),
+            vec![
+                ScalarValue::Int64(Some(i64::MIN)),
+                ScalarValue::Int64(Some(3)),
+            ],
+            DataType::Utf8,
+            "StringArray\n[\n  \"\",\n  null,\n]",
+        )?;
+        // the column is the separator, the other arguments are coerced to large utf8
+        test_string_function(
+            BuiltinScalarFunction::ConcatWithSeparator,
+            large_utf8.clone(),
+            vec![
+                ScalarValue::Utf8(Some("x".to_string())),
+                ScalarValue::Utf8(Some("y".to_string())),
+            ],
+            DataType::LargeUtf8,
+            "LargeStringArray\n[\n  \"xhelloy\",\n  null,\n]",
+        )?;
+        // the fill is coerced to large utf8
+        test_string_function(
+            BuiltinScalarFunction::Rpad,
+            large_utf8.clone(),
+            vec![
+                ScalarValue::Int64(Some(8)),
+                ScalarValue::Utf8(Some("ab".to_string())),
+            ],
+            DataType::LargeUtf8,
+            "LargeStringArray\n[\n  \"helloaba\",\n  null,\n]",
+        )?;
+        test_string_function(
+            BuiltinScalarFunction::Strpos,
+            utf8.clone(),
+            vec![ScalarValue::Utf8(Some("ll".to_string()))],
+            DataType::Int32,
+            "PrimitiveArray<Int32>\n[\n  3,\n  null,\n]",
+        )?;
+        test_string_function(
+            BuiltinScalarFunction::BitLength,
+            large_utf8,
+            vec![],
+            DataType::Int64,
+            "PrimitiveArray<Int64>\n[\n  40,\n  null,\n]",
+        )?;
+        test_string_function(
+            BuiltinScalarFunction::MD5,
+            utf8,
+            vec![],
+            DataType::Utf8,
+            "StringArray\n[\n  \"5d41402abc4b2a76b9719d911017c592\",\n  null,\n]",
+        )
+    }
+
+    #[test]
+    fn test_regex_functions() -> Result<()> {
+        let utf8: ArrayRef = Arc::new(StringArray::from(vec![Some("hello"), None]));
+        let large_utf8: ArrayRef =
+            Arc::new(LargeStringArray::from(vec![Some("hello"), None]));
+        let s = |v: &str| ScalarValue::Utf8(Some(v.to_string()));
+
+        // only the first match is replaced without the `g` flag
+        test_string_function(
+            BuiltinScalarFunction::RegexpReplace,
+            utf8.clone(),
+            vec![s("[el]"), s("X")],
+            DataType::Utf8,
+            "StringArray\n[\n  \"hXllo\",\n  null,\n]",
+        )?;
+        test_string_function(
+            BuiltinScalarFunction::RegexpReplace,
+            utf8.clone(),
+            vec![s("[el]"), s("X"), s("g")],
+            DataType::Utf8,
+            "StringArray\n[\n  \"hXXXo\",\n  null,\n]",
+        )?;
+        // capture groups are referenced with `\1`, flags are applied
+        test_string_function(
+            BuiltinScalarFunction::RegexpReplace,
+            large_utf8,
+            vec![s("H(E)"), s("<\\1>"), s("i")],
+            DataType::LargeUtf8,
+            "LargeStringArray\n[\n  \"<e>llo\",\n  null,\n]",
+        )?;
+
+        // an invalid pattern is an execution error
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+        let expr = create_physical_expr(
+            &BuiltinScalarFunction::RegexpMatch,
+            &vec![col("a"), lit(s("(unclosed"))],
+            &schema,
+        )?;
+        assert_eq!(
+            expr.data_type(&schema)?,
+            DataType::List(Box::new(Field::new("item", DataType::Utf8, true)))
+        );
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![utf8])?;
+        assert!(expr.evaluate(&batch).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_function_errors() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let columns: Vec<ArrayRef> = vec![Arc::new(Int32Array::from(vec![1]))];
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), columns)?;
+
+        // split_part requires a positive field position
+        let expr = create_physical_expr(
+            &BuiltinScalarFunction::SplitPart,
+            &vec![
+                lit(ScalarValue::Utf8(Some("a,b".to_string()))),
+                lit(ScalarValue::Utf8(Some(",".to_string()))),
+                lit(ScalarValue::Int64(Some(0))),
+            ],
+            &schema,
+        )?;
+        assert!(expr.evaluate(&batch).is_err());
+
+        // chr(0) is not a valid character
+        let expr = create_physical_expr(
+            &BuiltinScalarFunction::Chr,
+            &vec![lit(ScalarValue::Int64(Some(0)))],
+            &schema,
+        )?;
+        assert!(expr.evaluate(&batch).is_err());
+
+        // the length of the result is limited
+        let expr = create_physical_expr(
+            &BuiltinScalarFunction::Repeat,
+            &vec![
+                lit(ScalarValue::Utf8(Some("ab".to_string()))),
+                lit(ScalarValue::Int64(Some(i64::MAX))),
+            ],
+            &schema,
+        )?;
+        assert!(expr.evaluate(&batch).is_err());
+        let expr = create_physical_expr(
+            &BuiltinScalarFunction::Lpad,
+            &vec![
+                lit(ScalarValue::Utf8(Some("ab".to_string()))),
+                lit(ScalarValue::Int64(Some(i64::MAX))),
+            ],
+            &schema,
+        )?;
+        assert!(expr.evaluate(&batch).is_err());
+
+        // concat_ws requires a separator and a value
+        assert!(return_type(
+            &BuiltinScalarFunction::ConcatWithSeparator,
+            &vec![DataType::Utf8]
+        )
+        .is_err());
+
+        Ok(())
+    }
+
     fn generic_test_array(
         value1: ScalarValue,
         value2: ScalarValue,
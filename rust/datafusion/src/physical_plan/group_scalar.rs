@@ -34,7 +34,19 @@ pub(crate) enum GroupByScalar {
     Int16(i16),
     Int32(i32),
     Int64(i64),
-    Utf8(String),
+    /// A string, which is only null for the null keys of dictionaries
+    Utf8(Option<String>),
+}
+
+impl GroupByScalar {
+    /// An estimate of the memory held by the scalar, in bytes, including its own size
+    pub fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + match self {
+                GroupByScalar::Utf8(Some(v)) => v.capacity(),
+                _ => 0,
+            }
+    }
 }
 
 impl TryFrom<&ScalarValue> for GroupByScalar {
@@ -50,7 +62,7 @@ impl TryFrom<&ScalarValue> for GroupByScalar {
             ScalarValue::UInt16(Some(v)) => GroupByScalar::UInt16(*v),
             ScalarValue::UInt32(Some(v)) => GroupByScalar::UInt32(*v),
             ScalarValue::UInt64(Some(v)) => GroupByScalar::UInt64(*v),
-            ScalarValue::Utf8(Some(v)) => GroupByScalar::Utf8(v.clone()),
+            ScalarValue::Utf8(Some(v)) => GroupByScalar::Utf8(Some(v.clone())),
             ScalarValue::Int8(None)
             | ScalarValue::Int16(None)
             | ScalarValue::Int32(None)
@@ -86,7 +98,7 @@ impl From<&GroupByScalar> for ScalarValue {
             GroupByScalar::UInt16(v) => ScalarValue::UInt16(Some(*v)),
             GroupByScalar::UInt32(v) => ScalarValue::UInt32(Some(*v)),
             GroupByScalar::UInt64(v) => ScalarValue::UInt64(Some(*v)),
-            GroupByScalar::Utf8(v) => ScalarValue::Utf8(Some(v.clone())),
+            GroupByScalar::Utf8(v) => ScalarValue::Utf8(v.clone()),
         }
     }
 }
@@ -18,23 +18,30 @@
 //! Defines the execution plan for the hash aggregate operation
 
 use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
-use futures::stream::{Stream, StreamExt, TryStreamExt};
-use futures::FutureExt;
+use futures::stream::{self, BoxStream, Stream, StreamExt};
 
 use crate::error::{DataFusionError, Result};
+use crate::execution::memory_manager::{MemoryPool, MemoryReservation};
 use crate::physical_plan::{Accumulator, AggregateExpr};
 use crate::physical_plan::{Distribution, ExecutionPlan, Partitioning, PhysicalExpr};
 
-use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::datatypes::{
+    ArrowNativeType, ArrowPrimitiveType, DataType, Field, Int16Type, Int32Type,
+    Int64Type, Int8Type, Schema, SchemaRef, UInt16Type, UInt32Type, UInt64Type,
+    UInt8Type,
+};
 use arrow::error::Result as ArrowResult;
 use arrow::record_batch::RecordBatch;
 use arrow::{
     array::{
-        ArrayRef, Int16Array, Int32Array, Int64Array, Int8Array, StringArray,
-        UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+        Array, ArrayRef, DictionaryArray, Int16Array, Int32Array, Int64Array, Int8Array,
+        StringArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
     },
     compute,
 };
@@ -42,8 +49,12 @@ use arrow::{
 use fnv::FnvHashMap;
 
 use super::{
-    common, expressions::Column, group_scalar::GroupByScalar, RecordBatchStream,
-    SendableRecordBatchStream,
+    common,
+    expressions::Column,
+    group_scalar::GroupByScalar,
+    metrics::{BaselineMetrics, MetricsSet, SQLMetric},
+    spill::{spawn_blocking_io, SpillFile},
+    RecordBatchStream, SendableRecordBatchStream,
 };
 
 use async_trait::async_trait;
@@ -65,9 +76,23 @@ pub struct HashAggregateExec {
     aggr_expr: Vec<Arc<dyn AggregateExpr>>,
     input: Arc<dyn ExecutionPlan>,
     schema: SchemaRef,
+    /// Input schema of the partial aggregation, against which the expressions of the
+    /// aggregates are evaluated in both modes
+    input_schema: SchemaRef,
+    /// Execution metrics
+    metrics: BaselineMetrics,
+    /// The pool that the streams reserve the memory of their accumulators from
+    memory_pool: Arc<MemoryPool>,
+    /// The directory that the groups are spilled to when their accumulators exceed
+    /// the memory pool, if any
+    spill_dir: Option<PathBuf>,
+    /// The number of times the groups were spilled
+    spill_count: SQLMetric,
+    /// The number of rows of the states of groups that were spilled
+    spilled_rows: SQLMetric,
 }
 
-fn create_schema(
+pub(crate) fn create_schema(
     input_schema: &Schema,
     group_expr: &Vec<(Arc<dyn PhysicalExpr>, String)>,
     aggr_expr: &Vec<Arc<dyn AggregateExpr>>,
@@ -107,6 +132,7 @@ impl HashAggregateExec {
         group_expr: Vec<(Arc<dyn PhysicalExpr>, String)>,
         aggr_expr: Vec<Arc<dyn AggregateExpr>>,
         input: Arc<dyn ExecutionPlan>,
+        input_schema: SchemaRef,
     ) -> Result<Self> {
         let schema = create_schema(&input.schema(), &group_expr, &aggr_expr, mode)?;
 
@@ -118,8 +144,65 @@ impl HashAggregateExec {
             aggr_expr,
             input,
             schema,
+            input_schema,
+            metrics: BaselineMetrics::new(),
+            memory_pool: Arc::new(MemoryPool::default()),
+            spill_dir: None,
+            spill_count: SQLMetric::counter(),
+            spilled_rows: SQLMetric::counter(),
         })
     }
+
+    /// Reserve the memory of the accumulators from `memory_pool`, rather than from a
+    /// pool without a limit
+    pub fn with_memory_pool(mut self, memory_pool: Arc<MemoryPool>) -> Self {
+        self.memory_pool = memory_pool;
+        self
+    }
+
+    /// Spill the state of the groups to temporary files in `spill_dir` when their
+    /// accumulators exceed the memory pool, rather than failing. The groups are
+    /// partitioned by the hash of their keys, and each partition is merged on its own
+    /// once the input is exhausted.
+    pub fn with_spill_dir(mut self, spill_dir: Option<PathBuf>) -> Self {
+        self.spill_dir = spill_dir;
+        self
+    }
+
+    /// Aggregation mode (partial or final)
+    pub fn mode(&self) -> &AggregateMode {
+        &self.mode
+    }
+
+    /// Grouping expressions
+    pub fn group_expr(&self) -> &[(Arc<dyn PhysicalExpr>, String)] {
+        &self.group_expr
+    }
+
+    /// Aggregate expressions
+    pub fn aggr_expr(&self) -> &[Arc<dyn AggregateExpr>] {
+        &self.aggr_expr
+    }
+
+    /// Input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Input schema of the partial aggregation
+    pub fn input_schema(&self) -> SchemaRef {
+        self.input_schema.clone()
+    }
+
+    /// The pool that the memory of the accumulators is reserved from
+    pub fn memory_pool(&self) -> &Arc<MemoryPool> {
+        &self.memory_pool
+    }
+
+    /// The directory that the groups are spilled to, if any
+    pub fn spill_dir(&self) -> Option<&PathBuf> {
+        self.spill_dir.as_ref()
+    }
 }
 
 #[async_trait]
@@ -152,36 +235,76 @@ impl ExecutionPlan for HashAggregateExec {
     async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
         let input = self.input.execute(partition).await?;
         let group_expr = self.group_expr.iter().map(|x| x.0.clone()).collect();
+        let reservation = MemoryReservation::new(
+            format!("HashAggregateExec[{}]", partition),
+            self.memory_pool.clone(),
+        );
 
         if self.group_expr.is_empty() {
-            Ok(Box::pin(HashAggregateStream::new(
+            Ok(Box::pin(HashAggregateStream::try_new(
                 self.mode,
                 self.schema.clone(),
                 self.aggr_expr.clone(),
                 input,
-            )))
+                self.metrics.clone(),
+                reservation,
+            )?))
         } else {
-            Ok(Box::pin(GroupedHashAggregateStream::new(
+            let spill = match &self.spill_dir {
+                Some(dir) => Some(SpilledGroups::try_new(
+                    dir.clone(),
+                    Arc::new(create_schema(
+                        &input.schema(),
+                        &self.group_expr,
+                        &self.aggr_expr,
+                        AggregateMode::Partial,
+                    )?),
+                    self.group_expr.len(),
+                    &self.aggr_expr,
+                    self.spill_count.clone(),
+                    self.spilled_rows.clone(),
+                )?),
+                None => None,
+            };
+            let aggregator = GroupedHashAggregator::try_new(
                 self.mode.clone(),
                 self.schema.clone(),
                 group_expr,
                 self.aggr_expr.clone(),
                 input,
-            )))
+                self.metrics.clone(),
+                reservation,
+            )?
+            .with_spill(spill);
+            Ok(Box::pin(GroupedHashAggregateStream::new(aggregator)))
         }
     }
 
+    fn metrics(&self) -> MetricsSet {
+        let mut metrics = self.metrics.metrics();
+        if self.spill_dir.is_some() {
+            metrics.insert("spill_count".to_owned(), self.spill_count.clone());
+            metrics.insert("spilled_rows".to_owned(), self.spilled_rows.clone());
+        }
+        metrics
+    }
+
     fn with_new_children(
         &self,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         match children.len() {
-            1 => Ok(Arc::new(HashAggregateExec::try_new(
-                self.mode,
-                self.group_expr.clone(),
-                self.aggr_expr.clone(),
-                children[0].clone(),
-            )?)),
+            1 => Ok(Arc::new(
+                HashAggregateExec::try_new(
+                    self.mode,
+                    self.group_expr.clone(),
+                    self.aggr_expr.clone(),
+                    children[0].clone(),
+                    self.input_schema.clone(),
+                )?
+                .with_memory_pool(self.memory_pool.clone())
+                .with_spill_dir(self.spill_dir.clone()),
+            )),
             _ => Err(DataFusionError::Internal(
                 "HashAggregateExec wrong number of children".to_string(),
             )),
@@ -215,22 +338,69 @@ Example: average
 * Finally, `get_value` returns an array with one entry computed from the state
 */
 struct GroupedHashAggregateStream {
+    schema: SchemaRef,
+    batches: BoxStream<'static, ArrowResult<RecordBatch>>,
+    metrics: BaselineMetrics,
+}
+
+/// The state of a [GroupedHashAggregateStream], which aggregates its input, spilling
+/// the groups to disk if they exceed its memory, then outputs the groups
+struct GroupedHashAggregator {
     mode: AggregateMode,
     schema: SchemaRef,
     group_expr: Vec<Arc<dyn PhysicalExpr>>,
     aggr_expr: Vec<Arc<dyn AggregateExpr>>,
+    /// The expressions to evaluate the batches with, one vec of expressions per
+    /// aggregation
+    aggregate_expressions: Vec<Vec<Arc<dyn PhysicalExpr>>>,
     input: SendableRecordBatchStream,
+    /// mapping key -> (set of accumulators, indices of the key in the batch)
+    /// * the indexes are updated at each row
+    /// * the accumulators are updated at the end of each batch
+    /// * the indexes are `clear`ed at the end of each batch
+    accumulators: Accumulators,
+    /// The groups spilled to disk, if the aggregation can spill
+    spill: Option<SpilledGroups>,
+    /// Whether the input is exhausted
+    input_done: bool,
     finished: bool,
+    metrics: BaselineMetrics,
+    /// The size of the groups of `accumulators`, as estimated by [group_size], that is
+    /// updated with the groups of each batch rather than recomputed
+    groups_size: usize,
+    /// The memory held by the accumulators
+    reservation: MemoryReservation,
 }
 
-fn group_aggregate_batch(
+pub(crate) fn group_aggregate_batch(
     mode: &AggregateMode,
     group_expr: &Vec<Arc<dyn PhysicalExpr>>,
     aggr_expr: &Vec<Arc<dyn AggregateExpr>>,
     batch: RecordBatch,
-    mut accumulators: Accumulators,
+    accumulators: Accumulators,
     aggregate_expressions: &Vec<Vec<Arc<dyn PhysicalExpr>>>,
 ) -> Result<Accumulators> {
+    let (accumulators, _) = group_aggregate_batch_sized(
+        mode,
+        group_expr,
+        aggr_expr,
+        batch,
+        accumulators,
+        aggregate_expressions,
+    )?;
+    Ok(accumulators)
+}
+
+/// Like [group_aggregate_batch], but also returns the change in the size of the groups,
+/// as estimated by [group_size], from the groups of the rows of `batch` alone
+fn group_aggregate_batch_sized(
+    mode: &AggregateMode,
+    group_expr: &Vec<Arc<dyn PhysicalExpr>>,
+    aggr_expr: &Vec<Arc<dyn AggregateExpr>>,
+    batch: RecordBatch,
+    mut accumulators: Accumulators,
+    aggregate_expressions: &Vec<Vec<Arc<dyn PhysicalExpr>>>,
+) -> Result<(Accumulators, isize)> {
     // evaluate the grouping expressions
     let group_values = evaluate(group_expr, &batch)?;
 
@@ -247,6 +417,10 @@ fn group_aggregate_batch(
         key.push(GroupByScalar::UInt32(0));
     }
 
+    // the size of the groups of the batch before it is aggregated is subtracted, and
+    // their size afterwards is added
+    let mut size_change = 0isize;
+
     // 1.1 construct the key from the group values
     // 1.2 construct the mapping key if it does not exist
     // 1.3 add the row' index to `indices`
@@ -265,140 +439,438 @@ fn group_aggregate_batch(
                     .insert(key.clone(), (accumulator_set, Box::new(vec![row as u32])));
             }
             // 1.3
-            Some((_, v)) => v.push(row as u32),
+            Some((accumulator_set, v)) => {
+                // the first row of the group in this batch
+                if v.is_empty() {
+                    size_change -=
+                        group_size(&key, accumulator_set, v.capacity()) as isize;
+                }
+                v.push(row as u32)
+            }
         }
     }
 
-    // 2.1 for each key
+    // 2.1 for each key with rows in this batch
     // 2.2 for each aggregation
     // 2.3 `take` from each of its arrays the keys' values
     // 2.4 update / merge the accumulator with the values
     // 2.5 clear indices
-    accumulators
-        .iter_mut()
+    for (key, (accumulator_set, indices)) in accumulators.iter_mut() {
         // 2.1
-        .map(|(_, (accumulator_set, indices))| {
-            // 2.2
-            accumulator_set
-                .into_iter()
-                .zip(&aggr_input_values)
-                .map(|(accumulator, aggr_array)| {
-                    (
-                        accumulator,
-                        aggr_array
-                            .iter()
-                            .map(|array| {
-                                // 2.3
-                                compute::take(
-                                    array,
-                                    &UInt32Array::from(*indices.clone()),
-                                    None, // None: no index check
-                                )
-                                .unwrap()
-                            })
-                            .collect::<Vec<ArrayRef>>(),
-                    )
-                })
-                // 2.4
-                .map(|(accumulator, values)| match mode {
-                    AggregateMode::Partial => accumulator.update_batch(&values),
-                    AggregateMode::Final => {
-                        // note: the aggregation here is over states, not values, thus the merge
-                        accumulator.merge_batch(&values)
-                    }
-                })
-                .collect::<Result<()>>()
-                // 2.5
-                .and(Ok(indices.clear()))
-        })
-        .collect::<Result<()>>()?;
-    Ok(accumulators)
+        if indices.is_empty() {
+            continue;
+        }
+        // 2.2
+        accumulator_set
+            .iter_mut()
+            .zip(&aggr_input_values)
+            .map(|(accumulator, aggr_array)| {
+                (
+                    accumulator,
+                    aggr_array
+                        .iter()
+                        .map(|array| {
+                            // 2.3
+                            compute::take(
+                                array,
+                                &UInt32Array::from(*indices.clone()),
+                                None, // None: no index check
+                            )
+                            .unwrap()
+                        })
+                        .collect::<Vec<ArrayRef>>(),
+                )
+            })
+            // 2.4
+            .map(|(accumulator, values)| match mode {
+                AggregateMode::Partial => accumulator.update_batch(&values),
+                AggregateMode::Final => {
+                    // note: the aggregation here is over states, not values, thus the merge
+                    accumulator.merge_batch(&values)
+                }
+            })
+            .collect::<Result<()>>()?;
+        size_change += group_size(key, accumulator_set, indices.capacity()) as isize;
+        // 2.5
+        indices.clear();
+    }
+    Ok((accumulators, size_change))
 }
 
 impl GroupedHashAggregateStream {
-    /// Create a new HashAggregateStream
-    pub fn new(
+    /// Create a new stream of the groups aggregated by `aggregator`
+    fn new(aggregator: GroupedHashAggregator) -> Self {
+        let schema = aggregator.schema.clone();
+        let metrics = aggregator.metrics.clone();
+        let batches = stream::unfold(aggregator, |mut aggregator| async move {
+            if aggregator.finished {
+                return None;
+            }
+            match aggregator.next_batch().await {
+                Ok(Some(batch)) => Some((Ok(batch), aggregator)),
+                Ok(None) => None,
+                Err(e) => {
+                    aggregator.finished = true;
+                    Some((Err(e), aggregator))
+                }
+            }
+        });
+        Self {
+            schema,
+            batches: batches.boxed(),
+            metrics,
+        }
+    }
+}
+
+impl GroupedHashAggregator {
+    /// Create a new aggregator of the batches of `input`
+    fn try_new(
         mode: AggregateMode,
         schema: SchemaRef,
         group_expr: Vec<Arc<dyn PhysicalExpr>>,
         aggr_expr: Vec<Arc<dyn AggregateExpr>>,
         input: SendableRecordBatchStream,
-    ) -> Self {
-        GroupedHashAggregateStream {
+        metrics: BaselineMetrics,
+        reservation: MemoryReservation,
+    ) -> Result<Self> {
+        Ok(GroupedHashAggregator {
             mode,
             schema,
             group_expr,
+            aggregate_expressions: aggregate_expressions(&aggr_expr, &mode)?,
             aggr_expr,
             input,
+            accumulators: Accumulators::default(),
+            groups_size: 0,
+            spill: None,
+            input_done: false,
             finished: false,
-        }
+            metrics,
+            reservation,
+        })
     }
-}
 
-type AccumulatorSet = Vec<Box<dyn Accumulator>>;
-type Accumulators = FnvHashMap<Vec<GroupByScalar>, (AccumulatorSet, Box<Vec<u32>>)>;
+    /// Spill the groups with `spill` when they exceed the memory reservation
+    fn with_spill(mut self, spill: Option<SpilledGroups>) -> Self {
+        self.spill = spill;
+        self
+    }
 
-impl Stream for GroupedHashAggregateStream {
-    type Item = ArrowResult<RecordBatch>;
+    /// The next batch of the output, once the whole input is aggregated, or `None` once
+    /// the groups have all been output
+    async fn next_batch(&mut self) -> ArrowResult<Option<RecordBatch>> {
+        while !self.input_done {
+            match self.input.next().await {
+                Some(batch) => self
+                    .aggregate_batch(batch?)
+                    .await
+                    .map_err(DataFusionError::into_arrow_external_error)?,
+                None => self.input_done = true,
+            }
+        }
+        self.next_output()
+            .await
+            .map_err(DataFusionError::into_arrow_external_error)
+    }
 
-    fn poll_next(
-        mut self: std::pin::Pin<&mut Self>,
-        cx: &mut Context<'_>,
-    ) -> Poll<Option<Self::Item>> {
-        if self.finished {
-            return Poll::Ready(None);
+    /// Updates the accumulators of the groups of the rows of `batch`, and spills them
+    /// if they exceed the memory reservation
+    async fn aggregate_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        let resized = {
+            let _timer = self.metrics.elapsed_compute().timer();
+            let accumulators = std::mem::take(&mut self.accumulators);
+            let (accumulators, size_change) = group_aggregate_batch_sized(
+                &self.mode,
+                &self.group_expr,
+                &self.aggr_expr,
+                batch,
+                accumulators,
+                &self.aggregate_expressions,
+            )?;
+            self.accumulators = accumulators;
+            self.groups_size = (self.groups_size as isize + size_change) as usize;
+            self.reservation
+                .try_resize(accumulators_size(&self.accumulators, self.groups_size))
+        };
+
+        if let Err(e) = resized {
+            match &mut self.spill {
+                Some(spill) => {
+                    let accumulators = std::mem::take(&mut self.accumulators);
+                    spill.spill(accumulators, self.group_expr.len()).await?;
+                    self.groups_size = 0;
+                    self.reservation
+                        .try_resize(accumulators_size(&self.accumulators, 0))?;
+                }
+                None => return Err(e),
+            }
         }
+        Ok(())
+    }
 
-        // return single batch
+    /// The next batch of the output once the input is exhausted, or `None` once the
+    /// groups have all been output
+    async fn next_output(&mut self) -> Result<Option<RecordBatch>> {
+        let num_group_expr = self.group_expr.len();
+        let spill = match &mut self.spill {
+            Some(spill) if spill.is_spilled() => spill,
+            _ => {
+                let _timer = self.metrics.elapsed_compute().timer();
+                self.finished = true;
+                let batch = create_batch_from_map(
+                    &self.mode,
+                    &self.accumulators,
+                    num_group_expr,
+                    &self.schema,
+                )?;
+                self.accumulators = Accumulators::default();
+                self.groups_size = 0;
+                self.reservation.free();
+                return Ok(Some(batch));
+            }
+        };
+
+        // the groups still in memory join those that were spilled, and each partition
+        // of the groups is then merged on its own
+        if !self.accumulators.is_empty() {
+            let accumulators = std::mem::take(&mut self.accumulators);
+            spill.spill(accumulators, num_group_expr).await?;
+            self.groups_size = 0;
+        }
+        let reservation = &mut self.reservation;
+        while let Some(accumulators) = spill
+            .merge_next(&self.aggr_expr, &self.metrics, |a, size| {
+                reservation.try_resize(accumulators_size(a, size))
+            })
+            .await?
+        {
+            let _timer = self.metrics.elapsed_compute().timer();
+            let batch = create_batch_from_map(
+                &self.mode,
+                &accumulators,
+                num_group_expr,
+                &self.schema,
+            )?;
+            if batch.num_rows() > 0 {
+                return Ok(Some(batch));
+            }
+        }
         self.finished = true;
+        self.reservation.free();
+        Ok(None)
+    }
+}
+
+/// The number of files that the groups of an aggregation are partitioned into, by the
+/// hash of their keys, when they are spilled
+const SPILL_PARTITIONS: usize = 16;
+
+/// The state of the groups of an aggregation that exceeded its memory, partitioned into
+/// files by the hash of their keys, so that the partitions can be merged one at a time
+struct SpilledGroups {
+    dir: PathBuf,
+    /// The schema of the spilled batches: the keys of the groups, followed by the
+    /// states of their accumulators, as output by a partial aggregation
+    schema: SchemaRef,
+    /// The files of the partitions that remain to be merged, created by the first
+    /// spill
+    files: Vec<SpillFile>,
+    /// Whether any groups were spilled
+    spilled: bool,
+    /// The keys of the groups in the spilled batches
+    group_expr: Vec<Arc<dyn PhysicalExpr>>,
+    /// The states of the accumulators in the spilled batches
+    merge_expressions: Vec<Vec<Arc<dyn PhysicalExpr>>>,
+    spill_count: SQLMetric,
+    spilled_rows: SQLMetric,
+}
+
+impl SpilledGroups {
+    fn try_new(
+        dir: PathBuf,
+        schema: SchemaRef,
+        num_group_expr: usize,
+        aggr_expr: &[Arc<dyn AggregateExpr>],
+        spill_count: SQLMetric,
+        spilled_rows: SQLMetric,
+    ) -> Result<Self> {
+        let group_expr = schema.fields()[..num_group_expr]
+            .iter()
+            .map(|f| Arc::new(Column::new(f.name())) as Arc<dyn PhysicalExpr>)
+            .collect();
+        Ok(Self {
+            dir,
+            schema,
+            files: vec![],
+            spilled: false,
+            group_expr,
+            merge_expressions: aggregate_expressions(aggr_expr, &AggregateMode::Final)?,
+            spill_count,
+            spilled_rows,
+        })
+    }
 
-        let mode = self.mode.clone();
-        let group_expr = self.group_expr.clone();
-        let aggr_expr = self.aggr_expr.clone();
-        let schema = self.schema.clone();
-
-        // the expressions to evaluate the batch, one vec of expressions per aggregation
-        let aggregate_expressions = match aggregate_expressions(&aggr_expr, &mode) {
-            Ok(e) => e,
-            Err(e) => {
-                return Poll::Ready(Some(Err(
-                    DataFusionError::into_arrow_external_error(e),
-                )))
+    /// Whether any groups were spilled
+    fn is_spilled(&self) -> bool {
+        self.spilled
+    }
+
+    /// Appends the states of the accumulators of `accumulators` to the files of the
+    /// partitions of their keys
+    async fn spill(
+        &mut self,
+        accumulators: Accumulators,
+        num_group_expr: usize,
+    ) -> Result<()> {
+        let mut partitions = (0..SPILL_PARTITIONS)
+            .map(|_| Accumulators::default())
+            .collect::<Vec<_>>();
+        for (key, value) in accumulators {
+            let mut hasher = DefaultHasher::new();
+            key.hash(&mut hasher);
+            let partition = (hasher.finish() % SPILL_PARTITIONS as u64) as usize;
+            partitions[partition].insert(key, value);
+        }
+        let batches = partitions
+            .iter()
+            .map(|partition| {
+                if partition.is_empty() {
+                    Ok(None)
+                } else {
+                    create_batch_from_map(
+                        &AggregateMode::Partial,
+                        partition,
+                        num_group_expr,
+                        &self.schema,
+                    )
+                    .map(Some)
+                }
+            })
+            .collect::<ArrowResult<Vec<_>>>()?;
+        let num_rows = batches.iter().flatten().map(|b| b.num_rows()).sum();
+
+        // the files of the partitions are created by the first spill
+        let files = std::mem::take(&mut self.files);
+        let create_files = !self.spilled;
+        let (dir, schema) = (self.dir.clone(), self.schema.clone());
+        self.files = spawn_blocking_io(move || {
+            let mut files = if create_files {
+                (0..SPILL_PARTITIONS)
+                    .map(|_| SpillFile::try_new(&dir, &schema))
+                    .collect::<Result<Vec<_>>>()?
+            } else {
+                files
+            };
+            for (batch, file) in batches.iter().zip(files.iter_mut()) {
+                if let Some(batch) = batch {
+                    file.write(batch)?;
+                }
             }
+            Ok(files)
+        })
+        .await?;
+        self.spilled = true;
+        self.spilled_rows.add(num_rows);
+        self.spill_count.add(1);
+        Ok(())
+    }
+
+    /// Merges the spilled states of the groups of the next partition into new
+    /// accumulators, calling `reserve` with them and the size of their groups after
+    /// each spilled batch, or returns `None` once all the partitions are merged. The
+    /// batches are read one at a time, and only the merge is timed in `metrics`.
+    async fn merge_next<F>(
+        &mut self,
+        aggr_expr: &Vec<Arc<dyn AggregateExpr>>,
+        metrics: &BaselineMetrics,
+        mut reserve: F,
+    ) -> Result<Option<Accumulators>>
+    where
+        F: FnMut(&Accumulators, usize) -> Result<()>,
+    {
+        let file = match self.files.pop() {
+            Some(file) => file,
+            None => return Ok(None),
         };
+        // the file is deleted once it is read, when dropped
+        let (_file, mut reader) = spawn_blocking_io(move || {
+            let mut file = file;
+            let reader = file.read()?;
+            Ok((file, reader))
+        })
+        .await?;
+        let mut accumulators = Accumulators::default();
+        let mut groups_size = 0;
+        loop {
+            let (next_reader, batch) = spawn_blocking_io(move || {
+                let batch = reader.next();
+                Ok((reader, batch))
+            })
+            .await?;
+            reader = next_reader;
+            let batch = match batch {
+                Some(batch) => batch?,
+                None => break,
+            };
+
+            let _timer = metrics.elapsed_compute().timer();
+            let (merged, size_change) = group_aggregate_batch_sized(
+                &AggregateMode::Final,
+                &self.group_expr,
+                aggr_expr,
+                batch,
+                accumulators,
+                &self.merge_expressions,
+            )?;
+            accumulators = merged;
+            groups_size = (groups_size as isize + size_change) as usize;
+            reserve(&accumulators, groups_size)?;
+        }
+        Ok(Some(accumulators))
+    }
+}
 
-        // mapping key -> (set of accumulators, indices of the key in the batch)
-        // * the indexes are updated at each row
-        // * the accumulators are updated at the end of each batch
-        // * the indexes are `clear`ed at the end of each batch
-        //let mut accumulators: Accumulators = FnvHashMap::default();
-
-        // iterate over all input batches and update the accumulators
-        let future = self.input.as_mut().try_fold(
-            Accumulators::default(),
-            |accumulators, batch| async {
-                group_aggregate_batch(
-                    &mode,
-                    &group_expr,
-                    &aggr_expr,
-                    batch,
-                    accumulators,
-                    &aggregate_expressions,
-                )
-                .map_err(DataFusionError::into_arrow_external_error)
-            },
-        );
+pub(crate) type AccumulatorSet = Vec<Box<dyn Accumulator>>;
+pub(crate) type Accumulators =
+    FnvHashMap<Vec<GroupByScalar>, (AccumulatorSet, Box<Vec<u32>>)>;
 
-        let future = future.map(|maybe_accumulators| {
-            maybe_accumulators.map(|accumulators| {
-                create_batch_from_map(&mode, &accumulators, group_expr.len(), &schema)
-            })?
-        });
+/// An estimate of the memory held by a set of accumulators, in bytes
+fn accumulator_set_size(accumulator_set: &AccumulatorSet) -> usize {
+    accumulator_set.capacity() * std::mem::size_of::<Box<dyn Accumulator>>()
+        + accumulator_set.iter().map(|a| a.size()).sum::<usize>()
+}
+
+/// An estimate of the memory held by a group beyond its entry in [Accumulators], in
+/// bytes, given the capacity of the indices of its rows
+fn group_size(
+    key: &[GroupByScalar],
+    accumulator_set: &AccumulatorSet,
+    indices_capacity: usize,
+) -> usize {
+    key.iter().map(|k| k.size()).sum::<usize>()
+        + accumulator_set_size(accumulator_set)
+        + std::mem::size_of::<Vec<u32>>()
+        + indices_capacity * std::mem::size_of::<u32>()
+}
+
+/// An estimate of the memory held by the accumulators of all the groups, in bytes,
+/// given the sum of the [group_size] of their groups
+fn accumulators_size(accumulators: &Accumulators, groups_size: usize) -> usize {
+    let entry_size =
+        std::mem::size_of::<(Vec<GroupByScalar>, (AccumulatorSet, Box<Vec<u32>>))>();
+    accumulators.capacity() * entry_size + groups_size
+}
 
-        // send the stream to the heap, so that it outlives this function.
-        let mut combined = Box::pin(future.into_stream());
+impl Stream for GroupedHashAggregateStream {
+    type Item = ArrowResult<RecordBatch>;
 
-        combined.poll_next_unpin(cx)
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.batches.poll_next_unpin(cx);
+        self.metrics.record_poll(poll)
     }
 }
 
@@ -446,7 +918,7 @@ fn merge_expressions(
 /// The return value is to be understood as:
 /// * index 0 is the aggregation
 /// * index 1 is the expression i of the aggregation
-fn aggregate_expressions(
+pub(crate) fn aggregate_expressions(
     aggr_expr: &[Arc<dyn AggregateExpr>],
     mode: &AggregateMode,
 ) -> Result<Vec<Vec<Arc<dyn PhysicalExpr>>>> {
@@ -465,26 +937,47 @@ fn aggregate_expressions(
 struct HashAggregateStream {
     mode: AggregateMode,
     schema: SchemaRef,
-    aggr_expr: Vec<Arc<dyn AggregateExpr>>,
     input: SendableRecordBatchStream,
+    /// The accumulators of the aggregations, updated with each input batch
+    accumulators: AccumulatorSet,
+    /// The expressions of each aggregation that are evaluated against the batches
+    expressions: Vec<Vec<Arc<dyn PhysicalExpr>>>,
     finished: bool,
+    metrics: BaselineMetrics,
+    /// The memory held by the accumulators
+    reservation: MemoryReservation,
 }
 
 impl HashAggregateStream {
     /// Create a new HashAggregateStream
-    pub fn new(
+    pub fn try_new(
         mode: AggregateMode,
         schema: SchemaRef,
         aggr_expr: Vec<Arc<dyn AggregateExpr>>,
         input: SendableRecordBatchStream,
-    ) -> Self {
-        HashAggregateStream {
+        metrics: BaselineMetrics,
+        reservation: MemoryReservation,
+    ) -> Result<Self> {
+        Ok(HashAggregateStream {
             mode,
             schema,
-            aggr_expr,
             input,
+            accumulators: create_accumulators(&aggr_expr)?,
+            expressions: aggregate_expressions(&aggr_expr, &mode)?,
             finished: false,
-        }
+            metrics,
+            reservation,
+        })
+    }
+
+    /// Updates the accumulators with the values of `batch`
+    fn aggregate_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        let accumulators = std::mem::take(&mut self.accumulators);
+        self.accumulators =
+            aggregate_batch(&self.mode, batch, accumulators, &self.expressions)?;
+        // distinct accumulators grow with their input
+        self.reservation
+            .try_resize(accumulator_set_size(&self.accumulators))
     }
 }
 
@@ -533,59 +1026,41 @@ impl Stream for HashAggregateStream {
         if self.finished {
             return Poll::Ready(None);
         }
-
-        // return single batch
-        self.finished = true;
-
-        let accumulators = match create_accumulators(&self.aggr_expr) {
-            Ok(e) => e,
-            Err(e) => {
-                return Poll::Ready(Some(Err(
-                    DataFusionError::into_arrow_external_error(e),
-                )))
+        let metrics = self.metrics.clone();
+
+        // 1 for each batch, update / merge accumulators with the expressions' values.
+        // The input can be pending any number of times, so the accumulators are kept
+        // in the stream until it is exhausted
+        loop {
+            match self.input.poll_next_unpin(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Some(Ok(batch))) => {
+                    let _timer = metrics.elapsed_compute().timer();
+                    if let Err(e) = self.aggregate_batch(&batch) {
+                        self.finished = true;
+                        return Poll::Ready(Some(Err(
+                            DataFusionError::into_arrow_external_error(e),
+                        )));
+                    }
+                }
+                Poll::Ready(Some(Err(e))) => {
+                    self.finished = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+                Poll::Ready(None) => break,
             }
-        };
+        }
 
-        let expressions = match aggregate_expressions(&self.aggr_expr, &self.mode) {
-            Ok(e) => e,
-            Err(e) => {
-                return Poll::Ready(Some(Err(
-                    DataFusionError::into_arrow_external_error(e),
-                )))
-            }
+        // 2. convert values to a record batch, which is the single output batch
+        self.finished = true;
+        let poll = {
+            let _timer = metrics.elapsed_compute().timer();
+            let batch = finalize_aggregation(&self.accumulators, &self.mode)
+                .map_err(DataFusionError::into_arrow_external_error)
+                .and_then(|columns| RecordBatch::try_new(self.schema.clone(), columns));
+            Poll::Ready(Some(batch))
         };
-        let expressions = Arc::new(expressions);
-
-        let mode = self.mode;
-        let schema = self.schema();
-
-        // 1 for each batch, update / merge accumulators with the expressions' values
-        // future is ready when all batches are computed
-        let future = self
-            .input
-            .as_mut()
-            .try_fold(
-                // pass the expressions on every fold to handle closures' mutability
-                (accumulators, expressions),
-                |(acc, expr), batch| async move {
-                    aggregate_batch(&mode, &batch, acc, &expr)
-                        .map_err(DataFusionError::into_arrow_external_error)
-                        .map(|agg| (agg, expr))
-                },
-            )
-            // pick the accumulators (disregard the expressions)
-            .map(|e| e.map(|e| e.0));
-
-        let future = future.map(|maybe_accumulators| {
-            maybe_accumulators.map(|accumulators| {
-                // 2. convert values to a record batch
-                finalize_aggregation(&accumulators, &mode)
-                    .map_err(DataFusionError::into_arrow_external_error)
-                    .and_then(|columns| RecordBatch::try_new(schema.clone(), columns))
-            })?
-        });
-
-        Box::pin(future.into_stream()).poll_next_unpin(cx)
+        metrics.record_poll(poll)
     }
 }
 
@@ -607,7 +1082,7 @@ fn concatenate(arrays: Vec<Vec<ArrayRef>>) -> ArrowResult<Vec<ArrayRef>> {
 }
 
 /// Create a RecordBatch with all group keys and accumulator' states or values.
-fn create_batch_from_map(
+pub(crate) fn create_batch_from_map(
     mode: &AggregateMode,
     accumulators: &Accumulators,
     num_group_expr: usize,
@@ -634,7 +1109,9 @@ fn create_batch_from_map(
                     GroupByScalar::UInt16(n) => Arc::new(UInt16Array::from(vec![*n])),
                     GroupByScalar::UInt32(n) => Arc::new(UInt32Array::from(vec![*n])),
                     GroupByScalar::UInt64(n) => Arc::new(UInt64Array::from(vec![*n])),
-                    GroupByScalar::Utf8(str) => Arc::new(StringArray::from(vec![&**str])),
+                    GroupByScalar::Utf8(str) => {
+                        Arc::new(StringArray::from(vec![str.as_deref()]))
+                    }
                 })
                 .collect::<Vec<ArrayRef>>();
 
@@ -651,7 +1128,15 @@ fn create_batch_from_map(
 
     let batch = if arrays.len() != 0 {
         // 5.
-        let columns = concatenate(arrays)?;
+        let mut columns = concatenate(arrays)?;
+        // the keys of dictionaries are grouped by their values, which are encoded
+        // again in the output
+        for (i, column) in columns.iter_mut().enumerate().take(num_group_expr) {
+            let data_type = output_schema.field(i).data_type();
+            if column.data_type() != data_type {
+                *column = compute::cast(column, data_type)?;
+            }
+        }
         RecordBatch::try_new(Arc::new(output_schema.to_owned()), columns)?
     } else {
         common::create_batch_empty(output_schema)?
@@ -741,7 +1226,27 @@ fn create_key(
             }
             DataType::Utf8 => {
                 let array = col.as_any().downcast_ref::<StringArray>().unwrap();
-                vec[i] = GroupByScalar::Utf8(String::from(array.value(row)))
+                vec[i] = GroupByScalar::Utf8(Some(String::from(array.value(row))))
+            }
+            DataType::Dictionary(key_type, value_type)
+                if **value_type == DataType::Utf8 =>
+            {
+                let value = match key_type.as_ref() {
+                    DataType::Int8 => dictionary_value::<Int8Type>(col, row)?,
+                    DataType::Int16 => dictionary_value::<Int16Type>(col, row)?,
+                    DataType::Int32 => dictionary_value::<Int32Type>(col, row)?,
+                    DataType::Int64 => dictionary_value::<Int64Type>(col, row)?,
+                    DataType::UInt8 => dictionary_value::<UInt8Type>(col, row)?,
+                    DataType::UInt16 => dictionary_value::<UInt16Type>(col, row)?,
+                    DataType::UInt32 => dictionary_value::<UInt32Type>(col, row)?,
+                    DataType::UInt64 => dictionary_value::<UInt64Type>(col, row)?,
+                    _ => {
+                        return Err(DataFusionError::Internal(
+                            "Unsupported GROUP BY data type".to_string(),
+                        ));
+                    }
+                };
+                vec[i] = GroupByScalar::Utf8(value)
             }
             _ => {
                 // This is internal because we should have caught this before.
@@ -754,15 +1259,38 @@ fn create_key(
     Ok(())
 }
 
+/// The string of the dictionary array `col` at `row`, or None for a null key, which is
+/// grouped on its own
+fn dictionary_value<K: ArrowPrimitiveType>(
+    col: &ArrayRef,
+    row: usize,
+) -> Result<Option<String>> {
+    let dictionary = col.as_any().downcast_ref::<DictionaryArray<K>>().unwrap();
+    let keys = dictionary.keys();
+    if keys.is_null(row) {
+        return Ok(None);
+    }
+    let values = dictionary.values();
+    let values = values.as_any().downcast_ref::<StringArray>().unwrap();
+    keys.value(row)
+        .to_usize()
+        .filter(|key| *key < values.len())
+        .map(|key| Some(values.value(key).to_string()))
+        .ok_or_else(|| {
+            DataFusionError::Internal(format!("Invalid dictionary key at index {}", row))
+        })
+}
+
 #[cfg(test)]
 mod tests {
 
     use arrow::array::Float64Array;
 
     use super::*;
-    use crate::physical_plan::expressions::{col, Avg};
+    use crate::physical_plan::expressions::{col, Avg, Sum};
     use crate::physical_plan::merge::MergeExec;
     use crate::physical_plan::{common, memory::MemoryExec};
+    use tempfile::TempDir;
 
     fn some_data() -> ArrowResult<(Arc<Schema>, Vec<RecordBatch>)> {
         // define a schema.
@@ -798,7 +1326,8 @@ mod tests {
         let (schema, batches) = some_data().unwrap();
 
         let input: Arc<dyn ExecutionPlan> = Arc::new(
-            MemoryExec::try_new(&vec![batches.clone(), batches], schema, None).unwrap(),
+            MemoryExec::try_new(&vec![batches.clone(), batches], schema.clone(), None)
+                .unwrap(),
         );
 
         let groups: Vec<(Arc<dyn PhysicalExpr>, String)> =
@@ -815,6 +1344,7 @@ mod tests {
             groups.clone(),
             aggregates.clone(),
             input,
+            schema.clone(),
         )?);
 
         let result = common::collect(partial_aggregate.execute(0).await?).await?;
@@ -854,6 +1384,7 @@ mod tests {
                 .collect(),
             aggregates,
             merge,
+            schema,
         )?);
 
         let result = common::collect(merged_aggregate.execute(0).await?).await?;
@@ -886,4 +1417,200 @@ mod tests {
 
         Ok(())
     }
+
+    /// A stream of batches that is pending before each batch, like a stream that
+    /// receives its batches from another thread
+    struct PendingStream {
+        schema: SchemaRef,
+        batches: Vec<RecordBatch>,
+        pending: bool,
+    }
+
+    impl Stream for PendingStream {
+        type Item = ArrowResult<RecordBatch>;
+
+        fn poll_next(
+            mut self: std::pin::Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Option<Self::Item>> {
+            if self.batches.is_empty() {
+                return Poll::Ready(None);
+            }
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            } else {
+                Poll::Ready(Some(Ok(self.batches.remove(0))))
+            }
+        }
+    }
+
+    impl RecordBatchStream for PendingStream {
+        fn schema(&self) -> SchemaRef {
+            self.schema.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn aggregate_pending_input() -> Result<()> {
+        let (schema, batches) = some_data()?;
+        let input = Box::pin(PendingStream {
+            schema: schema.clone(),
+            batches,
+            pending: false,
+        });
+        let aggregates: Vec<Arc<dyn AggregateExpr>> = vec![Arc::new(Sum::new(
+            col("b"),
+            "SUM(b)".to_string(),
+            DataType::Float64,
+        ))];
+        let output_schema = Arc::new(Schema::new(vec![Field::new(
+            "SUM(b)",
+            DataType::Float64,
+            true,
+        )]));
+        let stream = HashAggregateStream::try_new(
+            AggregateMode::Partial,
+            output_schema,
+            aggregates,
+            input,
+            BaselineMetrics::new(),
+            MemoryReservation::new("test", Arc::new(MemoryPool::default())),
+        )?;
+
+        // the single output batch is only produced once the input is exhausted
+        let result = common::collect(Box::pin(stream)).await?;
+        assert_eq!(result.len(), 1);
+        let sums = result[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(*sums, Float64Array::from(vec![20.0]));
+        Ok(())
+    }
+
+    #[test]
+    fn incremental_groups_size() -> Result<()> {
+        let (_, batches) = some_data()?;
+        let group_expr = vec![col("a")];
+        let aggr_expr: Vec<Arc<dyn AggregateExpr>> = vec![Arc::new(Avg::new(
+            col("b"),
+            "AVG(b)".to_string(),
+            DataType::Float64,
+        ))];
+        let aggregate_expressions =
+            aggregate_expressions(&aggr_expr, &AggregateMode::Partial)?;
+
+        let mut accumulators = Accumulators::default();
+        let mut groups_size = 0isize;
+        for batch in batches {
+            let (updated, size_change) = group_aggregate_batch_sized(
+                &AggregateMode::Partial,
+                &group_expr,
+                &aggr_expr,
+                batch,
+                accumulators,
+                &aggregate_expressions,
+            )?;
+            accumulators = updated;
+            groups_size += size_change;
+
+            // the size that is tracked incrementally is the size of all the groups
+            let expected = accumulators
+                .iter()
+                .map(|(key, (accumulator_set, indices))| {
+                    group_size(key, accumulator_set, indices.capacity())
+                })
+                .sum::<usize>();
+            assert_eq!(groups_size as usize, expected);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn spill_groups() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new("b", DataType::Float64, false),
+        ]));
+        // 4000 groups of two rows each, in batches of 1000 rows
+        let batches = (0..8)
+            .map(|i| {
+                let keys = (0..1000)
+                    .map(|j| (i * 1000 + j) % 4000)
+                    .collect::<Vec<u32>>();
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(UInt32Array::from(keys)),
+                        Arc::new(Float64Array::from(vec![1.0; 1000])),
+                    ],
+                )
+            })
+            .collect::<ArrowResult<Vec<_>>>()?;
+        let input = Arc::new(MemoryExec::try_new(&vec![batches], schema.clone(), None)?);
+
+        // the pool can hold the groups of a partition of the spilled groups, but not
+        // all of them
+        let pool = Arc::new(MemoryPool::new(Some(200_000)));
+        let tmp_dir = TempDir::new()?;
+        let spill_dir = Some(tmp_dir.path().to_path_buf());
+        let groups = vec![(col("a"), "a".to_string())];
+        let aggregates: Vec<Arc<dyn AggregateExpr>> = vec![Arc::new(Sum::new(
+            col("b"),
+            "SUM(b)".to_string(),
+            DataType::Float64,
+        ))];
+        let partial_aggregate = Arc::new(
+            HashAggregateExec::try_new(
+                AggregateMode::Partial,
+                groups.clone(),
+                aggregates.clone(),
+                input,
+                schema.clone(),
+            )?
+            .with_memory_pool(pool.clone())
+            .with_spill_dir(spill_dir.clone()),
+        );
+        let final_aggregate = HashAggregateExec::try_new(
+            AggregateMode::Final,
+            groups,
+            aggregates,
+            partial_aggregate.clone(),
+            schema,
+        )?
+        .with_memory_pool(pool.clone())
+        .with_spill_dir(spill_dir);
+
+        let result = common::collect(final_aggregate.execute(0).await?).await?;
+        let mut sums = vec![];
+        for batch in &result {
+            let a = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<UInt32Array>()
+                .unwrap();
+            let b = batch
+                .column(1)
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .unwrap();
+            sums.extend((0..batch.num_rows()).map(|i| (a.value(i), b.value(i))));
+        }
+        sums.sort_by_key(|(a, _)| *a);
+        assert_eq!(sums.len(), 4000);
+        assert!(sums
+            .iter()
+            .enumerate()
+            .all(|(i, (a, b))| *a == i as u32 && *b == 2.0));
+
+        assert!(partial_aggregate.metrics()["spill_count"].value() > 0);
+        assert!(final_aggregate.metrics()["spill_count"].value() > 0);
+        // the spill files are deleted and the memory released once the output is read
+        assert_eq!(std::fs::read_dir(tmp_dir.path())?.count(), 0);
+        assert_eq!(pool.reserved(), 0);
+        Ok(())
+    }
 }
@@ -21,6 +21,7 @@ use std::any::Any;
 use std::sync::Arc;
 
 use crate::error::{DataFusionError, Result};
+use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::memory::MemoryStream;
 use crate::physical_plan::{Distribution, ExecutionPlan, Partitioning};
 use arrow::array::ArrayRef;
@@ -29,6 +30,7 @@ use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
 use futures::StreamExt;
 
+use super::metrics::{BaselineMetrics, MetricsSet};
 use super::SendableRecordBatchStream;
 
 use async_trait::async_trait;
@@ -42,6 +44,8 @@ pub struct GlobalLimitExec {
     limit: usize,
     /// Number of threads to run parallel LocalLimitExec on
     concurrency: usize,
+    /// Execution metrics
+    metrics: BaselineMetrics,
 }
 
 impl GlobalLimitExec {
@@ -51,8 +55,24 @@ impl GlobalLimitExec {
             input,
             limit,
             concurrency,
+            metrics: BaselineMetrics::new(),
         }
     }
+
+    /// Input execution plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Maximum number of rows to return
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Number of threads to run parallel LocalLimitExec on
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
 }
 
 #[async_trait]
@@ -79,6 +99,10 @@ impl ExecutionPlan for GlobalLimitExec {
         Partitioning::UnknownPartitioning(1)
     }
 
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        self.input.output_ordering()
+    }
+
     fn with_new_children(
         &self,
         children: Vec<Arc<dyn ExecutionPlan>>,
@@ -113,11 +137,15 @@ impl ExecutionPlan for GlobalLimitExec {
 
         let mut it = self.input.execute(0).await?;
         Ok(Box::pin(MemoryStream::try_new(
-            collect_with_limit(&mut it, self.limit).await?,
+            collect_with_limit(&mut it, self.limit, &self.metrics).await?,
             self.input.schema(),
             None,
         )?))
     }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
 }
 
 /// LocalLimitExec applies a limit to a single partition
@@ -125,12 +153,27 @@ impl ExecutionPlan for GlobalLimitExec {
 pub struct LocalLimitExec {
     input: Arc<dyn ExecutionPlan>,
     limit: usize,
+    metrics: BaselineMetrics,
 }
 
 impl LocalLimitExec {
     /// Create a new LocalLimitExec partition
     pub fn new(input: Arc<dyn ExecutionPlan>, limit: usize) -> Self {
-        Self { input, limit }
+        Self {
+            input,
+            limit,
+            metrics: BaselineMetrics::new(),
+        }
+    }
+
+    /// Input execution plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Maximum number of rows to return
+    pub fn limit(&self) -> usize {
+        self.limit
     }
 }
 
@@ -153,6 +196,10 @@ impl ExecutionPlan for LocalLimitExec {
         self.input.output_partitioning()
     }
 
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        self.input.output_ordering()
+    }
+
     fn with_new_children(
         &self,
         children: Vec<Arc<dyn ExecutionPlan>>,
@@ -171,11 +218,15 @@ impl ExecutionPlan for LocalLimitExec {
     async fn execute(&self, _: usize) -> Result<SendableRecordBatchStream> {
         let mut it = self.input.execute(0).await?;
         Ok(Box::pin(MemoryStream::try_new(
-            collect_with_limit(&mut it, self.limit).await?,
+            collect_with_limit(&mut it, self.limit, &self.metrics).await?,
             self.input.schema(),
             None,
         )?))
     }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
 }
 
 /// Truncate a RecordBatch to maximum of n rows
@@ -190,10 +241,11 @@ pub fn truncate_batch(batch: &RecordBatch, n: usize) -> Result<RecordBatch> {
     )?)
 }
 
-/// Create a vector of record batches from an iterator
+/// Create a vector of record batches from an iterator, recording them in `metrics`
 async fn collect_with_limit(
     reader: &mut SendableRecordBatchStream,
     limit: usize,
+    metrics: &BaselineMetrics,
 ) -> Result<Vec<RecordBatch>> {
     let mut count = 0;
     let mut results: Vec<RecordBatch> = vec![];
@@ -201,14 +253,15 @@ async fn collect_with_limit(
         match reader.as_mut().next().await {
             Some(Ok(batch)) => {
                 let capacity = limit - count;
-                if batch.num_rows() <= capacity {
-                    count += batch.num_rows();
-                    results.push(batch);
+                let batch = if batch.num_rows() <= capacity {
+                    batch
                 } else {
-                    let batch = truncate_batch(&batch, capacity)?;
-                    count += batch.num_rows();
-                    results.push(batch);
-                }
+                    let _timer = metrics.elapsed_compute().timer();
+                    truncate_batch(&batch, capacity)?
+                };
+                count += batch.num_rows();
+                metrics.record_output(&batch);
+                results.push(batch);
                 if count == limit {
                     return Ok(results);
                 }
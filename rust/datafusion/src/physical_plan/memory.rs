@@ -21,6 +21,8 @@ use std::any::Any;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use super::expressions::PhysicalSortExpr;
+use super::metrics::{BaselineMetrics, MetricsSet, ObservedStream};
 use super::{ExecutionPlan, Partitioning, RecordBatchStream, SendableRecordBatchStream};
 use crate::error::{DataFusionError, Result};
 use arrow::datatypes::SchemaRef;
@@ -39,6 +41,10 @@ pub struct MemoryExec {
     schema: SchemaRef,
     /// Optional projection
     projection: Option<Vec<usize>>,
+    /// The order in which the rows of each partition are sorted, if they are
+    output_ordering: Option<Vec<PhysicalSortExpr>>,
+    /// Execution metrics
+    metrics: BaselineMetrics,
 }
 
 #[async_trait]
@@ -63,6 +69,10 @@ impl ExecutionPlan for MemoryExec {
         Partitioning::UnknownPartitioning(self.partitions.len())
     }
 
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        self.output_ordering.clone()
+    }
+
     fn with_new_children(
         &self,
         _: Vec<Arc<dyn ExecutionPlan>>,
@@ -74,11 +84,19 @@ impl ExecutionPlan for MemoryExec {
     }
 
     async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
-        Ok(Box::pin(MemoryStream::try_new(
+        let stream = MemoryStream::try_new(
             self.partitions[partition].clone(),
             self.schema.clone(),
             self.projection.clone(),
-        )?))
+        )?;
+        Ok(Box::pin(ObservedStream::new(
+            Box::pin(stream),
+            self.metrics.clone(),
+        )))
+    }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
     }
 }
 
@@ -93,8 +111,32 @@ impl MemoryExec {
             partitions: partitions.clone(),
             schema,
             projection,
+            output_ordering: None,
+            metrics: BaselineMetrics::new(),
         })
     }
+
+    /// Declare that the rows of each partition are sorted by `ordering`, which must
+    /// only sort on columns of the projected schema. The rows are not checked to be
+    /// sorted.
+    pub fn with_output_ordering(mut self, ordering: Vec<PhysicalSortExpr>) -> Self {
+        self.output_ordering = if ordering.is_empty() {
+            None
+        } else {
+            Some(ordering)
+        };
+        self
+    }
+
+    /// The partitions to query
+    pub fn partitions(&self) -> &[Vec<RecordBatch>] {
+        &self.partitions
+    }
+
+    /// Optional projection
+    pub fn projection(&self) -> Option<&Vec<usize>> {
+        self.projection.as_ref()
+    }
 }
 
 /// Iterator over batches
@@ -26,16 +26,17 @@ use futures::future;
 
 use super::common;
 use crate::error::{DataFusionError, Result};
+use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::ExecutionPlan;
 use crate::physical_plan::Partitioning;
 
+use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
-use arrow::{datatypes::SchemaRef, error::ArrowError};
 
+use super::metrics::{BaselineMetrics, MetricsSet, ObservedStream};
 use super::SendableRecordBatchStream;
 
 use async_trait::async_trait;
-use tokio;
 
 /// Merge execution plan executes partitions in parallel and combines them into a single
 /// partition. No guarantees are made about the order of the resulting partition.
@@ -43,12 +44,22 @@ use tokio;
 pub struct MergeExec {
     /// Input execution plan
     input: Arc<dyn ExecutionPlan>,
+    /// Execution metrics
+    metrics: BaselineMetrics,
 }
 
 impl MergeExec {
     /// Create a new MergeExec
     pub fn new(input: Arc<dyn ExecutionPlan>) -> Self {
-        MergeExec { input }
+        MergeExec {
+            input,
+            metrics: BaselineMetrics::new(),
+        }
+    }
+
+    /// Input execution plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
     }
 }
 
@@ -72,6 +83,15 @@ impl ExecutionPlan for MergeExec {
         Partitioning::UnknownPartitioning(1)
     }
 
+    /// The order of the rows is only known when there is a single input partition
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        if self.input.output_partitioning().partition_count() == 1 {
+            self.input.output_ordering()
+        } else {
+            None
+        }
+    }
+
     fn with_new_children(
         &self,
         children: Vec<Arc<dyn ExecutionPlan>>,
@@ -100,20 +120,22 @@ impl ExecutionPlan for MergeExec {
             )),
             1 => {
                 // bypass any threading if there is a single partition
-                self.input.execute(0).await
+                Ok(Box::pin(ObservedStream::counting(
+                    self.input.execute(0).await?,
+                    self.metrics.clone(),
+                )))
             }
             _ => {
                 let tasks = (0..input_partitions).map(|part_i| {
                     let input = self.input.clone();
-                    tokio::spawn(async move {
+                    // the tasks are aborted if this future is dropped before they finish
+                    common::spawn_abortable(async move {
                         let stream = input.execute(part_i).await?;
                         common::collect(stream).await
                     })
                 });
 
-                let results = future::try_join_all(tasks)
-                    .await
-                    .map_err(|e| ArrowError::from_external_error(Box::new(e)))?;
+                let results = future::try_join_all(tasks).await?;
 
                 let combined_results = results
                     .into_iter()
@@ -122,7 +144,10 @@ impl ExecutionPlan for MergeExec {
                         Result::Ok(acc)
                     })?
                     .into_iter()
-                    .map(|x| Arc::new(x))
+                    .map(|x| {
+                        self.metrics.record_output(&x);
+                        Arc::new(x)
+                    })
                     .collect::<Vec<_>>();
 
                 Ok(Box::pin(common::SizedRecordBatchStream::new(
@@ -132,6 +157,10 @@ impl ExecutionPlan for MergeExec {
             }
         }
     }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,331 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Metrics that execution plans record while they run, such as the number of rows they
+//! produce and the time they spend computing them. They are reported by `EXPLAIN ANALYZE`.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use arrow::datatypes::SchemaRef;
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+use futures::{Stream, StreamExt};
+
+use super::{ExecutionPlan, RecordBatchStream, SendableRecordBatchStream};
+
+/// The metrics of an execution plan, by name
+pub type MetricsSet = BTreeMap<String, SQLMetric>;
+
+/// The kind of value a [SQLMetric] holds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricType {
+    /// A number of things, such as rows or bytes
+    Counter,
+    /// An elapsed time, in nanoseconds
+    TimeNanos,
+}
+
+/// A metric recorded by an execution plan. Clones share the same value, so that all the
+/// partitions of a plan add up to a single metric.
+#[derive(Clone)]
+pub struct SQLMetric {
+    metric_type: MetricType,
+    value: Arc<AtomicUsize>,
+}
+
+impl SQLMetric {
+    /// Create a new metric of type `metric_type`, starting at zero
+    pub fn new(metric_type: MetricType) -> Self {
+        Self {
+            metric_type,
+            value: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Create a new counter
+    pub fn counter() -> Self {
+        Self::new(MetricType::Counter)
+    }
+
+    /// Create a new metric of elapsed time
+    pub fn time_nanos() -> Self {
+        Self::new(MetricType::TimeNanos)
+    }
+
+    /// The kind of value of this metric
+    pub fn metric_type(&self) -> MetricType {
+        self.metric_type
+    }
+
+    /// The current value of this metric
+    pub fn value(&self) -> usize {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// Add `n` to the value of this metric
+    pub fn add(&self, n: usize) {
+        self.value.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Add the time elapsed since `start` to this metric
+    pub fn add_elapsed(&self, start: Instant) {
+        self.add(start.elapsed().as_nanos() as usize);
+    }
+
+    /// Start a timer that adds the time elapsed until it is dropped to this metric
+    pub fn timer(&self) -> ScopedTimer<'_> {
+        ScopedTimer {
+            metric: self,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Display for SQLMetric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.metric_type {
+            MetricType::Counter => write!(f, "{}", self.value()),
+            MetricType::TimeNanos => {
+                write!(f, "{:?}", Duration::from_nanos(self.value() as u64))
+            }
+        }
+    }
+}
+
+impl fmt::Debug for SQLMetric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// Adds the time elapsed between its creation and its drop to a [SQLMetric]
+pub struct ScopedTimer<'a> {
+    metric: &'a SQLMetric,
+    start: Instant,
+}
+
+impl Drop for ScopedTimer<'_> {
+    fn drop(&mut self) {
+        self.metric.add_elapsed(self.start)
+    }
+}
+
+/// The metrics that all the built-in operators record
+#[derive(Debug, Clone)]
+pub struct BaselineMetrics {
+    /// The number of rows produced
+    output_rows: SQLMetric,
+    /// The number of batches produced
+    output_batches: SQLMetric,
+    /// The time spent computing the output, excluding the time spent waiting for inputs
+    elapsed_compute: SQLMetric,
+}
+
+impl BaselineMetrics {
+    /// Create a new set of baseline metrics, all starting at zero
+    pub fn new() -> Self {
+        Self {
+            output_rows: SQLMetric::counter(),
+            output_batches: SQLMetric::counter(),
+            elapsed_compute: SQLMetric::time_nanos(),
+        }
+    }
+
+    /// The number of rows produced
+    pub fn output_rows(&self) -> &SQLMetric {
+        &self.output_rows
+    }
+
+    /// The number of batches produced
+    pub fn output_batches(&self) -> &SQLMetric {
+        &self.output_batches
+    }
+
+    /// The time spent computing the output
+    pub fn elapsed_compute(&self) -> &SQLMetric {
+        &self.elapsed_compute
+    }
+
+    /// Record that `batch` was produced
+    pub fn record_output(&self, batch: &RecordBatch) {
+        self.output_rows.add(batch.num_rows());
+        self.output_batches.add(1);
+    }
+
+    /// Record the batch, if any, of the result of polling a stream, and return the result
+    pub fn record_poll(
+        &self,
+        poll: Poll<Option<ArrowResult<RecordBatch>>>,
+    ) -> Poll<Option<ArrowResult<RecordBatch>>> {
+        if let Poll::Ready(Some(Ok(batch))) = &poll {
+            self.record_output(batch);
+        }
+        poll
+    }
+
+    /// The metrics, by name, to be returned by [ExecutionPlan::metrics]
+    pub fn metrics(&self) -> MetricsSet {
+        let mut metrics = MetricsSet::new();
+        metrics.insert("output_rows".to_owned(), self.output_rows.clone());
+        metrics.insert("output_batches".to_owned(), self.output_batches.clone());
+        metrics.insert("elapsed_compute".to_owned(), self.elapsed_compute.clone());
+        metrics
+    }
+}
+
+impl Default for BaselineMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Wraps a stream, recording the batches it produces in [BaselineMetrics]
+pub struct ObservedStream {
+    inner: SendableRecordBatchStream,
+    metrics: BaselineMetrics,
+    /// Whether the time spent polling `inner` is recorded as compute time
+    time_polls: bool,
+}
+
+impl ObservedStream {
+    /// Create a new stream that records the output of `inner` in `metrics`, as well as
+    /// the time spent polling it. This suits leaf operators, whose streams do all their
+    /// work when they are polled.
+    pub fn new(inner: SendableRecordBatchStream, metrics: BaselineMetrics) -> Self {
+        Self {
+            inner,
+            metrics,
+            time_polls: true,
+        }
+    }
+
+    /// Create a new stream that only records the output of `inner` in `metrics`. This
+    /// suits operators that pass the batches of their input through.
+    pub fn counting(inner: SendableRecordBatchStream, metrics: BaselineMetrics) -> Self {
+        Self {
+            inner,
+            metrics,
+            time_polls: false,
+        }
+    }
+}
+
+impl Stream for ObservedStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = if self.time_polls {
+            let _timer = self.metrics.elapsed_compute().timer();
+            self.inner.poll_next_unpin(cx)
+        } else {
+            self.inner.poll_next_unpin(cx)
+        };
+        self.metrics.record_poll(poll)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl RecordBatchStream for ObservedStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+/// Formats `plan` as an indented tree of operators, each followed by the metrics it has
+/// recorded so far, e.g.
+///
+/// ```text
+/// ProjectionExec: elapsed_compute=27.1µs, output_batches=1, output_rows=5
+///   HashAggregateExec: ...
+/// ```
+pub fn format_plan_with_metrics(plan: &dyn ExecutionPlan) -> String {
+    let mut lines = vec![];
+    format_node(plan, 0, &mut lines);
+    lines.join("\n")
+}
+
+fn format_node(plan: &dyn ExecutionPlan, indent: usize, lines: &mut Vec<String>) {
+    let metrics = plan
+        .metrics()
+        .iter()
+        .map(|(name, metric)| format!("{}={}", name, metric))
+        .collect::<Vec<_>>();
+    let mut line = format!("{}{}", "  ".repeat(indent), plan.name());
+    if !metrics.is_empty() {
+        line.push_str(": ");
+        line.push_str(&metrics.join(", "));
+    }
+    lines.push(line);
+    for child in plan.children() {
+        format_node(child.as_ref(), indent + 1, lines);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Result;
+    use crate::physical_plan::empty::EmptyExec;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    #[test]
+    fn baseline_metrics() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?;
+
+        let metrics = BaselineMetrics::new();
+        let cloned = metrics.clone();
+        metrics.record_output(&batch);
+        let _ = cloned.record_poll(Poll::Ready(Some(Ok(batch))));
+        let _ = cloned.record_poll(Poll::Ready(None));
+        {
+            let _timer = metrics.elapsed_compute().timer();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(6, metrics.output_rows().value());
+        assert_eq!(2, metrics.output_batches().value());
+        assert!(metrics.elapsed_compute().value() >= 1_000_000);
+        assert_eq!("6", metrics.metrics()["output_rows"].to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn plan_with_metrics() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let plan = EmptyExec::new(schema);
+        assert_eq!("EmptyExec", plan.name());
+        assert_eq!("EmptyExec", format_plan_with_metrics(&plan));
+        Ok(())
+    }
+}
@@ -28,6 +28,8 @@ use arrow::datatypes::{DataType, Schema, SchemaRef};
 use arrow::error::Result as ArrowResult;
 use arrow::record_batch::RecordBatch;
 use arrow::{array::ArrayRef, datatypes::Field};
+use expressions::PhysicalSortExpr;
+use metrics::MetricsSet;
 
 use async_trait::async_trait;
 use futures::stream::Stream;
@@ -61,6 +63,13 @@ pub trait ExecutionPlan: Debug + Send + Sync {
     /// Returns the execution plan as [`Any`](std::any::Any) so that it can be
     /// downcast to a specific implementation.
     fn as_any(&self) -> &dyn Any;
+    /// The name of the operator, e.g. in the output of `EXPLAIN ANALYZE`. Defaults to
+    /// the name of the type that implements the plan, without its path.
+    fn name(&self) -> &str {
+        let type_name = std::any::type_name::<Self>();
+        let path = type_name.split('<').next().unwrap_or(type_name);
+        path.rsplit("::").next().unwrap_or(path)
+    }
     /// Get the schema for this execution plan
     fn schema(&self) -> SchemaRef;
     /// Specifies the output partitioning scheme of this plan
@@ -69,10 +78,27 @@ pub trait ExecutionPlan: Debug + Send + Sync {
     fn required_child_distribution(&self) -> Distribution {
         Distribution::UnspecifiedDistribution
     }
+    /// Specifies the order in which the rows of each output partition of this plan are
+    /// sorted, or None if they are not known to be sorted
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        None
+    }
+    /// Specifies, for each child, the order in which the rows of each of its partitions
+    /// must be sorted, or None if this operator does not require them to be sorted.
+    /// The physical planner sorts the children whose output does not satisfy it.
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortExpr>>> {
+        vec![None; self.children().len()]
+    }
     /// Get a list of child execution plans that provide the input for this plan. The returned list
     /// will be empty for leaf nodes, will contain a single value for unary nodes, or two
     /// values for binary nodes (such as joins).
     fn children(&self) -> Vec<Arc<dyn ExecutionPlan>>;
+    /// Whether this plan may produce batches without end, e.g. because it reads a
+    /// live stream. Plans are unbounded when any of their children is, unless they
+    /// override this.
+    fn is_unbounded(&self) -> bool {
+        self.children().iter().any(|child| child.is_unbounded())
+    }
     /// Returns a new plan where all children were replaced by new plans.
     /// The size of `children` must be equal to the size of `ExecutionPlan::children()`.
     fn with_new_children(
@@ -82,6 +108,27 @@ pub trait ExecutionPlan: Debug + Send + Sync {
 
     /// creates an iterator
     async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream>;
+
+    /// Returns the metrics this plan has recorded so far, summed over all the partitions
+    /// it has executed. Plans that do not record metrics return an empty set.
+    fn metrics(&self) -> MetricsSet {
+        MetricsSet::new()
+    }
+}
+
+/// Whether the rows of a partition that are sorted by `provided`, if they are sorted,
+/// are also sorted by `required`, which is the case when `required` is a prefix of
+/// `provided`
+pub fn ordering_satisfies(
+    provided: Option<&[PhysicalSortExpr]>,
+    required: &[PhysicalSortExpr],
+) -> bool {
+    match provided {
+        Some(provided) => {
+            provided.len() >= required.len() && provided[..required.len()] == *required
+        }
+        None => required.is_empty(),
+    }
 }
 
 /// Partitioning schemes supported by operators.
@@ -113,6 +160,10 @@ pub enum Distribution {
 /// Expression that can be evaluated against a RecordBatch
 /// A Physical expression knows its type, nullability and how to evaluate itself.
 pub trait PhysicalExpr: Send + Sync + Display + Debug {
+    /// Returns the physical expression as [`Any`](std::any::Any) so that it can be
+    /// downcast to a specific implementation. Implementations return `self`; there is
+    /// no default, as one could not be called on a `dyn PhysicalExpr`.
+    fn as_any(&self) -> &dyn Any;
     /// Get the data type of this expression, given the schema of the input
     fn data_type(&self, input_schema: &Schema) -> Result<DataType>;
     /// Determine whether this expression is nullable, given the schema of the input
@@ -127,6 +178,10 @@ pub trait PhysicalExpr: Send + Sync + Display + Debug {
 /// * knows its accumulator's state's field
 /// * knows the expressions from whose its accumulator will receive values
 pub trait AggregateExpr: Send + Sync + Debug {
+    /// Returns the aggregate expression as [`Any`](std::any::Any) so that it can be
+    /// downcast to a specific implementation.
+    fn as_any(&self) -> &dyn Any;
+
     /// the field of the final result of this aggregation.
     fn field(&self) -> Result<Field>;
 
@@ -196,11 +251,21 @@ pub trait Accumulator: Send + Sync + Debug {
 
     /// returns its value based on its current state.
     fn evaluate(&self) -> Result<ScalarValue>;
+
+    /// returns an estimate, in bytes, of the memory held by the accumulator, including
+    /// its own size. Accumulators whose state grows with their input, such as
+    /// those of distinct aggregates, should override it so that the memory they
+    /// hold counts towards the limit of the query.
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
 }
 
 pub mod aggregates;
+pub mod analyze;
 pub mod array_expressions;
 pub mod common;
+pub mod conditional_expressions;
 pub mod csv;
 pub mod datetime_expressions;
 pub mod distinct_expressions;
@@ -215,11 +280,24 @@ pub mod limit;
 pub mod math_expressions;
 pub mod memory;
 pub mod merge;
+pub mod metrics;
 pub mod parquet;
 pub mod planner;
 pub mod projection;
+pub mod regex_expressions;
+pub mod sample;
+pub mod series;
 pub mod sort;
+pub mod sort_enforcement;
+pub mod sort_merge_join;
+pub mod sort_preserving_merge;
+pub mod spill;
+pub mod stream;
 pub mod string_expressions;
+pub mod table_functions;
 pub mod type_coercion;
 pub mod udaf;
 pub mod udf;
+pub mod udtf;
+pub mod unnest;
+pub mod window_aggregate;
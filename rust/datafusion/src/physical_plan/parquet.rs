@@ -18,27 +18,37 @@
 //! Execution plan for reading Parquet files
 
 use std::any::Any;
-use std::fs::File;
+use std::fmt;
+use std::io::Cursor;
 use std::rc::Rc;
 use std::sync::Arc;
 use std::task::{Context, Poll};
-use std::{fmt, thread};
 
+use super::metrics::{BaselineMetrics, MetricsSet, SQLMetric};
 use super::{RecordBatchStream, SendableRecordBatchStream};
+use crate::datasource::object_store::{
+    list_files, run_on_store_thread, spawn_store_thread, LocalFileSystem, ObjectMeta,
+    ObjectStore,
+};
 use crate::error::{DataFusionError, Result};
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::sample::Sampler;
 use crate::physical_plan::ExecutionPlan;
-use crate::physical_plan::{common, Partitioning};
+use crate::physical_plan::Partitioning;
 use arrow::datatypes::{Schema, SchemaRef};
 use arrow::error::{ArrowError, Result as ArrowResult};
 use arrow::record_batch::RecordBatch;
-use parquet::file::reader::SerializedFileReader;
+use parquet::errors::{ParquetError, Result as ParquetResult};
+use parquet::file::reader::{ChunkReader, FileReader, Length, SerializedFileReader};
 
-use crossbeam::channel::{bounded, Receiver, RecvError, Sender};
 use fmt::Debug;
 use parquet::arrow::{ArrowReader, ParquetFileArrowReader};
 
 use async_trait::async_trait;
+use futures::channel::mpsc::{channel, Receiver, Sender};
+use futures::executor::block_on;
 use futures::stream::Stream;
+use futures::{SinkExt, StreamExt};
 
 /// Execution plan for scanning a Parquet file
 #[derive(Debug, Clone)]
@@ -51,6 +61,27 @@ pub struct ParquetExec {
     projection: Vec<usize>,
     /// Batch size
     batch_size: usize,
+    /// The store that the files are read from
+    object_store: Arc<dyn ObjectStore>,
+    /// The order in which the rows of each file are sorted, if they are
+    output_ordering: Option<Vec<PhysicalSortExpr>>,
+    /// The fraction and seed of a random sample of the row groups that are read, if
+    /// they are sampled
+    row_group_sample: Option<(f64, u64)>,
+    /// Execution metrics
+    metrics: ParquetMetrics,
+}
+
+/// Metrics of a [ParquetExec]
+#[derive(Debug, Clone)]
+struct ParquetMetrics {
+    baseline: BaselineMetrics,
+    /// The number of row groups read
+    row_groups_read: SQLMetric,
+    /// The number of row groups skipped without being fetched
+    row_groups_pruned: SQLMetric,
+    /// The number of bytes fetched from the object store
+    bytes_scanned: SQLMetric,
 }
 
 impl ParquetExec {
@@ -60,17 +91,78 @@ impl ParquetExec {
         projection: Option<Vec<usize>>,
         batch_size: usize,
     ) -> Result<Self> {
-        let mut filenames: Vec<String> = vec![];
-        common::build_file_list(path, &mut filenames, ".parquet")?;
+        Self::try_new_with_object_store(
+            path,
+            projection,
+            batch_size,
+            Arc::new(LocalFileSystem),
+        )
+    }
+
+    /// Create a new Parquet reader execution plan for the files of `object_store`
+    /// under `path`
+    pub fn try_new_with_object_store(
+        path: &str,
+        projection: Option<Vec<usize>>,
+        batch_size: usize,
+        object_store: Arc<dyn ObjectStore>,
+    ) -> Result<Self> {
+        let files = run_on_store_thread({
+            let object_store = object_store.clone();
+            let path = path.to_string();
+            move || block_on(list_files(object_store.as_ref(), &path, ".parquet"))
+        })?;
+        let filenames = files.into_iter().map(|file| file.path).collect();
+        Self::try_from_files_with_object_store(
+            filenames,
+            projection,
+            batch_size,
+            object_store,
+        )
+    }
+
+    /// Create a new Parquet reader execution plan for a list of files, reading the
+    /// schema from the first file
+    pub fn try_from_files(
+        filenames: Vec<String>,
+        projection: Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Self> {
+        Self::try_from_files_with_object_store(
+            filenames,
+            projection,
+            batch_size,
+            Arc::new(LocalFileSystem),
+        )
+    }
+
+    /// Create a new Parquet reader execution plan for a list of files of
+    /// `object_store`, reading the schema from the footer of the first file
+    pub fn try_from_files_with_object_store(
+        filenames: Vec<String>,
+        projection: Option<Vec<usize>>,
+        batch_size: usize,
+        object_store: Arc<dyn ObjectStore>,
+    ) -> Result<Self> {
         if filenames.is_empty() {
             Err(DataFusionError::Plan("No files found".to_string()))
         } else {
-            let file = File::open(&filenames[0])?;
-            let file_reader = Rc::new(SerializedFileReader::new(file)?);
-            let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
-            let schema = arrow_reader.get_schema()?;
-
-            Ok(Self::new(filenames, schema, projection, batch_size))
+            let schema = run_on_store_thread({
+                let object_store = object_store.clone();
+                let path = filenames[0].clone();
+                move || {
+                    let size = block_on(object_store.size(&path))?;
+                    let file = ObjectMeta { path, size };
+                    let chunk_reader =
+                        ObjectChunkReader::new(object_store, file, SQLMetric::counter());
+                    let file_reader = Rc::new(SerializedFileReader::new(chunk_reader)?);
+                    let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
+                    Ok(arrow_reader.get_schema()?)
+                }
+            })?;
+
+            Ok(Self::new(filenames, schema, projection, batch_size)
+                .with_object_store(object_store))
         }
     }
 
@@ -98,8 +190,74 @@ impl ParquetExec {
             schema: Arc::new(projected_schema),
             projection,
             batch_size,
+            object_store: Arc::new(LocalFileSystem),
+            output_ordering: None,
+            row_group_sample: None,
+            metrics: ParquetMetrics {
+                baseline: BaselineMetrics::new(),
+                row_groups_read: SQLMetric::counter(),
+                row_groups_pruned: SQLMetric::counter(),
+                bytes_scanned: SQLMetric::counter(),
+            },
         }
     }
+
+    /// Read the files from `object_store` rather than from the local filesystem
+    pub fn with_object_store(mut self, object_store: Arc<dyn ObjectStore>) -> Self {
+        self.object_store = object_store;
+        self
+    }
+
+    /// Declare that the rows of each file are sorted by `ordering`, which must only
+    /// sort on columns of the projected schema. The rows are not checked to be sorted.
+    pub fn with_output_ordering(mut self, ordering: Vec<PhysicalSortExpr>) -> Self {
+        self.output_ordering = if ordering.is_empty() {
+            None
+        } else {
+            Some(ordering)
+        };
+        self
+    }
+
+    /// Only read a random sample of whole row groups, each of which is read with the
+    /// probability `fraction`. The row groups of each file are chosen from the random
+    /// choices of `seed`, so the same files are sampled the same way every time.
+    pub fn with_row_group_sample(mut self, fraction: f64, seed: u64) -> Result<Self> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(DataFusionError::Plan(format!(
+                "The fraction of a sample must be between 0 and 1, but is {}",
+                fraction
+            )));
+        }
+        self.row_group_sample = Some((fraction, seed));
+        Ok(self)
+    }
+
+    /// The Parquet files to read
+    pub fn filenames(&self) -> &[String] {
+        &self.filenames
+    }
+
+    /// Projection for which columns to load
+    pub fn projection(&self) -> &[usize] {
+        &self.projection
+    }
+
+    /// Batch size
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// The store that the files are read from
+    pub fn object_store(&self) -> &Arc<dyn ObjectStore> {
+        &self.object_store
+    }
+
+    /// The fraction and seed of the sample of the row groups that are read, if they
+    /// are sampled
+    pub fn row_group_sample(&self) -> Option<(f64, u64)> {
+        self.row_group_sample
+    }
 }
 
 #[async_trait]
@@ -123,6 +281,10 @@ impl ExecutionPlan for ParquetExec {
         Partitioning::UnknownPartitioning(self.filenames.len())
     }
 
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        self.output_ordering.clone()
+    }
+
     fn with_new_children(
         &self,
         children: Vec<Arc<dyn ExecutionPlan>>,
@@ -139,18 +301,37 @@ impl ExecutionPlan for ParquetExec {
 
     async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
         // because the parquet implementation is not thread-safe, it is necessary to execute
-        // on a thread and communicate with channels
+        // on a thread and communicate with channels. The thread stops once the stream,
+        // and so the receiver, is dropped.
         let (response_tx, response_rx): (
-            Sender<Option<ArrowResult<RecordBatch>>>,
-            Receiver<Option<ArrowResult<RecordBatch>>>,
-        ) = bounded(2);
-
-        let filename = self.filenames[partition].clone();
+            Sender<ArrowResult<RecordBatch>>,
+            Receiver<ArrowResult<RecordBatch>>,
+        ) = channel(2);
+
+        let filename = &self.filenames[partition];
+        let file = ObjectMeta {
+            path: filename.clone(),
+            size: self.object_store.size(filename).await?,
+        };
+        let object_store = self.object_store.clone();
         let projection = self.projection.clone();
         let batch_size = self.batch_size;
-
-        thread::spawn(move || {
-            if let Err(e) = read_file(&filename, projection, batch_size, response_tx) {
+        let sampler = self
+            .row_group_sample
+            .map(|(fraction, seed)| Sampler::new(fraction, seed, partition));
+        let metrics = self.metrics.clone();
+
+        spawn_store_thread(move || {
+            let chunk_reader =
+                ObjectChunkReader::new(object_store, file, metrics.bytes_scanned.clone());
+            if let Err(e) = read_file(
+                chunk_reader,
+                projection,
+                batch_size,
+                sampler,
+                response_tx,
+                &metrics,
+            ) {
                 println!("Parquet reader thread terminated due to error: {:?}", e);
             }
         });
@@ -160,35 +341,118 @@ impl ExecutionPlan for ParquetExec {
             response_rx,
         }))
     }
+
+    fn metrics(&self) -> MetricsSet {
+        let mut metrics = self.metrics.baseline.metrics();
+        metrics.insert(
+            "row_groups_read".to_owned(),
+            self.metrics.row_groups_read.clone(),
+        );
+        metrics.insert(
+            "row_groups_pruned".to_owned(),
+            self.metrics.row_groups_pruned.clone(),
+        );
+        metrics.insert(
+            "bytes_scanned".to_owned(),
+            self.metrics.bytes_scanned.clone(),
+        );
+        metrics
+    }
+}
+
+/// A Parquet `ChunkReader` of an object of an [ObjectStore]. Each range that the
+/// Parquet reader requests, such as the footer or the chunk of a projected column, is
+/// fetched with a ranged read, so that the columns that are not read are not fetched.
+struct ObjectChunkReader {
+    object_store: Arc<dyn ObjectStore>,
+    file: ObjectMeta,
+    /// The number of bytes fetched from the store
+    bytes_scanned: SQLMetric,
+}
+
+impl ObjectChunkReader {
+    fn new(
+        object_store: Arc<dyn ObjectStore>,
+        file: ObjectMeta,
+        bytes_scanned: SQLMetric,
+    ) -> Self {
+        Self {
+            object_store,
+            file,
+            bytes_scanned,
+        }
+    }
+}
+
+impl Length for ObjectChunkReader {
+    fn len(&self) -> u64 {
+        self.file.size
+    }
 }
 
+impl ChunkReader for ObjectChunkReader {
+    type T = Cursor<Vec<u8>>;
+
+    fn get_read(&self, start: u64, length: usize) -> ParquetResult<Self::T> {
+        let bytes =
+            block_on(self.object_store.read_range(&self.file.path, start, length))
+                .map_err(|e| ParquetError::General(e.to_string()))?;
+        self.bytes_scanned.add(bytes.len());
+        Ok(Cursor::new(bytes))
+    }
+}
+
+/// Sends `result` to the stream, waiting while its buffer is full. Returns false if the
+/// stream was dropped, in which case nothing more needs to be read.
 fn send_result(
-    response_tx: &Sender<Option<ArrowResult<RecordBatch>>>,
-    result: Option<ArrowResult<RecordBatch>>,
-) -> Result<()> {
-    response_tx
-        .send(result)
-        .map_err(|e| DataFusionError::Execution(e.to_string()))?;
-    Ok(())
+    response_tx: &mut Sender<ArrowResult<RecordBatch>>,
+    result: ArrowResult<RecordBatch>,
+) -> bool {
+    block_on(response_tx.send(result)).is_ok()
 }
 
 fn read_file(
-    filename: &str,
+    chunk_reader: ObjectChunkReader,
     projection: Vec<usize>,
     batch_size: usize,
-    response_tx: Sender<Option<ArrowResult<RecordBatch>>>,
+    sampler: Option<Sampler>,
+    mut response_tx: Sender<ArrowResult<RecordBatch>>,
+    metrics: &ParquetMetrics,
 ) -> Result<()> {
-    let file = File::open(&filename)?;
-    let file_reader = Rc::new(SerializedFileReader::new(file)?);
+    let filename = chunk_reader.file.path.clone();
+    let mut file_reader = SerializedFileReader::new(chunk_reader)?;
+    if let Some(mut sampler) = sampler {
+        // the skipped row groups are not fetched from the store
+        file_reader = file_reader.filter_row_groups(|_, _| {
+            let keep = sampler.keep();
+            if !keep {
+                metrics.row_groups_pruned.add(1);
+            }
+            keep
+        });
+    }
+    let file_reader = Rc::new(file_reader);
+    metrics
+        .row_groups_read
+        .add(file_reader.metadata().num_row_groups());
     let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
     let mut batch_reader =
         arrow_reader.get_record_reader_by_columns(projection.clone(), batch_size)?;
     loop {
-        match batch_reader.next() {
-            Some(Ok(batch)) => send_result(&response_tx, Some(Ok(batch)))?,
+        let next = {
+            let _timer = metrics.baseline.elapsed_compute().timer();
+            batch_reader.next()
+        };
+        match next {
+            Some(Ok(batch)) => {
+                metrics.baseline.record_output(&batch);
+                if !send_result(&mut response_tx, Ok(batch)) {
+                    // the stream was dropped
+                    break;
+                }
+            }
             None => {
-                // finished reading file
-                send_result(&response_tx, None)?;
+                // finished reading file, which ends the stream as the sender is dropped
                 break;
             }
             Some(Err(e)) => {
@@ -196,9 +460,9 @@ fn read_file(
                     format!("Error reading batch from {}: {}", filename, e.to_string());
                 // send error to operator
                 send_result(
-                    &response_tx,
-                    Some(Err(ArrowError::ParquetError(err_msg.clone()))),
-                )?;
+                    &mut response_tx,
+                    Err(ArrowError::ParquetError(err_msg.clone())),
+                );
                 // terminate thread with error
                 return Err(DataFusionError::Execution(err_msg));
             }
@@ -209,21 +473,17 @@ fn read_file(
 
 struct ParquetStream {
     schema: SchemaRef,
-    response_rx: Receiver<Option<ArrowResult<RecordBatch>>>,
+    response_rx: Receiver<ArrowResult<RecordBatch>>,
 }
 
 impl Stream for ParquetStream {
     type Item = ArrowResult<RecordBatch>;
 
     fn poll_next(
-        self: std::pin::Pin<&mut Self>,
-        _: &mut Context<'_>,
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        match self.response_rx.recv() {
-            Ok(batch) => Poll::Ready(batch),
-            // RecvError means receiver has exited and closed the channel
-            Err(RecvError) => Poll::Ready(None),
-        }
+        self.response_rx.poll_next_unpin(cx)
     }
 }
 
@@ -236,6 +496,7 @@ impl RecordBatchStream for ParquetStream {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::datasource::object_store::InMemoryObjectStore;
     use futures::StreamExt;
     use std::env;
 
@@ -269,4 +530,92 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn stop_reading_when_dropped() -> Result<()> {
+        let testdata =
+            env::var("PARQUET_TEST_DATA").expect("PARQUET_TEST_DATA not defined");
+        let filename = format!("{}/alltypes_plain.parquet", testdata);
+        // one batch per row, of the 8 rows of the file
+        let parquet_exec = ParquetExec::try_new(&filename, Some(vec![0]), 1)?;
+
+        let mut results = parquet_exec.execute(0).await?;
+        assert_eq!(1, results.next().await.unwrap()?.num_rows());
+        drop(results);
+        std::thread::sleep(std::time::Duration::from_millis(100));
+
+        // the batch that was read, the batches in the buffer of the channel, and the
+        // batch that could not be sent
+        assert!(parquet_exec.metrics()["output_rows"].value() <= 5);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sample_row_groups() -> Result<()> {
+        let testdata =
+            env::var("PARQUET_TEST_DATA").expect("PARQUET_TEST_DATA not defined");
+        let filename = format!("{}/alltypes_plain.parquet", testdata);
+        // the file has a single row group, which is either read or skipped
+        for &(fraction, rows) in &[(1.0, 8), (0.0, 0)] {
+            let parquet_exec = ParquetExec::try_new(&filename, Some(vec![0]), 1024)?
+                .with_row_group_sample(fraction, 3)?;
+            let mut results = parquet_exec.execute(0).await?;
+            let mut num_rows = 0;
+            while let Some(batch) = results.next().await {
+                num_rows += batch?.num_rows();
+            }
+            assert_eq!(rows, num_rows);
+            let metrics = parquet_exec.metrics();
+            assert_eq!(rows / 8, metrics["row_groups_read"].value());
+            assert_eq!(1 - rows / 8, metrics["row_groups_pruned"].value());
+        }
+
+        let err = ParquetExec::try_new(&filename, None, 1024)?
+            .with_row_group_sample(-0.5, 3)
+            .expect_err("expected an error");
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: The fraction of a sample must be between 0 and 1, but is -0.5"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_projected_columns_from_object_store() -> Result<()> {
+        let testdata =
+            env::var("PARQUET_TEST_DATA").expect("PARQUET_TEST_DATA not defined");
+        let data = std::fs::read(format!("{}/alltypes_plain.parquet", testdata))?;
+        let store = Arc::new(InMemoryObjectStore::new());
+        store.put("data/alltypes_plain.parquet", data);
+        store.put("data/README", vec![]);
+
+        let scan = |projection: Vec<usize>| {
+            let store = store.clone();
+            async move {
+                let parquet_exec = ParquetExec::try_new_with_object_store(
+                    "data/",
+                    Some(projection.clone()),
+                    1024,
+                    store,
+                )?;
+                assert_eq!(
+                    parquet_exec.filenames(),
+                    &["data/alltypes_plain.parquet".to_string()]
+                );
+
+                let mut results = parquet_exec.execute(0).await?;
+                let batch = results.next().await.unwrap()?;
+                assert_eq!(8, batch.num_rows());
+                assert_eq!(projection.len(), batch.num_columns());
+                assert!(results.next().await.is_none());
+                Ok::<_, DataFusionError>(parquet_exec.metrics()["bytes_scanned"].value())
+            }
+        };
+
+        // the chunks of the columns that are not projected are not fetched
+        let one_column = scan(vec![0]).await?;
+        let all_columns = scan((0..11).collect()).await?;
+        assert!(one_column < all_columns);
+        Ok(())
+    }
 }
@@ -23,8 +23,10 @@ use super::{aggregates, empty::EmptyExec, expressions::binary, functions, udaf};
 use crate::error::{DataFusionError, Result};
 use crate::execution::context::ExecutionContextState;
 use crate::logical_plan::{
-    Expr, LogicalPlan, PlanType, StringifiedPlan, TableSource, UserDefinedLogicalNode,
+    join_key_type, Column as LogicalColumn, DFSchema, Expr, LogicalPlan, Operator,
+    PlanType, SampleMethod, StringifiedPlan, TableSource, UserDefinedLogicalNode,
 };
+use crate::physical_plan::analyze::AnalyzeExec;
 use crate::physical_plan::csv::{CsvExec, CsvReadOptions};
 use crate::physical_plan::explain::ExplainExec;
 use crate::physical_plan::expressions::{Column, Literal, PhysicalSortExpr};
@@ -35,13 +37,18 @@ use crate::physical_plan::memory::MemoryExec;
 use crate::physical_plan::merge::MergeExec;
 use crate::physical_plan::parquet::ParquetExec;
 use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::sample::SampleExec;
 use crate::physical_plan::sort::SortExec;
+use crate::physical_plan::sort_enforcement;
+use crate::physical_plan::sort_merge_join::SortMergeJoinExec;
 use crate::physical_plan::udf;
+use crate::physical_plan::unnest::UnnestExec;
+use crate::physical_plan::window_aggregate::WindowAggregateExec;
 use crate::physical_plan::{expressions, Distribution};
 use crate::physical_plan::{AggregateExpr, ExecutionPlan, PhysicalExpr, PhysicalPlanner};
 use crate::variable::VarType;
 use arrow::compute::SortOptions;
-use arrow::datatypes::Schema;
+use arrow::datatypes::{DataType, Schema};
 use expressions::col;
 
 /// This trait permits the `DefaultPhysicalPlanner` to create plans for
@@ -79,7 +86,8 @@ impl PhysicalPlanner for DefaultPhysicalPlanner {
         ctx_state: &ExecutionContextState,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         let plan = self.create_initial_plan(logical_plan, ctx_state)?;
-        self.optimize_plan(plan, ctx_state)
+        let plan = self.optimize_plan(plan, ctx_state)?;
+        sort_enforcement::enforce_sorting(plan, ctx_state)
     }
 }
 
@@ -159,7 +167,7 @@ impl DefaultPhysicalPlanner {
                 ..
             } => Ok(Arc::new(MemoryExec::try_new(
                 data,
-                Arc::new(projected_schema.as_ref().to_owned()),
+                Arc::new(Schema::from(projected_schema.as_ref())),
                 projection.to_owned(),
             )?)),
             LogicalPlan::CsvScan {
@@ -169,7 +177,7 @@ impl DefaultPhysicalPlanner {
                 delimiter,
                 projection,
                 ..
-            } => Ok(Arc::new(CsvExec::try_new(
+            } => Ok(Arc::new(CsvExec::try_new_with_object_store(
                 path,
                 CsvReadOptions::new()
                     .schema(schema.as_ref())
@@ -177,27 +185,29 @@ impl DefaultPhysicalPlanner {
                     .has_header(*has_header),
                 projection.to_owned(),
                 batch_size,
+                ctx_state.config.object_store.clone(),
             )?)),
             LogicalPlan::ParquetScan {
                 path, projection, ..
-            } => Ok(Arc::new(ParquetExec::try_new(
+            } => Ok(Arc::new(ParquetExec::try_new_with_object_store(
                 path,
                 projection.to_owned(),
                 batch_size,
+                ctx_state.config.object_store.clone(),
             )?)),
             LogicalPlan::Projection { input, expr, .. } => {
-                let input = self.create_physical_plan(input, ctx_state)?;
-                let input_schema = input.as_ref().schema().clone();
+                let input_exec = self.create_physical_plan(input, ctx_state)?;
+                let input_schema = input_exec.as_ref().schema().clone();
                 let runtime_expr = expr
                     .iter()
                     .map(|e| {
                         tuple_err((
                             self.create_physical_expr(e, &input_schema, &ctx_state),
-                            e.name(&input_schema),
+                            e.name(input.schema()),
                         ))
                     })
                     .collect::<Result<Vec<_>>>()?;
-                Ok(Arc::new(ProjectionExec::try_new(runtime_expr, input)?))
+                Ok(Arc::new(ProjectionExec::try_new(runtime_expr, input_exec)?))
             }
             LogicalPlan::Aggregate {
                 input,
@@ -206,45 +216,82 @@ impl DefaultPhysicalPlanner {
                 ..
             } => {
                 // Initially need to perform the aggregate and then merge the partitions
-                let input = self.create_physical_plan(input, ctx_state)?;
-                let input_schema = input.as_ref().schema().clone();
+                let input_exec = self.create_physical_plan(input, ctx_state)?;
+                let input_schema = input_exec.as_ref().schema().clone();
 
                 let groups = group_expr
                     .iter()
                     .map(|e| {
                         tuple_err((
                             self.create_physical_expr(e, &input_schema, ctx_state),
-                            e.name(&input_schema),
+                            e.name(input.schema()),
                         ))
                     })
                     .collect::<Result<Vec<_>>>()?;
                 let aggregates = aggr_expr
                     .iter()
-                    .map(|e| self.create_aggregate_expr(e, &input_schema, ctx_state))
+                    .map(|e| {
+                        self.create_aggregate_expr(
+                            e,
+                            input.schema(),
+                            &input_schema,
+                            ctx_state,
+                        )
+                    })
                     .collect::<Result<Vec<_>>>()?;
 
-                let initial_aggr = Arc::new(HashAggregateExec::try_new(
-                    AggregateMode::Partial,
-                    groups.clone(),
-                    aggregates.clone(),
-                    input,
-                )?);
+                // the aggregation of an unbounded input would never end, unless it
+                // outputs the groups of each window of its rows once it is closed
+                if input_exec.is_unbounded() {
+                    let window_index =
+                        group_expr.iter().position(is_tumble).ok_or_else(|| {
+                            DataFusionError::Plan(
+                                "Aggregations of unbounded inputs must be grouped by a \
+                                 tumble window"
+                                    .to_string(),
+                            )
+                        })?;
+                    return Ok(Arc::new(WindowAggregateExec::try_new(
+                        groups,
+                        window_index,
+                        aggregates,
+                        input_exec,
+                        input_schema,
+                    )?));
+                }
+
+                let initial_aggr = Arc::new(
+                    HashAggregateExec::try_new(
+                        AggregateMode::Partial,
+                        groups.clone(),
+                        aggregates.clone(),
+                        input_exec,
+                        input_schema.clone(),
+                    )?
+                    .with_memory_pool(ctx_state.memory_pool.clone())
+                    .with_spill_dir(ctx_state.config.spill_dir.clone()),
+                );
 
                 let final_group: Vec<Arc<dyn PhysicalExpr>> =
                     (0..groups.len()).map(|i| col(&groups[i].1)).collect();
 
                 // construct a second aggregation, keeping the final column name equal to the first aggregation
                 // and the expressions corresponding to the respective aggregate
-                Ok(Arc::new(HashAggregateExec::try_new(
-                    AggregateMode::Final,
-                    final_group
-                        .iter()
-                        .enumerate()
-                        .map(|(i, expr)| (expr.clone(), groups[i].1.clone()))
-                        .collect(),
-                    aggregates,
-                    initial_aggr,
-                )?))
+                Ok(Arc::new(
+                    HashAggregateExec::try_new(
+                        AggregateMode::Final,
+                        final_group
+                            .iter()
+                            .enumerate()
+                            .map(|(i, expr)| (expr.clone(), groups[i].1.clone()))
+                            .collect(),
+                        aggregates,
+                        initial_aggr,
+                        input_schema,
+                    )?
+                    .with_memory_pool(ctx_state.memory_pool.clone())
+                    .with_spill_dir(ctx_state.config.spill_dir.clone()),
+                ))
             }
             LogicalPlan::Filter {
                 input, predicate, ..
@@ -257,6 +304,11 @@ impl DefaultPhysicalPlanner {
             }
             LogicalPlan::Sort { expr, input, .. } => {
                 let input = self.create_physical_plan(input, ctx_state)?;
+                if input.is_unbounded() {
+                    return Err(DataFusionError::Plan(
+                        "Unbounded inputs cannot be sorted".to_string(),
+                    ));
+                }
                 let input_schema = input.as_ref().schema().clone();
 
                 let sort_expr = expr
@@ -281,15 +333,64 @@ impl DefaultPhysicalPlanner {
                     })
                     .collect::<Result<Vec<_>>>()?;
 
-                Ok(Arc::new(SortExec::try_new(
-                    sort_expr,
-                    input,
-                    ctx_state.config.concurrency,
-                )?))
+                Ok(Arc::new(
+                    SortExec::try_new(sort_expr, input, ctx_state.config.concurrency)?
+                        .with_memory_pool(ctx_state.memory_pool.clone()),
+                ))
             }
-            LogicalPlan::EmptyRelation { schema } => {
-                Ok(Arc::new(EmptyExec::new(Arc::new(schema.as_ref().clone()))))
+            LogicalPlan::Join {
+                left,
+                right,
+                on,
+                join_type,
+                join_constraint,
+                schema,
+            } => {
+                // joins are executed by merging their inputs sorted on the join keys,
+                // which only buffers the rows of one key. The inputs are sorted when
+                // the plan is optimized, unless they already are.
+                let key_types = on
+                    .iter()
+                    .map(|(l, r)| {
+                        let l = left.schema().field_from_column(l)?;
+                        let r = right.schema().field_from_column(r)?;
+                        join_key_type(l.data_type(), r.data_type()).ok_or_else(|| {
+                            DataFusionError::Internal(format!(
+                                "Join columns {} and {} have no common type",
+                                l.qualified_name(),
+                                r.qualified_name()
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let (left_exec, left_keys) = join_input(
+                    self.create_physical_plan(left, ctx_state)?,
+                    left.schema(),
+                    schema,
+                    on.iter().map(|(l, _)| l).zip(&key_types),
+                )?;
+                let (right_exec, right_keys) = join_input(
+                    self.create_physical_plan(right, ctx_state)?,
+                    right.schema(),
+                    schema,
+                    on.iter().map(|(_, r)| r).zip(&key_types),
+                )?;
+                let on = left_keys.into_iter().zip(right_keys).collect();
+                Ok(Arc::new(
+                    SortMergeJoinExec::try_new(
+                        left_exec,
+                        right_exec,
+                        &on,
+                        join_type,
+                        join_constraint,
+                        ctx_state.config.batch_size,
+                    )?
+                    .with_memory_pool(ctx_state.memory_pool.clone()),
+                ))
             }
+            LogicalPlan::EmptyRelation { schema } => Ok(Arc::new(EmptyExec::new(
+                Arc::new(Schema::from(schema.as_ref())),
+            ))),
             LogicalPlan::Limit { input, n, .. } => {
                 let limit = *n;
                 let input = self.create_physical_plan(input, ctx_state)?;
@@ -309,6 +410,35 @@ impl DefaultPhysicalPlanner {
                     ctx_state.config.concurrency,
                 )))
             }
+            LogicalPlan::Unnest { input, column, .. } => {
+                let input = self.create_physical_plan(input, ctx_state)?;
+                let column_index = input
+                    .schema()
+                    .index_of(&physical_column_name(column, &input.schema()))?;
+                Ok(Arc::new(UnnestExec::try_new(input, column_index)?))
+            }
+            LogicalPlan::Sample {
+                input,
+                method,
+                fraction,
+                seed,
+            } => {
+                let input = self.create_physical_plan(input, ctx_state)?;
+                // Parquet files skip the row groups that are not sampled rather than
+                // reading them
+                if *method == SampleMethod::System {
+                    if let Some(exec) = input.as_any().downcast_ref::<ParquetExec>() {
+                        if exec.row_group_sample().is_none() {
+                            return Ok(Arc::new(
+                                exec.clone().with_row_group_sample(*fraction, *seed)?,
+                            ));
+                        }
+                    }
+                }
+                Ok(Arc::new(SampleExec::try_new(
+                    input, *method, *fraction, *seed,
+                )?))
+            }
             LogicalPlan::CreateExternalTable { .. } => {
                 // There is no default plan for "CREATE EXTERNAL
                 // TABLE" -- it must be handled at a higher level (so
@@ -339,9 +469,18 @@ impl DefaultPhysicalPlanner {
                         format!("{:#?}", input),
                     ));
                 }
-                let schema_ref = Arc::new(schema.as_ref().clone());
+                let schema_ref = Arc::new(Schema::from(schema.as_ref()));
                 Ok(Arc::new(ExplainExec::new(schema_ref, stringified_plans)))
             }
+            LogicalPlan::Analyze {
+                verbose,
+                input,
+                schema,
+            } => {
+                let input = self.create_physical_plan(input, ctx_state)?;
+                let schema_ref = Arc::new(Schema::from(schema.as_ref()));
+                Ok(Arc::new(AnalyzeExec::new(*verbose, input, schema_ref)))
+            }
             LogicalPlan::Extension { node } => {
                 let inputs = node
                     .inputs()
@@ -358,11 +497,12 @@ impl DefaultPhysicalPlanner {
                 // Ensure the ExecutionPlan's  schema matches the
                 // declared logical schema to catch and warn about
                 // logic errors when creating user defined plans.
-                if plan.schema() != *node.schema() {
+                let logical_schema = Schema::from(node.schema().as_ref());
+                if *plan.schema() != logical_schema {
                     Err(DataFusionError::Plan(format!(
                         "Extension planner for {:?} created an ExecutionPlan with mismatched schema. \
                          LogicalPlan schema: {:?}, ExecutionPlan schema: {:?}",
-                        node, node.schema(), plan.schema()
+                        node, logical_schema, plan.schema()
                     )))
                 } else {
                     Ok(plan)
@@ -382,10 +522,11 @@ impl DefaultPhysicalPlanner {
             Expr::Alias(expr, ..) => {
                 Ok(self.create_physical_expr(expr, input_schema, ctx_state)?)
             }
-            Expr::Column(name) => {
+            Expr::Column(c) => {
+                let name = physical_column_name(c, input_schema);
                 // check that name exists
                 input_schema.field_with_name(&name)?;
-                Ok(Arc::new(Column::new(name)))
+                Ok(Arc::new(Column::new(&name)))
             }
             Expr::Literal(value) => Ok(Arc::new(Literal::new(value.clone()))),
             Expr::ScalarVariable(variable_names) => {
@@ -423,6 +564,11 @@ impl DefaultPhysicalPlanner {
                 input_schema,
                 data_type.clone(),
             ),
+            Expr::GetIndexedField { expr, key } => expressions::get_indexed_field_expr(
+                self.create_physical_expr(expr, input_schema, ctx_state)?,
+                key.clone(),
+                input_schema,
+            ),
             Expr::Not(expr) => expressions::not(
                 self.create_physical_expr(expr, input_schema, ctx_state)?,
                 input_schema,
@@ -435,6 +581,41 @@ impl DefaultPhysicalPlanner {
             Expr::IsNotNull(expr) => expressions::is_not_null(
                 self.create_physical_expr(expr, input_schema, ctx_state)?,
             ),
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                let value = self.create_physical_expr(expr, input_schema, ctx_state)?;
+                let low = self.create_physical_expr(low, input_schema, ctx_state)?;
+                let high = self.create_physical_expr(high, input_schema, ctx_state)?;
+
+                // `expr BETWEEN low AND high` is evaluated as `expr >= low AND expr <= high`
+                let between = binary(
+                    binary(value.clone(), Operator::GtEq, low, input_schema)?,
+                    Operator::And,
+                    binary(value, Operator::LtEq, high, input_schema)?,
+                    input_schema,
+                )?;
+                if *negated {
+                    expressions::not(between, input_schema)
+                } else {
+                    Ok(between)
+                }
+            }
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let value = self.create_physical_expr(expr, input_schema, ctx_state)?;
+                let list = list
+                    .iter()
+                    .map(|e| self.create_physical_expr(e, input_schema, ctx_state))
+                    .collect::<Result<Vec<_>>>()?;
+                expressions::in_list(value, list, *negated, input_schema)
+            }
             Expr::ScalarFunction { fun, args } => {
                 let physical_args = args
                     .iter()
@@ -458,6 +639,11 @@ impl DefaultPhysicalPlanner {
                     input_schema,
                 )
             }
+            Expr::Placeholder { id, .. } => Err(DataFusionError::Plan(format!(
+                "Placeholder {} must be bound to a value before the query is executed. \
+                 Use ExecutionContext::prepare to run parameterized queries.",
+                id
+            ))),
             other => Err(DataFusionError::NotImplemented(format!(
                 "Physical plan does not support logical expression {:?}",
                 other
@@ -469,13 +655,14 @@ impl DefaultPhysicalPlanner {
     pub fn create_aggregate_expr(
         &self,
         e: &Expr,
+        logical_input_schema: &DFSchema,
         input_schema: &Schema,
         ctx_state: &ExecutionContextState,
     ) -> Result<Arc<dyn AggregateExpr>> {
         // unpack aliased logical expressions, e.g. "sum(col) as total"
         let (name, e) = match e {
             Expr::Alias(sub_expr, alias) => (alias.clone(), sub_expr.as_ref()),
-            _ => (e.name(input_schema)?, e),
+            _ => (e.name(logical_input_schema)?, e),
         };
 
         match e {
@@ -536,6 +723,18 @@ fn tuple_err<T, R>(value: (Result<T>, Result<R>)) -> Result<(T, R)> {
     }
 }
 
+/// Whether `expr` is the start of the tumbling window of a timestamp, possibly aliased
+fn is_tumble(expr: &Expr) -> bool {
+    match expr {
+        Expr::ScalarFunction {
+            fun: functions::BuiltinScalarFunction::Tumble,
+            ..
+        } => true,
+        Expr::Alias(expr, _) => is_tumble(expr),
+        _ => false,
+    }
+}
+
 struct DefaultExtensionPlanner {}
 
 impl ExtensionPlanner for DefaultExtensionPlanner {
@@ -553,12 +752,80 @@ impl ExtensionPlanner for DefaultExtensionPlanner {
     }
 }
 
+/// The name of the field of a physical schema that `column` references. Physical
+/// schemas are not qualified, except for the columns of joins that have the same name
+/// as another column of the join, which are named by their qualified names.
+fn physical_column_name(column: &LogicalColumn, schema: &Schema) -> String {
+    let qualified_name = column.flat_name();
+    if schema.field_with_name(&qualified_name).is_ok() {
+        qualified_name
+    } else {
+        column.name.clone()
+    }
+}
+
+/// Prepares `input`, the physical plan of an input of a join whose output schema is
+/// `join_schema`, to be joined on `keys`, its key columns paired with the types that
+/// they are coerced to. The columns of the input that have the same name as another
+/// column of the output of the join are renamed to their qualified names, and the keys
+/// are cast to their types. Returns the prepared input and the names of its keys.
+fn join_input<'a>(
+    input: Arc<dyn ExecutionPlan>,
+    input_schema: &DFSchema,
+    join_schema: &DFSchema,
+    keys: impl Iterator<Item = (&'a LogicalColumn, &'a DataType)>,
+) -> Result<(Arc<dyn ExecutionPlan>, Vec<String>)> {
+    let physical_schema = input.schema();
+    // whether the input needs to be projected
+    let mut changed = false;
+    let mut exprs = input_schema
+        .fields()
+        .iter()
+        .zip(physical_schema.fields())
+        .map(|(field, physical_field)| {
+            let shared_name = join_schema
+                .fields()
+                .iter()
+                .filter(|f| f.name() == field.name())
+                .count()
+                > 1;
+            let name = if shared_name {
+                changed = true;
+                field.qualified_name()
+            } else {
+                physical_field.name().clone()
+            };
+            (col(physical_field.name()), name)
+        })
+        .collect::<Vec<_>>();
+
+    let mut key_names = vec![];
+    for (key, key_type) in keys {
+        let index = input_schema.index_of_column(key)?;
+        if physical_schema.field(index).data_type() != key_type {
+            changed = true;
+            let expr = exprs[index].0.clone();
+            exprs[index].0 = expressions::cast(expr, &physical_schema, key_type.clone())?;
+        }
+        key_names.push(exprs[index].1.clone());
+    }
+
+    if changed {
+        Ok((Arc::new(ProjectionExec::try_new(exprs, input)?), key_names))
+    } else {
+        Ok((input, key_names))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::execution::memory_manager::MemoryPool;
     use crate::physical_plan::{csv::CsvReadOptions, expressions, Partitioning};
     use crate::{
-        logical_plan::{col, lit, sum, LogicalPlanBuilder},
+        logical_plan::{
+            col, lit, sum, Column, DFField, DFSchemaRef, JoinType, LogicalPlanBuilder,
+        },
         physical_plan::SendableRecordBatchStream,
     };
     use crate::{prelude::ExecutionConfig, test::arrow_testdata_path};
@@ -573,7 +840,9 @@ mod tests {
             scalar_functions: HashMap::new(),
             var_provider: HashMap::new(),
             aggregate_functions: HashMap::new(),
+            table_functions: HashMap::new(),
             config: ExecutionConfig::new(),
+            memory_pool: Arc::new(MemoryPool::default()),
         }
     }
 
@@ -640,6 +909,39 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn join_sorts_unsorted_inputs() -> Result<()> {
+        let testdata = arrow_testdata_path();
+        let path = format!("{}/csv/aggregate_test_100.csv", testdata);
+        let options = || CsvReadOptions::new().schema_infer_max_records(100);
+
+        let right =
+            LogicalPlanBuilder::scan_csv(&path, options(), Some(vec![0, 2]))?.build()?;
+        let logical_plan =
+            LogicalPlanBuilder::scan_csv(&path, options(), Some(vec![0, 1]))?
+                .sort(vec![col("c1").sort(true, true)])?
+                .join_using(&right, JoinType::Inner, vec![Column::from_name("c1")])?
+                .build()?;
+
+        let plan = plan(&logical_plan)?;
+        let join = plan
+            .as_any()
+            .downcast_ref::<SortMergeJoinExec>()
+            .expect("a sort-merge join");
+        let sorted_input = |input: &Arc<dyn ExecutionPlan>| {
+            input
+                .as_any()
+                .downcast_ref::<SortExec>()
+                .expect("a sorted input")
+                .input()
+                .clone()
+        };
+        // the left input is already sorted on the join key
+        assert!(sorted_input(join.left()).as_any().is::<CsvExec>());
+        assert!(sorted_input(join.right()).as_any().is::<CsvExec>());
+        Ok(())
+    }
+
     #[test]
     fn errors() -> Result<()> {
         let testdata = arrow_testdata_path();
@@ -675,6 +977,34 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn sample_parquet_row_groups() -> Result<()> {
+        let testdata =
+            std::env::var("PARQUET_TEST_DATA").expect("PARQUET_TEST_DATA not defined");
+        let path = format!("{}/alltypes_plain.parquet", testdata);
+        let scan = LogicalPlanBuilder::scan_parquet(&path, None)?;
+
+        // the row groups are sampled by the scan
+        let logical_plan = scan.sample(SampleMethod::System, 0.25, 5)?.build()?;
+        let physical_plan = plan(&logical_plan)?;
+        let exec = physical_plan
+            .as_any()
+            .downcast_ref::<ParquetExec>()
+            .expect("expected a ParquetExec");
+        assert_eq!(exec.row_group_sample(), Some((0.25, 5)));
+
+        // the rows of the row groups are sampled by a SampleExec
+        let logical_plan = scan.sample(SampleMethod::Bernoulli, 0.25, 5)?.build()?;
+        let physical_plan = plan(&logical_plan)?;
+        let exec = physical_plan
+            .as_any()
+            .downcast_ref::<SampleExec>()
+            .expect("expected a SampleExec");
+        assert_eq!(exec.method(), SampleMethod::Bernoulli);
+        assert!(exec.input().as_any().is::<ParquetExec>());
+        Ok(())
+    }
+
     #[test]
     fn default_extension_planner() -> Result<()> {
         let ctx_state = make_ctx_state();
@@ -727,17 +1057,16 @@ mod tests {
 
     /// An example extension node that doesn't do anything
     struct NoOpExtensionNode {
-        schema: SchemaRef,
+        schema: DFSchemaRef,
     }
 
     impl Default for NoOpExtensionNode {
         fn default() -> Self {
             Self {
-                schema: SchemaRef::new(Schema::new(vec![Field::new(
-                    "a",
-                    DataType::Int32,
-                    false,
-                )])),
+                schema: DFSchemaRef::new(
+                    DFSchema::new(vec![DFField::new(None, "a", DataType::Int32, false)])
+                        .unwrap(),
+                ),
             }
         }
     }
@@ -757,7 +1086,7 @@ mod tests {
             vec![]
         }
 
-        fn schema(&self) -> &SchemaRef {
+        fn schema(&self) -> &DFSchemaRef {
             &self.schema
         }
 
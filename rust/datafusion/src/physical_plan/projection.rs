@@ -26,11 +26,13 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use crate::error::{DataFusionError, Result};
+use crate::physical_plan::expressions::{col, PhysicalSortExpr};
 use crate::physical_plan::{ExecutionPlan, Partitioning, PhysicalExpr};
 use arrow::datatypes::{Field, Schema, SchemaRef};
 use arrow::error::Result as ArrowResult;
 use arrow::record_batch::RecordBatch;
 
+use super::metrics::{BaselineMetrics, MetricsSet};
 use super::{RecordBatchStream, SendableRecordBatchStream};
 use async_trait::async_trait;
 
@@ -46,6 +48,8 @@ pub struct ProjectionExec {
     schema: SchemaRef,
     /// The input plan
     input: Arc<dyn ExecutionPlan>,
+    /// Execution metrics
+    metrics: BaselineMetrics,
 }
 
 impl ProjectionExec {
@@ -73,8 +77,19 @@ impl ProjectionExec {
             expr,
             schema,
             input: input.clone(),
+            metrics: BaselineMetrics::new(),
         })
     }
+
+    /// The projection expressions stored as tuples of (expression, output column name)
+    pub fn expr(&self) -> &[(Arc<dyn PhysicalExpr>, String)] {
+        &self.expr
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
 }
 
 #[async_trait]
@@ -98,6 +113,30 @@ impl ExecutionPlan for ProjectionExec {
         self.input.output_partitioning()
     }
 
+    /// The rows are sorted by the leading sort expressions of the input whose
+    /// expression is projected, as the columns of the output they are projected to
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        let mut ordering = vec![];
+        for sort_expr in self.input.output_ordering()? {
+            let projected = self
+                .expr
+                .iter()
+                .find(|(expr, _)| expr.to_string() == sort_expr.expr.to_string());
+            match projected {
+                Some((_, name)) => ordering.push(PhysicalSortExpr {
+                    expr: col(name),
+                    options: sort_expr.options,
+                }),
+                None => break,
+            }
+        }
+        if ordering.is_empty() {
+            None
+        } else {
+            Some(ordering)
+        }
+    }
+
     fn with_new_children(
         &self,
         children: Vec<Arc<dyn ExecutionPlan>>,
@@ -118,8 +157,13 @@ impl ExecutionPlan for ProjectionExec {
             schema: self.schema.clone(),
             expr: self.expr.iter().map(|x| x.0.clone()).collect(),
             input: self.input.execute(partition).await?,
+            metrics: self.metrics.clone(),
         }))
     }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
 }
 
 fn batch_project(
@@ -142,6 +186,7 @@ struct ProjectionStream {
     schema: SchemaRef,
     expr: Vec<Arc<dyn PhysicalExpr>>,
     input: SendableRecordBatchStream,
+    metrics: BaselineMetrics,
 }
 
 impl Stream for ProjectionStream {
@@ -151,10 +196,14 @@ impl Stream for ProjectionStream {
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        self.input.poll_next_unpin(cx).map(|x| match x {
-            Some(Ok(batch)) => Some(batch_project(&batch, &self.expr, &self.schema)),
+        let poll = self.input.poll_next_unpin(cx).map(|x| match x {
+            Some(Ok(batch)) => {
+                let _timer = self.metrics.elapsed_compute().timer();
+                Some(batch_project(&batch, &self.expr, &self.schema))
+            }
             other => other,
-        })
+        });
+        self.metrics.record_poll(poll)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
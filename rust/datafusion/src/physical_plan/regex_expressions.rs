@@ -0,0 +1,197 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Regular expression functions
+
+use std::any::type_name;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::{DataFusionError, Result};
+use arrow::array::{Array, ArrayRef, GenericStringArray, StringOffsetSizeTrait};
+use arrow::compute;
+use arrow::datatypes::DataType;
+use regex::Regex;
+
+fn downcast_string_arg<'a, T: StringOffsetSizeTrait>(
+    arg: &'a ArrayRef,
+    name: &str,
+) -> Result<&'a GenericStringArray<T>> {
+    arg.as_any()
+        .downcast_ref::<GenericStringArray<T>>()
+        .ok_or_else(|| {
+            DataFusionError::Internal(format!(
+                "could not cast {} to {}",
+                name,
+                type_name::<GenericStringArray<T>>()
+            ))
+        })
+}
+
+fn regexp_match_generic<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let values = downcast_string_arg::<T>(&args[0], "string")?;
+    let patterns = downcast_string_arg::<T>(&args[1], "pattern")?;
+    match args.len() {
+        2 => Ok(compute::regexp_match(values, patterns, None)?),
+        3 => {
+            let flags = downcast_string_arg::<T>(&args[2], "flags")?;
+            Ok(compute::regexp_match(values, patterns, Some(flags))?)
+        }
+        other => Err(DataFusionError::Internal(format!(
+            "regexp_match was called with {} arguments. It requires 2 or 3.",
+            other
+        ))),
+    }
+}
+
+/// returns the capture groups of the first match of a POSIX-style regular expression,
+/// as a list of strings, or null when the string does not match.
+/// regexp_match('foobarbequebaz', '(bar)(beque)') = ['bar', 'beque']
+pub fn regexp_match(args: &[ArrayRef]) -> Result<ArrayRef> {
+    match args[0].data_type() {
+        DataType::Utf8 => regexp_match_generic::<i32>(args),
+        DataType::LargeUtf8 => regexp_match_generic::<i64>(args),
+        other => Err(DataFusionError::Internal(format!(
+            "Unsupported data type {:?} for function regexp_match",
+            other
+        ))),
+    }
+}
+
+/// converts a PostgreSQL replacement string, where `\1` refers to the first
+/// capture group and `\&` to the whole match, to the syntax of the `regex` crate.
+fn regex_replacement(replacement: &str) -> String {
+    let mut result = String::with_capacity(replacement.len());
+    let mut chars = replacement.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.peek() {
+                Some(d) if d.is_ascii_digit() => {
+                    result.push_str(&format!("${{{}}}", d));
+                    chars.next();
+                }
+                Some('&') => {
+                    result.push_str("${0}");
+                    chars.next();
+                }
+                Some('\\') => {
+                    result.push('\\');
+                    chars.next();
+                }
+                _ => result.push('\\'),
+            },
+            '$' => result.push_str("$$"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// compiles `pattern` with `flags`, returning the regex and whether all matches
+/// should be replaced (the `g` flag).
+fn compile_replace_regex(pattern: &str, flags: Option<&str>) -> Result<(Regex, bool)> {
+    let flags = flags.unwrap_or("");
+    let global = flags.contains('g');
+    let inline_flags = flags.replace("g", "");
+    let pattern = if inline_flags.is_empty() {
+        pattern.to_string()
+    } else {
+        format!("(?{}){}", inline_flags, pattern)
+    };
+    let regex = Regex::new(&pattern).map_err(|e| {
+        DataFusionError::Execution(format!("Regular expression did not compile: {:?}", e))
+    })?;
+    Ok((regex, global))
+}
+
+fn regexp_replace_generic<T: StringOffsetSizeTrait>(
+    args: &[ArrayRef],
+) -> Result<ArrayRef> {
+    let values = downcast_string_arg::<T>(&args[0], "string")?;
+    let patterns = downcast_string_arg::<T>(&args[1], "pattern")?;
+    let replacements = downcast_string_arg::<T>(&args[2], "replacement")?;
+    let flags = match args.len() {
+        3 => None,
+        4 => Some(downcast_string_arg::<T>(&args[3], "flags")?),
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "regexp_replace was called with {} arguments. It requires 3 or 4.",
+                other
+            )))
+        }
+    };
+
+    // each distinct (pattern, flags) pair is only compiled once
+    let mut regexes: HashMap<(&str, Option<&str>), (Regex, bool)> = HashMap::new();
+
+    let result = (0..values.len())
+        .map(|i| {
+            let flag = match flags {
+                Some(flags) if flags.is_null(i) => return Ok(None),
+                Some(flags) => Some(flags.value(i)),
+                None => None,
+            };
+            if values.is_null(i) || patterns.is_null(i) || replacements.is_null(i) {
+                return Ok(None);
+            }
+            let value = values.value(i);
+            let pattern = patterns.value(i);
+            let (regex, global) = match regexes.entry((pattern, flag)) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    entry.insert(compile_replace_regex(pattern, flag)?)
+                }
+            };
+            let replacement = regex_replacement(replacements.value(i));
+            Ok(Some(if *global {
+                regex.replace_all(value, replacement.as_str()).into_owned()
+            } else {
+                regex.replace(value, replacement.as_str()).into_owned()
+            }))
+        })
+        .collect::<Result<GenericStringArray<T>>>()?;
+
+    Ok(Arc::new(result) as ArrayRef)
+}
+
+/// replaces the substrings that match a POSIX-style regular expression. Only the
+/// first match is replaced unless the `g` flag is given.
+/// regexp_replace('foobarbaz', 'b(..)', 'X\1Y', 'g') = 'fooXarYXazY'
+pub fn regexp_replace(args: &[ArrayRef]) -> Result<ArrayRef> {
+    match args[0].data_type() {
+        DataType::Utf8 => regexp_replace_generic::<i32>(args),
+        DataType::LargeUtf8 => regexp_replace_generic::<i64>(args),
+        other => Err(DataFusionError::Internal(format!(
+            "Unsupported data type {:?} for function regexp_replace",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replacement_syntax() {
+        assert_eq!(regex_replacement(r"X\1Y"), "X${1}Y");
+        assert_eq!(regex_replacement(r"[\&]"), "[${0}]");
+        assert_eq!(regex_replacement(r"a\\b"), r"a\b");
+        assert_eq!(regex_replacement("$1"), "$$1");
+    }
+}
@@ -0,0 +1,379 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! SampleExec keeps a random sample of the rows of its input, which is reproducible
+//! from its seed.
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::metrics::{BaselineMetrics, MetricsSet};
+use super::{RecordBatchStream, SendableRecordBatchStream};
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::SampleMethod;
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::{ExecutionPlan, Partitioning};
+use arrow::array::BooleanArray;
+use arrow::compute::filter;
+use arrow::datatypes::SchemaRef;
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+
+use async_trait::async_trait;
+
+use futures::stream::{Stream, StreamExt};
+
+/// Chooses the rows or blocks of rows of a sample of a partition. The choices only
+/// depend on the seed, the partition and the number of previous choices, so the same
+/// input is sampled the same way every time.
+#[derive(Debug, Clone)]
+pub(crate) struct Sampler {
+    /// The probability that each choice keeps its rows
+    fraction: f64,
+    /// The state of the SplitMix64 generator of the random choices
+    state: u64,
+}
+
+impl Sampler {
+    /// Create the sampler of the partition `partition` of a sample seeded with `seed`
+    pub(crate) fn new(fraction: f64, seed: u64, partition: usize) -> Self {
+        let mut sampler = Self {
+            fraction,
+            state: seed,
+        };
+        // start the partitions from different states, so that they are not sampled
+        // alike
+        sampler.state ^= (partition as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        sampler.next_u64();
+        sampler
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Whether the next row or block of rows is kept
+    pub(crate) fn keep(&mut self) -> bool {
+        // the 53 high bits give a uniform value in [0, 1)
+        let value = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        value < self.fraction
+    }
+}
+
+/// SampleExec keeps a random sample of the rows of its input: each row with the
+/// [SampleMethod::Bernoulli] method, or each whole batch with the
+/// [SampleMethod::System] method. Each partition is sampled independently, so the
+/// sample of an input is the same as long as its partitions and batches are.
+#[derive(Debug)]
+pub struct SampleExec {
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// How the rows are chosen
+    method: SampleMethod,
+    /// The expected fraction of the rows that are kept
+    fraction: f64,
+    /// The seed of the random choices
+    seed: u64,
+    /// Execution metrics
+    metrics: BaselineMetrics,
+}
+
+impl SampleExec {
+    /// Create a SampleExec that keeps the expected `fraction` of the rows of `input`,
+    /// which must be between 0 and 1
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        method: SampleMethod,
+        fraction: f64,
+        seed: u64,
+    ) -> Result<Self> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(DataFusionError::Plan(format!(
+                "The fraction of a sample must be between 0 and 1, but is {}",
+                fraction
+            )));
+        }
+        Ok(Self {
+            input,
+            method,
+            fraction,
+            seed,
+            metrics: BaselineMetrics::new(),
+        })
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// How the rows are chosen
+    pub fn method(&self) -> SampleMethod {
+        self.method
+    }
+
+    /// The expected fraction of the rows that are kept
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    /// The seed of the random choices
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for SampleExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        self.input.output_ordering()
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(SampleExec::try_new(
+                children[0].clone(),
+                self.method,
+                self.fraction,
+                self.seed,
+            )?)),
+            _ => Err(DataFusionError::Internal(
+                "SampleExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        Ok(Box::pin(SampleStream {
+            schema: self.input.schema(),
+            input: self.input.execute(partition).await?,
+            method: self.method,
+            sampler: Sampler::new(self.fraction, self.seed, partition),
+            metrics: self.metrics.clone(),
+        }))
+    }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
+}
+
+/// Samples the batches of a partition
+struct SampleStream {
+    schema: SchemaRef,
+    input: SendableRecordBatchStream,
+    method: SampleMethod,
+    sampler: Sampler,
+    metrics: BaselineMetrics,
+}
+
+/// Returns the sampled rows of `batch`, or None if the whole batch is skipped
+fn sample_batch(
+    batch: RecordBatch,
+    method: SampleMethod,
+    sampler: &mut Sampler,
+) -> Option<ArrowResult<RecordBatch>> {
+    match method {
+        SampleMethod::System => {
+            if sampler.keep() {
+                Some(Ok(batch))
+            } else {
+                None
+            }
+        }
+        SampleMethod::Bernoulli => {
+            let keep = BooleanArray::from(
+                (0..batch.num_rows())
+                    .map(|_| sampler.keep())
+                    .collect::<Vec<_>>(),
+            );
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|column| filter(column.as_ref(), &keep))
+                .collect::<ArrowResult<Vec<_>>>();
+            Some(
+                columns.and_then(|columns| RecordBatch::try_new(batch.schema(), columns)),
+            )
+        }
+    }
+}
+
+impl Stream for SampleStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        loop {
+            let poll = match self.input.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(batch))) => {
+                    let this = &mut *self;
+                    let _timer = this.metrics.elapsed_compute().timer();
+                    match sample_batch(batch, this.method, &mut this.sampler) {
+                        Some(result) => Poll::Ready(Some(result)),
+                        // skipped batches are not output
+                        None => continue,
+                    }
+                }
+                other => other,
+            };
+            return self.metrics.record_poll(poll);
+        }
+    }
+}
+
+impl RecordBatchStream for SampleStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::test;
+    use arrow::array::{Array, Int32Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    /// 2 partitions of 10 batches of 100 rows each, numbered from 0
+    fn numbers() -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int32, false)]));
+        let partitions = (0..2)
+            .map(|partition| {
+                (0..10)
+                    .map(|batch| {
+                        let start = (partition * 10 + batch) * 100;
+                        RecordBatch::try_new(
+                            schema.clone(),
+                            vec![Arc::new(Int32Array::from(
+                                (start..start + 100).collect::<Vec<_>>(),
+                            ))],
+                        )
+                    })
+                    .collect::<ArrowResult<Vec<_>>>()
+            })
+            .collect::<ArrowResult<Vec<_>>>()?;
+        Ok(Arc::new(MemoryExec::try_new(&partitions, schema, None)?))
+    }
+
+    async fn sample(
+        method: SampleMethod,
+        fraction: f64,
+        seed: u64,
+    ) -> Result<Vec<RecordBatch>> {
+        let sample = SampleExec::try_new(numbers()?, method, fraction, seed)?;
+        test::execute(Arc::new(sample)).await
+    }
+
+    fn sorted_values(batches: &[RecordBatch]) -> Vec<i32> {
+        let mut values = batches
+            .iter()
+            .flat_map(|batch| {
+                let array = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .expect("expected an Int32Array");
+                (0..array.len()).map(|i| array.value(i)).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        values
+    }
+
+    #[tokio::test]
+    async fn bernoulli_sample() -> Result<()> {
+        let values = sorted_values(&sample(SampleMethod::Bernoulli, 0.1, 42).await?);
+        assert!(
+            values.len() > 100 && values.len() < 300,
+            "sampled {} rows",
+            values.len()
+        );
+
+        // the same seed samples the same rows, and another seed other rows
+        let again = sorted_values(&sample(SampleMethod::Bernoulli, 0.1, 42).await?);
+        assert_eq!(values, again);
+        let other = sorted_values(&sample(SampleMethod::Bernoulli, 0.1, 43).await?);
+        assert_ne!(values, other);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn system_sample() -> Result<()> {
+        let batches = sample(SampleMethod::System, 0.5, 7).await?;
+        assert!(!batches.is_empty() && batches.len() < 20);
+        // whole batches are kept
+        for batch in &batches {
+            assert_eq!(batch.num_rows(), 100);
+        }
+        let values = sorted_values(&batches);
+        let again = sorted_values(&sample(SampleMethod::System, 0.5, 7).await?);
+        assert_eq!(values, again);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sample_everything_or_nothing() -> Result<()> {
+        for method in &[SampleMethod::Bernoulli, SampleMethod::System] {
+            let all = sorted_values(&sample(*method, 1.0, 1).await?);
+            assert_eq!(all, (0..2000).collect::<Vec<_>>());
+            let none = sorted_values(&sample(*method, 0.0, 1).await?);
+            assert!(none.is_empty());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_fraction() -> Result<()> {
+        let err = SampleExec::try_new(numbers()?, SampleMethod::Bernoulli, 1.5, 0)
+            .expect_err("expected an error");
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: The fraction of a sample must be between 0 and 1, but is 1.5"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,425 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution plan that generates a series of numbers or timestamps
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::datetime_expressions::shift_months_nanos;
+use super::expressions::{col, PhysicalSortExpr};
+use super::metrics::{BaselineMetrics, MetricsSet, ObservedStream};
+use super::sort::SortOptions;
+use super::{ExecutionPlan, Partitioning, RecordBatchStream, SendableRecordBatchStream};
+use crate::error::{DataFusionError, Result};
+use arrow::array::{ArrayRef, Int64Array, TimestampNanosecondArray};
+use arrow::datatypes::{DataType, SchemaRef, TimeUnit};
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+
+use async_trait::async_trait;
+use futures::Stream;
+
+/// The difference between two consecutive values of a series
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeriesStep {
+    /// A number of units of the values, e.g. nanoseconds for timestamps
+    Fixed(i64),
+    /// A number of calendar months, for timestamps
+    Months(i32),
+}
+
+impl SeriesStep {
+    fn is_ascending(&self) -> bool {
+        match self {
+            SeriesStep::Fixed(step) => *step > 0,
+            SeriesStep::Months(months) => *months > 0,
+        }
+    }
+}
+
+/// Execution plan that generates the values from `start` to `stop`, separated by
+/// `step`, into the single column of its schema, which is of type `Int64` or
+/// `Timestamp(Nanosecond, None)`
+#[derive(Debug)]
+pub struct SeriesExec {
+    /// The first value of the series
+    start: i64,
+    /// The bound of the values of the series
+    stop: i64,
+    /// The difference between two consecutive values
+    step: SeriesStep,
+    /// Whether `stop` is a value of the series, when the step reaches it
+    include_stop: bool,
+    /// The schema of the output, whose single column holds the values
+    schema: SchemaRef,
+    /// The maximum number of values of each output batch
+    batch_size: usize,
+    /// Execution metrics
+    metrics: BaselineMetrics,
+}
+
+impl SeriesExec {
+    /// Create a new execution plan that generates the values from `start` to `stop`,
+    /// separated by `step`, including `stop` if `include_stop` is true. Values
+    /// are timestamps if the single column of `schema` is a timestamp.
+    pub fn try_new(
+        start: i64,
+        stop: i64,
+        step: SeriesStep,
+        include_stop: bool,
+        schema: SchemaRef,
+        batch_size: usize,
+    ) -> Result<Self> {
+        if schema.fields().len() != 1 {
+            return Err(DataFusionError::Internal(format!(
+                "A series has a single column, but the schema has {} columns",
+                schema.fields().len()
+            )));
+        }
+        match (schema.field(0).data_type(), step) {
+            (_, SeriesStep::Fixed(0)) | (_, SeriesStep::Months(0)) => {
+                return Err(DataFusionError::Plan(
+                    "The step of a series cannot be zero".to_string(),
+                ))
+            }
+            (DataType::Int64, SeriesStep::Fixed(_))
+            | (DataType::Timestamp(TimeUnit::Nanosecond, None), _) => {}
+            (data_type, step) => {
+                return Err(DataFusionError::NotImplemented(format!(
+                    "Series of {:?} with a step of {:?} are not supported",
+                    data_type, step
+                )))
+            }
+        }
+        Ok(Self {
+            start,
+            stop,
+            step,
+            include_stop,
+            schema,
+            batch_size,
+            metrics: BaselineMetrics::new(),
+        })
+    }
+
+    /// The first value of the series
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// The bound of the values of the series
+    pub fn stop(&self) -> i64 {
+        self.stop
+    }
+
+    /// The difference between two consecutive values
+    pub fn step(&self) -> SeriesStep {
+        self.step
+    }
+
+    /// Whether `stop` is a value of the series, when the step reaches it
+    pub fn include_stop(&self) -> bool {
+        self.include_stop
+    }
+
+    /// The maximum number of values of each output batch
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for SeriesExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        Some(vec![PhysicalSortExpr {
+            expr: col(self.schema.field(0).name()),
+            options: SortOptions {
+                descending: !self.step.is_ascending(),
+                nulls_first: false,
+            },
+        }])
+    }
+
+    fn with_new_children(
+        &self,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Err(DataFusionError::Internal(format!(
+            "Children cannot be replaced in {:?}",
+            self
+        )))
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "SeriesExec invalid partition {}",
+                partition
+            )));
+        }
+        let stream = SeriesStream {
+            next: Some(self.start),
+            index: 0,
+            start: self.start,
+            stop: self.stop,
+            step: self.step,
+            include_stop: self.include_stop,
+            schema: self.schema.clone(),
+            batch_size: self.batch_size,
+        };
+        Ok(Box::pin(ObservedStream::new(
+            Box::pin(stream),
+            self.metrics.clone(),
+        )))
+    }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
+}
+
+/// Stream of the batches of values of a [SeriesExec]
+struct SeriesStream {
+    /// The next value of the series, if the series has not ended
+    next: Option<i64>,
+    /// The index of `next` in the series
+    index: i64,
+    start: i64,
+    stop: i64,
+    step: SeriesStep,
+    include_stop: bool,
+    schema: SchemaRef,
+    batch_size: usize,
+}
+
+impl SeriesStream {
+    /// Whether `value` is beyond the end of the series
+    fn is_past_stop(&self, value: i64) -> bool {
+        match (self.step.is_ascending(), self.include_stop) {
+            (true, true) => value > self.stop,
+            (true, false) => value >= self.stop,
+            (false, true) => value < self.stop,
+            (false, false) => value <= self.stop,
+        }
+    }
+
+    /// The value at `index` in the series, or `None` if it overflows. Values are
+    /// computed from the start, so that month steps do not accumulate the clamping
+    /// of days to the end of months.
+    fn value_at(&self, index: i64) -> Option<i64> {
+        match self.step {
+            SeriesStep::Fixed(step) => step.checked_mul(index)?.checked_add(self.start),
+            SeriesStep::Months(months) => {
+                let months = (months as i64).checked_mul(index)?;
+                if months > i32::MAX as i64 || months < i32::MIN as i64 {
+                    return None;
+                }
+                shift_months_nanos(self.start, months as i32)
+            }
+        }
+    }
+
+    fn next_batch(&mut self) -> ArrowResult<RecordBatch> {
+        let mut values = Vec::with_capacity(self.batch_size);
+        while values.len() < self.batch_size {
+            match self.next {
+                Some(value) if !self.is_past_stop(value) => {
+                    values.push(value);
+                    self.index += 1;
+                    self.next = self.value_at(self.index);
+                }
+                _ => {
+                    self.next = None;
+                    break;
+                }
+            }
+        }
+
+        let array: ArrayRef = match self.schema.field(0).data_type() {
+            DataType::Timestamp(_, _) => {
+                Arc::new(TimestampNanosecondArray::from_vec(values, None))
+            }
+            _ => Arc::new(Int64Array::from(values)),
+        };
+        RecordBatch::try_new(self.schema.clone(), vec![array])
+    }
+}
+
+impl Stream for SeriesStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        Poll::Ready(match self.next {
+            Some(value) if !self.is_past_stop(value) => Some(self.next_batch()),
+            _ => None,
+        })
+    }
+}
+
+impl RecordBatchStream for SeriesStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+    use arrow::array::Array;
+    use arrow::datatypes::{Field, Schema};
+
+    fn schema(data_type: DataType) -> SchemaRef {
+        Arc::new(Schema::new(vec![Field::new("value", data_type, false)]))
+    }
+
+    async fn values(exec: SeriesExec) -> Result<Vec<Vec<i64>>> {
+        let batches = test::execute(Arc::new(exec)).await?;
+        Ok(batches
+            .iter()
+            .map(|batch| {
+                let column = batch.column(0);
+                (0..column.len())
+                    .map(|i| match column.data_type() {
+                        DataType::Timestamp(_, _) => column
+                            .as_any()
+                            .downcast_ref::<TimestampNanosecondArray>()
+                            .unwrap()
+                            .value(i),
+                        _ => column
+                            .as_any()
+                            .downcast_ref::<Int64Array>()
+                            .unwrap()
+                            .value(i),
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn series_of_numbers() -> Result<()> {
+        let exec = |start, stop, step, include_stop| {
+            SeriesExec::try_new(
+                start,
+                stop,
+                SeriesStep::Fixed(step),
+                include_stop,
+                schema(DataType::Int64),
+                3,
+            )
+        };
+
+        assert_eq!(
+            values(exec(1, 7, 1, true)?).await?,
+            vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]]
+        );
+        assert_eq!(values(exec(1, 7, 2, false)?).await?, vec![vec![1, 3, 5]]);
+        assert_eq!(values(exec(1, 7, 3, false)?).await?, vec![vec![1, 4]]);
+        assert_eq!(values(exec(5, 1, -2, true)?).await?, vec![vec![5, 3, 1]]);
+        assert_eq!(values(exec(5, 1, 1, true)?).await?, Vec::<Vec<i64>>::new());
+        assert_eq!(
+            values(exec(i64::MAX - 1, i64::MAX, 5, true)?).await?,
+            vec![vec![i64::MAX - 1]]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn series_of_timestamps() -> Result<()> {
+        // 2021-01-31T00:00:00 and 2021-05-01T00:00:00
+        let start = 1_612_051_200_000_000_000;
+        let stop = 1_619_827_200_000_000_000;
+        let exec = SeriesExec::try_new(
+            start,
+            stop,
+            SeriesStep::Months(1),
+            true,
+            schema(DataType::Timestamp(TimeUnit::Nanosecond, None)),
+            10,
+        )?;
+
+        // 2021-01-31, 2021-02-28, 2021-03-31 and 2021-04-30
+        assert_eq!(
+            values(exec).await?,
+            vec![vec![
+                start,
+                1_614_470_400_000_000_000,
+                1_617_148_800_000_000_000,
+                1_619_740_800_000_000_000
+            ]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn zero_step() {
+        let err = SeriesExec::try_new(
+            1,
+            10,
+            SeriesStep::Fixed(0),
+            true,
+            schema(DataType::Int64),
+            10,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: The step of a series cannot be zero"
+        );
+    }
+
+    #[tokio::test]
+    async fn output_ordering() -> Result<()> {
+        let exec = SeriesExec::try_new(
+            5,
+            1,
+            SeriesStep::Fixed(-1),
+            true,
+            schema(DataType::Int64),
+            10,
+        )?;
+        let ordering = exec.output_ordering().unwrap();
+        assert_eq!(ordering.len(), 1);
+        assert!(ordering[0].options.descending);
+        assert_eq!(exec.children().len(), 0);
+        assert_eq!(values(exec).await?[0].len(), 5);
+        Ok(())
+    }
+}
@@ -25,9 +25,12 @@ pub use arrow::compute::SortOptions;
 use arrow::compute::{concat, lexsort_to_indices, take, SortColumn, TakeOptions};
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
+use futures::StreamExt;
 
+use super::metrics::{BaselineMetrics, MetricsSet};
 use super::SendableRecordBatchStream;
 use crate::error::{DataFusionError, Result};
+use crate::execution::memory_manager::{MemoryPool, MemoryReservation};
 use crate::physical_plan::common::SizedRecordBatchStream;
 use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::{common, Distribution, ExecutionPlan, Partitioning};
@@ -43,6 +46,10 @@ pub struct SortExec {
     expr: Vec<PhysicalSortExpr>,
     /// Number of threads to execute input partitions on before combining into a single partition
     concurrency: usize,
+    /// Execution metrics
+    metrics: BaselineMetrics,
+    /// The pool that the memory of the buffered input is reserved from
+    memory_pool: Arc<MemoryPool>,
 }
 
 impl SortExec {
@@ -56,8 +63,37 @@ impl SortExec {
             expr,
             input,
             concurrency,
+            metrics: BaselineMetrics::new(),
+            memory_pool: Arc::new(MemoryPool::default()),
         })
     }
+
+    /// Reserve the memory of the buffered input from `memory_pool`, rather than from a
+    /// pool without a limit
+    pub fn with_memory_pool(mut self, memory_pool: Arc<MemoryPool>) -> Self {
+        self.memory_pool = memory_pool;
+        self
+    }
+
+    /// Input schema
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Sort expressions
+    pub fn expr(&self) -> &[PhysicalSortExpr] {
+        &self.expr
+    }
+
+    /// Number of threads to execute input partitions on
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// The pool that the memory of the buffered input is reserved from
+    pub fn memory_pool(&self) -> &Arc<MemoryPool> {
+        &self.memory_pool
+    }
 }
 
 #[async_trait]
@@ -84,16 +120,23 @@ impl ExecutionPlan for SortExec {
         Distribution::SinglePartition
     }
 
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        Some(self.expr.clone())
+    }
+
     fn with_new_children(
         &self,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         match children.len() {
-            1 => Ok(Arc::new(SortExec::try_new(
-                self.expr.clone(),
-                children[0].clone(),
-                self.concurrency,
-            )?)),
+            1 => Ok(Arc::new(
+                SortExec::try_new(
+                    self.expr.clone(),
+                    children[0].clone(),
+                    self.concurrency,
+                )?
+                .with_memory_pool(self.memory_pool.clone()),
+            )),
             _ => Err(DataFusionError::Internal(
                 "SortExec wrong number of children".to_string(),
             )),
@@ -114,8 +157,19 @@ impl ExecutionPlan for SortExec {
                 "SortExec requires a single input partition".to_owned(),
             ));
         }
-        let it = self.input.execute(0).await?;
-        let batches = common::collect(it).await?;
+        // the input is buffered, as well as combined into a single batch, so twice
+        // its size is reserved
+        let mut reservation =
+            MemoryReservation::new("SortExec", self.memory_pool.clone());
+        let mut input = self.input.execute(0).await?;
+        let mut batches = vec![];
+        while let Some(batch) = input.next().await {
+            let batch = batch?;
+            reservation.try_grow(2 * common::batch_memory_size(&batch))?;
+            batches.push(batch);
+        }
+
+        let _timer = self.metrics.elapsed_compute().timer();
 
         // combine all record batches into one for each column
         let combined_batch = RecordBatch::try_new(
@@ -163,12 +217,17 @@ impl ExecutionPlan for SortExec {
                 })
                 .collect::<Result<Vec<ArrayRef>>>()?,
         )?;
+        self.metrics.record_output(&sorted_batch);
 
         Ok(Box::pin(SizedRecordBatchStream::new(
             self.schema(),
             vec![Arc::new(sorted_batch)],
         )))
     }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
 }
 
 #[cfg(test)]
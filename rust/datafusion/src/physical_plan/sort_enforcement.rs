@@ -0,0 +1,240 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Sort enforcement, a pass of the physical planner that sorts the inputs of the
+//! operators that [require them to be sorted](ExecutionPlan::required_input_ordering)
+//! and removes the sorts whose input is already sorted
+
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::execution::context::ExecutionContextState;
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::merge::MergeExec;
+use crate::physical_plan::sort::SortExec;
+use crate::physical_plan::sort_preserving_merge::SortPreservingMergeExec;
+use crate::physical_plan::{common, ordering_satisfies, ExecutionPlan};
+
+/// Rewrites `plan`, whose children must already satisfy the
+/// [required distribution](ExecutionPlan::required_child_distribution) of their
+/// parent, so that:
+///
+/// * a [SortExec] whose input is a single partition sorted by its sort expressions
+///   is removed
+/// * a [SortExec] whose input merges partitions that are each sorted by its sort
+///   expressions is replaced by a [SortPreservingMergeExec] of those partitions
+/// * the children whose output does not satisfy the required input ordering of
+///   their parent are sorted, in the same way
+pub fn enforce_sorting(
+    plan: Arc<dyn ExecutionPlan>,
+    ctx_state: &ExecutionContextState,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let children = plan
+        .children()
+        .into_iter()
+        .map(|child| enforce_sorting(child, ctx_state))
+        .collect::<Result<Vec<_>>>()?;
+    if children.is_empty() {
+        return Ok(plan);
+    }
+
+    if let Some(sort) = plan.as_any().downcast_ref::<SortExec>() {
+        return match sorted_without_sort(&children[0], sort.expr(), ctx_state)? {
+            Some(sorted) => Ok(sorted),
+            None => plan.with_new_children(children),
+        };
+    }
+
+    let children = children
+        .into_iter()
+        .zip(plan.required_input_ordering())
+        .map(|(child, required)| match required {
+            Some(required)
+                if !ordering_satisfies(child.output_ordering().as_deref(), &required) =>
+            {
+                sort(child, required, ctx_state)
+            }
+            _ => Ok(child),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    plan.with_new_children(children)
+}
+
+/// Sorts the rows of `input` by `expr` into a single partition
+fn sort(
+    input: Arc<dyn ExecutionPlan>,
+    expr: Vec<PhysicalSortExpr>,
+    ctx_state: &ExecutionContextState,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if let Some(sorted) = sorted_without_sort(&input, &expr, ctx_state)? {
+        return Ok(sorted);
+    }
+    let input = if input.output_partitioning().partition_count() == 1 {
+        input
+    } else {
+        Arc::new(MergeExec::new(input))
+    };
+    Ok(Arc::new(
+        SortExec::try_new(expr, input, ctx_state.config.concurrency)?
+            .with_memory_pool(ctx_state.memory_pool.clone()),
+    ))
+}
+
+/// The rows of `input` sorted by `expr` in a single partition, without a [SortExec],
+/// if the partitions of `input`, or of the input of the [MergeExec] that it is, are
+/// sorted by `expr`
+fn sorted_without_sort(
+    input: &Arc<dyn ExecutionPlan>,
+    expr: &[PhysicalSortExpr],
+    ctx_state: &ExecutionContextState,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    if input.output_partitioning().partition_count() == 1
+        && ordering_satisfies(input.output_ordering().as_deref(), expr)
+    {
+        return Ok(Some(input.clone()));
+    }
+
+    let partitions = match input.as_any().downcast_ref::<MergeExec>() {
+        Some(merge) => merge.input().clone(),
+        None => input.clone(),
+    };
+    let schema = partitions.schema();
+    let comparable = expr
+        .iter()
+        .map(|sort_expr| sort_expr.expr.data_type(&schema))
+        .collect::<Result<Vec<_>>>()?
+        .iter()
+        .all(common::is_comparable_type);
+    if comparable && ordering_satisfies(partitions.output_ordering().as_deref(), expr) {
+        Ok(Some(Arc::new(SortPreservingMergeExec::try_new(
+            expr.to_vec(),
+            partitions,
+            ctx_state.config.batch_size,
+        )?)))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::context::ExecutionContext;
+    use crate::logical_plan::{JoinConstraint, JoinType};
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::sort::SortOptions;
+    use crate::physical_plan::sort_merge_join::SortMergeJoinExec;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    fn sort_expr(name: &str) -> PhysicalSortExpr {
+        PhysicalSortExpr {
+            expr: col(name),
+            options: SortOptions::default(),
+        }
+    }
+
+    /// An input of the columns `columns` with `partitions` empty partitions, which
+    /// are sorted on the first column if `sorted` is true
+    fn input(
+        columns: &[&str],
+        partitions: usize,
+        sorted: bool,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(
+            columns
+                .iter()
+                .map(|name| Field::new(name, DataType::Int32, true))
+                .collect(),
+        ));
+        let exec = MemoryExec::try_new(&vec![vec![]; partitions], schema, None)?;
+        Ok(Arc::new(if sorted {
+            exec.with_output_ordering(vec![sort_expr(columns[0])])
+        } else {
+            exec
+        }))
+    }
+
+    #[test]
+    fn removes_sort_of_sorted_partition() -> Result<()> {
+        let ctx = ExecutionContext::new();
+        let sort = Arc::new(SortExec::try_new(
+            vec![sort_expr("a")],
+            input(&["a", "b"], 1, true)?,
+            1,
+        )?);
+
+        let plan = enforce_sorting(sort, &ctx.state)?;
+        assert!(plan.as_any().downcast_ref::<MemoryExec>().is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_sort_of_unsorted_partition() -> Result<()> {
+        let ctx = ExecutionContext::new();
+        let sort = Arc::new(SortExec::try_new(
+            vec![sort_expr("b")],
+            input(&["a", "b"], 1, true)?,
+            1,
+        )?);
+
+        let plan = enforce_sorting(sort, &ctx.state)?;
+        assert!(plan.as_any().downcast_ref::<SortExec>().is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn merges_sorted_partitions() -> Result<()> {
+        let ctx = ExecutionContext::new();
+        let sort = Arc::new(SortExec::try_new(
+            vec![sort_expr("a")],
+            Arc::new(MergeExec::new(input(&["a", "b"], 3, true)?)),
+            1,
+        )?);
+
+        let plan = enforce_sorting(sort, &ctx.state)?;
+        let merge = plan
+            .as_any()
+            .downcast_ref::<SortPreservingMergeExec>()
+            .unwrap();
+        assert_eq!(merge.expr(), &[sort_expr("a")][..]);
+        assert_eq!(merge.input().output_partitioning().partition_count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn sorts_inputs_of_join() -> Result<()> {
+        let ctx = ExecutionContext::new();
+        let join = Arc::new(SortMergeJoinExec::try_new(
+            Arc::new(MergeExec::new(input(&["a", "b"], 2, true)?)),
+            input(&["a", "c"], 1, false)?,
+            &vec![("a".to_string(), "a".to_string())],
+            &JoinType::Inner,
+            &JoinConstraint::Using,
+            1024,
+        )?);
+
+        let plan = enforce_sorting(join, &ctx.state)?;
+        let children = plan.children();
+        assert!(children[0]
+            .as_any()
+            .downcast_ref::<SortPreservingMergeExec>()
+            .is_some());
+        assert!(children[1].as_any().downcast_ref::<SortExec>().is_some());
+        Ok(())
+    }
+}
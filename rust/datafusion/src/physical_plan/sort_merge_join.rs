@@ -0,0 +1,1170 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the sort-merge join plan, which joins two inputs that are sorted on their
+//! join keys by merging them. Only the right rows that share the key of the current
+//! left row are buffered, so the memory that the join holds is bounded by the largest
+//! group of rows with the same key, rather than by the size of an input.
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow::array::{ArrayRef, UInt32Array};
+use arrow::compute::{concat, take, SortOptions};
+use arrow::datatypes::{Field, Schema, SchemaRef};
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+
+use super::metrics::{BaselineMetrics, MetricsSet};
+use super::{RecordBatchStream, SendableRecordBatchStream};
+use crate::error::{DataFusionError, Result};
+use crate::execution::memory_manager::{MemoryPool, MemoryReservation};
+use crate::logical_plan::{JoinConstraint, JoinType};
+use crate::physical_plan::expressions::{col, PhysicalSortExpr};
+use crate::physical_plan::{common, Distribution, ExecutionPlan, Partitioning};
+use crate::scalar::ScalarValue;
+
+/// The pairs of columns, of the left and of the right input, that are equal in the
+/// rows that a join matches
+pub type JoinOn = Vec<(String, String)>;
+
+/// Join execution plan that merges two inputs sorted on their join keys.
+///
+/// Both inputs must be sorted in ascending order on their join keys, in the order of
+/// `on`, with nulls first, which the physical planner ensures as they are the
+/// [required input ordering](ExecutionPlan::required_input_ordering) of the join.
+/// Rows whose keys contain a null never match.
+///
+/// The output contains the columns of the left input, followed by those of the right
+/// input, except for semi and anti joins, which only produce the columns of the left
+/// input. With [JoinConstraint::Using], a right join key that has the same name as its
+/// left join key is merged into it: the merged column holds the key of the left row,
+/// or of the right row when there is no left row.
+#[derive(Debug)]
+pub struct SortMergeJoinExec {
+    /// The left input
+    left: Arc<dyn ExecutionPlan>,
+    /// The right input
+    right: Arc<dyn ExecutionPlan>,
+    /// The pairs of columns that the inputs are joined on
+    on: JoinOn,
+    /// The type of the join
+    join_type: JoinType,
+    /// Whether the join keys with the same name are merged
+    join_constraint: JoinConstraint,
+    /// The columns of the output
+    columns: JoinColumns,
+    /// The schema of the output
+    schema: SchemaRef,
+    /// The maximum number of rows of the output batches
+    batch_size: usize,
+    /// Execution metrics
+    metrics: BaselineMetrics,
+    /// The pool that the memory of the buffered right rows is reserved from
+    memory_pool: Arc<MemoryPool>,
+}
+
+/// How the columns of the output of a join are made from those of its inputs
+#[derive(Debug, Clone)]
+struct JoinColumns {
+    /// The indices of the join keys in the left input
+    left_on: Vec<usize>,
+    /// The indices of the join keys in the right input
+    right_on: Vec<usize>,
+    /// For each column of the left input, the index of the right join key that is
+    /// merged into it, if any
+    merged: Vec<Option<usize>>,
+    /// The indices of the columns of the right input that are in the output
+    right_columns: Vec<usize>,
+}
+
+impl SortMergeJoinExec {
+    /// Create a new sort-merge join of `left` and `right` on the columns `on`
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: &JoinOn,
+        join_type: &JoinType,
+        join_constraint: &JoinConstraint,
+        batch_size: usize,
+    ) -> Result<Self> {
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        if on.is_empty() {
+            return Err(DataFusionError::Plan(
+                "A sort-merge join requires at least one pair of join columns"
+                    .to_string(),
+            ));
+        }
+
+        let mut left_on = vec![];
+        let mut right_on = vec![];
+        for (l, r) in on {
+            let l = left_schema.index_of(l)?;
+            let r = right_schema.index_of(r)?;
+            let (l_type, r_type) = (
+                left_schema.field(l).data_type(),
+                right_schema.field(r).data_type(),
+            );
+            if l_type != r_type {
+                return Err(DataFusionError::Plan(format!(
+                    "Join columns {} and {} have different types {:?} and {:?}",
+                    on[left_on.len()].0,
+                    on[left_on.len()].1,
+                    l_type,
+                    r_type
+                )));
+            }
+            if !common::is_comparable_type(l_type) {
+                return Err(DataFusionError::NotImplemented(format!(
+                    "Sort-merge join on columns of type {:?} is not supported",
+                    l_type
+                )));
+            }
+            left_on.push(l);
+            right_on.push(r);
+        }
+
+        // semi and anti joins only produce the columns of the left input
+        let with_right = !matches!(join_type, JoinType::Semi | JoinType::Anti);
+        let mut merged = vec![None; left_schema.fields().len()];
+        if with_right && *join_constraint == JoinConstraint::Using {
+            for (l, r) in left_on.iter().zip(&right_on) {
+                if left_schema.field(*l).name() == right_schema.field(*r).name() {
+                    merged[*l] = Some(*r);
+                }
+            }
+        }
+        let right_columns = if with_right {
+            (0..right_schema.fields().len())
+                .filter(|i| !merged.contains(&Some(*i)))
+                .collect()
+        } else {
+            vec![]
+        };
+        let columns = JoinColumns {
+            left_on,
+            right_on,
+            merged,
+            right_columns,
+        };
+        let schema = Arc::new(columns.schema(&left_schema, &right_schema, join_type)?);
+
+        Ok(Self {
+            left,
+            right,
+            on: on.clone(),
+            join_type: *join_type,
+            join_constraint: *join_constraint,
+            columns,
+            schema,
+            batch_size,
+            metrics: BaselineMetrics::new(),
+            memory_pool: Arc::new(MemoryPool::default()),
+        })
+    }
+
+    /// Reserve the memory of the buffered right rows from `memory_pool`, rather than
+    /// from a pool without a limit
+    pub fn with_memory_pool(mut self, memory_pool: Arc<MemoryPool>) -> Self {
+        self.memory_pool = memory_pool;
+        self
+    }
+
+    /// The left input
+    pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.left
+    }
+
+    /// The right input
+    pub fn right(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.right
+    }
+
+    /// The pairs of columns that the inputs are joined on
+    pub fn on(&self) -> &JoinOn {
+        &self.on
+    }
+
+    /// The type of the join
+    pub fn join_type(&self) -> &JoinType {
+        &self.join_type
+    }
+
+    /// Whether the join keys with the same name are merged
+    pub fn join_constraint(&self) -> &JoinConstraint {
+        &self.join_constraint
+    }
+
+    /// The maximum number of rows of the output batches
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// The pool that the memory of the buffered right rows is reserved from
+    pub fn memory_pool(&self) -> &Arc<MemoryPool> {
+        &self.memory_pool
+    }
+}
+
+impl JoinColumns {
+    /// The schema of the output of the join. The columns of a side of the join that
+    /// may have no matching row become nullable.
+    fn schema(
+        &self,
+        left: &Schema,
+        right: &Schema,
+        join_type: &JoinType,
+    ) -> Result<Schema> {
+        let left_nullable = matches!(join_type, JoinType::Right | JoinType::Full);
+        let right_nullable = matches!(join_type, JoinType::Left | JoinType::Full);
+
+        let mut fields: Vec<Field> = left
+            .fields()
+            .iter()
+            .zip(&self.merged)
+            .map(|(field, merged)| {
+                let nullable = match merged {
+                    Some(r) => match join_type {
+                        JoinType::Right => right.field(*r).is_nullable(),
+                        JoinType::Full => {
+                            field.is_nullable() || right.field(*r).is_nullable()
+                        }
+                        _ => field.is_nullable(),
+                    },
+                    None => field.is_nullable() || left_nullable,
+                };
+                Field::new(field.name(), field.data_type().clone(), nullable)
+            })
+            .collect();
+        fields.extend(self.right_columns.iter().map(|i| {
+            let field = right.field(*i);
+            Field::new(
+                field.name(),
+                field.data_type().clone(),
+                field.is_nullable() || right_nullable,
+            )
+        }));
+
+        for (i, field) in fields.iter().enumerate() {
+            if fields[..i].iter().any(|f| f.name() == field.name()) {
+                return Err(DataFusionError::Plan(format!(
+                    "Joins whose inputs both have a column named '{}' are not \
+                     supported, unless they are merged keys of a USING join",
+                    field.name()
+                )));
+            }
+        }
+        Ok(Schema::new(fields))
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for SortMergeJoinExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::SinglePartition
+    }
+
+    /// The rows are sorted on the left join keys, unless the join produces right rows
+    /// without a left row
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        match self.join_type {
+            JoinType::Inner | JoinType::Left | JoinType::Semi | JoinType::Anti => {
+                Some(sort_on_keys(self.on.iter().map(|(left, _)| left)))
+            }
+            JoinType::Right | JoinType::Full => None,
+        }
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortExpr>>> {
+        vec![
+            Some(sort_on_keys(self.on.iter().map(|(left, _)| left))),
+            Some(sort_on_keys(self.on.iter().map(|(_, right)| right))),
+        ]
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            2 => Ok(Arc::new(
+                SortMergeJoinExec::try_new(
+                    children[0].clone(),
+                    children[1].clone(),
+                    &self.on,
+                    &self.join_type,
+                    &self.join_constraint,
+                    self.batch_size,
+                )?
+                .with_memory_pool(self.memory_pool.clone()),
+            )),
+            _ => Err(DataFusionError::Internal(
+                "SortMergeJoinExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        if 0 != partition {
+            return Err(DataFusionError::Internal(format!(
+                "SortMergeJoinExec invalid partition {}",
+                partition
+            )));
+        }
+        let left = self.left.execute(0).await?;
+        let right = self.right.execute(0).await?;
+
+        let null_row = |plan: &Arc<dyn ExecutionPlan>| -> Result<Vec<ArrayRef>> {
+            plan.schema()
+                .fields()
+                .iter()
+                .map(|field| Ok(ScalarValue::try_from(field.data_type())?.to_array()))
+                .collect()
+        };
+        let left_nulls = match self.join_type {
+            JoinType::Right | JoinType::Full => Some(null_row(&self.left)?),
+            _ => None,
+        };
+        let right_nulls = match self.join_type {
+            JoinType::Left | JoinType::Full => Some(null_row(&self.right)?),
+            _ => None,
+        };
+
+        let joiner = SortMergeJoiner {
+            schema: self.schema.clone(),
+            join_type: self.join_type,
+            batch_size: self.batch_size,
+            left: JoinSide::new(left, self.columns.left_on.clone()),
+            right: JoinSide::new(right, self.columns.right_on.clone()),
+            run: None,
+            output: JoinOutput {
+                columns: self.columns.clone(),
+                left_nulls,
+                right_nulls,
+                left_batches: vec![],
+                right_batches: vec![],
+                rows: vec![],
+            },
+            reservation: MemoryReservation::new(
+                "SortMergeJoinExec",
+                self.memory_pool.clone(),
+            ),
+            metrics: self.metrics.clone(),
+            finished: false,
+        };
+
+        let batches = stream::unfold(joiner, |mut joiner| async move {
+            if joiner.finished {
+                return None;
+            }
+            match joiner.next_batch().await {
+                Ok(Some(batch)) => Some((Ok(batch), joiner)),
+                Ok(None) => None,
+                Err(e) => {
+                    joiner.finished = true;
+                    Some((Err(e.into_arrow_external_error()), joiner))
+                }
+            }
+        });
+
+        Ok(Box::pin(SortMergeJoinStream {
+            schema: self.schema.clone(),
+            batches: batches.boxed(),
+            metrics: self.metrics.clone(),
+        }))
+    }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
+}
+
+/// Sorts on the columns `keys` in ascending order with nulls first
+fn sort_on_keys<'a>(keys: impl Iterator<Item = &'a String>) -> Vec<PhysicalSortExpr> {
+    keys.map(|key| PhysicalSortExpr {
+        expr: col(key),
+        options: SortOptions {
+            descending: false,
+            nulls_first: true,
+        },
+    })
+    .collect()
+}
+
+/// A batch of an input of the join
+#[derive(Clone)]
+struct SideBatch {
+    /// Identifies the batch among those of its input
+    id: usize,
+    batch: RecordBatch,
+    /// The columns of the join keys
+    keys: Vec<ArrayRef>,
+}
+
+/// An input of the join, and the position of its current row
+struct JoinSide {
+    stream: SendableRecordBatchStream,
+    /// The indices of the join keys
+    on: Vec<usize>,
+    /// The batch of the current row
+    batch: Option<SideBatch>,
+    /// The index of the current row in `batch`
+    row: usize,
+    next_id: usize,
+    finished: bool,
+}
+
+impl JoinSide {
+    fn new(stream: SendableRecordBatchStream, on: Vec<usize>) -> Self {
+        Self {
+            stream,
+            on,
+            batch: None,
+            row: 0,
+            next_id: 0,
+            finished: false,
+        }
+    }
+
+    /// Fetch the next batches of the input until the current row is valid. Returns
+    /// false once the input is exhausted.
+    async fn fill(&mut self) -> Result<bool> {
+        loop {
+            if let Some(batch) = &self.batch {
+                if self.row < batch.batch.num_rows() {
+                    return Ok(true);
+                }
+            }
+            if self.finished {
+                return Ok(false);
+            }
+            match self.stream.next().await {
+                Some(batch) => {
+                    let batch = batch?;
+                    let keys = self.on.iter().map(|i| batch.column(*i).clone()).collect();
+                    self.batch = Some(SideBatch {
+                        id: self.next_id,
+                        batch,
+                        keys,
+                    });
+                    self.next_id += 1;
+                    self.row = 0;
+                }
+                None => {
+                    self.finished = true;
+                    self.batch = None;
+                }
+            }
+        }
+    }
+
+    /// The batch of the current row, which must be valid
+    fn current(&self) -> &SideBatch {
+        self.batch.as_ref().unwrap()
+    }
+}
+
+/// The right rows that share the key of the current left rows
+struct Run {
+    /// The first row of the run, whose key is the key of the run
+    key: (SideBatch, usize),
+    /// The rows of the run, as ranges of rows of batches
+    ranges: Vec<(SideBatch, usize, usize)>,
+    /// The position in `ranges` of the next right row to join with the current left
+    /// row, once the current left row is known to match
+    next: Option<(usize, usize)>,
+}
+
+/// The rows of the output of the join that are not produced yet, as references to
+/// the rows of the input batches
+struct JoinOutput {
+    columns: JoinColumns,
+    /// A row of nulls for each column of the left input, if the left row of an output
+    /// row may be missing
+    left_nulls: Option<Vec<ArrayRef>>,
+    /// A row of nulls for each column of the right input, if the right row of an
+    /// output row may be missing
+    right_nulls: Option<Vec<ArrayRef>>,
+    left_batches: Vec<SideBatch>,
+    right_batches: Vec<SideBatch>,
+    /// The left and right rows of each output row, as the index of their batch in
+    /// `left_batches` or `right_batches` and their index in that batch
+    rows: Vec<(Option<(usize, usize)>, Option<(usize, usize)>)>,
+}
+
+impl JoinOutput {
+    fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn push(
+        &mut self,
+        left: Option<(&SideBatch, usize)>,
+        right: Option<(&SideBatch, usize)>,
+    ) {
+        let left = left.map(|(batch, row)| (slot(&mut self.left_batches, batch), row));
+        let right = right.map(|(batch, row)| (slot(&mut self.right_batches, batch), row));
+        self.rows.push((left, right));
+    }
+
+    /// Build a batch of the rows, and clear them
+    fn build(&mut self, schema: &SchemaRef) -> Result<RecordBatch> {
+        let left_offsets = offsets(&self.left_batches, self.left_nulls.is_some());
+        let right_offsets = offsets(&self.right_batches, self.right_nulls.is_some());
+        let left_indices: Vec<Option<usize>> = self
+            .rows
+            .iter()
+            .map(|(left, _)| left.map(|(slot, row)| left_offsets[slot] + row))
+            .collect();
+        let right_indices: Vec<Option<usize>> = self
+            .rows
+            .iter()
+            .map(|(_, right)| right.map(|(slot, row)| right_offsets[slot] + row))
+            .collect();
+
+        let mut columns = vec![];
+        for (i, merged) in self.columns.merged.iter().enumerate() {
+            let left = concat_column(&self.left_batches, self.left_nulls.as_ref(), i)?;
+            columns.push(match merged {
+                Some(r) => {
+                    // the key of the right row, where there is no left row
+                    let right = concat_column(
+                        &self.right_batches,
+                        self.right_nulls.as_ref(),
+                        *r,
+                    )?;
+                    let indices = left_indices
+                        .iter()
+                        .zip(&right_indices)
+                        .map(|(l, r)| match (l, r) {
+                            (Some(l), _) => *l,
+                            (None, Some(r)) => left.len() + r,
+                            (None, None) => 0,
+                        })
+                        .collect::<Vec<_>>();
+                    take_rows(&concat(&[left, right])?, &indices)?
+                }
+                None => take_rows(&left, &nulls_first(&left_indices))?,
+            });
+        }
+        for i in &self.columns.right_columns {
+            let right =
+                concat_column(&self.right_batches, self.right_nulls.as_ref(), *i)?;
+            columns.push(take_rows(&right, &nulls_first(&right_indices))?);
+        }
+
+        self.rows.clear();
+        self.left_batches.clear();
+        self.right_batches.clear();
+        Ok(RecordBatch::try_new(schema.clone(), columns)?)
+    }
+}
+
+/// The index of `batch` in `batches`, to which it is added if it is not there yet
+fn slot(batches: &mut Vec<SideBatch>, batch: &SideBatch) -> usize {
+    match batches.iter().position(|b| b.id == batch.id) {
+        Some(slot) => slot,
+        None => {
+            batches.push(batch.clone());
+            batches.len() - 1
+        }
+    }
+}
+
+/// The offset of each batch in the concatenation of their columns, which starts with
+/// a row of nulls if `with_nulls` is true
+fn offsets(batches: &[SideBatch], with_nulls: bool) -> Vec<usize> {
+    let mut offset = if with_nulls { 1 } else { 0 };
+    batches
+        .iter()
+        .map(|batch| {
+            let start = offset;
+            offset += batch.batch.num_rows();
+            start
+        })
+        .collect()
+}
+
+/// The concatenation of the column `i` of `nulls`, if any, and of `batches`
+fn concat_column(
+    batches: &[SideBatch],
+    nulls: Option<&Vec<ArrayRef>>,
+    i: usize,
+) -> Result<ArrayRef> {
+    let arrays: Vec<ArrayRef> = nulls
+        .map(|nulls| nulls[i].clone())
+        .into_iter()
+        .chain(batches.iter().map(|batch| batch.batch.column(i).clone()))
+        .collect();
+    Ok(concat(&arrays)?)
+}
+
+/// Indices where missing rows refer to the row of nulls at the start of the columns
+fn nulls_first(indices: &[Option<usize>]) -> Vec<usize> {
+    indices.iter().map(|i| i.unwrap_or(0)).collect()
+}
+
+fn take_rows(array: &ArrayRef, indices: &[usize]) -> Result<ArrayRef> {
+    let indices =
+        UInt32Array::from(indices.iter().map(|i| *i as u32).collect::<Vec<_>>());
+    Ok(take(array, &indices, None)?)
+}
+
+/// The state of the merge of the inputs of a join
+struct SortMergeJoiner {
+    schema: SchemaRef,
+    join_type: JoinType,
+    batch_size: usize,
+    left: JoinSide,
+    right: JoinSide,
+    /// The right rows that share the key of the current left row, if it matched
+    run: Option<Run>,
+    output: JoinOutput,
+    /// The reservation of the memory of the batches of `run`
+    reservation: MemoryReservation,
+    metrics: BaselineMetrics,
+    finished: bool,
+}
+
+impl SortMergeJoiner {
+    /// Produce the next batch of the output, or None once the join is complete
+    async fn next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        loop {
+            if self.output.len() >= self.batch_size {
+                return self.build_output().map(Some);
+            }
+            if self.run.is_some() {
+                self.join_run().await?;
+                continue;
+            }
+
+            let has_left = self.left.fill().await?;
+            let has_right = self.right.fill().await?;
+            let elapsed_compute = self.metrics.elapsed_compute().clone();
+            let timer = elapsed_compute.timer();
+            match (has_left, has_right) {
+                (true, true) => {
+                    let (left, right) = (self.left.current(), self.right.current());
+                    let ordering = compare_keys(
+                        &left.keys,
+                        self.left.row,
+                        &right.keys,
+                        self.right.row,
+                    )?;
+                    match ordering {
+                        // rows whose keys contain a null never match
+                        Ordering::Less => self.left_unmatched(),
+                        Ordering::Equal if has_null(&left.keys, self.left.row) => {
+                            self.left_unmatched()
+                        }
+                        Ordering::Greater => self.right_unmatched(),
+                        Ordering::Equal => {
+                            drop(timer);
+                            self.start_run().await?;
+                        }
+                    }
+                }
+                (true, false) if self.emits_unmatched_left() => self.left_unmatched(),
+                (false, true) if self.emits_unmatched_right() => self.right_unmatched(),
+                // the remaining rows of the other input, if any, produce no output
+                _ => {
+                    self.finished = true;
+                    return if self.output.len() > 0 {
+                        self.build_output().map(Some)
+                    } else {
+                        Ok(None)
+                    };
+                }
+            }
+        }
+    }
+
+    fn emits_unmatched_left(&self) -> bool {
+        matches!(
+            self.join_type,
+            JoinType::Left | JoinType::Full | JoinType::Anti
+        )
+    }
+
+    fn emits_unmatched_right(&self) -> bool {
+        matches!(self.join_type, JoinType::Right | JoinType::Full)
+    }
+
+    /// Produce the current left row, which has no match, and move to the next one
+    fn left_unmatched(&mut self) {
+        if self.emits_unmatched_left() {
+            self.output
+                .push(Some((self.left.current(), self.left.row)), None);
+        }
+        self.left.row += 1;
+    }
+
+    /// Produce the current right row, which has no match, and move to the next one
+    fn right_unmatched(&mut self) {
+        if self.emits_unmatched_right() {
+            self.output
+                .push(None, Some((self.right.current(), self.right.row)));
+        }
+        self.right.row += 1;
+    }
+
+    /// Buffer the right rows that share the key of the current right row, which
+    /// matches the current left row
+    async fn start_run(&mut self) -> Result<()> {
+        let key = (self.right.current().clone(), self.right.row);
+        let mut ranges = vec![];
+        loop {
+            let batch = self.right.current().clone();
+            let start = self.right.row;
+            let mut end = start;
+            while end < batch.batch.num_rows()
+                && compare_keys(&batch.keys, end, &key.0.keys, key.1)? == Ordering::Equal
+            {
+                end += 1;
+            }
+            let num_rows = batch.batch.num_rows();
+            if end > start {
+                ranges.push((batch, start, end));
+            }
+            self.right.row = end;
+            if end < num_rows || !self.right.fill().await? {
+                break;
+            }
+        }
+
+        let mut size = 0;
+        for (i, (batch, _, _)) in ranges.iter().enumerate() {
+            // the same batch may hold the end of a run and the start of the next one
+            if i == 0 || ranges[i - 1].0.id != batch.id {
+                size += common::batch_memory_size(&batch.batch);
+            }
+        }
+        self.reservation.try_resize(size)?;
+
+        self.run = Some(Run {
+            key,
+            ranges,
+            next: None,
+        });
+        Ok(())
+    }
+
+    /// Join the current left row with the rows of the run, if it shares their key,
+    /// until the output is full
+    async fn join_run(&mut self) -> Result<()> {
+        if !self.left.fill().await? {
+            return self.end_run();
+        }
+        let _timer = self.metrics.elapsed_compute().timer();
+        let run = self.run.as_mut().unwrap();
+        let left = self.left.current();
+
+        let (mut i, mut row) = match run.next {
+            Some(next) => next,
+            None => {
+                let ordering =
+                    compare_keys(&left.keys, self.left.row, &run.key.0.keys, run.key.1)?;
+                if ordering != Ordering::Equal {
+                    drop(_timer);
+                    return self.end_run();
+                }
+                match self.join_type {
+                    JoinType::Semi => {
+                        self.output.push(Some((left, self.left.row)), None);
+                        self.left.row += 1;
+                        return Ok(());
+                    }
+                    JoinType::Anti => {
+                        self.left.row += 1;
+                        return Ok(());
+                    }
+                    _ => (0, run.ranges[0].1),
+                }
+            }
+        };
+
+        while self.output.len() < self.batch_size && i < run.ranges.len() {
+            let (batch, _, end) = &run.ranges[i];
+            self.output
+                .push(Some((left, self.left.row)), Some((batch, row)));
+            row += 1;
+            if row == *end {
+                i += 1;
+                if i < run.ranges.len() {
+                    row = run.ranges[i].1;
+                }
+            }
+        }
+        if i == run.ranges.len() {
+            run.next = None;
+            self.left.row += 1;
+        } else {
+            run.next = Some((i, row));
+        }
+        Ok(())
+    }
+
+    fn end_run(&mut self) -> Result<()> {
+        self.run = None;
+        self.reservation.free();
+        Ok(())
+    }
+
+    fn build_output(&mut self) -> Result<RecordBatch> {
+        let _timer = self.metrics.elapsed_compute().timer();
+        self.output.build(&self.schema)
+    }
+}
+
+/// Stream of the output of a [SortMergeJoinExec]
+struct SortMergeJoinStream {
+    schema: SchemaRef,
+    batches: BoxStream<'static, ArrowResult<RecordBatch>>,
+    metrics: BaselineMetrics,
+}
+
+impl Stream for SortMergeJoinStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.batches.poll_next_unpin(cx);
+        self.metrics.record_poll(poll)
+    }
+}
+
+impl RecordBatchStream for SortMergeJoinStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Whether the key of the row `row` contains a null
+fn has_null(keys: &[ArrayRef], row: usize) -> bool {
+    keys.iter().any(|key| key.is_null(row))
+}
+
+/// Compares the key of the row `l` of `left` with that of the row `r` of `right`, in
+/// ascending order with nulls first
+fn compare_keys(
+    left: &[ArrayRef],
+    l: usize,
+    right: &[ArrayRef],
+    r: usize,
+) -> Result<Ordering> {
+    for (left, right) in left.iter().zip(right) {
+        let ordering = match (left.is_null(l), right.is_null(r)) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => common::compare_values(left, l, right, r)?,
+        };
+        if ordering != Ordering::Equal {
+            return Ok(ordering);
+        }
+    }
+    Ok(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::test;
+    use arrow::array::{Array, Int32Array};
+    use arrow::datatypes::DataType;
+
+    /// Batches of the columns `key` and `value`, sorted on `key` with nulls first,
+    /// where each vector of rows is a batch
+    fn build_input(
+        key: &str,
+        value: &str,
+        batches: Vec<Vec<(Option<i32>, i32)>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(key, DataType::Int32, true),
+            Field::new(value, DataType::Int32, false),
+        ]));
+        let batches = batches
+            .into_iter()
+            .map(|rows| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(Int32Array::from(
+                            rows.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+                        )),
+                        Arc::new(Int32Array::from(
+                            rows.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+                        )),
+                    ],
+                )
+            })
+            .collect::<ArrowResult<Vec<_>>>()?;
+        Ok(Arc::new(MemoryExec::try_new(&vec![batches], schema, None)?))
+    }
+
+    fn left() -> Result<Arc<dyn ExecutionPlan>> {
+        build_input(
+            "a",
+            "b",
+            vec![
+                vec![(None, 0), (Some(1), 10), (Some(2), 20)],
+                vec![(Some(2), 21), (Some(4), 40)],
+            ],
+        )
+    }
+
+    fn right(key: &str) -> Result<Arc<dyn ExecutionPlan>> {
+        build_input(
+            key,
+            "c",
+            vec![
+                vec![(None, -1), (Some(2), 200)],
+                vec![(Some(2), 201), (Some(3), 300), (Some(4), 400)],
+                vec![(Some(4), 401)],
+            ],
+        )
+    }
+
+    /// The rows of the output of the join of `left()` and `right(right_key)` on
+    /// `a = right_key`, sorted, with batches of at most 2 rows. The keys are merged
+    /// when `right_key` is `a`.
+    async fn join(join_type: JoinType, right_key: &str) -> Result<Vec<String>> {
+        let join_constraint = if right_key == "a" {
+            JoinConstraint::Using
+        } else {
+            JoinConstraint::On
+        };
+        let join = SortMergeJoinExec::try_new(
+            left()?,
+            right(right_key)?,
+            &vec![("a".to_string(), right_key.to_string())],
+            &join_type,
+            &join_constraint,
+            2,
+        )?;
+        let batches = test::execute(Arc::new(join)).await?;
+
+        let mut rows = vec![];
+        for batch in &batches {
+            assert!(batch.num_rows() <= 2);
+            for row in 0..batch.num_rows() {
+                let values = batch
+                    .columns()
+                    .iter()
+                    .map(|column| {
+                        let column =
+                            column.as_any().downcast_ref::<Int32Array>().unwrap();
+                        if column.is_null(row) {
+                            "NULL".to_string()
+                        } else {
+                            column.value(row).to_string()
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                rows.push(values.join(","));
+            }
+        }
+        rows.sort();
+        Ok(rows)
+    }
+
+    #[tokio::test]
+    async fn inner_join() -> Result<()> {
+        let expected = vec![
+            "2,20,2,200",
+            "2,20,2,201",
+            "2,21,2,200",
+            "2,21,2,201",
+            "4,40,4,400",
+            "4,40,4,401",
+        ];
+        assert_eq!(join(JoinType::Inner, "d").await?, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn inner_join_merges_keys() -> Result<()> {
+        let expected = vec![
+            "2,20,200", "2,20,201", "2,21,200", "2,21,201", "4,40,400", "4,40,401",
+        ];
+        assert_eq!(join(JoinType::Inner, "a").await?, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn left_join() -> Result<()> {
+        let expected = vec![
+            "1,10,NULL",
+            "2,20,200",
+            "2,20,201",
+            "2,21,200",
+            "2,21,201",
+            "4,40,400",
+            "4,40,401",
+            "NULL,0,NULL",
+        ];
+        assert_eq!(join(JoinType::Left, "a").await?, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn right_join() -> Result<()> {
+        let expected = vec![
+            "2,20,200",
+            "2,20,201",
+            "2,21,200",
+            "2,21,201",
+            "3,NULL,300",
+            "4,40,400",
+            "4,40,401",
+            "NULL,NULL,-1",
+        ];
+        assert_eq!(join(JoinType::Right, "a").await?, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn full_join() -> Result<()> {
+        let expected = vec![
+            "1,10,NULL,NULL",
+            "2,20,2,200",
+            "2,20,2,201",
+            "2,21,2,200",
+            "2,21,2,201",
+            "4,40,4,400",
+            "4,40,4,401",
+            "NULL,0,NULL,NULL",
+            "NULL,NULL,3,300",
+            "NULL,NULL,NULL,-1",
+        ];
+        assert_eq!(join(JoinType::Full, "d").await?, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn semi_join() -> Result<()> {
+        let expected = vec!["2,20", "2,21", "4,40"];
+        assert_eq!(join(JoinType::Semi, "a").await?, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn anti_join() -> Result<()> {
+        let expected = vec!["1,10", "NULL,0"];
+        assert_eq!(join(JoinType::Anti, "a").await?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn outer_join_schema_is_nullable() -> Result<()> {
+        let join = SortMergeJoinExec::try_new(
+            left()?,
+            right("a")?,
+            &vec![("a".to_string(), "a".to_string())],
+            &JoinType::Left,
+            &JoinConstraint::Using,
+            2,
+        )?;
+        let schema = join.schema();
+        let names = schema
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["a", "b", "c"]);
+        assert!(!schema.field(1).is_nullable());
+        assert!(schema.field(2).is_nullable());
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_column_names() -> Result<()> {
+        let err = SortMergeJoinExec::try_new(
+            left()?,
+            build_input("d", "b", vec![])?,
+            &vec![("a".to_string(), "d".to_string())],
+            &JoinType::Inner,
+            &JoinConstraint::On,
+            2,
+        )
+        .unwrap_err()
+        .to_string();
+        assert_eq!(
+            err,
+            "Error during planning: Joins whose inputs both have a column named 'b' \
+             are not supported, unless they are merged keys of a USING join"
+        );
+
+        // keys with the same name are only merged by USING joins
+        let err = SortMergeJoinExec::try_new(
+            left()?,
+            right("a")?,
+            &vec![("a".to_string(), "a".to_string())],
+            &JoinType::Inner,
+            &JoinConstraint::On,
+            2,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("column named 'a'"), "{}", err);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn buffered_rows_exceed_memory_limit() -> Result<()> {
+        let join = SortMergeJoinExec::try_new(
+            left()?,
+            right("a")?,
+            &vec![("a".to_string(), "a".to_string())],
+            &JoinType::Inner,
+            &JoinConstraint::Using,
+            2,
+        )?
+        .with_memory_pool(Arc::new(MemoryPool::new(Some(1))));
+        let err = test::execute(Arc::new(join)).await.unwrap_err();
+        assert!(err.to_string().contains("SortMergeJoinExec"), "{}", err);
+        Ok(())
+    }
+}
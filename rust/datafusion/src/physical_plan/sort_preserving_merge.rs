@@ -0,0 +1,569 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the sort preserving merge plan, which merges partitions that are each
+//! sorted into a single sorted partition
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow::array::{ArrayRef, UInt32Array};
+use arrow::compute::{concat, take};
+use arrow::datatypes::SchemaRef;
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use futures::future;
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+
+use super::metrics::{BaselineMetrics, MetricsSet};
+use super::{RecordBatchStream, SendableRecordBatchStream};
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::{common, ExecutionPlan, Partitioning};
+
+/// Sort preserving merge execution plan, which merges the partitions of its input,
+/// whose rows must each be sorted by the sort expressions, into a single partition
+/// that is sorted by them.
+///
+/// Unlike a [MergeExec](super::merge::MergeExec) followed by a
+/// [SortExec](super::sort::SortExec), it streams its output and only buffers the
+/// current batch of each partition.
+#[derive(Debug)]
+pub struct SortPreservingMergeExec {
+    /// Input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Sort expressions
+    expr: Vec<PhysicalSortExpr>,
+    /// The maximum number of rows of the output batches
+    batch_size: usize,
+    /// Execution metrics
+    metrics: BaselineMetrics,
+}
+
+impl SortPreservingMergeExec {
+    /// Create a new sort preserving merge of the partitions of `input`
+    pub fn try_new(
+        expr: Vec<PhysicalSortExpr>,
+        input: Arc<dyn ExecutionPlan>,
+        batch_size: usize,
+    ) -> Result<Self> {
+        let schema = input.schema();
+        for sort_expr in &expr {
+            let data_type = sort_expr.expr.data_type(&schema)?;
+            if !common::is_comparable_type(&data_type) {
+                return Err(DataFusionError::NotImplemented(format!(
+                    "Sort preserving merge on expressions of type {:?} is not supported",
+                    data_type
+                )));
+            }
+        }
+        Ok(Self {
+            input,
+            expr,
+            batch_size,
+            metrics: BaselineMetrics::new(),
+        })
+    }
+
+    /// Input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Sort expressions
+    pub fn expr(&self) -> &[PhysicalSortExpr] {
+        &self.expr
+    }
+
+    /// The maximum number of rows of the output batches
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for SortPreservingMergeExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        Some(self.expr.clone())
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortExpr>>> {
+        vec![Some(self.expr.clone())]
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(SortPreservingMergeExec::try_new(
+                self.expr.clone(),
+                children[0].clone(),
+                self.batch_size,
+            )?)),
+            _ => Err(DataFusionError::Internal(
+                "SortPreservingMergeExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        if 0 != partition {
+            return Err(DataFusionError::Internal(format!(
+                "SortPreservingMergeExec invalid partition {}",
+                partition
+            )));
+        }
+
+        let partitions = self.input.output_partitioning().partition_count();
+        let streams = future::try_join_all(
+            (0..partitions).map(|partition| self.input.execute(partition)),
+        )
+        .await?;
+
+        let merger = SortPreservingMerger {
+            schema: self.schema(),
+            expr: self.expr.clone(),
+            batch_size: self.batch_size,
+            cursors: streams.into_iter().map(MergeCursor::new).collect(),
+            batches: vec![],
+            rows: vec![],
+            metrics: self.metrics.clone(),
+            finished: false,
+        };
+
+        let batches = stream::unfold(merger, |mut merger| async move {
+            if merger.finished {
+                return None;
+            }
+            match merger.next_batch().await {
+                Ok(Some(batch)) => Some((Ok(batch), merger)),
+                Ok(None) => None,
+                Err(e) => {
+                    merger.finished = true;
+                    Some((Err(e.into_arrow_external_error()), merger))
+                }
+            }
+        });
+
+        Ok(Box::pin(SortPreservingMergeStream {
+            schema: self.schema(),
+            batches: batches.boxed(),
+            metrics: self.metrics.clone(),
+        }))
+    }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
+}
+
+/// A partition of the input, and the position of its current row
+struct MergeCursor {
+    stream: SendableRecordBatchStream,
+    /// The batch of the current row
+    batch: Option<RecordBatch>,
+    /// The values of the sort expressions for the rows of `batch`
+    sort_columns: Vec<ArrayRef>,
+    /// The index of the current row in `batch`
+    row: usize,
+    /// The index of `batch` in the batches of the output that is not produced yet,
+    /// once one of its rows is in that output
+    slot: Option<usize>,
+    finished: bool,
+}
+
+impl MergeCursor {
+    fn new(stream: SendableRecordBatchStream) -> Self {
+        Self {
+            stream,
+            batch: None,
+            sort_columns: vec![],
+            row: 0,
+            slot: None,
+            finished: false,
+        }
+    }
+
+    /// Fetch the next batches of the partition until the current row is valid.
+    /// Returns false once the partition is exhausted.
+    async fn fill(&mut self, expr: &[PhysicalSortExpr]) -> Result<bool> {
+        loop {
+            if let Some(batch) = &self.batch {
+                if self.row < batch.num_rows() {
+                    return Ok(true);
+                }
+            }
+            if self.finished {
+                return Ok(false);
+            }
+            match self.stream.next().await {
+                Some(batch) => {
+                    let batch = batch?;
+                    self.sort_columns = expr
+                        .iter()
+                        .map(|sort_expr| sort_expr.expr.evaluate(&batch))
+                        .collect::<Result<_>>()?;
+                    self.batch = Some(batch);
+                    self.row = 0;
+                    self.slot = None;
+                }
+                None => {
+                    self.finished = true;
+                    self.batch = None;
+                    self.sort_columns = vec![];
+                }
+            }
+        }
+    }
+}
+
+/// The state of the merge of the partitions of the input
+struct SortPreservingMerger {
+    schema: SchemaRef,
+    expr: Vec<PhysicalSortExpr>,
+    batch_size: usize,
+    cursors: Vec<MergeCursor>,
+    /// The batches of the rows of the output that is not produced yet
+    batches: Vec<RecordBatch>,
+    /// The rows of the output that is not produced yet, as the index of their batch in
+    /// `batches` and their index in that batch
+    rows: Vec<(usize, usize)>,
+    metrics: BaselineMetrics,
+    finished: bool,
+}
+
+impl SortPreservingMerger {
+    /// Produce the next batch of the output, or None once the merge is complete
+    async fn next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        loop {
+            if self.rows.len() >= self.batch_size {
+                return self.build_output().map(Some);
+            }
+            let mut has_row = vec![];
+            for cursor in self.cursors.iter_mut() {
+                has_row.push(cursor.fill(&self.expr).await?);
+            }
+
+            let _timer = self.metrics.elapsed_compute().timer();
+            // the smallest current row, which is that of the first partition among
+            // equal rows, so that the merge is stable
+            let mut next: Option<usize> = None;
+            for i in (0..self.cursors.len()).filter(|i| has_row[*i]) {
+                next = match next {
+                    Some(n)
+                        if compare_rows(
+                            &self.expr,
+                            &self.cursors[i],
+                            &self.cursors[n],
+                        )? != Ordering::Less =>
+                    {
+                        Some(n)
+                    }
+                    _ => Some(i),
+                };
+            }
+
+            match next {
+                Some(i) => {
+                    let cursor = &mut self.cursors[i];
+                    let slot = match cursor.slot {
+                        Some(slot) => slot,
+                        None => {
+                            self.batches.push(cursor.batch.clone().unwrap());
+                            cursor.slot = Some(self.batches.len() - 1);
+                            self.batches.len() - 1
+                        }
+                    };
+                    self.rows.push((slot, cursor.row));
+                    cursor.row += 1;
+                }
+                None => {
+                    self.finished = true;
+                    drop(_timer);
+                    return if self.rows.is_empty() {
+                        Ok(None)
+                    } else {
+                        self.build_output().map(Some)
+                    };
+                }
+            }
+        }
+    }
+
+    /// Build a batch of the rows of the output, and clear them
+    fn build_output(&mut self) -> Result<RecordBatch> {
+        let _timer = self.metrics.elapsed_compute().timer();
+        let mut offsets = vec![];
+        let mut offset = 0;
+        for batch in &self.batches {
+            offsets.push(offset);
+            offset += batch.num_rows();
+        }
+        let indices = UInt32Array::from(
+            self.rows
+                .iter()
+                .map(|(slot, row)| (offsets[*slot] + row) as u32)
+                .collect::<Vec<_>>(),
+        );
+        let columns = (0..self.schema.fields().len())
+            .map(|i| {
+                let arrays = self
+                    .batches
+                    .iter()
+                    .map(|batch| batch.column(i).clone())
+                    .collect::<Vec<_>>();
+                Ok(take(&concat(&arrays)?, &indices, None)?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.rows.clear();
+        self.batches.clear();
+        for cursor in self.cursors.iter_mut() {
+            cursor.slot = None;
+        }
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+/// Compares the current row of `left` with that of `right`, which must both be valid,
+/// in the order of `expr`
+fn compare_rows(
+    expr: &[PhysicalSortExpr],
+    left: &MergeCursor,
+    right: &MergeCursor,
+) -> Result<Ordering> {
+    let (l, r) = (left.row, right.row);
+    for (i, sort_expr) in expr.iter().enumerate() {
+        let (left_values, right_values) = (&left.sort_columns[i], &right.sort_columns[i]);
+        let options = sort_expr.options;
+        let ordering = match (left_values.is_null(l), right_values.is_null(r)) {
+            (true, true) => Ordering::Equal,
+            (true, false) if options.nulls_first => Ordering::Less,
+            (true, false) => Ordering::Greater,
+            (false, true) if options.nulls_first => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => {
+                let ordering = common::compare_values(left_values, l, right_values, r)?;
+                if options.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            }
+        };
+        if ordering != Ordering::Equal {
+            return Ok(ordering);
+        }
+    }
+    Ok(Ordering::Equal)
+}
+
+/// Stream of the output of a [SortPreservingMergeExec]
+struct SortPreservingMergeStream {
+    schema: SchemaRef,
+    batches: BoxStream<'static, ArrowResult<RecordBatch>>,
+    metrics: BaselineMetrics,
+}
+
+impl Stream for SortPreservingMergeStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.batches.poll_next_unpin(cx);
+        self.metrics.record_poll(poll)
+    }
+}
+
+impl RecordBatchStream for SortPreservingMergeStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::sort::SortOptions;
+    use crate::test;
+    use arrow::array::{Array, Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    /// Partitions of the columns `a` and `b`, where each vector of rows is a batch
+    fn build_input(
+        partitions: Vec<Vec<Vec<(Option<i32>, &str)>>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, false),
+        ]));
+        let partitions = partitions
+            .into_iter()
+            .map(|batches| {
+                batches
+                    .into_iter()
+                    .map(|rows| {
+                        RecordBatch::try_new(
+                            schema.clone(),
+                            vec![
+                                Arc::new(Int32Array::from(
+                                    rows.iter().map(|(a, _)| *a).collect::<Vec<_>>(),
+                                )),
+                                Arc::new(StringArray::from(
+                                    rows.iter().map(|(_, b)| *b).collect::<Vec<_>>(),
+                                )),
+                            ],
+                        )
+                    })
+                    .collect::<ArrowResult<Vec<_>>>()
+            })
+            .collect::<ArrowResult<Vec<_>>>()?;
+        Ok(Arc::new(MemoryExec::try_new(&partitions, schema, None)?))
+    }
+
+    async fn merge(
+        input: Arc<dyn ExecutionPlan>,
+        options: SortOptions,
+    ) -> Result<Vec<String>> {
+        let merge = SortPreservingMergeExec::try_new(
+            vec![PhysicalSortExpr {
+                expr: col("a"),
+                options,
+            }],
+            input,
+            3,
+        )?;
+        let batches = test::execute(Arc::new(merge)).await?;
+
+        let mut rows = vec![];
+        for batch in &batches {
+            assert!(batch.num_rows() <= 3);
+            let a = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            let b = batch
+                .column(1)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            for row in 0..batch.num_rows() {
+                let a = if a.is_null(row) {
+                    "NULL".to_string()
+                } else {
+                    a.value(row).to_string()
+                };
+                rows.push(format!("{},{}", a, b.value(row)));
+            }
+        }
+        Ok(rows)
+    }
+
+    #[tokio::test]
+    async fn merge_ascending() -> Result<()> {
+        let input = build_input(vec![
+            vec![
+                vec![(None, "x"), (Some(1), "x"), (Some(3), "x")],
+                vec![],
+                vec![(Some(3), "x"), (Some(7), "x")],
+            ],
+            vec![vec![(Some(2), "y")], vec![(Some(3), "y"), (Some(8), "y")]],
+            vec![],
+            vec![vec![(None, "z"), (Some(5), "z")]],
+        ])?;
+        let rows = merge(
+            input,
+            SortOptions {
+                descending: false,
+                nulls_first: true,
+            },
+        )
+        .await?;
+
+        // equal rows are in the order of their partitions
+        let expected = vec![
+            "NULL,x", "NULL,z", "1,x", "2,y", "3,x", "3,x", "3,y", "5,z", "7,x", "8,y",
+        ];
+        assert_eq!(rows, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn merge_descending_nulls_last() -> Result<()> {
+        let input = build_input(vec![
+            vec![vec![(Some(9), "x"), (Some(4), "x"), (None, "x")]],
+            vec![vec![(Some(6), "y")], vec![(Some(4), "y"), (Some(1), "y")]],
+        ])?;
+        let rows = merge(
+            input,
+            SortOptions {
+                descending: true,
+                nulls_first: false,
+            },
+        )
+        .await?;
+
+        let expected = vec!["9,x", "6,y", "4,x", "4,y", "1,y", "NULL,x"];
+        assert_eq!(rows, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn merge_ordering() -> Result<()> {
+        let input = build_input(vec![vec![], vec![]])?;
+        let sort_expr = PhysicalSortExpr {
+            expr: col("a"),
+            options: SortOptions::default(),
+        };
+        let merge =
+            SortPreservingMergeExec::try_new(vec![sort_expr.clone()], input, 1024)?;
+
+        assert_eq!(merge.output_partitioning().partition_count(), 1);
+        assert_eq!(merge.output_ordering(), Some(vec![sort_expr.clone()]));
+        assert_eq!(merge.required_input_ordering(), vec![Some(vec![sort_expr])]);
+        assert!(test::execute(Arc::new(merge)).await?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Temporary Arrow IPC files that operators spill record batches to when they cannot
+//! hold them in memory
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::{DataFusionError, Result};
+use arrow::datatypes::Schema;
+use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use tokio::runtime::Handle;
+
+/// Distinguishes the files spilled by the operators of this process
+static NEXT_SPILL_FILE: AtomicUsize = AtomicUsize::new(0);
+
+/// A temporary Arrow IPC file that record batches are appended to, then read back
+/// once. The file is deleted when dropped.
+pub struct SpillFile {
+    path: PathBuf,
+    /// The writer of the file, until it is read
+    writer: Option<FileWriter<File>>,
+    /// The number of rows written to the file
+    num_rows: usize,
+}
+
+impl SpillFile {
+    /// Create a new, empty, file of batches of schema `schema` in the directory `dir`
+    pub fn try_new(dir: &Path, schema: &Schema) -> Result<Self> {
+        let path = dir.join(format!(
+            "datafusion-spill-{}-{}.arrow",
+            std::process::id(),
+            NEXT_SPILL_FILE.fetch_add(1, Ordering::SeqCst)
+        ));
+        let file = File::create(&path)?;
+        match FileWriter::try_new(file, schema) {
+            Ok(writer) => Ok(Self {
+                path,
+                writer: Some(writer),
+                num_rows: 0,
+            }),
+            Err(e) => {
+                let _ = fs::remove_file(&path);
+                Err(e.into())
+            }
+        }
+    }
+
+    /// The path of the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The number of rows written to the file
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Append `batch` to the file
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        match &mut self.writer {
+            Some(writer) => {
+                writer.write(batch)?;
+                self.num_rows += batch.num_rows();
+                Ok(())
+            }
+            None => Err(DataFusionError::Internal(format!(
+                "The spill file {:?} cannot be written to once it is read",
+                self.path
+            ))),
+        }
+    }
+
+    /// Finish writing the file and read its batches back, in the order they were
+    /// written
+    pub fn read(&mut self) -> Result<FileReader<File>> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.finish()?;
+        }
+        Ok(FileReader::try_new(File::open(&self.path)?)?)
+    }
+}
+
+/// Runs `f`, which reads or writes spill files, on the threads of the tokio runtime
+/// that may block, so that the I/O does not block the workers of the runtime. `f` runs
+/// on the current thread when there is no runtime.
+pub(crate) async fn spawn_blocking_io<F, T>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    if Handle::try_current().is_err() {
+        return f();
+    }
+    tokio::task::spawn_blocking(f).await.unwrap_or_else(|e| {
+        Err(DataFusionError::Internal(format!(
+            "The task reading or writing spill files failed: {}",
+            e
+        )))
+    })
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        // the writer is closed first, and the file may not exist if it failed
+        self.writer = None;
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn write_and_read() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = |values: Vec<i32>| {
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(values))])
+        };
+
+        let mut file = SpillFile::try_new(tmp_dir.path(), &schema)?;
+        file.write(&batch(vec![1, 2])?)?;
+        file.write(&batch(vec![3])?)?;
+        assert_eq!(file.num_rows(), 3);
+
+        let batches = file.read()?.collect::<arrow::error::Result<Vec<_>>>()?;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[1].num_rows(), 1);
+        assert!(file.write(&batch(vec![4])?).is_err());
+
+        let path = file.path().to_path_buf();
+        assert!(path.exists());
+        drop(file);
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn blocking_io() -> Result<()> {
+        assert_eq!(spawn_blocking_io(|| Ok(1)).await?, 1);
+        // without a runtime, the I/O runs on the current thread
+        let result = std::thread::spawn(|| {
+            futures::executor::block_on(spawn_blocking_io(|| Ok(2)))
+        })
+        .join()
+        .unwrap()?;
+        assert_eq!(result, 2);
+        Ok(())
+    }
+}
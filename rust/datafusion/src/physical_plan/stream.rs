@@ -0,0 +1,288 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution plan that reads the batches of a stream as they arrive, e.g. from a
+//! live source
+
+use std::any::Any;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use super::metrics::{BaselineMetrics, MetricsSet, ObservedStream};
+use super::{ExecutionPlan, Partitioning, RecordBatchStream, SendableRecordBatchStream};
+use crate::error::{DataFusionError, Result};
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+
+/// A stream of record batches that can be taken once, shared by the plans that scan it
+pub type SharedRecordBatchStream = Arc<Mutex<Option<SendableRecordBatchStream>>>;
+
+/// Execution plan that reads the batches of a stream in a single partition. The
+/// stream can only be executed once, as its batches are not kept.
+pub struct StreamExec {
+    /// The stream to read, until it is executed
+    stream: SharedRecordBatchStream,
+    /// Schema representing the data after the optional projection is applied
+    schema: SchemaRef,
+    /// Optional projection
+    projection: Option<Vec<usize>>,
+    /// Whether the stream may not end
+    unbounded: bool,
+    /// Execution metrics
+    metrics: BaselineMetrics,
+}
+
+impl fmt::Debug for StreamExec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StreamExec")
+            .field("schema", &self.schema)
+            .field("projection", &self.projection)
+            .field("unbounded", &self.unbounded)
+            .finish()
+    }
+}
+
+impl StreamExec {
+    /// Create a new execution plan that reads the batches of `stream`, whose schema
+    /// is `schema` before the optional `projection` is applied
+    pub fn try_new(
+        stream: SharedRecordBatchStream,
+        schema: SchemaRef,
+        projection: Option<Vec<usize>>,
+        unbounded: bool,
+    ) -> Result<Self> {
+        let schema = match &projection {
+            Some(columns) => {
+                if let Some(i) = columns.iter().find(|i| **i >= schema.fields().len()) {
+                    return Err(DataFusionError::Internal(format!(
+                        "Projection index {} is out of the {} columns of the stream",
+                        i,
+                        schema.fields().len()
+                    )));
+                }
+                Arc::new(Schema::new(
+                    columns.iter().map(|i| schema.field(*i).clone()).collect(),
+                ))
+            }
+            None => schema,
+        };
+        Ok(Self {
+            stream,
+            schema,
+            projection,
+            unbounded,
+            metrics: BaselineMetrics::new(),
+        })
+    }
+
+    /// Optional projection
+    pub fn projection(&self) -> Option<&Vec<usize>> {
+        self.projection.as_ref()
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for StreamExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn is_unbounded(&self) -> bool {
+        self.unbounded
+    }
+
+    fn with_new_children(
+        &self,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Err(DataFusionError::Internal(format!(
+            "Children cannot be replaced in {:?}",
+            self
+        )))
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "StreamExec invalid partition {}",
+                partition
+            )));
+        }
+        let input = self.stream.lock().unwrap().take().ok_or_else(|| {
+            DataFusionError::Execution(
+                "The stream has already been read, it can only be queried once"
+                    .to_string(),
+            )
+        })?;
+        let stream = ProjectionStream {
+            input,
+            schema: self.schema.clone(),
+            projection: self.projection.clone(),
+        };
+        Ok(Box::pin(ObservedStream::counting(
+            Box::pin(stream),
+            self.metrics.clone(),
+        )))
+    }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
+}
+
+/// Stream that applies the projection of a [StreamExec] to the batches of its input
+struct ProjectionStream {
+    input: SendableRecordBatchStream,
+    schema: SchemaRef,
+    projection: Option<Vec<usize>>,
+}
+
+impl Stream for ProjectionStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.input.poll_next_unpin(cx).map(|batch| {
+            batch.map(|batch| match &self.projection {
+                Some(columns) => batch.and_then(|batch| {
+                    RecordBatch::try_new(
+                        self.schema.clone(),
+                        columns.iter().map(|i| batch.column(*i).clone()).collect(),
+                    )
+                }),
+                None => batch,
+            })
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl RecordBatchStream for ProjectionStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Adapts a stream of record batches of a known schema to a [RecordBatchStream]
+pub struct RecordBatchStreamAdapter<S> {
+    schema: SchemaRef,
+    stream: S,
+}
+
+impl<S> RecordBatchStreamAdapter<S> {
+    /// Create a new stream of the batches of `stream`, which are of schema `schema`
+    pub fn new(schema: SchemaRef, stream: S) -> Self {
+        Self { schema, stream }
+    }
+}
+
+impl<S> Stream for RecordBatchStreamAdapter<S>
+where
+    S: Stream<Item = ArrowResult<RecordBatch>> + Unpin,
+{
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+impl<S> RecordBatchStream for RecordBatchStreamAdapter<S>
+where
+    S: Stream<Item = ArrowResult<RecordBatch>> + Unpin,
+{
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field};
+
+    #[tokio::test]
+    async fn read_once_with_projection() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(Int32Array::from(vec![3, 4])),
+            ],
+        )?;
+        let input = futures::stream::iter(vec![Ok(batch.clone()), Ok(batch)]);
+        let stream: SendableRecordBatchStream =
+            Box::pin(RecordBatchStreamAdapter::new(schema.clone(), input));
+        let exec = Arc::new(StreamExec::try_new(
+            Arc::new(Mutex::new(Some(stream))),
+            schema,
+            Some(vec![1]),
+            true,
+        )?);
+        assert!(exec.is_unbounded());
+
+        let batches = test::execute(exec.clone()).await?;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].num_columns(), 1);
+        assert_eq!(batches[0].schema().field(0).name(), "b");
+
+        match exec.execute(0).await {
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "Execution error: The stream has already been read, it can only be queried once"
+            ),
+            Ok(_) => panic!("Expected the second execution to fail"),
+        }
+        Ok(())
+    }
+}
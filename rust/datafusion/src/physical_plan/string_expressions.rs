@@ -17,52 +17,750 @@
 
 //! String expressions
 
+use std::any::type_name;
+use std::cmp::max;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
 use crate::error::{DataFusionError, Result};
-use arrow::array::{Array, ArrayRef, StringArray, StringBuilder};
+use crate::physical_plan::functions::ColumnarValue;
+use crate::scalar::ScalarValue;
+use arrow::array::{
+    Array, ArrayRef, BooleanArray, GenericStringArray, Int32Array, Int64Array,
+    StringArray, StringOffsetSizeTrait,
+};
+use arrow::datatypes::DataType;
+use sha2::{Digest, Sha256};
 
-macro_rules! downcast_vec {
-    ($ARGS:expr, $ARRAY_TYPE:ident) => {{
-        $ARGS
-            .iter()
-            .map(|e| match e.as_any().downcast_ref::<$ARRAY_TYPE>() {
-                Some(array) => Ok(array),
-                _ => Err(DataFusionError::Internal("failed to downcast".to_string())),
-            })
+/// downcasts a single argument to `$ARRAY_TYPE`, returning an internal error on failure.
+macro_rules! downcast_arg {
+    ($ARG:expr, $NAME:expr, $ARRAY_TYPE:ty) => {{
+        $ARG.as_any().downcast_ref::<$ARRAY_TYPE>().ok_or_else(|| {
+            DataFusionError::Internal(format!(
+                "could not cast {} to {}",
+                $NAME,
+                type_name::<$ARRAY_TYPE>()
+            ))
+        })?
     }};
 }
 
-/// concatenate string columns together.
-pub fn concatenate(args: &[ArrayRef]) -> Result<StringArray> {
-    // downcast all arguments to strings
-    let args = downcast_vec!(args, StringArray).collect::<Result<Vec<&StringArray>>>()?;
+/// calls `$FUNC::<i32>` when the first argument is `Utf8` and `$FUNC::<i64>`
+/// when it is `LargeUtf8`, forwarding any extra arguments.
+macro_rules! dispatch_on_string_type {
+    ($ARGS:expr, $NAME:expr, $FUNC:ident $(, $EXTRA:expr)*) => {{
+        match $ARGS[0].data_type() {
+            DataType::Utf8 => $FUNC::<i32>($ARGS $(, $EXTRA)*),
+            DataType::LargeUtf8 => $FUNC::<i64>($ARGS $(, $EXTRA)*),
+            other => Err(DataFusionError::Internal(format!(
+                "Unsupported data type {:?} for function {}",
+                other, $NAME,
+            ))),
+        }
+    }};
+}
+
+/// The maximum length of the strings that `repeat`, `lpad` and `rpad` build, like the
+/// 1 GiB limit of PostgreSQL, so that a large count fails rather than exhausting the
+/// memory.
+const MAX_STRING_LENGTH: usize = 1 << 30;
+
+fn string_length_error(function: &str) -> DataFusionError {
+    DataFusionError::Execution(format!(
+        "requested length too large in {}, the maximum is {} bytes",
+        function, MAX_STRING_LENGTH
+    ))
+}
+
+/// A string argument of `concat` or `concat_ws`, which is either a column or the
+/// same value for all the rows
+enum StringArg<'a, T: StringOffsetSizeTrait> {
+    Array(&'a GenericStringArray<T>),
+    Scalar(Option<&'a str>),
+}
+
+impl<'a, T: StringOffsetSizeTrait> StringArg<'a, T> {
+    fn try_new(arg: &'a ColumnarValue) -> Result<Self> {
+        match arg {
+            ColumnarValue::Array(array) => Ok(StringArg::Array(downcast_arg!(
+                array,
+                "string",
+                GenericStringArray<T>
+            ))),
+            ColumnarValue::Scalar(ScalarValue::Utf8(value))
+            | ColumnarValue::Scalar(ScalarValue::LargeUtf8(value)) => {
+                Ok(StringArg::Scalar(value.as_deref()))
+            }
+            other => Err(DataFusionError::Internal(format!(
+                "Unsupported data type {:?} for a string argument",
+                other.data_type()
+            ))),
+        }
+    }
+
+    fn value(&self, index: usize) -> Option<&'a str> {
+        match self {
+            StringArg::Array(array) if array.is_null(index) => None,
+            StringArg::Array(array) => Some(array.value(index)),
+            StringArg::Scalar(value) => *value,
+        }
+    }
+}
+
+/// applies `op` to every row of the string arguments `args`, without expanding
+/// the scalar arguments into arrays. The result is a scalar when all the arguments
+/// are scalars.
+fn string_args_op<T: StringOffsetSizeTrait>(
+    args: &[ColumnarValue],
+    op: impl Fn(&[StringArg<T>], usize) -> Option<String>,
+) -> Result<ColumnarValue> {
+    let len = args.iter().find_map(|arg| match arg {
+        ColumnarValue::Array(array) => Some(array.len()),
+        ColumnarValue::Scalar(_) => None,
+    });
+    let args = args
+        .iter()
+        .map(StringArg::try_new)
+        .collect::<Result<Vec<_>>>()?;
+    match len {
+        None => {
+            let value = op(&args, 0);
+            Ok(ColumnarValue::Scalar(match T::DATA_TYPE {
+                DataType::LargeUtf8 => ScalarValue::LargeUtf8(value),
+                _ => ScalarValue::Utf8(value),
+            }))
+        }
+        Some(len) => {
+            let result = (0..len)
+                .map(|index| op(&args, index))
+                .collect::<GenericStringArray<T>>();
+            Ok(ColumnarValue::Array(Arc::new(result)))
+        }
+    }
+}
+
+/// concatenates string columns or scalars together; if any is null, the result is
+/// null.
+pub fn concat(args: &[ColumnarValue]) -> Result<ColumnarValue> {
     // do not accept 0 arguments.
-    if args.len() == 0 {
+    if args.is_empty() {
         return Err(DataFusionError::Internal(
             "Concatenate was called with 0 arguments. It requires at least one."
                 .to_string(),
         ));
     }
 
-    let mut builder = StringBuilder::new(args.len());
-    // for each entry in the array
-    for index in 0..args[0].len() {
-        let mut owned_string: String = "".to_owned();
-
-        // if any is null, the result is null
-        let mut is_null = false;
-        for arg in &args {
-            if arg.is_null(index) {
-                is_null = true;
-                break; // short-circuit as we already know the result
-            } else {
-                owned_string.push_str(&arg.value(index));
-            }
+    string_args_op::<i32>(args, |args, index| {
+        let mut owned_string = String::new();
+        for arg in args {
+            // a null argument makes the result null
+            owned_string.push_str(arg.value(index)?);
         }
-        if is_null {
-            builder.append_null()?;
+        Some(owned_string)
+    })
+}
+
+fn concat_ws_generic<T: StringOffsetSizeTrait>(
+    args: &[ColumnarValue],
+) -> Result<ColumnarValue> {
+    string_args_op::<T>(args, |args, index| {
+        let separator = args[0].value(index)?;
+        let values = args[1..]
+            .iter()
+            .filter_map(|arg| arg.value(index))
+            .collect::<Vec<&str>>();
+        Some(values.join(separator))
+    })
+}
+
+/// concatenates all but the first argument, using the first argument as separator.
+/// Unlike `concat`, null arguments are skipped; a null separator yields null.
+/// concat_ws(',', 'abc', NULL, 'def') = 'abc,def'
+pub fn concat_ws(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    if args.len() < 2 {
+        return Err(DataFusionError::Internal(format!(
+            "concat_ws was called with {} arguments. It requires at least two.",
+            args.len()
+        )));
+    }
+
+    dispatch_on_string_type!(args, "concat_ws", concat_ws_generic)
+}
+
+/// applies `op` to every non-null value of the first argument; nulls stay null.
+fn unary_string_op<T: StringOffsetSizeTrait>(
+    args: &[ArrayRef],
+    op: fn(&str) -> String,
+) -> Result<ArrayRef> {
+    let string_array = downcast_arg!(args[0], "string", GenericStringArray<T>);
+
+    let result = string_array
+        .iter()
+        .map(|string| string.map(op))
+        .collect::<GenericStringArray<T>>();
+
+    Ok(Arc::new(result) as ArrayRef)
+}
+
+/// builds an `Int32Array` for `Utf8` and an `Int64Array` for `LargeUtf8` inputs,
+/// following the convention of the `length` kernel.
+fn offset_size_array<T: StringOffsetSizeTrait>(values: Vec<Option<usize>>) -> ArrayRef {
+    match T::DATA_TYPE {
+        DataType::LargeUtf8 => Arc::new(
+            values
+                .into_iter()
+                .map(|value| value.map(|value| value as i64))
+                .collect::<Int64Array>(),
+        ) as ArrayRef,
+        _ => Arc::new(
+            values
+                .into_iter()
+                .map(|value| value.map(|value| value as i32))
+                .collect::<Int32Array>(),
+        ) as ArrayRef,
+    }
+}
+
+/// converts the string to all lower case.
+/// lower('TOM') = 'tom'
+pub fn lower(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "lower", unary_string_op, str::to_lowercase)
+}
+
+/// converts the string to all upper case.
+/// upper('tom') = 'TOM'
+pub fn upper(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "upper", unary_string_op, str::to_uppercase)
+}
+
+/// reverses the order of the characters in the string.
+/// reverse('abcde') = 'edcba'
+pub fn reverse(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "reverse", unary_string_op, |s: &str| {
+        s.chars().rev().collect::<String>()
+    })
+}
+
+fn initcap_str(string: &str) -> String {
+    let mut result = String::with_capacity(string.len());
+    let mut capitalize_next = true;
+    for c in string.chars() {
+        if capitalize_next {
+            result.extend(c.to_uppercase());
         } else {
-            builder.append_value(&owned_string)?;
+            result.extend(c.to_lowercase());
+        }
+        capitalize_next = !c.is_alphanumeric();
+    }
+    result
+}
+
+/// converts the first letter of each word to upper case and the rest to lower case.
+/// Words are sequences of alphanumeric characters separated by non-alphanumeric characters.
+/// initcap('hi THOMAS') = 'Hi Thomas'
+pub fn initcap(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "initcap", unary_string_op, initcap_str)
+}
+
+fn md5_str(string: &str) -> String {
+    format!("{:x}", ::md5::Md5::digest(string.as_bytes()))
+}
+
+fn sha256_str(string: &str) -> String {
+    format!("{:x}", Sha256::digest(string.as_bytes()))
+}
+
+/// computes the MD5 hash of the string, returning the result in hexadecimal.
+/// md5('abc') = '900150983cd24fb0d6963f7d28e17f72'
+pub fn md5(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "md5", unary_string_op, md5_str)
+}
+
+/// computes the SHA-256 hash of the string, returning the result in hexadecimal.
+pub fn sha256(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "sha256", unary_string_op, sha256_str)
+}
+
+#[derive(Clone, Copy)]
+enum TrimType {
+    Left,
+    Right,
+    Both,
+}
+
+fn trim_str<'a>(string: &'a str, characters: &str, trim_type: TrimType) -> &'a str {
+    let pattern = |c: char| characters.contains(c);
+    match trim_type {
+        TrimType::Left => string.trim_start_matches(pattern),
+        TrimType::Right => string.trim_end_matches(pattern),
+        TrimType::Both => string.trim_matches(pattern),
+    }
+}
+
+fn trim_generic<T: StringOffsetSizeTrait>(
+    args: &[ArrayRef],
+    trim_type: TrimType,
+) -> Result<ArrayRef> {
+    let string_array = downcast_arg!(args[0], "string", GenericStringArray<T>);
+
+    let result: GenericStringArray<T> = match args.len() {
+        1 => string_array
+            .iter()
+            .map(|string| string.map(|string| trim_str(string, " ", trim_type)))
+            .collect(),
+        2 => {
+            let characters_array =
+                downcast_arg!(args[1], "characters", GenericStringArray<T>);
+            string_array
+                .iter()
+                .zip(characters_array.iter())
+                .map(|(string, characters)| match (string, characters) {
+                    (Some(string), Some(characters)) => {
+                        Some(trim_str(string, characters, trim_type))
+                    }
+                    _ => None,
+                })
+                .collect()
+        }
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "trim was called with {} arguments. It requires one or two.",
+                other
+            )))
+        }
+    };
+
+    Ok(Arc::new(result) as ArrayRef)
+}
+
+/// removes the longest string containing only characters in `characters` (a space by default)
+/// from the start and end of the string.
+/// btrim('xyxtrimyyx', 'xyz') = 'trim'
+pub fn btrim(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "btrim", trim_generic, TrimType::Both)
+}
+
+/// removes the longest string containing only characters in `characters` (a space by default)
+/// from the start of the string.
+/// ltrim('zzzytest', 'xyz') = 'test'
+pub fn ltrim(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "ltrim", trim_generic, TrimType::Left)
+}
+
+/// removes the longest string containing only characters in `characters` (a space by default)
+/// from the end of the string.
+/// rtrim('testxxzx', 'xyz') = 'test'
+pub fn rtrim(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "rtrim", trim_generic, TrimType::Right)
+}
+
+fn substr_generic<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let string_array = downcast_arg!(args[0], "string", GenericStringArray<T>);
+    let start_array = downcast_arg!(args[1], "start", Int64Array);
+
+    let result = match args.len() {
+        2 => string_array
+            .iter()
+            .zip(start_array.iter())
+            .map(|(string, start)| match (string, start) {
+                (Some(string), Some(start)) => {
+                    let skip = max(start.saturating_sub(1), 0) as usize;
+                    Some(string.chars().skip(skip).collect::<String>())
+                }
+                _ => None,
+            })
+            .collect::<GenericStringArray<T>>(),
+        3 => {
+            let count_array = downcast_arg!(args[2], "count", Int64Array);
+            string_array
+                .iter()
+                .zip(start_array.iter())
+                .zip(count_array.iter())
+                .map(|((string, start), count)| match (string, start, count) {
+                    (Some(string), Some(start), Some(count)) => {
+                        if count < 0 {
+                            return Err(DataFusionError::Execution(format!(
+                                "negative substring length not allowed: substr(<str>, {}, {})",
+                                start, count
+                            )));
+                        }
+                        // the substring covers the characters in [start, start + count),
+                        // clipped to the start of the string.
+                        let end = start.saturating_add(count);
+                        let first = max(start, 1);
+                        let skip = (first - 1) as usize;
+                        let take = max(end.saturating_sub(first), 0) as usize;
+                        Ok(Some(string.chars().skip(skip).take(take).collect::<String>()))
+                    }
+                    _ => Ok(None),
+                })
+                .collect::<Result<GenericStringArray<T>>>()?
         }
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "substr was called with {} arguments. It requires two or three.",
+                other
+            )))
+        }
+    };
+
+    Ok(Arc::new(result) as ArrayRef)
+}
+
+/// extracts the substring starting at the `start`th character (1-based), optionally
+/// limited to `count` characters.
+/// substr('alphabet', 3) = 'phabet'
+/// substr('alphabet', 3, 2) = 'ph'
+pub fn substr(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "substr", substr_generic)
+}
+
+fn left_generic<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let string_array = downcast_arg!(args[0], "string", GenericStringArray<T>);
+    let n_array = downcast_arg!(args[1], "n", Int64Array);
+
+    let result = string_array
+        .iter()
+        .zip(n_array.iter())
+        .map(|(string, n)| match (string, n) {
+            (Some(string), Some(n)) => {
+                let take = if n < 0 {
+                    max(string.chars().count() as i64 + n, 0)
+                } else {
+                    n
+                };
+                Some(string.chars().take(take as usize).collect::<String>())
+            }
+            _ => None,
+        })
+        .collect::<GenericStringArray<T>>();
+
+    Ok(Arc::new(result) as ArrayRef)
+}
+
+/// returns the first `n` characters of the string; when `n` is negative,
+/// returns all but the last `|n|` characters.
+/// left('abcde', 2) = 'ab'
+pub fn left(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "left", left_generic)
+}
+
+fn right_generic<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let string_array = downcast_arg!(args[0], "string", GenericStringArray<T>);
+    let n_array = downcast_arg!(args[1], "n", Int64Array);
+
+    let result = string_array
+        .iter()
+        .zip(n_array.iter())
+        .map(|(string, n)| match (string, n) {
+            (Some(string), Some(n)) => {
+                let skip = if n < 0 {
+                    n.checked_neg().unwrap_or(i64::MAX)
+                } else {
+                    max(string.chars().count() as i64 - n, 0)
+                };
+                Some(string.chars().skip(skip as usize).collect::<String>())
+            }
+            _ => None,
+        })
+        .collect::<GenericStringArray<T>>();
+
+    Ok(Arc::new(result) as ArrayRef)
+}
+
+/// returns the last `n` characters of the string; when `n` is negative,
+/// returns all but the first `|n|` characters.
+/// right('abcde', 2) = 'de'
+pub fn right(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "right", right_generic)
+}
+
+fn pad_str(string: &str, length: i64, fill: &str, pad_left: bool) -> Result<String> {
+    if length <= 0 {
+        return Ok("".to_string());
+    }
+    let length = length as u64;
+    let string_length = string.chars().count();
+    if length <= string_length as u64 || fill.is_empty() {
+        // the string is truncated when it is already longer than `length`
+        return Ok(string.chars().take(length as usize).collect());
     }
-    Ok(builder.finish())
+    // the length is in characters, of up to 4 bytes each
+    let function = if pad_left { "lpad" } else { "rpad" };
+    if length.saturating_mul(4) > MAX_STRING_LENGTH as u64 {
+        return Err(string_length_error(function));
+    }
+
+    let padding = fill.chars().cycle().take(length as usize - string_length);
+    Ok(if pad_left {
+        padding.chain(string.chars()).collect()
+    } else {
+        string.chars().chain(padding).collect()
+    })
+}
+
+fn pad_generic<T: StringOffsetSizeTrait>(
+    args: &[ArrayRef],
+    pad_left: bool,
+) -> Result<ArrayRef> {
+    let string_array = downcast_arg!(args[0], "string", GenericStringArray<T>);
+    let length_array = downcast_arg!(args[1], "length", Int64Array);
+
+    let result: GenericStringArray<T> = match args.len() {
+        2 => string_array
+            .iter()
+            .zip(length_array.iter())
+            .map(|(string, length)| match (string, length) {
+                (Some(string), Some(length)) => {
+                    pad_str(string, length, " ", pad_left).map(Some)
+                }
+                _ => Ok(None),
+            })
+            .collect::<Result<_>>()?,
+        3 => {
+            let fill_array = downcast_arg!(args[2], "fill", GenericStringArray<T>);
+            string_array
+                .iter()
+                .zip(length_array.iter())
+                .zip(fill_array.iter())
+                .map(|((string, length), fill)| match (string, length, fill) {
+                    (Some(string), Some(length), Some(fill)) => {
+                        pad_str(string, length, fill, pad_left).map(Some)
+                    }
+                    _ => Ok(None),
+                })
+                .collect::<Result<_>>()?
+        }
+        other => {
+            return Err(DataFusionError::Internal(format!(
+                "pad was called with {} arguments. It requires two or three.",
+                other
+            )))
+        }
+    };
+
+    Ok(Arc::new(result) as ArrayRef)
+}
+
+/// extends the string to `length` characters by prepending `fill` (a space by default).
+/// If the string is already longer than `length`, it is truncated (on the right).
+/// lpad('hi', 5, 'xy') = 'xyxhi'
+pub fn lpad(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "lpad", pad_generic, true)
+}
+
+/// extends the string to `length` characters by appending `fill` (a space by default).
+/// If the string is already longer than `length`, it is truncated.
+/// rpad('hi', 5, 'xy') = 'hixyx'
+pub fn rpad(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "rpad", pad_generic, false)
+}
+
+fn replace_generic<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let string_array = downcast_arg!(args[0], "string", GenericStringArray<T>);
+    let from_array = downcast_arg!(args[1], "from", GenericStringArray<T>);
+    let to_array = downcast_arg!(args[2], "to", GenericStringArray<T>);
+
+    let result = string_array
+        .iter()
+        .zip(from_array.iter())
+        .zip(to_array.iter())
+        .map(|((string, from), to)| match (string, from, to) {
+            (Some(string), Some(""), Some(_)) => Some(string.to_string()),
+            (Some(string), Some(from), Some(to)) => Some(string.replace(from, to)),
+            _ => None,
+        })
+        .collect::<GenericStringArray<T>>();
+
+    Ok(Arc::new(result) as ArrayRef)
+}
+
+/// replaces all occurrences of `from` in the string with `to`.
+/// replace('abcdefabcdef', 'cd', 'XX') = 'abXXefabXXef'
+pub fn replace(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "replace", replace_generic)
+}
+
+fn split_part_generic<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let string_array = downcast_arg!(args[0], "string", GenericStringArray<T>);
+    let delimiter_array = downcast_arg!(args[1], "delimiter", GenericStringArray<T>);
+    let n_array = downcast_arg!(args[2], "n", Int64Array);
+
+    let result = string_array
+        .iter()
+        .zip(delimiter_array.iter())
+        .zip(n_array.iter())
+        .map(|((string, delimiter), n)| match (string, delimiter, n) {
+            (Some(string), Some(delimiter), Some(n)) => {
+                if n <= 0 {
+                    Err(DataFusionError::Execution(
+                        "field position must be greater than zero".to_string(),
+                    ))
+                } else if delimiter.is_empty() {
+                    Ok(Some(if n == 1 { string } else { "" }))
+                } else {
+                    Ok(Some(
+                        string.split(delimiter).nth((n - 1) as usize).unwrap_or(""),
+                    ))
+                }
+            }
+            _ => Ok(None),
+        })
+        .collect::<Result<GenericStringArray<T>>>()?;
+
+    Ok(Arc::new(result) as ArrayRef)
+}
+
+/// splits the string on `delimiter` and returns the `n`th field (1-based).
+/// split_part('abc~@~def~@~ghi', '~@~', 2) = 'def'
+pub fn split_part(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "split_part", split_part_generic)
+}
+
+fn repeat_generic<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let string_array = downcast_arg!(args[0], "string", GenericStringArray<T>);
+    let n_array = downcast_arg!(args[1], "n", Int64Array);
+
+    let result = string_array
+        .iter()
+        .zip(n_array.iter())
+        .map(|(string, n)| match (string, n) {
+            (Some(string), Some(n)) => {
+                let n = max(n, 0) as u64;
+                match (string.len() as u64).checked_mul(n) {
+                    Some(length) if length <= MAX_STRING_LENGTH as u64 => {
+                        Ok(Some(string.repeat(n as usize)))
+                    }
+                    _ => Err(string_length_error("repeat")),
+                }
+            }
+            _ => Ok(None),
+        })
+        .collect::<Result<GenericStringArray<T>>>()?;
+
+    Ok(Arc::new(result) as ArrayRef)
+}
+
+/// repeats the string `n` times.
+/// repeat('Pg', 4) = 'PgPgPgPg'
+pub fn repeat(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "repeat", repeat_generic)
+}
+
+fn strpos_generic<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let string_array = downcast_arg!(args[0], "string", GenericStringArray<T>);
+    let substring_array = downcast_arg!(args[1], "substring", GenericStringArray<T>);
+
+    let result = string_array
+        .iter()
+        .zip(substring_array.iter())
+        .map(|(string, substring)| match (string, substring) {
+            (Some(string), Some(substring)) => Some(
+                // positions are counted in characters, not bytes
+                string
+                    .find(substring)
+                    .map(|index| string[..index].chars().count() + 1)
+                    .unwrap_or(0),
+            ),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(offset_size_array::<T>(result))
+}
+
+/// returns the (1-based) character position of the first occurrence of `substring`
+/// in the string, or 0 when it does not occur.
+/// strpos('high', 'ig') = 2
+pub fn strpos(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "strpos", strpos_generic)
+}
+
+fn bit_length_generic<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let string_array = downcast_arg!(args[0], "string", GenericStringArray<T>);
+
+    let result = string_array
+        .iter()
+        .map(|string| string.map(|string| string.len() * 8))
+        .collect::<Vec<_>>();
+
+    Ok(offset_size_array::<T>(result))
+}
+
+/// returns the number of bits in the string.
+/// bit_length('jose') = 32
+pub fn bit_length(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "bit_length", bit_length_generic)
+}
+
+fn starts_with_generic<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let string_array = downcast_arg!(args[0], "string", GenericStringArray<T>);
+    let prefix_array = downcast_arg!(args[1], "prefix", GenericStringArray<T>);
+
+    let result = string_array
+        .iter()
+        .zip(prefix_array.iter())
+        .map(|(string, prefix)| match (string, prefix) {
+            (Some(string), Some(prefix)) => Some(string.starts_with(prefix)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Arc::new(BooleanArray::from(result)) as ArrayRef)
+}
+
+/// returns true if the string starts with `prefix`.
+/// starts_with('alphabet', 'alph') = true
+pub fn starts_with(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "starts_with", starts_with_generic)
+}
+
+fn ascii_generic<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let string_array = downcast_arg!(args[0], "string", GenericStringArray<T>);
+
+    let result = string_array
+        .iter()
+        .map(|string| string.map(|string| string.chars().next().map_or(0, |c| c as i32)))
+        .collect::<Int32Array>();
+
+    Ok(Arc::new(result) as ArrayRef)
+}
+
+/// returns the numeric code of the first character of the string, or 0 for an empty string.
+/// ascii('x') = 120
+pub fn ascii(args: &[ArrayRef]) -> Result<ArrayRef> {
+    dispatch_on_string_type!(args, "ascii", ascii_generic)
+}
+
+/// returns the character with the given code.
+/// chr(65) = 'A'
+pub fn chr(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let integer_array = downcast_arg!(args[0], "integer", Int64Array);
+
+    let result = integer_array
+        .iter()
+        .map(|integer| {
+            integer
+                .map(|integer| {
+                    if integer == 0 {
+                        return Err(DataFusionError::Execution(
+                            "null character not permitted".to_string(),
+                        ));
+                    }
+                    u32::try_from(integer)
+                        .ok()
+                        .and_then(std::char::from_u32)
+                        .map(|c| c.to_string())
+                        .ok_or_else(|| {
+                            DataFusionError::Execution(format!(
+                                "requested character {} is not a valid code point",
+                                integer
+                            ))
+                        })
+                })
+                .transpose()
+        })
+        .collect::<Result<StringArray>>()?;
+
+    Ok(Arc::new(result) as ArrayRef)
 }
@@ -0,0 +1,249 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Declaration of built-in table functions, which are called in the FROM clause
+//! of a query and produce a table.
+//!
+//! * `generate_series(start, stop[, step])` produces the values from `start` to
+//!   `stop` included
+//! * `range([start, ]stop[, step])` produces the values from `start`, 0 by
+//!   default, to `stop` excluded
+//!
+//! The values are integers, separated by `step`, 1 by default, or timestamps,
+//! separated by an interval `step`.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, TimeUnit};
+
+use super::series::SeriesStep;
+use crate::datasource::series::SeriesTable;
+use crate::datasource::TableProvider;
+use crate::error::{DataFusionError, Result};
+use crate::scalar::ScalarValue;
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+const NANOS_PER_MILLI: i64 = 1_000_000;
+
+/// Enum of all built-in table functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinTableFunction {
+    /// generate_series
+    GenerateSeries,
+    /// range
+    Range,
+}
+
+impl fmt::Display for BuiltinTableFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuiltinTableFunction::GenerateSeries => write!(f, "generate_series"),
+            BuiltinTableFunction::Range => write!(f, "range"),
+        }
+    }
+}
+
+impl FromStr for BuiltinTableFunction {
+    type Err = DataFusionError;
+    fn from_str(name: &str) -> Result<BuiltinTableFunction> {
+        Ok(match name {
+            "generate_series" => BuiltinTableFunction::GenerateSeries,
+            "range" => BuiltinTableFunction::Range,
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "There is no built-in table function named {}",
+                    name
+                )))
+            }
+        })
+    }
+}
+
+impl BuiltinTableFunction {
+    /// Creates the table of a call of the function with `args`
+    pub fn create_table(
+        &self,
+        args: &[ScalarValue],
+    ) -> Result<Arc<dyn TableProvider + Send + Sync>> {
+        if let Some(arg) = args.iter().find(|arg| arg.is_null()) {
+            return Err(DataFusionError::Plan(format!(
+                "The arguments of {} cannot be null, but got {:?}",
+                self, arg
+            )));
+        }
+        let zero = ScalarValue::Int64(Some(0));
+        let (start, stop, step, include_stop) = match (self, args) {
+            (BuiltinTableFunction::Range, [stop]) => (&zero, stop, None, false),
+            (BuiltinTableFunction::Range, [start, stop]) => (start, stop, None, false),
+            (BuiltinTableFunction::Range, [start, stop, step]) => {
+                (start, stop, Some(step), false)
+            }
+            (BuiltinTableFunction::GenerateSeries, [start, stop]) => {
+                (start, stop, None, true)
+            }
+            (BuiltinTableFunction::GenerateSeries, [start, stop, step]) => {
+                (start, stop, Some(step), true)
+            }
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "{} does not accept {} arguments",
+                    self,
+                    args.len()
+                )))
+            }
+        };
+
+        let table = match (integer(start), integer(stop), start, stop) {
+            (Some(start), Some(stop), _, _) => {
+                let step = match step {
+                    None => 1,
+                    Some(step) => integer(step).ok_or_else(|| {
+                        DataFusionError::Plan(format!(
+                            "The step of a series of integers must be an integer, but got {:?}",
+                            step
+                        ))
+                    })?,
+                };
+                SeriesTable::new(
+                    DataType::Int64,
+                    start,
+                    stop,
+                    SeriesStep::Fixed(step),
+                    include_stop,
+                )
+            }
+            (
+                _,
+                _,
+                ScalarValue::TimestampNanosecond(Some(start), None),
+                ScalarValue::TimestampNanosecond(Some(stop), None),
+            ) => {
+                let step = match step {
+                    Some(ScalarValue::IntervalDayTime(Some(interval))) => {
+                        // the days are in the upper 32 bits, the milliseconds in the lower
+                        let days = (interval >> 32) as i32 as i64;
+                        let millis = *interval as i32 as i64;
+                        let nanos = (days * MILLIS_PER_DAY + millis)
+                            .checked_mul(NANOS_PER_MILLI)
+                            .ok_or_else(|| {
+                                DataFusionError::Plan(format!(
+                                    "The step of {} is out of the range of timestamps",
+                                    self
+                                ))
+                            })?;
+                        SeriesStep::Fixed(nanos)
+                    }
+                    Some(ScalarValue::IntervalYearMonth(Some(months))) => {
+                        SeriesStep::Months(*months)
+                    }
+                    _ => {
+                        return Err(DataFusionError::Plan(format!(
+                            "The step of a series of timestamps must be an interval, but got {:?}",
+                            step
+                        )))
+                    }
+                };
+                SeriesTable::new(
+                    DataType::Timestamp(TimeUnit::Nanosecond, None),
+                    *start,
+                    *stop,
+                    step,
+                    include_stop,
+                )
+            }
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "{} produces series of integers or timestamps, but got {:?} and {:?}",
+                    self, start, stop
+                )))
+            }
+        };
+        Ok(Arc::new(table))
+    }
+}
+
+/// The value of `value` if it is an integer that fits an `i64`
+fn integer(value: &ScalarValue) -> Option<i64> {
+    match value {
+        ScalarValue::Int8(Some(v)) => Some(*v as i64),
+        ScalarValue::Int16(Some(v)) => Some(*v as i64),
+        ScalarValue::Int32(Some(v)) => Some(*v as i64),
+        ScalarValue::Int64(Some(v)) => Some(*v),
+        ScalarValue::UInt8(Some(v)) => Some(*v as i64),
+        ScalarValue::UInt16(Some(v)) => Some(*v as i64),
+        ScalarValue::UInt32(Some(v)) => Some(*v as i64),
+        ScalarValue::UInt64(Some(v)) if *v <= i64::MAX as u64 => Some(*v as i64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() -> Result<()> {
+        assert_eq!(
+            BuiltinTableFunction::from_str("generate_series")?,
+            BuiltinTableFunction::GenerateSeries
+        );
+        assert_eq!(
+            BuiltinTableFunction::from_str("range")?,
+            BuiltinTableFunction::Range
+        );
+        assert!(BuiltinTableFunction::from_str("series").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let cases = vec![
+            (
+                BuiltinTableFunction::GenerateSeries,
+                vec![ScalarValue::Int64(Some(1))],
+                "generate_series does not accept 1 arguments",
+            ),
+            (
+                BuiltinTableFunction::Range,
+                vec![ScalarValue::Int64(None)],
+                "The arguments of range cannot be null, but got Int64(NULL)",
+            ),
+            (
+                BuiltinTableFunction::Range,
+                vec![ScalarValue::Utf8(Some("a".to_string()))],
+                "range produces series of integers or timestamps, but got Int64(0) and Utf8(\"a\")",
+            ),
+            (
+                BuiltinTableFunction::GenerateSeries,
+                vec![
+                    ScalarValue::TimestampNanosecond(Some(0), None),
+                    ScalarValue::TimestampNanosecond(Some(1), None),
+                ],
+                "The step of a series of timestamps must be an interval, but got None",
+            ),
+        ];
+        for (fun, args, expected) in cases {
+            let err = fun.create_table(&args).err().expect("expected an error");
+            assert_eq!(
+                err.to_string(),
+                format!("Error during planning: {}", expected)
+            );
+        }
+    }
+}
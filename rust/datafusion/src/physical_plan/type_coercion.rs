@@ -31,7 +31,7 @@
 
 use std::sync::Arc;
 
-use arrow::datatypes::{DataType, Schema};
+use arrow::datatypes::{DataType, Schema, TimeUnit};
 
 use super::{functions::Signature, PhysicalExpr};
 use crate::error::{DataFusionError, Result};
@@ -68,6 +68,31 @@ pub fn data_types(
     current_types: &Vec<DataType>,
     signature: &Signature,
 ) -> Result<Vec<DataType>> {
+    let valid_types = get_valid_types(signature, current_types)?;
+
+    if valid_types.contains(current_types) {
+        return Ok(current_types.clone());
+    }
+
+    for valid_types in valid_types {
+        if let Some(types) = maybe_data_types(&valid_types, &current_types) {
+            return Ok(types);
+        }
+    }
+
+    // none possible -> Error
+    Err(DataFusionError::Plan(format!(
+        "Coercion from {:?} to the signature {:?} failed.",
+        current_types, signature
+    )))
+}
+
+/// Returns the lists of argument types that `signature` accepts for
+/// arguments of types `current_types`, in order of preference.
+fn get_valid_types(
+    signature: &Signature,
+    current_types: &Vec<DataType>,
+) -> Result<Vec<Vec<DataType>>> {
     let valid_types = match signature {
         Signature::Variadic(valid_types) => valid_types
             .iter()
@@ -95,23 +120,18 @@ pub fn data_types(
             }
             vec![(0..*number).map(|i| current_types[i].clone()).collect()]
         }
-    };
-
-    if valid_types.contains(current_types) {
-        return Ok(current_types.clone());
-    }
-
-    for valid_types in valid_types {
-        if let Some(types) = maybe_data_types(&valid_types, &current_types) {
-            return Ok(types);
+        Signature::OneOf(signatures) => {
+            let mut valid_types = vec![];
+            for signature in signatures {
+                // alternatives that cannot accept these arguments are skipped
+                if let Ok(types) = get_valid_types(signature, current_types) {
+                    valid_types.extend(types);
+                }
+            }
+            valid_types
         }
-    }
-
-    // none possible -> Error
-    Err(DataFusionError::Plan(format!(
-        "Coercion from {:?} to the signature {:?} failed.",
-        current_types, signature
-    )))
+    };
+    Ok(valid_types)
 }
 
 /// Try to coerce current_types into valid_types.
@@ -193,7 +213,21 @@ pub fn can_coerce_from(type_into: &DataType, type_from: &DataType) -> bool {
             Float32 | Float64 => true,
             _ => false,
         },
-        Utf8 => true,
+        // `LargeUtf8` values may not fit the offsets of a `Utf8` array
+        Utf8 => match type_from {
+            LargeUtf8 => false,
+            _ => true,
+        },
+        LargeUtf8 => match type_from {
+            Utf8 | LargeUtf8 => true,
+            _ => false,
+        },
+        // timestamps of any unit are represented in nanoseconds since the epoch, while
+        // timestamps with a time zone are not coerced, as it would be dropped
+        Timestamp(TimeUnit::Nanosecond, None) => match type_from {
+            Timestamp(_, None) => true,
+            _ => false,
+        },
         _ => false,
     }
 }
@@ -313,6 +347,34 @@ mod tests {
                 Signature::Any(1),
                 vec![DataType::Float32],
             )?,
+            // exact match with the second alternative
+            case(
+                vec![DataType::LargeUtf8, DataType::Int64],
+                Signature::OneOf(vec![
+                    Signature::Exact(vec![DataType::Utf8, DataType::Int64]),
+                    Signature::Exact(vec![DataType::LargeUtf8, DataType::Int64]),
+                ]),
+                vec![DataType::LargeUtf8, DataType::Int64],
+            )?,
+            // utf8 -> large utf8, as the reverse could lose data
+            case(
+                vec![DataType::LargeUtf8, DataType::Utf8],
+                Signature::Uniform(2, vec![DataType::Utf8, DataType::LargeUtf8]),
+                vec![DataType::LargeUtf8, DataType::LargeUtf8],
+            )?,
+            // i32 -> i64, on the alternative with the matching number of arguments
+            case(
+                vec![DataType::Utf8, DataType::Int32, DataType::Int32],
+                Signature::OneOf(vec![
+                    Signature::Exact(vec![DataType::Utf8, DataType::Int64]),
+                    Signature::Exact(vec![
+                        DataType::Utf8,
+                        DataType::Int64,
+                        DataType::Int64,
+                    ]),
+                ]),
+                vec![DataType::Utf8, DataType::Int64, DataType::Int64],
+            )?,
         ];
 
         for case in cases {
@@ -343,6 +405,15 @@ mod tests {
             )?,
             // expected two arguments
             case(vec![DataType::UInt32], Signature::Any(2), vec![])?,
+            // no alternative accepts three arguments
+            case(
+                vec![DataType::Utf8, DataType::Int64, DataType::Int64],
+                Signature::OneOf(vec![
+                    Signature::Exact(vec![DataType::Utf8]),
+                    Signature::Any(2),
+                ]),
+                vec![],
+            )?,
         ];
 
         for case in cases {
@@ -28,6 +28,7 @@ use crate::{logical_plan::Expr, physical_plan::PhysicalExpr};
 use super::{
     functions::{
         ReturnTypeFunction, ScalarFunctionExpr, ScalarFunctionImplementation, Signature,
+        Volatility,
     },
     type_coercion::coerce,
 };
@@ -40,6 +41,8 @@ pub struct ScalarUDF {
     pub name: String,
     /// signature
     pub signature: Signature,
+    /// whether the UDF returns the same value for the same arguments
+    pub volatility: Volatility,
     /// Return type
     pub return_type: ReturnTypeFunction,
     /// actual implementation
@@ -51,6 +54,7 @@ impl Debug for ScalarUDF {
         f.debug_struct("ScalarUDF")
             .field("name", &self.name)
             .field("signature", &self.signature)
+            .field("volatility", &self.volatility)
             .field("fun", &"<FUNC>")
             .finish()
     }
@@ -61,12 +65,14 @@ impl ScalarUDF {
     pub fn new(
         name: &str,
         signature: &Signature,
+        volatility: Volatility,
         return_type: &ReturnTypeFunction,
         fun: &ScalarFunctionImplementation,
     ) -> Self {
         Self {
             name: name.to_owned(),
             signature: signature.clone(),
+            volatility,
             return_type: return_type.clone(),
             fun: fun.clone(),
         }
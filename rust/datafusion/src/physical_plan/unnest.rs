@@ -0,0 +1,328 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! UnnestExec produces a row for each element of the lists of a column of its input,
+//! repeating the other columns of the row of the list.
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::array_expressions::list_ranges;
+use super::metrics::{BaselineMetrics, MetricsSet};
+use super::{RecordBatchStream, SendableRecordBatchStream};
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::expressions::{Column, PhysicalSortExpr};
+use crate::physical_plan::{ExecutionPlan, Partitioning};
+use arrow::array::UInt32Array;
+use arrow::compute::take;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+
+use async_trait::async_trait;
+
+use futures::stream::{Stream, StreamExt};
+
+/// UnnestExec produces a row for each element of the lists of a column of its input,
+/// repeating the other columns of the row of the list. Rows whose list is empty or
+/// null produce no rows.
+#[derive(Debug)]
+pub struct UnnestExec {
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// The index of the list column in the schema of the input
+    column_index: usize,
+    /// The schema of the output, where the list column is replaced by the elements
+    schema: SchemaRef,
+    /// Execution metrics
+    metrics: BaselineMetrics,
+}
+
+impl UnnestExec {
+    /// Create an UnnestExec of the list column at `column_index` of `input`
+    pub fn try_new(input: Arc<dyn ExecutionPlan>, column_index: usize) -> Result<Self> {
+        let input_schema = input.schema();
+        let fields = input_schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                if i != column_index {
+                    return Ok(field.clone());
+                }
+                match field.data_type() {
+                    DataType::List(item)
+                    | DataType::LargeList(item)
+                    | DataType::FixedSizeList(item, _) => Ok(Field::new(
+                        field.name(),
+                        item.data_type().clone(),
+                        item.is_nullable(),
+                    )),
+                    other => Err(DataFusionError::Plan(format!(
+                        "Only list columns can be unnested, but {} is of type {:?}",
+                        field.name(),
+                        other
+                    ))),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if column_index >= fields.len() {
+            return Err(DataFusionError::Internal(format!(
+                "UnnestExec of column {} of an input of {} columns",
+                column_index,
+                fields.len()
+            )));
+        }
+
+        Ok(Self {
+            input,
+            column_index,
+            schema: Arc::new(Schema::new(fields)),
+            metrics: BaselineMetrics::new(),
+        })
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// The index of the list column in the schema of the input
+    pub fn column_index(&self) -> usize {
+        self.column_index
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for UnnestExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        // the rows stay in the order of the input, but the order of the elements
+        // of the lists is unknown: the ordering stops at the unnested column
+        let name = self.schema.field(self.column_index).name();
+        let ordering = self
+            .input
+            .output_ordering()?
+            .into_iter()
+            .take_while(|sort| match sort.expr.as_any().downcast_ref::<Column>() {
+                Some(column) => column.name() != name,
+                None => false,
+            })
+            .collect::<Vec<_>>();
+        if ordering.is_empty() {
+            None
+        } else {
+            Some(ordering)
+        }
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(UnnestExec::try_new(
+                children[0].clone(),
+                self.column_index,
+            )?)),
+            _ => Err(DataFusionError::Internal(
+                "UnnestExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        Ok(Box::pin(UnnestExecStream {
+            schema: self.schema.clone(),
+            column_index: self.column_index,
+            input: self.input.execute(partition).await?,
+            metrics: self.metrics.clone(),
+        }))
+    }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
+}
+
+/// The UnnestExec stream wraps the input stream and unnests each of its batches
+struct UnnestExecStream {
+    /// Output schema
+    schema: SchemaRef,
+    /// The index of the list column
+    column_index: usize,
+    /// The input partition to unnest
+    input: SendableRecordBatchStream,
+    /// Execution metrics
+    metrics: BaselineMetrics,
+}
+
+/// Unnests the list column at `column_index` of `batch`: the elements of the lists
+/// and the other columns are taken at the indices of the rows they belong to.
+fn batch_unnest(
+    batch: &RecordBatch,
+    column_index: usize,
+    schema: &SchemaRef,
+) -> Result<RecordBatch> {
+    let (values, ranges) = list_ranges(batch.column(column_index))?;
+    let mut row_indices = vec![];
+    let mut value_indices = vec![];
+    for (row, range) in ranges.into_iter().enumerate() {
+        if let Some(range) = range {
+            for i in range {
+                row_indices.push(row as u32);
+                value_indices.push(i as u32);
+            }
+        }
+    }
+    let row_indices = UInt32Array::from(row_indices);
+    let value_indices = UInt32Array::from(value_indices);
+
+    let columns = batch
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            if i == column_index {
+                take(&values, &value_indices, None)
+            } else {
+                take(column, &row_indices, None)
+            }
+        })
+        .collect::<ArrowResult<Vec<_>>>()?;
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+impl Stream for UnnestExecStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.input.poll_next_unpin(cx).map(|x| match x {
+            Some(Ok(batch)) => {
+                let _timer = self.metrics.elapsed_compute().timer();
+                Some(
+                    batch_unnest(&batch, self.column_index, &self.schema)
+                        .map_err(DataFusionError::into_arrow_external_error),
+                )
+            }
+            other => other,
+        });
+        self.metrics.record_poll(poll)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // same number of record batches
+        self.input.size_hint()
+    }
+}
+
+impl RecordBatchStream for UnnestExecStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::test;
+    use arrow::array::{Int32Array, Int32Builder, ListBuilder, StringArray};
+
+    fn lists() -> Result<Arc<dyn ExecutionPlan>> {
+        let mut builder = ListBuilder::new(Int32Builder::new(8));
+        // [1, 2], null, [], [3]
+        builder.values().append_value(1)?;
+        builder.values().append_value(2)?;
+        builder.append(true)?;
+        builder.append(false)?;
+        builder.append(true)?;
+        builder.values().append_value(3)?;
+        builder.append(true)?;
+        let lists = builder.finish();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("list", lists.data_type().clone(), true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["a", "b", "c", "d"])),
+                Arc::new(lists),
+            ],
+        )?;
+        Ok(Arc::new(MemoryExec::try_new(
+            &vec![vec![batch]],
+            schema,
+            None,
+        )?))
+    }
+
+    #[tokio::test]
+    async fn unnest_lists() -> Result<()> {
+        let unnest = Arc::new(UnnestExec::try_new(lists()?, 1)?);
+        assert_eq!(unnest.schema().field(1).data_type(), &DataType::Int32);
+
+        let results = test::execute(unnest).await?;
+        let rows = results
+            .iter()
+            .flat_map(test::format_batch)
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec!["a,1", "a,2", "d,3"]);
+
+        let values = results[0]
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .expect("expected an Int32Array");
+        assert_eq!(values.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn unnest_non_list() -> Result<()> {
+        let err = UnnestExec::try_new(lists()?, 0).expect_err("expected an error");
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Only list columns can be unnested, but id is of type Utf8"
+        );
+        Ok(())
+    }
+}
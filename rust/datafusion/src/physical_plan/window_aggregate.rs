@@ -0,0 +1,481 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the execution plan of the aggregation of a possibly unbounded input over
+//! the tumbling windows of its event time
+
+use std::any::Any;
+use std::collections::{BTreeMap, VecDeque};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::hash_aggregate::{
+    aggregate_expressions, create_batch_from_map, create_schema, group_aggregate_batch,
+    Accumulators, AggregateMode,
+};
+use super::metrics::{BaselineMetrics, MetricsSet, ObservedStream};
+use super::{
+    AggregateExpr, Distribution, ExecutionPlan, Partitioning, PhysicalExpr,
+    RecordBatchStream, SendableRecordBatchStream,
+};
+use crate::error::{DataFusionError, Result};
+use arrow::array::{Array, TimestampNanosecondArray, UInt32Array};
+use arrow::compute;
+use arrow::datatypes::{DataType, Schema, SchemaRef, TimeUnit};
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+
+/// Execution plan of an aggregation grouped by the tumbling window of each row, the
+/// start of which is one of its group expressions, e.g. `tumble(ts, INTERVAL '1
+/// minute')`.
+///
+/// Unlike [HashAggregateExec](super::hash_aggregate::HashAggregateExec), the groups
+/// of a window are output as soon as the window is closed, rather than at the end of
+/// the input, so that the aggregation of an unbounded input produces results. The
+/// rows are expected in the order of their windows: a window is closed when a row of
+/// a later window arrives, and the rows of windows that are closed, or that are of
+/// no window, are dropped. The windows that are still open are output at the end of
+/// the input.
+#[derive(Debug)]
+pub struct WindowAggregateExec {
+    group_expr: Vec<(Arc<dyn PhysicalExpr>, String)>,
+    /// The index of the group expression of the start of the window of each row
+    window_index: usize,
+    aggr_expr: Vec<Arc<dyn AggregateExpr>>,
+    input: Arc<dyn ExecutionPlan>,
+    schema: SchemaRef,
+    /// Input schema, against which the expressions of the aggregates are evaluated
+    input_schema: SchemaRef,
+    /// Execution metrics
+    metrics: BaselineMetrics,
+}
+
+impl WindowAggregateExec {
+    /// Create a new execution plan that aggregates `input` over the windows that start
+    /// at the values of the group expression at `window_index`, a timestamp
+    pub fn try_new(
+        group_expr: Vec<(Arc<dyn PhysicalExpr>, String)>,
+        window_index: usize,
+        aggr_expr: Vec<Arc<dyn AggregateExpr>>,
+        input: Arc<dyn ExecutionPlan>,
+        input_schema: SchemaRef,
+    ) -> Result<Self> {
+        let window_type = match group_expr.get(window_index) {
+            Some((expr, _)) => expr.data_type(&input.schema())?,
+            None => {
+                return Err(DataFusionError::Internal(format!(
+                    "The window index {} is out of the {} group expressions",
+                    window_index,
+                    group_expr.len()
+                )))
+            }
+        };
+        if window_type != DataType::Timestamp(TimeUnit::Nanosecond, None) {
+            return Err(DataFusionError::Plan(format!(
+                "The windows of an aggregation must start at timestamps, not {:?}",
+                window_type
+            )));
+        }
+
+        let schema = create_schema(
+            &input.schema(),
+            &group_expr,
+            &aggr_expr,
+            AggregateMode::Final,
+        )?;
+
+        Ok(Self {
+            group_expr,
+            window_index,
+            aggr_expr,
+            input,
+            schema: Arc::new(schema),
+            input_schema,
+            metrics: BaselineMetrics::new(),
+        })
+    }
+
+    /// Grouping expressions
+    pub fn group_expr(&self) -> &[(Arc<dyn PhysicalExpr>, String)] {
+        &self.group_expr
+    }
+
+    /// The index of the group expression of the start of the window of each row
+    pub fn window_index(&self) -> usize {
+        self.window_index
+    }
+
+    /// Aggregate expressions
+    pub fn aggr_expr(&self) -> &[Arc<dyn AggregateExpr>] {
+        &self.aggr_expr
+    }
+
+    /// Input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for WindowAggregateExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::SinglePartition
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(WindowAggregateExec::try_new(
+                self.group_expr.clone(),
+                self.window_index,
+                self.aggr_expr.clone(),
+                children[0].clone(),
+                self.input_schema.clone(),
+            )?)),
+            _ => Err(DataFusionError::Internal(
+                "WindowAggregateExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    async fn execute(&self, partition: usize) -> Result<SendableRecordBatchStream> {
+        let input = self.input.execute(partition).await?;
+
+        // the windows are not keys of the accumulators, nor columns of their batches
+        let group_expr = self
+            .group_expr
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != self.window_index)
+            .map(|(_, (expr, _))| expr.clone())
+            .collect();
+        let window_schema = Schema::new(
+            self.schema
+                .fields()
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != self.window_index)
+                .map(|(_, field)| field.clone())
+                .collect(),
+        );
+
+        let stream = WindowAggregateStream {
+            schema: self.schema.clone(),
+            window_schema,
+            group_expr,
+            window_expr: self.group_expr[self.window_index].0.clone(),
+            window_index: self.window_index,
+            aggregate_expressions: aggregate_expressions(
+                &self.aggr_expr,
+                &AggregateMode::Partial,
+            )?,
+            aggr_expr: self.aggr_expr.clone(),
+            input,
+            windows: BTreeMap::new(),
+            watermark: None,
+            output: VecDeque::new(),
+            finished: false,
+            metrics: self.metrics.clone(),
+        };
+        Ok(Box::pin(ObservedStream::counting(
+            Box::pin(stream),
+            self.metrics.clone(),
+        )))
+    }
+
+    fn metrics(&self) -> MetricsSet {
+        self.metrics.metrics()
+    }
+}
+
+/// Stream of the groups of each window of a [WindowAggregateExec], output when the
+/// window is closed
+struct WindowAggregateStream {
+    schema: SchemaRef,
+    /// The schema of the output, without the column of the window
+    window_schema: Schema,
+    /// The group expressions other than the window
+    group_expr: Vec<Arc<dyn PhysicalExpr>>,
+    window_expr: Arc<dyn PhysicalExpr>,
+    window_index: usize,
+    aggr_expr: Vec<Arc<dyn AggregateExpr>>,
+    aggregate_expressions: Vec<Vec<Arc<dyn PhysicalExpr>>>,
+    input: SendableRecordBatchStream,
+    /// The accumulators of the groups of each open window, by the start of the window
+    windows: BTreeMap<i64, Accumulators>,
+    /// The start of the latest window, before which windows are closed
+    watermark: Option<i64>,
+    /// The batches of the windows that are closed, which have not been output yet
+    output: VecDeque<RecordBatch>,
+    finished: bool,
+    metrics: BaselineMetrics,
+}
+
+impl WindowAggregateStream {
+    /// Aggregates the rows of `batch` into the groups of their windows, and closes the
+    /// windows that start before the latest one
+    fn aggregate_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        let starts = self.window_expr.evaluate(&batch)?;
+        let starts = starts
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(
+                    "The windows of the aggregation do not start at timestamps"
+                        .to_string(),
+                )
+            })?;
+
+        // the indices of the rows of each window
+        let mut windows = BTreeMap::<i64, Vec<u32>>::new();
+        for row in 0..starts.len() {
+            if starts.is_null(row) {
+                continue;
+            }
+            let start = starts.value(row);
+            match self.watermark {
+                Some(watermark) if start < watermark => {}
+                _ => windows
+                    .entry(start)
+                    .or_insert_with(Vec::new)
+                    .push(row as u32),
+            }
+        }
+
+        for (start, indices) in windows {
+            let indices = UInt32Array::from(indices);
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|column| compute::take(column, &indices, None))
+                .collect::<ArrowResult<Vec<_>>>()?;
+            let rows = RecordBatch::try_new(batch.schema(), columns)?;
+
+            let accumulators = self.windows.remove(&start).unwrap_or_default();
+            let accumulators = group_aggregate_batch(
+                &AggregateMode::Partial,
+                &self.group_expr,
+                &self.aggr_expr,
+                rows,
+                accumulators,
+                &self.aggregate_expressions,
+            )?;
+            self.windows.insert(start, accumulators);
+            self.watermark = Some(self.watermark.map_or(start, |w| w.max(start)));
+        }
+
+        self.close_windows(self.watermark)
+    }
+
+    /// Outputs the groups of the windows that start before `end`, or of all the
+    /// windows if `end` is `None`
+    fn close_windows(&mut self, end: Option<i64>) -> Result<()> {
+        let open = match end {
+            Some(end) => self.windows.split_off(&end),
+            None => BTreeMap::new(),
+        };
+        let closed = std::mem::replace(&mut self.windows, open);
+
+        for (start, accumulators) in closed {
+            let batch = create_batch_from_map(
+                &AggregateMode::Final,
+                &accumulators,
+                self.group_expr.len(),
+                &self.window_schema,
+            )?;
+            let mut columns = batch.columns().to_vec();
+            columns.insert(
+                self.window_index,
+                Arc::new(TimestampNanosecondArray::from_vec(
+                    vec![start; batch.num_rows()],
+                    None,
+                )),
+            );
+            self.output
+                .push_back(RecordBatch::try_new(self.schema.clone(), columns)?);
+        }
+        Ok(())
+    }
+}
+
+impl Stream for WindowAggregateStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(batch) = self.output.pop_front() {
+                return Poll::Ready(Some(Ok(batch)));
+            }
+            if self.finished {
+                return Poll::Ready(None);
+            }
+
+            let result = match self.input.poll_next_unpin(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(Some(Ok(batch))) => {
+                    let metrics = self.metrics.clone();
+                    let _timer = metrics.elapsed_compute().timer();
+                    self.aggregate_batch(batch)
+                }
+                Poll::Ready(None) => {
+                    self.finished = true;
+                    let metrics = self.metrics.clone();
+                    let _timer = metrics.elapsed_compute().timer();
+                    self.close_windows(None)
+                }
+            };
+            if let Err(e) = result {
+                self.finished = true;
+                return Poll::Ready(Some(Err(
+                    DataFusionError::into_arrow_external_error(e),
+                )));
+            }
+        }
+    }
+}
+
+impl RecordBatchStream for WindowAggregateStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::common;
+    use crate::physical_plan::expressions::{col, Sum};
+    use crate::physical_plan::memory::MemoryExec;
+    use arrow::array::Int64Array;
+    use arrow::datatypes::Field;
+
+    #[tokio::test]
+    async fn aggregate_windows() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("k", DataType::UInt32, false),
+            Field::new("ts", DataType::Timestamp(TimeUnit::Nanosecond, None), false),
+            Field::new("v", DataType::Int64, false),
+        ]));
+        let batch = |k: Vec<u32>, ts: Vec<i64>, v: Vec<i64>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(UInt32Array::from(k)),
+                    Arc::new(TimestampNanosecondArray::from_vec(ts, None)),
+                    Arc::new(Int64Array::from(v)),
+                ],
+            )
+        };
+        let batches = vec![
+            batch(vec![1, 2, 1], vec![0, 0, 0], vec![1, 2, 3])?,
+            batch(vec![1, 1, 2], vec![60, 60, 0], vec![4, 5, 6])?,
+        ];
+        let input = Arc::new(MemoryExec::try_new(&vec![batches], schema.clone(), None)?);
+
+        let exec = WindowAggregateExec::try_new(
+            vec![(col("k"), "k".to_string()), (col("ts"), "ts".to_string())],
+            1,
+            vec![Arc::new(Sum::new(
+                col("v"),
+                "SUM(v)".to_string(),
+                DataType::Int64,
+            ))],
+            input,
+            schema,
+        )?;
+        let result = common::collect(exec.execute(0).await?).await?;
+
+        // the groups of each window, sorted by key
+        let groups = result
+            .iter()
+            .map(|batch| {
+                let k = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<UInt32Array>()
+                    .unwrap();
+                let ts = batch
+                    .column(1)
+                    .as_any()
+                    .downcast_ref::<TimestampNanosecondArray>()
+                    .unwrap();
+                let v = batch
+                    .column(2)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap();
+                let mut groups = (0..batch.num_rows())
+                    .map(|i| (k.value(i), ts.value(i), v.value(i)))
+                    .collect::<Vec<_>>();
+                groups.sort();
+                groups
+            })
+            .collect::<Vec<_>>();
+
+        // the row of the first window in the second batch is aggregated, as the
+        // window is closed after it
+        assert_eq!(groups, vec![vec![(1, 0, 4), (2, 0, 8)], vec![(1, 60, 9)]]);
+        Ok(())
+    }
+
+    #[test]
+    fn window_of_timestamps() -> Result<()> {
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("k", DataType::UInt32, false)]));
+        let input = Arc::new(MemoryExec::try_new(&vec![], schema.clone(), None)?);
+        let err = WindowAggregateExec::try_new(
+            vec![(col("k"), "k".to_string())],
+            0,
+            vec![],
+            input,
+            schema,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: The windows of an aggregation must start at \
+             timestamps, not UInt32"
+        );
+        Ok(())
+    }
+}
@@ -27,7 +27,15 @@
 
 pub use crate::dataframe::DataFrame;
 pub use crate::execution::context::{ExecutionConfig, ExecutionContext};
+pub use crate::execution::prepared_statement::PreparedStatement;
 pub use crate::logical_plan::{
-    array, avg, col, concat, count, create_udf, length, lit, max, min, sum,
+    array, ascii, avg, bit_length, btrim, chr, coalesce, col, concat, concat_ws, count,
+    create_udf, create_udtf, date_part, date_trunc, initcap, left, length, lit, lower,
+    lpad, ltrim, max, md5, min, now, nullif, octet_length, regexp_match, regexp_replace,
+    repeat, replace, reverse, right, rpad, rtrim, sha256, split_part, starts_with,
+    strpos, substr, sum, to_char, trim, tumble, upper, JoinType, SampleMethod,
 };
 pub use crate::physical_plan::csv::CsvReadOptions;
+pub use crate::physical_plan::functions::{
+    make_scalar_function, ColumnarValue, Volatility,
+};
@@ -17,20 +17,32 @@
 
 //! This module provides ScalarValue, an enum that can be used for storage of single elements
 
-use std::{convert::TryFrom, fmt, sync::Arc};
+use std::{cmp::Ordering, convert::TryFrom, fmt, sync::Arc};
 
 use arrow::array::{
-    Array, BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
-    Int8Array, LargeStringArray, ListArray, StringArray, UInt16Array, UInt32Array,
+    Array, BinaryArray, BooleanArray, Date32Array, Date64Array, DictionaryArray,
+    DurationMicrosecondArray, DurationMillisecondArray, DurationNanosecondArray,
+    DurationSecondArray, FixedSizeBinaryArray, Float32Array, Float64Array, Int16Array,
+    Int32Array, Int64Array, Int8Array, IntervalDayTimeArray, IntervalYearMonthArray,
+    LargeBinaryArray, LargeStringArray, ListArray, StringArray, StructArray,
+    Time32MillisecondArray, Time32SecondArray, Time64MicrosecondArray,
+    Time64NanosecondArray, TimestampMicrosecondArray, TimestampMillisecondArray,
+    TimestampNanosecondArray, TimestampSecondArray, UInt16Array, UInt32Array,
     UInt64Array, UInt8Array,
 };
 use arrow::array::{
-    Int16Builder, Int32Builder, Int64Builder, Int8Builder, ListBuilder, UInt16Builder,
-    UInt32Builder, UInt64Builder, UInt8Builder,
+    FixedSizeBinaryBuilder, Float32Builder, Float64Builder, Int16Builder, Int32Builder,
+    Int64Builder, Int8Builder, ListBuilder, UInt16Builder, UInt32Builder, UInt64Builder,
+    UInt8Builder,
 };
+use arrow::buffer::MutableBuffer;
 use arrow::{
     array::ArrayRef,
-    datatypes::{DataType, Field},
+    datatypes::{
+        ArrowDictionaryKeyType, ArrowNativeType, DataType, DateUnit, Field, Int16Type,
+        Int32Type, Int64Type, Int8Type, IntervalUnit, TimeUnit, UInt16Type, UInt32Type,
+        UInt64Type, UInt8Type,
+    },
 };
 
 use crate::error::{DataFusionError, Result};
@@ -65,44 +77,91 @@ pub enum ScalarValue {
     Utf8(Option<String>),
     /// utf-8 encoded string representing a LargeString's arrow type.
     LargeUtf8(Option<String>),
+    /// binary
+    Binary(Option<Vec<u8>>),
+    /// binary representing a LargeBinary's arrow type.
+    LargeBinary(Option<Vec<u8>>),
+    /// binary of the given number of bytes
+    FixedSizeBinary(Option<Vec<u8>>, i32),
     /// list of nested ScalarValue
     List(Option<Vec<ScalarValue>>, DataType),
+    /// struct of nested ScalarValue, one for each of the fields
+    Struct(Option<Vec<ScalarValue>>, Vec<Field>),
+    /// date in days since the UNIX epoch
+    Date32(Option<i32>),
+    /// date in milliseconds since the UNIX epoch
+    Date64(Option<i64>),
+    /// time in seconds since midnight
+    Time32Second(Option<i32>),
+    /// time in milliseconds since midnight
+    Time32Millisecond(Option<i32>),
+    /// time in microseconds since midnight
+    Time64Microsecond(Option<i64>),
+    /// time in nanoseconds since midnight
+    Time64Nanosecond(Option<i64>),
+    /// timestamp in seconds since the UNIX epoch, with an optional timezone
+    TimestampSecond(Option<i64>, Option<Arc<String>>),
+    /// timestamp in milliseconds since the UNIX epoch, with an optional timezone
+    TimestampMillisecond(Option<i64>, Option<Arc<String>>),
+    /// timestamp in microseconds since the UNIX epoch, with an optional timezone
+    TimestampMicrosecond(Option<i64>, Option<Arc<String>>),
+    /// timestamp in nanoseconds since the UNIX epoch, with an optional timezone
+    TimestampNanosecond(Option<i64>, Option<Arc<String>>),
+    /// interval of a number of months
+    IntervalYearMonth(Option<i32>),
+    /// interval of a number of days (upper 32 bits) and milliseconds (lower 32 bits)
+    IntervalDayTime(Option<i64>),
+    /// duration in seconds
+    DurationSecond(Option<i64>),
+    /// duration in milliseconds
+    DurationMillisecond(Option<i64>),
+    /// duration in microseconds
+    DurationMicrosecond(Option<i64>),
+    /// duration in nanoseconds
+    DurationNanosecond(Option<i64>),
 }
 
 macro_rules! typed_cast {
-    ($array:expr, $index:expr, $ARRAYTYPE:ident, $SCALAR:ident) => {{
+    ($array:expr, $index:expr, $ARRAYTYPE:ident, $SCALAR:ident $(, $EXTRA:expr)*) => {{
         let array = $array.as_any().downcast_ref::<$ARRAYTYPE>().unwrap();
-        ScalarValue::$SCALAR(match array.is_null($index) {
-            true => None,
-            false => Some(array.value($index).into()),
-        })
+        ScalarValue::$SCALAR(
+            match array.is_null($index) {
+                true => None,
+                false => Some(array.value($index).into()),
+            }
+            $(, $EXTRA)*
+        )
     }};
 }
 
 macro_rules! build_list {
-    ($VALUE_BUILDER_TY:ident, $SCALAR_TY:ident, $VALUES:expr) => {{
+    ($VALUE_BUILDER_TY:ident, $SCALAR_TY:ident, $VALUES:expr, $SIZE:expr) => {{
         match $VALUES {
             None => {
                 let mut builder = ListBuilder::new($VALUE_BUILDER_TY::new(0));
-                builder.append(false).unwrap();
+                for _ in 0..$SIZE {
+                    builder.append(false).unwrap();
+                }
                 builder.finish()
             }
             Some(values) => {
-                let mut builder = ListBuilder::new($VALUE_BUILDER_TY::new(values.len()));
-
-                for scalar_value in values {
-                    match scalar_value {
-                        ScalarValue::$SCALAR_TY(Some(v)) => {
-                            builder.values().append_value(*v).unwrap()
-                        }
-                        ScalarValue::$SCALAR_TY(None) => {
-                            builder.values().append_null().unwrap();
-                        }
-                        _ => panic!("Incompatible ScalarValue for list"),
-                    };
-                }
+                let mut builder =
+                    ListBuilder::new($VALUE_BUILDER_TY::new(values.len() * $SIZE));
 
-                builder.append(true).unwrap();
+                for _ in 0..$SIZE {
+                    for scalar_value in values {
+                        match scalar_value {
+                            ScalarValue::$SCALAR_TY(Some(v)) => {
+                                builder.values().append_value(*v).unwrap()
+                            }
+                            ScalarValue::$SCALAR_TY(None) => {
+                                builder.values().append_null().unwrap();
+                            }
+                            _ => panic!("Incompatible ScalarValue for list"),
+                        };
+                    }
+                    builder.append(true).unwrap();
+                }
                 builder.finish()
             }
         }
@@ -126,9 +185,45 @@ impl ScalarValue {
             ScalarValue::Float64(_) => DataType::Float64,
             ScalarValue::Utf8(_) => DataType::Utf8,
             ScalarValue::LargeUtf8(_) => DataType::LargeUtf8,
+            ScalarValue::Binary(_) => DataType::Binary,
+            ScalarValue::LargeBinary(_) => DataType::LargeBinary,
+            ScalarValue::FixedSizeBinary(_, size) => DataType::FixedSizeBinary(*size),
             ScalarValue::List(_, data_type) => {
                 DataType::List(Box::new(Field::new("item", data_type.clone(), true)))
             }
+            ScalarValue::Struct(_, fields) => DataType::Struct(fields.clone()),
+            ScalarValue::Date32(_) => DataType::Date32(DateUnit::Day),
+            ScalarValue::Date64(_) => DataType::Date64(DateUnit::Millisecond),
+            ScalarValue::Time32Second(_) => DataType::Time32(TimeUnit::Second),
+            ScalarValue::Time32Millisecond(_) => DataType::Time32(TimeUnit::Millisecond),
+            ScalarValue::Time64Microsecond(_) => DataType::Time64(TimeUnit::Microsecond),
+            ScalarValue::Time64Nanosecond(_) => DataType::Time64(TimeUnit::Nanosecond),
+            ScalarValue::TimestampSecond(_, tz) => {
+                DataType::Timestamp(TimeUnit::Second, tz.clone())
+            }
+            ScalarValue::TimestampMillisecond(_, tz) => {
+                DataType::Timestamp(TimeUnit::Millisecond, tz.clone())
+            }
+            ScalarValue::TimestampMicrosecond(_, tz) => {
+                DataType::Timestamp(TimeUnit::Microsecond, tz.clone())
+            }
+            ScalarValue::TimestampNanosecond(_, tz) => {
+                DataType::Timestamp(TimeUnit::Nanosecond, tz.clone())
+            }
+            ScalarValue::IntervalYearMonth(_) => {
+                DataType::Interval(IntervalUnit::YearMonth)
+            }
+            ScalarValue::IntervalDayTime(_) => DataType::Interval(IntervalUnit::DayTime),
+            ScalarValue::DurationSecond(_) => DataType::Duration(TimeUnit::Second),
+            ScalarValue::DurationMillisecond(_) => {
+                DataType::Duration(TimeUnit::Millisecond)
+            }
+            ScalarValue::DurationMicrosecond(_) => {
+                DataType::Duration(TimeUnit::Microsecond)
+            }
+            ScalarValue::DurationNanosecond(_) => {
+                DataType::Duration(TimeUnit::Nanosecond)
+            }
         }
     }
 
@@ -148,44 +243,158 @@ impl ScalarValue {
             | ScalarValue::Float64(None)
             | ScalarValue::Utf8(None)
             | ScalarValue::LargeUtf8(None)
-            | ScalarValue::List(None, _) => true,
+            | ScalarValue::Binary(None)
+            | ScalarValue::LargeBinary(None)
+            | ScalarValue::FixedSizeBinary(None, _)
+            | ScalarValue::List(None, _)
+            | ScalarValue::Struct(None, _)
+            | ScalarValue::Date32(None)
+            | ScalarValue::Date64(None)
+            | ScalarValue::Time32Second(None)
+            | ScalarValue::Time32Millisecond(None)
+            | ScalarValue::Time64Microsecond(None)
+            | ScalarValue::Time64Nanosecond(None)
+            | ScalarValue::TimestampSecond(None, _)
+            | ScalarValue::TimestampMillisecond(None, _)
+            | ScalarValue::TimestampMicrosecond(None, _)
+            | ScalarValue::TimestampNanosecond(None, _)
+            | ScalarValue::IntervalYearMonth(None)
+            | ScalarValue::IntervalDayTime(None)
+            | ScalarValue::DurationSecond(None)
+            | ScalarValue::DurationMillisecond(None)
+            | ScalarValue::DurationMicrosecond(None)
+            | ScalarValue::DurationNanosecond(None) => true,
             _ => false,
         }
     }
 
     /// Converts a scalar value into an 1-row array.
     pub fn to_array(&self) -> ArrayRef {
+        self.to_array_of_size(1)
+    }
+
+    /// Converts a scalar value into an array of `size` rows, each of them the value.
+    pub fn to_array_of_size(&self, size: usize) -> ArrayRef {
         match self {
-            ScalarValue::Boolean(e) => Arc::new(BooleanArray::from(vec![*e])) as ArrayRef,
-            ScalarValue::Float64(e) => Arc::new(Float64Array::from(vec![*e])) as ArrayRef,
-            ScalarValue::Float32(e) => Arc::new(Float32Array::from(vec![*e])),
-            ScalarValue::Int8(e) => Arc::new(Int8Array::from(vec![*e])),
-            ScalarValue::Int16(e) => Arc::new(Int16Array::from(vec![*e])),
-            ScalarValue::Int32(e) => Arc::new(Int32Array::from(vec![*e])),
-            ScalarValue::Int64(e) => Arc::new(Int64Array::from(vec![*e])),
-            ScalarValue::UInt8(e) => Arc::new(UInt8Array::from(vec![*e])),
-            ScalarValue::UInt16(e) => Arc::new(UInt16Array::from(vec![*e])),
-            ScalarValue::UInt32(e) => Arc::new(UInt32Array::from(vec![*e])),
-            ScalarValue::UInt64(e) => Arc::new(UInt64Array::from(vec![*e])),
-            ScalarValue::Utf8(e) => Arc::new(StringArray::from(vec![e.as_deref()])),
+            ScalarValue::Boolean(e) => {
+                Arc::new(BooleanArray::from(vec![*e; size])) as ArrayRef
+            }
+            ScalarValue::Float64(e) => Arc::new(Float64Array::from(vec![*e; size])),
+            ScalarValue::Float32(e) => Arc::new(Float32Array::from(vec![*e; size])),
+            ScalarValue::Int8(e) => Arc::new(Int8Array::from(vec![*e; size])),
+            ScalarValue::Int16(e) => Arc::new(Int16Array::from(vec![*e; size])),
+            ScalarValue::Int32(e) => Arc::new(Int32Array::from(vec![*e; size])),
+            ScalarValue::Int64(e) => Arc::new(Int64Array::from(vec![*e; size])),
+            ScalarValue::UInt8(e) => Arc::new(UInt8Array::from(vec![*e; size])),
+            ScalarValue::UInt16(e) => Arc::new(UInt16Array::from(vec![*e; size])),
+            ScalarValue::UInt32(e) => Arc::new(UInt32Array::from(vec![*e; size])),
+            ScalarValue::UInt64(e) => Arc::new(UInt64Array::from(vec![*e; size])),
+            ScalarValue::Utf8(e) => Arc::new(StringArray::from(vec![e.as_deref(); size])),
             ScalarValue::LargeUtf8(e) => {
-                Arc::new(LargeStringArray::from(vec![e.as_deref()]))
+                Arc::new(LargeStringArray::from(vec![e.as_deref(); size]))
+            }
+            ScalarValue::Binary(e) => {
+                Arc::new(BinaryArray::from(vec![e.as_deref(); size]))
+            }
+            ScalarValue::LargeBinary(e) => {
+                Arc::new(LargeBinaryArray::from(vec![e.as_deref(); size]))
+            }
+            ScalarValue::FixedSizeBinary(e, byte_width) => {
+                let mut builder = FixedSizeBinaryBuilder::new(size, *byte_width);
+                for _ in 0..size {
+                    match e {
+                        Some(value) => builder.append_value(value).unwrap(),
+                        None => builder.append_null().unwrap(),
+                    }
+                }
+                Arc::new(builder.finish())
             }
             ScalarValue::List(values, data_type) => Arc::new(match data_type {
-                DataType::Int8 => build_list!(Int8Builder, Int8, values),
-                DataType::Int16 => build_list!(Int16Builder, Int16, values),
-                DataType::Int32 => build_list!(Int32Builder, Int32, values),
-                DataType::Int64 => build_list!(Int64Builder, Int64, values),
-                DataType::UInt8 => build_list!(UInt8Builder, UInt8, values),
-                DataType::UInt16 => build_list!(UInt16Builder, UInt16, values),
-                DataType::UInt32 => build_list!(UInt32Builder, UInt32, values),
-                DataType::UInt64 => build_list!(UInt64Builder, UInt64, values),
+                DataType::Int8 => build_list!(Int8Builder, Int8, values, size),
+                DataType::Int16 => build_list!(Int16Builder, Int16, values, size),
+                DataType::Int32 => build_list!(Int32Builder, Int32, values, size),
+                DataType::Int64 => build_list!(Int64Builder, Int64, values, size),
+                DataType::UInt8 => build_list!(UInt8Builder, UInt8, values, size),
+                DataType::UInt16 => build_list!(UInt16Builder, UInt16, values, size),
+                DataType::UInt32 => build_list!(UInt32Builder, UInt32, values, size),
+                DataType::UInt64 => build_list!(UInt64Builder, UInt64, values, size),
+                DataType::Float32 => build_list!(Float32Builder, Float32, values, size),
+                DataType::Float64 => build_list!(Float64Builder, Float64, values, size),
                 _ => panic!("Unexpected DataType for list"),
             }),
+            ScalarValue::Struct(values, fields) => {
+                let columns = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        let column = match values {
+                            Some(values) => values[i].to_array_of_size(size),
+                            // the fields of a null struct are null
+                            None => ScalarValue::try_from(field.data_type())
+                                .expect("Unexpected DataType for struct field")
+                                .to_array_of_size(size),
+                        };
+                        (field.clone(), column)
+                    })
+                    .collect::<Vec<_>>();
+                match values {
+                    Some(_) => Arc::new(StructArray::from(columns)),
+                    None => Arc::new(StructArray::from((
+                        columns,
+                        MutableBuffer::new_null(size).freeze(),
+                        size,
+                    ))),
+                }
+            }
+            ScalarValue::Date32(e) => Arc::new(Date32Array::from(vec![*e; size])),
+            ScalarValue::Date64(e) => Arc::new(Date64Array::from(vec![*e; size])),
+            ScalarValue::Time32Second(e) => {
+                Arc::new(Time32SecondArray::from(vec![*e; size]))
+            }
+            ScalarValue::Time32Millisecond(e) => {
+                Arc::new(Time32MillisecondArray::from(vec![*e; size]))
+            }
+            ScalarValue::Time64Microsecond(e) => {
+                Arc::new(Time64MicrosecondArray::from(vec![*e; size]))
+            }
+            ScalarValue::Time64Nanosecond(e) => {
+                Arc::new(Time64NanosecondArray::from(vec![*e; size]))
+            }
+            ScalarValue::TimestampSecond(e, tz) => Arc::new(
+                TimestampSecondArray::from_opt_vec(vec![*e; size], tz.clone()),
+            ),
+            ScalarValue::TimestampMillisecond(e, tz) => Arc::new(
+                TimestampMillisecondArray::from_opt_vec(vec![*e; size], tz.clone()),
+            ),
+            ScalarValue::TimestampMicrosecond(e, tz) => Arc::new(
+                TimestampMicrosecondArray::from_opt_vec(vec![*e; size], tz.clone()),
+            ),
+            ScalarValue::TimestampNanosecond(e, tz) => Arc::new(
+                TimestampNanosecondArray::from_opt_vec(vec![*e; size], tz.clone()),
+            ),
+            ScalarValue::IntervalYearMonth(e) => {
+                Arc::new(IntervalYearMonthArray::from(vec![*e; size]))
+            }
+            ScalarValue::IntervalDayTime(e) => {
+                Arc::new(IntervalDayTimeArray::from(vec![*e; size]))
+            }
+            ScalarValue::DurationSecond(e) => {
+                Arc::new(DurationSecondArray::from(vec![*e; size]))
+            }
+            ScalarValue::DurationMillisecond(e) => {
+                Arc::new(DurationMillisecondArray::from(vec![*e; size]))
+            }
+            ScalarValue::DurationMicrosecond(e) => {
+                Arc::new(DurationMicrosecondArray::from(vec![*e; size]))
+            }
+            ScalarValue::DurationNanosecond(e) => {
+                Arc::new(DurationNanosecondArray::from(vec![*e; size]))
+            }
         }
     }
 
-    /// Converts a value in `array` at `index` into a ScalarValue
+    /// Converts a value in `array` at `index` into a ScalarValue. The value of a
+    /// dictionary array is the value its key refers to.
     pub fn try_from_array(array: &ArrayRef, index: usize) -> Result<Self> {
         Ok(match array.data_type() {
             DataType::Boolean => typed_cast!(array, index, BooleanArray, Boolean),
@@ -201,6 +410,77 @@ impl ScalarValue {
             DataType::Int8 => typed_cast!(array, index, Int8Array, Int8),
             DataType::Utf8 => typed_cast!(array, index, StringArray, Utf8),
             DataType::LargeUtf8 => typed_cast!(array, index, LargeStringArray, LargeUtf8),
+            DataType::Binary => typed_cast!(array, index, BinaryArray, Binary),
+            DataType::LargeBinary => {
+                typed_cast!(array, index, LargeBinaryArray, LargeBinary)
+            }
+            DataType::FixedSizeBinary(size) => {
+                typed_cast!(array, index, FixedSizeBinaryArray, FixedSizeBinary, *size)
+            }
+            DataType::Date32(DateUnit::Day) => {
+                typed_cast!(array, index, Date32Array, Date32)
+            }
+            DataType::Date64(DateUnit::Millisecond) => {
+                typed_cast!(array, index, Date64Array, Date64)
+            }
+            DataType::Time32(TimeUnit::Second) => {
+                typed_cast!(array, index, Time32SecondArray, Time32Second)
+            }
+            DataType::Time32(TimeUnit::Millisecond) => {
+                typed_cast!(array, index, Time32MillisecondArray, Time32Millisecond)
+            }
+            DataType::Time64(TimeUnit::Microsecond) => {
+                typed_cast!(array, index, Time64MicrosecondArray, Time64Microsecond)
+            }
+            DataType::Time64(TimeUnit::Nanosecond) => {
+                typed_cast!(array, index, Time64NanosecondArray, Time64Nanosecond)
+            }
+            DataType::Timestamp(TimeUnit::Second, tz) => typed_cast!(
+                array,
+                index,
+                TimestampSecondArray,
+                TimestampSecond,
+                tz.clone()
+            ),
+            DataType::Timestamp(TimeUnit::Millisecond, tz) => typed_cast!(
+                array,
+                index,
+                TimestampMillisecondArray,
+                TimestampMillisecond,
+                tz.clone()
+            ),
+            DataType::Timestamp(TimeUnit::Microsecond, tz) => typed_cast!(
+                array,
+                index,
+                TimestampMicrosecondArray,
+                TimestampMicrosecond,
+                tz.clone()
+            ),
+            DataType::Timestamp(TimeUnit::Nanosecond, tz) => typed_cast!(
+                array,
+                index,
+                TimestampNanosecondArray,
+                TimestampNanosecond,
+                tz.clone()
+            ),
+            DataType::Interval(IntervalUnit::YearMonth) => {
+                typed_cast!(array, index, IntervalYearMonthArray, IntervalYearMonth)
+            }
+            DataType::Interval(IntervalUnit::DayTime) => {
+                typed_cast!(array, index, IntervalDayTimeArray, IntervalDayTime)
+            }
+            DataType::Duration(TimeUnit::Second) => {
+                typed_cast!(array, index, DurationSecondArray, DurationSecond)
+            }
+            DataType::Duration(TimeUnit::Millisecond) => {
+                typed_cast!(array, index, DurationMillisecondArray, DurationMillisecond)
+            }
+            DataType::Duration(TimeUnit::Microsecond) => {
+                typed_cast!(array, index, DurationMicrosecondArray, DurationMicrosecond)
+            }
+            DataType::Duration(TimeUnit::Nanosecond) => {
+                typed_cast!(array, index, DurationNanosecondArray, DurationNanosecond)
+            }
             DataType::List(nested_type) => {
                 let list_array = array.as_any().downcast_ref::<ListArray>().ok_or(
                     DataFusionError::Internal("Failed to downcast ListArray".to_string()),
@@ -217,6 +497,49 @@ impl ScalarValue {
                 };
                 ScalarValue::List(value, nested_type.data_type().clone())
             }
+            DataType::Struct(fields) => {
+                let struct_array = array
+                    .as_any()
+                    .downcast_ref::<StructArray>()
+                    .ok_or_else(|| {
+                        DataFusionError::Internal(
+                            "Failed to downcast StructArray".to_string(),
+                        )
+                    })?;
+                let value = match struct_array.is_null(index) {
+                    true => None,
+                    false => Some(
+                        struct_array
+                            .columns()
+                            .into_iter()
+                            .map(|column| ScalarValue::try_from_array(column, index))
+                            .collect::<Result<Vec<_>>>()?,
+                    ),
+                };
+                ScalarValue::Struct(value, fields.clone())
+            }
+            DataType::Dictionary(key_type, _) => {
+                let (values, key) = match key_type.as_ref() {
+                    DataType::Int8 => dictionary_key::<Int8Type>(array, index)?,
+                    DataType::Int16 => dictionary_key::<Int16Type>(array, index)?,
+                    DataType::Int32 => dictionary_key::<Int32Type>(array, index)?,
+                    DataType::Int64 => dictionary_key::<Int64Type>(array, index)?,
+                    DataType::UInt8 => dictionary_key::<UInt8Type>(array, index)?,
+                    DataType::UInt16 => dictionary_key::<UInt16Type>(array, index)?,
+                    DataType::UInt32 => dictionary_key::<UInt32Type>(array, index)?,
+                    DataType::UInt64 => dictionary_key::<UInt64Type>(array, index)?,
+                    other => {
+                        return Err(DataFusionError::Internal(format!(
+                            "Invalid dictionary keys of type {:?}",
+                            other
+                        )))
+                    }
+                };
+                match key {
+                    Some(key) => ScalarValue::try_from_array(&values, key)?,
+                    None => ScalarValue::try_from(values.data_type())?,
+                }
+            }
             other => {
                 return Err(DataFusionError::NotImplemented(format!(
                     "Can't create a scalar of array of type \"{:?}\"",
@@ -227,6 +550,31 @@ impl ScalarValue {
     }
 }
 
+/// The values of the dictionary array `array` and the index in these values of its
+/// key at `index`, `None` when the key is null
+fn dictionary_key<K: ArrowDictionaryKeyType>(
+    array: &ArrayRef,
+    index: usize,
+) -> Result<(ArrayRef, Option<usize>)> {
+    let dictionary = array
+        .as_any()
+        .downcast_ref::<DictionaryArray<K>>()
+        .ok_or_else(|| {
+            DataFusionError::Internal("Failed to downcast DictionaryArray".to_string())
+        })?;
+    let keys = dictionary.keys_array();
+    let key = match keys.is_null(index) {
+        true => None,
+        false => Some(keys.value(index).to_usize().ok_or_else(|| {
+            DataFusionError::Internal(format!(
+                "Invalid dictionary key at index {}",
+                index
+            ))
+        })?),
+    };
+    Ok((dictionary.values(), key))
+}
+
 impl From<f64> for ScalarValue {
     fn from(value: f64) -> Self {
         ScalarValue::Float64(Some(value))
@@ -311,9 +659,57 @@ impl TryFrom<&DataType> for ScalarValue {
             &DataType::UInt64 => ScalarValue::UInt64(None),
             &DataType::Utf8 => ScalarValue::Utf8(None),
             &DataType::LargeUtf8 => ScalarValue::LargeUtf8(None),
+            &DataType::Binary => ScalarValue::Binary(None),
+            &DataType::LargeBinary => ScalarValue::LargeBinary(None),
+            &DataType::FixedSizeBinary(size) => ScalarValue::FixedSizeBinary(None, size),
+            &DataType::Date32(DateUnit::Day) => ScalarValue::Date32(None),
+            &DataType::Date64(DateUnit::Millisecond) => ScalarValue::Date64(None),
+            &DataType::Time32(TimeUnit::Second) => ScalarValue::Time32Second(None),
+            &DataType::Time32(TimeUnit::Millisecond) => {
+                ScalarValue::Time32Millisecond(None)
+            }
+            &DataType::Time64(TimeUnit::Microsecond) => {
+                ScalarValue::Time64Microsecond(None)
+            }
+            &DataType::Time64(TimeUnit::Nanosecond) => {
+                ScalarValue::Time64Nanosecond(None)
+            }
+            &DataType::Timestamp(TimeUnit::Second, ref tz) => {
+                ScalarValue::TimestampSecond(None, tz.clone())
+            }
+            &DataType::Timestamp(TimeUnit::Millisecond, ref tz) => {
+                ScalarValue::TimestampMillisecond(None, tz.clone())
+            }
+            &DataType::Timestamp(TimeUnit::Microsecond, ref tz) => {
+                ScalarValue::TimestampMicrosecond(None, tz.clone())
+            }
+            &DataType::Timestamp(TimeUnit::Nanosecond, ref tz) => {
+                ScalarValue::TimestampNanosecond(None, tz.clone())
+            }
+            &DataType::Interval(IntervalUnit::YearMonth) => {
+                ScalarValue::IntervalYearMonth(None)
+            }
+            &DataType::Interval(IntervalUnit::DayTime) => {
+                ScalarValue::IntervalDayTime(None)
+            }
+            &DataType::Duration(TimeUnit::Second) => ScalarValue::DurationSecond(None),
+            &DataType::Duration(TimeUnit::Millisecond) => {
+                ScalarValue::DurationMillisecond(None)
+            }
+            &DataType::Duration(TimeUnit::Microsecond) => {
+                ScalarValue::DurationMicrosecond(None)
+            }
+            &DataType::Duration(TimeUnit::Nanosecond) => {
+                ScalarValue::DurationNanosecond(None)
+            }
             &DataType::List(ref nested_type) => {
                 ScalarValue::List(None, nested_type.data_type().clone())
             }
+            &DataType::Struct(ref fields) => ScalarValue::Struct(None, fields.clone()),
+            // the values of dictionaries are their scalars, see `try_from_array`
+            &DataType::Dictionary(_, ref value_type) => {
+                ScalarValue::try_from(value_type.as_ref())?
+            }
             _ => {
                 return Err(DataFusionError::NotImplemented(format!(
                     "Can't create a scalar of type \"{:?}\"",
@@ -324,6 +720,67 @@ impl TryFrom<&DataType> for ScalarValue {
     }
 }
 
+/// Values of the same type are ordered as their arrays are sorted, with nulls first.
+/// Values of different types are not comparable.
+impl PartialOrd for ScalarValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        use ScalarValue::*;
+        match (self, other) {
+            (Boolean(v1), Boolean(v2)) => v1.partial_cmp(v2),
+            (Float32(v1), Float32(v2)) => v1.partial_cmp(v2),
+            (Float64(v1), Float64(v2)) => v1.partial_cmp(v2),
+            (Int8(v1), Int8(v2)) => v1.partial_cmp(v2),
+            (Int16(v1), Int16(v2)) => v1.partial_cmp(v2),
+            (Int32(v1), Int32(v2)) => v1.partial_cmp(v2),
+            (Int64(v1), Int64(v2)) => v1.partial_cmp(v2),
+            (UInt8(v1), UInt8(v2)) => v1.partial_cmp(v2),
+            (UInt16(v1), UInt16(v2)) => v1.partial_cmp(v2),
+            (UInt32(v1), UInt32(v2)) => v1.partial_cmp(v2),
+            (UInt64(v1), UInt64(v2)) => v1.partial_cmp(v2),
+            (Utf8(v1), Utf8(v2)) => v1.partial_cmp(v2),
+            (LargeUtf8(v1), LargeUtf8(v2)) => v1.partial_cmp(v2),
+            (Binary(v1), Binary(v2)) => v1.partial_cmp(v2),
+            (LargeBinary(v1), LargeBinary(v2)) => v1.partial_cmp(v2),
+            (FixedSizeBinary(v1, s1), FixedSizeBinary(v2, s2)) if s1 == s2 => {
+                v1.partial_cmp(v2)
+            }
+            (List(v1, t1), List(v2, t2)) if t1 == t2 => v1.partial_cmp(v2),
+            (Struct(v1, f1), Struct(v2, f2)) if f1 == f2 => v1.partial_cmp(v2),
+            (Date32(v1), Date32(v2)) => v1.partial_cmp(v2),
+            (Date64(v1), Date64(v2)) => v1.partial_cmp(v2),
+            (Time32Second(v1), Time32Second(v2)) => v1.partial_cmp(v2),
+            (Time32Millisecond(v1), Time32Millisecond(v2)) => v1.partial_cmp(v2),
+            (Time64Microsecond(v1), Time64Microsecond(v2)) => v1.partial_cmp(v2),
+            (Time64Nanosecond(v1), Time64Nanosecond(v2)) => v1.partial_cmp(v2),
+            (TimestampSecond(v1, tz1), TimestampSecond(v2, tz2)) if tz1 == tz2 => {
+                v1.partial_cmp(v2)
+            }
+            (TimestampMillisecond(v1, tz1), TimestampMillisecond(v2, tz2))
+                if tz1 == tz2 =>
+            {
+                v1.partial_cmp(v2)
+            }
+            (TimestampMicrosecond(v1, tz1), TimestampMicrosecond(v2, tz2))
+                if tz1 == tz2 =>
+            {
+                v1.partial_cmp(v2)
+            }
+            (TimestampNanosecond(v1, tz1), TimestampNanosecond(v2, tz2))
+                if tz1 == tz2 =>
+            {
+                v1.partial_cmp(v2)
+            }
+            (IntervalYearMonth(v1), IntervalYearMonth(v2)) => v1.partial_cmp(v2),
+            (IntervalDayTime(v1), IntervalDayTime(v2)) => v1.partial_cmp(v2),
+            (DurationSecond(v1), DurationSecond(v2)) => v1.partial_cmp(v2),
+            (DurationMillisecond(v1), DurationMillisecond(v2)) => v1.partial_cmp(v2),
+            (DurationMicrosecond(v1), DurationMicrosecond(v2)) => v1.partial_cmp(v2),
+            (DurationNanosecond(v1), DurationNanosecond(v2)) => v1.partial_cmp(v2),
+            _ => None,
+        }
+    }
+}
+
 macro_rules! format_option {
     ($F:expr, $EXPR:expr) => {{
         match $EXPR {
@@ -333,6 +790,14 @@ macro_rules! format_option {
     }};
 }
 
+/// Formats bytes as lowercase hexadecimal digits
+fn format_binary(f: &mut fmt::Formatter, e: &Option<Vec<u8>>) -> fmt::Result {
+    match e {
+        Some(bytes) => bytes.iter().try_for_each(|b| write!(f, "{:02x}", b)),
+        None => write!(f, "NULL"),
+    }
+}
+
 impl fmt::Display for ScalarValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -349,6 +814,9 @@ impl fmt::Display for ScalarValue {
             ScalarValue::UInt64(e) => format_option!(f, e)?,
             ScalarValue::Utf8(e) => format_option!(f, e)?,
             ScalarValue::LargeUtf8(e) => format_option!(f, e)?,
+            ScalarValue::Binary(e) => format_binary(f, e)?,
+            ScalarValue::LargeBinary(e) => format_binary(f, e)?,
+            ScalarValue::FixedSizeBinary(e, _) => format_binary(f, e)?,
             ScalarValue::List(e, _) => match e {
                 Some(l) => write!(
                     f,
@@ -360,11 +828,60 @@ impl fmt::Display for ScalarValue {
                 )?,
                 None => write!(f, "NULL")?,
             },
+            ScalarValue::Struct(e, fields) => match e {
+                Some(values) => write!(
+                    f,
+                    "{{{}}}",
+                    fields
+                        .iter()
+                        .zip(values.iter())
+                        .map(|(field, v)| format!("{}:{}", field.name(), v))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                )?,
+                None => write!(f, "NULL")?,
+            },
+            ScalarValue::Date32(e) => format_option!(f, e)?,
+            ScalarValue::Date64(e) => format_option!(f, e)?,
+            ScalarValue::Time32Second(e) => format_option!(f, e)?,
+            ScalarValue::Time32Millisecond(e) => format_option!(f, e)?,
+            ScalarValue::Time64Microsecond(e) => format_option!(f, e)?,
+            ScalarValue::Time64Nanosecond(e) => format_option!(f, e)?,
+            ScalarValue::TimestampSecond(e, _) => format_option!(f, e)?,
+            ScalarValue::TimestampMillisecond(e, _) => format_option!(f, e)?,
+            ScalarValue::TimestampMicrosecond(e, _) => format_option!(f, e)?,
+            ScalarValue::TimestampNanosecond(e, _) => format_option!(f, e)?,
+            ScalarValue::IntervalYearMonth(e) => match e {
+                Some(months) => write!(f, "{} months", months)?,
+                None => write!(f, "NULL")?,
+            },
+            // the days are in the upper 32 bits and the milliseconds in the lower 32 bits
+            ScalarValue::IntervalDayTime(e) => match e {
+                Some(value) => write!(f, "{} days {} ms", value >> 32, *value as i32)?,
+                None => write!(f, "NULL")?,
+            },
+            ScalarValue::DurationSecond(e) => format_option!(f, e)?,
+            ScalarValue::DurationMillisecond(e) => format_option!(f, e)?,
+            ScalarValue::DurationMicrosecond(e) => format_option!(f, e)?,
+            ScalarValue::DurationNanosecond(e) => format_option!(f, e)?,
         };
         Ok(())
     }
 }
 
+/// Formats a timestamp with its timezone, when it has one
+fn format_timestamp(
+    f: &mut fmt::Formatter,
+    name: &str,
+    value: &ScalarValue,
+    tz: &Option<Arc<String>>,
+) -> fmt::Result {
+    match tz {
+        Some(tz) => write!(f, "{}({}, {})", name, value, tz),
+        None => write!(f, "{}({})", name, value),
+    }
+}
+
 impl fmt::Debug for ScalarValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -381,7 +898,43 @@ impl fmt::Debug for ScalarValue {
             ScalarValue::UInt64(_) => write!(f, "UInt64({})", self),
             ScalarValue::Utf8(_) => write!(f, "Utf8(\"{}\")", self),
             ScalarValue::LargeUtf8(_) => write!(f, "LargeUtf8(\"{}\")", self),
+            ScalarValue::Binary(_) => write!(f, "Binary(\"{}\")", self),
+            ScalarValue::LargeBinary(_) => write!(f, "LargeBinary(\"{}\")", self),
+            ScalarValue::FixedSizeBinary(_, size) => {
+                write!(f, "FixedSizeBinary({}, \"{}\")", size, self)
+            }
             ScalarValue::List(_, _) => write!(f, "List([{}])", self),
+            ScalarValue::Struct(_, _) => write!(f, "Struct({})", self),
+            ScalarValue::Date32(_) => write!(f, "Date32({})", self),
+            ScalarValue::Date64(_) => write!(f, "Date64({})", self),
+            ScalarValue::Time32Second(_) => write!(f, "Time32Second({})", self),
+            ScalarValue::Time32Millisecond(_) => write!(f, "Time32Millisecond({})", self),
+            ScalarValue::Time64Microsecond(_) => write!(f, "Time64Microsecond({})", self),
+            ScalarValue::Time64Nanosecond(_) => write!(f, "Time64Nanosecond({})", self),
+            ScalarValue::TimestampSecond(_, tz) => {
+                format_timestamp(f, "TimestampSecond", self, tz)
+            }
+            ScalarValue::TimestampMillisecond(_, tz) => {
+                format_timestamp(f, "TimestampMillisecond", self, tz)
+            }
+            ScalarValue::TimestampMicrosecond(_, tz) => {
+                format_timestamp(f, "TimestampMicrosecond", self, tz)
+            }
+            ScalarValue::TimestampNanosecond(_, tz) => {
+                format_timestamp(f, "TimestampNanosecond", self, tz)
+            }
+            ScalarValue::IntervalYearMonth(_) => write!(f, "IntervalYearMonth({})", self),
+            ScalarValue::IntervalDayTime(_) => write!(f, "IntervalDayTime({})", self),
+            ScalarValue::DurationSecond(_) => write!(f, "DurationSecond({})", self),
+            ScalarValue::DurationMillisecond(_) => {
+                write!(f, "DurationMillisecond({})", self)
+            }
+            ScalarValue::DurationMicrosecond(_) => {
+                write!(f, "DurationMicrosecond({})", self)
+            }
+            ScalarValue::DurationNanosecond(_) => {
+                write!(f, "DurationNanosecond({})", self)
+            }
         }
     }
 }
@@ -389,6 +942,7 @@ impl fmt::Debug for ScalarValue {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use arrow::array::{PrimitiveBuilder, StringBuilder, StringDictionaryBuilder};
 
     #[test]
     fn scalar_list_null_to_array() -> Result<()> {
@@ -430,4 +984,134 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn scalar_roundtrip_array() -> Result<()> {
+        let fields = vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ];
+        let values = vec![
+            ScalarValue::Binary(Some(vec![1, 2])),
+            ScalarValue::LargeBinary(None),
+            ScalarValue::FixedSizeBinary(Some(vec![1, 2, 3]), 3),
+            ScalarValue::FixedSizeBinary(None, 3),
+            ScalarValue::Struct(
+                Some(vec![
+                    ScalarValue::Int32(Some(1)),
+                    ScalarValue::Utf8(Some("x".to_string())),
+                ]),
+                fields.clone(),
+            ),
+            ScalarValue::Struct(None, fields),
+            ScalarValue::Date32(Some(18262)),
+            ScalarValue::Date64(Some(1_577_836_800_000)),
+            ScalarValue::Time32Second(Some(60)),
+            ScalarValue::Time32Millisecond(None),
+            ScalarValue::Time64Microsecond(Some(60_000_000)),
+            ScalarValue::Time64Nanosecond(Some(1)),
+            ScalarValue::TimestampSecond(Some(1), None),
+            ScalarValue::TimestampMillisecond(Some(2), Some(Arc::new("UTC".to_string()))),
+            ScalarValue::TimestampMicrosecond(None, None),
+            ScalarValue::TimestampNanosecond(Some(4), None),
+            ScalarValue::DurationSecond(Some(1)),
+            ScalarValue::DurationMillisecond(Some(2)),
+            ScalarValue::DurationMicrosecond(None),
+            ScalarValue::DurationNanosecond(Some(4)),
+            ScalarValue::List(
+                Some(vec![
+                    ScalarValue::Float64(Some(1.5)),
+                    ScalarValue::Float64(None),
+                ]),
+                DataType::Float64,
+            ),
+        ];
+        for value in values {
+            let array = value.to_array_of_size(3);
+            assert_eq!(array.len(), 3);
+            assert_eq!(array.data_type(), &value.get_datatype());
+            assert_eq!(array.null_count(), if value.is_null() { 3 } else { 0 });
+            for i in 0..3 {
+                assert_eq!(ScalarValue::try_from_array(&array, i)?, value);
+            }
+            let null = ScalarValue::try_from(&value.get_datatype())?;
+            assert!(null.is_null());
+            assert_eq!(null.get_datatype(), value.get_datatype());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn scalar_from_dictionary() -> Result<()> {
+        let mut builder = StringDictionaryBuilder::new(
+            PrimitiveBuilder::<Int8Type>::new(3),
+            StringBuilder::new(2),
+        );
+        builder.append("a")?;
+        builder.append_null()?;
+        builder.append("b")?;
+        let array: ArrayRef = Arc::new(builder.finish());
+
+        assert_eq!(
+            ScalarValue::try_from_array(&array, 0)?,
+            ScalarValue::Utf8(Some("a".to_string()))
+        );
+        assert_eq!(
+            ScalarValue::try_from_array(&array, 1)?,
+            ScalarValue::Utf8(None)
+        );
+        assert_eq!(
+            ScalarValue::try_from_array(&array, 2)?,
+            ScalarValue::Utf8(Some("b".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn scalar_partial_ordering() {
+        let tz = Some(Arc::new("UTC".to_string()));
+        assert!(ScalarValue::Int32(None) < ScalarValue::Int32(Some(-1)));
+        assert!(ScalarValue::Date32(Some(1)) < ScalarValue::Date32(Some(2)));
+        assert!(
+            ScalarValue::Binary(Some(vec![1, 2])) < ScalarValue::Binary(Some(vec![1, 3]))
+        );
+        assert!(
+            ScalarValue::TimestampSecond(Some(2), tz.clone())
+                > ScalarValue::TimestampSecond(Some(1), tz.clone())
+        );
+        // values of different types, or timezones, are not comparable
+        assert_eq!(
+            ScalarValue::Int32(Some(1)).partial_cmp(&ScalarValue::Int64(Some(1))),
+            None
+        );
+        assert_eq!(
+            ScalarValue::TimestampSecond(Some(1), tz)
+                .partial_cmp(&ScalarValue::TimestampSecond(Some(1), None)),
+            None
+        );
+    }
+
+    #[test]
+    fn scalar_display() {
+        let fields = vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Binary, true),
+        ];
+        let value = ScalarValue::Struct(
+            Some(vec![
+                ScalarValue::Int32(Some(1)),
+                ScalarValue::Binary(Some(vec![0, 255])),
+            ]),
+            fields,
+        );
+        assert_eq!(format!("{}", value), "{a:1,b:00ff}");
+        assert_eq!(
+            format!(
+                "{:?}",
+                ScalarValue::TimestampSecond(Some(1), Some(Arc::new("UTC".to_string())))
+            ),
+            "TimestampSecond(1, UTC)"
+        );
+        assert_eq!(format!("{:?}", ScalarValue::Date32(None)), "Date32(NULL)");
+    }
 }
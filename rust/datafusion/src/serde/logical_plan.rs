@@ -0,0 +1,1465 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Conversion of logical plans, logical expressions, data types and scalar values
+//! to and from their protobuf representation
+
+use std::convert::TryFrom;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, DateUnit, Field, IntervalUnit, Schema, TimeUnit};
+
+use super::protobuf::{
+    arrow_type::ArrowTypeEnum, logical_expr_node::ExprType,
+    logical_plan_node::LogicalPlanType, scalar_value::Value,
+};
+use super::{
+    invalid_field, missing_field, parse_batches, parse_join_constraint, parse_join_type,
+    parse_operator, parse_projection, parse_sample_method, protobuf, serialize_batches,
+    serialize_join_constraint, serialize_join_type, serialize_operator,
+    serialize_projection, serialize_sample_method, LogicalExtensionCodec,
+};
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::{
+    Column, DFSchemaRef, Expr, FunctionRegistry, LogicalPlan, LogicalPlanBuilder,
+    PlanType, StringifiedPlan, TableSource, ToDFSchema,
+};
+use crate::physical_plan::aggregates::AggregateFunction;
+use crate::physical_plan::functions::BuiltinScalarFunction;
+use crate::scalar::ScalarValue;
+use crate::sql::parser::FileType;
+
+/// Serializes a logical plan. User-defined nodes are encoded by `codec`.
+pub fn serialize_logical_plan(
+    plan: &LogicalPlan,
+    codec: &dyn LogicalExtensionCodec,
+) -> Result<protobuf::LogicalPlanNode> {
+    let input = |input: &LogicalPlan| -> Result<Option<Box<protobuf::LogicalPlanNode>>> {
+        Ok(Some(Box::new(serialize_logical_plan(input, codec)?)))
+    };
+    let plan_type = match plan {
+        LogicalPlan::Projection { expr, input: i, .. } => {
+            LogicalPlanType::Projection(Box::new(protobuf::ProjectionNode {
+                input: input(i)?,
+                expr: serialize_exprs(expr)?,
+            }))
+        }
+        LogicalPlan::Filter {
+            predicate,
+            input: i,
+        } => LogicalPlanType::Selection(Box::new(protobuf::SelectionNode {
+            input: input(i)?,
+            expr: Some(serialize_expr(predicate)?),
+        })),
+        LogicalPlan::Aggregate {
+            input: i,
+            group_expr,
+            aggr_expr,
+            ..
+        } => LogicalPlanType::Aggregate(Box::new(protobuf::AggregateNode {
+            input: input(i)?,
+            group_expr: serialize_exprs(group_expr)?,
+            aggr_expr: serialize_exprs(aggr_expr)?,
+        })),
+        LogicalPlan::Sort { expr, input: i } => {
+            LogicalPlanType::Sort(Box::new(protobuf::SortNode {
+                input: input(i)?,
+                expr: serialize_exprs(expr)?,
+            }))
+        }
+        LogicalPlan::Limit { n, input: i } => {
+            LogicalPlanType::Limit(Box::new(protobuf::LimitNode {
+                input: input(i)?,
+                limit: *n as u64,
+            }))
+        }
+        LogicalPlan::TableScan {
+            schema_name,
+            source,
+            table_schema,
+            projection,
+            alias,
+            ..
+        } => match source {
+            TableSource::FromContext(table_name) => {
+                LogicalPlanType::TableScan(protobuf::TableScanNode {
+                    schema_name: schema_name.clone(),
+                    table_name: table_name.clone(),
+                    table_schema: Some(table_schema.as_ref().into()),
+                    projection: serialize_projection(projection.as_ref()),
+                    alias: alias.clone().unwrap_or_default(),
+                })
+            }
+            TableSource::FromProvider(_) => {
+                return Err(DataFusionError::NotImplemented(
+                    "Scans of tables that are not registered in a context cannot be serialized"
+                        .to_string(),
+                ))
+            }
+        },
+        LogicalPlan::InMemoryScan {
+            data,
+            schema,
+            projection,
+            ..
+        } => LogicalPlanType::InMemoryScan(protobuf::InMemoryScanNode {
+            partitions: data
+                .iter()
+                .map(|batches| serialize_batches(batches))
+                .collect::<Result<_>>()?,
+            schema: Some(schema.as_ref().into()),
+            projection: serialize_projection(projection.as_ref()),
+        }),
+        LogicalPlan::ParquetScan {
+            path,
+            schema,
+            projection,
+            ..
+        } => LogicalPlanType::ParquetScan(protobuf::ParquetScanNode {
+            path: path.clone(),
+            schema: Some(schema.as_ref().into()),
+            projection: serialize_projection(projection.as_ref()),
+        }),
+        LogicalPlan::CsvScan {
+            path,
+            schema,
+            has_header,
+            delimiter,
+            projection,
+            ..
+        } => LogicalPlanType::CsvScan(protobuf::CsvScanNode {
+            path: path.clone(),
+            schema: Some(schema.as_ref().into()),
+            has_header: *has_header,
+            delimiter: serialize_delimiter(*delimiter),
+            projection: serialize_projection(projection.as_ref()),
+        }),
+        LogicalPlan::EmptyRelation { schema } => {
+            LogicalPlanType::EmptyRelation(protobuf::EmptyRelationNode {
+                schema: Some((&Schema::from(schema.as_ref())).into()),
+            })
+        }
+        LogicalPlan::CreateExternalTable {
+            schema,
+            name,
+            location,
+            file_type,
+            has_header,
+        } => LogicalPlanType::CreateExternalTable(protobuf::CreateExternalTableNode {
+            name: name.clone(),
+            location: location.clone(),
+            file_type: match file_type {
+                FileType::NdJson => protobuf::FileType::Ndjson,
+                FileType::Parquet => protobuf::FileType::Parquet,
+                FileType::CSV => protobuf::FileType::Csv,
+            } as i32,
+            has_header: *has_header,
+            schema: Some((&Schema::from(schema.as_ref())).into()),
+        }),
+        LogicalPlan::Explain {
+            verbose,
+            plan,
+            stringified_plans,
+            ..
+        } => LogicalPlanType::Explain(Box::new(protobuf::ExplainNode {
+            input: input(plan)?,
+            verbose: *verbose,
+            stringified_plans: stringified_plans.iter().map(|p| p.into()).collect(),
+        })),
+        LogicalPlan::Join {
+            left,
+            right,
+            on,
+            join_type,
+            join_constraint,
+            ..
+        } => {
+            let column = |column: &Column| protobuf::Column {
+                name: column.name.clone(),
+                relation: column.relation.clone().unwrap_or_default(),
+            };
+            LogicalPlanType::Join(Box::new(protobuf::JoinNode {
+                left: input(left)?,
+                right: input(right)?,
+                join_type: serialize_join_type(join_type) as i32,
+                left_join_column: on.iter().map(|(l, _)| column(l)).collect(),
+                right_join_column: on.iter().map(|(_, r)| column(r)).collect(),
+                join_constraint: serialize_join_constraint(join_constraint) as i32,
+            }))
+        }
+        LogicalPlan::Unnest {
+            input: i, column, ..
+        } => LogicalPlanType::Unnest(Box::new(protobuf::UnnestNode {
+            input: input(i)?,
+            column: Some(protobuf::Column {
+                name: column.name.clone(),
+                relation: column.relation.clone().unwrap_or_default(),
+            }),
+        })),
+        LogicalPlan::Sample {
+            method,
+            fraction,
+            seed,
+            input: i,
+        } => LogicalPlanType::Sample(Box::new(protobuf::SampleNode {
+            input: input(i)?,
+            method: serialize_sample_method(*method) as i32,
+            fraction: *fraction,
+            seed: *seed,
+        })),
+        LogicalPlan::Analyze { verbose, input: i, .. } => {
+            LogicalPlanType::Analyze(Box::new(protobuf::AnalyzeNode {
+                input: input(i)?,
+                verbose: *verbose,
+            }))
+        }
+        LogicalPlan::Extension { node } => {
+            LogicalPlanType::Extension(protobuf::ExtensionNode {
+                node: codec.try_encode(node.as_ref())?,
+                inputs: node
+                    .inputs()
+                    .into_iter()
+                    .map(|i| serialize_logical_plan(i, codec))
+                    .collect::<Result<_>>()?,
+            })
+        }
+    };
+    Ok(protobuf::LogicalPlanNode {
+        logical_plan_type: Some(plan_type),
+    })
+}
+
+/// Deserializes a logical plan. Functions are resolved by name through `registry` and
+/// user-defined nodes are decoded by `codec`.
+pub fn parse_logical_plan(
+    proto: &protobuf::LogicalPlanNode,
+    registry: &dyn FunctionRegistry,
+    codec: &dyn LogicalExtensionCodec,
+) -> Result<LogicalPlan> {
+    let input = |input: Option<&protobuf::LogicalPlanNode>,
+                 field: &str|
+     -> Result<LogicalPlan> {
+        parse_logical_plan(input.ok_or_else(|| missing_field(field))?, registry, codec)
+    };
+    let plan_type = proto
+        .logical_plan_type
+        .as_ref()
+        .ok_or_else(|| missing_field("LogicalPlanNode.logical_plan_type"))?;
+    match plan_type {
+        LogicalPlanType::Projection(node) => {
+            let input = input(node.input.as_deref(), "ProjectionNode.input")?;
+            LogicalPlanBuilder::from(&input)
+                .project(parse_exprs(&node.expr, registry)?)?
+                .build()
+        }
+        LogicalPlanType::Selection(node) => {
+            let input = input(node.input.as_deref(), "SelectionNode.input")?;
+            let expr =
+                parse_required_expr(node.expr.as_ref(), registry, "SelectionNode.expr")?;
+            LogicalPlanBuilder::from(&input).filter(expr)?.build()
+        }
+        LogicalPlanType::Aggregate(node) => {
+            let input = input(node.input.as_deref(), "AggregateNode.input")?;
+            LogicalPlanBuilder::from(&input)
+                .aggregate(
+                    parse_exprs(&node.group_expr, registry)?,
+                    parse_exprs(&node.aggr_expr, registry)?,
+                )?
+                .build()
+        }
+        LogicalPlanType::Sort(node) => {
+            let input = input(node.input.as_deref(), "SortNode.input")?;
+            LogicalPlanBuilder::from(&input)
+                .sort(parse_exprs(&node.expr, registry)?)?
+                .build()
+        }
+        LogicalPlanType::Limit(node) => {
+            let input = input(node.input.as_deref(), "LimitNode.input")?;
+            LogicalPlanBuilder::from(&input)
+                .limit(node.limit as usize)?
+                .build()
+        }
+        LogicalPlanType::TableScan(node) => {
+            let schema = parse_required_schema(
+                node.table_schema.as_ref(),
+                "TableScanNode.table_schema",
+            )?;
+            LogicalPlanBuilder::scan_with_alias(
+                &node.schema_name,
+                &node.table_name,
+                non_empty(&node.alias),
+                &schema,
+                parse_projection(node.projection.as_ref()),
+            )?
+            .build()
+        }
+        LogicalPlanType::InMemoryScan(node) => {
+            let schema = Arc::new(parse_required_schema(
+                node.schema.as_ref(),
+                "InMemoryScanNode.schema",
+            )?);
+            let projection = parse_projection(node.projection.as_ref());
+            Ok(LogicalPlan::InMemoryScan {
+                data: node
+                    .partitions
+                    .iter()
+                    .map(|bytes| parse_batches(bytes))
+                    .collect::<Result<_>>()?,
+                projected_schema: project_schema(&schema, projection.as_ref())?,
+                schema,
+                projection,
+            })
+        }
+        LogicalPlanType::ParquetScan(node) => {
+            let schema = Arc::new(parse_required_schema(
+                node.schema.as_ref(),
+                "ParquetScanNode.schema",
+            )?);
+            let projection = parse_projection(node.projection.as_ref());
+            Ok(LogicalPlan::ParquetScan {
+                path: node.path.clone(),
+                projected_schema: project_schema(&schema, projection.as_ref())?,
+                schema,
+                projection,
+            })
+        }
+        LogicalPlanType::CsvScan(node) => {
+            let schema = Arc::new(parse_required_schema(
+                node.schema.as_ref(),
+                "CsvScanNode.schema",
+            )?);
+            let projection = parse_projection(node.projection.as_ref());
+            Ok(LogicalPlan::CsvScan {
+                path: node.path.clone(),
+                projected_schema: project_schema(&schema, projection.as_ref())?,
+                schema,
+                has_header: node.has_header,
+                delimiter: parse_delimiter(&node.delimiter)?,
+                projection,
+            })
+        }
+        LogicalPlanType::EmptyRelation(node) => Ok(LogicalPlan::EmptyRelation {
+            schema: parse_required_schema(
+                node.schema.as_ref(),
+                "EmptyRelationNode.schema",
+            )?
+            .to_dfschema_ref()?,
+        }),
+        LogicalPlanType::CreateExternalTable(node) => {
+            let file_type = protobuf::FileType::from_i32(node.file_type)
+                .ok_or_else(|| invalid_field("FileType", node.file_type))?;
+            Ok(LogicalPlan::CreateExternalTable {
+                schema: parse_required_schema(
+                    node.schema.as_ref(),
+                    "CreateExternalTableNode.schema",
+                )?
+                .to_dfschema_ref()?,
+                name: node.name.clone(),
+                location: node.location.clone(),
+                file_type: match file_type {
+                    protobuf::FileType::Ndjson => FileType::NdJson,
+                    protobuf::FileType::Parquet => FileType::Parquet,
+                    protobuf::FileType::Csv => FileType::CSV,
+                },
+                has_header: node.has_header,
+            })
+        }
+        LogicalPlanType::Explain(node) => {
+            let input = input(node.input.as_deref(), "ExplainNode.input")?;
+            Ok(LogicalPlan::Explain {
+                verbose: node.verbose,
+                plan: Arc::new(input),
+                stringified_plans: node
+                    .stringified_plans
+                    .iter()
+                    .map(StringifiedPlan::try_from)
+                    .collect::<Result<_>>()?,
+                schema: LogicalPlan::explain_schema().to_dfschema_ref()?,
+            })
+        }
+        LogicalPlanType::Analyze(node) => {
+            let input = input(node.input.as_deref(), "AnalyzeNode.input")?;
+            LogicalPlanBuilder::from(&input)
+                .explain_analyze(node.verbose)?
+                .build()
+        }
+        LogicalPlanType::Join(node) => {
+            let left = input(node.left.as_deref(), "JoinNode.left")?;
+            let right = input(node.right.as_deref(), "JoinNode.right")?;
+            let columns = |columns: &[protobuf::Column]| {
+                columns
+                    .iter()
+                    .map(|c| Column::new(non_empty(&c.relation), &c.name))
+                    .collect::<Vec<_>>()
+            };
+            LogicalPlanBuilder::from(&left)
+                .join_detailed(
+                    &right,
+                    parse_join_type(node.join_type)?,
+                    columns(&node.left_join_column),
+                    columns(&node.right_join_column),
+                    parse_join_constraint(node.join_constraint)?,
+                )?
+                .build()
+        }
+        LogicalPlanType::Unnest(node) => {
+            let input = input(node.input.as_deref(), "UnnestNode.input")?;
+            let column = node
+                .column
+                .as_ref()
+                .ok_or_else(|| missing_field("UnnestNode.column"))?;
+            LogicalPlanBuilder::from(&input)
+                .unnest(Column::new(non_empty(&column.relation), &column.name))?
+                .build()
+        }
+        LogicalPlanType::Sample(node) => {
+            let input = input(node.input.as_deref(), "SampleNode.input")?;
+            LogicalPlanBuilder::from(&input)
+                .sample(parse_sample_method(node.method)?, node.fraction, node.seed)?
+                .build()
+        }
+        LogicalPlanType::Extension(node) => {
+            let inputs = node
+                .inputs
+                .iter()
+                .map(|i| parse_logical_plan(i, registry, codec))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(LogicalPlan::Extension {
+                node: codec.try_decode(&node.node, &inputs)?,
+            })
+        }
+    }
+}
+
+/// Serializes a logical expression
+pub fn serialize_expr(expr: &Expr) -> Result<protobuf::LogicalExprNode> {
+    let boxed = |expr: &Expr| -> Result<Option<Box<protobuf::LogicalExprNode>>> {
+        Ok(Some(Box::new(serialize_expr(expr)?)))
+    };
+    let expr_type = match expr {
+        Expr::Alias(expr, alias) => ExprType::Alias(Box::new(protobuf::AliasNode {
+            expr: boxed(expr)?,
+            alias: alias.clone(),
+        })),
+        Expr::Column(column) => ExprType::Column(protobuf::Column {
+            name: column.name.clone(),
+            relation: column.relation.clone().unwrap_or_default(),
+        }),
+        Expr::ScalarVariable(names) => {
+            ExprType::ScalarVariable(protobuf::ScalarVariableNode {
+                names: names.clone(),
+            })
+        }
+        Expr::Literal(value) => ExprType::Literal(value.into()),
+        Expr::BinaryExpr { left, op, right } => {
+            ExprType::BinaryExpr(Box::new(protobuf::BinaryExprNode {
+                l: boxed(left)?,
+                op: serialize_operator(op) as i32,
+                r: boxed(right)?,
+            }))
+        }
+        Expr::Nested(expr) => {
+            ExprType::Nested(Box::new(protobuf::NestedNode { expr: boxed(expr)? }))
+        }
+        Expr::Not(expr) => {
+            ExprType::NotExpr(Box::new(protobuf::NotNode { expr: boxed(expr)? }))
+        }
+        Expr::IsNull(expr) => {
+            ExprType::IsNullExpr(Box::new(protobuf::IsNullNode { expr: boxed(expr)? }))
+        }
+        Expr::IsNotNull(expr) => {
+            ExprType::IsNotNullExpr(Box::new(protobuf::IsNotNullNode {
+                expr: boxed(expr)?,
+            }))
+        }
+        Expr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => ExprType::Between(Box::new(protobuf::BetweenNode {
+            expr: boxed(expr)?,
+            negated: *negated,
+            low: boxed(low)?,
+            high: boxed(high)?,
+        })),
+        Expr::InList {
+            expr,
+            list,
+            negated,
+        } => ExprType::InList(Box::new(protobuf::InListNode {
+            expr: boxed(expr)?,
+            list: serialize_exprs(list)?,
+            negated: *negated,
+        })),
+        Expr::Cast { expr, data_type } => ExprType::Cast(Box::new(protobuf::CastNode {
+            expr: boxed(expr)?,
+            arrow_type: Some(data_type.into()),
+        })),
+        Expr::GetIndexedField { expr, key } => {
+            ExprType::GetIndexedField(Box::new(protobuf::GetIndexedFieldNode {
+                expr: boxed(expr)?,
+                key: Some(key.into()),
+            }))
+        }
+        Expr::Sort {
+            expr,
+            asc,
+            nulls_first,
+        } => ExprType::Sort(Box::new(protobuf::SortExprNode {
+            expr: boxed(expr)?,
+            asc: *asc,
+            nulls_first: *nulls_first,
+        })),
+        Expr::ScalarFunction { fun, args } => {
+            ExprType::ScalarFunction(protobuf::ScalarFunctionNode {
+                fun_name: fun.to_string(),
+                args: serialize_exprs(args)?,
+            })
+        }
+        Expr::ScalarUDF { fun, args } => {
+            ExprType::ScalarUdfExpr(protobuf::ScalarUdfExprNode {
+                fun_name: fun.name.clone(),
+                args: serialize_exprs(args)?,
+            })
+        }
+        Expr::AggregateFunction {
+            fun,
+            args,
+            distinct,
+        } => ExprType::AggregateExpr(protobuf::AggregateExprNode {
+            fun_name: fun.to_string(),
+            args: serialize_exprs(args)?,
+            distinct: *distinct,
+        }),
+        Expr::AggregateUDF { fun, args } => {
+            ExprType::AggregateUdfExpr(protobuf::AggregateUdfExprNode {
+                fun_name: fun.name.clone(),
+                args: serialize_exprs(args)?,
+            })
+        }
+        Expr::Wildcard => ExprType::Wildcard(true),
+        Expr::Placeholder { id, data_type } => {
+            ExprType::Placeholder(protobuf::PlaceholderNode {
+                id: id.clone(),
+                data_type: data_type.as_ref().map(|t| t.into()),
+            })
+        }
+    };
+    Ok(protobuf::LogicalExprNode {
+        expr_type: Some(expr_type),
+    })
+}
+
+/// Deserializes a logical expression, resolving its functions by name through `registry`
+pub fn parse_expr(
+    proto: &protobuf::LogicalExprNode,
+    registry: &dyn FunctionRegistry,
+) -> Result<Expr> {
+    let boxed =
+        |expr: Option<&protobuf::LogicalExprNode>, field: &str| -> Result<Box<Expr>> {
+            Ok(Box::new(parse_required_expr(expr, registry, field)?))
+        };
+    let expr_type = proto
+        .expr_type
+        .as_ref()
+        .ok_or_else(|| missing_field("LogicalExprNode.expr_type"))?;
+    Ok(match expr_type {
+        ExprType::Alias(node) => Expr::Alias(
+            boxed(node.expr.as_deref(), "AliasNode.expr")?,
+            node.alias.clone(),
+        ),
+        ExprType::Column(column) => {
+            Expr::Column(Column::new(non_empty(&column.relation), &column.name))
+        }
+        ExprType::ScalarVariable(node) => Expr::ScalarVariable(node.names.clone()),
+        ExprType::Literal(value) => Expr::Literal(ScalarValue::try_from(value)?),
+        ExprType::BinaryExpr(node) => Expr::BinaryExpr {
+            left: boxed(node.l.as_deref(), "BinaryExprNode.l")?,
+            op: parse_operator(node.op)?,
+            right: boxed(node.r.as_deref(), "BinaryExprNode.r")?,
+        },
+        ExprType::Nested(node) => {
+            Expr::Nested(boxed(node.expr.as_deref(), "NestedNode.expr")?)
+        }
+        ExprType::NotExpr(node) => {
+            Expr::Not(boxed(node.expr.as_deref(), "NotNode.expr")?)
+        }
+        ExprType::IsNullExpr(node) => {
+            Expr::IsNull(boxed(node.expr.as_deref(), "IsNullNode.expr")?)
+        }
+        ExprType::IsNotNullExpr(node) => {
+            Expr::IsNotNull(boxed(node.expr.as_deref(), "IsNotNullNode.expr")?)
+        }
+        ExprType::Between(node) => Expr::Between {
+            expr: boxed(node.expr.as_deref(), "BetweenNode.expr")?,
+            negated: node.negated,
+            low: boxed(node.low.as_deref(), "BetweenNode.low")?,
+            high: boxed(node.high.as_deref(), "BetweenNode.high")?,
+        },
+        ExprType::InList(node) => Expr::InList {
+            expr: boxed(node.expr.as_deref(), "InListNode.expr")?,
+            list: parse_exprs(&node.list, registry)?,
+            negated: node.negated,
+        },
+        ExprType::Cast(node) => Expr::Cast {
+            expr: boxed(node.expr.as_deref(), "CastNode.expr")?,
+            data_type: DataType::try_from(
+                node.arrow_type
+                    .as_ref()
+                    .ok_or_else(|| missing_field("CastNode.arrow_type"))?,
+            )?,
+        },
+        ExprType::GetIndexedField(node) => Expr::GetIndexedField {
+            expr: boxed(node.expr.as_deref(), "GetIndexedFieldNode.expr")?,
+            key: ScalarValue::try_from(
+                node.key
+                    .as_ref()
+                    .ok_or_else(|| missing_field("GetIndexedFieldNode.key"))?,
+            )?,
+        },
+        ExprType::Sort(node) => Expr::Sort {
+            expr: boxed(node.expr.as_deref(), "SortExprNode.expr")?,
+            asc: node.asc,
+            nulls_first: node.nulls_first,
+        },
+        ExprType::ScalarFunction(node) => Expr::ScalarFunction {
+            fun: BuiltinScalarFunction::from_str(&node.fun_name)?,
+            args: parse_exprs(&node.args, registry)?,
+        },
+        ExprType::ScalarUdfExpr(node) => Expr::ScalarUDF {
+            fun: Arc::new(registry.udf(&node.fun_name)?.clone()),
+            args: parse_exprs(&node.args, registry)?,
+        },
+        ExprType::AggregateExpr(node) => Expr::AggregateFunction {
+            fun: AggregateFunction::from_str(&node.fun_name)?,
+            args: parse_exprs(&node.args, registry)?,
+            distinct: node.distinct,
+        },
+        ExprType::AggregateUdfExpr(node) => Expr::AggregateUDF {
+            fun: Arc::new(registry.udaf(&node.fun_name)?.clone()),
+            args: parse_exprs(&node.args, registry)?,
+        },
+        ExprType::Wildcard(_) => Expr::Wildcard,
+        ExprType::Placeholder(node) => Expr::Placeholder {
+            id: node.id.clone(),
+            data_type: node
+                .data_type
+                .as_ref()
+                .map(DataType::try_from)
+                .transpose()?,
+        },
+    })
+}
+
+fn serialize_exprs(expr: &[Expr]) -> Result<Vec<protobuf::LogicalExprNode>> {
+    expr.iter().map(serialize_expr).collect()
+}
+
+fn parse_exprs(
+    proto: &[protobuf::LogicalExprNode],
+    registry: &dyn FunctionRegistry,
+) -> Result<Vec<Expr>> {
+    proto.iter().map(|e| parse_expr(e, registry)).collect()
+}
+
+fn parse_required_expr(
+    proto: Option<&protobuf::LogicalExprNode>,
+    registry: &dyn FunctionRegistry,
+    field: &str,
+) -> Result<Expr> {
+    parse_expr(proto.ok_or_else(|| missing_field(field))?, registry)
+}
+
+pub(crate) fn parse_required_schema(
+    proto: Option<&protobuf::Schema>,
+    field: &str,
+) -> Result<Schema> {
+    Schema::try_from(proto.ok_or_else(|| missing_field(field))?)
+}
+
+/// Maps the empty string, which protobuf uses for unset strings, to `None`
+fn non_empty(s: &str) -> Option<&str> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+/// Returns the schema of the columns of `schema` selected by `projection`
+fn project_schema(
+    schema: &Schema,
+    projection: Option<&Vec<usize>>,
+) -> Result<DFSchemaRef> {
+    match projection {
+        None => schema.clone().to_dfschema_ref(),
+        Some(projection) => {
+            let fields = projection
+                .iter()
+                .map(|i| {
+                    schema
+                        .fields()
+                        .get(*i)
+                        .cloned()
+                        .ok_or_else(|| invalid_field("ProjectionColumns.columns", i))
+                })
+                .collect::<Result<_>>()?;
+            Schema::new(fields).to_dfschema_ref()
+        }
+    }
+}
+
+pub(crate) fn serialize_delimiter(delimiter: Option<u8>) -> String {
+    delimiter
+        .map(|d| (d as char).to_string())
+        .unwrap_or_default()
+}
+
+pub(crate) fn parse_delimiter(delimiter: &str) -> Result<Option<u8>> {
+    match delimiter.as_bytes() {
+        [] => Ok(None),
+        [d] => Ok(Some(*d)),
+        _ => Err(invalid_field("delimiter", delimiter)),
+    }
+}
+
+impl From<&StringifiedPlan> for protobuf::StringifiedPlan {
+    fn from(plan: &StringifiedPlan) -> Self {
+        let (plan_type, optimized) = match &plan.plan_type {
+            PlanType::LogicalPlan => ("logical_plan".to_string(), false),
+            PlanType::OptimizedLogicalPlan { optimizer_name } => {
+                (optimizer_name.clone(), true)
+            }
+            PlanType::PhysicalPlan => ("physical_plan".to_string(), false),
+        };
+        protobuf::StringifiedPlan {
+            plan_type,
+            optimized,
+            plan: plan.plan.as_ref().clone(),
+        }
+    }
+}
+
+impl TryFrom<&protobuf::StringifiedPlan> for StringifiedPlan {
+    type Error = DataFusionError;
+
+    fn try_from(proto: &protobuf::StringifiedPlan) -> Result<Self> {
+        let plan_type = match (proto.plan_type.as_str(), proto.optimized) {
+            (optimizer_name, true) => PlanType::OptimizedLogicalPlan {
+                optimizer_name: optimizer_name.to_string(),
+            },
+            ("logical_plan", false) => PlanType::LogicalPlan,
+            ("physical_plan", false) => PlanType::PhysicalPlan,
+            (other, false) => {
+                return Err(invalid_field("StringifiedPlan.plan_type", other))
+            }
+        };
+        Ok(StringifiedPlan::new(plan_type, proto.plan.clone()))
+    }
+}
+
+impl From<&Schema> for protobuf::Schema {
+    fn from(schema: &Schema) -> Self {
+        protobuf::Schema {
+            columns: schema.fields().iter().map(|f| f.into()).collect(),
+        }
+    }
+}
+
+impl TryFrom<&protobuf::Schema> for Schema {
+    type Error = DataFusionError;
+
+    fn try_from(proto: &protobuf::Schema) -> Result<Self> {
+        let fields = proto
+            .columns
+            .iter()
+            .map(Field::try_from)
+            .collect::<Result<_>>()?;
+        Ok(Schema::new(fields))
+    }
+}
+
+impl From<&Field> for protobuf::Field {
+    fn from(field: &Field) -> Self {
+        protobuf::Field {
+            name: field.name().clone(),
+            arrow_type: Some(Box::new(field.data_type().into())),
+            nullable: field.is_nullable(),
+        }
+    }
+}
+
+impl TryFrom<&protobuf::Field> for Field {
+    type Error = DataFusionError;
+
+    fn try_from(proto: &protobuf::Field) -> Result<Self> {
+        let data_type = proto
+            .arrow_type
+            .as_deref()
+            .ok_or_else(|| missing_field("Field.arrow_type"))?;
+        Ok(Field::new(
+            &proto.name,
+            DataType::try_from(data_type)?,
+            proto.nullable,
+        ))
+    }
+}
+
+impl From<&DataType> for protobuf::ArrowType {
+    fn from(data_type: &DataType) -> Self {
+        let primitive = |t: protobuf::PrimitiveType| ArrowTypeEnum::Primitive(t as i32);
+        let list = |field: &Field| protobuf::List {
+            field_type: Some(Box::new(field.into())),
+        };
+        let arrow_type_enum = match data_type {
+            DataType::Null => primitive(protobuf::PrimitiveType::Null),
+            DataType::Boolean => primitive(protobuf::PrimitiveType::Boolean),
+            DataType::Int8 => primitive(protobuf::PrimitiveType::Int8),
+            DataType::Int16 => primitive(protobuf::PrimitiveType::Int16),
+            DataType::Int32 => primitive(protobuf::PrimitiveType::Int32),
+            DataType::Int64 => primitive(protobuf::PrimitiveType::Int64),
+            DataType::UInt8 => primitive(protobuf::PrimitiveType::Uint8),
+            DataType::UInt16 => primitive(protobuf::PrimitiveType::Uint16),
+            DataType::UInt32 => primitive(protobuf::PrimitiveType::Uint32),
+            DataType::UInt64 => primitive(protobuf::PrimitiveType::Uint64),
+            DataType::Float16 => primitive(protobuf::PrimitiveType::Float16),
+            DataType::Float32 => primitive(protobuf::PrimitiveType::Float32),
+            DataType::Float64 => primitive(protobuf::PrimitiveType::Float64),
+            DataType::Binary => primitive(protobuf::PrimitiveType::Binary),
+            DataType::LargeBinary => primitive(protobuf::PrimitiveType::LargeBinary),
+            DataType::Utf8 => primitive(protobuf::PrimitiveType::Utf8),
+            DataType::LargeUtf8 => primitive(protobuf::PrimitiveType::LargeUtf8),
+            DataType::Timestamp(unit, timezone) => {
+                ArrowTypeEnum::Timestamp(protobuf::Timestamp {
+                    time_unit: serialize_time_unit(unit) as i32,
+                    timezone: timezone
+                        .as_ref()
+                        .map(|tz| tz.to_string())
+                        .unwrap_or_default(),
+                })
+            }
+            DataType::Date32(unit) => {
+                ArrowTypeEnum::Date32(serialize_date_unit(unit) as i32)
+            }
+            DataType::Date64(unit) => {
+                ArrowTypeEnum::Date64(serialize_date_unit(unit) as i32)
+            }
+            DataType::Time32(unit) => {
+                ArrowTypeEnum::Time32(serialize_time_unit(unit) as i32)
+            }
+            DataType::Time64(unit) => {
+                ArrowTypeEnum::Time64(serialize_time_unit(unit) as i32)
+            }
+            DataType::Duration(unit) => {
+                ArrowTypeEnum::Duration(serialize_time_unit(unit) as i32)
+            }
+            DataType::Interval(unit) => ArrowTypeEnum::Interval(match unit {
+                IntervalUnit::YearMonth => protobuf::IntervalUnit::YearMonth,
+                IntervalUnit::DayTime => protobuf::IntervalUnit::DayTime,
+            } as i32),
+            DataType::FixedSizeBinary(size) => ArrowTypeEnum::FixedSizeBinary(*size),
+            DataType::List(field) => ArrowTypeEnum::List(Box::new(list(field.as_ref()))),
+            DataType::LargeList(field) => {
+                ArrowTypeEnum::LargeList(Box::new(list(field.as_ref())))
+            }
+            DataType::FixedSizeList(field, size) => {
+                ArrowTypeEnum::FixedSizeList(Box::new(protobuf::FixedSizeList {
+                    field_type: Some(Box::new(field.as_ref().into())),
+                    list_size: *size,
+                }))
+            }
+            DataType::Struct(fields) => ArrowTypeEnum::Struct(protobuf::Struct {
+                sub_field_types: fields.iter().map(|f| f.into()).collect(),
+            }),
+            DataType::Union(fields) => ArrowTypeEnum::Union(protobuf::Struct {
+                sub_field_types: fields.iter().map(|f| f.into()).collect(),
+            }),
+            DataType::Dictionary(key, value) => {
+                ArrowTypeEnum::Dictionary(Box::new(protobuf::Dictionary {
+                    key: Some(Box::new(key.as_ref().into())),
+                    value: Some(Box::new(value.as_ref().into())),
+                }))
+            }
+        };
+        protobuf::ArrowType {
+            arrow_type_enum: Some(arrow_type_enum),
+        }
+    }
+}
+
+impl TryFrom<&protobuf::ArrowType> for DataType {
+    type Error = DataFusionError;
+
+    fn try_from(proto: &protobuf::ArrowType) -> Result<Self> {
+        let list_field = |list: &protobuf::List| -> Result<Box<Field>> {
+            let field = list
+                .field_type
+                .as_deref()
+                .ok_or_else(|| missing_field("List.field_type"))?;
+            Ok(Box::new(Field::try_from(field)?))
+        };
+        let fields = |s: &protobuf::Struct| -> Result<Vec<Field>> {
+            s.sub_field_types.iter().map(Field::try_from).collect()
+        };
+        let arrow_type_enum = proto
+            .arrow_type_enum
+            .as_ref()
+            .ok_or_else(|| missing_field("ArrowType.arrow_type_enum"))?;
+        Ok(match arrow_type_enum {
+            ArrowTypeEnum::Primitive(t) => {
+                let t = protobuf::PrimitiveType::from_i32(*t)
+                    .ok_or_else(|| invalid_field("PrimitiveType", t))?;
+                match t {
+                    protobuf::PrimitiveType::Null => DataType::Null,
+                    protobuf::PrimitiveType::Boolean => DataType::Boolean,
+                    protobuf::PrimitiveType::Int8 => DataType::Int8,
+                    protobuf::PrimitiveType::Int16 => DataType::Int16,
+                    protobuf::PrimitiveType::Int32 => DataType::Int32,
+                    protobuf::PrimitiveType::Int64 => DataType::Int64,
+                    protobuf::PrimitiveType::Uint8 => DataType::UInt8,
+                    protobuf::PrimitiveType::Uint16 => DataType::UInt16,
+                    protobuf::PrimitiveType::Uint32 => DataType::UInt32,
+                    protobuf::PrimitiveType::Uint64 => DataType::UInt64,
+                    protobuf::PrimitiveType::Float16 => DataType::Float16,
+                    protobuf::PrimitiveType::Float32 => DataType::Float32,
+                    protobuf::PrimitiveType::Float64 => DataType::Float64,
+                    protobuf::PrimitiveType::Binary => DataType::Binary,
+                    protobuf::PrimitiveType::LargeBinary => DataType::LargeBinary,
+                    protobuf::PrimitiveType::Utf8 => DataType::Utf8,
+                    protobuf::PrimitiveType::LargeUtf8 => DataType::LargeUtf8,
+                }
+            }
+            ArrowTypeEnum::Timestamp(timestamp) => DataType::Timestamp(
+                parse_time_unit(timestamp.time_unit)?,
+                match timestamp.timezone.as_str() {
+                    "" => None,
+                    tz => Some(Arc::new(tz.to_string())),
+                },
+            ),
+            ArrowTypeEnum::Date32(unit) => DataType::Date32(parse_date_unit(*unit)?),
+            ArrowTypeEnum::Date64(unit) => DataType::Date64(parse_date_unit(*unit)?),
+            ArrowTypeEnum::Time32(unit) => DataType::Time32(parse_time_unit(*unit)?),
+            ArrowTypeEnum::Time64(unit) => DataType::Time64(parse_time_unit(*unit)?),
+            ArrowTypeEnum::Duration(unit) => DataType::Duration(parse_time_unit(*unit)?),
+            ArrowTypeEnum::Interval(unit) => {
+                let unit = protobuf::IntervalUnit::from_i32(*unit)
+                    .ok_or_else(|| invalid_field("IntervalUnit", unit))?;
+                DataType::Interval(match unit {
+                    protobuf::IntervalUnit::YearMonth => IntervalUnit::YearMonth,
+                    protobuf::IntervalUnit::DayTime => IntervalUnit::DayTime,
+                })
+            }
+            ArrowTypeEnum::FixedSizeBinary(size) => DataType::FixedSizeBinary(*size),
+            ArrowTypeEnum::List(list) => DataType::List(list_field(list)?),
+            ArrowTypeEnum::LargeList(list) => DataType::LargeList(list_field(list)?),
+            ArrowTypeEnum::FixedSizeList(list) => {
+                let field = list
+                    .field_type
+                    .as_deref()
+                    .ok_or_else(|| missing_field("FixedSizeList.field_type"))?;
+                DataType::FixedSizeList(Box::new(Field::try_from(field)?), list.list_size)
+            }
+            ArrowTypeEnum::Struct(s) => DataType::Struct(fields(s)?),
+            ArrowTypeEnum::Union(s) => DataType::Union(fields(s)?),
+            ArrowTypeEnum::Dictionary(dictionary) => {
+                let key = dictionary
+                    .key
+                    .as_deref()
+                    .ok_or_else(|| missing_field("Dictionary.key"))?;
+                let value = dictionary
+                    .value
+                    .as_deref()
+                    .ok_or_else(|| missing_field("Dictionary.value"))?;
+                DataType::Dictionary(
+                    Box::new(DataType::try_from(key)?),
+                    Box::new(DataType::try_from(value)?),
+                )
+            }
+        })
+    }
+}
+
+fn serialize_time_unit(unit: &TimeUnit) -> protobuf::TimeUnit {
+    match unit {
+        TimeUnit::Second => protobuf::TimeUnit::Second,
+        TimeUnit::Millisecond => protobuf::TimeUnit::Millisecond,
+        TimeUnit::Microsecond => protobuf::TimeUnit::Microsecond,
+        TimeUnit::Nanosecond => protobuf::TimeUnit::Nanosecond,
+    }
+}
+
+fn parse_time_unit(unit: i32) -> Result<TimeUnit> {
+    let unit = protobuf::TimeUnit::from_i32(unit)
+        .ok_or_else(|| invalid_field("TimeUnit", unit))?;
+    Ok(match unit {
+        protobuf::TimeUnit::Second => TimeUnit::Second,
+        protobuf::TimeUnit::Millisecond => TimeUnit::Millisecond,
+        protobuf::TimeUnit::Microsecond => TimeUnit::Microsecond,
+        protobuf::TimeUnit::Nanosecond => TimeUnit::Nanosecond,
+    })
+}
+
+fn serialize_date_unit(unit: &DateUnit) -> protobuf::DateUnit {
+    match unit {
+        DateUnit::Day => protobuf::DateUnit::Day,
+        DateUnit::Millisecond => protobuf::DateUnit::DateMillisecond,
+    }
+}
+
+fn parse_date_unit(unit: i32) -> Result<DateUnit> {
+    let unit = protobuf::DateUnit::from_i32(unit)
+        .ok_or_else(|| invalid_field("DateUnit", unit))?;
+    Ok(match unit {
+        protobuf::DateUnit::Day => DateUnit::Day,
+        protobuf::DateUnit::DateMillisecond => DateUnit::Millisecond,
+    })
+}
+
+impl From<&ScalarValue> for protobuf::ScalarValue {
+    fn from(value: &ScalarValue) -> Self {
+        let temporal_value = |v: i64| {
+            Value::TemporalValue(protobuf::ScalarTemporalValue {
+                value: v,
+                datatype: Some((&value.get_datatype()).into()),
+            })
+        };
+        let value = match value {
+            ScalarValue::Boolean(Some(v)) => Value::BoolValue(*v),
+            ScalarValue::Float32(Some(v)) => Value::Float32Value(*v),
+            ScalarValue::Float64(Some(v)) => Value::Float64Value(*v),
+            ScalarValue::Int8(Some(v)) => Value::Int8Value(*v as i32),
+            ScalarValue::Int16(Some(v)) => Value::Int16Value(*v as i32),
+            ScalarValue::Int32(Some(v)) => Value::Int32Value(*v),
+            ScalarValue::Int64(Some(v)) => Value::Int64Value(*v),
+            ScalarValue::UInt8(Some(v)) => Value::Uint8Value(*v as u32),
+            ScalarValue::UInt16(Some(v)) => Value::Uint16Value(*v as u32),
+            ScalarValue::UInt32(Some(v)) => Value::Uint32Value(*v),
+            ScalarValue::UInt64(Some(v)) => Value::Uint64Value(*v),
+            ScalarValue::Utf8(Some(v)) => Value::Utf8Value(v.clone()),
+            ScalarValue::LargeUtf8(Some(v)) => Value::LargeUtf8Value(v.clone()),
+            ScalarValue::List(values, data_type) => {
+                Value::ListValue(protobuf::ScalarListValue {
+                    values: values
+                        .iter()
+                        .flatten()
+                        .map(protobuf::ScalarValue::from)
+                        .collect(),
+                    is_null: values.is_none(),
+                    datatype: Some(data_type.into()),
+                })
+            }
+            ScalarValue::Binary(Some(v)) => Value::BinaryValue(v.clone()),
+            ScalarValue::LargeBinary(Some(v)) => Value::LargeBinaryValue(v.clone()),
+            ScalarValue::FixedSizeBinary(Some(v), byte_width) => {
+                Value::FixedSizeBinaryValue(protobuf::ScalarFixedSizeBinaryValue {
+                    value: v.clone(),
+                    byte_width: *byte_width,
+                })
+            }
+            ScalarValue::Struct(values, fields) => {
+                Value::StructValue(protobuf::ScalarStructValue {
+                    values: values
+                        .iter()
+                        .flatten()
+                        .map(protobuf::ScalarValue::from)
+                        .collect(),
+                    is_null: values.is_none(),
+                    fields: fields.iter().map(protobuf::Field::from).collect(),
+                })
+            }
+            ScalarValue::Date32(Some(v))
+            | ScalarValue::Time32Second(Some(v))
+            | ScalarValue::Time32Millisecond(Some(v)) => temporal_value(*v as i64),
+            ScalarValue::Date64(Some(v))
+            | ScalarValue::Time64Microsecond(Some(v))
+            | ScalarValue::Time64Nanosecond(Some(v))
+            | ScalarValue::TimestampSecond(Some(v), _)
+            | ScalarValue::TimestampMillisecond(Some(v), _)
+            | ScalarValue::TimestampMicrosecond(Some(v), _)
+            | ScalarValue::TimestampNanosecond(Some(v), _)
+            | ScalarValue::DurationSecond(Some(v))
+            | ScalarValue::DurationMillisecond(Some(v))
+            | ScalarValue::DurationMicrosecond(Some(v))
+            | ScalarValue::DurationNanosecond(Some(v)) => temporal_value(*v),
+            ScalarValue::IntervalYearMonth(Some(v)) => Value::IntervalYearMonthValue(*v),
+            ScalarValue::IntervalDayTime(Some(v)) => Value::IntervalDayTimeValue(*v),
+            ScalarValue::Boolean(None)
+            | ScalarValue::Float32(None)
+            | ScalarValue::Float64(None)
+            | ScalarValue::Int8(None)
+            | ScalarValue::Int16(None)
+            | ScalarValue::Int32(None)
+            | ScalarValue::Int64(None)
+            | ScalarValue::UInt8(None)
+            | ScalarValue::UInt16(None)
+            | ScalarValue::UInt32(None)
+            | ScalarValue::UInt64(None)
+            | ScalarValue::Utf8(None)
+            | ScalarValue::LargeUtf8(None)
+            | ScalarValue::Binary(None)
+            | ScalarValue::LargeBinary(None)
+            | ScalarValue::FixedSizeBinary(None, _)
+            | ScalarValue::Date32(None)
+            | ScalarValue::Date64(None)
+            | ScalarValue::Time32Second(None)
+            | ScalarValue::Time32Millisecond(None)
+            | ScalarValue::Time64Microsecond(None)
+            | ScalarValue::Time64Nanosecond(None)
+            | ScalarValue::TimestampSecond(None, _)
+            | ScalarValue::TimestampMillisecond(None, _)
+            | ScalarValue::TimestampMicrosecond(None, _)
+            | ScalarValue::TimestampNanosecond(None, _)
+            | ScalarValue::DurationSecond(None)
+            | ScalarValue::DurationMillisecond(None)
+            | ScalarValue::DurationMicrosecond(None)
+            | ScalarValue::DurationNanosecond(None)
+            | ScalarValue::IntervalYearMonth(None)
+            | ScalarValue::IntervalDayTime(None) => {
+                Value::NullValue((&value.get_datatype()).into())
+            }
+        };
+        protobuf::ScalarValue { value: Some(value) }
+    }
+}
+
+impl TryFrom<&protobuf::ScalarValue> for ScalarValue {
+    type Error = DataFusionError;
+
+    fn try_from(proto: &protobuf::ScalarValue) -> Result<Self> {
+        let value = proto
+            .value
+            .as_ref()
+            .ok_or_else(|| missing_field("ScalarValue.value"))?;
+        Ok(match value {
+            Value::NullValue(data_type) => {
+                ScalarValue::try_from(&DataType::try_from(data_type)?)?
+            }
+            Value::BoolValue(v) => ScalarValue::Boolean(Some(*v)),
+            Value::Float32Value(v) => ScalarValue::Float32(Some(*v)),
+            Value::Float64Value(v) => ScalarValue::Float64(Some(*v)),
+            Value::Int8Value(v) => ScalarValue::Int8(Some(*v as i8)),
+            Value::Int16Value(v) => ScalarValue::Int16(Some(*v as i16)),
+            Value::Int32Value(v) => ScalarValue::Int32(Some(*v)),
+            Value::Int64Value(v) => ScalarValue::Int64(Some(*v)),
+            Value::Uint8Value(v) => ScalarValue::UInt8(Some(*v as u8)),
+            Value::Uint16Value(v) => ScalarValue::UInt16(Some(*v as u16)),
+            Value::Uint32Value(v) => ScalarValue::UInt32(Some(*v)),
+            Value::Uint64Value(v) => ScalarValue::UInt64(Some(*v)),
+            Value::Utf8Value(v) => ScalarValue::Utf8(Some(v.clone())),
+            Value::LargeUtf8Value(v) => ScalarValue::LargeUtf8(Some(v.clone())),
+            Value::ListValue(list) => {
+                let data_type = list
+                    .datatype
+                    .as_ref()
+                    .ok_or_else(|| missing_field("ScalarListValue.datatype"))?;
+                let values = if list.is_null {
+                    None
+                } else {
+                    Some(
+                        list.values
+                            .iter()
+                            .map(ScalarValue::try_from)
+                            .collect::<Result<_>>()?,
+                    )
+                };
+                ScalarValue::List(values, DataType::try_from(data_type)?)
+            }
+            Value::TemporalValue(temporal) => {
+                let data_type = temporal
+                    .datatype
+                    .as_ref()
+                    .ok_or_else(|| missing_field("ScalarTemporalValue.datatype"))?;
+                let v = temporal.value;
+                match DataType::try_from(data_type)? {
+                    DataType::Date32(DateUnit::Day) => {
+                        ScalarValue::Date32(Some(v as i32))
+                    }
+                    DataType::Date64(DateUnit::Millisecond) => {
+                        ScalarValue::Date64(Some(v))
+                    }
+                    DataType::Time32(TimeUnit::Second) => {
+                        ScalarValue::Time32Second(Some(v as i32))
+                    }
+                    DataType::Time32(TimeUnit::Millisecond) => {
+                        ScalarValue::Time32Millisecond(Some(v as i32))
+                    }
+                    DataType::Time64(TimeUnit::Microsecond) => {
+                        ScalarValue::Time64Microsecond(Some(v))
+                    }
+                    DataType::Time64(TimeUnit::Nanosecond) => {
+                        ScalarValue::Time64Nanosecond(Some(v))
+                    }
+                    DataType::Timestamp(TimeUnit::Second, tz) => {
+                        ScalarValue::TimestampSecond(Some(v), tz)
+                    }
+                    DataType::Timestamp(TimeUnit::Millisecond, tz) => {
+                        ScalarValue::TimestampMillisecond(Some(v), tz)
+                    }
+                    DataType::Timestamp(TimeUnit::Microsecond, tz) => {
+                        ScalarValue::TimestampMicrosecond(Some(v), tz)
+                    }
+                    DataType::Timestamp(TimeUnit::Nanosecond, tz) => {
+                        ScalarValue::TimestampNanosecond(Some(v), tz)
+                    }
+                    DataType::Duration(TimeUnit::Second) => {
+                        ScalarValue::DurationSecond(Some(v))
+                    }
+                    DataType::Duration(TimeUnit::Millisecond) => {
+                        ScalarValue::DurationMillisecond(Some(v))
+                    }
+                    DataType::Duration(TimeUnit::Microsecond) => {
+                        ScalarValue::DurationMicrosecond(Some(v))
+                    }
+                    DataType::Duration(TimeUnit::Nanosecond) => {
+                        ScalarValue::DurationNanosecond(Some(v))
+                    }
+                    other => {
+                        return Err(DataFusionError::Internal(format!(
+                            "Unsupported type of a temporal scalar value: {:?}",
+                            other
+                        )))
+                    }
+                }
+            }
+            Value::BinaryValue(v) => ScalarValue::Binary(Some(v.clone())),
+            Value::LargeBinaryValue(v) => ScalarValue::LargeBinary(Some(v.clone())),
+            Value::FixedSizeBinaryValue(v) => {
+                ScalarValue::FixedSizeBinary(Some(v.value.clone()), v.byte_width)
+            }
+            Value::StructValue(s) => {
+                let values = if s.is_null {
+                    None
+                } else {
+                    Some(
+                        s.values
+                            .iter()
+                            .map(ScalarValue::try_from)
+                            .collect::<Result<_>>()?,
+                    )
+                };
+                let fields = s
+                    .fields
+                    .iter()
+                    .map(Field::try_from)
+                    .collect::<Result<_>>()?;
+                ScalarValue::Struct(values, fields)
+            }
+            Value::IntervalYearMonthValue(v) => ScalarValue::IntervalYearMonth(Some(*v)),
+            Value::IntervalDayTimeValue(v) => ScalarValue::IntervalDayTime(Some(*v)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataframe::DataFrame;
+    use crate::execution::context::ExecutionContext;
+    use crate::logical_plan::{col, create_udf, lit, JoinType};
+    use crate::physical_plan::csv::CsvReadOptions;
+    use crate::physical_plan::functions::{make_scalar_function, Volatility};
+    use crate::serde::{
+        logical_plan_from_bytes, logical_plan_to_bytes, DefaultLogicalExtensionCodec,
+    };
+    use crate::test;
+    use arrow::array::ArrayRef;
+
+    fn roundtrip(ctx: &ExecutionContext, plan: &LogicalPlan) -> Result<()> {
+        let codec = DefaultLogicalExtensionCodec {};
+        let bytes = logical_plan_to_bytes(plan, &codec)?;
+        let roundtrip = logical_plan_from_bytes(&bytes, ctx.registry(), &codec)?;
+        assert_eq!(format!("{:?}", plan), format!("{:?}", roundtrip));
+        Ok(())
+    }
+
+    fn create_ctx() -> Result<ExecutionContext> {
+        let mut ctx = ExecutionContext::new();
+        let testdata = test::arrow_testdata_path();
+        ctx.register_csv(
+            "aggregate_test_100",
+            &format!("{}/csv/aggregate_test_100.csv", testdata),
+            CsvReadOptions::new().schema(&test::aggr_test_schema()),
+        )?;
+        Ok(ctx)
+    }
+
+    #[test]
+    fn roundtrip_sql_plan() -> Result<()> {
+        let ctx = create_ctx()?;
+        let sql = "SELECT c1, MIN(c12), COUNT(DISTINCT c3) FROM aggregate_test_100 \
+                   WHERE c2 IN (1, 2) AND c11 BETWEEN 0.1 AND 0.9 AND NOT c4 IS NULL \
+                   GROUP BY c1 ORDER BY c1 DESC LIMIT 3";
+        let plan = ctx.create_logical_plan(sql)?;
+        roundtrip(&ctx, &plan)?;
+        roundtrip(&ctx, &ctx.optimize(&plan)?)
+    }
+
+    #[test]
+    fn roundtrip_table_alias() -> Result<()> {
+        let ctx = create_ctx()?;
+        let sql = "SELECT t.c1, MAX(t.c4) FROM aggregate_test_100 AS t GROUP BY t.c1";
+        let plan = ctx.create_logical_plan(sql)?;
+        roundtrip(&ctx, &plan)?;
+        roundtrip(&ctx, &ctx.optimize(&plan)?)
+    }
+
+    #[test]
+    fn roundtrip_table_sample() -> Result<()> {
+        let ctx = create_ctx()?;
+        let sql =
+            "SELECT c1 FROM aggregate_test_100 TABLESAMPLE SYSTEM (12.5) REPEATABLE (3)";
+        let plan = ctx.create_logical_plan(sql)?;
+        roundtrip(&ctx, &plan)?;
+        roundtrip(&ctx, &ctx.optimize(&plan)?)
+    }
+
+    #[test]
+    fn roundtrip_join() -> Result<()> {
+        let mut ctx = create_ctx()?;
+        let right = ctx
+            .table("aggregate_test_100")?
+            .select_columns(vec!["c1", "c3"])?;
+        let plan = ctx
+            .table("aggregate_test_100")?
+            .select_columns(vec!["c1", "c2"])?
+            .join(right, JoinType::Full, &["c1"], &["c1"])?
+            .to_logical_plan();
+        roundtrip(&ctx, &plan)?;
+        roundtrip(&ctx, &ctx.optimize(&plan)?)
+    }
+
+    #[test]
+    fn roundtrip_explain_analyze() -> Result<()> {
+        let ctx = create_ctx()?;
+        let sql = "EXPLAIN ANALYZE SELECT c1 FROM aggregate_test_100 WHERE c2 > 3";
+        let plan = ctx.create_logical_plan(sql)?;
+        roundtrip(&ctx, &plan)?;
+        roundtrip(&ctx, &ctx.optimize(&plan)?)
+    }
+
+    #[test]
+    fn roundtrip_udf() -> Result<()> {
+        let mut ctx = create_ctx()?;
+        let identity = make_scalar_function(|args: &[ArrayRef]| Ok(args[0].clone()));
+        ctx.register_udf(create_udf(
+            "identity",
+            vec![DataType::Int32],
+            Arc::new(DataType::Int32),
+            Volatility::Immutable,
+            identity,
+        ));
+
+        let plan = ctx.create_logical_plan(
+            "SELECT identity(c5), CAST(c2 AS BIGINT), sqrt(c11) FROM aggregate_test_100",
+        )?;
+        roundtrip(&ctx, &plan)?;
+
+        // the UDF must be registered in the context that deserializes the plan
+        let codec = DefaultLogicalExtensionCodec {};
+        let bytes = logical_plan_to_bytes(&plan, &codec)?;
+        let err = logical_plan_from_bytes(&bytes, create_ctx()?.registry(), &codec)
+            .expect_err("the UDF should not be found");
+        assert_eq!(
+            format!("{:?}", err),
+            "Plan(\"There is no UDF named \\\"identity\\\" in the registry\")"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrip_expr() -> Result<()> {
+        let ctx = create_ctx()?;
+        let exprs = vec![
+            col("a").gt(lit(1)).alias("b"),
+            Expr::Column(Column::new(Some("t"), "a")),
+            Expr::Placeholder {
+                id: "$1".to_string(),
+                data_type: Some(DataType::Int64),
+            },
+            Expr::Wildcard,
+            col("c").sort(false, true),
+            col("s").field("a").index(2),
+        ];
+        for expr in exprs {
+            let proto = serialize_expr(&expr)?;
+            let roundtrip = parse_expr(&proto, ctx.registry())?;
+            assert_eq!(format!("{:?}", expr), format!("{:?}", roundtrip));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrip_scalar_values() -> Result<()> {
+        let values = vec![
+            ScalarValue::Boolean(Some(true)),
+            ScalarValue::Int8(Some(-8)),
+            ScalarValue::UInt16(None),
+            ScalarValue::Float64(Some(1.5)),
+            ScalarValue::Utf8(Some("datafusion".to_string())),
+            ScalarValue::LargeUtf8(None),
+            ScalarValue::List(
+                Some(vec![ScalarValue::Int32(Some(1)), ScalarValue::Int32(None)]),
+                DataType::Int32,
+            ),
+            ScalarValue::List(None, DataType::Utf8),
+            ScalarValue::TimestampNanosecond(Some(1_600_000_000_000_000_000), None),
+            ScalarValue::TimestampSecond(Some(1), Some(Arc::new("UTC".to_string()))),
+            ScalarValue::TimestampMillisecond(None, Some(Arc::new("UTC".to_string()))),
+            ScalarValue::Date32(Some(18_000)),
+            ScalarValue::Time32Millisecond(Some(-1)),
+            ScalarValue::DurationMicrosecond(Some(7)),
+            ScalarValue::IntervalDayTime(Some(3)),
+            ScalarValue::Binary(Some(vec![0, 255])),
+            ScalarValue::FixedSizeBinary(None, 4),
+            ScalarValue::Struct(
+                Some(vec![ScalarValue::Int32(Some(1)), ScalarValue::Utf8(None)]),
+                vec![
+                    Field::new("a", DataType::Int32, false),
+                    Field::new("b", DataType::Utf8, true),
+                ],
+            ),
+            ScalarValue::Struct(None, vec![Field::new("a", DataType::Int32, false)]),
+        ];
+        for value in values {
+            let proto = protobuf::ScalarValue::from(&value);
+            assert_eq!(value, ScalarValue::try_from(&proto)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrip_data_types() -> Result<()> {
+        let data_types = vec![
+            DataType::Timestamp(TimeUnit::Millisecond, Some(Arc::new("UTC".to_string()))),
+            DataType::Date32(DateUnit::Day),
+            DataType::Interval(IntervalUnit::YearMonth),
+            DataType::List(Box::new(Field::new("item", DataType::Float32, true))),
+            DataType::Struct(vec![
+                Field::new("a", DataType::UInt64, false),
+                Field::new("b", DataType::LargeBinary, true),
+            ]),
+            DataType::Dictionary(Box::new(DataType::Int16), Box::new(DataType::Utf8)),
+        ];
+        for data_type in data_types {
+            let proto = protobuf::ArrowType::from(&data_type);
+            assert_eq!(data_type, DataType::try_from(&proto)?);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Serialization of logical and physical plans to and from protobuf, so that query
+//! fragments can be sent to other processes.
+//!
+//! The protobuf schema is defined in `proto/datafusion.proto`. Functions are serialized
+//! by name: built-in functions are looked up when a plan is deserialized, and
+//! user-defined functions are resolved through a [FunctionRegistry], which must
+//! therefore know the same UDFs as the process that serialized the plan.
+//!
+//! ```
+//! # use datafusion::prelude::*;
+//! # use datafusion::error::Result;
+//! # use datafusion::serde::{logical_plan_from_bytes, logical_plan_to_bytes, DefaultLogicalExtensionCodec};
+//! # fn main() -> Result<()> {
+//! let mut ctx = ExecutionContext::new();
+//! ctx.register_csv("example", "tests/example.csv", CsvReadOptions::new())?;
+//! let plan = ctx.create_logical_plan("SELECT a, MIN(b) FROM example GROUP BY a")?;
+//!
+//! let codec = DefaultLogicalExtensionCodec {};
+//! let bytes = logical_plan_to_bytes(&plan, &codec)?;
+//! let plan2 = logical_plan_from_bytes(&bytes, ctx.registry(), &codec)?;
+//! assert_eq!(format!("{:?}", plan), format!("{:?}", plan2));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use arrow::error::Result as ArrowResult;
+use arrow::ipc::reader::StreamReader;
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+use prost::Message;
+
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::{
+    FunctionRegistry, JoinConstraint, JoinType, LogicalPlan, Operator, SampleMethod,
+    UserDefinedLogicalNode,
+};
+use crate::physical_plan::ExecutionPlan;
+
+pub mod logical_plan;
+pub mod physical_plan;
+
+/// Types generated from `proto/datafusion.proto`
+#[allow(missing_docs)]
+pub mod protobuf {
+    include!(concat!(env!("OUT_DIR"), "/datafusion.rs"));
+}
+
+/// Encodes and decodes the user-defined nodes of a logical plan
+/// ([LogicalPlan::Extension]), which DataFusion does not know how to serialize.
+pub trait LogicalExtensionCodec {
+    /// Encodes `node`, without its inputs, which are serialized by DataFusion
+    fn try_encode(&self, node: &dyn UserDefinedLogicalNode) -> Result<Vec<u8>>;
+
+    /// Decodes a node encoded by [LogicalExtensionCodec::try_encode], whose inputs
+    /// have already been deserialized
+    fn try_decode(
+        &self,
+        buf: &[u8],
+        inputs: &[LogicalPlan],
+    ) -> Result<Arc<dyn UserDefinedLogicalNode + Send + Sync>>;
+}
+
+/// A [LogicalExtensionCodec] for plans without user-defined nodes, that errors
+/// when it is asked to encode or decode one
+#[derive(Debug)]
+pub struct DefaultLogicalExtensionCodec {}
+
+impl LogicalExtensionCodec for DefaultLogicalExtensionCodec {
+    fn try_encode(&self, _node: &dyn UserDefinedLogicalNode) -> Result<Vec<u8>> {
+        Err(DataFusionError::NotImplemented(
+            "No extension codec was provided to serialize user-defined logical nodes"
+                .to_string(),
+        ))
+    }
+
+    fn try_decode(
+        &self,
+        _buf: &[u8],
+        _inputs: &[LogicalPlan],
+    ) -> Result<Arc<dyn UserDefinedLogicalNode + Send + Sync>> {
+        Err(DataFusionError::NotImplemented(
+            "No extension codec was provided to deserialize user-defined logical nodes"
+                .to_string(),
+        ))
+    }
+}
+
+/// Serializes a logical plan to bytes
+pub fn logical_plan_to_bytes(
+    plan: &LogicalPlan,
+    codec: &dyn LogicalExtensionCodec,
+) -> Result<Vec<u8>> {
+    let proto = logical_plan::serialize_logical_plan(plan, codec)?;
+    encode(&proto)
+}
+
+/// Deserializes a logical plan from bytes written by [logical_plan_to_bytes]
+pub fn logical_plan_from_bytes(
+    bytes: &[u8],
+    registry: &dyn FunctionRegistry,
+    codec: &dyn LogicalExtensionCodec,
+) -> Result<LogicalPlan> {
+    let proto = decode::<protobuf::LogicalPlanNode>(bytes)?;
+    logical_plan::parse_logical_plan(&proto, registry, codec)
+}
+
+/// Serializes a physical plan to bytes
+pub fn physical_plan_to_bytes(plan: &Arc<dyn ExecutionPlan>) -> Result<Vec<u8>> {
+    let proto = physical_plan::serialize_physical_plan(plan)?;
+    encode(&proto)
+}
+
+/// Deserializes a physical plan from bytes written by [physical_plan_to_bytes]
+pub fn physical_plan_from_bytes(
+    bytes: &[u8],
+    registry: &dyn FunctionRegistry,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let proto = decode::<protobuf::PhysicalPlanNode>(bytes)?;
+    physical_plan::parse_physical_plan(&proto, registry)
+}
+
+fn encode<M: Message>(message: &M) -> Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(message.encoded_len());
+    message.encode(&mut buf).map_err(|e| {
+        DataFusionError::Internal(format!("Could not encode plan: {}", e))
+    })?;
+    Ok(buf)
+}
+
+fn decode<M: Message + Default>(bytes: &[u8]) -> Result<M> {
+    M::decode(bytes)
+        .map_err(|e| DataFusionError::Execution(format!("Could not decode plan: {}", e)))
+}
+
+/// Returns the error for a message whose required field `field` is not set
+pub(crate) fn missing_field(field: &str) -> DataFusionError {
+    DataFusionError::Execution(format!(
+        "Could not deserialize plan: {} is missing",
+        field
+    ))
+}
+
+/// Returns the error for a message whose field `field` has an invalid value
+pub(crate) fn invalid_field(field: &str, value: impl std::fmt::Debug) -> DataFusionError {
+    DataFusionError::Execution(format!(
+        "Could not deserialize plan: {:?} is not a valid value of {}",
+        value, field
+    ))
+}
+
+pub(crate) fn serialize_join_type(join_type: &JoinType) -> protobuf::JoinType {
+    match join_type {
+        JoinType::Inner => protobuf::JoinType::Inner,
+        JoinType::Left => protobuf::JoinType::Left,
+        JoinType::Right => protobuf::JoinType::Right,
+        JoinType::Full => protobuf::JoinType::Full,
+        JoinType::Semi => protobuf::JoinType::Semi,
+        JoinType::Anti => protobuf::JoinType::Anti,
+    }
+}
+
+pub(crate) fn parse_join_type(join_type: i32) -> Result<JoinType> {
+    let join_type = protobuf::JoinType::from_i32(join_type)
+        .ok_or_else(|| invalid_field("JoinType", join_type))?;
+    Ok(match join_type {
+        protobuf::JoinType::Inner => JoinType::Inner,
+        protobuf::JoinType::Left => JoinType::Left,
+        protobuf::JoinType::Right => JoinType::Right,
+        protobuf::JoinType::Full => JoinType::Full,
+        protobuf::JoinType::Semi => JoinType::Semi,
+        protobuf::JoinType::Anti => JoinType::Anti,
+    })
+}
+
+pub(crate) fn serialize_join_constraint(
+    join_constraint: &JoinConstraint,
+) -> protobuf::JoinConstraint {
+    match join_constraint {
+        JoinConstraint::On => protobuf::JoinConstraint::On,
+        JoinConstraint::Using => protobuf::JoinConstraint::Using,
+    }
+}
+
+pub(crate) fn parse_join_constraint(join_constraint: i32) -> Result<JoinConstraint> {
+    let join_constraint = protobuf::JoinConstraint::from_i32(join_constraint)
+        .ok_or_else(|| invalid_field("JoinConstraint", join_constraint))?;
+    Ok(match join_constraint {
+        protobuf::JoinConstraint::On => JoinConstraint::On,
+        protobuf::JoinConstraint::Using => JoinConstraint::Using,
+    })
+}
+
+pub(crate) fn serialize_sample_method(method: SampleMethod) -> protobuf::SampleMethod {
+    match method {
+        SampleMethod::Bernoulli => protobuf::SampleMethod::Bernoulli,
+        SampleMethod::System => protobuf::SampleMethod::System,
+    }
+}
+
+pub(crate) fn parse_sample_method(method: i32) -> Result<SampleMethod> {
+    let method = protobuf::SampleMethod::from_i32(method)
+        .ok_or_else(|| invalid_field("SampleMethod", method))?;
+    Ok(match method {
+        protobuf::SampleMethod::Bernoulli => SampleMethod::Bernoulli,
+        protobuf::SampleMethod::System => SampleMethod::System,
+    })
+}
+
+pub(crate) fn serialize_operator(op: &Operator) -> protobuf::BinaryOperator {
+    match op {
+        Operator::Eq => protobuf::BinaryOperator::Eq,
+        Operator::NotEq => protobuf::BinaryOperator::NotEq,
+        Operator::Lt => protobuf::BinaryOperator::Lt,
+        Operator::LtEq => protobuf::BinaryOperator::LtEq,
+        Operator::Gt => protobuf::BinaryOperator::Gt,
+        Operator::GtEq => protobuf::BinaryOperator::GtEq,
+        Operator::Plus => protobuf::BinaryOperator::Plus,
+        Operator::Minus => protobuf::BinaryOperator::Minus,
+        Operator::Multiply => protobuf::BinaryOperator::Multiply,
+        Operator::Divide => protobuf::BinaryOperator::Divide,
+        Operator::Modulus => protobuf::BinaryOperator::Modulus,
+        Operator::And => protobuf::BinaryOperator::And,
+        Operator::Or => protobuf::BinaryOperator::Or,
+        Operator::Like => protobuf::BinaryOperator::Like,
+        Operator::NotLike => protobuf::BinaryOperator::NotLike,
+        Operator::RegexMatch => protobuf::BinaryOperator::RegexMatch,
+        Operator::RegexIMatch => protobuf::BinaryOperator::RegexIMatch,
+        Operator::RegexNotMatch => protobuf::BinaryOperator::RegexNotMatch,
+        Operator::RegexNotIMatch => protobuf::BinaryOperator::RegexNotIMatch,
+        Operator::IsDistinctFrom => protobuf::BinaryOperator::IsDistinctFrom,
+        Operator::IsNotDistinctFrom => protobuf::BinaryOperator::IsNotDistinctFrom,
+    }
+}
+
+pub(crate) fn parse_operator(op: i32) -> Result<Operator> {
+    let op = protobuf::BinaryOperator::from_i32(op)
+        .ok_or_else(|| invalid_field("BinaryOperator", op))?;
+    Ok(match op {
+        protobuf::BinaryOperator::Eq => Operator::Eq,
+        protobuf::BinaryOperator::NotEq => Operator::NotEq,
+        protobuf::BinaryOperator::Lt => Operator::Lt,
+        protobuf::BinaryOperator::LtEq => Operator::LtEq,
+        protobuf::BinaryOperator::Gt => Operator::Gt,
+        protobuf::BinaryOperator::GtEq => Operator::GtEq,
+        protobuf::BinaryOperator::Plus => Operator::Plus,
+        protobuf::BinaryOperator::Minus => Operator::Minus,
+        protobuf::BinaryOperator::Multiply => Operator::Multiply,
+        protobuf::BinaryOperator::Divide => Operator::Divide,
+        protobuf::BinaryOperator::Modulus => Operator::Modulus,
+        protobuf::BinaryOperator::And => Operator::And,
+        protobuf::BinaryOperator::Or => Operator::Or,
+        protobuf::BinaryOperator::Like => Operator::Like,
+        protobuf::BinaryOperator::NotLike => Operator::NotLike,
+        protobuf::BinaryOperator::RegexMatch => Operator::RegexMatch,
+        protobuf::BinaryOperator::RegexIMatch => Operator::RegexIMatch,
+        protobuf::BinaryOperator::RegexNotMatch => Operator::RegexNotMatch,
+        protobuf::BinaryOperator::RegexNotIMatch => Operator::RegexNotIMatch,
+        protobuf::BinaryOperator::IsDistinctFrom => Operator::IsDistinctFrom,
+        protobuf::BinaryOperator::IsNotDistinctFrom => Operator::IsNotDistinctFrom,
+    })
+}
+
+pub(crate) fn serialize_projection(
+    projection: Option<&Vec<usize>>,
+) -> Option<protobuf::ProjectionColumns> {
+    projection.map(|columns| protobuf::ProjectionColumns {
+        columns: columns.iter().map(|i| *i as u64).collect(),
+    })
+}
+
+pub(crate) fn parse_projection(
+    projection: Option<&protobuf::ProjectionColumns>,
+) -> Option<Vec<usize>> {
+    projection.map(|p| p.columns.iter().map(|i| *i as usize).collect())
+}
+
+/// Encodes a partition of record batches as an Arrow IPC stream. Empty partitions
+/// are encoded as no bytes at all, as they have no schema to write.
+pub(crate) fn serialize_batches(batches: &[RecordBatch]) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    if let Some(first) = batches.first() {
+        let mut writer = StreamWriter::try_new(&mut bytes, &first.schema())?;
+        for batch in batches {
+            writer.write(batch)?;
+        }
+        writer.finish()?;
+    }
+    Ok(bytes)
+}
+
+/// Decodes a partition encoded by [serialize_batches]
+pub(crate) fn parse_batches(bytes: &[u8]) -> Result<Vec<RecordBatch>> {
+    if bytes.is_empty() {
+        return Ok(vec![]);
+    }
+    let reader = StreamReader::try_new(bytes)?;
+    Ok(reader.collect::<ArrowResult<Vec<_>>>()?)
+}
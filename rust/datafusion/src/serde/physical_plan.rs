@@ -0,0 +1,788 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Conversion of the built-in execution plans and physical expressions to and from
+//! their protobuf representation
+
+use std::convert::TryFrom;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow::compute::SortOptions;
+use arrow::datatypes::{DataType, Schema};
+
+use super::logical_plan::{parse_delimiter, parse_required_schema, serialize_delimiter};
+use super::protobuf::{
+    physical_expr_node::ExprType, physical_plan_node::PhysicalPlanType,
+};
+use super::{
+    invalid_field, missing_field, parse_batches, parse_join_constraint, parse_join_type,
+    parse_operator, parse_projection, parse_sample_method, protobuf, serialize_batches,
+    serialize_join_constraint, serialize_join_type, serialize_operator,
+    serialize_projection, serialize_sample_method,
+};
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::{FunctionRegistry, StringifiedPlan};
+use crate::physical_plan::aggregates::{self, AggregateFunction};
+use crate::physical_plan::analyze::AnalyzeExec;
+use crate::physical_plan::csv::{CsvExec, CsvReadOptions};
+use crate::physical_plan::distinct_expressions::DistinctCount;
+use crate::physical_plan::empty::EmptyExec;
+use crate::physical_plan::explain::ExplainExec;
+use crate::physical_plan::expressions::{
+    Avg, BinaryExpr, CastExpr, Column, Count, InListExpr, IsNotNullExpr, IsNullExpr,
+    Literal, Max, Min, NotExpr, PhysicalSortExpr, Sum,
+};
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::functions::{self, BuiltinScalarFunction, ScalarFunctionExpr};
+use crate::physical_plan::hash_aggregate::{AggregateMode, HashAggregateExec};
+use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
+use crate::physical_plan::memory::MemoryExec;
+use crate::physical_plan::merge::MergeExec;
+use crate::physical_plan::parquet::ParquetExec;
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::sample::SampleExec;
+use crate::physical_plan::sort::SortExec;
+use crate::physical_plan::sort_merge_join::SortMergeJoinExec;
+use crate::physical_plan::sort_preserving_merge::SortPreservingMergeExec;
+use crate::physical_plan::udaf::{self, AggregateFunctionExpr};
+use crate::physical_plan::udf;
+use crate::physical_plan::{AggregateExpr, ExecutionPlan, PhysicalExpr};
+use crate::scalar::ScalarValue;
+
+/// Serializes an execution plan made of the built-in operators of DataFusion
+pub fn serialize_physical_plan(
+    plan: &Arc<dyn ExecutionPlan>,
+) -> Result<protobuf::PhysicalPlanNode> {
+    let input = |input: &Arc<dyn ExecutionPlan>| -> Result<Option<Box<protobuf::PhysicalPlanNode>>> {
+        Ok(Some(Box::new(serialize_physical_plan(input)?)))
+    };
+    let any = plan.as_any();
+    let plan_type = if let Some(exec) = any.downcast_ref::<ProjectionExec>() {
+        PhysicalPlanType::Projection(Box::new(protobuf::ProjectionExecNode {
+            input: input(exec.input())?,
+            expr: exec
+                .expr()
+                .iter()
+                .map(|(expr, _)| serialize_physical_expr(expr))
+                .collect::<Result<_>>()?,
+            expr_name: exec.expr().iter().map(|(_, name)| name.clone()).collect(),
+        }))
+    } else if let Some(exec) = any.downcast_ref::<FilterExec>() {
+        PhysicalPlanType::Filter(Box::new(protobuf::FilterExecNode {
+            input: input(exec.input())?,
+            expr: Some(serialize_physical_expr(exec.predicate())?),
+        }))
+    } else if let Some(exec) = any.downcast_ref::<HashAggregateExec>() {
+        PhysicalPlanType::HashAggregate(Box::new(protobuf::HashAggregateExecNode {
+            input: input(exec.input())?,
+            mode: match exec.mode() {
+                AggregateMode::Partial => protobuf::AggregateMode::Partial,
+                AggregateMode::Final => protobuf::AggregateMode::Final,
+            } as i32,
+            group_expr: exec
+                .group_expr()
+                .iter()
+                .map(|(expr, _)| serialize_physical_expr(expr))
+                .collect::<Result<_>>()?,
+            group_expr_name: exec
+                .group_expr()
+                .iter()
+                .map(|(_, name)| name.clone())
+                .collect(),
+            aggr_expr: exec
+                .aggr_expr()
+                .iter()
+                .map(serialize_aggregate_expr)
+                .collect::<Result<_>>()?,
+            input_schema: Some(exec.input_schema().as_ref().into()),
+        }))
+    } else if let Some(exec) = any.downcast_ref::<SortExec>() {
+        PhysicalPlanType::Sort(Box::new(protobuf::SortExecNode {
+            input: input(exec.input())?,
+            expr: serialize_sort_exprs(exec.expr())?,
+            concurrency: exec.concurrency() as u64,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<GlobalLimitExec>() {
+        PhysicalPlanType::GlobalLimit(Box::new(protobuf::GlobalLimitExecNode {
+            input: input(exec.input())?,
+            limit: exec.limit() as u64,
+            concurrency: exec.concurrency() as u64,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<LocalLimitExec>() {
+        PhysicalPlanType::LocalLimit(Box::new(protobuf::LocalLimitExecNode {
+            input: input(exec.input())?,
+            limit: exec.limit() as u64,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<MergeExec>() {
+        PhysicalPlanType::Merge(Box::new(protobuf::MergeExecNode {
+            input: input(exec.input())?,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<CsvExec>() {
+        PhysicalPlanType::CsvScan(protobuf::CsvScanExecNode {
+            path: exec.path().to_string(),
+            schema: Some(exec.file_schema().as_ref().into()),
+            has_header: exec.has_header(),
+            delimiter: serialize_delimiter(exec.delimiter()),
+            file_extension: exec.file_extension().to_string(),
+            projection: serialize_projection(exec.projection()),
+            batch_size: exec.batch_size() as u64,
+            target_partition_size: exec.target_partition_size(),
+            newlines_in_values: exec.newlines_in_values(),
+        })
+    } else if let Some(exec) = any.downcast_ref::<ParquetExec>() {
+        PhysicalPlanType::ParquetScan(protobuf::ParquetScanExecNode {
+            filenames: exec.filenames().to_vec(),
+            projection: serialize_projection(Some(&exec.projection().to_vec())),
+            batch_size: exec.batch_size() as u64,
+            output_ordering: serialize_sort_exprs(
+                &exec.output_ordering().unwrap_or_default(),
+            )?,
+            row_group_sample: exec
+                .row_group_sample()
+                .map(|(fraction, seed)| protobuf::RowGroupSample { fraction, seed }),
+        })
+    } else if let Some(exec) = any.downcast_ref::<MemoryExec>() {
+        PhysicalPlanType::Memory(protobuf::MemoryExecNode {
+            partitions: exec
+                .partitions()
+                .iter()
+                .map(|batches| serialize_batches(batches))
+                .collect::<Result<_>>()?,
+            schema: Some(exec.schema().as_ref().into()),
+            projection: serialize_projection(exec.projection()),
+            output_ordering: serialize_sort_exprs(
+                &exec.output_ordering().unwrap_or_default(),
+            )?,
+        })
+    } else if let Some(exec) = any.downcast_ref::<EmptyExec>() {
+        PhysicalPlanType::Empty(protobuf::EmptyExecNode {
+            schema: Some(exec.schema().as_ref().into()),
+        })
+    } else if let Some(exec) = any.downcast_ref::<ExplainExec>() {
+        PhysicalPlanType::Explain(protobuf::ExplainExecNode {
+            schema: Some(exec.schema().as_ref().into()),
+            stringified_plans: exec
+                .stringified_plans()
+                .iter()
+                .map(|p| p.into())
+                .collect(),
+        })
+    } else if let Some(exec) = any.downcast_ref::<AnalyzeExec>() {
+        PhysicalPlanType::Analyze(Box::new(protobuf::AnalyzeExecNode {
+            input: input(exec.input())?,
+            verbose: exec.verbose(),
+            schema: Some(exec.schema().as_ref().into()),
+        }))
+    } else if let Some(exec) = any.downcast_ref::<SortMergeJoinExec>() {
+        PhysicalPlanType::SortMergeJoin(Box::new(protobuf::SortMergeJoinExecNode {
+            left: input(exec.left())?,
+            right: input(exec.right())?,
+            on: exec
+                .on()
+                .iter()
+                .map(|(left, right)| protobuf::JoinOn {
+                    left: left.clone(),
+                    right: right.clone(),
+                })
+                .collect(),
+            join_type: serialize_join_type(exec.join_type()) as i32,
+            batch_size: exec.batch_size() as u64,
+            join_constraint: serialize_join_constraint(exec.join_constraint()) as i32,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<SortPreservingMergeExec>() {
+        PhysicalPlanType::SortPreservingMerge(Box::new(
+            protobuf::SortPreservingMergeExecNode {
+                input: input(exec.input())?,
+                expr: serialize_sort_exprs(exec.expr())?,
+                batch_size: exec.batch_size() as u64,
+            },
+        ))
+    } else if let Some(exec) = any.downcast_ref::<SampleExec>() {
+        PhysicalPlanType::Sample(Box::new(protobuf::SampleExecNode {
+            input: input(exec.input())?,
+            method: serialize_sample_method(exec.method()) as i32,
+            fraction: exec.fraction(),
+            seed: exec.seed(),
+        }))
+    } else {
+        return Err(DataFusionError::NotImplemented(format!(
+            "Serialization of {:?} is not supported",
+            plan
+        )));
+    };
+    Ok(protobuf::PhysicalPlanNode {
+        physical_plan_type: Some(plan_type),
+    })
+}
+
+/// Deserializes an execution plan. Functions are resolved by name through `registry`.
+pub fn parse_physical_plan(
+    proto: &protobuf::PhysicalPlanNode,
+    registry: &dyn FunctionRegistry,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let input = |input: Option<&protobuf::PhysicalPlanNode>,
+                 field: &str|
+     -> Result<Arc<dyn ExecutionPlan>> {
+        parse_physical_plan(input.ok_or_else(|| missing_field(field))?, registry)
+    };
+    let plan_type = proto
+        .physical_plan_type
+        .as_ref()
+        .ok_or_else(|| missing_field("PhysicalPlanNode.physical_plan_type"))?;
+    Ok(match plan_type {
+        PhysicalPlanType::Projection(node) => {
+            let input = input(node.input.as_deref(), "ProjectionExecNode.input")?;
+            let expr = parse_physical_exprs(&node.expr, &input.schema(), registry)?;
+            Arc::new(ProjectionExec::try_new(
+                expr.into_iter()
+                    .zip(node.expr_name.iter().cloned())
+                    .collect(),
+                input,
+            )?)
+        }
+        PhysicalPlanType::Filter(node) => {
+            let input = input(node.input.as_deref(), "FilterExecNode.input")?;
+            let predicate = parse_required_physical_expr(
+                node.expr.as_ref(),
+                &input.schema(),
+                registry,
+                "FilterExecNode.expr",
+            )?;
+            Arc::new(FilterExec::try_new(predicate, input)?)
+        }
+        PhysicalPlanType::HashAggregate(node) => {
+            let input = input(node.input.as_deref(), "HashAggregateExecNode.input")?;
+            let mode = protobuf::AggregateMode::from_i32(node.mode)
+                .ok_or_else(|| invalid_field("AggregateMode", node.mode))?;
+            let mode = match mode {
+                protobuf::AggregateMode::Partial => AggregateMode::Partial,
+                protobuf::AggregateMode::Final => AggregateMode::Final,
+            };
+            let group_expr =
+                parse_physical_exprs(&node.group_expr, &input.schema(), registry)?;
+            let input_schema = Arc::new(parse_required_schema(
+                node.input_schema.as_ref(),
+                "HashAggregateExecNode.input_schema",
+            )?);
+            let aggr_expr = node
+                .aggr_expr
+                .iter()
+                .map(|e| parse_aggregate_expr(e, &input_schema, registry))
+                .collect::<Result<_>>()?;
+            Arc::new(HashAggregateExec::try_new(
+                mode,
+                group_expr
+                    .into_iter()
+                    .zip(node.group_expr_name.iter().cloned())
+                    .collect(),
+                aggr_expr,
+                input,
+                input_schema,
+            )?)
+        }
+        PhysicalPlanType::Sort(node) => {
+            let input = input(node.input.as_deref(), "SortExecNode.input")?;
+            let expr = parse_sort_exprs(&node.expr, &input.schema(), registry)?;
+            Arc::new(SortExec::try_new(expr, input, node.concurrency as usize)?)
+        }
+        PhysicalPlanType::GlobalLimit(node) => {
+            let input = input(node.input.as_deref(), "GlobalLimitExecNode.input")?;
+            Arc::new(GlobalLimitExec::new(
+                input,
+                node.limit as usize,
+                node.concurrency as usize,
+            ))
+        }
+        PhysicalPlanType::LocalLimit(node) => {
+            let input = input(node.input.as_deref(), "LocalLimitExecNode.input")?;
+            Arc::new(LocalLimitExec::new(input, node.limit as usize))
+        }
+        PhysicalPlanType::Merge(node) => {
+            let input = input(node.input.as_deref(), "MergeExecNode.input")?;
+            Arc::new(MergeExec::new(input))
+        }
+        PhysicalPlanType::CsvScan(node) => {
+            let schema =
+                parse_required_schema(node.schema.as_ref(), "CsvScanExecNode.schema")?;
+            let options = CsvReadOptions::new()
+                .schema(&schema)
+                .has_header(node.has_header)
+                .delimiter_option(parse_delimiter(&node.delimiter)?)
+                .file_extension(&node.file_extension)
+                .target_partition_size(node.target_partition_size)
+                .newlines_in_values(node.newlines_in_values);
+            Arc::new(CsvExec::try_new(
+                &node.path,
+                options,
+                parse_projection(node.projection.as_ref()),
+                node.batch_size as usize,
+            )?)
+        }
+        PhysicalPlanType::ParquetScan(node) => {
+            let exec = ParquetExec::try_from_files(
+                node.filenames.clone(),
+                parse_projection(node.projection.as_ref()),
+                node.batch_size as usize,
+            )?;
+            let exec = match &node.row_group_sample {
+                Some(sample) => {
+                    exec.with_row_group_sample(sample.fraction, sample.seed)?
+                }
+                None => exec,
+            };
+            let ordering =
+                parse_sort_exprs(&node.output_ordering, &exec.schema(), registry)?;
+            Arc::new(exec.with_output_ordering(ordering))
+        }
+        PhysicalPlanType::Memory(node) => {
+            let schema =
+                parse_required_schema(node.schema.as_ref(), "MemoryExecNode.schema")?;
+            let partitions = node
+                .partitions
+                .iter()
+                .map(|bytes| parse_batches(bytes))
+                .collect::<Result<Vec<_>>>()?;
+            let exec = MemoryExec::try_new(
+                &partitions,
+                Arc::new(schema),
+                parse_projection(node.projection.as_ref()),
+            )?;
+            let ordering =
+                parse_sort_exprs(&node.output_ordering, &exec.schema(), registry)?;
+            Arc::new(exec.with_output_ordering(ordering))
+        }
+        PhysicalPlanType::Empty(node) => {
+            let schema =
+                parse_required_schema(node.schema.as_ref(), "EmptyExecNode.schema")?;
+            Arc::new(EmptyExec::new(Arc::new(schema)))
+        }
+        PhysicalPlanType::Explain(node) => {
+            let schema =
+                parse_required_schema(node.schema.as_ref(), "ExplainExecNode.schema")?;
+            Arc::new(ExplainExec::new(
+                Arc::new(schema),
+                node.stringified_plans
+                    .iter()
+                    .map(StringifiedPlan::try_from)
+                    .collect::<Result<_>>()?,
+            ))
+        }
+        PhysicalPlanType::Analyze(node) => {
+            let input = input(node.input.as_deref(), "AnalyzeExecNode.input")?;
+            let schema =
+                parse_required_schema(node.schema.as_ref(), "AnalyzeExecNode.schema")?;
+            Arc::new(AnalyzeExec::new(node.verbose, input, Arc::new(schema)))
+        }
+        PhysicalPlanType::SortMergeJoin(node) => {
+            let left = input(node.left.as_deref(), "SortMergeJoinExecNode.left")?;
+            let right = input(node.right.as_deref(), "SortMergeJoinExecNode.right")?;
+            let on = node
+                .on
+                .iter()
+                .map(|on| (on.left.clone(), on.right.clone()))
+                .collect();
+            Arc::new(SortMergeJoinExec::try_new(
+                left,
+                right,
+                &on,
+                &parse_join_type(node.join_type)?,
+                &parse_join_constraint(node.join_constraint)?,
+                node.batch_size as usize,
+            )?)
+        }
+        PhysicalPlanType::SortPreservingMerge(node) => {
+            let input =
+                input(node.input.as_deref(), "SortPreservingMergeExecNode.input")?;
+            let expr = parse_sort_exprs(&node.expr, &input.schema(), registry)?;
+            Arc::new(SortPreservingMergeExec::try_new(
+                expr,
+                input,
+                node.batch_size as usize,
+            )?)
+        }
+        PhysicalPlanType::Sample(node) => {
+            let input = input(node.input.as_deref(), "SampleExecNode.input")?;
+            Arc::new(SampleExec::try_new(
+                input,
+                parse_sample_method(node.method)?,
+                node.fraction,
+                node.seed,
+            )?)
+        }
+    })
+}
+
+fn serialize_sort_exprs(
+    expr: &[PhysicalSortExpr],
+) -> Result<Vec<protobuf::PhysicalSortExprNode>> {
+    expr.iter()
+        .map(|e| {
+            Ok(protobuf::PhysicalSortExprNode {
+                expr: Some(serialize_physical_expr(&e.expr)?),
+                asc: !e.options.descending,
+                nulls_first: e.options.nulls_first,
+            })
+        })
+        .collect()
+}
+
+fn parse_sort_exprs(
+    expr: &[protobuf::PhysicalSortExprNode],
+    input_schema: &Schema,
+    registry: &dyn FunctionRegistry,
+) -> Result<Vec<PhysicalSortExpr>> {
+    expr.iter()
+        .map(|e| {
+            Ok(PhysicalSortExpr {
+                expr: parse_required_physical_expr(
+                    e.expr.as_ref(),
+                    input_schema,
+                    registry,
+                    "PhysicalSortExprNode.expr",
+                )?,
+                options: SortOptions {
+                    descending: !e.asc,
+                    nulls_first: e.nulls_first,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Serializes a physical expression made of the built-in expressions of DataFusion
+pub fn serialize_physical_expr(
+    expr: &Arc<dyn PhysicalExpr>,
+) -> Result<protobuf::PhysicalExprNode> {
+    let boxed = |expr: &Arc<dyn PhysicalExpr>| -> Result<Option<Box<protobuf::PhysicalExprNode>>> {
+        Ok(Some(Box::new(serialize_physical_expr(expr)?)))
+    };
+    let any = expr.as_any();
+    let expr_type = if let Some(column) = any.downcast_ref::<Column>() {
+        ExprType::ColumnName(column.name().to_string())
+    } else if let Some(literal) = any.downcast_ref::<Literal>() {
+        ExprType::Literal(literal.value().into())
+    } else if let Some(binary) = any.downcast_ref::<BinaryExpr>() {
+        ExprType::BinaryExpr(Box::new(protobuf::PhysicalBinaryExprNode {
+            l: boxed(binary.left())?,
+            op: serialize_operator(binary.op()) as i32,
+            r: boxed(binary.right())?,
+        }))
+    } else if let Some(not) = any.downcast_ref::<NotExpr>() {
+        ExprType::NotExpr(Box::new(protobuf::PhysicalNotNode {
+            expr: boxed(not.arg())?,
+        }))
+    } else if let Some(is_null) = any.downcast_ref::<IsNullExpr>() {
+        ExprType::IsNullExpr(Box::new(protobuf::PhysicalIsNullNode {
+            expr: boxed(is_null.arg())?,
+        }))
+    } else if let Some(is_not_null) = any.downcast_ref::<IsNotNullExpr>() {
+        ExprType::IsNotNullExpr(Box::new(protobuf::PhysicalIsNotNullNode {
+            expr: boxed(is_not_null.arg())?,
+        }))
+    } else if let Some(in_list) = any.downcast_ref::<InListExpr>() {
+        ExprType::InList(Box::new(protobuf::PhysicalInListNode {
+            expr: boxed(in_list.expr())?,
+            list: in_list
+                .list()
+                .iter()
+                .map(serialize_physical_expr)
+                .collect::<Result<_>>()?,
+            negated: in_list.negated(),
+        }))
+    } else if let Some(cast) = any.downcast_ref::<CastExpr>() {
+        ExprType::Cast(Box::new(protobuf::PhysicalCastNode {
+            expr: boxed(cast.expr())?,
+            arrow_type: Some(cast.cast_type().into()),
+        }))
+    } else if let Some(fun) = any.downcast_ref::<ScalarFunctionExpr>() {
+        ExprType::ScalarFunction(protobuf::PhysicalScalarFunctionNode {
+            name: fun.name().to_string(),
+            args: fun
+                .args()
+                .iter()
+                .map(serialize_physical_expr)
+                .collect::<Result<_>>()?,
+        })
+    } else {
+        return Err(DataFusionError::NotImplemented(format!(
+            "Serialization of the physical expression {:?} is not supported",
+            expr
+        )));
+    };
+    Ok(protobuf::PhysicalExprNode {
+        expr_type: Some(expr_type),
+    })
+}
+
+/// Deserializes a physical expression that is evaluated against `input_schema`.
+/// Scalar functions are resolved by name, first among the built-in functions and
+/// then through `registry`.
+pub fn parse_physical_expr(
+    proto: &protobuf::PhysicalExprNode,
+    input_schema: &Schema,
+    registry: &dyn FunctionRegistry,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let required = |expr: Option<&protobuf::PhysicalExprNode>,
+                    field: &str|
+     -> Result<Arc<dyn PhysicalExpr>> {
+        parse_required_physical_expr(expr, input_schema, registry, field)
+    };
+    let expr_type = proto
+        .expr_type
+        .as_ref()
+        .ok_or_else(|| missing_field("PhysicalExprNode.expr_type"))?;
+    Ok(match expr_type {
+        ExprType::ColumnName(name) => Arc::new(Column::new(name)),
+        ExprType::Literal(value) => Arc::new(Literal::new(ScalarValue::try_from(value)?)),
+        ExprType::BinaryExpr(node) => Arc::new(BinaryExpr::new(
+            required(node.l.as_deref(), "PhysicalBinaryExprNode.l")?,
+            parse_operator(node.op)?,
+            required(node.r.as_deref(), "PhysicalBinaryExprNode.r")?,
+        )),
+        ExprType::NotExpr(node) => Arc::new(NotExpr::new(required(
+            node.expr.as_deref(),
+            "PhysicalNotNode.expr",
+        )?)),
+        ExprType::IsNullExpr(node) => Arc::new(IsNullExpr::new(required(
+            node.expr.as_deref(),
+            "PhysicalIsNullNode.expr",
+        )?)),
+        ExprType::IsNotNullExpr(node) => Arc::new(IsNotNullExpr::new(required(
+            node.expr.as_deref(),
+            "PhysicalIsNotNullNode.expr",
+        )?)),
+        ExprType::InList(node) => Arc::new(InListExpr::new(
+            required(node.expr.as_deref(), "PhysicalInListNode.expr")?,
+            parse_physical_exprs(&node.list, input_schema, registry)?,
+            node.negated,
+        )),
+        ExprType::Cast(node) => {
+            let cast_type = node
+                .arrow_type
+                .as_ref()
+                .ok_or_else(|| missing_field("PhysicalCastNode.arrow_type"))?;
+            Arc::new(CastExpr::new(
+                required(node.expr.as_deref(), "PhysicalCastNode.expr")?,
+                DataType::try_from(cast_type)?,
+            ))
+        }
+        ExprType::ScalarFunction(node) => {
+            let args = parse_physical_exprs(&node.args, input_schema, registry)?;
+            match BuiltinScalarFunction::from_str(&node.name) {
+                Ok(fun) => functions::create_physical_expr(&fun, &args, input_schema)?,
+                Err(_) => udf::create_physical_expr(
+                    registry.udf(&node.name)?,
+                    &args,
+                    input_schema,
+                )?,
+            }
+        }
+    })
+}
+
+fn parse_physical_exprs(
+    proto: &[protobuf::PhysicalExprNode],
+    input_schema: &Schema,
+    registry: &dyn FunctionRegistry,
+) -> Result<Vec<Arc<dyn PhysicalExpr>>> {
+    proto
+        .iter()
+        .map(|e| parse_physical_expr(e, input_schema, registry))
+        .collect()
+}
+
+fn parse_required_physical_expr(
+    proto: Option<&protobuf::PhysicalExprNode>,
+    input_schema: &Schema,
+    registry: &dyn FunctionRegistry,
+    field: &str,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    parse_physical_expr(
+        proto.ok_or_else(|| missing_field(field))?,
+        input_schema,
+        registry,
+    )
+}
+
+fn serialize_aggregate_expr(
+    expr: &Arc<dyn AggregateExpr>,
+) -> Result<protobuf::PhysicalAggregateExprNode> {
+    let any = expr.as_any();
+    let (fun_name, user_defined, distinct) = if any.is::<Sum>() {
+        (AggregateFunction::Sum.to_string(), false, false)
+    } else if any.is::<Avg>() {
+        (AggregateFunction::Avg.to_string(), false, false)
+    } else if any.is::<Max>() {
+        (AggregateFunction::Max.to_string(), false, false)
+    } else if any.is::<Min>() {
+        (AggregateFunction::Min.to_string(), false, false)
+    } else if any.is::<Count>() {
+        (AggregateFunction::Count.to_string(), false, false)
+    } else if any.is::<DistinctCount>() {
+        (AggregateFunction::Count.to_string(), false, true)
+    } else if let Some(udaf) = any.downcast_ref::<AggregateFunctionExpr>() {
+        (udaf.fun().name.clone(), true, false)
+    } else {
+        return Err(DataFusionError::NotImplemented(format!(
+            "Serialization of the aggregate expression {:?} is not supported",
+            expr
+        )));
+    };
+    Ok(protobuf::PhysicalAggregateExprNode {
+        fun_name,
+        user_defined,
+        distinct,
+        args: expr
+            .expressions()
+            .iter()
+            .map(serialize_physical_expr)
+            .collect::<Result<_>>()?,
+        name: expr.field()?.name().clone(),
+    })
+}
+
+fn parse_aggregate_expr(
+    proto: &protobuf::PhysicalAggregateExprNode,
+    input_schema: &Schema,
+    registry: &dyn FunctionRegistry,
+) -> Result<Arc<dyn AggregateExpr>> {
+    let args = parse_physical_exprs(&proto.args, input_schema, registry)?;
+    if proto.user_defined {
+        udaf::create_aggregate_expr(
+            registry.udaf(&proto.fun_name)?,
+            &args,
+            input_schema,
+            proto.name.clone(),
+        )
+    } else {
+        aggregates::create_aggregate_expr(
+            &AggregateFunction::from_str(&proto.fun_name)?,
+            proto.distinct,
+            &args,
+            input_schema,
+            proto.name.clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::MemTable;
+    use crate::execution::context::ExecutionContext;
+    use crate::serde::{physical_plan_from_bytes, physical_plan_to_bytes};
+    use crate::test;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::Field;
+    use arrow::record_batch::RecordBatch;
+    use arrow::util::pretty::pretty_format_batches;
+
+    async fn roundtrip(ctx: &ExecutionContext, sql: &str) -> Result<()> {
+        let plan = ctx.create_logical_plan(sql)?;
+        let plan = ctx.optimize(&plan)?;
+        let plan = ctx.create_physical_plan(&plan)?;
+
+        let bytes = physical_plan_to_bytes(&plan)?;
+        let roundtrip = physical_plan_from_bytes(&bytes, ctx.registry())?;
+        assert_eq!(format!("{:?}", plan), format!("{:?}", roundtrip));
+
+        let expected = pretty_format_batches(&ctx.collect(plan).await?)?;
+        let actual = pretty_format_batches(&ctx.collect(roundtrip).await?)?;
+        assert_eq!(expected, actual);
+        Ok(())
+    }
+
+    fn create_ctx() -> Result<ExecutionContext> {
+        let mut ctx = ExecutionContext::new();
+        let testdata = test::arrow_testdata_path();
+        ctx.register_csv(
+            "aggregate_test_100",
+            &format!("{}/csv/aggregate_test_100.csv", testdata),
+            CsvReadOptions::new().schema(&test::aggr_test_schema()),
+        )?;
+        Ok(ctx)
+    }
+
+    #[tokio::test]
+    async fn roundtrip_aggregate() -> Result<()> {
+        let ctx = create_ctx()?;
+        roundtrip(
+            &ctx,
+            "SELECT c1, MIN(c12), AVG(c3), COUNT(DISTINCT c2) FROM aggregate_test_100 \
+             WHERE c11 > 0.1 AND c2 NOT IN (1, 3) \
+             GROUP BY c1 ORDER BY c1 DESC LIMIT 3",
+        )
+        .await
+    }
+
+    #[test]
+    fn roundtrip_explain_analyze() -> Result<()> {
+        let ctx = create_ctx()?;
+        let plan = ctx.create_logical_plan(
+            "EXPLAIN ANALYZE VERBOSE SELECT c1 FROM aggregate_test_100 WHERE c2 > 3",
+        )?;
+        let plan = ctx.create_physical_plan(&ctx.optimize(&plan)?)?;
+
+        let bytes = physical_plan_to_bytes(&plan)?;
+        let roundtrip = physical_plan_from_bytes(&bytes, ctx.registry())?;
+        assert_eq!(format!("{:?}", plan), format!("{:?}", roundtrip));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn roundtrip_functions() -> Result<()> {
+        let ctx = create_ctx()?;
+        roundtrip(
+            &ctx,
+            "SELECT upper(c1), sqrt(c12), CAST(c2 AS DOUBLE), c13 IS NULL \
+             FROM aggregate_test_100 ORDER BY c12 LIMIT 5",
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn roundtrip_sample() -> Result<()> {
+        let ctx = create_ctx()?;
+        roundtrip(
+            &ctx,
+            "SELECT c1, c2 FROM aggregate_test_100 \
+             TABLESAMPLE BERNOULLI (50) REPEATABLE (3)",
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn roundtrip_sort_preserving_merge() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = |a: Vec<i32>| {
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(a))])
+        };
+        let table = MemTable::new(
+            schema.clone(),
+            vec![vec![batch(vec![1, 4])?], vec![batch(vec![2, 3])?]],
+        )?
+        .with_sort_order(vec![PhysicalSortExpr {
+            expr: Arc::new(Column::new("a")),
+            options: SortOptions::default(),
+        }]);
+        let mut ctx = ExecutionContext::new();
+        ctx.register_table("t", Box::new(table));
+
+        roundtrip(&ctx, "SELECT a FROM t ORDER BY a").await
+    }
+}
@@ -19,6 +19,9 @@
 //!
 //! Declares a SQL parser based on sqlparser that handles custom formats that we need.
 
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
 use sqlparser::{
     ast::{ColumnDef, Statement as SQLStatement, TableConstraint},
     dialect::{keywords::Keyword, Dialect, GenericDialect},
@@ -33,6 +36,9 @@ macro_rules! parser_err {
     };
 }
 
+/// The name of the table hint that a `TABLESAMPLE` clause is rewritten into
+pub(crate) const TABLESAMPLE_HINT: &str = "__tablesample";
+
 /// Types of files to parse as DataFrames
 #[derive(Debug, Clone, PartialEq)]
 pub enum FileType {
@@ -59,9 +65,11 @@ pub struct CreateExternalTable {
     pub location: String,
 }
 
-/// DataFusion extension DDL for `EXPLAIN` and `EXPLAIN VERBOSE`
+/// DataFusion extension DDL for `EXPLAIN [ANALYZE] [VERBOSE]`
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExplainPlan {
+    /// If true, runs the statement and reports the metrics recorded while it ran
+    pub analyze: bool,
     /// If true, dumps more intermediate plans and results of optimizaton passes
     pub verbose: bool,
     /// The statement for which to generate an planning explanation
@@ -101,7 +109,7 @@ impl DFParser {
         let mut tokenizer = Tokenizer::new(dialect, sql);
         let tokens = tokenizer.tokenize()?;
         Ok(DFParser {
-            parser: Parser::new(tokens),
+            parser: Parser::new(rewrite_table_samples(rewrite_placeholders(tokens)?)?),
         })
     }
 
@@ -184,23 +192,31 @@ impl DFParser {
     /// Parse an SQL EXPLAIN statement.
     pub fn parse_explain(&mut self) -> Result<Statement, ParserError> {
         // Parser is at the token immediately after EXPLAIN
-        // Check for EXPLAIN VERBOSE
-        let verbose = match self.parser.peek_token() {
-            Token::Word(w) => match w.keyword {
-                Keyword::NoKeyword if w.value.to_uppercase() == "VERBOSE" => {
-                    self.parser.next_token();
-                    true
-                }
-                _ => false,
-            },
-            _ => false,
-        };
+        // Check for EXPLAIN ANALYZE and EXPLAIN VERBOSE
+        let analyze = self.consume_word("ANALYZE");
+        let verbose = self.consume_word("VERBOSE");
 
         let statement = Box::new(self.parse_statement()?);
-        let explain_plan = ExplainPlan { statement, verbose };
+        let explain_plan = ExplainPlan {
+            statement,
+            analyze,
+            verbose,
+        };
         Ok(Statement::Explain(explain_plan))
     }
 
+    /// Consumes the next token if it is the word `word`, in any case, and returns
+    /// whether it did
+    fn consume_word(&mut self, word: &str) -> bool {
+        match self.parser.peek_token() {
+            Token::Word(w) if w.value.to_uppercase() == word => {
+                self.parser.next_token();
+                true
+            }
+            _ => false,
+        }
+    }
+
     // This is a copy of the equivalent implementation in sqlparser.
     fn parse_columns(
         &mut self,
@@ -317,6 +333,134 @@ impl DFParser {
     }
 }
 
+/// Replaces the placeholders of a parameterized query, `$1` or `?`, by words that the
+/// SQL planner recognizes as placeholders. `?` placeholders are numbered in their order
+/// of appearance.
+fn rewrite_placeholders(tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let mut result = Vec::with_capacity(tokens.len());
+    let mut positional = 0;
+    let mut numbered = false;
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Char('?') => {
+                positional += 1;
+                result.push(Token::make_word(&format!("${}", positional), None));
+            }
+            Token::Char('$') => match tokens.peek() {
+                Some(Token::Number(n)) => {
+                    numbered = true;
+                    let word = format!("${}", n);
+                    tokens.next();
+                    result.push(Token::make_word(&word, None));
+                }
+                _ => result.push(Token::Char('$')),
+            },
+            token => result.push(token),
+        }
+    }
+    if numbered && positional > 0 {
+        return parser_err!("Placeholders `?` and `$n` cannot be used in the same query");
+    }
+    Ok(result)
+}
+
+/// Replaces the `TABLESAMPLE BERNOULLI | SYSTEM (percentage) [REPEATABLE (seed)]`
+/// clauses of tables, which sqlparser does not support, by the table hint
+/// `WITH (__tablesample('bernoulli' | 'system', percentage [, seed]))`, which the SQL
+/// planner turns into a sample of the table.
+fn rewrite_table_samples(tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let mut result = Vec::with_capacity(tokens.len());
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        if !is_word(&token, "TABLESAMPLE") {
+            result.push(token);
+            continue;
+        }
+        skip_whitespace(&mut tokens);
+        let method = match tokens.next() {
+            Some(ref t) if is_word(t, "BERNOULLI") => "bernoulli",
+            Some(ref t) if is_word(t, "SYSTEM") => "system",
+            Some(other) => {
+                return parser_err!(format!(
+                    "Expected BERNOULLI or SYSTEM, found: {}",
+                    other
+                ))
+            }
+            None => return parser_err!("Expected BERNOULLI or SYSTEM, found: EOF"),
+        };
+        let mut args = vec![Token::SingleQuotedString(method.to_string()), Token::Comma];
+        args.extend(parenthesized_tokens(&mut tokens)?);
+        let whitespace = skip_whitespace(&mut tokens);
+        if tokens.peek().map_or(false, |t| is_word(t, "REPEATABLE")) {
+            tokens.next();
+            args.push(Token::Comma);
+            args.extend(parenthesized_tokens(&mut tokens)?);
+        } else {
+            result.extend(whitespace);
+        }
+
+        result.push(Token::make_word("WITH", None));
+        result.push(Token::LParen);
+        result.push(Token::make_word(TABLESAMPLE_HINT, None));
+        result.push(Token::LParen);
+        result.extend(args);
+        result.push(Token::RParen);
+        result.push(Token::RParen);
+    }
+    Ok(result)
+}
+
+/// Whether `token` is the unquoted word `word`, in any case
+fn is_word(token: &Token, word: &str) -> bool {
+    match token {
+        Token::Word(w) => w.quote_style.is_none() && w.value.to_uppercase() == word,
+        _ => false,
+    }
+}
+
+/// Consumes the whitespace at the start of `tokens`, and returns it
+fn skip_whitespace(tokens: &mut Peekable<IntoIter<Token>>) -> Vec<Token> {
+    let mut whitespace = vec![];
+    while let Some(Token::Whitespace(_)) = tokens.peek() {
+        whitespace.extend(tokens.next());
+    }
+    whitespace
+}
+
+/// Consumes a parenthesized list of tokens at the start of `tokens`, after any
+/// whitespace, and returns the tokens between the parentheses
+fn parenthesized_tokens(
+    tokens: &mut Peekable<IntoIter<Token>>,
+) -> Result<Vec<Token>, ParserError> {
+    skip_whitespace(tokens);
+    match tokens.next() {
+        Some(Token::LParen) => {}
+        Some(other) => return parser_err!(format!("Expected (, found: {}", other)),
+        None => return parser_err!("Expected (, found: EOF"),
+    }
+    let mut inner = vec![];
+    let mut depth = 0;
+    loop {
+        match tokens.next() {
+            Some(Token::RParen) if depth == 0 => break,
+            Some(token) => {
+                match token {
+                    Token::LParen => depth += 1,
+                    Token::RParen => depth -= 1,
+                    _ => {}
+                }
+                inner.push(token);
+            }
+            None => return parser_err!("Expected ), found: EOF"),
+        }
+    }
+    if inner.iter().all(|t| matches!(t, Token::Whitespace(_))) {
+        return parser_err!("Expected an expression, found: )");
+    }
+    Ok(inner)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -402,4 +546,77 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn explain() -> Result<(), ParserError> {
+        let parse = |sql: &str| -> Result<(bool, bool), ParserError> {
+            match DFParser::parse_sql(sql)?.remove(0) {
+                Statement::Explain(plan) => Ok((plan.analyze, plan.verbose)),
+                other => panic!("Expected an EXPLAIN statement, found {:?}", other),
+            }
+        };
+        assert_eq!((false, false), parse("EXPLAIN SELECT 1")?);
+        assert_eq!((false, true), parse("EXPLAIN VERBOSE SELECT 1")?);
+        assert_eq!((true, false), parse("explain analyze SELECT 1")?);
+        assert_eq!((true, true), parse("EXPLAIN ANALYZE VERBOSE SELECT 1")?);
+        Ok(())
+    }
+
+    #[test]
+    fn placeholders() -> Result<(), ParserError> {
+        let parse = |sql: &str| -> Result<String, ParserError> {
+            match DFParser::parse_sql(sql)?.remove(0) {
+                Statement::Statement(statement) => Ok(statement.to_string()),
+                other => panic!("Expected a query, found {:?}", other),
+            }
+        };
+
+        assert_eq!(
+            parse("SELECT a FROM t WHERE a > ? AND b = ?")?,
+            "SELECT a FROM t WHERE a > $1 AND b = $2"
+        );
+        assert_eq!(
+            parse("SELECT a FROM t WHERE a > $2 AND b = $1")?,
+            "SELECT a FROM t WHERE a > $2 AND b = $1"
+        );
+        expect_parse_error(
+            "SELECT a FROM t WHERE a > ? AND b = $1",
+            "Placeholders `?` and `$n` cannot be used in the same query",
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn table_samples() -> Result<(), ParserError> {
+        let parse = |sql: &str| -> Result<String, ParserError> {
+            match DFParser::parse_sql(sql)?.remove(0) {
+                Statement::Statement(statement) => Ok(statement.to_string()),
+                other => panic!("Expected a query, found {:?}", other),
+            }
+        };
+
+        assert_eq!(
+            parse("SELECT a FROM t TABLESAMPLE BERNOULLI (10) WHERE a > 1")?,
+            "SELECT a FROM t WITH (__tablesample('bernoulli', 10)) WHERE a > 1"
+        );
+        assert_eq!(
+            parse("SELECT a FROM t AS s tablesample system(2.5) repeatable (42)")?,
+            "SELECT a FROM t AS s WITH (__tablesample('system', 2.5, 42))"
+        );
+        expect_parse_error(
+            "SELECT a FROM t TABLESAMPLE RESERVOIR (10)",
+            "Expected BERNOULLI or SYSTEM, found: RESERVOIR",
+        )?;
+        expect_parse_error(
+            "SELECT a FROM t TABLESAMPLE BERNOULLI",
+            "Expected (, found: EOF",
+        )?;
+        expect_parse_error(
+            "SELECT a FROM t TABLESAMPLE BERNOULLI (10) REPEATABLE ()",
+            "Expected an expression, found: )",
+        )?;
+
+        Ok(())
+    }
 }
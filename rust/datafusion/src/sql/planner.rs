@@ -22,7 +22,8 @@ use std::sync::Arc;
 
 use crate::logical_plan::Expr::Alias;
 use crate::logical_plan::{
-    lit, Expr, LogicalPlan, LogicalPlanBuilder, Operator, PlanType, StringifiedPlan,
+    lit, Column, DFSchema, Expr, JoinType, LogicalPlan, LogicalPlanBuilder, Operator,
+    PlanType, SampleMethod, StringifiedPlan, ToDFSchema,
 };
 use crate::scalar::ScalarValue;
 use crate::{
@@ -30,17 +31,25 @@ use crate::{
     physical_plan::udaf::AggregateUDF,
 };
 use crate::{
+    physical_plan::datetime_expressions::string_to_timestamp_nanos,
+    physical_plan::expressions::cast_scalar,
+    physical_plan::table_functions::BuiltinTableFunction,
     physical_plan::udf::ScalarUDF,
+    physical_plan::udtf::TableUDF,
     physical_plan::{aggregates, functions},
-    sql::parser::{CreateExternalTable, FileType, Statement as DFStatement},
+    sql::parser::{
+        CreateExternalTable, FileType, Statement as DFStatement, TABLESAMPLE_HINT,
+    },
 };
 
 use arrow::datatypes::*;
+use chrono::NaiveDate;
 
 use super::parser::ExplainPlan;
 use sqlparser::ast::{
-    BinaryOperator, DataType as SQLDataType, Expr as SQLExpr, Query, Select, SelectItem,
-    SetExpr, TableFactor, TableWithJoins, UnaryOperator, Value,
+    BinaryOperator, DataType as SQLDataType, DateTimeField, Expr as SQLExpr,
+    JoinConstraint, JoinOperator, Query, Select, SelectItem, SetExpr, TableFactor,
+    TableWithJoins, UnaryOperator, Value,
 };
 use sqlparser::ast::{ColumnDef as SQLColumnDef, ColumnOption};
 use sqlparser::ast::{OrderByExpr, Statement};
@@ -54,6 +63,8 @@ pub trait SchemaProvider {
     fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>>;
     /// Getter for a UDAF description
     fn get_aggregate_meta(&self, name: &str) -> Option<Arc<AggregateUDF>>;
+    /// Getter for a UDTF description
+    fn get_table_function_meta(&self, name: &str) -> Option<Arc<TableUDF>>;
 }
 
 /// SQL query planner
@@ -133,7 +144,7 @@ impl<'a, S: SchemaProvider> SqlToRel<'a, S> {
             FileType::NdJson => {}
         };
 
-        let schema = SchemaRef::new(self.build_schema(&columns)?);
+        let schema = self.build_schema(&columns)?.to_dfschema_ref()?;
 
         Ok(LogicalPlan::CreateExternalTable {
             schema,
@@ -153,12 +164,18 @@ impl<'a, S: SchemaProvider> SqlToRel<'a, S> {
         let verbose = explain_plan.verbose;
         let plan = self.statement_to_plan(&explain_plan.statement)?;
 
+        if explain_plan.analyze {
+            return LogicalPlanBuilder::from(&plan)
+                .explain_analyze(verbose)?
+                .build();
+        }
+
         let stringified_plans = vec![StringifiedPlan::new(
             PlanType::LogicalPlan,
             format!("{:#?}", plan),
         )];
 
-        let schema = LogicalPlan::explain_schema();
+        let schema = LogicalPlan::explain_schema().to_dfschema_ref()?;
         let plan = Arc::new(plan);
 
         Ok(LogicalPlan::Explain {
@@ -197,9 +214,9 @@ impl<'a, S: SchemaProvider> SqlToRel<'a, S> {
             SQLDataType::Float(_) => Ok(DataType::Float32),
             SQLDataType::Real | SQLDataType::Double => Ok(DataType::Float64),
             SQLDataType::Boolean => Ok(DataType::Boolean),
-            SQLDataType::Date => Ok(DataType::Date64(DateUnit::Day)),
+            SQLDataType::Date => Ok(DataType::Date32(DateUnit::Day)),
             SQLDataType::Time => Ok(DataType::Time64(TimeUnit::Millisecond)),
-            SQLDataType::Timestamp => Ok(DataType::Date64(DateUnit::Millisecond)),
+            SQLDataType::Timestamp => Ok(DataType::Timestamp(TimeUnit::Nanosecond, None)),
             _ => Err(DataFusionError::NotImplemented(format!(
                 "The SQL data type {:?} is not implemented",
                 sql_type
@@ -216,23 +233,109 @@ impl<'a, S: SchemaProvider> SqlToRel<'a, S> {
                 "FROM with multiple tables is still not implemented".to_string(),
             ));
         };
-        let relation = &from[0].relation;
+        let mut plan = self.relation_to_plan(&from[0].relation)?;
+        for join in &from[0].joins {
+            let right = self.relation_to_plan(&join.relation)?;
+            plan = self.join_to_plan(&plan, &right, &join.join_operator)?;
+        }
+        Ok(plan)
+    }
+
+    /// Generate a logical plan joining `left` and `right`. Only the joins whose
+    /// condition is a conjunction of equalities of a left column and a right column
+    /// are supported.
+    fn join_to_plan(
+        &self,
+        left: &LogicalPlan,
+        right: &LogicalPlan,
+        operator: &JoinOperator,
+    ) -> Result<LogicalPlan> {
+        let (join_type, constraint) = match operator {
+            JoinOperator::Inner(constraint) => (JoinType::Inner, constraint),
+            JoinOperator::LeftOuter(constraint) => (JoinType::Left, constraint),
+            JoinOperator::RightOuter(constraint) => (JoinType::Right, constraint),
+            JoinOperator::FullOuter(constraint) => (JoinType::Full, constraint),
+            _ => {
+                return Err(DataFusionError::NotImplemented(format!(
+                    "Unsupported join operator {:?}",
+                    operator
+                )))
+            }
+        };
+        let (left_keys, right_keys) = match constraint {
+            JoinConstraint::On(expr) => {
+                // the condition may reference the columns of both inputs
+                let mut fields = left.schema().fields().clone();
+                fields.extend(right.schema().fields().iter().cloned());
+                let expr = self.sql_to_rex(expr, &DFSchema::new(fields)?)?;
+
+                let mut keys = vec![];
+                extract_join_keys(&expr, left.schema(), right.schema(), &mut keys)?;
+                keys.into_iter().unzip()
+            }
+            JoinConstraint::Using(idents) => {
+                let keys = idents
+                    .iter()
+                    .map(|id| Column::from_name(&id.value))
+                    .collect();
+                return LogicalPlanBuilder::from(left)
+                    .join_using(right, join_type, keys)?
+                    .build();
+            }
+            JoinConstraint::Natural => {
+                return Err(DataFusionError::NotImplemented(
+                    "NATURAL JOIN is not supported".to_string(),
+                ))
+            }
+        };
+        LogicalPlanBuilder::from(left)
+            .join(right, join_type, left_keys, right_keys)?
+            .build()
+    }
+
+    /// Generate a logical plan scanning a table of the FROM clause
+    fn relation_to_plan(&self, relation: &TableFactor) -> Result<LogicalPlan> {
         match relation {
-            TableFactor::Table { name, .. } => {
+            TableFactor::Table {
+                name,
+                alias,
+                args,
+                with_hints,
+                ..
+            } => {
                 let name = name.to_string();
-                match self.schema_provider.get_table_meta(&name) {
-                    Some(schema) => Ok(LogicalPlanBuilder::scan(
-                        "default",
-                        &name,
-                        schema.as_ref(),
-                        None,
-                    )?
-                    .build()?),
-                    None => Err(DataFusionError::Plan(format!(
-                        "no schema found for table {}",
-                        name
-                    ))),
-                }
+                let alias = match alias {
+                    Some(alias) if !alias.columns.is_empty() => {
+                        return Err(DataFusionError::NotImplemented(
+                            "Column aliases of tables are not supported".to_string(),
+                        ))
+                    }
+                    Some(alias) => Some(alias.name.value.as_str()),
+                    None => None,
+                };
+                let plan = if !args.is_empty() {
+                    self.table_function_to_plan(&name, alias, args)?
+                } else {
+                    match self.schema_provider.get_table_meta(&name) {
+                        Some(schema) => LogicalPlanBuilder::scan_with_alias(
+                            "default",
+                            &name,
+                            alias,
+                            schema.as_ref(),
+                            None,
+                        )?
+                        .build()?,
+                        None => {
+                            return Err(DataFusionError::Plan(format!(
+                                "no schema found for table {}",
+                                name
+                            )))
+                        }
+                    }
+                };
+                with_hints
+                    .iter()
+                    .try_fold(plan, |plan, hint| self.table_hint_to_plan(plan, hint))
             }
             _ => Err(DataFusionError::NotImplemented(
                 "Subqueries are still not supported".to_string(),
@@ -240,6 +343,114 @@ impl<'a, S: SchemaProvider> SqlToRel<'a, S> {
         }
     }
 
+    /// Apply a hint of a table of the FROM clause to the plan of the table. The only
+    /// supported hints are the samples of `TABLESAMPLE` clauses, e.g.
+    /// `FROM t TABLESAMPLE BERNOULLI (10) REPEATABLE (42)`, which the parser rewrites
+    /// into hints. Without `REPEATABLE`, the seed of the sample is 0.
+    fn table_hint_to_plan(
+        &self,
+        plan: LogicalPlan,
+        hint: &SQLExpr,
+    ) -> Result<LogicalPlan> {
+        let args = match hint {
+            SQLExpr::Function(function)
+                if function.name.to_string() == TABLESAMPLE_HINT =>
+            {
+                &function.args
+            }
+            _ => {
+                return Err(DataFusionError::NotImplemented(format!(
+                    "Unsupported table hint {}",
+                    hint
+                )))
+            }
+        };
+        let method = match args.first() {
+            Some(SQLExpr::Value(Value::SingleQuotedString(method)))
+                if method == "bernoulli" =>
+            {
+                SampleMethod::Bernoulli
+            }
+            Some(SQLExpr::Value(Value::SingleQuotedString(method)))
+                if method == "system" =>
+            {
+                SampleMethod::System
+            }
+            _ => {
+                return Err(DataFusionError::Internal(format!(
+                    "Invalid TABLESAMPLE hint {}",
+                    hint
+                )))
+            }
+        };
+        let number = |arg: &SQLExpr, data_type: &DataType| -> Result<ScalarValue> {
+            match self.sql_to_rex(arg, &DFSchema::empty())? {
+                Expr::Literal(value) => cast_scalar(&value, data_type),
+                other => Err(DataFusionError::Plan(format!(
+                    "The arguments of TABLESAMPLE must be numbers, but got {:?}",
+                    other
+                ))),
+            }
+        };
+        let fraction = match args.get(1).map(|arg| number(arg, &DataType::Float64)) {
+            Some(Ok(ScalarValue::Float64(Some(percentage))))
+                if (0.0..=100.0).contains(&percentage) =>
+            {
+                percentage / 100.0
+            }
+            Some(Err(e)) => return Err(e),
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "The percentage of TABLESAMPLE must be between 0 and 100, but is {}",
+                    args.get(1).map(|arg| arg.to_string()).unwrap_or_default()
+                )))
+            }
+        };
+        let seed = match args.get(2).map(|arg| number(arg, &DataType::UInt64)) {
+            None => 0,
+            Some(Ok(ScalarValue::UInt64(Some(seed)))) => seed,
+            Some(Err(e)) => return Err(e),
+            Some(Ok(_)) => {
+                return Err(DataFusionError::Plan(format!(
+                    "The seed of REPEATABLE must be a non-negative integer, but is {}",
+                    args[2]
+                )))
+            }
+        };
+        LogicalPlanBuilder::from(&plan)
+            .sample(method, fraction, seed)?
+            .build()
+    }
+
+    /// Generate a logical plan scanning the table of a table function call of the
+    /// FROM clause, e.g. `generate_series(1, 10)`
+    fn table_function_to_plan(
+        &self,
+        name: &str,
+        alias: Option<&str>,
+        args: &[SQLExpr],
+    ) -> Result<LogicalPlan> {
+        let args = args
+            .iter()
+            .map(|arg| table_function_arg(&self.sql_to_rex(arg, &DFSchema::empty())?))
+            .collect::<Result<Vec<_>>>()?;
+
+        // first, table built-ins, then user-defined table functions (UDTF)
+        let table = match BuiltinTableFunction::from_str(name) {
+            Ok(fun) => fun.create_table(&args)?,
+            Err(_) => match self.schema_provider.get_table_function_meta(name) {
+                Some(fun) => fun.call(&args)?,
+                None => {
+                    return Err(DataFusionError::Plan(format!(
+                        "Invalid table function '{}'",
+                        name
+                    )))
+                }
+            },
+        };
+        LogicalPlanBuilder::scan_provider(table, alias, None)?.build()
+    }
+
     /// Generate a logic plan from an SQL select
     fn select_to_plan(&self, select: &Select) -> Result<LogicalPlan> {
         if select.having.is_some() {
@@ -335,7 +546,7 @@ impl<'a, S: SchemaProvider> SqlToRel<'a, S> {
                 &plan,
                 expected_columns
                     .iter()
-                    .map(|c| Expr::Column(c.clone()))
+                    .map(|c| Expr::Column(Column::from_name(c)))
                     .collect(),
             )
         } else {
@@ -388,7 +599,7 @@ impl<'a, S: SchemaProvider> SqlToRel<'a, S> {
     }
 
     /// Generate a relational expression from a select SQL expression
-    fn sql_select_to_rex(&self, sql: &SelectItem, schema: &Schema) -> Result<Expr> {
+    fn sql_select_to_rex(&self, sql: &SelectItem, schema: &DFSchema) -> Result<Expr> {
         match sql {
             SelectItem::UnnamedExpr(expr) => self.sql_to_rex(expr, schema),
             SelectItem::ExprWithAlias { expr, alias } => Ok(Alias(
@@ -403,27 +614,57 @@ impl<'a, S: SchemaProvider> SqlToRel<'a, S> {
     }
 
     /// Generate a relational expression from a SQL expression
-    pub fn sql_to_rex(&self, sql: &SQLExpr, schema: &Schema) -> Result<Expr> {
+    pub fn sql_to_rex(&self, sql: &SQLExpr, schema: &DFSchema) -> Result<Expr> {
         match sql {
             SQLExpr::Value(Value::Number(n)) => match n.parse::<i64>() {
                 Ok(n) => Ok(lit(n)),
                 Err(_) => Ok(lit(n.parse::<f64>().unwrap())),
             },
             SQLExpr::Value(Value::SingleQuotedString(ref s)) => Ok(lit(s.clone())),
+            SQLExpr::Value(Value::Date(ref s)) => parse_date(s),
+            SQLExpr::Value(Value::Timestamp(ref s)) => {
+                Ok(Expr::Literal(ScalarValue::TimestampNanosecond(
+                    Some(string_to_timestamp_nanos(s)?),
+                    None,
+                )))
+            }
+
+            SQLExpr::Value(Value::Interval {
+                ref value,
+                ref leading_field,
+                ref last_field,
+                ..
+            }) => {
+                if last_field.is_some() {
+                    return Err(DataFusionError::NotImplemented(format!(
+                        "Unsupported interval literal {:?}: ranges of fields are not supported",
+                        sql
+                    )));
+                }
+                parse_interval(value, leading_field)
+            }
+
+            SQLExpr::Extract {
+                ref field,
+                ref expr,
+            } => Ok(Expr::ScalarFunction {
+                fun: functions::BuiltinScalarFunction::DatePart,
+                args: vec![
+                    lit(field.to_string().to_lowercase()),
+                    self.sql_to_rex(expr, schema)?,
+                ],
+            }),
 
             SQLExpr::Identifier(ref id) => {
-                if &id.value[0..1] == "@" {
+                if id.quote_style.is_none() && id.value.starts_with('$') {
+                    parse_placeholder(&id.value)
+                } else if &id.value[0..1] == "@" {
                     let var_names = vec![id.value.clone()];
                     Ok(Expr::ScalarVariable(var_names))
                 } else {
-                    match schema.field_with_name(&id.value) {
-                        Ok(field) => Ok(Expr::Column(field.name().clone())),
-                        Err(_) => Err(DataFusionError::Plan(format!(
-                            "Invalid identifier '{}' for schema {}",
-                            id,
-                            schema.to_string()
-                        ))),
-                    }
+                    // errors when the name is unknown, or ambiguous
+                    let field = schema.field_with_unqualified_name(&id.value)?;
+                    Ok(Expr::Column(Column::from_name(field.name())))
                 }
             }
 
@@ -436,11 +677,23 @@ impl<'a, S: SchemaProvider> SqlToRel<'a, S> {
                 if &var_names[0][0..1] == "@" {
                     Ok(Expr::ScalarVariable(var_names))
                 } else {
-                    Err(DataFusionError::Plan(format!(
-                        "Invalid compound identifier '{:?}' for schema {}",
-                        var_names,
-                        schema.to_string()
-                    )))
+                    // a column, qualified by a relation or not, followed by the names
+                    // of fields of structs, e.g. `t.a`, `a.b` or `t.a.b`
+                    let qualified = Column::new(Some(&var_names[0]), &var_names[1]);
+                    let (column, fields) = match schema.field_from_column(&qualified) {
+                        Ok(_) => (qualified, &var_names[2..]),
+                        Err(e) => match schema.field_with_unqualified_name(&var_names[0])
+                        {
+                            Ok(field) => {
+                                (Column::from_name(field.name()), &var_names[1..])
+                            }
+                            // errors when the relation has no such column
+                            Err(_) => return Err(e),
+                        },
+                    };
+                    Ok(fields
+                        .iter()
+                        .fold(Expr::Column(column), |expr, name| expr.field(name)))
                 }
             }
 
@@ -462,10 +715,59 @@ impl<'a, S: SchemaProvider> SqlToRel<'a, S> {
                 Ok(Expr::IsNotNull(Box::new(self.sql_to_rex(expr, schema)?)))
             }
 
+            SQLExpr::Between {
+                ref expr,
+                ref negated,
+                ref low,
+                ref high,
+            } => {
+                let expr = self.sql_to_rex(&expr, &schema)?;
+                let low = self.sql_to_rex(&low, &schema)?;
+                let high = self.sql_to_rex(&high, &schema)?;
+                Ok(Expr::Between {
+                    low: Box::new(infer_placeholder_type(low, &expr, schema)),
+                    high: Box::new(infer_placeholder_type(high, &expr, schema)),
+                    expr: Box::new(expr),
+                    negated: *negated,
+                })
+            }
+
+            SQLExpr::InList {
+                ref expr,
+                ref list,
+                ref negated,
+            } => {
+                let expr = self.sql_to_rex(&expr, &schema)?;
+                let list_expr = list
+                    .iter()
+                    .map(|e| {
+                        let e = self.sql_to_rex(e, schema)?;
+                        Ok(infer_placeholder_type(e, &expr, schema))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(Expr::InList {
+                    expr: Box::new(expr),
+                    list: list_expr,
+                    negated: *negated,
+                })
+            }
+
             SQLExpr::UnaryOp { ref op, ref expr } => match *op {
                 UnaryOperator::Not => {
                     Ok(Expr::Not(Box::new(self.sql_to_rex(expr, schema)?)))
                 }
+                // only the negation of numbers is supported, e.g. `-1`
+                UnaryOperator::Minus => match expr.as_ref() {
+                    SQLExpr::Value(Value::Number(n)) => match n.parse::<i64>() {
+                        Ok(n) => Ok(lit(-n)),
+                        Err(_) => Ok(lit(-n.parse::<f64>().unwrap())),
+                    },
+                    _ => Err(DataFusionError::NotImplemented(format!(
+                        "Unsupported negation of {}",
+                        expr
+                    ))),
+                },
                 _ => Err(DataFusionError::Internal(format!(
                     "SQL binary operator cannot be interpreted as a unary operator"
                 ))),
@@ -498,10 +800,12 @@ impl<'a, S: SchemaProvider> SqlToRel<'a, S> {
                     ))),
                 }?;
 
+                let left = self.sql_to_rex(&left, &schema)?;
+                let right = self.sql_to_rex(&right, &schema)?;
                 Ok(Expr::BinaryExpr {
-                    left: Box::new(self.sql_to_rex(&left, &schema)?),
+                    left: Box::new(infer_placeholder_type(left.clone(), &right, schema)),
                     op: operator,
-                    right: Box::new(self.sql_to_rex(&right, &schema)?),
+                    right: Box::new(infer_placeholder_type(right, &left, schema)),
                 })
             }
 
@@ -592,6 +896,28 @@ impl<'a, S: SchemaProvider> SqlToRel<'a, S> {
 }
 
 /// Determine if an expression is an aggregate expression or not
+/// The value of `expr`, an argument of a table function, which must be a literal,
+/// possibly cast to another type
+fn table_function_arg(expr: &Expr) -> Result<ScalarValue> {
+    match expr {
+        Expr::Literal(value) => Ok(value.clone()),
+        Expr::Cast { expr, data_type } => match (table_function_arg(expr)?, data_type) {
+            (
+                ScalarValue::Utf8(Some(value)),
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+            ) => Ok(ScalarValue::TimestampNanosecond(
+                Some(string_to_timestamp_nanos(&value)?),
+                None,
+            )),
+            (value, data_type) => cast_scalar(&value, data_type),
+        },
+        _ => Err(DataFusionError::Plan(format!(
+            "The arguments of table functions must be literals, but got {:?}",
+            expr
+        ))),
+    }
+}
+
 fn is_aggregate_expr(e: &Expr) -> bool {
     match e {
         Expr::AggregateFunction { .. } | Expr::AggregateUDF { .. } => true,
@@ -600,6 +926,171 @@ fn is_aggregate_expr(e: &Expr) -> bool {
     }
 }
 
+/// Collects the pairs of a column of `left` and a column of `right` that the join
+/// condition `expr` requires to be equal, if it is a conjunction of such equalities
+fn extract_join_keys(
+    expr: &Expr,
+    left: &DFSchema,
+    right: &DFSchema,
+    keys: &mut Vec<(Column, Column)>,
+) -> Result<()> {
+    match expr {
+        Expr::BinaryExpr {
+            left: l,
+            op: Operator::And,
+            right: r,
+        } => {
+            extract_join_keys(l, left, right, keys)?;
+            extract_join_keys(r, left, right, keys)
+        }
+        Expr::BinaryExpr {
+            left: l,
+            op: Operator::Eq,
+            right: r,
+        } => match (l.as_ref(), r.as_ref()) {
+            (Expr::Column(a), Expr::Column(b))
+                if left.field_from_column(a).is_ok()
+                    && right.field_from_column(b).is_ok() =>
+            {
+                keys.push((a.clone(), b.clone()));
+                Ok(())
+            }
+            (Expr::Column(a), Expr::Column(b))
+                if left.field_from_column(b).is_ok()
+                    && right.field_from_column(a).is_ok() =>
+            {
+                keys.push((b.clone(), a.clone()));
+                Ok(())
+            }
+            _ => Err(DataFusionError::NotImplemented(format!(
+                "Unsupported join condition {:?}: only equalities of a column of each \
+                 side of the join are supported",
+                expr
+            ))),
+        },
+        Expr::Nested(expr) => extract_join_keys(expr, left, right, keys),
+        _ => Err(DataFusionError::NotImplemented(format!(
+            "Unsupported join condition {:?}: only equalities of columns, combined \
+             with AND, are supported",
+            expr
+        ))),
+    }
+}
+
+/// Parses the value of an interval literal, such as `INTERVAL '1 day 2 hours'`, or
+/// `INTERVAL '3' MONTH` where the unit of a single number is given by `leading_field`.
+///
+/// Arrow intervals either count months or days and milliseconds, so an interval that
+/// mixes both is not supported.
+fn parse_interval(value: &str, leading_field: &Option<DateTimeField>) -> Result<Expr> {
+    let invalid =
+        || DataFusionError::Plan(format!("Invalid interval literal '{}'", value));
+
+    let tokens = value.split_whitespace().collect::<Vec<_>>();
+    let parts = match (tokens.as_slice(), leading_field) {
+        // a number without a unit is in the leading field, or in seconds by default
+        ([number], Some(field)) => vec![(*number, field.to_string())],
+        ([number], None) => vec![(*number, "second".to_string())],
+        _ if !tokens.is_empty() && tokens.len() % 2 == 0 => tokens
+            .chunks(2)
+            .map(|pair| (pair[0], pair[1].to_string()))
+            .collect(),
+        _ => return Err(invalid()),
+    };
+
+    let mut months = 0_f64;
+    let mut days = 0_f64;
+    let mut millis = 0_f64;
+    for (number, unit) in parts {
+        let number = number.parse::<f64>().map_err(|_| invalid())?;
+        let unit = unit.to_lowercase();
+        match unit.trim_end_matches('s') {
+            "year" => months += number * 12.0,
+            "month" => months += number,
+            "week" => days += number * 7.0,
+            "day" => days += number,
+            "hour" => millis += number * 3_600_000.0,
+            "minute" => millis += number * 60_000.0,
+            "second" => millis += number * 1_000.0,
+            "millisecond" => millis += number,
+            _ => {
+                return Err(DataFusionError::NotImplemented(format!(
+                    "Unsupported interval unit '{}' in '{}'",
+                    unit, value
+                )))
+            }
+        }
+    }
+
+    if months != 0.0 {
+        if days != 0.0 || millis != 0.0 || months.fract() != 0.0 {
+            return Err(DataFusionError::NotImplemented(format!(
+                "Interval '{}' is not supported: intervals must either be a whole \
+                 number of months or a number of days and seconds",
+                value
+            )));
+        }
+        return Ok(Expr::Literal(ScalarValue::IntervalYearMonth(Some(
+            months as i32,
+        ))));
+    }
+
+    // fractions of days are carried over as milliseconds, and whole days of
+    // milliseconds are normalized into days
+    const MILLIS_PER_DAY: i64 = 86_400_000;
+    let total_millis = (days * MILLIS_PER_DAY as f64 + millis).round() as i64;
+    let days = total_millis / MILLIS_PER_DAY;
+    let millis = total_millis % MILLIS_PER_DAY;
+    if days > i32::MAX as i64 || days < i32::MIN as i64 {
+        return Err(invalid());
+    }
+    // the days are stored in the upper 32 bits and the milliseconds in the lower 32 bits
+    let value = (days << 32) | (millis as i32 as u32 as i64);
+    Ok(Expr::Literal(ScalarValue::IntervalDayTime(Some(value))))
+}
+
+/// Parses the value of a date literal, e.g. `DATE '2020-01-01'`, to the number of days
+/// since the UNIX epoch
+fn parse_date(value: &str) -> Result<Expr> {
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        DataFusionError::Plan(format!("Invalid date literal '{}'", value))
+    })?;
+    let days = date.signed_duration_since(NaiveDate::from_ymd(1970, 1, 1));
+    Ok(Expr::Literal(ScalarValue::Date32(Some(
+        days.num_days() as i32
+    ))))
+}
+
+/// Parses a placeholder of a parameterized query, e.g. `$1`. Its type is not known
+/// until it is used alongside an expression of a known type.
+fn parse_placeholder(id: &str) -> Result<Expr> {
+    match id[1..].parse::<usize>() {
+        Ok(index) if index > 0 => Ok(Expr::Placeholder {
+            id: id.to_string(),
+            data_type: None,
+        }),
+        _ => Err(DataFusionError::Plan(format!(
+            "Invalid placeholder '{}'. Placeholders are numbered from $1",
+            id
+        ))),
+    }
+}
+
+/// Gives a placeholder whose type is not yet known the type of the expression it is
+/// compared or combined with, e.g. the type of `age` in `age > $1`.
+fn infer_placeholder_type(expr: Expr, other: &Expr, schema: &DFSchema) -> Expr {
+    match expr {
+        Expr::Placeholder {
+            id,
+            data_type: None,
+        } => Expr::Placeholder {
+            id,
+            data_type: other.get_type(schema).ok(),
+        },
+        expr => expr,
+    }
+}
+
 /// Convert SQL data type to relational representation of data type
 pub fn convert_data_type(sql: &SQLDataType) -> Result<DataType> {
     match sql {
@@ -611,6 +1102,7 @@ pub fn convert_data_type(sql: &SQLDataType) -> Result<DataType> {
         SQLDataType::Double => Ok(DataType::Float64),
         SQLDataType::Char(_) | SQLDataType::Varchar(_) => Ok(DataType::Utf8),
         SQLDataType::Timestamp => Ok(DataType::Timestamp(TimeUnit::Nanosecond, None)),
+        SQLDataType::Date => Ok(DataType::Date32(DateUnit::Day)),
         other => Err(DataFusionError::NotImplemented(format!(
             "Unsupported SQL type {:?}",
             other
@@ -621,8 +1113,14 @@ pub fn convert_data_type(sql: &SQLDataType) -> Result<DataType> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{logical_plan::create_udf, sql::parser::DFParser};
-    use functions::ScalarFunctionImplementation;
+    use crate::datasource::series::SeriesTable;
+    use crate::physical_plan::series::SeriesStep;
+    use crate::physical_plan::udtf::TableFunctionImplementation;
+    use crate::{
+        logical_plan::{create_udf, create_udtf},
+        sql::parser::DFParser,
+    };
+    use functions::{ScalarFunctionImplementation, Volatility};
 
     #[test]
     fn select_no_relation() {
@@ -672,6 +1170,85 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn select_between_and_in_list() {
+        let sql = "SELECT id FROM person \
+                   WHERE age NOT BETWEEN 21 AND 65 AND state IN ('CO', 'NY')";
+        let expected = "Projection: #id\
+            \n  Filter: #age NOT BETWEEN Int64(21) AND Int64(65) And #state IN (Utf8(\"CO\"), Utf8(\"NY\"))\
+            \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_placeholders() -> Result<()> {
+        let sql = "SELECT id, salary * $3 FROM person \
+                   WHERE age BETWEEN $1 AND 65 AND state IN ('CO', $2)";
+        let plan = logical_plan(sql)?;
+        let expected = "Projection: #id, #salary Multiply $3\
+            \n  Filter: #age BETWEEN $1 AND Int64(65) And #state IN (Utf8(\"CO\"), $2)\
+            \n    TableScan: person projection=None";
+        assert_eq!(expected, format!("{:?}", plan));
+
+        // the type of each placeholder is the one of the expression it is used with
+        assert_eq!(plan.schema().field(1).data_type(), &DataType::Float64);
+        let filter = match &plan {
+            LogicalPlan::Projection { input, .. } => match input.as_ref() {
+                LogicalPlan::Filter { predicate, .. } => predicate.clone(),
+                other => panic!("Expected a filter, found {:?}", other),
+            },
+            other => panic!("Expected a projection, found {:?}", other),
+        };
+        let mut types = vec![];
+        collect_placeholder_types(&filter, &mut types);
+        assert_eq!(types, vec![Some(DataType::Int32), Some(DataType::Utf8)]);
+        Ok(())
+    }
+
+    fn collect_placeholder_types(expr: &Expr, types: &mut Vec<Option<DataType>>) {
+        match expr {
+            Expr::Placeholder { data_type, .. } => types.push(data_type.clone()),
+            Expr::BinaryExpr { left, right, .. } => {
+                collect_placeholder_types(left, types);
+                collect_placeholder_types(right, types);
+            }
+            Expr::Between { low, high, .. } => {
+                collect_placeholder_types(low, types);
+                collect_placeholder_types(high, types);
+            }
+            Expr::InList { list, .. } => list
+                .iter()
+                .for_each(|e| collect_placeholder_types(e, types)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn select_placeholder_without_type() {
+        let sql = "SELECT $1 FROM person";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"The type of placeholder $1 could not be inferred. Use CAST($1 AS <type>) to declare it.\")",
+            format!("{:?}", err)
+        );
+
+        let sql = "SELECT id FROM person WHERE age > $0";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"Invalid placeholder '$0'. Placeholders are numbered from $1\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn select_coalesce_and_nullif() {
+        let sql = "SELECT coalesce(first_name, last_name), nullif(age, 0) FROM person";
+        let expected =
+            "Projection: coalesce(#first_name, #last_name), nullif(#age, Int64(0))\
+                        \n  TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
     #[test]
     fn test_timestamp_filter() {
         let sql = "SELECT state FROM person WHERE birth_date < CAST (158412331400600000 as timestamp)";
@@ -863,6 +1440,145 @@ mod tests {
         );
     }
 
+    #[test]
+    fn select_qualified_columns() {
+        let sql = "SELECT person.id, first_name FROM person WHERE person.age > 21";
+        let expected = "Projection: #person.id, #first_name\
+            \n  Filter: #person.age Gt Int64(21)\
+            \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_with_table_alias() {
+        let sql = "SELECT p.state, MAX(p.age) FROM person AS p \
+                   GROUP BY p.state ORDER BY p.state";
+        let expected = "Sort: #p.state ASC NULLS FIRST\
+            \n  Aggregate: groupBy=[[#p.state]], aggr=[[MAX(#p.age)]]\
+            \n    TableScan: person AS p projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_from_table_sample() {
+        let sql = "SELECT p.id FROM person AS p TABLESAMPLE BERNOULLI (12.5) \
+                   REPEATABLE (42) WHERE p.age > 20";
+        let expected = "Projection: #p.id\
+            \n  Filter: #p.age Gt Int64(20)\
+            \n    Sample: method=Bernoulli, fraction=0.125, seed=42\
+            \n      TableScan: person AS p projection=None";
+        quick_test(sql, expected);
+
+        let sql = "SELECT id FROM person TABLESAMPLE SYSTEM (50)";
+        let expected = "Projection: #id\
+            \n  Sample: method=System, fraction=0.5, seed=0\
+            \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_from_table_sample_out_of_range() {
+        let sql = "SELECT id FROM person TABLESAMPLE BERNOULLI (150)";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"The percentage of TABLESAMPLE must be between 0 and 100, but is 150\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn join_on() {
+        let sql = "SELECT id, order_id FROM person \
+                   JOIN orders ON person.id = orders.customer_id AND qty = age";
+        let expected = "Projection: #id, #order_id\
+            \n  Join: type=Inner, on=[person.id = orders.customer_id, person.age = orders.qty]\
+            \n    TableScan: person projection=None\
+            \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn join_using() {
+        let sql = "SELECT first_name, state, governor FROM person \
+                   LEFT JOIN states USING (state)";
+        let expected = "Projection: #first_name, #state, #governor\
+            \n  Join: type=Left, using=[person.state = states.state]\
+            \n    TableScan: person projection=None\
+            \n    TableScan: states projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn join_on_keys_with_the_same_name() {
+        // the keys are not merged, as with USING, so both can be selected
+        let sql = "SELECT first_name, states.state FROM person \
+                   LEFT JOIN states ON person.state = states.state";
+        let expected = "Projection: #first_name, #states.state\
+            \n  Join: type=Left, on=[person.state = states.state]\
+            \n    TableScan: person projection=None\
+            \n    TableScan: states projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn join_on_non_equality() {
+        let sql = "SELECT id FROM person JOIN orders ON id > customer_id";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert!(
+            matches!(err, DataFusionError::NotImplemented(_)),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn explain_analyze() {
+        let sql = "EXPLAIN ANALYZE SELECT id FROM person WHERE age > 20";
+        let expected = "Analyze: verbose=false\
+            \n  Projection: #id\
+            \n    Filter: #age Gt Int64(20)\
+            \n      TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_unknown_relation() {
+        // the alias replaces the table name
+        let sql = "SELECT person.id FROM person AS p";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"No field named 'person.id'. Valid fields are 'p.id', 'p.first_name', \
+             'p.last_name', 'p.age', 'p.state', 'p.salary', 'p.birth_date'.\")",
+            format!("{:?}", err)
+        );
+
+        let sql = "SELECT a.b.c FROM person";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"No field named 'a.b'. Valid fields are 'person.id', \
+             'person.first_name', 'person.last_name', 'person.age', 'person.state', \
+             'person.salary', 'person.birth_date'.\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn select_struct_fields() {
+        let sql = "SELECT s.a, n.s.b, n.id FROM nested AS n WHERE s.a > 1";
+        let expected = "Projection: #s[a], #n.s[b], #n.id\
+            \n  Filter: #s[a] Gt Int64(1)\
+            \n    TableScan: nested AS n projection=None";
+        quick_test(sql, expected);
+
+        let sql = "SELECT id.a FROM nested";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"Only the fields of structs and the elements of lists can be \
+             accessed, but got an expression of type UInt32\")",
+            format!("{:?}", err)
+        );
+    }
+
     #[test]
     fn create_external_table_csv() {
         let sql = "CREATE EXTERNAL TABLE t(c1 int) STORED AS CSV LOCATION 'foo.csv'";
@@ -898,6 +1614,135 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn select_extract_and_date_trunc() {
+        let sql = "SELECT EXTRACT(YEAR FROM birth_date), date_trunc('hour', birth_date) \
+                   FROM person";
+        let expected = "Projection: date_part(Utf8(\"year\"), #birth_date), \
+                        date_trunc(Utf8(\"hour\"), #birth_date)\
+                        \n  TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_timestamp_interval_arithmetic() {
+        let sql = "SELECT birth_date + INTERVAL '1 day 2 hours', \
+                   birth_date - INTERVAL '3' MONTH, birth_date - birth_date \
+                   FROM person";
+        let expected =
+            "Projection: #birth_date Plus IntervalDayTime(1 days 7200000 ms), \
+                        #birth_date Minus IntervalYearMonth(3 months), \
+                        #birth_date Minus #birth_date\
+                        \n  TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    /// parses an interval literal to its scalar value
+    fn interval(
+        value: &str,
+        leading_field: Option<DateTimeField>,
+    ) -> Result<ScalarValue> {
+        match parse_interval(value, &leading_field)? {
+            Expr::Literal(value) => Ok(value),
+            other => panic!("Unexpected interval expression {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_interval_literals() -> Result<()> {
+        let day_time = |days: i64, millis: i32| {
+            ScalarValue::IntervalDayTime(Some((days << 32) | (millis as u32 as i64)))
+        };
+        assert_eq!(interval("1 day", None)?, day_time(1, 0));
+        assert_eq!(interval("2 weeks", None)?, day_time(14, 0));
+        assert_eq!(interval("1.5 days", None)?, day_time(1, 43_200_000));
+        assert_eq!(interval("25 hours", None)?, day_time(1, 3_600_000));
+        assert_eq!(interval("-1 hour", None)?, day_time(0, -3_600_000));
+        assert_eq!(interval("90", None)?, day_time(0, 90_000));
+        assert_eq!(
+            interval("5", Some(DateTimeField::Minute))?,
+            day_time(0, 300_000)
+        );
+        assert_eq!(
+            interval("1 year 2 months", None)?,
+            ScalarValue::IntervalYearMonth(Some(14))
+        );
+
+        // months and days can not be mixed in a single arrow interval
+        assert!(interval("1 month 1 day", None).is_err());
+        assert!(interval("1 fortnight", None).is_err());
+        assert!(interval("one day", None).is_err());
+        assert!(interval("", None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn date_and_timestamp_literals() {
+        let sql =
+            "SELECT DATE '2020-01-01', TIMESTAMP '2020-01-01T00:00:00Z' FROM person";
+        let expected =
+            "Projection: Date32(18262), TimestampNanosecond(1577836800000000000)\
+             \n  TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        let sql = "SELECT DATE '2020-13-01' FROM person";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"Invalid date literal '2020-13-01'\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn cast_to_date() {
+        let sql = "SELECT CAST(age AS DATE) FROM person";
+        let expected = "Projection: CAST(#age AS Date32(Day))\
+                        \n  TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_from_table_function() {
+        quick_test(
+            "SELECT value FROM generate_series(1, 10, 2)",
+            "Projection: #value\
+             \n  TableScan: projection=None",
+        );
+        quick_test(
+            "SELECT r.value FROM range(-5, 5) AS r WHERE r.value > 0",
+            "Projection: #r.value\
+             \n  Filter: #r.value Gt Int64(0)\
+             \n    TableScan: AS r projection=None",
+        );
+        quick_test(
+            "SELECT * FROM my_series(1)",
+            "Projection: #value\
+             \n  TableScan: projection=None",
+        );
+    }
+
+    #[test]
+    fn select_from_invalid_table_function() {
+        let cases = vec![
+            (
+                "SELECT * FROM unknown(1)",
+                "Error during planning: Invalid table function 'unknown'",
+            ),
+            (
+                "SELECT * FROM range(abs(1))",
+                "Error during planning: The arguments of table functions must be literals, but got abs(Int64(1))",
+            ),
+            (
+                "SELECT * FROM generate_series(1)",
+                "Error during planning: generate_series does not accept 1 arguments",
+            ),
+        ];
+        for (sql, expected) in cases {
+            let err = logical_plan(sql).expect_err("query should have failed");
+            assert_eq!(expected, err.to_string());
+        }
+    }
+
     fn logical_plan(sql: &str) -> Result<LogicalPlan> {
         let planner = SqlToRel::new(&MockSchemaProvider {});
         let ast = DFParser::parse_sql(&sql).unwrap();
@@ -943,6 +1788,35 @@ mod tests {
                     Field::new("c12", DataType::Float64, false),
                     Field::new("c13", DataType::Utf8, false),
                 ]))),
+                "orders" => Some(Arc::new(Schema::new(vec![
+                    Field::new("order_id", DataType::UInt32, false),
+                    Field::new("customer_id", DataType::UInt32, false),
+                    Field::new("qty", DataType::Int32, false),
+                ]))),
+                "states" => Some(Arc::new(Schema::new(vec![
+                    Field::new("state", DataType::Utf8, false),
+                    Field::new("governor", DataType::Utf8, false),
+                ]))),
+                "nested" => Some(Arc::new(Schema::new(vec![
+                    Field::new("id", DataType::UInt32, false),
+                    Field::new(
+                        "s",
+                        DataType::Struct(vec![
+                            Field::new("a", DataType::Int32, false),
+                            Field::new("b", DataType::Utf8, true),
+                        ]),
+                        false,
+                    ),
+                    Field::new(
+                        "l",
+                        DataType::List(Box::new(Field::new(
+                            "item",
+                            DataType::Int64,
+                            true,
+                        ))),
+                        true,
+                    ),
+                ]))),
                 _ => None,
             }
         }
@@ -955,6 +1829,7 @@ mod tests {
                     "my_sqrt",
                     vec![DataType::Float64],
                     Arc::new(DataType::Float64),
+                    Volatility::Immutable,
                     f,
                 ))),
                 _ => None,
@@ -964,5 +1839,21 @@ mod tests {
         fn get_aggregate_meta(&self, _name: &str) -> Option<Arc<AggregateUDF>> {
             unimplemented!()
         }
+
+        fn get_table_function_meta(&self, name: &str) -> Option<Arc<TableUDF>> {
+            let f: TableFunctionImplementation = Arc::new(|_| {
+                Ok(Arc::new(SeriesTable::new(
+                    DataType::Int64,
+                    0,
+                    10,
+                    SeriesStep::Fixed(1),
+                    false,
+                )))
+            });
+            match name {
+                "my_series" => Some(Arc::new(create_udtf("my_series", f))),
+                _ => None,
+            }
+        }
     }
 }
@@ -32,8 +32,9 @@ use arrow::{
 use datafusion::datasource::{csv::CsvReadOptions, MemTable};
 use datafusion::error::Result;
 use datafusion::execution::context::ExecutionContext;
-use datafusion::logical_plan::LogicalPlan;
-use datafusion::prelude::create_udf;
+use datafusion::logical_plan::{col, lit, LogicalPlan};
+use datafusion::prelude::{create_udf, make_scalar_function, Volatility};
+use datafusion::scalar::ScalarValue;
 
 #[tokio::test]
 async fn nyc() -> Result<()> {
@@ -112,6 +113,19 @@ async fn parquet_query() {
     assert_eq!(expected, actual);
 }
 
+#[tokio::test]
+async fn parquet_query_table_sample() {
+    let mut ctx = ExecutionContext::new();
+    register_alltypes_parquet(&mut ctx);
+    // the file has a single row group, which is either sampled or not
+    let sql = "SELECT COUNT(*) FROM alltypes_plain TABLESAMPLE SYSTEM (100)";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec![vec!["8"]], actual);
+    let sql = "SELECT COUNT(*) FROM alltypes_plain TABLESAMPLE SYSTEM (0)";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec![vec!["0"]], actual);
+}
+
 #[tokio::test]
 async fn parquet_single_nan_schema() {
     let mut ctx = ExecutionContext::new();
@@ -252,6 +266,60 @@ async fn csv_query_with_predicate() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn csv_query_table_sample() -> Result<()> {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx)?;
+    let sql = "SELECT COUNT(*) FROM aggregate_test_100 TABLESAMPLE BERNOULLI (100)";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec![vec!["100"]], actual);
+    let sql = "SELECT COUNT(*) FROM aggregate_test_100 TABLESAMPLE BERNOULLI (0)";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec![vec!["0"]], actual);
+
+    // the same seed samples the same rows
+    let sql = "SELECT c1, c2, c3 FROM aggregate_test_100 AS t \
+               TABLESAMPLE BERNOULLI (30) REPEATABLE (5) WHERE t.c2 > 1";
+    let actual = execute(&mut ctx, sql).await;
+    assert!(!actual.is_empty() && actual.len() < 100);
+    assert_eq!(actual, execute(&mut ctx, sql).await);
+    Ok(())
+}
+
+#[tokio::test]
+async fn csv_query_with_table_alias() -> Result<()> {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx)?;
+    let sql = "SELECT t.c1, c12 FROM aggregate_test_100 AS t \
+               WHERE t.c12 > 0.376 AND aggregate_test_100.c12 < 0.4";
+    // the table name is hidden by its alias
+    let plan = ctx.create_logical_plan(&sql);
+    assert!(plan.is_err());
+
+    let sql = "SELECT t.c1, c12 FROM aggregate_test_100 AS t \
+               WHERE t.c12 > 0.376 AND t.c12 < 0.4";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["e", "0.39144436569161134"],
+        vec!["d", "0.38870280983958583"],
+    ];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT aggregate_test_100.c2, MIN(aggregate_test_100.c12) \
+               FROM aggregate_test_100 GROUP BY aggregate_test_100.c2";
+    let mut actual = execute(&mut ctx, sql).await;
+    actual.sort();
+    let expected = vec![
+        vec!["1", "0.05636955101974106"],
+        vec!["2", "0.16301110515739792"],
+        vec!["3", "0.047343434291126085"],
+        vec!["4", "0.02182578039211991"],
+        vec!["5", "0.01479305307777301"],
+    ];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
 #[tokio::test]
 async fn csv_query_with_negated_predicate() -> Result<()> {
     let mut ctx = ExecutionContext::new();
@@ -417,7 +485,8 @@ fn create_ctx() -> Result<ExecutionContext> {
         "custom_sqrt",
         vec![DataType::Float64],
         Arc::new(DataType::Float64),
-        Arc::new(custom_sqrt),
+        Volatility::Immutable,
+        make_scalar_function(custom_sqrt),
     ));
 
     Ok(ctx)
@@ -948,6 +1017,40 @@ async fn csv_explain_verbose() {
     assert!(actual.contains("#c2 Gt Int64(10)"), "Actual: '{}'", actual);
 }
 
+#[tokio::test]
+async fn csv_explain_analyze() {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv_by_sql(&mut ctx).await;
+    let sql = "EXPLAIN ANALYZE SELECT c1, MIN(c12) FROM aggregate_test_100 \
+               WHERE c2 > 3 GROUP BY c1";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(1, actual.len());
+    assert_eq!("Plan with Metrics", actual[0][0]);
+
+    // each operator is followed by its metrics
+    let plan = &actual[0][1];
+    assert!(
+        plan.starts_with("ProjectionExec: elapsed_compute="),
+        "Actual: '{}'",
+        plan
+    );
+    assert!(plan.contains("HashAggregateExec: "), "Actual: '{}'", plan);
+    assert!(plan.contains("output_rows=5\n"), "Actual: '{}'", plan);
+    assert!(
+        plan.contains("CsvExec: elapsed_compute="),
+        "Actual: '{}'",
+        plan
+    );
+    assert!(plan.contains("output_rows=100"), "Actual: '{}'", plan);
+
+    let sql = "EXPLAIN ANALYZE VERBOSE SELECT c1, MIN(c12) FROM aggregate_test_100 \
+               WHERE c2 > 3 GROUP BY c1";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(3, actual.len());
+    assert_eq!(vec!["Output Rows", "5"], actual[1]);
+    assert_eq!("Duration", actual[2][0]);
+}
+
 fn aggr_test_schema() -> SchemaRef {
     Arc::new(Schema::new(vec![
         Field::new("c1", DataType::Utf8, false),
@@ -1045,7 +1148,10 @@ async fn execute(ctx: &mut ExecutionContext, sql: &str) -> Vec<Vec<String>> {
     let results = ctx.collect(plan).await.expect(&msg);
 
     assert_eq!(logical_schema.as_ref(), optimized_logical_schema.as_ref());
-    assert_eq!(logical_schema.as_ref(), physical_schema.as_ref());
+    assert_eq!(
+        &Schema::from(logical_schema.as_ref()),
+        physical_schema.as_ref()
+    );
 
     result_vec(&results)
 }
@@ -1179,6 +1285,216 @@ async fn query_concat() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn query_string_functions() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![Field::new("c1", DataType::Utf8, true)]));
+
+    let data = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(StringArray::from(vec![
+            Some(" Tom "),
+            Some("alphabet"),
+            None,
+        ]))],
+    )?;
+
+    let table = MemTable::new(schema, vec![vec![data]])?;
+
+    let mut ctx = ExecutionContext::new();
+    ctx.register_table("test", Box::new(table));
+    let sql = "SELECT upper(c1), trim(c1), substr(c1, 3, 2), lpad(c1, 7, 'xy'), \
+               strpos(c1, 'ph'), split_part(c1, 'h', 2), initcap(c1), \
+               concat_ws('-', c1, 'z') FROM test";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec![" TOM ", "Tom", "om", "xy Tom ", "0", "", " Tom ", " Tom -z"],
+        vec![
+            "ALPHABET",
+            "alphabet",
+            "ph",
+            "alphabe",
+            "3",
+            "abet",
+            "Alphabet",
+            "alphabet-z",
+        ],
+        vec!["NULL", "NULL", "NULL", "NULL", "NULL", "NULL", "NULL", "z"],
+    ];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_regex_functions() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![Field::new("c1", DataType::Utf8, true)]));
+
+    let data = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(StringArray::from(vec![
+            Some("foobarbequebaz"),
+            Some("Hello World"),
+            None,
+        ]))],
+    )?;
+
+    let table = MemTable::new(schema, vec![vec![data]])?;
+
+    let mut ctx = ExecutionContext::new();
+    ctx.register_table("test", Box::new(table));
+    let sql = "SELECT regexp_match(c1, '(bar)(beque)'), regexp_match(c1, 'w(o)', 'i'), \
+               regexp_replace(c1, 'b(..)', 'X\\1Y', 'g'), \
+               regexp_replace(c1, '[aeiou]', '') FROM test";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["[bar, beque]", "NULL", "fooXarYXeqYueXazY", "fobarbequebaz"],
+        vec!["NULL", "[o]", "Hello World", "Hllo World"],
+        vec!["NULL", "NULL", "NULL", "NULL"],
+    ];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_date_time_functions() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![Field::new("c1", DataType::Utf8, true)]));
+
+    let data = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(StringArray::from(vec![
+            Some("2020-09-08T13:42:29.190855Z"),
+            Some("2020-01-31T00:00:00Z"),
+            None,
+        ]))],
+    )?;
+
+    let table = MemTable::new(schema, vec![vec![data]])?;
+
+    let mut ctx = ExecutionContext::new();
+    ctx.register_table("test", Box::new(table));
+    let sql = "SELECT to_char(date_trunc('hour', to_timestamp(c1)), '%Y-%m-%d %H:%M:%S'), \
+               EXTRACT(MONTH FROM to_timestamp(c1)), date_part('dow', to_timestamp(c1)), \
+               to_char(to_timestamp(c1) + INTERVAL '1' MONTH, '%Y-%m-%d'), \
+               to_char(to_timestamp(c1) - INTERVAL '36 hours', '%Y-%m-%d %H:%M', 'Asia/Kolkata'), \
+               to_timestamp(c1) - to_timestamp('2020-01-01T00:00:00Z'), \
+               now() > to_timestamp(c1) \
+               FROM test";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec![
+            "2020-09-08 13:00:00",
+            "9",
+            "2",
+            "2020-10-08",
+            "2020-09-07 07:12",
+            "21735749190855000",
+            "true",
+        ],
+        vec![
+            "2020-01-31 00:00:00",
+            "1",
+            "5",
+            "2020-02-29",
+            "2020-01-29 17:30",
+            "2592000000000000",
+            "true",
+        ],
+        vec!["NULL", "NULL", "NULL", "NULL", "NULL", "NULL", "NULL"],
+    ];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn csv_query_prepared_statement() -> Result<()> {
+    let mut ctx = ExecutionContext::new();
+    register_aggregate_csv(&mut ctx)?;
+    let sql = "SELECT c1, c12 * ? FROM aggregate_test_100 \
+               WHERE c12 > ? AND c12 < ? AND c1 IN (?, ?)";
+    let statement = ctx.prepare(sql)?;
+
+    let params = vec![
+        ScalarValue::Float64(Some(2.0)),
+        ScalarValue::Float64(Some(0.376)),
+        ScalarValue::Float64(Some(0.4)),
+        ScalarValue::Utf8(Some("d".to_string())),
+        ScalarValue::Utf8(Some("e".to_string())),
+    ];
+    let results = statement.execute(params).await?;
+    // columns are named after the placeholders, whatever their values
+    let field_names = |schema: &Schema| {
+        schema
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        field_names(&results[0].schema()),
+        field_names(&statement.logical_plan().schema().as_ref().into())
+    );
+    let expected = vec![
+        vec!["e", "0.7828887313832227"],
+        vec!["d", "0.7774056196791717"],
+    ];
+    assert_eq!(expected, result_vec(&results));
+
+    // the same statement, with parameters that cannot be injected into the query
+    let params = vec![
+        ScalarValue::Float64(Some(1.0)),
+        ScalarValue::Float64(Some(0.376)),
+        ScalarValue::Float64(Some(0.4)),
+        ScalarValue::Utf8(Some("d') OR ('a' = 'a".to_string())),
+        ScalarValue::Utf8(Some("e".to_string())),
+    ];
+    let results = statement.execute(params).await?;
+    let expected = vec![vec!["e", "0.39144436569161134"]];
+    assert_eq!(expected, result_vec(&results));
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_in_list_between_and_conditionals() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("c1", DataType::Utf8, true),
+        Field::new("c2", DataType::Int32, true),
+    ]));
+
+    let data = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(vec![
+                Some("a"),
+                Some("b"),
+                None,
+                Some("d"),
+            ])),
+            Arc::new(Int32Array::from(vec![Some(1), None, Some(3), Some(4)])),
+        ],
+    )?;
+
+    let table = MemTable::new(schema, vec![vec![data]])?;
+
+    let mut ctx = ExecutionContext::new();
+    ctx.register_table("test", Box::new(table));
+    let sql = "SELECT c1 IN ('a', 'd'), c2 BETWEEN 2 AND 4, c2 NOT IN (1, 2), \
+               coalesce(c1, 'z'), nullif(c2, 3) \
+               FROM test";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["true", "false", "false", "a", "1"],
+        vec!["false", "NULL", "NULL", "b", "NULL"],
+        vec!["NULL", "true", "true", "z", "NULL"],
+        vec!["true", "true", "true", "d", "4"],
+    ];
+    assert_eq!(expected, actual);
+
+    let sql = "SELECT c1 FROM test WHERE c2 IN (3, 4)";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["NULL"], vec!["d"]];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
 #[tokio::test]
 async fn query_array() -> Result<()> {
     let schema = Arc::new(Schema::new(vec![
@@ -1210,6 +1526,87 @@ async fn query_array() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn query_nested() -> Result<()> {
+    let s = StructArray::from(vec![
+        (
+            Field::new("a", DataType::Int32, false),
+            Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef,
+        ),
+        (
+            Field::new("b", DataType::Utf8, true),
+            Arc::new(StringArray::from(vec![Some("x"), None, Some("z")])) as ArrayRef,
+        ),
+    ]);
+    // [1, 2], null, [3, 4, 5]
+    let mut builder = ListBuilder::new(Int64Builder::new(8));
+    builder.values().append_value(1)?;
+    builder.values().append_value(2)?;
+    builder.append(true)?;
+    builder.append(false)?;
+    builder.values().append_value(3)?;
+    builder.values().append_value(4)?;
+    builder.values().append_value(5)?;
+    builder.append(true)?;
+    let l = builder.finish();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("s", s.data_type().clone(), false),
+        Field::new("l", l.data_type().clone(), true),
+    ]));
+    let data = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from(vec![10, 20, 30])),
+            Arc::new(s),
+            Arc::new(l),
+        ],
+    )?;
+    let table = MemTable::new(schema, vec![vec![data]])?;
+
+    let mut ctx = ExecutionContext::new();
+    ctx.register_table("test", Box::new(table));
+    let sql = "SELECT s.a, test.s.b, array_length(l), array_contains(l, 2) \
+               FROM test WHERE s.a > 1 OR array_contains(l, 1)";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![
+        vec!["1", "x", "2", "true"],
+        vec!["2", "NULL", "NULL", "NULL"],
+        vec!["3", "z", "3", "false"],
+    ];
+    assert_eq!(expected, actual);
+
+    // list indexing and unnesting have no SQL syntax yet
+    let df = ctx.table("test")?.select(vec![
+        col("l").index(1),
+        col("l").index(3),
+        col("s").field("b"),
+    ])?;
+    let actual = result_vec(&df.collect().await?);
+    let expected = vec![
+        vec!["1", "NULL", "x"],
+        vec!["NULL", "NULL", "NULL"],
+        vec!["3", "5", "z"],
+    ];
+    assert_eq!(expected, actual);
+
+    let df = ctx
+        .table("test")?
+        .select(vec![col("id"), col("l")])?
+        .unnest("l")?
+        .filter(col("l").gt(lit(1)))?;
+    let actual = result_vec(&df.collect().await?);
+    let expected = vec![
+        vec!["10", "2"],
+        vec!["30", "3"],
+        vec!["30", "4"],
+        vec!["30", "5"],
+    ];
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
 #[tokio::test]
 async fn csv_query_sum_cast() {
     let mut ctx = ExecutionContext::new();
@@ -1269,6 +1666,30 @@ async fn to_timstamp() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn date_and_timestamp_literals() -> Result<()> {
+    let mut ctx = ExecutionContext::new();
+    ctx.register_table("ts_data", make_timestamp_nano_table()?);
+
+    let sql =
+        "SELECT COUNT(*) FROM ts_data WHERE ts > TIMESTAMP '2020-09-08T12:00:00+00:00'";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec![vec!["2"]], actual);
+
+    // dates are the number of days since the epoch
+    let sql = "SELECT DATE '2020-09-08' FROM ts_data LIMIT 1";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec![vec!["18513"]], actual);
+
+    // the date functions accept dates
+    let sql = "SELECT date_part('dow', DATE '2020-09-08'), \
+               to_char(date_trunc('month', DATE '2020-09-08'), '%Y-%m-%d') \
+               FROM ts_data LIMIT 1";
+    let actual = execute(&mut ctx, sql).await;
+    assert_eq!(vec![vec!["2", "2020-09-01"]], actual);
+    Ok(())
+}
+
 #[tokio::test]
 async fn query_is_null() -> Result<()> {
     let schema = Arc::new(Schema::new(vec![Field::new("c1", DataType::Float64, true)]));
@@ -1350,7 +1771,11 @@ async fn query_on_string_dictionary() -> Result<()> {
     // Use StringDictionary (32 bit indexes = keys)
     let field_type =
         DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
-    let schema = Arc::new(Schema::new(vec![Field::new("d1", field_type, true)]));
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "d1",
+        field_type.clone(),
+        true,
+    )]));
 
     let keys_builder = PrimitiveBuilder::<Int32Type>::new(10);
     let values_builder = StringBuilder::new(10);
@@ -1385,6 +1810,12 @@ async fn query_on_string_dictionary() -> Result<()> {
     let expected = vec![vec!["three"]];
     assert_eq!(expected, actual);
 
+    // comparison with constants, on either side
+    let sql = "SELECT * FROM test WHERE d1 > 'one' OR 'one' = d1";
+    let actual = execute(&mut ctx, sql).await;
+    let expected = vec![vec!["one"], vec!["three"]];
+    assert_eq!(expected, actual);
+
     // Expression evaluation
     let sql = "SELECT concat(d1, '-foo') FROM test";
     let actual = execute(&mut ctx, sql).await;
@@ -1397,5 +1828,16 @@ async fn query_on_string_dictionary() -> Result<()> {
     let expected = vec![vec!["2"]];
     assert_eq!(expected, actual);
 
+    // grouping, which keeps the dictionary encoding and the null keys as their own group
+    let sql = "SELECT d1, COUNT(*) FROM test GROUP BY d1";
+    let mut actual = execute(&mut ctx, sql).await;
+    actual.sort();
+    let expected = vec![vec!["NULL", "1"], vec!["one", "1"], vec!["three", "1"]];
+    assert_eq!(expected, actual);
+    let results = ctx.sql(sql)?.collect().await?;
+    assert_eq!(results[0].schema().field(0).data_type(), &field_type);
+    let null_count: usize = results.iter().map(|b| b.column(0).null_count()).sum();
+    assert_eq!(null_count, 1);
+
     Ok(())
 }
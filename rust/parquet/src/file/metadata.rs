@@ -82,6 +82,26 @@ impl ParquetMetaData {
     pub fn row_groups(&self) -> &[RowGroupMetaData] {
         &self.row_groups
     }
+
+    /// Returns the metadata with only the row groups for which `predicate`, given the
+    /// position and metadata of each row group, returns true. The file metadata, such
+    /// as the number of rows of the file, is unchanged.
+    pub fn filter_row_groups<P>(self, mut predicate: P) -> Self
+    where
+        P: FnMut(usize, &RowGroupMetaData) -> bool,
+    {
+        let row_groups = self
+            .row_groups
+            .into_iter()
+            .enumerate()
+            .filter(|(i, row_group)| predicate(*i, row_group))
+            .map(|(_, row_group)| row_group)
+            .collect();
+        ParquetMetaData {
+            file_metadata: self.file_metadata,
+            row_groups,
+        }
+    }
 }
 
 pub type KeyValue = parquet_format::KeyValue;
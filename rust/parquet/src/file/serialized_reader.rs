@@ -137,6 +137,18 @@ impl<R: 'static + ChunkReader> SerializedFileReader<R> {
             metadata,
         })
     }
+
+    /// Restricts the reader to the row groups for which `predicate`, given the
+    /// position and metadata of each row group, returns true.
+    pub fn filter_row_groups<P>(self, predicate: P) -> Self
+    where
+        P: FnMut(usize, &RowGroupMetaData) -> bool,
+    {
+        Self {
+            chunk_reader: self.chunk_reader,
+            metadata: self.metadata.filter_row_groups(predicate),
+        }
+    }
 }
 
 impl<R: 'static + ChunkReader> FileReader for SerializedFileReader<R> {
@@ -405,6 +417,22 @@ mod tests {
         assert!(file_iter.eq(cursor_iter));
     }
 
+    #[test]
+    fn test_filter_row_groups() {
+        let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+            .unwrap()
+            .filter_row_groups(|i, row_group| i == 0 && row_group.num_rows() == 8);
+        assert_eq!(reader.num_row_groups(), 1);
+        assert_eq!(reader.get_row_iter(None).unwrap().count(), 8);
+
+        let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+            .unwrap()
+            .filter_row_groups(|_, _| false);
+        assert_eq!(reader.num_row_groups(), 0);
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 8);
+        assert_eq!(reader.get_row_iter(None).unwrap().count(), 0);
+    }
+
     #[test]
     fn test_file_reader_try_from() {
         // Valid file path